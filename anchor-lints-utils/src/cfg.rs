@@ -0,0 +1,97 @@
+//! Reachability and dominance over a control flow graph, usually the `BasicBlocks` of a MIR
+//! body. Every helper is generic over rustc's graph traits so it can be checked on small
+//! hand-written graphs.
+
+use rustc_data_structures::graph::{Successors, dominators::Dominators};
+use rustc_index::Idx;
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Find the node closest to `from` matching `target`, `from` included
+pub fn reachable<G: Successors>(
+    graph: &G,
+    from: G::Node,
+    target: impl Fn(G::Node) -> bool,
+) -> Option<G::Node> {
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+
+    while let Some(node) = queue.pop_front() {
+        if target(node) {
+            return Some(node);
+        }
+        for succ in graph.successors(node) {
+            if visited.insert(succ) {
+                queue.push_back(succ);
+            }
+        }
+    }
+    None
+}
+
+/// Collect the nodes reachable from any node of `from`, `from` included, closest first
+pub fn reachable_from<G: Successors>(
+    graph: &G,
+    from: impl IntoIterator<Item = G::Node>,
+) -> Vec<G::Node> {
+    let mut queue: VecDeque<G::Node> = VecDeque::new();
+    let mut visited = HashSet::new();
+    for node in from {
+        if visited.insert(node) {
+            queue.push_back(node);
+        }
+    }
+
+    let mut nodes = Vec::new();
+    while let Some(node) = queue.pop_front() {
+        nodes.push(node);
+        for succ in graph.successors(node) {
+            if visited.insert(succ) {
+                queue.push_back(succ);
+            }
+        }
+    }
+    nodes
+}
+
+/// Find the nodes of `to` reachable from a node of `from` without passing through a node of
+/// `without`, along with the `from` node each of them is reached from
+pub fn reachable_without_passing<G: Successors>(
+    graph: &G,
+    from: &HashSet<G::Node>,
+    to: &HashSet<G::Node>,
+    without: &HashSet<G::Node>,
+) -> Vec<(G::Node, G::Node)> {
+    let mut queue = VecDeque::new();
+    // Map of nodes to the `from` node they are reachable from
+    let mut origin = HashMap::new();
+
+    for &node in from {
+        origin.insert(node, node);
+        queue.push_back(node);
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if without.contains(&node) {
+            continue;
+        }
+        for succ in graph.successors(node) {
+            if without.contains(&succ) || origin.contains_key(&succ) {
+                continue;
+            }
+            origin.insert(succ, origin[&node]);
+            queue.push_back(succ);
+        }
+    }
+
+    to.iter()
+        .filter_map(|node| origin.get(node).map(|from| (*node, *from)))
+        .collect()
+}
+
+/// Check if every path from the start node to `node` passes through `dominator`. A node that
+/// cannot be reached from the start node is not dominated by any node.
+pub fn dominates<N: Idx>(dominators: &Dominators<N>, dominator: N, node: N) -> bool {
+    // `Dominators::dominates` panics when `node` is unreachable
+    dominators.is_reachable(node) && dominators.dominates(dominator, node)
+}
@@ -0,0 +1,206 @@
+//! Configuration of every lint, one table per lint, read once per process.
+//!
+//! The configuration is read from the file named by `ANCHOR_LINTS_CONFIG` when it is set, else
+//! from the `DYLINT_TOML` contents `cargo dylint` passes down, else from `dylint.toml` in the
+//! current directory, i.e. the workspace root rustc is invoked from. Without any of them, every
+//! lint runs with its defaults.
+//!
+//! ```toml
+//! [missing_account_field_init]
+//! padding_field_patterns = ["^padding", "^reserved", "^_", "^gap"]
+//!
+//! [missing_owner_check]
+//! owner_validated_types = ["ValidatedMetadata", "my_program::state::OwnerChecked"]
+//!
+//! [unsafe_pyth_price_account]
+//! canonical_feed_addresses = ["7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"]
+//! ```
+//!
+//! Unknown keys of a lint table, values of the wrong type, invalid regex patterns and malformed
+//! pubkeys are rejected by [`Config::from_toml_str`]. [`config`] panics with the error and the
+//! source of the configuration, so a typo fails the run instead of silently running the lints
+//! with their defaults. Unknown tables are ignored, as `dylint.toml` is shared with the other
+//! dylint libraries of the workspace, each reading its own tables.
+//!
+//! Only individual lints are configured here. Settings of the whole run, e.g. the minimum
+//! confidence of reported findings, are read from their `ANCHOR_LINTS_*` environment variables.
+
+use regex::Regex;
+use serde::Deserialize;
+
+use std::{fmt, path::Path, sync::LazyLock};
+
+/// Environment variable naming the configuration file, overriding `dylint.toml`
+pub const CONFIG_ENV_VAR: &str = "ANCHOR_LINTS_CONFIG";
+
+/// Environment variable `cargo dylint` passes the contents of `dylint.toml` in
+pub const DYLINT_TOML_ENV_VAR: &str = "DYLINT_TOML";
+
+/// File the configuration is read from when neither environment variable is set
+pub const CONFIG_FILE: &str = "dylint.toml";
+
+/// Configuration of every configurable lint, each in the table named after the lint
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    pub missing_account_field_init: MissingAccountFieldInitConfig,
+    pub missing_owner_check: MissingOwnerCheckConfig,
+    pub unsafe_pyth_price_account: UnsafePythPriceAccountConfig,
+}
+
+/// `[missing_account_field_init]`
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MissingAccountFieldInitConfig {
+    /// Regex patterns matching field names that are padding/reserved space and never need to be assigned
+    pub padding_field_patterns: Vec<String>,
+}
+
+impl Default for MissingAccountFieldInitConfig {
+    fn default() -> Self {
+        Self {
+            padding_field_patterns: vec!["^padding".into(), "^reserved".into(), "^_".into()],
+        }
+    }
+}
+
+/// `[missing_owner_check]`
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MissingOwnerCheckConfig {
+    /// Types, or traits implemented by types, that validate the account owner on construction
+    pub owner_validated_types: Vec<String>,
+}
+
+/// `[unsafe_pyth_price_account]`
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnsafePythPriceAccountConfig {
+    /// Base58 addresses of the price accounts a key check must compare against. When empty, a
+    /// comparison against any constant counts as a canonical source check.
+    pub canonical_feed_addresses: Vec<String>,
+}
+
+/// Why a configuration is rejected
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    /// Not valid TOML, an unknown key of a lint table, or a value of the wrong type
+    Parse(String),
+    /// A value that must be a base58 pubkey is not one
+    InvalidPubkey { key: &'static str, value: String },
+    /// A value that must be a regex pattern is not one
+    InvalidPattern {
+        key: &'static str,
+        value: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Parse(error) => write!(f, "{}", error.trim_end()),
+            ConfigError::InvalidPubkey { key, value } => {
+                write!(f, "`{}` has `{}`, which is not a base58 pubkey", key, value)
+            }
+            ConfigError::InvalidPattern { key, value, error } => {
+                write!(
+                    f,
+                    "`{}` has `{}`, which is not a regex pattern: {}",
+                    key, value, error
+                )
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Parse and validate a configuration
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        let config: Config =
+            toml::from_str(toml).map_err(|err| ConfigError::Parse(err.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the values serde cannot, i.e. the regex patterns and the pubkeys
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for pattern in &self.missing_account_field_init.padding_field_patterns {
+            if let Err(err) = Regex::new(pattern) {
+                return Err(ConfigError::InvalidPattern {
+                    key: "missing_account_field_init.padding_field_patterns",
+                    value: pattern.clone(),
+                    error: err.to_string(),
+                });
+            }
+        }
+        for address in &self.unsafe_pyth_price_account.canonical_feed_addresses {
+            if decode_pubkey(address).is_none() {
+                return Err(ConfigError::InvalidPubkey {
+                    key: "unsafe_pyth_price_account.canonical_feed_addresses",
+                    value: address.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    let Some((source, toml)) = read_config_source() else {
+        return Config::default();
+    };
+    Config::from_toml_str(&toml)
+        .unwrap_or_else(|err| panic!("invalid anchor-lints configuration in {}: {}", source, err))
+});
+
+/// The configuration of the run, loaded and validated on first use
+pub fn config() -> &'static Config {
+    &CONFIG
+}
+
+/// The source of the configuration and its contents, `None` when there is none
+fn read_config_source() -> Option<(String, String)> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV_VAR) {
+        let path = Path::new(&path);
+        let toml = std::fs::read_to_string(path).unwrap_or_else(|err| {
+            panic!(
+                "failed to read the anchor-lints configuration `{}` set by `{}`: {}",
+                path.display(),
+                CONFIG_ENV_VAR,
+                err
+            )
+        });
+        return Some((format!("`{}`", path.display()), toml));
+    }
+    if let Ok(toml) = std::env::var(DYLINT_TOML_ENV_VAR) {
+        return Some((format!("`{}`", DYLINT_TOML_ENV_VAR), toml));
+    }
+    std::fs::read_to_string(CONFIG_FILE)
+        .ok()
+        .map(|toml| (format!("`{}`", CONFIG_FILE), toml))
+}
+
+/// Decode a base58 pubkey, `None` if it is not valid base58, is not 32 to 44 characters long, or
+/// does not fit in 32 bytes
+pub fn decode_pubkey(address: &str) -> Option<[u8; 32]> {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let address = address.trim();
+    if !(32..=44).contains(&address.len()) {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for c in address.bytes() {
+        let mut carry = ALPHABET.iter().position(|&digit| digit == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            carry += u32::from(*byte) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
@@ -1,8 +1,8 @@
 #![allow(clippy::disallowed_methods)] // We use `def_path_str` as a fallback
 
-use rustc_hir::def_id::DefId;
+use rustc_hir::{def::DefKind, def_id::DefId};
 use rustc_middle::ty::{self, Ty, TyCtxt, TyKind};
-use rustc_span::Symbol;
+use rustc_span::{Symbol, sym};
 
 #[derive(Copy, Clone, Debug)]
 pub enum DiagnoticItem {
@@ -10,8 +10,16 @@ pub enum DiagnoticItem {
     AnchorAccount,
     /// `anchor_lang::prelude::AccountLoader`
     AnchorAccountLoader,
+    /// `anchor_lang::accounts::account_loader::AccountLoader::load`
+    AnchorAccountLoaderLoad,
+    /// `anchor_lang::accounts::account_loader::AccountLoader::load_mut`
+    AnchorAccountLoaderLoadMut,
     /// `anchor_lang::accounts::account::Account::reload`
     AnchorAccountReload,
+    /// `anchor_lang::AccountDeserialize::try_deserialize`
+    AnchorAccountDeserialize,
+    /// `anchor_lang::AccountDeserialize::try_deserialize_unchecked`
+    AnchorAccountDeserializeUnchecked,
     /// `anchor_lang::context::CpiContext`
     AnchorCpiContext,
     /// `anchor_lang::context::CpiContext::with_remaining_accounts`
@@ -22,10 +30,14 @@ pub enum DiagnoticItem {
     AnchorCpiInvokeUnchecked,
     AnchorCpiInvokeSigned,
     AnchorCpiInvokeSignedUnchecked,
+    /// `anchor_lang::Id::id`
+    AnchorIdFn,
     /// `anchor_lang::prelude::InterfaceAccount`
     AnchorInterfaceAccount,
     /// `anchor_lang::prelude::Key::key`
     AnchorKey,
+    /// `anchor_lang::prelude::Program`
+    AnchorProgram,
     /// `anchor_lang::prelude::Account::set_inner`
     AnchorAccountSetInner,
     /// `anchor_lang::prelude::Signer`
@@ -46,6 +58,8 @@ pub enum DiagnoticItem {
     AnchorUncheckedAccount,
     /// `anchor_spl::token::transfer`
     AnchorSplTokenTransfer,
+    /// `anchor_spl::token::Transfer` and `anchor_spl::token::TransferChecked`
+    AnchorSplTokenTransferAccounts,
     /// `anchor_spl::token::TokenAccount`
     AnchorSplTokenAccount,
     /// `anchor_spl::token_interface::TokenAccount`
@@ -64,16 +78,33 @@ pub enum DiagnoticItem {
     AnchorSplTokenInterfaceGetMintLen,
     /// `anchor_spl::token_2022::get_account_data_size`
     AnchorSplToken2022GetAccountDataSize,
+    /// `anchor_spl::token_2022::transfer`, also exported by `anchor_spl::token_interface`
+    AnchorSplToken2022Transfer,
+    /// `anchor_spl::token_2022::transfer_checked`, also exported by `anchor_spl::token_interface`
+    AnchorSplToken2022TransferChecked,
+    /// `anchor_spl::token_2022::Transfer` and `anchor_spl::token_2022::TransferChecked`, also
+    /// exported by `anchor_spl::token_interface`
+    AnchorSplToken2022TransferAccounts,
     /// `pyth_solana_receiver_sdk::price_update::PriceUpdateV2`
     PythPriceUpdateV2,
     /// `pyth_solana_receiver_sdk::price_update::PriceUpdateV2::get_price_no_older_than`
     PythPriceUpdateV2GetPriceNoOlderThan,
     /// `solana_program::account_info::AccountInfo`
     SolanaAccountInfo,
+    /// `solana_program::instruction::AccountMeta::new`
+    SolanaAccountMetaNew,
+    /// `solana_program::instruction::AccountMeta::new_readonly`
+    SolanaAccountMetaNewReadonly,
     /// `solana_program::instruction::Instruction`
     SolanaInstruction,
+    /// `solana_program::sysvar::instructions::load_instruction_at_checked`
+    SolanaLoadInstructionAtChecked,
+    /// `solana_program::sysvar::instructions::get_instruction_relative`
+    SolanaGetInstructionRelative,
     /// `solana_program::pubkey::Pubkey`
     SolanaPubkey,
+    /// `solana_program::pubkey::Pubkey::create_program_address`
+    SolanaPubkeyCreateProgramAddress,
     /// `spl_token::state::Account`
     SplTokenAccount,
     /// `spl_token::state::Mint`
@@ -88,6 +119,13 @@ impl DiagnoticItem {
             DiagnoticItem::AnchorAccount => "AnchorAccount",
             DiagnoticItem::AnchorAccountLoader => "AnchorAccountLoader",
             DiagnoticItem::AnchorAccountReload => "AnchorAccountReload",
+            DiagnoticItem::AnchorAccountLoaderLoad
+            | DiagnoticItem::AnchorAccountLoaderLoadMut
+            | DiagnoticItem::AnchorAccountDeserialize
+            | DiagnoticItem::AnchorAccountDeserializeUnchecked
+            | DiagnoticItem::AnchorIdFn => {
+                return None;
+            }
             DiagnoticItem::AnchorCpiContext => "AnchorCpiContext",
             DiagnoticItem::AnchorCpiContextWithRemainingAccounts => {
                 "AnchorCpiContextWithRemainingAccounts"
@@ -99,6 +137,7 @@ impl DiagnoticItem {
             DiagnoticItem::AnchorCpiInvokeSignedUnchecked => "AnchorCpiInvokeSignedUnchecked",
             DiagnoticItem::AnchorInterfaceAccount => "AnchorInterfaceAccount",
             DiagnoticItem::AnchorKey => "AnchorKey",
+            DiagnoticItem::AnchorProgram => "AnchorProgram",
             DiagnoticItem::AnchorAccountSetInner => "AnchorAccountSetInner",
             DiagnoticItem::AnchorSigner => "AnchorSigner",
             DiagnoticItem::AnchorSystemProgramTransfer => "AnchorSystemProgramTransfer",
@@ -134,13 +173,28 @@ impl DiagnoticItem {
             DiagnoticItem::PythPriceUpdateV2GetPriceNoOlderThan => {
                 return None;
             }
+            DiagnoticItem::AnchorSplToken2022Transfer
+            | DiagnoticItem::AnchorSplToken2022TransferChecked => {
+                return None;
+            }
+            DiagnoticItem::AnchorSplTokenTransferAccounts
+            | DiagnoticItem::AnchorSplToken2022TransferAccounts => {
+                return None;
+            }
             DiagnoticItem::SolanaAccountInfo => {
                 return None;
             }
+            DiagnoticItem::SolanaAccountMetaNew | DiagnoticItem::SolanaAccountMetaNewReadonly => {
+                return None;
+            }
             DiagnoticItem::SolanaInstruction => {
                 return None;
             }
-            DiagnoticItem::SolanaPubkey => {
+            DiagnoticItem::SolanaLoadInstructionAtChecked
+            | DiagnoticItem::SolanaGetInstructionRelative => {
+                return None;
+            }
+            DiagnoticItem::SolanaPubkey | DiagnoticItem::SolanaPubkeyCreateProgramAddress => {
                 return None;
             }
             DiagnoticItem::SplTokenAccount => {
@@ -160,9 +214,23 @@ impl DiagnoticItem {
                 "anchor_lang::prelude::Account",
             ],
             DiagnoticItem::AnchorAccountLoader => &["anchor_lang::prelude::AccountLoader"],
+            DiagnoticItem::AnchorAccountLoaderLoad => {
+                &["anchor_lang::accounts::account_loader::AccountLoader::<'info, T>::load"]
+            }
+            DiagnoticItem::AnchorAccountLoaderLoadMut => {
+                &["anchor_lang::accounts::account_loader::AccountLoader::<'info, T>::load_mut"]
+            }
             DiagnoticItem::AnchorAccountReload => {
                 &["anchor_lang::accounts::account::Account::reload"]
             }
+            DiagnoticItem::AnchorAccountDeserialize => &[
+                "anchor_lang::AccountDeserialize::try_deserialize",
+                "anchor_lang::prelude::AccountDeserialize::try_deserialize",
+            ],
+            DiagnoticItem::AnchorAccountDeserializeUnchecked => &[
+                "anchor_lang::AccountDeserialize::try_deserialize_unchecked",
+                "anchor_lang::prelude::AccountDeserialize::try_deserialize_unchecked",
+            ],
             DiagnoticItem::AnchorCpiContext => &["anchor_lang::context::CpiContext"],
             DiagnoticItem::AnchorCpiContextWithRemainingAccounts => &[
                 "anchor_lang::context::CpiContext::with_remaining_accounts",
@@ -188,6 +256,7 @@ impl DiagnoticItem {
                 "anchor_lang::solana_program::program::invoke_signed_unchecked",
                 "solana_invoke::invoke_signed_unchecked",
             ],
+            DiagnoticItem::AnchorIdFn => &["anchor_lang::Id::id", "anchor_lang::prelude::Id::id"],
             DiagnoticItem::AnchorInterfaceAccount => &[
                 "anchor_lang::prelude::InterfaceAccount",
                 "anchor_lang::accounts::interface_account::InterfaceAccount",
@@ -196,6 +265,10 @@ impl DiagnoticItem {
             DiagnoticItem::AnchorKey => {
                 &["anchor_lang::prelude::Key::key", "anchor_lang::Key::key"]
             }
+            DiagnoticItem::AnchorProgram => &[
+                "anchor_lang::prelude::Program",
+                "anchor_lang::accounts::program::Program",
+            ],
             DiagnoticItem::AnchorAccountSetInner => &[
                 "anchor_lang::prelude::Account::set_inner",
                 "anchor_lang::accounts::account::Account::set_inner",
@@ -218,6 +291,10 @@ impl DiagnoticItem {
             ],
             DiagnoticItem::AnchorUncheckedAccount => &["anchor_lang::prelude::UncheckedAccount"],
             DiagnoticItem::AnchorSplTokenTransfer => &["anchor_spl::token::transfer"],
+            DiagnoticItem::AnchorSplTokenTransferAccounts => &[
+                "anchor_spl::token::Transfer",
+                "anchor_spl::token::TransferChecked",
+            ],
             DiagnoticItem::AnchorSplTokenAccount => &["anchor_spl::token::TokenAccount"],
             DiagnoticItem::AnchorSplTokenInterfaceTokenAccount => {
                 &["anchor_spl::token_interface::TokenAccount"]
@@ -239,6 +316,14 @@ impl DiagnoticItem {
             DiagnoticItem::AnchorSplToken2022GetAccountDataSize => {
                 &["anchor_spl::token_2022::get_account_data_size"]
             }
+            DiagnoticItem::AnchorSplToken2022Transfer => &["anchor_spl::token_2022::transfer"],
+            DiagnoticItem::AnchorSplToken2022TransferChecked => {
+                &["anchor_spl::token_2022::transfer_checked"]
+            }
+            DiagnoticItem::AnchorSplToken2022TransferAccounts => &[
+                "anchor_spl::token_2022::Transfer",
+                "anchor_spl::token_2022::TransferChecked",
+            ],
             DiagnoticItem::PythPriceUpdateV2 => {
                 &["pyth_solana_receiver_sdk::price_update::PriceUpdateV2"]
             }
@@ -246,13 +331,35 @@ impl DiagnoticItem {
                 &["pyth_solana_receiver_sdk::price_update::PriceUpdateV2::get_price_no_older_than"]
             }
             DiagnoticItem::SolanaAccountInfo => &["solana_program::account_info::AccountInfo"],
+            DiagnoticItem::SolanaAccountMetaNew => &[
+                "solana_program::instruction::AccountMeta::new",
+                "solana_instruction::AccountMeta::new",
+                "solana_instruction::account_meta::AccountMeta::new",
+            ],
+            DiagnoticItem::SolanaAccountMetaNewReadonly => &[
+                "solana_program::instruction::AccountMeta::new_readonly",
+                "solana_instruction::AccountMeta::new_readonly",
+                "solana_instruction::account_meta::AccountMeta::new_readonly",
+            ],
             DiagnoticItem::SolanaInstruction => &[
                 "solana_program::instruction::Instruction",
                 "solana_program::instruction::CompiledInstruction",
             ],
+            DiagnoticItem::SolanaLoadInstructionAtChecked => &[
+                "solana_program::sysvar::instructions::load_instruction_at_checked",
+                "solana_instructions_sysvar::load_instruction_at_checked",
+            ],
+            DiagnoticItem::SolanaGetInstructionRelative => &[
+                "solana_program::sysvar::instructions::get_instruction_relative",
+                "solana_instructions_sysvar::get_instruction_relative",
+            ],
             DiagnoticItem::SolanaPubkey => {
                 &["solana_program::pubkey::Pubkey", "solana_pubkey::Pubkey"]
             }
+            DiagnoticItem::SolanaPubkeyCreateProgramAddress => &[
+                "solana_program::pubkey::Pubkey::create_program_address",
+                "solana_pubkey::Pubkey::create_program_address",
+            ],
             DiagnoticItem::SplTokenAccount => &["spl_token::state::Account"],
             DiagnoticItem::SplTokenMint => &["spl_token::state::Mint"],
         }
@@ -310,6 +417,28 @@ pub fn is_anchor_cpi_context(tcx: TyCtxt, ty: Ty) -> bool {
     DiagnoticItem::AnchorCpiContext.defid_is_type(tcx, ty)
 }
 
+/// Check if a given [`DefId`] is an instruction of the `cpi` module Anchor generates for a
+/// program, e.g. `other_program::cpi::deposit`, which take a [`CpiContext`] as first argument.
+///
+/// [`CpiContext`]: DiagnoticItem::AnchorCpiContext
+pub fn is_anchor_generated_cpi_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    if !matches!(tcx.def_kind(def_id), DefKind::Fn) {
+        return false;
+    }
+    let in_cpi_module = tcx
+        .opt_parent(def_id)
+        .and_then(|parent| tcx.opt_item_name(parent))
+        .is_some_and(|name| name.as_str() == "cpi");
+    in_cpi_module
+        && tcx
+            .fn_sig(def_id)
+            .skip_binder()
+            .skip_binder()
+            .inputs()
+            .first()
+            .is_some_and(|ty| is_anchor_cpi_context(tcx, *ty))
+}
+
 pub fn is_anchor_context(tcx: TyCtxt, ty: Ty) -> bool {
     let ty = ty.peel_refs();
     DiagnoticItem::AnchorContext.defid_is_type(tcx, ty)
@@ -357,6 +486,11 @@ pub fn is_anchor_unchecked_account_type(tcx: TyCtxt, ty: Ty) -> bool {
     DiagnoticItem::AnchorUncheckedAccount.defid_is_type(tcx, ty)
 }
 
+pub fn is_anchor_program_type(tcx: TyCtxt, ty: Ty) -> bool {
+    let ty = ty.peel_refs();
+    DiagnoticItem::AnchorProgram.defid_is_type(tcx, ty)
+}
+
 pub fn is_account_info_type(tcx: TyCtxt, ty: Ty) -> bool {
     let ty = ty.peel_refs();
     DiagnoticItem::SolanaAccountInfo.defid_is_type(tcx, ty)
@@ -367,6 +501,11 @@ pub fn is_solana_pubkey_type(tcx: TyCtxt, ty: Ty) -> bool {
     DiagnoticItem::SolanaPubkey.defid_is_type(tcx, ty)
 }
 
+/// Check if a given [`DefId`] is `Pubkey::create_program_address`
+pub fn is_solana_create_program_address_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    DiagnoticItem::SolanaPubkeyCreateProgramAddress.defid_is_item(tcx, def_id)
+}
+
 pub fn is_anchor_account_set_inner_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     DiagnoticItem::AnchorAccountSetInner.defid_is_item(tcx, def_id)
 }
@@ -379,10 +518,26 @@ pub fn is_anchor_cpi_context_with_remaining_accounts_fn(tcx: TyCtxt, def_id: Def
     DiagnoticItem::AnchorCpiContextWithRemainingAccounts.defid_is_item(tcx, def_id)
 }
 
+/// Check if a given [`DefId`] borrows the data of a zero-copy account, i.e. `AccountLoader::load`
+/// or `AccountLoader::load_mut`
+pub fn is_anchor_account_loader_load_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::AnchorAccountLoaderLoad,
+        DiagnoticItem::AnchorAccountLoaderLoadMut,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
 pub fn is_anchor_key_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     DiagnoticItem::AnchorKey.defid_is_item(tcx, def_id)
 }
 
+/// Check if a given [`DefId`] is `anchor_lang::Id::id`, the fixed address of a program type
+pub fn is_anchor_id_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    DiagnoticItem::AnchorIdFn.defid_is_item(tcx, def_id)
+}
+
 pub fn is_anchor_system_program_type(tcx: TyCtxt, ty: Ty) -> bool {
     let ty::Adt(adt, _) = ty.kind() else {
         return false;
@@ -405,6 +560,27 @@ pub fn is_anchor_system_program_lamports_only_cpi(tcx: TyCtxt, def_id: DefId) ->
     .any(|item| item.defid_is_item(tcx, def_id))
 }
 
+/// Check if a given [`DefId`] is a Token-2022 transfer CPI helper of `anchor_spl`
+pub fn is_anchor_spl_token_2022_transfer_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::AnchorSplToken2022Transfer,
+        DiagnoticItem::AnchorSplToken2022TransferChecked,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
+/// Check if a given [`DefId`] is the accounts struct of an `anchor_spl` token transfer CPI, e.g.
+/// `anchor_spl::token::Transfer`
+pub fn is_anchor_spl_transfer_accounts(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::AnchorSplTokenTransferAccounts,
+        DiagnoticItem::AnchorSplToken2022TransferAccounts,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
 pub fn is_anchor_spl_token_interface_safe_cpi(tcx: TyCtxt, def_id: DefId) -> bool {
     [
         DiagnoticItem::AnchorSplToken2022GetAccountDataSize,
@@ -461,6 +637,39 @@ pub fn is_solana_instruction_type(tcx: TyCtxt, ty: Ty) -> bool {
     DiagnoticItem::SolanaInstruction.defid_is_type(tcx, ty)
 }
 
+/// Check if a given [`DefId`] builds the `AccountMeta` of a hand-built instruction, i.e.
+/// `AccountMeta::new` or `AccountMeta::new_readonly`, whose second argument is `is_signer`
+pub fn is_account_meta_constructor_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::SolanaAccountMetaNew,
+        DiagnoticItem::SolanaAccountMetaNewReadonly,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
+/// Check if a given [`DefId`] loads an instruction of the transaction from the instructions
+/// sysvar, i.e. `load_instruction_at_checked` or `get_instruction_relative`
+pub fn is_instruction_introspection_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::SolanaLoadInstructionAtChecked,
+        DiagnoticItem::SolanaGetInstructionRelative,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
+/// Check if a given [`DefId`] decodes an `#[account]` type with `AccountDeserialize`, i.e.
+/// `try_deserialize` or `try_deserialize_unchecked`
+pub fn is_anchor_account_deserialize_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::AnchorAccountDeserialize,
+        DiagnoticItem::AnchorAccountDeserializeUnchecked,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(tcx, def_id))
+}
+
 pub fn is_box_type(tcx: TyCtxt, ty: Ty) -> bool {
     let ty = ty.peel_refs();
     if let ty::Adt(adt_def, _) = ty.kind() {
@@ -475,6 +684,11 @@ pub fn is_constructor_like_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     tcx.item_name(def_id).as_str().starts_with("new")
 }
 
+/// Check if a given [`DefId`] is `Default::default`
+pub fn is_default_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    tcx.is_diagnostic_item(sym::default_fn, def_id)
+}
+
 pub fn is_borrow_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     matches!(tcx.item_name(def_id).as_str(), "borrow" | "borrow_mut")
 }
@@ -483,6 +697,20 @@ pub fn is_deserialize_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     tcx.item_name(def_id).as_str().contains("deserialize")
 }
 
+/// Check if a given [`DefId`] is an SPL Token or Token-2022 instruction builder, e.g.
+/// `spl_token::instruction::transfer`, which take the token program id as first argument
+pub fn is_spl_token_instruction_builder_fn(tcx: TyCtxt, def_id: DefId) -> bool {
+    let path = tcx.def_path_str(def_id);
+    [
+        "spl_token::instruction::",
+        "spl_token_2022::instruction::",
+        "spl_token_interface::instruction::",
+        "spl_token_2022_interface::instruction::",
+    ]
+    .iter()
+    .any(|module| path.contains(module))
+}
+
 pub fn is_cpi_builder_constructor_fn(tcx: TyCtxt, def_id: DefId) -> bool {
     let path = tcx.def_path_str(def_id);
     path.contains("CpiBuilder::new")
@@ -0,0 +1,75 @@
+//! Wrappers mirroring `clippy_utils::diagnostics` that report through the
+//! [findings sink](crate::findings). Lints emit through these instead of `clippy_utils` directly.
+
+use rustc_hir::HirId;
+use rustc_lint::{LateContext, Lint};
+use rustc_span::Span;
+
+use crate::findings::{Finding, emit_finding, emit_finding_at};
+
+/// Emit a basic lint message
+pub fn span_lint(cx: &LateContext<'_>, lint: &'static Lint, sp: Span, msg: impl Into<String>) {
+    emit_finding(cx, lint, Finding::new(lint, sp, msg));
+}
+
+/// Emit a basic lint message with the lint level in effect at `hir_id`
+pub fn span_lint_hir(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    hir_id: HirId,
+    sp: Span,
+    msg: impl Into<String>,
+) {
+    emit_finding_at(cx, lint, hir_id, Finding::new(lint, sp, msg));
+}
+
+/// Emit a lint message with a note, optionally pointing at `note_span`
+pub fn span_lint_and_note(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    span: Span,
+    msg: impl Into<String>,
+    note_span: Option<Span>,
+    note: impl Into<String>,
+) {
+    emit_finding(
+        cx,
+        lint,
+        Finding::new(lint, span, msg).with_note(note_span, note),
+    );
+}
+
+/// Emit a lint message with a note, optionally pointing at `note_span`, with the lint level in
+/// effect at `hir_id`
+pub fn span_lint_hir_and_note(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    hir_id: HirId,
+    span: Span,
+    msg: impl Into<String>,
+    note_span: Option<Span>,
+    note: impl Into<String>,
+) {
+    emit_finding_at(
+        cx,
+        lint,
+        hir_id,
+        Finding::new(lint, span, msg).with_note(note_span, note),
+    );
+}
+
+/// Emit a lint message with a help message, optionally pointing at `help_span`
+pub fn span_lint_and_help(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    span: Span,
+    msg: impl Into<String>,
+    help_span: Option<Span>,
+    help: impl Into<String>,
+) {
+    emit_finding(
+        cx,
+        lint,
+        Finding::new(lint, span, msg).with_help(help_span, help),
+    );
+}
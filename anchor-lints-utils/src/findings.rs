@@ -0,0 +1,400 @@
+//! Findings sink shared by all lints.
+//!
+//! Every lint reports through [`emit_finding`] with a [`Finding`]. By default findings are emitted
+//! as rustc diagnostics; tools embedding the lints as a library can install their own
+//! [`FindingSink`] with [`set_finding_sink`] to collect them instead.
+//!
+//! When `ANCHOR_LINTS_SUMMARY` is set to a file path, every emitted finding is appended to that
+//! file as a `lint<TAB>crate<TAB>file<TAB>message<TAB>confidence` line. Lint libraries are invoked per function
+//! and every crate is analyzed by its own driver process, so findings are written as soon as they
+//! are emitted instead of being buffered and lost when the process exits. The
+//! `anchor-lints-summary` binary aggregates the file once the run is over, and
+//! `anchor-lints-diff` compares it against the file recorded for a baseline run.
+//!
+//! When `ANCHOR_LINTS_HELP_NOTES` is set, the first finding of each lint also carries the
+//! remediation help of the lint from the [registry](crate::registry).
+//!
+//! Every finding carries the [`Confidence`] of its lint from the registry. When
+//! `ANCHOR_LINTS_MIN_CONFIDENCE` is set to `high`, `medium` or `low`, findings below that confidence
+//! are dropped before they are recorded or reported. The variable is the only control over the
+//! minimum confidence, the [configuration](crate::config) has no key for it.
+//!
+//! A finding is suppressed by an `// anchor-lints: validated-elsewhere <reason>` comment at the
+//! end of the line it points at, or on its own on the line above, documenting why the flagged
+//! pattern is safe. Findings allowed with `#[allow]`, `-A` or an `// anchor-lints:allow(<lint>)`
+//! comment on the line above them, and findings dropped by `ANCHOR_LINTS_MIN_CONFIDENCE`, are
+//! suppressed as well. Suppressed findings are not reported, but are still recorded with their
+//! reason in a sixth column and their [`SuppressionSource`] in a seventh one, so the summary
+//! report and the baseline diff list them for auditing.
+
+use rustc_hir::{HirId, def_id::LOCAL_CRATE};
+use rustc_lint::{LateContext, Level, Lint};
+use rustc_middle::lint::LintLevelSource;
+use rustc_span::{DUMMY_SP, FileNameDisplayPreference, Span};
+
+use std::{
+    collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
+    sync::{Mutex, RwLock},
+};
+
+use crate::{
+    registry::{HELP_NOTES_ENV_VAR, confidence_for, help_for},
+    utils::is_allowed_inline,
+};
+
+/// Environment variable naming the file findings are recorded to
+pub const SUMMARY_ENV_VAR: &str = "ANCHOR_LINTS_SUMMARY";
+
+/// Environment variable setting the minimum confidence of reported findings
+pub const MIN_CONFIDENCE_ENV_VAR: &str = "ANCHOR_LINTS_MIN_CONFIDENCE";
+
+/// Comment suppressing the finding on its line, followed by the reason the pattern is safe
+pub const SUPPRESSION_MARKER: &str = "anchor-lints: validated-elsewhere";
+
+/// Severity of a finding, derived from the lint level in effect where it is emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// How likely a finding is a true positive. Lints matching exact declarations, e.g. duplicate
+/// seeds, are high confidence; lints relying on heuristics, e.g. names or loop bounds, are low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    /// Name of the confidence, as accepted by `ANCHOR_LINTS_MIN_CONFIDENCE`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+
+    /// Parse a confidence name case-insensitively
+    pub fn parse(name: &str) -> Option<Self> {
+        [Confidence::Low, Confidence::Medium, Confidence::High]
+            .into_iter()
+            .find(|confidence| confidence.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// What kept a finding from being reported during the run. Baselines are only known to
+/// `anchor-lints-diff`, which lists the findings already present in the baseline with a
+/// `baseline` source of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionSource {
+    /// `#[allow]` on an enclosing item, `-A` on the command line, or an
+    /// `// anchor-lints:allow(..)` comment above the finding
+    Allow,
+    /// An `// anchor-lints: validated-elsewhere` comment
+    Comment,
+    /// The confidence of the finding is below `ANCHOR_LINTS_MIN_CONFIDENCE`
+    Confidence,
+}
+
+impl SuppressionSource {
+    /// Name of the source, as recorded in the summary file
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SuppressionSource::Allow => "allow",
+            SuppressionSource::Comment => "comment",
+            SuppressionSource::Confidence => "confidence",
+        }
+    }
+}
+
+/// A single lint finding, independent of how it is reported
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Declared name of the lint, e.g. `MISSING_SIGNER_VALIDATION`
+    pub lint: &'static str,
+    pub message: String,
+    pub span: Span,
+    /// Notes attached to the finding, notes at `DUMMY_SP` have no location
+    pub notes: Vec<(Span, String)>,
+    /// Help messages attached to the finding, helps at `DUMMY_SP` have no location
+    pub helps: Vec<(Span, String)>,
+    pub severity: Severity,
+    pub confidence: Confidence,
+}
+
+impl Finding {
+    pub fn new(lint: &'static Lint, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            lint: lint.name,
+            message: message.into(),
+            span,
+            notes: Vec::new(),
+            helps: Vec::new(),
+            severity: Severity::Warning,
+            confidence: confidence_for(lint.name),
+        }
+    }
+
+    /// Override the confidence of the lint for this finding
+    pub fn with_confidence(mut self, confidence: Confidence) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Attach a note, pointing at `span` if given
+    pub fn with_note(mut self, span: Option<Span>, note: impl Into<String>) -> Self {
+        self.notes.push((span.unwrap_or(DUMMY_SP), note.into()));
+        self
+    }
+
+    /// Attach a help message, pointing at `span` if given
+    pub fn with_help(mut self, span: Option<Span>, help: impl Into<String>) -> Self {
+        self.helps.push((span.unwrap_or(DUMMY_SP), help.into()));
+        self
+    }
+}
+
+/// Receives the findings of every lint in place of rustc diagnostics
+pub trait FindingSink: Send + Sync {
+    fn emit(&self, finding: Finding);
+}
+
+static FINDING_SINK: RwLock<Option<Box<dyn FindingSink>>> = RwLock::new(None);
+
+/// Lints whose remediation help was already attached to a finding
+static HELPED_LINTS: Mutex<Option<HashSet<&'static str>>> = Mutex::new(None);
+
+/// Install a sink receiving all findings, or restore rustc diagnostics with `None`.
+/// Returns the previously installed sink.
+pub fn set_finding_sink(sink: Option<Box<dyn FindingSink>>) -> Option<Box<dyn FindingSink>> {
+    match FINDING_SINK.write() {
+        Ok(mut installed) => std::mem::replace(&mut *installed, sink),
+        Err(poisoned) => std::mem::replace(&mut *poisoned.into_inner(), sink),
+    }
+}
+
+/// Hand the finding to the installed sink, giving it back if no sink is installed
+pub fn deliver_to_installed_sink(finding: Finding) -> Option<Finding> {
+    let installed = FINDING_SINK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match installed.as_ref() {
+        Some(sink) => {
+            sink.emit(finding);
+            None
+        }
+        None => Some(finding),
+    }
+}
+
+/// Report a finding of `lint` through the installed sink, or as a rustc diagnostic by default
+pub fn emit_finding(cx: &LateContext<'_>, lint: &'static Lint, finding: Finding) {
+    emit_finding_at(cx, lint, cx.last_node_with_lint_attrs, finding);
+}
+
+/// Report a finding with the lint level in effect at `hir_id` instead of the item being checked,
+/// for findings located in another item, e.g. a helper analyzed from its caller
+pub fn emit_finding_at(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    hir_id: HirId,
+    mut finding: Finding,
+) {
+    let min_confidence = min_confidence();
+    if finding.confidence < min_confidence {
+        let reason = format!("{MIN_CONFIDENCE_ENV_VAR}={}", min_confidence.as_str());
+        record_suppressed_finding(cx, lint, &finding, SuppressionSource::Confidence, &reason);
+        return;
+    }
+
+    let level_and_source = cx.tcx.lint_level_at_node(lint, hir_id);
+    let level = level_and_source.level;
+    finding.severity = if matches!(level, Level::Deny | Level::Forbid) {
+        Severity::Error
+    } else {
+        Severity::Warning
+    };
+
+    if level != Level::Allow {
+        // An `// anchor-lints:allow(..)` comment above the finding allows the lint on that line only
+        if is_allowed_inline(cx, lint, finding.span) {
+            record_suppressed_finding(cx, lint, &finding, SuppressionSource::Allow, "");
+            return;
+        }
+        // Suppressed findings are only recorded, along with the reason documented in the source
+        if let Some(reason) = suppression_reason(cx, finding.span) {
+            record_suppressed_finding(cx, lint, &finding, SuppressionSource::Comment, &reason);
+            return;
+        }
+        record_finding(cx, lint, &finding);
+        attach_help_note(&mut finding);
+    } else {
+        // Lints allowed by default were never enabled, only explicit allows are suppressions
+        let reason = match level_and_source.src {
+            LintLevelSource::Default => None,
+            LintLevelSource::Node { reason, .. } => {
+                Some(reason.map(|reason| reason.to_string()).unwrap_or_default())
+            }
+            LintLevelSource::CommandLine(..) => Some(String::new()),
+        };
+        if let Some(reason) = reason {
+            record_suppressed_finding(cx, lint, &finding, SuppressionSource::Allow, &reason);
+        }
+    }
+
+    // Allowed lints never reach a custom sink, rustc silences the diagnostic itself
+    let finding = if level == Level::Allow {
+        finding
+    } else {
+        match deliver_to_installed_sink(finding) {
+            Some(finding) => finding,
+            None => return,
+        }
+    };
+
+    clippy_utils::diagnostics::span_lint_hir_and_then(
+        cx,
+        lint,
+        hir_id,
+        finding.span,
+        finding.message,
+        |diag| {
+            for (span, note) in finding.notes {
+                if span == DUMMY_SP {
+                    diag.note(note);
+                } else {
+                    diag.span_note(span, note);
+                }
+            }
+            for (span, help) in finding.helps {
+                if span == DUMMY_SP {
+                    diag.help(help);
+                } else {
+                    diag.span_help(span, help);
+                }
+            }
+        },
+    );
+}
+
+/// Reason of the suppression comment on the line of `span`, or alone on the line above it
+fn suppression_reason(cx: &LateContext<'_>, span: Span) -> Option<String> {
+    let span = span.source_callsite();
+    if span == DUMMY_SP {
+        return None;
+    }
+    let loc = cx.tcx.sess.source_map().lookup_char_pos(span.lo());
+    // `Loc::line` is 1-based, `SourceFile::get_line` is 0-based
+    let line_index = loc.line.checked_sub(1)?;
+    if let Some(line) = loc.file.get_line(line_index)
+        && let Some(reason) = parse_suppression(&line)
+    {
+        return Some(reason.to_string());
+    }
+    let previous = loc.file.get_line(line_index.checked_sub(1)?)?;
+    if !previous.trim_start().starts_with("//") {
+        return None;
+    }
+    parse_suppression(&previous).map(str::to_string)
+}
+
+/// Parse the reason of a suppression comment in a source line, `Some("")` if none is given
+pub fn parse_suppression(line: &str) -> Option<&str> {
+    let rest = line.match_indices("//").find_map(|(start, _)| {
+        line[start + 2..]
+            .trim_start()
+            .strip_prefix(SUPPRESSION_MARKER)
+    })?;
+    Some(
+        rest.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, ':' | '-' | '—'))
+            .trim_end(),
+    )
+}
+
+/// Minimum confidence of reported findings, every finding is reported if it is unset or invalid
+fn min_confidence() -> Confidence {
+    std::env::var(MIN_CONFIDENCE_ENV_VAR)
+        .ok()
+        .and_then(|name| Confidence::parse(&name))
+        .unwrap_or(Confidence::Low)
+}
+
+/// Attach the remediation help of the lint to its first finding if help notes are enabled
+fn attach_help_note(finding: &mut Finding) {
+    if std::env::var_os(HELP_NOTES_ENV_VAR).is_none() {
+        return;
+    }
+    let help = help_for(finding.lint);
+    if help.is_empty() {
+        return;
+    }
+    let mut helped = HELPED_LINTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if helped.get_or_insert_default().insert(finding.lint) {
+        finding.helps.push((DUMMY_SP, help.to_string()));
+    }
+}
+
+/// Record a finding if the summary report is enabled
+pub fn record_finding(cx: &LateContext<'_>, lint: &'static Lint, finding: &Finding) {
+    write_record(cx, lint, finding, None);
+}
+
+/// Record a suppressed finding with the source and the reason of its suppression if the summary
+/// report is enabled, the reason is empty if none was given
+pub fn record_suppressed_finding(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    finding: &Finding,
+    source: SuppressionSource,
+    reason: &str,
+) {
+    write_record(cx, lint, finding, Some((source, reason)));
+}
+
+fn write_record(
+    cx: &LateContext<'_>,
+    lint: &'static Lint,
+    finding: &Finding,
+    suppression: Option<(SuppressionSource, &str)>,
+) {
+    let Some(path) = std::env::var_os(SUMMARY_ENV_VAR) else {
+        return;
+    };
+
+    let file = cx
+        .tcx
+        .sess
+        .source_map()
+        .span_to_filename(finding.span)
+        .display(FileNameDisplayPreference::Local)
+        .to_string();
+    // Keep the record on a single line
+    let message = finding.message.replace(['\t', '\n'], " ");
+    let mut record = format!(
+        "{}\t{}\t{}\t{}\t{}",
+        lint.name_lower(),
+        cx.tcx.crate_name(LOCAL_CRATE),
+        file,
+        message,
+        finding.confidence.as_str()
+    );
+    if let Some((source, reason)) = suppression {
+        record.push('\t');
+        record.push_str(&reason.replace(['\t', '\n'], " "));
+        record.push('\t');
+        record.push_str(source.as_str());
+    }
+    record.push('\n');
+
+    // Failing to record a finding must not fail the lint run
+    if let Ok(mut out) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = out.write_all(record.as_bytes());
+    }
+}
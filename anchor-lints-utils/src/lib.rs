@@ -4,13 +4,20 @@
 
 extern crate rustc_ast;
 extern crate rustc_data_structures;
+extern crate rustc_errors;
 extern crate rustc_hir;
+extern crate rustc_index;
 extern crate rustc_lint;
 extern crate rustc_middle;
 extern crate rustc_span;
 
+pub mod cfg;
+pub mod config;
 pub mod cpi_types;
 pub mod diag_items;
+pub mod diagnostics;
+pub mod findings;
 pub mod mir_analyzer;
 pub mod models;
+pub mod registry;
 pub mod utils;
@@ -1,14 +1,17 @@
 use clippy_utils::source::HasSession;
+use rustc_data_structures::graph::Successors;
 use rustc_middle::{
-    mir::{HasLocalDecls, Local},
+    mir::{BasicBlock, HasLocalDecls, Local, Operand, RETURN_PLACE, TerminatorKind},
     ty::{self as rustc_ty, TyKind},
 };
-use rustc_span::source_map::Spanned;
+use rustc_span::{Span, source_map::Spanned};
 
 use std::collections::HashSet;
 
 use super::types::{AnchorContextInfo, MirAnalyzer};
-use crate::utils::remove_comments;
+use crate::cfg::reachable_from;
+use crate::diag_items::{is_anchor_cpi_context, is_cpi_invoke_fn};
+use crate::utils::{extract_arg_local, get_hir_body_from_local_def_id, remove_comments};
 use crate::{diag_items::DiagnoticItem, models::*};
 
 impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
@@ -176,6 +179,52 @@ impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
         from_local_original == to_local_original
     }
 
+    /// Collect the CPIs that can run after the code at `span`, i.e. the reachable calls to
+    /// `invoke`/`invoke_signed` or to a function taking a `CpiContext`, with the span of each call
+    pub fn reachable_cpis_after(&self, span: Span) -> Vec<(BasicBlock, Span)> {
+        let basic_blocks = &self.mir.basic_blocks;
+        let mut starts = Vec::new();
+        for (bb, bbdata) in basic_blocks.iter_enumerated() {
+            if bbdata
+                .statements
+                .iter()
+                .any(|stmt| span.contains(stmt.source_info.span))
+            {
+                // The call ending the block runs after its statements
+                starts.push(bb);
+            } else if span.contains(bbdata.terminator().source_info.span) {
+                starts.extend(basic_blocks.successors(bb));
+            }
+        }
+
+        reachable_from(basic_blocks, starts)
+            .into_iter()
+            .filter_map(|bb| Some((bb, self.cpi_call_span(bb)?)))
+            .collect()
+    }
+
+    /// Get the span of the CPI ending `bb`, if any
+    fn cpi_call_span(&self, bb: BasicBlock) -> Option<Span> {
+        let tcx = self.cx.tcx;
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &self.mir.basic_blocks[bb].terminator().kind
+        else {
+            return None;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            return None;
+        };
+        // Builders such as `CpiContext::with_signer` take a context without invoking it
+        let return_ty = tcx.fn_sig(*fn_def_id).skip_binder().skip_binder().output();
+        let is_cpi = is_cpi_invoke_fn(tcx, *fn_def_id)
+            || (self.takes_cpi_context(args) && !is_anchor_cpi_context(tcx, return_ty));
+        is_cpi.then_some(*fn_span)
+    }
+
     pub fn takes_cpi_context(&self, args: &[Spanned<rustc_middle::mir::Operand>]) -> bool {
         args.iter().any(|arg| {
             if let rustc_middle::mir::Operand::Copy(place) | rustc_middle::mir::Operand::Move(place) = &arg.node
@@ -222,6 +271,50 @@ impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
                 }
             }
         }
-        None
+        self.find_helper_accounts_struct(*account_stuct_local)
+    }
+
+    /// Finds the accounts of an accounts struct returned by a same-crate helper, e.g.
+    /// `make_accounts(&ctx)` returning `Transfer { .. }`. The fields are built inside the helper,
+    /// so the arguments of the call they are derived from stand for them in this body.
+    fn find_helper_accounts_struct(&self, account_stuct_local: Local) -> Option<Vec<Local>> {
+        let (helper_def_id, args) = self.mir.basic_blocks.iter().find_map(|bbdata| {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && destination.as_local() == Some(account_stuct_local)
+                && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+                && let Some(helper_def_id) = fn_def_id.as_local()
+            {
+                Some((helper_def_id, args))
+            } else {
+                None
+            }
+        })?;
+        let body_id = get_hir_body_from_local_def_id(self.cx, helper_def_id)?;
+        let helper = MirAnalyzer::new(self.cx, self.cx.tcx.hir_body(body_id), helper_def_id);
+
+        // Fields of the struct literal the helper returns
+        let fields = helper
+            .collect_source_locals(RETURN_PLACE)
+            .into_iter()
+            .find_map(|local| helper.cpi_account_local_map.get(&local))?;
+
+        let mut accounts = Vec::new();
+        for field in fields {
+            let sources = helper.collect_source_locals(*field);
+            for (arg_index, param) in helper.mir.args_iter().enumerate() {
+                if sources.contains(&param)
+                    && let Some(arg_local) = extract_arg_local(args, arg_index)
+                    && !accounts.contains(&arg_local)
+                {
+                    accounts.push(arg_local);
+                }
+            }
+        }
+        (!accounts.is_empty()).then_some(accounts)
     }
 }
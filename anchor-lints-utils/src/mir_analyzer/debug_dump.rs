@@ -0,0 +1,102 @@
+//! Debug dump of the MIR of a function and the maps derived from it by [`MirAnalyzer`].
+//!
+//! When `ANCHOR_LINTS_DUMP_MIR` is set to a function name, e.g. `withdraw`, or to its full path,
+//! e.g. `my_program::my_program::withdraw`, every lint analyzing that function prints its basic
+//! blocks, the assignment maps, the origin of each `Pubkey` local and the Anchor context info to
+//! stderr. Each lint dumps the function from its own entry, so the dump shows the analyzer state
+//! the findings of that lint were computed from.
+
+use rustc_lint::Lint;
+use rustc_middle::mir::{Local, Operand, Place};
+
+use std::{collections::HashMap, fmt::Debug, fmt::Write};
+
+use super::types::MirAnalyzer;
+
+/// Environment variable selecting the function to dump
+pub const DUMP_MIR_ENV_VAR: &str = "ANCHOR_LINTS_DUMP_MIR";
+
+impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
+    /// Print the MIR and the derived maps to stderr if the analyzed function is selected with
+    /// `ANCHOR_LINTS_DUMP_MIR`
+    pub fn debug_dump(&self, lint: &Lint) {
+        let Ok(selected) = std::env::var(DUMP_MIR_ENV_VAR) else {
+            return;
+        };
+        let def_id = self.mir.source.def_id();
+        let tcx = self.cx.tcx;
+        let fn_path = tcx.def_path_str(def_id);
+        let is_selected = fn_path == selected
+            || tcx
+                .opt_item_name(def_id)
+                .is_some_and(|name| name.as_str() == selected);
+        if !is_selected {
+            return;
+        }
+
+        eprint!("{}", self.render_dump(&lint.name_lower(), &fn_path));
+    }
+
+    fn render_dump(&self, lint_name: &str, fn_path: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "==== MIR of `{}` for `{}` ====", fn_path, lint_name);
+
+        let _ = writeln!(out, "-- basic blocks --");
+        for (bb, bbdata) in self.mir.basic_blocks.iter_enumerated() {
+            let _ = writeln!(out, "{:?}:", bb);
+            for stmt in &bbdata.statements {
+                let _ = writeln!(out, "    {:?};", stmt);
+            }
+            let _ = writeln!(out, "    {:?};", bbdata.terminator().kind);
+        }
+
+        write_map(&mut out, "assignments", &self.assignment_map);
+        write_map(
+            &mut out,
+            "reverse assignments",
+            &self.reverse_assignment_map,
+        );
+        write_map(
+            &mut out,
+            "transitive reverse assignments",
+            &self.transitive_assignment_reverse_map,
+        );
+        write_map(&mut out, "CPI account locals", &self.cpi_account_local_map);
+        write_map(
+            &mut out,
+            "method call receivers",
+            &self.method_call_receiver_map,
+        );
+
+        let _ = writeln!(out, "-- origins of Pubkey locals --");
+        for local in self.mir.local_decls.indices() {
+            if self.is_pubkey_type(local) {
+                let origin = self.origin_of_operand(&Operand::Copy(Place::from(local)));
+                let _ = writeln!(out, "{:?}: {:?}", local, origin);
+            }
+        }
+
+        let _ = writeln!(out, "-- parameters --");
+        for param in &self.param_info {
+            let _ = writeln!(
+                out,
+                "{:?}: {} ({:?})",
+                param.param_local, param.param_name, param.param_ty
+            );
+        }
+
+        let _ = writeln!(out, "-- anchor context --");
+        let _ = writeln!(out, "{:#?}", self.anchor_context_info);
+        out
+    }
+}
+
+/// Write a map keyed by locals, sorted by local so dumps of the same function are comparable
+fn write_map<V: Debug>(out: &mut String, title: &str, map: &HashMap<Local, V>) {
+    let _ = writeln!(out, "-- {} --", title);
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by_key(|(local, _)| **local);
+    for (local, value) in entries {
+        let _ = writeln!(out, "{:?}: {:?}", local, value);
+    }
+}
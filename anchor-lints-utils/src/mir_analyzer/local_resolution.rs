@@ -29,6 +29,31 @@ impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
         from_local
     }
 
+    /// Collect the locals a value is derived from, following assignments and method call receivers
+    pub fn collect_source_locals(&self, local: Local) -> HashSet<Local> {
+        let mut visited = HashSet::new();
+        let mut to_check = vec![local];
+
+        while let Some(current) = to_check.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+
+            for (src_local, dest_locals) in &self.reverse_assignment_map {
+                if dest_locals.contains(&current) {
+                    to_check.push(*src_local);
+                }
+            }
+
+            // Follow `Try::branch` and similar calls back to the value they were called on
+            if let Some(receiver_local) = self.method_call_receiver_map.get(&current) {
+                to_check.push(*receiver_local);
+            }
+        }
+
+        visited
+    }
+
     /// Get local from operand
     pub fn get_local_from_operand(
         &self,
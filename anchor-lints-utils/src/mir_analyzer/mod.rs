@@ -2,8 +2,10 @@ pub use types::*;
 
 pub mod account_extraction;
 pub mod cpi_context;
+pub mod debug_dump;
 pub mod initialization;
 pub mod local_resolution;
+pub mod monotonic;
 pub mod nested_arguments;
 pub mod parameter_analysis;
 pub mod type_analysis;
@@ -0,0 +1,120 @@
+use rustc_middle::mir::{BinOp, Local, Operand, Place, Rvalue, StatementKind};
+
+use std::collections::HashSet;
+
+use super::types::MirAnalyzer;
+use crate::models::*;
+
+impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
+    /// Detect whether a new value is compared against a stored state field and written back to it,
+    /// e.g. `require!(new_ts > state.last_update_ts)` followed by `state.last_update_ts = new_ts`.
+    /// `is_new_value` matches places holding the incoming value, `is_state_field` matches the
+    /// stored field.
+    pub fn detects_monotonic_store_and_compare(
+        &self,
+        is_new_value: impl Fn(&Place<'tcx>) -> bool,
+        is_state_field: impl Fn(&Place<'tcx>) -> bool,
+    ) -> MonotonicEnforcement {
+        let mut enforcement = MonotonicEnforcement::default();
+
+        let mut new_value_locals = HashSet::new();
+        let mut state_field_locals = HashSet::new();
+
+        for (_bb, bbdata) in self.mir.basic_blocks.iter_enumerated() {
+            for stmt in &bbdata.statements {
+                let StatementKind::Assign(box (dest_place, rvalue)) = &stmt.kind else {
+                    continue;
+                };
+
+                if let Rvalue::Use(Operand::Copy(src_place) | Operand::Move(src_place)) = rvalue {
+                    // Track locals holding the new value or the state field
+                    if let Some(dest_local) = dest_place.as_local() {
+                        if is_new_value(src_place)
+                            || src_place.as_local().is_some_and(|src_local| {
+                                self.is_tracked_local(src_local, &new_value_locals)
+                            })
+                        {
+                            new_value_locals.insert(dest_local);
+                        }
+                        if is_state_field(src_place)
+                            || src_place.as_local().is_some_and(|src_local| {
+                                self.is_tracked_local(src_local, &state_field_locals)
+                            })
+                        {
+                            state_field_locals.insert(dest_local);
+                        }
+                    }
+
+                    // Store: state field = new value
+                    if is_state_field(dest_place)
+                        && let Some(src_local) = src_place.as_local()
+                        && self.is_tracked_local(src_local, &new_value_locals)
+                    {
+                        enforcement.has_store = true;
+                    }
+                }
+
+                // Compare: new value against the state field
+                if let Rvalue::BinaryOp(op, box (left, right)) = rvalue
+                    && matches!(op, BinOp::Gt | BinOp::Lt | BinOp::Ge | BinOp::Le)
+                    && let (
+                        Operand::Copy(left_place) | Operand::Move(left_place),
+                        Operand::Copy(right_place) | Operand::Move(right_place),
+                    ) = (left, right)
+                {
+                    let (lhs_is_new_value, lhs_is_state_field) = self.classify_monotonic_place(
+                        left_place,
+                        &is_new_value,
+                        &is_state_field,
+                        &new_value_locals,
+                        &state_field_locals,
+                    );
+                    let (rhs_is_new_value, rhs_is_state_field) = self.classify_monotonic_place(
+                        right_place,
+                        &is_new_value,
+                        &is_state_field,
+                        &new_value_locals,
+                        &state_field_locals,
+                    );
+
+                    // Comparison must involve the new value and the state field
+                    if (lhs_is_new_value && rhs_is_state_field)
+                        || (rhs_is_new_value && lhs_is_state_field)
+                    {
+                        enforcement.has_compare = true;
+                    }
+                }
+            }
+        }
+
+        enforcement
+    }
+
+    /// Check if a local transitively represents a value from the tracked locals set
+    fn is_tracked_local(&self, local: Local, tracked_locals: &HashSet<Local>) -> bool {
+        tracked_locals.contains(&local)
+            || self
+                .transitive_assignment_reverse_map
+                .get(&local)
+                .is_some_and(|sources| sources.iter().any(|src| tracked_locals.contains(src)))
+    }
+
+    /// Classify a place as the new value or the state field (direct access or via tracked locals)
+    fn classify_monotonic_place(
+        &self,
+        place: &Place<'tcx>,
+        is_new_value: &impl Fn(&Place<'tcx>) -> bool,
+        is_state_field: &impl Fn(&Place<'tcx>) -> bool,
+        new_value_locals: &HashSet<Local>,
+        state_field_locals: &HashSet<Local>,
+    ) -> (bool, bool) {
+        if let Some(local) = place.as_local() {
+            (
+                self.is_tracked_local(local, new_value_locals),
+                self.is_tracked_local(local, state_field_locals),
+            )
+        } else {
+            (is_new_value(place), is_state_field(place))
+        }
+    }
+}
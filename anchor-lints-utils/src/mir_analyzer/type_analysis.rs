@@ -1,11 +1,25 @@
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_hir::def_id::DefId;
 use rustc_middle::{
-    mir::{HasLocalDecls, Local, Operand},
-    ty::Ty,
+    mir::{
+        AggregateKind, HasLocalDecls, Local, Operand, Place, ProjectionElem, RETURN_PLACE, Rvalue,
+        StatementKind, TerminatorKind,
+    },
+    ty::{Ty, TyKind},
 };
-use rustc_span::source_map::Spanned;
+use rustc_span::{source_map::Spanned, sym};
+
+use std::collections::HashSet;
 
 use super::types::MirAnalyzer;
-use crate::models::*;
+use crate::{
+    diag_items::{
+        is_anchor_context, is_anchor_id_fn, is_anchor_program_type,
+        is_solana_create_program_address_fn,
+    },
+    models::*,
+    utils::account_data::remaining_accounts_read,
+};
 
 impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
     /// Check if a local is a Pubkey type
@@ -33,40 +47,369 @@ impl<'cx, 'tcx> MirAnalyzer<'cx, 'tcx> {
 
     /// Get origin of an operand (Constant, Parameter, or Unknown)
     pub fn origin_of_operand(&self, op: &Operand<'tcx>) -> Origin {
+        self.resolve_operand_origin(op, &mut HashSet::new())
+    }
+
+    /// Resolve the origin of an operand, `visited` holds the locals being resolved
+    fn resolve_operand_origin(&self, op: &Operand<'tcx>, visited: &mut HashSet<Local>) -> Origin {
         match op {
             Operand::Constant(_) => Origin::Constant,
             Operand::Copy(place) | Operand::Move(place) => {
-                if let Some(local) = place.as_local() {
-                    self.resolve_local_origin(local)
-                } else {
-                    Origin::Unknown
-                }
+                self.resolve_place_origin(place, visited)
             }
         }
     }
 
+    /// Resolve the origin of a place, reading through references
+    fn resolve_place_origin(&self, place: &Place<'tcx>, visited: &mut HashSet<Local>) -> Origin {
+        if let Some(local) = place.as_local() {
+            return self.resolve_local_origin(local, visited);
+        }
+
+        // `ctx.program_id` is the currently executing program
+        if self.is_context_program_id(place) {
+            return Origin::Constant;
+        }
+
+        // `*local` has the origin of the value the reference points to
+        if place
+            .projection
+            .iter()
+            .all(|elem| matches!(elem, ProjectionElem::Deref))
+        {
+            return self.resolve_local_origin(place.local, visited);
+        }
+
+        // An element of `ctx.remaining_accounts`, e.g. `*ctx.remaining_accounts[i].key`, is any
+        // account the caller passes
+        if remaining_accounts_read(self, place.local).is_some() {
+            return Origin::Parameter;
+        }
+        Origin::Unknown
+    }
+
+    /// Check if a place reads the `program_id` field of the Anchor `Context`
+    fn is_context_program_id(&self, place: &Place<'tcx>) -> bool {
+        place.iter_projections().any(|(base, elem)| {
+            let ProjectionElem::Field(field_idx, _) = elem else {
+                return false;
+            };
+            let base_ty = base.ty(self.mir, self.cx.tcx).ty;
+            if !is_anchor_context(self.cx.tcx, base_ty) {
+                return false;
+            }
+            matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "program_id")
+        })
+    }
+
     /// Resolve the origin of a local variable
-    fn resolve_local_origin(&self, local: Local) -> Origin {
+    ///
+    /// A local assigned more than once, e.g. a program ID reassigned in a loop, may hold the value
+    /// of any of its assignments, so its origin is the least safe origin among them.
+    fn resolve_local_origin(&self, local: Local, visited: &mut HashSet<Local>) -> Origin {
         // Check if it's a function parameter
         if local.index() < self.mir.arg_count {
             return Origin::Parameter;
         }
 
-        // Check assignment map
-        if let Some(kind) = self.assignment_map.get(&local) {
-            match kind {
-                AssignmentKind::Const => return Origin::Constant,
-                AssignmentKind::FromPlace(src_place) => {
-                    if let Some(src_local) = src_place.as_local() {
-                        return self.resolve_local_origin(src_local);
-                    }
+        // Reaching the local again through its own assignments (a loop-carried value) adds
+        // nothing to the origins of its other assignments
+        if !visited.insert(local) {
+            return Origin::Constant;
+        }
+
+        // Anchor checks the key of a `Program<'info, T>` account against `T::id()`
+        if self.is_program_account_key(local, &mut HashSet::new()) {
+            return Origin::Constant;
+        }
+
+        let mut origin = None;
+        for bbdata in self.mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                    && place.as_local() == Some(local)
+                {
+                    let assigned = match rvalue {
+                        Rvalue::Use(Operand::Constant(_)) => Origin::Constant,
+                        Rvalue::Use(Operand::Copy(src_place) | Operand::Move(src_place))
+                        | Rvalue::Ref(_, _, src_place)
+                        | Rvalue::CopyForDeref(src_place) => {
+                            self.resolve_place_origin(src_place, visited)
+                        }
+                        _ => Origin::Unknown,
+                    };
+                    origin = Some(origin.map_or(assigned, |o: Origin| o.join(assigned)));
                 }
-                _ => {}
             }
+            if let TerminatorKind::Call {
+                func,
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && destination.as_local() == Some(local)
+            {
+                let returned = self.call_result_origin(local, func, args, visited);
+                origin = Some(origin.map_or(returned, |o: Origin| o.join(returned)));
+            }
+        }
+
+        visited.remove(&local);
+        origin.unwrap_or(Origin::Unknown)
+    }
+
+    /// Check if a local holds the key of a typed `Program<'info, T>` account, or a reference to
+    /// it, e.g. `program.key()`, `*program.key`, `*program.as_ref().key` or
+    /// `*program.to_account_info().key`, reading through `Deref`, `AsRef`, `Borrow` and
+    /// `to_account_info` on the way
+    fn is_program_account_key(&self, local: Local, visited: &mut HashSet<Local>) -> bool {
+        let tcx = self.cx.tcx;
+        let Some(decl) = self.mir.local_decls().get(local) else {
+            return false;
+        };
+        if is_anchor_program_type(tcx, decl.ty) {
+            return true;
+        }
+        // Only keys and the `AccountInfo` of an account are followed, other values derived from
+        // a program account are not its key
+        if !visited.insert(local)
+            || !(self.is_pubkey_type(local) || self.is_account_info_type(decl.ty))
+        {
+            return false;
+        }
+
+        // Every assignment must read the key of a program account
+        let mut sources = Vec::new();
+        for bbdata in self.mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                    && place.as_local() == Some(local)
+                {
+                    let source = match rvalue {
+                        Rvalue::Use(Operand::Copy(src) | Operand::Move(src))
+                        | Rvalue::Ref(_, _, src)
+                        | Rvalue::CopyForDeref(src) => self.account_key_source(src),
+                        _ => None,
+                    };
+                    sources.push(source);
+                }
+            }
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && destination.as_local() == Some(local)
+            {
+                let receiver = match func.ty().kind() {
+                    TyKind::FnDef(fn_def_id, _)
+                        if tcx.opt_item_name(*fn_def_id).is_some_and(|name| {
+                            matches!(
+                                name.as_str(),
+                                "key" | "deref" | "as_ref" | "borrow" | "to_account_info" | "clone"
+                            )
+                        }) =>
+                    {
+                        args.first()
+                            .and_then(|arg| arg.node.place())
+                            .and_then(|place| self.account_key_source(&place))
+                    }
+                    _ => None,
+                };
+                sources.push(receiver);
+            }
+        }
+
+        let is_program_key = !sources.is_empty()
+            && sources.iter().all(|source| {
+                source.is_some_and(|source| self.is_program_account_key(source, visited))
+            });
+        visited.remove(&local);
+        is_program_key
+    }
+
+    /// Get the local a place reads an account or its key from, through dereferences and the `key`
+    /// field of an `AccountInfo`, e.g. `_3` for `(*_3).key`
+    fn account_key_source(&self, place: &Place<'tcx>) -> Option<Local> {
+        let reads_key = place.iter_projections().all(|(base, elem)| match elem {
+            ProjectionElem::Deref => true,
+            ProjectionElem::Field(field_idx, _) => {
+                let base_ty = base.ty(self.mir, self.cx.tcx).ty;
+                self.is_account_info_type(base_ty)
+                    && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                        if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "key")
+            }
+            _ => false,
+        });
+        reads_key.then_some(place.local)
+    }
+
+    /// Resolve the origin of a `Pubkey` returned by a call into `local`
+    fn call_result_origin(
+        &self,
+        local: Local,
+        func: &Operand<'tcx>,
+        args: &[Spanned<Operand<'tcx>>],
+        visited: &mut HashSet<Local>,
+    ) -> Origin {
+        let Operand::Constant(func) = func else {
+            return Origin::Unknown;
+        };
+        let TyKind::FnDef(fn_def_id, _) = func.ty().kind() else {
+            return Origin::Unknown;
+        };
+        // A PDA from `create_program_address`, unwrapped or with `?`, is an address picked by its
+        // seeds, even constant ones, like the PDA of `find_program_address`
+        if is_solana_create_program_address_fn(self.cx.tcx, *fn_def_id)
+            || !self.is_pubkey_type(local)
+        {
+            return Origin::Unknown;
+        }
+
+        // `crate::id()`, `MyProgram::id()` or `Pubkey::from_str_const(..)`
+        if self.is_constant_call(*fn_def_id, args, visited) {
+            return Origin::Constant;
+        }
+
+        // `Option::unwrap_or` and friends are only as safe as both the payload and the default
+        if let Some(origin) = self.resolve_unwrap_or_origin(*fn_def_id, args, visited) {
+            return origin;
+        }
+
+        // The key of an account pulled from `ctx.remaining_accounts`, e.g. `account.key()` while
+        // iterating them, is chosen by the caller
+        if let Some(receiver) = args.first().and_then(|arg| arg.node.place())
+            && remaining_accounts_read(self, receiver.local).is_some()
+        {
+            return Origin::Parameter;
         }
         Origin::Unknown
     }
 
+    /// Check if a call is to a `const fn` with constant arguments, or to `anchor_lang::Id::id`,
+    /// which returns the fixed address of an Anchor program type
+    fn is_constant_call(
+        &self,
+        fn_def_id: DefId,
+        args: &[Spanned<Operand<'tcx>>],
+        visited: &mut HashSet<Local>,
+    ) -> bool {
+        let tcx = self.cx.tcx;
+        (tcx.is_const_fn(fn_def_id)
+            && args
+                .iter()
+                .all(|arg| self.resolve_operand_origin(&arg.node, visited) == Origin::Constant))
+            || (args.is_empty() && is_anchor_id_fn(tcx, fn_def_id))
+    }
+
+    /// If a call is `Option::unwrap_or`/`unwrap_or_else`/`unwrap_or_default`, return the least
+    /// safe origin of the `Some` payload and the default
+    fn resolve_unwrap_or_origin(
+        &self,
+        fn_def_id: DefId,
+        args: &[Spanned<Operand<'tcx>>],
+        visited: &mut HashSet<Local>,
+    ) -> Option<Origin> {
+        let receiver = args.first()?;
+        if !is_type_diagnostic_item(
+            self.cx,
+            receiver.node.ty(self.mir, self.cx.tcx),
+            sym::Option,
+        ) {
+            return None;
+        }
+
+        let default_origin = match self.cx.tcx.opt_item_name(fn_def_id)?.as_str() {
+            "unwrap_or" => self.resolve_operand_origin(&args.get(1)?.node, visited),
+            "unwrap_or_else" => self.closure_return_origin(&args.get(1)?.node),
+            // `Pubkey::default()` is a fixed address
+            "unwrap_or_default" => Origin::Constant,
+            _ => return None,
+        };
+        Some(
+            self.resolve_option_payload_origin(&receiver.node, visited)
+                .join(default_origin),
+        )
+    }
+
+    /// Resolve the origin of the value wrapped by an `Option`, following `Some(..)` construction
+    fn resolve_option_payload_origin(
+        &self,
+        op: &Operand<'tcx>,
+        visited: &mut HashSet<Local>,
+    ) -> Origin {
+        let Some(local) = op.place().and_then(|place| place.as_local()) else {
+            return self.resolve_operand_origin(op, visited);
+        };
+        if local.index() < self.mir.arg_count {
+            return Origin::Parameter;
+        }
+
+        // Join every `Some(..)`/`None` the option may be built from
+        let mut origin = None;
+        for bbdata in self.mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                if let StatementKind::Assign(box (place, Rvalue::Aggregate(box kind, operands))) =
+                    &stmt.kind
+                    && place.as_local() == Some(local)
+                    && matches!(kind, AggregateKind::Adt(..))
+                {
+                    let variant_origin =
+                        operands.iter().next().map_or(Origin::Constant, |payload| {
+                            self.resolve_operand_origin(payload, visited)
+                        });
+                    origin =
+                        Some(origin.map_or(variant_origin, |o: Origin| o.join(variant_origin)));
+                }
+            }
+        }
+        if let Some(origin) = origin {
+            return origin;
+        }
+
+        match self.assignment_map.get(&local) {
+            Some(AssignmentKind::Const) => Origin::Constant,
+            Some(AssignmentKind::FromPlace(src_place)) => {
+                self.resolve_option_payload_origin(&Operand::Copy(*src_place), visited)
+            }
+            _ => Origin::Unknown,
+        }
+    }
+
+    /// Resolve the origin of the value returned by a closure, only closures that
+    /// always return a constant are considered constant
+    fn closure_return_origin(&self, op: &Operand<'tcx>) -> Origin {
+        let TyKind::Closure(closure_def_id, _) = op.ty(self.mir, self.cx.tcx).kind() else {
+            return Origin::Unknown;
+        };
+        let closure_mir = self.cx.tcx.optimized_mir(*closure_def_id);
+
+        let mut origin = None;
+        for bbdata in closure_mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                    && place.local == RETURN_PLACE
+                {
+                    let assigned = if matches!(rvalue, Rvalue::Use(Operand::Constant(_))) {
+                        Origin::Constant
+                    } else {
+                        Origin::Unknown
+                    };
+                    origin = Some(origin.map_or(assigned, |o: Origin| o.join(assigned)));
+                }
+            }
+            // Returning the result of a call can't be traced
+            if let TerminatorKind::Call { destination, .. } = &bbdata.terminator().kind
+                && destination.local == RETURN_PLACE
+            {
+                return Origin::Unknown;
+            }
+        }
+        origin.unwrap_or(Origin::Unknown)
+    }
+
     /// If this [`Operand`] refers to a [`Local`] that is a `Pubkey`, return it
     pub fn pubkey_operand_to_local(&self, op: &Operand<'_>) -> Option<Local> {
         match op {
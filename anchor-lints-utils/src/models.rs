@@ -35,6 +35,33 @@ pub enum Origin {
     Unknown,
 }
 
+impl Origin {
+    /// Join two origins, keeping the least safe of the two
+    pub fn join(self, other: Origin) -> Origin {
+        match (self, other) {
+            (Origin::Unknown, _) | (_, Origin::Unknown) => Origin::Unknown,
+            (Origin::Parameter, _) | (_, Origin::Parameter) => Origin::Parameter,
+            _ => Origin::Constant,
+        }
+    }
+}
+
+/// Which halves of a monotonic store-and-compare pattern were found for a state field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonotonicEnforcement {
+    /// The new value is compared against the stored state field
+    pub has_compare: bool,
+    /// The new value is written back to the state field
+    pub has_store: bool,
+}
+
+impl MonotonicEnforcement {
+    /// Monotonicity is only enforced when the value is both compared and stored
+    pub fn is_complete(&self) -> bool {
+        self.has_compare && self.has_store
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum NestedArgumentType {
     Ctx,
@@ -105,4 +132,23 @@ pub struct AccountConstraint {
     pub mutable: bool,
     pub has_address_constraint: bool,
     pub constraints: Vec<String>,
+    /// Source text of the `token::mint` or `associated_token::mint` constraint, e.g. `mint.key()`
+    pub mint: Option<String>,
+}
+
+/// Constraint evidence of an accounts struct field that bears on whether it can alias another field
+#[derive(Debug, Clone, Default)]
+pub struct AccountAliasEvidence {
+    pub account_name: String,
+    pub mutable: bool,
+    pub seeds: Vec<String>,
+    pub attributes: Vec<String>,
+    /// `a:b` pairs from `constraint = a.key() != b.key()`, recorded in both orders
+    pub constraints: Vec<String>,
+    /// Accounts referenced by `has_one = ...`
+    pub has_one: Vec<String>,
+    /// Expression of an `address = ...` constraint
+    pub address: Option<String>,
+    /// Expression of a `token::mint = ...` or `associated_token::mint = ...` constraint
+    pub mint: Option<String>,
 }
@@ -0,0 +1,302 @@
+//! Registry of the lints of this repository, their remediation help and confidence.
+//!
+//! The help of a lint condenses the "Good" example of its documentation into what to change to
+//! resolve a finding. Tools explaining a finding look it up with [`help_for`]; when
+//! `ANCHOR_LINTS_HELP_NOTES` is set, the first finding of each lint in a crate also carries it as
+//! a help message.
+//!
+//! The confidence of a lint states how likely its findings are true positives, it is attached to
+//! every finding of the lint and used to filter findings with `ANCHOR_LINTS_MIN_CONFIDENCE`.
+//!
+//! `ANCHOR_LINTS_ONLY` and `ANCHOR_LINTS_SKIP` take comma separated lint names and select the lints
+//! that run, regardless of their lint levels. Lints check [`is_lint_enabled`] before analyzing a
+//! function, so disabled lints do no work at all.
+
+use rustc_lint::Lint;
+
+use std::sync::OnceLock;
+
+use crate::findings::Confidence;
+
+/// Environment variable enabling the remediation help note, the `--help-notes` switch of a lint run
+pub const HELP_NOTES_ENV_VAR: &str = "ANCHOR_LINTS_HELP_NOTES";
+
+/// Environment variable restricting a run to the listed lints, the `--only` switch of a lint run
+pub const ONLY_ENV_VAR: &str = "ANCHOR_LINTS_ONLY";
+
+/// Environment variable excluding the listed lints from a run, the `--skip` switch of a lint run
+pub const SKIP_ENV_VAR: &str = "ANCHOR_LINTS_SKIP";
+
+/// A lint of this repository
+#[derive(Debug, Clone, Copy)]
+pub struct LintInfo {
+    /// Name of the lint, e.g. `missing_account_reload`
+    pub name: &'static str,
+    /// How to resolve a finding of the lint
+    pub help: &'static str,
+    /// How likely a finding of the lint is a true positive
+    pub confidence: Confidence,
+}
+
+/// Every lint of this repository
+pub const LINTS: &[LintInfo] = &[
+    LintInfo {
+        name: "missing_account_reload",
+        help: "call `reload()` on the account after the CPI and before reading it, e.g. `ctx.accounts.vault.reload()?;`, so its data reflects the changes made by the invoked program",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "arbitrary_cpi_call",
+        help: "invoke a program the accounts struct validates, e.g. `Program<'info, Token>`, or compare the program ID against the expected ID with `require_keys_eq!` before the CPI",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "duplicate_mutable_accounts",
+        help: "add `#[account(constraint = a.key() != b.key())]` so the same account cannot be passed for both fields, or allow the lint if they may be the same account",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "cpi_no_result",
+        help: "propagate the CPI result with `?` or handle the error explicitly instead of discarding it with `unwrap_or_default`, `unwrap_or` or `unwrap_or_else`",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "pda_signer_account_overlap",
+        help: "add `constraint = user_account.key() != pda_signer.key()` so the user-controlled account cannot be the PDA signer, or do not pass it mutable to the signed CPI",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_signer_validation",
+        help: "declare the authority of the CPI as `Signer<'info>` or `#[account(signer)]`, or sign for it as a PDA with `CpiContext::new_with_signer`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_owner_check",
+        help: "use a typed account such as `Account<'info, T>`, or add an `owner = ...` constraint or an explicit `account.owner` check before reading the account data",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_account_field_init",
+        help: "assign every field of the account when initializing it, including authorities and limits that should not stay zeroed",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "ata_should_use_init_if_needed",
+        help: "use `init_if_needed` instead of `init` for associated token accounts, so the instruction succeeds when the account already exists",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "direct_lamport_cpi_dos",
+        help: "pass every account whose lamports were changed to the following CPI, e.g. with `with_remaining_accounts`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "overconstrained_seed_account",
+        help: "declare accounts only used as seeds as `UncheckedAccount<'info>` instead of `SystemAccount<'info>`, so a change of their owner does not lock the instruction",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "unsafe_pyth_price_account",
+        help: "pin the price account to the canonical feed address and require its `publish_time` to be newer than the last price the program used",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "missing_mut_constraint",
+        help: "add `#[account(mut)]` to every account the instruction writes to",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "invoke_with_account_infos_mismatch",
+        help: "pass an account info for every account meta of the instruction to `invoke` or `invoke_signed`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "init_if_needed_without_payer_or_space",
+        help: "give every `init` or `init_if_needed` account a mutable `payer = ...` and a `space = ...` large enough for its data",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "unchecked_account_used_as_authority_without_signer_or_pda",
+        help: "declare the authority as `Signer<'info>`, or derive it with `seeds = [...]` and `bump` and sign for it with `CpiContext::new_with_signer`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_signer_check_on_governance_config_update",
+        help: "require the stored admin to sign, e.g. `has_one = admin` on the config with `admin: Signer<'info>`",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "associated_token_authority_mismatch",
+        help: "set `associated_token::authority` to the account that should own the tokens, e.g. the user, rather than the payer",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_signer_validation_for_native_sol_withdrawal",
+        help: "require the owner of the vault to sign the withdrawal, e.g. `has_one = authority` with `authority: Signer<'info>`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "unbounded_loop_over_accounts_cu_dos",
+        help: "cap the number of accounts or iterations with a constant, e.g. `require!(ctx.remaining_accounts.len() <= MAX_ACCOUNTS)`, before the loop",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "token_transfer_amount_from_unvalidated_balance",
+        help: "validate the token account the amount is read from, e.g. with `token::mint = ...` and `token::authority = ...`, or take the amount from validated state",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "missing_owner_check_on_cpi_return_account",
+        help: "check that the account is still owned by the expected program after the CPI, e.g. `require_keys_eq!(*account.owner, expected_program::ID)`, before reading its data",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "account_space_mismatch_with_struct_size",
+        help: "allocate `8 + T::INIT_SPACE` with `#[derive(InitSpace)]` on the account type, or a `space` covering the discriminator and every field",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "unsafe_unwrap_on_account_deserialization",
+        help: "propagate the decoding error with `?` or map it to a program error instead of unwrapping it",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "signer_not_mutable_when_paying",
+        help: "add `#[account(mut)]` to the account paying lamports, so the runtime lets the program debit it",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "missing_bump_verification_on_signer_pda",
+        help: "sign with the canonical bump from `ctx.bumps` or the bump stored in the account instead of a bump passed as an instruction argument",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "duplicate_seeds_across_distinct_account_types",
+        help: "add a seed that tells the account types apart, e.g. a prefix naming the type such as `b\"user\"` and `b\"config\"`",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "missing_owner_check_on_manually_loaded_zero_copy",
+        help: "construct the loader with `AccountLoader::try_from`, which validates the owner, or check `account_info.owner` before calling `try_from_unchecked`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "cpi_context_reused_across_different_programs",
+        help: "build a separate `CpiContext` for each invoked program instead of reassigning the `program_id` of a context already used for a CPI",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "unchecked_pda_canonical_bump_in_find_program_address_mismatch",
+        help: "sign with the bump returned by `find_program_address`, e.g. `let (pda, bump) = ...`, instead of a bump taken from elsewhere",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "token_2022_missing_extension_account_handling",
+        help: "pass the extra accounts of the transfer hook to the `CpiContext` with `with_remaining_accounts`, e.g. `.with_remaining_accounts(ctx.remaining_accounts.to_vec())`",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "missing_signer_validation_on_delegate_transfer",
+        help: "declare the delegate authority as `Signer<'info>` or add `#[account(signer)]`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "account_written_without_persisting",
+        help: "serialize the modified struct back into the account data, e.g. `state.serialize(&mut &mut account.try_borrow_mut_data()?[..])?`, or use `Account<'info, T>` which persists its data on exit",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_owner_check_before_lamport_read_as_trust",
+        help: "validate the account before trusting its balance: derive it with `seeds`, pin it with `address`, check its `owner`, or compare its key in the handler",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "seed_contains_user_controlled_value_without_validation",
+        help: "start the seeds with a constant such as `b\"profile\"`, or bound the length of the instruction argument with a `constraint` or a `require!` in the handler",
+        confidence: Confidence::Low,
+    },
+    LintInfo {
+        name: "missing_signer_validation_cross_program_instruction_introspection",
+        help: "compare the `program_id` of the introspected instruction to the expected program, e.g. `require_keys_eq!(ix.program_id, crate::ID)`, before trusting its data or accounts",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "account_reinit_via_close_then_init_in_same_tx",
+        help: "keep the closed and the initialized account at different addresses, e.g. with distinct seeds or `constraint = closed.key() != created.key()`",
+        confidence: Confidence::Medium,
+    },
+    LintInfo {
+        name: "missing_owner_check_on_remaining_account_deserialization",
+        help: "check the `owner` of the remaining account before deserializing it, or use `Account::<T>::try_from`",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "missing_account_field_init_for_bump",
+        help: "assign the stored bump when initializing the account, e.g. from `ctx.bumps`, so later instructions can re-derive the PDA",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "token_program_id_from_account_instead_of_typed_program",
+        help: "declare the token program as `Program<'info, Token>` or `Interface<'info, TokenInterface>` so Anchor validates its program id",
+        confidence: Confidence::High,
+    },
+    LintInfo {
+        name: "unchecked_cpi_program_in_generic_anchor_cpi_context",
+        help: "declare the program account as `Program<'info, TargetProgram>` so Anchor validates the program the generated CPI invokes",
+        confidence: Confidence::High,
+    },
+];
+
+/// Get the remediation help of a lint, matching its name case-insensitively so both the declared
+/// `MISSING_ACCOUNT_RELOAD` and `missing_account_reload` resolve. Unknown lints have no help.
+pub fn help_for(lint_name: &str) -> &'static str {
+    LINTS
+        .iter()
+        .find(|lint| lint.name.eq_ignore_ascii_case(lint_name))
+        .map_or("", |lint| lint.help)
+}
+
+/// Get the confidence of a lint, matching its name case-insensitively. Findings of unknown lints
+/// are not filtered out, so they default to high confidence.
+pub fn confidence_for(lint_name: &str) -> Confidence {
+    LINTS
+        .iter()
+        .find(|lint| lint.name.eq_ignore_ascii_case(lint_name))
+        .map_or(Confidence::High, |lint| lint.confidence)
+}
+
+/// Lints selected by `ANCHOR_LINTS_ONLY` and `ANCHOR_LINTS_SKIP`, read once per driver process
+struct LintFilter {
+    only: Option<Vec<String>>,
+    skip: Vec<String>,
+}
+
+static LINT_FILTER: OnceLock<LintFilter> = OnceLock::new();
+
+/// Parse a comma separated list of lint names from an environment variable
+fn lint_names_from_env(env_var: &str) -> Option<Vec<String>> {
+    let names = std::env::var(env_var).ok()?;
+    Some(
+        names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Check if a lint runs, i.e. it is listed in `ANCHOR_LINTS_ONLY` when that is set and is not
+/// listed in `ANCHOR_LINTS_SKIP`. Names match case-insensitively, like [`help_for`].
+pub fn is_lint_enabled(lint: &Lint) -> bool {
+    let filter = LINT_FILTER.get_or_init(|| LintFilter {
+        only: lint_names_from_env(ONLY_ENV_VAR),
+        skip: lint_names_from_env(SKIP_ENV_VAR).unwrap_or_default(),
+    });
+    let listed = |names: &[String]| {
+        names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(lint.name))
+    };
+    filter.only.as_deref().is_none_or(listed) && !listed(&filter.skip)
+}
@@ -0,0 +1,316 @@
+use rustc_lint::LateContext;
+use rustc_middle::ty::{FieldDef, VariantDef};
+use std::collections::{BTreeSet, HashSet};
+
+use crate::models::*;
+use crate::utils::account_constraints::{
+    AccountConstraints, ConstraintEntry, normalize_source, parse_account_constraints,
+};
+
+/// Answers may-alias queries between the fields of an Anchor accounts struct, combining seed
+/// equality, `has_one`, `address` and `constraint = a.key() != b.key()` evidence.
+#[derive(Debug, Clone, Default)]
+pub struct AccountsAliasAnalysis {
+    fields: Vec<AccountAliasEvidence>,
+    /// `(parent, target)` pairs of the `has_one = target` constraints of each `parent` field
+    has_one_pairs: HashSet<(String, String)>,
+    distinct_pairs: HashSet<String>,
+}
+
+impl AccountsAliasAnalysis {
+    /// Collect the aliasing evidence of every field of the accounts struct
+    pub fn new<'tcx>(cx: &LateContext<'tcx>, accounts_variant: &VariantDef) -> Self {
+        Self::from_evidence(
+            accounts_variant
+                .fields
+                .iter()
+                .map(|field| extract_alias_evidence(cx, field)),
+        )
+    }
+
+    /// Build the analysis from the evidence of every field of an accounts struct, e.g. built
+    /// with [`alias_evidence_from_constraints`]
+    pub fn from_evidence(fields: impl IntoIterator<Item = AccountAliasEvidence>) -> Self {
+        let mut analysis = Self::default();
+        for evidence in fields {
+            analysis.has_one_pairs.extend(
+                evidence
+                    .has_one
+                    .iter()
+                    .map(|target| (evidence.account_name.clone(), target.clone())),
+            );
+            analysis
+                .distinct_pairs
+                .extend(evidence.constraints.iter().cloned());
+            analysis.fields.push(evidence);
+        }
+        analysis
+    }
+
+    pub fn fields(&self) -> &[AccountAliasEvidence] {
+        &self.fields
+    }
+
+    pub fn evidence(&self, account_name: &str) -> Option<&AccountAliasEvidence> {
+        self.fields
+            .iter()
+            .find(|evidence| evidence.account_name == account_name)
+    }
+
+    /// Record `a:b` pairs proven distinct outside of the accounts struct, e.g. by manual key checks
+    pub fn add_distinct_pairs(&mut self, pairs: impl IntoIterator<Item = String>) {
+        self.distinct_pairs.extend(pairs);
+    }
+
+    /// Check whether two fields of the accounts struct may refer to the same account
+    pub fn accounts_may_alias(&self, account_a: &str, account_b: &str) -> bool {
+        if account_a == account_b {
+            return true;
+        }
+        let (Some(first), Some(second)) = (self.evidence(account_a), self.evidence(account_b))
+        else {
+            return true;
+        };
+
+        // Token accounts of syntactically different mints hold different tokens, while the same
+        // mint, or no mint constraint on either side, leaves them candidates
+        if let (Some(mint_a), Some(mint_b)) = (&first.mint, &second.mint)
+            && mint_a != mint_b
+        {
+            return false;
+        }
+
+        // The other attributes must match (e.g. token::authority bindings)
+        constraints_match(
+            &non_mint_attributes(&first.attributes),
+            &non_mint_attributes(&second.attributes),
+        ) && self.addresses_may_alias(account_a, account_b)
+    }
+
+    /// Check whether two fields of the accounts struct may resolve to the same address, whatever
+    /// their other constraints, e.g. an account closed and an account initialized by one
+    /// instruction
+    pub fn addresses_may_alias(&self, account_a: &str, account_b: &str) -> bool {
+        self.addresses_may_alias_visiting(account_a, account_b, &mut Vec::new())
+    }
+
+    /// [`Self::addresses_may_alias`], with the pairs already being compared up the `has_one`
+    /// chain in `visiting`, so that cyclic `has_one` constraints are not followed forever
+    fn addresses_may_alias_visiting<'a>(
+        &'a self,
+        account_a: &'a str,
+        account_b: &'a str,
+        visiting: &mut Vec<(&'a str, &'a str)>,
+    ) -> bool {
+        if account_a == account_b {
+            return true;
+        }
+        let (Some(first), Some(second)) = (self.evidence(account_a), self.evidence(account_b))
+        else {
+            return true;
+        };
+
+        // Accounts bound through `has_one` are pinned by the accounts that reference them
+        if self.pinned_apart_by_has_one(account_a, account_b, visiting) {
+            return false;
+        }
+
+        // Different pinned addresses can never resolve to the same account
+        if let (Some(address_a), Some(address_b)) = (&first.address, &second.address)
+            && address_a != address_b
+        {
+            return false;
+        }
+
+        // Seeds must match (unless one side has none)
+        if (!first.seeds.is_empty() || !second.seeds.is_empty())
+            && !seeds_match(&first.seeds, &second.seeds)
+        {
+            return false;
+        }
+
+        // An explicit key inequality keeps the accounts distinct
+        let key = format!("{}:{}", account_a, account_b);
+        let reverse = format!("{}:{}", account_b, account_a);
+        !self.distinct_pairs.contains(&key) && !self.distinct_pairs.contains(&reverse)
+    }
+
+    /// Fields whose `has_one` constraints pin `account`, e.g. `market` for
+    /// `#[account(has_one = vault)] market`
+    fn has_one_parents<'a>(&'a self, account: &'a str) -> impl Iterator<Item = &'a str> {
+        self.has_one_pairs
+            .iter()
+            .filter(move |(_, target)| target == account)
+            .map(|(parent, _)| parent.as_str())
+    }
+
+    /// Check if `has_one` pins the accounts to different keys: they are two fields stored in one
+    /// parent, e.g. `market.base_vault` and `market.quote_vault`, or fields stored in two parents
+    /// that cannot be the same account, e.g. `market_a.vault_a` and `market_b.vault_b` with
+    /// `market_a` and `market_b` derived from different seeds. When both parents may be the same
+    /// account, or an account is pinned on one side only, the accounts may still alias.
+    fn pinned_apart_by_has_one<'a>(
+        &'a self,
+        account_a: &'a str,
+        account_b: &'a str,
+        visiting: &mut Vec<(&'a str, &'a str)>,
+    ) -> bool {
+        if visiting.contains(&(account_a, account_b)) {
+            return false;
+        }
+        visiting.push((account_a, account_b));
+        let pinned_apart = self.has_one_parents(account_a).any(|parent_a| {
+            self.has_one_parents(account_b).any(|parent_b| {
+                parent_a == parent_b
+                    || !self.addresses_may_alias_visiting(parent_a, parent_b, visiting)
+            })
+        });
+        visiting.pop();
+        pinned_apart
+    }
+}
+
+/// Check whether two fields of an accounts struct may refer to the same account
+pub fn accounts_may_alias<'tcx>(
+    cx: &LateContext<'tcx>,
+    accounts_variant: &VariantDef,
+    field_a: &FieldDef,
+    field_b: &FieldDef,
+) -> bool {
+    AccountsAliasAnalysis::new(cx, accounts_variant).accounts_may_alias(
+        field_a.ident(cx.tcx).as_str(),
+        field_b.ident(cx.tcx).as_str(),
+    )
+}
+
+/// Check if two sets of constraints match
+pub fn constraints_match(constraints_a: &[String], constraints_b: &[String]) -> bool {
+    let set_a: BTreeSet<_> = constraints_a.iter().map(|s| s.trim()).collect();
+    let set_b: BTreeSet<_> = constraints_b.iter().map(|s| s.trim()).collect();
+    set_a == set_b
+}
+
+/// Attributes other than the `token::mint` and `associated_token::mint` bindings, which
+/// [`AccountsAliasAnalysis::accounts_may_alias`] compares on their own
+fn non_mint_attributes(attributes: &[String]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|attribute| {
+            let attribute = attribute.trim();
+            !attribute.starts_with("token::mint=")
+                && !attribute.starts_with("associated_token::mint=")
+        })
+        .cloned()
+        .collect()
+}
+
+/// Check if two PDA seed lists, e.g. the seeds of [`AccountAliasEvidence`], are identical
+pub fn seeds_match(seeds_a: &[String], seeds_b: &[String]) -> bool {
+    seeds_a.len() == seeds_b.len()
+        && seeds_a
+            .iter()
+            .zip(seeds_b)
+            .all(|(seed_a, seed_b)| seed_a.trim() == seed_b.trim())
+}
+
+/// Parse a constraint string like "user_a.key!=user_b.key" to extract account names
+pub fn parse_constraint_string(constraint: &str) -> Option<(String, String)> {
+    let constraint = constraint.trim();
+
+    // Find the != operator
+    if let Some(neq_pos) = constraint.find("!=") {
+        let left = &constraint[..neq_pos].trim();
+        let right = &constraint[neq_pos + 2..].trim();
+
+        let acc1 = left.split('.').next()?.trim();
+
+        let acc2 = right.split('.').next()?.trim();
+
+        if !acc1.is_empty() && !acc2.is_empty() {
+            return Some((acc1.to_string(), acc2.to_string()));
+        }
+    }
+
+    None
+}
+
+/// Extract the PDA seeds, attributes and key constraints of an accounts struct field
+pub fn extract_alias_evidence<'tcx>(
+    cx: &LateContext<'tcx>,
+    account_field: &FieldDef,
+) -> AccountAliasEvidence {
+    alias_evidence_from_constraints(
+        account_field.ident(cx.tcx).to_string(),
+        &parse_account_constraints(cx, account_field),
+    )
+}
+
+/// The aliasing evidence of the field `account_name` declaring `constraints`
+pub fn alias_evidence_from_constraints(
+    account_name: String,
+    constraints: &AccountConstraints,
+) -> AccountAliasEvidence {
+    let mut evidence = AccountAliasEvidence {
+        account_name,
+        mutable: constraints.mut_,
+        seeds: constraints
+            .seeds
+            .as_ref()
+            .map(|seed_list| {
+                seed_list
+                    .seeds
+                    .iter()
+                    .map(|seed| normalize_source(&seed.text))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        has_one: constraints
+            .has_one
+            .iter()
+            .map(|target| target.path.clone())
+            .collect(),
+        address: constraints
+            .address
+            .as_ref()
+            .map(|address| normalize_source(&address.text)),
+        mint: constraints
+            .token
+            .mint
+            .as_ref()
+            .or(constraints.associated_token.mint.as_ref())
+            .map(|mint| mint.text.trim().to_string()),
+        ..Default::default()
+    };
+
+    // Seeds and key constraints are compared on their own, and the bump does not change the address
+    for entry in &constraints.entries {
+        if matches!(entry.key.as_str(), "mut" | "seeds" | "bump" | "constraint") {
+            continue;
+        }
+        evidence.attributes.push(attribute_text(entry));
+    }
+    for constraint in &constraints.constraints {
+        // Parse "user_a.key() != user_b.key()" to extract account names
+        if let Some((acc1, acc2)) = parse_constraint_string(&constraint.text) {
+            evidence.constraints.push(format!("{}:{}", acc1, acc2));
+            evidence.constraints.push(format!("{}:{}", acc2, acc1));
+        }
+    }
+    evidence
+}
+
+/// An attribute as compared between two accounts, e.g. `has_one=authority@MyError::Unauthorized`
+fn attribute_text(entry: &ConstraintEntry) -> String {
+    let mut text = entry.key.clone();
+    if let Some(value) = &entry.value {
+        if !value.tokens.is_empty() {
+            text.push('=');
+            text.push_str(&normalize_source(&value.text));
+        }
+        if let Some(error) = &value.error {
+            text.push('@');
+            text.push_str(&normalize_source(error));
+        }
+    }
+    text
+}
@@ -1,80 +1,445 @@
+use rustc_ast::{
+    token::{Delimiter, TokenKind},
+    tokenstream::{TokenStream, TokenTree},
+};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
 use rustc_lint::LateContext;
-use rustc_span::Symbol;
+use rustc_middle::ty::{FieldDef, TyKind};
+use rustc_span::{DUMMY_SP, Span, source_map::SourceMap};
 
-use crate::models::*;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
 
-/// Extract account constraints from Anchor attributes
-pub fn extract_account_constraints<'tcx>(
+use crate::{mir_analyzer::AnchorContextInfo, models::*};
+
+thread_local! {
+    // Constraints of the fields of each accounts struct parsed so far, keyed by the struct, as
+    // `check_fn` runs once per handler and handlers often share their accounts struct
+    static CONSTRAINTS_CACHE: RefCell<HashMap<DefId, HashMap<DefId, AccountConstraints>>> =
+        RefCell::new(HashMap::new());
+    // Number of accounts structs parsed on this thread, to check that each struct is only parsed
+    // once
+    static PARSED_STRUCTS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The value of an `#[account(...)]` constraint, e.g. `user` in `payer = user`
+#[derive(Debug, Clone)]
+pub struct ConstraintValue {
+    /// Source text of the value, e.g. `user.key()`
+    pub text: String,
+    /// Identifiers, `.` and `::` of the value outside of delimiters, e.g. `user.key` for
+    /// `user.key()`
+    pub path: String,
+    /// Tokens of the value, without the custom error
+    pub tokens: Vec<TokenTree>,
+    /// Span of the value, for suggestions
+    pub span: Span,
+    /// Source text of the custom error, e.g. `MyError::Unauthorized` in
+    /// `has_one = authority @ MyError::Unauthorized`
+    pub error: Option<String>,
+}
+
+/// A single `key` or `key = value` entry of `#[account(...)]`
+#[derive(Debug, Clone)]
+pub struct ConstraintEntry {
+    /// Key of the entry, e.g. `init` or `associated_token::mint`
+    pub key: String,
+    pub key_span: Span,
+    /// Value after `=`, or only the custom error of a bare key, e.g. `signer @ MyError`
+    pub value: Option<ConstraintValue>,
+}
+
+/// The `seeds = [...]` constraint
+#[derive(Debug, Clone)]
+pub struct SeedList {
+    /// Seeds of the list, empty if the value is not a `[...]` literal
+    pub seeds: Vec<ConstraintValue>,
+    /// The whole value, including the brackets
+    pub value: ConstraintValue,
+}
+
+/// The `token::*` constraints
+#[derive(Debug, Clone, Default)]
+pub struct TokenConstraints {
+    pub mint: Option<ConstraintValue>,
+    pub authority: Option<ConstraintValue>,
+    pub token_program: Option<ConstraintValue>,
+}
+
+/// The `associated_token::*` constraints
+#[derive(Debug, Clone, Default)]
+pub struct AssociatedTokenConstraints {
+    pub mint: Option<ConstraintValue>,
+    pub authority: Option<ConstraintValue>,
+    pub token_program: Option<ConstraintValue>,
+}
+
+/// The `mint::*` constraints
+#[derive(Debug, Clone, Default)]
+pub struct MintConstraints {
+    pub decimals: Option<ConstraintValue>,
+    pub authority: Option<ConstraintValue>,
+    pub freeze_authority: Option<ConstraintValue>,
+    pub token_program: Option<ConstraintValue>,
+}
+
+/// Every constraint of an accounts struct field, parsed from its `#[account(...)]` attributes
+#[derive(Debug, Clone, Default)]
+pub struct AccountConstraints {
+    pub init: bool,
+    pub init_if_needed: bool,
+    pub zero: bool,
+    pub mut_: bool,
+    pub signer: bool,
+    pub executable: bool,
+    pub seeds: Option<SeedList>,
+    /// `bump` or `bump = ...`, a bare `bump` has an empty value spanning the keyword
+    pub bump: Option<ConstraintValue>,
+    /// `seeds::program = ...`
+    pub seeds_program: Option<ConstraintValue>,
+    pub payer: Option<ConstraintValue>,
+    pub space: Option<ConstraintValue>,
+    pub realloc: Option<ConstraintValue>,
+    /// `realloc::payer = ...`
+    pub realloc_payer: Option<ConstraintValue>,
+    /// `realloc::zero = ...`
+    pub realloc_zero: Option<ConstraintValue>,
+    pub address: Option<ConstraintValue>,
+    pub owner: Option<ConstraintValue>,
+    pub close: Option<ConstraintValue>,
+    pub rent_exempt: Option<ConstraintValue>,
+    pub has_one: Vec<ConstraintValue>,
+    /// `constraint = ...` expressions
+    pub constraints: Vec<ConstraintValue>,
+    pub token: TokenConstraints,
+    pub associated_token: AssociatedTokenConstraints,
+    pub mint: MintConstraints,
+    /// All entries in source order, including keys not covered above
+    pub entries: Vec<ConstraintEntry>,
+}
+
+impl AccountConstraints {
+    /// Check if the field has a constraint with this key, or with this namespace,
+    /// e.g. `associated_token` for `associated_token::mint`
+    pub fn has(&self, key: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            entry.key == key
+                || entry
+                    .key
+                    .strip_prefix(key)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    }
+
+    /// Get the value of the first constraint with this key
+    pub fn value(&self, key: &str) -> Option<&ConstraintValue> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == key)
+            .and_then(|entry| entry.value.as_ref())
+    }
+}
+
+/// Parse every `#[account(...)]` constraint of an accounts struct field
+///
+/// The constraints of every field of the struct are parsed on first use and cached per struct.
+pub fn parse_account_constraints<'tcx>(
     cx: &LateContext<'tcx>,
-    account_field: &rustc_middle::ty::FieldDef,
-) -> AccountConstraint {
-    let mut account_constraints = AccountConstraint {
-        mutable: false,
-        has_address_constraint: false,
-        constraints: Vec::new(),
+    field: &rustc_middle::ty::FieldDef,
+) -> AccountConstraints {
+    let tcx = cx.tcx;
+    let struct_def_id = tcx.parent(field.did);
+    if tcx.def_kind(struct_def_id) != DefKind::Struct {
+        return parse_field_constraints(cx, field.did);
+    }
+
+    cached_field_constraints(struct_def_id, field.did, || {
+        tcx.adt_def(struct_def_id)
+            .all_fields()
+            .map(|struct_field| {
+                (
+                    struct_field.did,
+                    parse_field_constraints(cx, struct_field.did),
+                )
+            })
+            .collect()
+    })
+    .unwrap_or_else(|| parse_field_constraints(cx, field.did))
+}
+
+/// Find the field of the accounts struct declaring `account_name`
+pub fn find_context_field<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    account_name: &str,
+) -> Option<&'tcx FieldDef> {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return None;
     };
+    if !adt_def.is_struct() {
+        return None;
+    }
+    adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == account_name)
+}
 
-    let tcx = cx.tcx;
-    let attrs = tcx.get_all_attrs(account_field.did);
-    let mut last_ident_constraint = false;
-    let mut latest_account_constraint = String::new();
-
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let attr_item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                delim_args.tokens.iter().for_each(|token| {
-                    if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token {
-                        match token.kind {
-                            rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                if ident == Symbol::intern("mut") {
-                                    account_constraints.mutable = true;
-                                } else if ident == Symbol::intern("constraint") {
-                                    last_ident_constraint = true;
-                                } else if ident == Symbol::intern("address") {
-                                    account_constraints.has_address_constraint = true;
-                                } else if last_ident_constraint {
-                                    latest_account_constraint =
-                                        latest_account_constraint.clone() + &ident.to_string();
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Comma => {
-                                if last_ident_constraint {
-                                    last_ident_constraint = false;
-                                    if !latest_account_constraint.is_empty() {
-                                        account_constraints
-                                            .constraints
-                                            .push(latest_account_constraint.clone());
-                                        latest_account_constraint = String::new();
-                                    }
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Dot => {
-                                if last_ident_constraint {
-                                    latest_account_constraint.push('.');
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Ne => {
-                                if last_ident_constraint {
-                                    latest_account_constraint.push_str("!=");
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Eq => {
-                                if !latest_account_constraint.is_empty() {
-                                    latest_account_constraint.push('=');
-                                }
-                            }
-                            _ => {
-                                // Ignore other token kinds
-                            }
-                        }
-                    }
-                });
+/// Get the cached constraints of a field of an accounts struct, running `parse_struct` to parse
+/// the constraints of every field of the struct if it was not parsed yet
+///
+/// Returns `None` if the field is not one of the parsed fields of the struct.
+pub fn cached_field_constraints(
+    struct_def_id: DefId,
+    field_def_id: DefId,
+    parse_struct: impl FnOnce() -> HashMap<DefId, AccountConstraints>,
+) -> Option<AccountConstraints> {
+    let cached = CONSTRAINTS_CACHE.with(|cache| {
+        cache
+            .borrow()
+            .get(&struct_def_id)
+            .map(|fields| fields.get(&field_def_id).cloned())
+    });
+    if let Some(constraints) = cached {
+        return constraints;
+    }
+
+    PARSED_STRUCTS.with(|count| count.set(count.get() + 1));
+    let fields = parse_struct();
+    let constraints = fields.get(&field_def_id).cloned();
+    CONSTRAINTS_CACHE.with(|cache| cache.borrow_mut().insert(struct_def_id, fields));
+    constraints
+}
+
+/// Number of accounts structs whose constraints were parsed on this thread
+pub fn parsed_struct_count() -> usize {
+    PARSED_STRUCTS.with(Cell::get)
+}
+
+/// Parse the `#[account(...)]` attributes of a single field
+fn parse_field_constraints<'tcx>(
+    cx: &LateContext<'tcx>,
+    field_def_id: DefId,
+) -> AccountConstraints {
+    let source_map = cx.tcx.sess.source_map();
+    let mut constraints = AccountConstraints::default();
+    for attr in cx.tcx.get_all_attrs(field_def_id) {
+        let rustc_hir::Attribute::Unparsed(_) = attr else {
+            continue;
+        };
+        let rustc_hir::AttrArgs::Delimited(args) = &attr.get_normal_item().args else {
+            continue;
+        };
+        constraints.add_tokens(source_map, &args.tokens);
+    }
+    constraints
+}
+
+impl AccountConstraints {
+    /// Add the constraints of one `#[account(...)]` attribute, given the tokens between its
+    /// parentheses. Source texts are read from `source_map`.
+    pub fn add_tokens(&mut self, source_map: &SourceMap, tokens: &TokenStream) {
+        let tokens: Vec<&TokenTree> = tokens.iter().collect();
+        for entry_tokens in split_at_commas(&tokens) {
+            if let Some(entry) = parse_entry(source_map, entry_tokens) {
+                self.add(source_map, entry);
+            }
+        }
+    }
+
+    fn add(&mut self, source_map: &SourceMap, entry: ConstraintEntry) {
+        let value = entry.value.clone();
+        let key_only = || ConstraintValue {
+            text: String::new(),
+            path: String::new(),
+            tokens: Vec::new(),
+            span: entry.key_span,
+            error: None,
+        };
+        match entry.key.as_str() {
+            "init" => self.init = true,
+            "init_if_needed" => self.init_if_needed = true,
+            "zero" => self.zero = true,
+            "mut" => self.mut_ = true,
+            "signer" => self.signer = true,
+            "executable" => self.executable = true,
+            "seeds" => {
+                self.seeds = value.map(|value| SeedList {
+                    seeds: seed_values(source_map, &value),
+                    value,
+                })
+            }
+            "bump" => {
+                self.bump = Some(
+                    value
+                        .filter(|value| !value.tokens.is_empty())
+                        .unwrap_or_else(key_only),
+                )
             }
+            "seeds::program" => self.seeds_program = value,
+            "payer" => self.payer = value,
+            "space" => self.space = value,
+            "realloc" => self.realloc = value,
+            "realloc::payer" => self.realloc_payer = value,
+            "realloc::zero" => self.realloc_zero = value,
+            "address" => self.address = value,
+            "owner" => self.owner = value,
+            "close" => self.close = value,
+            "rent_exempt" => self.rent_exempt = value,
+            "has_one" => self.has_one.extend(value),
+            "constraint" => self.constraints.extend(value),
+            "token::mint" => self.token.mint = value,
+            "token::authority" => self.token.authority = value,
+            "token::token_program" => self.token.token_program = value,
+            "associated_token::mint" => self.associated_token.mint = value,
+            "associated_token::authority" => self.associated_token.authority = value,
+            "associated_token::token_program" => self.associated_token.token_program = value,
+            "mint::decimals" => self.mint.decimals = value,
+            "mint::authority" => self.mint.authority = value,
+            "mint::freeze_authority" => self.mint.freeze_authority = value,
+            "mint::token_program" => self.mint.token_program = value,
+            _ => {}
         }
+        self.entries.push(entry);
     }
+}
+
+fn split_at_commas<'a>(tokens: &[&'a TokenTree]) -> Vec<Vec<&'a TokenTree>> {
+    let mut groups = vec![Vec::new()];
+    for token in tokens {
+        if matches!(token, TokenTree::Token(tok, _) if matches!(tok.kind, TokenKind::Comma)) {
+            groups.push(Vec::new());
+        } else if let Some(group) = groups.last_mut() {
+            group.push(*token);
+        }
+    }
+    groups.retain(|group| !group.is_empty());
+    groups
+}
+
+/// Parse `key`, `key @ Error`, `key = value` or `key = value @ Error`
+fn parse_entry(source_map: &SourceMap, tokens: Vec<&TokenTree>) -> Option<ConstraintEntry> {
+    let mut key = String::new();
+    let mut key_span: Option<Span> = None;
+    let mut rest = tokens.as_slice();
+    while let Some((TokenTree::Token(tok, _), tail)) = rest.split_first() {
+        match tok.kind {
+            TokenKind::Ident(ident, ..) => key.push_str(ident.as_str()),
+            TokenKind::PathSep => key.push_str("::"),
+            _ => break,
+        }
+        key_span = Some(key_span.map_or(tok.span, |span| span.to(tok.span)));
+        rest = tail;
+    }
+    let key_span = key_span?;
+
+    let (value_tokens, error_tokens) = match rest.iter().position(
+        |token| matches!(token, TokenTree::Token(tok, _) if matches!(tok.kind, TokenKind::At)),
+    ) {
+        Some(at) => (&rest[..at], Some(&rest[at + 1..])),
+        None => (rest, None),
+    };
+    let error = error_tokens.and_then(|tokens| tokens_snippet(source_map, tokens));
+
+    let value = match value_tokens.split_first() {
+        Some((TokenTree::Token(tok, _), value_tokens)) if matches!(tok.kind, TokenKind::Eq) => {
+            Some(constraint_value(source_map, value_tokens, error))
+        }
+        // A bare key with a custom error, e.g. `signer @ MyError`
+        _ if error.is_some() => Some(ConstraintValue {
+            text: String::new(),
+            path: String::new(),
+            tokens: Vec::new(),
+            span: key_span,
+            error,
+        }),
+        _ => None,
+    };
+
+    Some(ConstraintEntry {
+        key,
+        key_span,
+        value,
+    })
+}
+
+fn constraint_value(
+    source_map: &SourceMap,
+    tokens: &[&TokenTree],
+    error: Option<String>,
+) -> ConstraintValue {
+    let mut path = String::new();
+    for token in tokens {
+        if let TokenTree::Token(tok, _) = token {
+            match tok.kind {
+                TokenKind::Ident(ident, ..) => path.push_str(ident.as_str()),
+                TokenKind::PathSep => path.push_str("::"),
+                TokenKind::Dot => path.push('.'),
+                _ => {}
+            }
+        }
+    }
+    ConstraintValue {
+        text: tokens_snippet(source_map, tokens).unwrap_or_else(|| path.clone()),
+        path,
+        tokens: tokens.iter().map(|token| (*token).clone()).collect(),
+        span: tokens_span(tokens).unwrap_or(DUMMY_SP),
+        error,
+    }
+}
+
+/// Remove whitespace from a constraint text, so the same expression compares equal however it is
+/// formatted
+pub fn normalize_source(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
 
-    account_constraints
+fn tokens_span(tokens: &[&TokenTree]) -> Option<Span> {
+    let first = tokens.first()?.span();
+    let last = tokens.last()?.span();
+    Some(first.to(last))
+}
+
+fn tokens_snippet(source_map: &SourceMap, tokens: &[&TokenTree]) -> Option<String> {
+    source_map.span_to_snippet(tokens_span(tokens)?).ok()
+}
+
+/// Split a `[...]` seeds value into its seeds
+fn seed_values(source_map: &SourceMap, value: &ConstraintValue) -> Vec<ConstraintValue> {
+    let [TokenTree::Delimited(_, _, Delimiter::Bracket, stream)] = value.tokens.as_slice() else {
+        return Vec::new();
+    };
+    let tokens: Vec<&TokenTree> = stream.iter().collect();
+    split_at_commas(&tokens)
+        .into_iter()
+        .map(|seed_tokens| constraint_value(source_map, &seed_tokens, None))
+        .collect()
+}
+
+/// Extract account constraints from Anchor attributes
+pub fn extract_account_constraints<'tcx>(
+    cx: &LateContext<'tcx>,
+    account_field: &rustc_middle::ty::FieldDef,
+) -> AccountConstraint {
+    let constraints = parse_account_constraints(cx, account_field);
+    AccountConstraint {
+        mutable: constraints.mut_,
+        has_address_constraint: constraints.address.is_some(),
+        constraints: constraints
+            .constraints
+            .into_iter()
+            .map(|constraint| constraint.text)
+            .collect(),
+        mint: constraints
+            .token
+            .mint
+            .or(constraints.associated_token.mint)
+            .map(|mint| mint.text.trim().to_string()),
+    }
 }
 
 /// Check if a field has a specific account constraint (e.g., `init`, `init_if_needed`, `associated_token`).
@@ -83,22 +448,36 @@ pub fn has_account_constraint<'tcx>(
     field: &rustc_middle::ty::FieldDef,
     constraint_name: &str,
 ) -> bool {
-    let constraint_symbol = Symbol::intern(constraint_name);
-    let attrs = cx.tcx.get_all_attrs(field.did);
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(args) = &item.args {
-                for token in args.tokens.iter() {
-                    if let rustc_ast::tokenstream::TokenTree::Token(tok, _) = token
-                        && let rustc_ast::token::TokenKind::Ident(ident, ..) = tok.kind
-                        && ident == constraint_symbol
-                    {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    false
+    parse_account_constraints(cx, field).has(constraint_name)
+}
+
+/// Extract the top-level constraint keys of a field, e.g. `init`, `payer` and
+/// `associated_token::mint` for `#[account(init, payer = user, associated_token::mint = mint)]`.
+pub fn extract_constraint_keys<'tcx>(
+    cx: &LateContext<'tcx>,
+    field: &rustc_middle::ty::FieldDef,
+) -> Vec<String> {
+    parse_account_constraints(cx, field)
+        .entries
+        .into_iter()
+        .map(|entry| entry.key)
+        .collect()
+}
+
+/// Extract the top-level `key = value` constraints of a field, e.g. `("payer", "user")` and
+/// `("associated_token::authority", "owner")` for
+/// `#[account(init, payer = user, associated_token::authority = owner)]`.
+/// Values keep their identifiers, `.` and `::`, so `user.key()` is read as `user.key`.
+pub fn extract_constraint_values<'tcx>(
+    cx: &LateContext<'tcx>,
+    field: &rustc_middle::ty::FieldDef,
+) -> Vec<(String, String)> {
+    parse_account_constraints(cx, field)
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let value = entry.value?;
+            (!value.path.is_empty()).then_some((entry.key, value.path))
+        })
+        .collect()
 }
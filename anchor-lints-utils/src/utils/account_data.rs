@@ -0,0 +1,221 @@
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_hir::def_id::DefId;
+use rustc_middle::{
+    mir::{
+        Local, Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind,
+    },
+    ty::{Ty, TyKind, UintTy},
+};
+use rustc_span::{Span, sym};
+
+use std::collections::HashSet;
+
+use crate::diag_items::{is_account_info_type, is_anchor_context};
+use crate::mir_analyzer::MirAnalyzer;
+
+/// Fallible borrows of an account's data
+pub const DATA_ACCESS_METHODS: &[&str] = &["try_borrow_data", "try_borrow_mut_data"];
+
+/// Methods decoding a value from a byte buffer
+pub const DESERIALIZE_METHODS: &[&str] = &[
+    "try_from_slice",
+    "try_from_slice_unchecked",
+    "deserialize",
+    "try_deserialize",
+    "try_deserialize_unchecked",
+];
+
+/// A call decoding a value from account data
+pub struct DeserializeCall {
+    pub def_id: DefId,
+    pub destination: Local,
+    /// The borrow of the account data the value is decoded from
+    pub data_borrow: Local,
+    pub span: Span,
+}
+
+/// Name of the function or method `def_id`, e.g. `try_from_slice`
+pub fn method_name(mir_analyzer: &MirAnalyzer, def_id: DefId) -> Option<String> {
+    mir_analyzer
+        .cx
+        .tcx
+        .opt_item_name(def_id)
+        .map(|name| name.to_string())
+}
+
+/// Check if a type is the `RefCell<&mut [u8]>` holding the data of an `AccountInfo`
+fn is_account_data_cell<'tcx>(mir_analyzer: &MirAnalyzer<'_, 'tcx>, ty: Ty<'tcx>) -> bool {
+    let ty = ty.peel_refs();
+    let TyKind::Adt(_, args) = ty.kind() else {
+        return false;
+    };
+    is_type_diagnostic_item(mir_analyzer.cx, ty, sym::RefCell)
+        && args.types().next().is_some_and(|inner| {
+            matches!(inner.peel_refs().kind(), TyKind::Slice(elem)
+                if matches!(elem.kind(), TyKind::Uint(UintTy::U8)))
+        })
+}
+
+/// Collect the locals holding a borrow of an account's data: `account.try_borrow_data()`,
+/// `account.try_borrow_mut_data()`, `account.data.borrow()` or `account.data.borrow_mut()`
+fn collect_account_data_borrows(mir_analyzer: &MirAnalyzer) -> HashSet<Local> {
+    let mir = mir_analyzer.mir;
+    let mut borrows = HashSet::new();
+    for bbdata in mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+            && let Some(method) = method_name(mir_analyzer, *fn_def_id)
+            && let Some(destination) = destination.as_local()
+        {
+            let is_data_borrow = DATA_ACCESS_METHODS.contains(&method.as_str())
+                || (matches!(method.as_str(), "borrow" | "borrow_mut")
+                    && args.first().is_some_and(|receiver| {
+                        is_account_data_cell(
+                            mir_analyzer,
+                            receiver.node.ty(mir, mir_analyzer.cx.tcx),
+                        )
+                    }));
+            if is_data_borrow {
+                borrows.insert(destination);
+            }
+        }
+    }
+    borrows
+}
+
+/// Collect the calls decoding a value from a borrow of an account's data
+pub fn collect_account_deserializations(mir_analyzer: &MirAnalyzer) -> Vec<DeserializeCall> {
+    let data_borrows = collect_account_data_borrows(mir_analyzer);
+    if data_borrows.is_empty() {
+        return Vec::new();
+    }
+
+    let mut calls = Vec::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+            && method_name(mir_analyzer, *fn_def_id)
+                .is_some_and(|method| DESERIALIZE_METHODS.contains(&method.as_str()))
+            && let Some(destination) = destination.as_local()
+            && let Some(data_borrow) = args.iter().find_map(|arg| {
+                let local = arg.node.place()?.as_local()?;
+                mir_analyzer
+                    .collect_source_locals(local)
+                    .intersection(&data_borrows)
+                    .next()
+                    .copied()
+            })
+        {
+            calls.push(DeserializeCall {
+                def_id: *fn_def_id,
+                destination,
+                data_borrow,
+                span: *fn_span,
+            });
+        }
+    }
+    calls
+}
+
+/// If `local` is derived from `ctx.remaining_accounts`, return the span of the read
+pub fn remaining_accounts_read(mir_analyzer: &MirAnalyzer, local: Local) -> Option<Span> {
+    let sources = mir_analyzer.collect_source_locals(local);
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata.statements.iter().find_map(|stmt| {
+            let StatementKind::Assign(box (dest, rvalue)) = &stmt.kind else {
+                return None;
+            };
+            let place = read_place(rvalue)?;
+            (sources.contains(&dest.local) && reads_remaining_accounts(mir_analyzer, place))
+                .then_some(stmt.source_info.span)
+        })
+    })
+}
+
+fn reads_remaining_accounts(mir_analyzer: &MirAnalyzer, place: &Place) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    place.iter_projections().any(|(base, elem)| {
+        let ProjectionElem::Field(field_idx, _) = elem else {
+            return false;
+        };
+        let base_ty = base.ty(mir_analyzer.mir, tcx).ty;
+        is_anchor_context(tcx, base_ty)
+            && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "remaining_accounts")
+    })
+}
+
+/// The place an assignment reads from, for plain copies, moves and borrows
+fn read_place<'a, 'tcx>(rvalue: &'a Rvalue<'tcx>) -> Option<&'a Place<'tcx>> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Ref(_, _, place)
+        | Rvalue::CopyForDeref(place) => Some(place),
+        _ => None,
+    }
+}
+
+/// If `stmt` reads the `owner` of an `AccountInfo`, return the local the account info is read
+/// through
+pub fn owner_read_local(mir_analyzer: &MirAnalyzer, stmt: &Statement) -> Option<Local> {
+    let StatementKind::Assign(box (_, rvalue)) = &stmt.kind else {
+        return None;
+    };
+    let place = match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) | Rvalue::Ref(_, _, place) => {
+            place
+        }
+        _ => return None,
+    };
+    reads_account_info_owner(mir_analyzer, place).then_some(place.local)
+}
+
+fn reads_account_info_owner(mir_analyzer: &MirAnalyzer, place: &Place) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    place.iter_projections().any(|(base, elem)| {
+        let ProjectionElem::Field(field_idx, _) = elem else {
+            return false;
+        };
+        let base_ty = base.ty(mir_analyzer.mir, tcx).ty;
+        is_account_info_type(tcx, base_ty)
+            && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "owner")
+    })
+}
+
+/// Collect the locals whose `owner` is read anywhere in the function
+pub fn collect_owner_reads(mir_analyzer: &MirAnalyzer) -> Vec<Local> {
+    mir_analyzer
+        .mir
+        .basic_blocks
+        .iter()
+        .flat_map(|bbdata| bbdata.statements.iter())
+        .filter_map(|stmt| owner_read_local(mir_analyzer, stmt))
+        .collect()
+}
+
+/// Check if two locals refer to the same account info, i.e. are derived from a common variable
+/// of this function. Function arguments such as the `Context` are shared by every account, so
+/// they do not relate two locals.
+pub fn same_account_info(mir_analyzer: &MirAnalyzer, first: Local, second: Local) -> bool {
+    let mir = mir_analyzer.mir;
+    let first_sources = mir_analyzer.collect_source_locals(first);
+    let second_sources = mir_analyzer.collect_source_locals(second);
+    first_sources.intersection(&second_sources).any(|local| {
+        *local == first
+            || *local == second
+            || (mir.local_decls[*local].is_user_variable()
+                && !mir.args_iter().any(|arg| arg == *local))
+    })
+}
@@ -0,0 +1,44 @@
+use rustc_lint::{LateContext, Lint};
+use rustc_span::{DUMMY_SP, Span};
+
+/// Comment allowing the lints it lists on the line below it, e.g.
+/// `// anchor-lints:allow(arbitrary_cpi_call)`
+pub const INLINE_ALLOW_MARKER: &str = "anchor-lints:allow(";
+
+/// Check if `span` is on the line right below an `// anchor-lints:allow(..)` comment listing `lint`.
+/// Only the line below the comment is allowed, so a handler with several findings keeps the others.
+pub fn is_allowed_inline(cx: &LateContext<'_>, lint: &Lint, span: Span) -> bool {
+    let span = span.source_callsite();
+    if span == DUMMY_SP {
+        return false;
+    }
+    let loc = cx.tcx.sess.source_map().lookup_char_pos(span.lo());
+    // `Loc::line` is 1-based, `SourceFile::get_line` is 0-based
+    let Some(previous_index) = loc.line.checked_sub(2) else {
+        return false;
+    };
+    let Some(previous) = loc.file.get_line(previous_index) else {
+        return false;
+    };
+    parse_inline_allow(&previous).is_some_and(|lints| {
+        lints
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(lint.name))
+    })
+}
+
+/// Parse the lint names of an `// anchor-lints:allow(..)` comment standing alone on a source line
+pub fn parse_inline_allow(line: &str) -> Option<Vec<&str>> {
+    let comment = line.trim_start().strip_prefix("//")?;
+    let (lints, _) = comment
+        .trim_start()
+        .strip_prefix(INLINE_ALLOW_MARKER)?
+        .split_once(')')?;
+    Some(
+        lints
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .collect(),
+    )
+}
@@ -1,15 +1,20 @@
 use rustc_middle::{
     mir::{
-        Body as MirBody, HasLocalDecls, Local, Operand, Place, Rvalue, StatementKind,
-        TerminatorKind,
+        AggregateKind, BasicBlock, Body as MirBody, HasLocalDecls, Local, Operand, Place, Rvalue,
+        Statement, StatementKind, TerminatorKind, UnOp,
     },
-    ty::TyKind,
+    ty::{self as rustc_ty, TyKind},
 };
 use rustc_span::source_map::Spanned;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::{mir_analyzer::AnchorContextInfo, models::*, utils::compare_adt_def_ids};
+use crate::{
+    diag_items::is_anchor_cpi_context,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    models::*,
+    utils::{check_locals_are_related, compare_adt_def_ids, extract_arg_local},
+};
 
 /// Builds the analysis maps for the MIR body
 pub fn build_mir_analysis_maps<'tcx>(mir: &MirBody<'tcx>) -> MirAnalysisMaps<'tcx> {
@@ -167,3 +172,100 @@ pub fn is_implementation_method<'tcx>(
         })
         .unwrap_or(false)
 }
+
+/// If the switch discriminant `discr` is last assigned the negation of a local in `statements`,
+/// e.g. `_5 = Not(move _4)` for `if !allowed.contains(&program_id)`, return the negated local
+pub fn negated_condition(statements: &[Statement<'_>], discr: Local) -> Option<Local> {
+    let assigned = statements.iter().rev().find_map(|stmt| match &stmt.kind {
+        StatementKind::Assign(box (place, rvalue)) if place.as_local() == Some(discr) => {
+            Some(rvalue)
+        }
+        _ => None,
+    })?;
+    let Rvalue::UnaryOp(UnOp::Not, Operand::Copy(cond) | Operand::Move(cond)) = assigned else {
+        return None;
+    };
+    cond.as_local()
+}
+
+/// Find the accounts struct of the `CpiContext` passed to the CPI in `cpi_block`
+pub fn find_cpi_accounts(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    cpi_ctx_local: Local,
+) -> Option<Local> {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            let fn_sig = mir_analyzer.cx.tcx.fn_sig(*fn_def_id).skip_binder();
+            let return_ty = fn_sig.skip_binder().output();
+
+            if is_anchor_cpi_context(mir_analyzer.cx.tcx, return_ty)
+                && let Some(accounts_local) = extract_arg_local(args, 1)
+                && let Some(destination_local) = destination.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &destination_local,
+                    &cpi_ctx_local,
+                )
+            {
+                return Some(accounts_local);
+            }
+        }
+
+        // The context must be built before the CPI consumes it
+        if cpi_block == bb {
+            break;
+        }
+    }
+    None
+}
+
+/// Resolve the context account passed as `field_name` in the CPI accounts struct
+pub fn extract_cpi_account(
+    mir_analyzer: &MirAnalyzer,
+    cpi_accounts_local: Local,
+    field_name: &str,
+) -> Option<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                && let Rvalue::Aggregate(box AggregateKind::Adt(adt_def_id, _, _, _, _), fields) =
+                    rvalue
+                && let Some(account_struct_local) = place.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &account_struct_local,
+                    &cpi_accounts_local,
+                )
+            {
+                let adt_def = tcx.adt_def(*adt_def_id);
+                if !adt_def.is_struct() {
+                    continue;
+                }
+                let field_index = adt_def
+                    .non_enum_variant()
+                    .fields
+                    .iter()
+                    .position(|f| f.ident(tcx).as_str() == field_name)?;
+
+                if let Some(Operand::Copy(p) | Operand::Move(p)) = fields.iter().nth(field_index)
+                    && let Some(local) = p.as_local()
+                    && let Some(account_info) =
+                        mir_analyzer.extract_account_name_from_local(&local, true)
+                {
+                    return Some(account_info.account_name);
+                }
+                return None;
+            }
+        }
+    }
+    None
+}
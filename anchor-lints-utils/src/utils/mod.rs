@@ -1,16 +1,22 @@
 // Re-export all modules
+pub mod account_aliasing;
 pub mod account_constraints;
+pub mod account_data;
 pub mod account_extraction;
 pub mod hir_utils;
+pub mod inline_allow;
 pub mod mir_analysis;
 pub mod param_extraction;
 pub mod pda_detection;
 pub mod string_extraction;
 pub mod type_checking;
 
+pub use account_aliasing::*;
 pub use account_constraints::*;
+pub use account_data::*;
 pub use account_extraction::*;
 pub use hir_utils::*;
+pub use inline_allow::*;
 pub use mir_analysis::*;
 pub use param_extraction::*;
 pub use pda_detection::*;
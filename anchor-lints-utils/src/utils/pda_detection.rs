@@ -1,9 +1,17 @@
 use rustc_hir::def_id::DefId;
 use rustc_lint::LateContext;
-use rustc_middle::mir::Operand;
-use rustc_span::{Symbol, source_map::Spanned};
+use rustc_middle::{
+    mir::{AggregateKind, Body as MirBody, HasLocalDecls, Local, Operand, Rvalue, StatementKind},
+    ty::{Ty, TyKind, UintTy},
+};
+use rustc_span::source_map::Spanned;
 
-use crate::{mir_analyzer::MirAnalyzer, models::*};
+use crate::{
+    diag_items::{DiagnoticItem, is_anchor_cpi_context},
+    mir_analyzer::MirAnalyzer,
+    models::*,
+    utils::{account_constraints::parse_account_constraints, extract_arg_local},
+};
 
 /// Check if an account is a PDA (has seeds constraint or address constraint pointing to a PDA)
 pub fn is_pda_account<'tcx>(
@@ -11,42 +19,16 @@ pub fn is_pda_account<'tcx>(
     account_field: &rustc_middle::ty::FieldDef,
 ) -> Option<PdaSigner> {
     let tcx = cx.tcx;
-    let attrs = tcx.get_all_attrs(account_field.did);
     let account_name = account_field.ident(tcx).to_string();
     let account_span = tcx.def_span(account_field.did);
 
-    let mut has_seeds = false;
-    let mut seeds = Vec::new();
-    let mut has_address = false;
-
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let attr_item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                let mut last_ident_seeds = false;
-                for token in delim_args.tokens.iter() {
-                    if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token {
-                        match token.kind {
-                            rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                if ident == Symbol::intern("seeds") {
-                                    last_ident_seeds = true;
-                                    has_seeds = true;
-                                } else if ident == Symbol::intern("address") {
-                                    has_address = true;
-                                } else if last_ident_seeds {
-                                    seeds.push(ident.to_string());
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Comma => {
-                                last_ident_seeds = false;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let constraints = parse_account_constraints(cx, account_field);
+    let has_seeds = constraints.seeds.is_some();
+    let has_address = constraints.address.is_some();
+    let seeds = constraints
+        .seeds
+        .map(|seed_list| seed_list.seeds.into_iter().map(|seed| seed.text).collect())
+        .unwrap_or_default();
 
     // PDA accounts typically have either seeds or address constraint pointing to a const PDA
     if has_seeds || has_address {
@@ -73,3 +55,99 @@ pub fn check_cpi_call_is_new_with_signer<'tcx>(
     }
     false
 }
+
+/// Get the signer seeds passed to a signed CPI, i.e. `CpiContext::new_with_signer` or
+/// `invoke_signed`
+pub fn signed_cpi_seeds<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    fn_def_id: DefId,
+    args: &[Spanned<Operand<'tcx>>],
+) -> Option<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let return_ty = tcx.fn_sig(fn_def_id).skip_binder().skip_binder().output();
+    let is_signed_cpi = (is_anchor_cpi_context(tcx, return_ty)
+        && check_cpi_call_is_new_with_signer(mir_analyzer, args, fn_def_id))
+        || DiagnoticItem::AnchorCpiInvokeSigned.defid_is_item(tcx, fn_def_id)
+        || DiagnoticItem::AnchorCpiInvokeSignedUnchecked.defid_is_item(tcx, fn_def_id);
+    if !is_signed_cpi {
+        return None;
+    }
+    extract_arg_local(args, 2)
+}
+
+/// Collect the lists of seeds `seeds` is built from, i.e. the `[&[u8]; N]` arrays such as
+/// `[b"vault", key.as_ref(), &[bump]]`. Works for the seeds of `find_program_address` as well as
+/// the signer seeds of a CPI, which hold one list per signer.
+pub fn seed_lists(mir_analyzer: &MirAnalyzer, seeds: Local) -> Vec<Local> {
+    mir_analyzer
+        .collect_source_locals(seeds)
+        .into_iter()
+        .filter(|local| is_array_of(mir_analyzer.mir, *local, is_byte_slice))
+        .collect()
+}
+
+/// Get the bump ending a list of seeds, the single byte of `&[bump]`
+pub fn seed_list_bump<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    seed_list: Local,
+) -> Option<Operand<'tcx>> {
+    let mir = mir_analyzer.mir;
+    let last_seed = array_operands(mir, seed_list)?
+        .last()?
+        .place()?
+        .as_local()?;
+    mir_analyzer
+        .collect_source_locals(last_seed)
+        .into_iter()
+        .find_map(|seed_source| match array_operands(mir, seed_source) {
+            Some([bump]) if is_array_of(mir, seed_source, is_u8) => Some(bump.clone()),
+            _ => None,
+        })
+}
+
+/// Collect the bumps of signer seeds, the single byte ending each list of seeds, e.g. `bump` in
+/// `&[b"vault", &[bump]]`
+pub fn signer_seed_bumps<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    signer_seeds: Local,
+) -> Vec<Operand<'tcx>> {
+    seed_lists(mir_analyzer, signer_seeds)
+        .into_iter()
+        .filter_map(|seed_list| seed_list_bump(mir_analyzer, seed_list))
+        .collect()
+}
+
+/// Get the elements of the array assigned to `local`, e.g. `[a, b]`
+pub fn array_operands<'a, 'tcx>(
+    mir: &'a MirBody<'tcx>,
+    local: Local,
+) -> Option<&'a [Operand<'tcx>]> {
+    mir.basic_blocks
+        .iter()
+        .flat_map(|bbdata| &bbdata.statements)
+        .find_map(|statement| match &statement.kind {
+            StatementKind::Assign(box (
+                place,
+                Rvalue::Aggregate(box AggregateKind::Array(_), operands),
+            )) if place.as_local() == Some(local) => Some(operands.raw.as_slice()),
+            _ => None,
+        })
+}
+
+fn is_array_of<'tcx>(
+    mir: &MirBody<'tcx>,
+    local: Local,
+    is_elem: impl Fn(Ty<'tcx>) -> bool,
+) -> bool {
+    matches!(mir.local_decls()[local].ty.kind(), TyKind::Array(elem_ty, _) if is_elem(*elem_ty))
+}
+
+fn is_u8(ty: Ty) -> bool {
+    matches!(ty.kind(), TyKind::Uint(UintTy::U8))
+}
+
+/// A single seed, i.e. `&[u8]`
+fn is_byte_slice(ty: Ty) -> bool {
+    matches!(ty.kind(), TyKind::Ref(_, inner, _)
+        if matches!(inner.kind(), TyKind::Slice(elem_ty) if is_u8(*elem_ty)))
+}
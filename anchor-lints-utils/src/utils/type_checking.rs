@@ -1,6 +1,11 @@
 use rustc_lint::LateContext;
 use rustc_middle::ty::{Ty, TyKind};
 
+use crate::diag_items::{
+    is_anchor_account_loader_type, is_anchor_account_type, is_anchor_interface_account_type,
+    is_box_type,
+};
+
 /// Check if a type is Option<UncheckedAccount>
 pub fn is_option_unchecked_account_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty) -> bool {
     let ty = ty.peel_refs();
@@ -33,3 +38,21 @@ pub fn compare_adt_def_ids(ty1: Ty, ty2: Ty) -> bool {
         false
     }
 }
+
+/// Get the type of the data stored in an `Account`, `AccountLoader` or `InterfaceAccount`,
+/// looking through `Box`
+pub fn account_data_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    let TyKind::Adt(_, generics) = ty.kind() else {
+        return None;
+    };
+    if is_box_type(cx.tcx, ty) {
+        return account_data_type(cx, generics.type_at(0));
+    }
+    let is_typed_account = is_anchor_account_type(cx.tcx, ty)
+        || is_anchor_account_loader_type(cx.tcx, ty)
+        || is_anchor_interface_account_type(cx.tcx, ty);
+    if !is_typed_account {
+        return None;
+    }
+    generics.types().next()
+}
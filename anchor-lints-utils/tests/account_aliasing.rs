@@ -0,0 +1,165 @@
+#![feature(rustc_private)]
+
+extern crate rustc_parse;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use anchor_lints_utils::utils::{
+    account_aliasing::{
+        AccountsAliasAnalysis, alias_evidence_from_constraints, constraints_match,
+        parse_constraint_string, seeds_match,
+    },
+    account_constraints::AccountConstraints,
+};
+use rustc_session::parse::ParseSess;
+use rustc_span::{FileName, create_default_session_globals_then};
+
+/// Build the alias analysis of an accounts struct from the contents of the `#[account(...)]`
+/// attribute of each field
+fn analysis(fields: &[(&str, &str)]) -> AccountsAliasAnalysis {
+    create_default_session_globals_then(|| {
+        let psess = ParseSess::new(Vec::new());
+        AccountsAliasAnalysis::from_evidence(fields.iter().map(|(name, attribute)| {
+            let tokens = rustc_parse::source_str_to_stream(
+                &psess,
+                FileName::Custom(name.to_string()),
+                attribute.to_string(),
+                None,
+            )
+            .unwrap_or_else(|_| panic!("`{attribute}` does not tokenize"));
+            let mut constraints = AccountConstraints::default();
+            constraints.add_tokens(psess.source_map(), &tokens);
+            alias_evidence_from_constraints(name.to_string(), &constraints)
+        }))
+    })
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn seeds_match_in_order() {
+    let seeds = strings(&["b\"vault\"", "user.key().as_ref()"]);
+    assert!(seeds_match(
+        &seeds,
+        &strings(&[" b\"vault\"", "user.key().as_ref() "])
+    ));
+    assert!(!seeds_match(
+        &seeds,
+        &strings(&["user.key().as_ref()", "b\"vault\""])
+    ));
+    assert!(!seeds_match(&seeds, &strings(&["b\"vault\""])));
+    assert!(seeds_match(&[], &[]));
+}
+
+#[test]
+fn constraints_match_as_sets() {
+    let constraints = strings(&["token::authority=owner", "mut"]);
+    assert!(constraints_match(
+        &constraints,
+        &strings(&["mut", " token::authority=owner"])
+    ));
+    assert!(!constraints_match(
+        &constraints,
+        &strings(&["mut", "token::authority=admin"])
+    ));
+    assert!(!constraints_match(&constraints, &strings(&["mut"])));
+}
+
+#[test]
+fn key_inequalities_are_parsed() {
+    assert_eq!(
+        parse_constraint_string("user_a.key() != user_b.key()"),
+        Some(("user_a".to_string(), "user_b".to_string()))
+    );
+    assert_eq!(
+        parse_constraint_string("user_a.key!=user_b.key"),
+        Some(("user_a".to_string(), "user_b".to_string()))
+    );
+    assert_eq!(
+        parse_constraint_string("user_a.key() == user_b.key()"),
+        None
+    );
+    assert_eq!(parse_constraint_string(" != user_b.key()"), None);
+}
+
+#[test]
+fn has_one_targets_of_one_parent_are_distinct() {
+    let analysis = analysis(&[
+        ("market", "has_one = base_vault, has_one = quote_vault"),
+        ("base_vault", "mut"),
+        ("quote_vault", "mut"),
+        ("other_vault", "mut"),
+    ]);
+    assert!(!analysis.addresses_may_alias("base_vault", "quote_vault"));
+    assert!(!analysis.accounts_may_alias("base_vault", "quote_vault"));
+    // An account pinned on one side only may still be the other one
+    assert!(analysis.addresses_may_alias("base_vault", "other_vault"));
+}
+
+#[test]
+fn has_one_targets_of_distinct_parents_are_distinct() {
+    let analysis = analysis(&[
+        (
+            "market_a",
+            "seeds = [b\"market\", b\"a\"], bump, has_one = vault_a",
+        ),
+        (
+            "market_b",
+            "seeds = [b\"market\", b\"b\"], bump, has_one = vault_b",
+        ),
+        (
+            "market_c",
+            "seeds = [b\"market\", b\"a\"], bump, has_one = vault_c",
+        ),
+        ("vault_a", "mut"),
+        ("vault_b", "mut"),
+        ("vault_c", "mut"),
+    ]);
+    assert!(!analysis.addresses_may_alias("vault_a", "vault_b"));
+    // `market_a` and `market_c` share their seeds, so their vaults may be the same account
+    assert!(analysis.addresses_may_alias("vault_a", "vault_c"));
+}
+
+#[test]
+fn cyclic_has_one_constraints_terminate() {
+    let analysis = analysis(&[("left", "has_one = right"), ("right", "has_one = left")]);
+    assert!(analysis.addresses_may_alias("left", "right"));
+}
+
+#[test]
+fn different_addresses_are_distinct() {
+    let analysis = analysis(&[
+        ("treasury", "mut, address = crate::TREASURY"),
+        ("fee_vault", "mut, address = crate::FEE_VAULT"),
+        ("treasury_copy", "mut, address = crate :: TREASURY"),
+        ("destination", "mut"),
+    ]);
+    assert!(!analysis.addresses_may_alias("treasury", "fee_vault"));
+    assert!(analysis.addresses_may_alias("treasury", "treasury_copy"));
+    assert!(analysis.addresses_may_alias("treasury", "destination"));
+}
+
+#[test]
+fn seeds_and_key_constraints_keep_accounts_apart() {
+    let analysis = analysis(&[
+        (
+            "vault_a",
+            "mut, seeds = [b\"vault\", user.key().as_ref()], bump",
+        ),
+        (
+            "vault_b",
+            "mut, seeds = [b\"vault\", admin.key().as_ref()], bump",
+        ),
+        ("user_a", "mut, constraint = user_a.key() != user_b.key()"),
+        ("user_b", "mut"),
+        ("user_c", "mut"),
+    ]);
+    assert!(!analysis.addresses_may_alias("vault_a", "vault_b"));
+    assert!(!analysis.addresses_may_alias("user_a", "user_b"));
+    assert!(!analysis.addresses_may_alias("user_b", "user_a"));
+    assert!(analysis.addresses_may_alias("user_b", "user_c"));
+    // Fields missing from the struct may be any account
+    assert!(analysis.addresses_may_alias("user_b", "unknown"));
+}
@@ -0,0 +1,253 @@
+#![feature(rustc_private)]
+
+extern crate rustc_parse;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use anchor_lints_utils::utils::account_constraints::{
+    AccountConstraints, ConstraintValue, cached_field_constraints, parsed_struct_count,
+};
+use rustc_session::parse::ParseSess;
+use rustc_span::{
+    FileName, create_default_session_globals_then,
+    def_id::{DefId, DefIndex, LOCAL_CRATE},
+    source_map::SourceMap,
+};
+use std::collections::HashMap;
+
+/// Parse the contents of `#[account(...)]` and check the constraints with the source map the
+/// tokens come from
+fn with_constraints(attributes: &[&str], check: impl FnOnce(&AccountConstraints, &SourceMap)) {
+    create_default_session_globals_then(|| {
+        let psess = ParseSess::new(Vec::new());
+        let mut constraints = AccountConstraints::default();
+        for (index, attribute) in attributes.iter().enumerate() {
+            let tokens = rustc_parse::source_str_to_stream(
+                &psess,
+                FileName::Custom(format!("account_{index}")),
+                attribute.to_string(),
+                None,
+            )
+            .unwrap_or_else(|_| panic!("`{attribute}` does not tokenize"));
+            constraints.add_tokens(psess.source_map(), &tokens);
+        }
+        check(&constraints, psess.source_map());
+    });
+}
+
+fn snippet(source_map: &SourceMap, value: &ConstraintValue) -> String {
+    source_map.span_to_snippet(value.span).unwrap()
+}
+
+#[test]
+fn pda_init_constraints_are_parsed() {
+    with_constraints(
+        &[
+            "init, payer = user, space = 8 + Vault::INIT_SPACE, seeds = [b\"vault\", user.key().as_ref()], bump",
+        ],
+        |constraints, source_map| {
+            assert!(constraints.init);
+            assert!(!constraints.init_if_needed);
+
+            let payer = constraints.payer.as_ref().unwrap();
+            assert_eq!(payer.text, "user");
+            assert_eq!(payer.path, "user");
+            assert_eq!(snippet(source_map, payer), "user");
+
+            let space = constraints.space.as_ref().unwrap();
+            assert_eq!(space.text, "8 + Vault::INIT_SPACE");
+            assert_eq!(snippet(source_map, space), "8 + Vault::INIT_SPACE");
+
+            let seeds = constraints.seeds.as_ref().unwrap();
+            assert_eq!(seeds.value.text, "[b\"vault\", user.key().as_ref()]");
+            let seed_texts: Vec<_> = seeds.seeds.iter().map(|seed| seed.text.as_str()).collect();
+            assert_eq!(seed_texts, ["b\"vault\"", "user.key().as_ref()"]);
+            assert_eq!(seeds.seeds[1].path, "user.key.as_ref");
+            assert_eq!(snippet(source_map, &seeds.seeds[1]), "user.key().as_ref()");
+
+            // A bare `bump` has an empty value spanning the keyword
+            let bump = constraints.bump.as_ref().unwrap();
+            assert!(bump.tokens.is_empty());
+            assert_eq!(snippet(source_map, bump), "bump");
+
+            let keys: Vec<_> = constraints
+                .entries
+                .iter()
+                .map(|entry| entry.key.as_str())
+                .collect();
+            assert_eq!(keys, ["init", "payer", "space", "seeds", "bump"]);
+        },
+    );
+}
+
+#[test]
+fn bump_and_seeds_program_values_are_parsed() {
+    with_constraints(
+        &["seeds = VAULT_SEEDS, bump = vault.bump, seeds::program = other_program.key()"],
+        |constraints, _| {
+            // A seeds value that is not a literal list keeps no seeds
+            let seeds = constraints.seeds.as_ref().unwrap();
+            assert!(seeds.seeds.is_empty());
+            assert_eq!(seeds.value.path, "VAULT_SEEDS");
+
+            assert_eq!(constraints.bump.as_ref().unwrap().path, "vault.bump");
+            let seeds_program = constraints.seeds_program.as_ref().unwrap();
+            assert_eq!(seeds_program.text, "other_program.key()");
+            assert_eq!(seeds_program.path, "other_program.key");
+        },
+    );
+}
+
+#[test]
+fn realloc_constraints_are_parsed() {
+    with_constraints(
+        &["mut, realloc = 8 + len as usize, realloc::payer = payer, realloc::zero = false"],
+        |constraints, source_map| {
+            assert!(constraints.mut_);
+            let realloc = constraints.realloc.as_ref().unwrap();
+            assert_eq!(realloc.text, "8 + len as usize");
+            assert_eq!(snippet(source_map, realloc), "8 + len as usize");
+            assert_eq!(constraints.realloc_payer.as_ref().unwrap().text, "payer");
+            assert_eq!(constraints.realloc_zero.as_ref().unwrap().text, "false");
+            assert!(constraints.has("realloc"));
+            assert!(constraints.value("realloc::payer").is_some());
+        },
+    );
+}
+
+#[test]
+fn custom_errors_are_split_from_values() {
+    with_constraints(
+        &[
+            "has_one = authority @ VaultError::Unauthorized, has_one = mint",
+            "constraint = vault.amount > 0 @ VaultError::Empty, signer @ VaultError::NotSigner",
+        ],
+        |constraints, source_map| {
+            let has_one: Vec<_> = constraints
+                .has_one
+                .iter()
+                .map(|value| (value.text.as_str(), value.error.as_deref()))
+                .collect();
+            assert_eq!(
+                has_one,
+                [
+                    ("authority", Some("VaultError::Unauthorized")),
+                    ("mint", None)
+                ]
+            );
+            // The value span stops before the custom error
+            assert_eq!(snippet(source_map, &constraints.has_one[0]), "authority");
+
+            let constraint = &constraints.constraints[0];
+            assert_eq!(constraint.text, "vault.amount > 0");
+            assert_eq!(constraint.error.as_deref(), Some("VaultError::Empty"));
+
+            // A bare key with an error keeps the error in a value spanning the key
+            assert!(constraints.signer);
+            let signer = constraints.value("signer").unwrap();
+            assert_eq!(signer.error.as_deref(), Some("VaultError::NotSigner"));
+            assert_eq!(snippet(source_map, signer), "signer");
+        },
+    );
+}
+
+#[test]
+fn token_namespaces_are_parsed() {
+    with_constraints(
+        &[
+            "token::mint = mint, token::authority = vault_authority, token::token_program = token_program",
+            "associated_token::mint = other_mint, associated_token::authority = owner.key()",
+        ],
+        |constraints, source_map| {
+            assert_eq!(constraints.token.mint.as_ref().unwrap().text, "mint");
+            assert_eq!(
+                constraints.token.authority.as_ref().unwrap().text,
+                "vault_authority"
+            );
+            assert_eq!(
+                constraints.token.token_program.as_ref().unwrap().text,
+                "token_program"
+            );
+            assert_eq!(
+                constraints.associated_token.mint.as_ref().unwrap().text,
+                "other_mint"
+            );
+            let authority = constraints.associated_token.authority.as_ref().unwrap();
+            assert_eq!(authority.path, "owner.key");
+            assert_eq!(snippet(source_map, authority), "owner.key()");
+            assert!(constraints.associated_token.token_program.is_none());
+
+            assert!(constraints.has("token"));
+            assert!(constraints.has("associated_token"));
+            assert!(!constraints.has("mint"));
+            let key_span = constraints.entries[1].key_span;
+            assert_eq!(
+                source_map.span_to_snippet(key_span).unwrap(),
+                "token::authority"
+            );
+        },
+    );
+}
+
+#[test]
+fn mint_namespace_and_address_are_parsed() {
+    with_constraints(
+        &[
+            "init, payer = payer, mint::decimals = 6, mint::authority = authority, address = crate::MINT_ID",
+        ],
+        |constraints, _| {
+            assert_eq!(constraints.mint.decimals.as_ref().unwrap().text, "6");
+            assert_eq!(
+                constraints.mint.authority.as_ref().unwrap().text,
+                "authority"
+            );
+            assert!(constraints.mint.freeze_authority.is_none());
+            assert_eq!(constraints.address.as_ref().unwrap().path, "crate::MINT_ID");
+            assert!(constraints.has("mint"));
+        },
+    );
+}
+
+#[test]
+fn struct_constraints_are_parsed_once() {
+    let def_id = |index| DefId {
+        krate: LOCAL_CRATE,
+        index: DefIndex::from_u32(index),
+    };
+    let (shared_struct, other_struct) = (def_id(1), def_id(2));
+    let (authority, vault, unknown_field) = (def_id(3), def_id(4), def_id(5));
+    let parse_shared_struct = || {
+        let signer = AccountConstraints {
+            signer: true,
+            ..Default::default()
+        };
+        let mutable = AccountConstraints {
+            mut_: true,
+            ..Default::default()
+        };
+        HashMap::from([(authority, signer), (vault, mutable)])
+    };
+
+    let parsed_before = parsed_struct_count();
+    // Two handlers sharing the accounts struct read its fields
+    let first = cached_field_constraints(shared_struct, authority, parse_shared_struct).unwrap();
+    let second = cached_field_constraints(shared_struct, authority, || {
+        panic!("`shared_struct` was parsed again")
+    })
+    .unwrap();
+    let vault_constraints = cached_field_constraints(shared_struct, vault, || {
+        panic!("`shared_struct` was parsed again")
+    })
+    .unwrap();
+    assert_eq!(parsed_struct_count(), parsed_before + 1);
+
+    // The cached constraints are the parsed ones
+    assert!(first.signer && second.signer);
+    assert!(!first.mut_ && !second.mut_);
+    assert!(vault_constraints.mut_ && !vault_constraints.signer);
+    assert!(cached_field_constraints(shared_struct, unknown_field, || unreachable!()).is_none());
+
+    // Another struct is parsed on its own
+    cached_field_constraints(other_struct, unknown_field, HashMap::new);
+    assert_eq!(parsed_struct_count(), parsed_before + 2);
+}
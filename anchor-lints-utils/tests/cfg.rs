@@ -0,0 +1,166 @@
+#![feature(rustc_private)]
+
+extern crate rustc_data_structures;
+
+use anchor_lints_utils::cfg::{dominates, reachable, reachable_from, reachable_without_passing};
+use rustc_data_structures::graph::{
+    DirectedGraph, Predecessors, StartNode, Successors, dominators::dominators,
+};
+use std::collections::HashSet;
+
+/// A control flow graph over the nodes `0..num_nodes`, starting at node 0
+struct TestGraph {
+    successors: Vec<Vec<usize>>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl TestGraph {
+    fn new(num_nodes: usize, edges: &[(usize, usize)]) -> Self {
+        let mut graph = TestGraph {
+            successors: vec![Vec::new(); num_nodes],
+            predecessors: vec![Vec::new(); num_nodes],
+        };
+        for &(from, to) in edges {
+            graph.successors[from].push(to);
+            graph.predecessors[to].push(from);
+        }
+        graph
+    }
+}
+
+impl DirectedGraph for TestGraph {
+    type Node = usize;
+
+    fn num_nodes(&self) -> usize {
+        self.successors.len()
+    }
+}
+
+impl StartNode for TestGraph {
+    fn start_node(&self) -> usize {
+        0
+    }
+}
+
+impl Successors for TestGraph {
+    fn successors(&self, node: usize) -> impl Iterator<Item = usize> {
+        self.successors[node].iter().copied()
+    }
+}
+
+impl Predecessors for TestGraph {
+    fn predecessors(&self, node: usize) -> impl Iterator<Item = usize> {
+        self.predecessors[node].iter().copied()
+    }
+}
+
+fn set(nodes: &[usize]) -> HashSet<usize> {
+    nodes.iter().copied().collect()
+}
+
+/// `0` branches to `1` and `2`, which join in `3`
+fn diamond() -> TestGraph {
+    TestGraph::new(4, &[(0, 1), (0, 2), (1, 3), (2, 3)])
+}
+
+/// `0` enters the loop `1 -> 2 -> 1`, which exits to `3`
+fn simple_loop() -> TestGraph {
+    TestGraph::new(4, &[(0, 1), (1, 2), (2, 1), (1, 3)])
+}
+
+/// `0` returns early through `1` or continues to `2`, both end in `3`; `4` is unreachable
+fn early_return() -> TestGraph {
+    TestGraph::new(5, &[(0, 1), (0, 2), (1, 3), (2, 3), (4, 3)])
+}
+
+#[test]
+fn reachable_includes_the_start_node() {
+    let graph = diamond();
+    assert_eq!(reachable(&graph, 1, |node| node == 1), Some(1));
+    assert_eq!(reachable(&graph, 0, |node| node == 3), Some(3));
+    assert_eq!(reachable(&graph, 1, |node| node == 2), None);
+    assert_eq!(reachable(&graph, 3, |node| node == 0), None);
+}
+
+#[test]
+fn reachable_finds_the_closest_node() {
+    let graph = diamond();
+    assert_eq!(reachable(&graph, 0, |node| node == 1 || node == 3), Some(1));
+}
+
+#[test]
+fn reachable_follows_back_edges() {
+    let graph = simple_loop();
+    assert_eq!(reachable(&graph, 2, |node| node == 1), Some(1));
+    assert_eq!(reachable(&graph, 2, |node| node == 3), Some(3));
+    assert_eq!(reachable(&graph, 3, |node| node == 1), None);
+}
+
+#[test]
+fn reachable_from_collects_closest_first() {
+    let graph = diamond();
+    assert_eq!(reachable_from(&graph, [0]), vec![0, 1, 2, 3]);
+    assert_eq!(reachable_from(&graph, [2, 1]), vec![2, 1, 3]);
+
+    let graph = simple_loop();
+    assert_eq!(reachable_from(&graph, [2]), vec![2, 1, 3]);
+}
+
+#[test]
+fn reachable_without_passing_reports_bypassing_paths() {
+    // `0` is a CPI, `1` a reload and `3` an access; `2` bypasses the reload
+    let graph = diamond();
+    assert_eq!(
+        reachable_without_passing(&graph, &set(&[0]), &set(&[3]), &set(&[1])),
+        vec![(3, 0)]
+    );
+    assert!(reachable_without_passing(&graph, &set(&[0]), &set(&[3]), &set(&[1, 2])).is_empty());
+}
+
+#[test]
+fn reachable_without_passing_keeps_start_nodes() {
+    let graph = simple_loop();
+    assert_eq!(
+        reachable_without_passing(&graph, &set(&[2]), &set(&[2]), &set(&[1])),
+        vec![(2, 2)]
+    );
+    assert!(reachable_without_passing(&graph, &set(&[2]), &set(&[3]), &set(&[1])).is_empty());
+}
+
+#[test]
+fn dominates_diamond() {
+    let graph = diamond();
+    let dominators = dominators(&graph);
+    assert!(dominates(&dominators, 0, 3));
+    assert!(dominates(&dominators, 3, 3));
+    assert!(!dominates(&dominators, 1, 3));
+    assert!(!dominates(&dominators, 2, 3));
+}
+
+#[test]
+fn dominates_loop() {
+    let graph = simple_loop();
+    let dominators = dominators(&graph);
+    assert!(dominates(&dominators, 1, 2));
+    assert!(dominates(&dominators, 1, 3));
+    assert!(!dominates(&dominators, 2, 1));
+    assert!(!dominates(&dominators, 2, 3));
+}
+
+#[test]
+fn dominates_early_return() {
+    let graph = early_return();
+    let dominators = dominators(&graph);
+    assert!(dominates(&dominators, 0, 2));
+    assert!(!dominates(&dominators, 1, 3));
+    assert!(!dominates(&dominators, 2, 3));
+}
+
+#[test]
+fn unreachable_nodes_are_not_dominated() {
+    let graph = early_return();
+    let dominators = dominators(&graph);
+    assert!(!dominates(&dominators, 0, 4));
+    assert!(!dominates(&dominators, 4, 4));
+    assert!(!dominates(&dominators, 4, 3));
+}
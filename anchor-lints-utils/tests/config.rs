@@ -0,0 +1,131 @@
+#![feature(rustc_private)]
+
+use anchor_lints_utils::config::{Config, ConfigError};
+
+#[test]
+fn valid_config_is_loaded() {
+    let config = Config::from_toml_str(
+        r#"
+[missing_account_field_init]
+padding_field_patterns = ["^padding", "^gap"]
+
+[missing_owner_check]
+owner_validated_types = ["ValidatedMetadata"]
+
+[unsafe_pyth_price_account]
+canonical_feed_addresses = ["7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"]
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.missing_account_field_init.padding_field_patterns,
+        ["^padding", "^gap"]
+    );
+    assert_eq!(
+        config.missing_owner_check.owner_validated_types,
+        ["ValidatedMetadata"]
+    );
+    assert_eq!(
+        config.unsafe_pyth_price_account.canonical_feed_addresses,
+        ["7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"]
+    );
+}
+
+#[test]
+fn missing_tables_keep_their_defaults() {
+    let config = Config::from_toml_str("[missing_owner_check]\n").unwrap();
+    assert_eq!(config, Config::default());
+    assert_eq!(
+        config.missing_account_field_init.padding_field_patterns,
+        ["^padding", "^reserved", "^_"]
+    );
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let unknown_key = Config::from_toml_str(
+        r#"
+[missing_owner_check]
+owner_validated_type = ["ValidatedMetadata"]
+"#,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&unknown_key, ConfigError::Parse(error) if error.contains("owner_validated_type")),
+        "{unknown_key}"
+    );
+}
+
+#[test]
+fn foreign_tables_are_ignored() {
+    // `dylint.toml` also configures the other dylint libraries of the workspace
+    let config = Config::from_toml_str(
+        r#"
+[other_library]
+some_option = true
+
+[other_library.nested]
+threshold = 3
+
+[missing_owner_check]
+owner_validated_types = ["ValidatedMetadata"]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.missing_owner_check.owner_validated_types,
+        ["ValidatedMetadata"]
+    );
+    assert_eq!(
+        config.missing_account_field_init,
+        Config::default().missing_account_field_init
+    );
+}
+
+#[test]
+fn malformed_pubkeys_are_rejected() {
+    // `0` is not a base58 digit
+    let error = Config::from_toml_str(
+        r#"
+[unsafe_pyth_price_account]
+canonical_feed_addresses = ["7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLi0"]
+"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        ConfigError::InvalidPubkey {
+            key: "unsafe_pyth_price_account.canonical_feed_addresses",
+            value: "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLi0".to_string(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "`unsafe_pyth_price_account.canonical_feed_addresses` has \
+         `7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLi0`, which is not a base58 pubkey"
+    );
+
+    let too_short = Config::from_toml_str(
+        r#"
+[unsafe_pyth_price_account]
+canonical_feed_addresses = ["7UVimffxr9ow"]
+"#,
+    );
+    assert!(matches!(too_short, Err(ConfigError::InvalidPubkey { .. })));
+}
+
+#[test]
+fn invalid_patterns_are_rejected() {
+    let error = Config::from_toml_str(
+        r#"
+[missing_account_field_init]
+padding_field_patterns = ["^(padding"]
+"#,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&error, ConfigError::InvalidPattern { value, .. } if value == "^(padding"),
+        "{error}"
+    );
+}
@@ -0,0 +1,89 @@
+#![feature(rustc_private)]
+
+extern crate rustc_span;
+
+use anchor_lints_utils::findings::{
+    Confidence, Finding, FindingSink, Severity, deliver_to_installed_sink, parse_suppression,
+    set_finding_sink,
+};
+use rustc_span::DUMMY_SP;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default, Clone)]
+struct InMemorySink {
+    findings: Arc<Mutex<Vec<Finding>>>,
+}
+
+impl FindingSink for InMemorySink {
+    fn emit(&self, finding: Finding) {
+        self.findings.lock().unwrap().push(finding);
+    }
+}
+
+fn test_finding(message: &str) -> Finding {
+    Finding {
+        lint: "TEST_LINT",
+        message: message.to_string(),
+        span: DUMMY_SP,
+        notes: Vec::new(),
+        helps: Vec::new(),
+        severity: Severity::Warning,
+        confidence: Confidence::High,
+    }
+}
+
+#[test]
+fn custom_sink_captures_findings() {
+    let sink = InMemorySink::default();
+    assert!(set_finding_sink(Some(Box::new(sink.clone()))).is_none());
+
+    let finding = test_finding("first").with_note(None, "declared here");
+    assert!(deliver_to_installed_sink(finding).is_none());
+    assert!(deliver_to_installed_sink(test_finding("second").with_help(None, "fix it")).is_none());
+
+    {
+        let captured = sink.findings.lock().unwrap();
+        assert_eq!(captured.len(), 2);
+        assert_eq!(captured[0].lint, "TEST_LINT");
+        assert_eq!(captured[0].message, "first");
+        assert_eq!(
+            captured[0].notes,
+            vec![(DUMMY_SP, "declared here".to_string())]
+        );
+        assert_eq!(captured[1].message, "second");
+        assert_eq!(captured[1].helps, vec![(DUMMY_SP, "fix it".to_string())]);
+    }
+
+    // Without a sink, findings are handed back for the default diagnostic path
+    assert!(set_finding_sink(None).is_some());
+    let returned = deliver_to_installed_sink(test_finding("third"));
+    assert_eq!(
+        returned.map(|finding| finding.message),
+        Some("third".to_string())
+    );
+    assert_eq!(sink.findings.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn suppression_comments_carry_their_reason() {
+    assert_eq!(
+        parse_suppression("    // anchor-lints: validated-elsewhere checked by the router"),
+        Some("checked by the router")
+    );
+    assert_eq!(
+        parse_suppression("let x = y; // anchor-lints: validated-elsewhere: pinned by the caller"),
+        Some("pinned by the caller")
+    );
+    assert_eq!(
+        parse_suppression("// anchor-lints: validated-elsewhere"),
+        Some("")
+    );
+    assert_eq!(
+        parse_suppression("// anchor-lints validated elsewhere"),
+        None
+    );
+    assert_eq!(
+        parse_suppression("let marker = \"anchor-lints: validated-elsewhere\";"),
+        None
+    );
+}
@@ -0,0 +1,33 @@
+#![feature(rustc_private)]
+
+use anchor_lints_utils::utils::parse_inline_allow;
+
+#[test]
+fn inline_allow_comments_list_their_lints() {
+    assert_eq!(
+        parse_inline_allow("        // anchor-lints:allow(arbitrary_cpi_call)"),
+        Some(vec!["arbitrary_cpi_call"])
+    );
+    assert_eq!(
+        parse_inline_allow("// anchor-lints:allow(arbitrary_cpi_call, missing_account_reload)"),
+        Some(vec!["arbitrary_cpi_call", "missing_account_reload"])
+    );
+    assert_eq!(parse_inline_allow("// anchor-lints:allow()"), Some(vec![]));
+}
+
+#[test]
+fn inline_allow_comments_stand_alone_on_their_line() {
+    // Trailing comments would allow the line they are on, not the one below
+    assert_eq!(
+        parse_inline_allow("invoke(&ix, &accounts)?; // anchor-lints:allow(arbitrary_cpi_call)"),
+        None
+    );
+    assert_eq!(
+        parse_inline_allow("// anchor-lints:allow(arbitrary_cpi_call"),
+        None
+    );
+    assert_eq!(
+        parse_inline_allow("// anchor-lints: validated-elsewhere checked by the router"),
+        None
+    );
+}
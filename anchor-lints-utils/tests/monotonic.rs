@@ -0,0 +1,153 @@
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_span;
+
+use anchor_lints_utils::{mir_analyzer::MirAnalyzer, models::MonotonicEnforcement};
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::mir::{Local, Place, ProjectionElem};
+use rustc_span::Span;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Functions keeping a `last_update_ts` field monotonic, or failing to, without any oracle
+const PROGRAM: &str = r#"
+pub struct State {
+    pub last_update_ts: i64,
+}
+
+pub struct Clock {
+    pub unix_timestamp: i64,
+}
+
+pub fn store_only(state: &mut State, clock: &Clock) {
+    let now = clock.unix_timestamp;
+    state.last_update_ts = now;
+}
+
+pub fn compare_only(state: &State, clock: &Clock) -> Result<(), ()> {
+    let now = clock.unix_timestamp;
+    if now <= state.last_update_ts {
+        return Err(());
+    }
+    Ok(())
+}
+
+pub fn store_and_compare(state: &mut State, clock: &Clock) -> Result<(), ()> {
+    let now = clock.unix_timestamp;
+    if now <= state.last_update_ts {
+        return Err(());
+    }
+    state.last_update_ts = now;
+    Ok(())
+}
+
+pub fn store_and_compare_other_value(state: &mut State, clock: &Clock, other: i64) {
+    let now = clock.unix_timestamp;
+    if other > state.last_update_ts {
+        state.last_update_ts = now;
+    }
+}
+"#;
+
+/// Enforcement found in each function of [`PROGRAM`], by function name
+static ENFORCEMENTS: Mutex<Option<HashMap<String, MonotonicEnforcement>>> = Mutex::new(None);
+
+rustc_session::declare_lint_pass!(MonotonicPass => []);
+
+impl<'tcx> LateLintPass<'tcx> for MonotonicPass {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        _: Span,
+        def_id: LocalDefId,
+    ) {
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        // The first argument is the state, the second one the clock the new value is read from
+        let field_of_argument = |argument: u32| {
+            move |place: &Place<'_>| {
+                place.local == Local::from_u32(argument)
+                    && matches!(place.projection.last(), Some(ProjectionElem::Field(..)))
+            }
+        };
+        let enforcement = mir_analyzer
+            .detects_monotonic_store_and_compare(field_of_argument(2), field_of_argument(1));
+        ENFORCEMENTS.lock().unwrap().get_or_insert_default().insert(
+            cx.tcx.item_name(def_id.to_def_id()).to_string(),
+            enforcement,
+        );
+    }
+}
+
+struct MonotonicCallbacks;
+
+impl rustc_driver::Callbacks for MonotonicCallbacks {
+    fn config(&mut self, config: &mut rustc_interface::interface::Config) {
+        config.register_lints = Some(Box::new(|_, lint_store| {
+            lint_store.register_late_pass(|_| Box::new(MonotonicPass));
+        }));
+    }
+}
+
+/// Compile [`PROGRAM`] and collect the enforcement found in each of its functions
+fn program_enforcements() -> HashMap<String, MonotonicEnforcement> {
+    let dir = std::env::temp_dir().join(format!("anchor-lints-monotonic-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("program.rs");
+    std::fs::write(&source, PROGRAM).unwrap();
+
+    let args = [
+        "rustc",
+        source.to_str().unwrap(),
+        "--crate-type=lib",
+        "--edition=2024",
+        "--emit=metadata",
+        "--out-dir",
+        dir.to_str().unwrap(),
+    ]
+    .map(String::from);
+    rustc_driver::run_compiler(&args, &mut MonotonicCallbacks);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    ENFORCEMENTS.lock().unwrap().take().unwrap_or_default()
+}
+
+#[test]
+fn store_and_compare_patterns_are_classified() {
+    let enforcements = program_enforcements();
+    let enforcement = |name: &str| {
+        *enforcements
+            .get(name)
+            .unwrap_or_else(|| panic!("`{name}` was not analyzed"))
+    };
+
+    let store_only = enforcement("store_only");
+    assert!(store_only.has_store && !store_only.has_compare);
+    assert!(!store_only.is_complete());
+
+    let compare_only = enforcement("compare_only");
+    assert!(compare_only.has_compare && !compare_only.has_store);
+    assert!(!compare_only.is_complete());
+
+    assert_eq!(
+        enforcement("store_and_compare"),
+        MonotonicEnforcement {
+            has_compare: true,
+            has_store: true,
+        }
+    );
+    assert!(enforcement("store_and_compare").is_complete());
+
+    // Comparing another value than the stored one does not enforce monotonicity
+    let other_value = enforcement("store_and_compare_other_value");
+    assert!(other_value.has_store && !other_value.has_compare);
+    assert!(!other_value.is_complete());
+}
@@ -0,0 +1,131 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{AccountsAliasAnalysis, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `#[derive(Accounts)]` structs that close one account and `init` or
+    /// `init_if_needed` another one which can resolve to the same address, e.g. both are
+    /// derived from the same seeds and no `constraint` keeps their keys apart.
+    ///
+    /// ### Why is this bad?
+    /// Anchor closes an account when the instruction exits, after the other accounts were
+    /// initialized and written. When both fields are the same account, the closed account is
+    /// reused by `init_if_needed` with its old data, or written back after it was closed, so its
+    /// state survives until the end of the transaction and later instructions of the same
+    /// transaction operate on an account that should no longer exist.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Rollover<'info> {
+    ///     #[account(mut, close = owner, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    ///     pub old_receipt: Account<'info, Receipt>,
+    ///     #[account(init_if_needed, payer = owner, space = 8 + Receipt::INIT_SPACE, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    ///     pub new_receipt: Account<'info, Receipt>,
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Rollover<'info> {
+    ///     #[account(mut, close = owner, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    ///     pub old_receipt: Account<'info, Receipt>,
+    ///     #[account(init_if_needed, payer = owner, space = 8 + Receipt::INIT_SPACE, seeds = [b"archive", owner.key().as_ref()], bump)]
+    ///     pub new_receipt: Account<'info, Receipt>,
+    ///     // ...
+    /// }
+    /// ```
+    pub ACCOUNT_REINIT_VIA_CLOSE_THEN_INIT_IN_SAME_TX,
+    Warn,
+    "an account closed and an account initialized by the same instruction can be the same address"
+}
+
+impl<'tcx> LateLintPass<'tcx> for AccountReinitViaCloseThenInitInSameTx {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ACCOUNT_REINIT_VIA_CLOSE_THEN_INIT_IN_SAME_TX) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(ACCOUNT_REINIT_VIA_CLOSE_THEN_INIT_IN_SAME_TX);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_close_then_init(cx, anchor_context);
+    }
+}
+
+fn analyze_close_then_init<'tcx>(cx: &LateContext<'tcx>, anchor_context: &AnchorContextInfo<'tcx>) {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+
+    let accounts_variant = adt_def.non_enum_variant();
+    let alias_analysis = AccountsAliasAnalysis::new(cx, accounts_variant);
+    let (closed, initialized) = collect_closed_and_initialized(cx, accounts_variant, generics);
+
+    // Report each initialized account against the first closed account it may alias
+    for created in &initialized {
+        let Some(closed) = closed.iter().find(|closed| {
+            closed.name != created.name
+                && data_types_may_match(closed, created)
+                && closed.seeds_program == created.seeds_program
+                && alias_analysis.addresses_may_alias(&closed.name, &created.name)
+        }) else {
+            continue;
+        };
+
+        span_lint_and_note(
+            cx,
+            ACCOUNT_REINIT_VIA_CLOSE_THEN_INIT_IN_SAME_TX,
+            created.span,
+            format!(
+                "`{}` is initialized by the instruction closing `{}`, and nothing keeps the two at different addresses — the closed account can be re-initialized with its old data in the same transaction",
+                created.name, closed.name,
+            ),
+            Some(closed.constraint_span),
+            format!("`{}` is closed here", closed.name),
+        );
+    }
+}
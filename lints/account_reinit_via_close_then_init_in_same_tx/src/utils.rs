@@ -0,0 +1,62 @@
+use anchor_lints_utils::utils::{
+    account_constraints::{normalize_source, parse_account_constraints},
+    account_data_type, compare_adt_def_ids,
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::ty::{GenericArgsRef, Ty, VariantDef};
+use rustc_span::Span;
+
+/// A field of an accounts struct closed or initialized by the instruction
+pub struct LifecycleAccount<'tcx> {
+    pub name: String,
+    pub span: Span,
+    /// Span of the `close = ...` constraint, or of the `init`/`init_if_needed` keyword
+    pub constraint_span: Span,
+    /// Type of the data stored in the account, `None` for untyped accounts
+    pub data_ty: Option<Ty<'tcx>>,
+    /// Source text of `seeds::program = ...`, `None` for the program itself
+    pub seeds_program: Option<String>,
+}
+
+/// Collect the fields with a `close` constraint and the fields with `init` or `init_if_needed`
+pub fn collect_closed_and_initialized<'tcx>(
+    cx: &LateContext<'tcx>,
+    accounts_variant: &VariantDef,
+    generics: GenericArgsRef<'tcx>,
+) -> (Vec<LifecycleAccount<'tcx>>, Vec<LifecycleAccount<'tcx>>) {
+    let mut closed = Vec::new();
+    let mut initialized = Vec::new();
+    for field in &accounts_variant.fields {
+        let constraints = parse_account_constraints(cx, field);
+        let lifecycle_account = |key: &str| {
+            let entry = constraints.entries.iter().find(|entry| entry.key == key)?;
+            Some(LifecycleAccount {
+                name: field.ident(cx.tcx).to_string(),
+                span: cx.tcx.def_span(field.did),
+                constraint_span: entry
+                    .value
+                    .as_ref()
+                    .map_or(entry.key_span, |value| entry.key_span.to(value.span)),
+                data_ty: account_data_type(cx, field.ty(cx.tcx, generics)),
+                seeds_program: constraints
+                    .seeds_program
+                    .as_ref()
+                    .map(|program| normalize_source(&program.text)),
+            })
+        };
+        closed.extend(lifecycle_account("close"));
+        initialized
+            .extend(lifecycle_account("init").or_else(|| lifecycle_account("init_if_needed")));
+    }
+    (closed, initialized)
+}
+
+/// Check if two accounts can hold the same data, as Anchor rejects an account whose
+/// discriminator does not match its declared type
+pub fn data_types_may_match(closed: &LifecycleAccount, created: &LifecycleAccount) -> bool {
+    match (closed.data_ty, created.data_ty) {
+        (Some(closed_ty), Some(created_ty)) => compare_adt_def_ids(closed_ty, created_ty),
+        _ => true,
+    }
+}
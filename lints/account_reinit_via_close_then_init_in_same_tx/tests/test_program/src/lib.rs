@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod account_reinit_via_close_then_init_in_same_tx_tests {
+    use super::*;
+
+    // BAD: the closed and the new receipt are derived from the same seeds
+    pub fn rollover_receipt(ctx: Context<RolloverReceipt>, amount: u64) -> Result<()> {
+        ctx.accounts.new_receipt.amount = ctx.accounts.old_receipt.amount + amount;
+        Ok(())
+    }
+
+    // BAD: nothing keeps the caller from passing the closed ticket as the new one
+    pub fn reissue_ticket(ctx: Context<ReissueTicket>) -> Result<()> {
+        ctx.accounts.new_ticket.seat = ctx.accounts.old_ticket.seat;
+        Ok(())
+    }
+
+    // GOOD: a distinct seed prefix keeps the accounts at different addresses
+    pub fn archive_receipt(ctx: Context<ArchiveReceipt>) -> Result<()> {
+        ctx.accounts.archive.amount = ctx.accounts.receipt.amount;
+        Ok(())
+    }
+
+    // GOOD: the keys of the closed and the new ticket must differ
+    pub fn reissue_distinct_ticket(ctx: Context<ReissueDistinctTicket>) -> Result<()> {
+        ctx.accounts.new_ticket.seat = ctx.accounts.old_ticket.seat;
+        Ok(())
+    }
+
+    // GOOD: an account cannot be both a `Ticket` and a `Receipt`
+    pub fn redeem_ticket(ctx: Context<RedeemTicket>) -> Result<()> {
+        ctx.accounts.receipt.amount = ctx.accounts.ticket.seat;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RolloverReceipt<'info> {
+    #[account(mut, close = owner, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    pub old_receipt: Account<'info, Receipt>,
+    #[account(init_if_needed, payer = owner, space = 8 + 8, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    pub new_receipt: Account<'info, Receipt>, // [account_reinit_via_close_then_init_in_same_tx: note=`old_receipt` is closed here]
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReissueTicket<'info> {
+    #[account(mut, close = owner)]
+    pub old_ticket: Account<'info, Ticket>,
+    #[account(init_if_needed, payer = owner, space = 8 + 8)]
+    pub new_ticket: Account<'info, Ticket>, // [account_reinit_via_close_then_init_in_same_tx]
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ArchiveReceipt<'info> {
+    #[account(mut, close = owner, seeds = [b"receipt", owner.key().as_ref()], bump)]
+    pub receipt: Account<'info, Receipt>,
+    #[account(init, payer = owner, space = 8 + 8, seeds = [b"archive", owner.key().as_ref()], bump)]
+    pub archive: Account<'info, Receipt>, // [safe_close_then_init]
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReissueDistinctTicket<'info> {
+    #[account(mut, close = owner)]
+    pub old_ticket: Account<'info, Ticket>,
+    #[account(init_if_needed, payer = owner, space = 8 + 8, constraint = old_ticket.key() != new_ticket.key())]
+    pub new_ticket: Account<'info, Ticket>, // [safe_close_then_init]
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemTicket<'info> {
+    #[account(mut, close = owner)]
+    pub ticket: Account<'info, Ticket>,
+    #[account(init_if_needed, payer = owner, space = 8 + 8)]
+    pub receipt: Account<'info, Receipt>, // [safe_close_then_init]
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Receipt {
+    pub amount: u64,
+}
+
+#[account]
+pub struct Ticket {
+    pub seat: u64,
+}
@@ -0,0 +1,146 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_ast;
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+mod utils;
+
+use anchor_lints_utils::{
+    diag_items::anchor_inner_account_type,
+    findings::{Finding, emit_finding},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::should_skip_function,
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects accounts whose `space` constraint is an integer literal expression smaller than
+    /// the minimum serialized size of the account type: the 8-byte discriminator plus the size
+    /// of every field, counting `String` and `Vec` as their 4-byte length prefix and `Option`
+    /// as its 1-byte tag.
+    ///
+    /// ### Why is this bad?
+    /// Anchor allocates exactly `space` bytes. When the account data does not fit, serializing
+    /// it at the end of the instruction fails, so the account can never be initialized or
+    /// written to.
+    ///
+    /// ### Example
+    /// ```rust
+    /// #[account]
+    /// pub struct Vault {
+    ///     pub authority: Pubkey,
+    ///     pub amount: u64,
+    ///     pub bump: u8,
+    /// }
+    ///
+    /// // Bad:
+    /// #[account(init, payer = user, space = 8 + 32 + 8)] // `bump` is not accounted for
+    /// pub vault: Account<'info, Vault>,
+    ///
+    /// // Good:
+    /// #[account(init, payer = user, space = 8 + 32 + 8 + 1)]
+    /// pub vault: Account<'info, Vault>,
+    /// ```
+    pub ACCOUNT_SPACE_MISMATCH_WITH_STRUCT_SIZE,
+    Warn,
+    "literal `space` constraint is smaller than the minimum serialized size of the account type"
+}
+
+impl<'tcx> LateLintPass<'tcx> for AccountSpaceMismatchWithStructSize {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ACCOUNT_SPACE_MISMATCH_WITH_STRUCT_SIZE) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(ACCOUNT_SPACE_MISMATCH_WITH_STRUCT_SIZE);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_space_constraints(cx, anchor_context);
+    }
+}
+
+fn analyze_space_constraints<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+
+    for field in &adt_def.non_enum_variant().fields {
+        let Some(space) = extract_literal_space(cx, field) else {
+            continue;
+        };
+
+        let mut field_ty = field.ty(cx.tcx, generics);
+        if field_ty.is_box() {
+            field_ty = field_ty.expect_boxed_ty();
+        }
+        // Only `Account` is Borsh-serialized; zero-copy accounts are sized by their layout
+        let Some(account_ty) = anchor_inner_account_type(cx.tcx, field_ty) else {
+            continue;
+        };
+        let TyKind::Adt(account_adt, _) = account_ty.kind() else {
+            continue;
+        };
+        if !account_adt.did().is_local() {
+            continue;
+        }
+
+        let min_size = DISCRIMINATOR_SIZE + min_serialized_size(cx, account_ty, 0);
+        if space >= min_size {
+            continue;
+        }
+
+        let account_name = field.ident(cx.tcx).to_string();
+        let finding = Finding::new(
+            ACCOUNT_SPACE_MISMATCH_WITH_STRUCT_SIZE,
+            cx.tcx.def_span(field.did),
+            format!(
+                "account `{}` allocates `space = {}`, but `{}` needs at least {} bytes including the 8-byte discriminator",
+                account_name,
+                space,
+                cx.tcx.item_name(account_adt.did()),
+                min_size
+            ),
+        )
+        .with_note(
+            Some(cx.tcx.def_span(account_adt.did())),
+            "`String` and `Vec` fields are counted as their 4-byte length prefix and `Option` fields as their 1-byte tag",
+        );
+        emit_finding(cx, ACCOUNT_SPACE_MISMATCH_WITH_STRUCT_SIZE, finding);
+    }
+}
@@ -0,0 +1,121 @@
+use anchor_lints_utils::{
+    diag_items::is_solana_pubkey_type, utils::account_constraints::parse_account_constraints,
+};
+use clippy_utils::ty::is_type_diagnostic_item;
+
+use rustc_ast::{
+    token::{LitKind, TokenKind},
+    tokenstream::TokenTree,
+};
+use rustc_lint::LateContext;
+use rustc_middle::ty::{FieldDef, FloatTy, IntTy, Ty, TyKind, UintTy};
+use rustc_span::sym;
+
+/// Size of the discriminator Anchor prepends to every `#[account]`
+pub const DISCRIMINATOR_SIZE: u64 = 8;
+
+/// Nesting depth after which nested types are counted as empty
+const MAX_TYPE_DEPTH: usize = 8;
+
+/// Evaluate the `space` constraint of a field when it only adds and multiplies integer
+/// literals, e.g. `space = 8 + 32 + 4 * 10`
+pub fn extract_literal_space<'tcx>(cx: &LateContext<'tcx>, field: &FieldDef) -> Option<u64> {
+    let space = parse_account_constraints(cx, field).space?;
+    evaluate_sum_of_products(&space.tokens)
+}
+
+// Anything but integer literals, `+` and `*` is not evaluated
+fn evaluate_sum_of_products(tokens: &[TokenTree]) -> Option<u64> {
+    let mut sum: u64 = 0;
+    let mut product: u64 = 1;
+    let mut expect_operand = true;
+    for token in tokens {
+        let TokenTree::Token(tok, _) = token else {
+            return None;
+        };
+        match tok.kind {
+            TokenKind::Literal(lit) if expect_operand && lit.kind == LitKind::Integer => {
+                let value: u64 = lit.symbol.as_str().replace('_', "").parse().ok()?;
+                product = product.checked_mul(value)?;
+                expect_operand = false;
+            }
+            TokenKind::Plus if !expect_operand => {
+                sum = sum.checked_add(product)?;
+                product = 1;
+                expect_operand = true;
+            }
+            TokenKind::Star if !expect_operand => expect_operand = true,
+            _ => return None,
+        }
+    }
+    if expect_operand {
+        return None;
+    }
+    sum.checked_add(product)
+}
+
+/// Lower bound of the Borsh-serialized size of `ty`. Variable-length and unknown parts are
+/// counted at their minimum: `String`/`Vec` as their 4-byte length prefix, `Option` as `None`,
+/// enums as their tag plus their smallest variant, and anything else not understood as empty.
+pub fn min_serialized_size<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>, depth: usize) -> u64 {
+    if depth > MAX_TYPE_DEPTH {
+        return 0;
+    }
+    let tcx = cx.tcx;
+    match ty.kind() {
+        TyKind::Bool | TyKind::Int(IntTy::I8) | TyKind::Uint(UintTy::U8) => 1,
+        TyKind::Int(IntTy::I16) | TyKind::Uint(UintTy::U16) => 2,
+        TyKind::Int(IntTy::I32) | TyKind::Uint(UintTy::U32) | TyKind::Float(FloatTy::F32) => 4,
+        TyKind::Char => 4,
+        TyKind::Int(IntTy::I64) | TyKind::Uint(UintTy::U64) | TyKind::Float(FloatTy::F64) => 8,
+        TyKind::Int(IntTy::I128) | TyKind::Uint(UintTy::U128) => 16,
+        TyKind::Array(elem_ty, len) => len.try_to_target_usize(tcx).map_or(0, |len| {
+            len.saturating_mul(min_serialized_size(cx, *elem_ty, depth + 1))
+        }),
+        TyKind::Tuple(elem_tys) => elem_tys
+            .iter()
+            .map(|elem_ty| min_serialized_size(cx, elem_ty, depth + 1))
+            .sum(),
+        TyKind::Adt(adt_def, generics) => {
+            if ty.is_box() {
+                return min_serialized_size(cx, ty.expect_boxed_ty(), depth + 1);
+            }
+            if is_solana_pubkey_type(tcx, ty) {
+                return 32;
+            }
+            if is_type_diagnostic_item(cx, ty, sym::String)
+                || is_type_diagnostic_item(cx, ty, sym::Vec)
+            {
+                return 4;
+            }
+            if is_type_diagnostic_item(cx, ty, sym::Option) {
+                return 1;
+            }
+            // Types of other crates may serialize differently from their layout
+            if !adt_def.did().is_local() {
+                return 0;
+            }
+
+            let variant_size = |variant: &rustc_middle::ty::VariantDef| -> u64 {
+                variant
+                    .fields
+                    .iter()
+                    .map(|field| min_serialized_size(cx, field.ty(tcx, generics), depth + 1))
+                    .sum()
+            };
+            if adt_def.is_struct() {
+                variant_size(adt_def.non_enum_variant())
+            } else if adt_def.is_enum() {
+                1 + adt_def
+                    .variants()
+                    .iter()
+                    .map(variant_size)
+                    .min()
+                    .unwrap_or(0)
+            } else {
+                0
+            }
+        }
+        _ => 0,
+    }
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+const MAX_NAME_LEN: usize = 32;
+
+#[program]
+pub mod account_space_mismatch_with_struct_size_tests {
+    use super::*;
+
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn initialize_vault_undersized(ctx: Context<InitializeVaultUndersized>) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn initialize_profile(ctx: Context<InitializeProfile>) -> Result<()> {
+        ctx.accounts.profile.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn initialize_profile_undersized(ctx: Context<InitializeProfileUndersized>) -> Result<()> {
+        ctx.accounts.profile.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn initialize_profile_computed(ctx: Context<InitializeProfileComputed>) -> Result<()> {
+        ctx.accounts.profile.owner = ctx.accounts.user.key();
+        Ok(())
+    }
+
+    pub fn initialize_vault_pda_undersized(
+        ctx: Context<InitializeVaultPdaUndersized>,
+    ) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.user.key();
+        Ok(())
+    }
+}
+
+// GOOD: every field is accounted for
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(init, payer = user, space = 8 + 32 + 8 + 1)]
+    pub vault: Account<'info, Vault>, // [safe_account_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: `bump` is missing from the space
+#[derive(Accounts)]
+pub struct InitializeVaultUndersized<'info> {
+    #[account(init, payer = user, space = 8 + 32 + 8)]
+    pub vault: Account<'info, Vault>, // [account_space_mismatch_with_struct_size]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: room for the name, ten scores and a referrer
+#[derive(Accounts)]
+pub struct InitializeProfile<'info> {
+    #[account(init, payer = user, space = 8 + 32 + 4 + 32 + 4 + 10 * 8 + 1 + 32)]
+    pub profile: Account<'info, Profile>, // [safe_account_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: not even the length prefixes and the `Option` tag fit
+#[derive(Accounts)]
+pub struct InitializeProfileUndersized<'info> {
+    #[account(init, payer = user, space = 8 + 32)]
+    pub profile: Account<'info, Profile>, // [account_space_mismatch_with_struct_size]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: non-literal space is not evaluated
+#[derive(Accounts)]
+pub struct InitializeProfileComputed<'info> {
+    #[account(init, payer = user, space = 8 + 32 + 4 + MAX_NAME_LEN)]
+    pub profile: Account<'info, Profile>, // [safe_account_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: `space` following a `seeds` list misses `bump`
+#[derive(Accounts)]
+pub struct InitializeVaultPdaUndersized<'info> {
+    #[account(init, seeds = [b"vault", user.key().as_ref()], bump, payer = user, space = 8 + 32 + 8)]
+    pub vault: Account<'info, Vault>, // [account_space_mismatch_with_struct_size]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[account]
+pub struct Profile {
+    pub owner: Pubkey,
+    pub name: String,
+    pub scores: Vec<u64>,
+    pub referrer: Option<Pubkey>,
+}
@@ -0,0 +1,122 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{collect_account_deserializations, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects structs deserialized from the raw data of an `AccountInfo` or `UncheckedAccount`,
+    /// e.g. with `try_from_slice` or `try_deserialize`, whose fields are modified but that are
+    /// never serialized back, e.g. with `serialize` or `try_serialize`.
+    ///
+    /// Structs moved out of the function, e.g. returned to the caller, are skipped as the caller
+    /// may persist them, and so are structs passed by reference to a call that also takes the
+    /// account, e.g. `state.persist(&account)?`, or to a function of the program serializing a
+    /// value.
+    ///
+    /// ### Why is this bad?
+    /// Only `Account<'info, T>` writes its data back when the instruction returns. A struct
+    /// decoded manually is a copy of the account data, so modifying it without serializing it
+    /// into the data buffer loses the change, e.g. a counter or a balance that is never updated.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let mut vault = Vault::try_from_slice(&ctx.accounts.vault.try_borrow_data()?)?;
+    /// vault.balance += amount;
+    ///
+    /// // Good:
+    /// let mut data = ctx.accounts.vault.try_borrow_mut_data()?;
+    /// let mut vault = Vault::try_from_slice(&data)?;
+    /// vault.balance += amount;
+    /// vault.serialize(&mut &mut data[..])?;
+    /// ```
+    pub ACCOUNT_WRITTEN_WITHOUT_PERSISTING,
+    Warn,
+    "account data struct modified without being serialized back"
+}
+
+impl<'tcx> LateLintPass<'tcx> for AccountWrittenWithoutPersisting {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ACCOUNT_WRITTEN_WITHOUT_PERSISTING) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(ACCOUNT_WRITTEN_WITHOUT_PERSISTING);
+
+        analyze_unpersisted_writes(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_unpersisted_writes<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    let deserializations = collect_account_deserializations(mir_analyzer);
+    if deserializations.is_empty() {
+        return;
+    }
+
+    for local in mir_analyzer.mir.local_decls.indices() {
+        if !is_local_struct_variable(mir_analyzer, local) {
+            continue;
+        }
+
+        // The struct must be decoded from account data
+        let sources = mir_analyzer.collect_source_locals(local);
+        let Some(deserialization) = deserializations
+            .iter()
+            .find(|call| sources.contains(&call.destination))
+        else {
+            continue;
+        };
+
+        let Some(first_write) = first_field_write(mir_analyzer, local) else {
+            continue;
+        };
+        if is_serialized(mir_analyzer, local) || is_moved_out(mir_analyzer, local) {
+            continue;
+        }
+
+        span_lint_and_note(
+            cx,
+            ACCOUNT_WRITTEN_WITHOUT_PERSISTING,
+            first_write,
+            format!(
+                "`{}` is deserialized from account data and modified, but never serialized back — the change is lost when the instruction returns",
+                mir_analyzer.mir.local_decls[local].ty
+            ),
+            Some(deserialization.span),
+            "deserialized from the account data here",
+        );
+    }
+}
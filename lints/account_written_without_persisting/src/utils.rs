@@ -0,0 +1,141 @@
+use anchor_lints_utils::{
+    diag_items::{is_account_info_type, is_anchor_unchecked_account_type},
+    mir_analyzer::MirAnalyzer,
+    utils::method_name,
+};
+use clippy_utils::fn_has_unsatisfiable_preds;
+
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::{
+    mir::{BasicBlockData, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    ty::TyKind,
+};
+use rustc_span::{Span, source_map::Spanned};
+
+/// Methods encoding a value, into a writer or into a buffer copied into the account data
+pub const SERIALIZE_METHODS: &[&str] = &[
+    "serialize",
+    "try_serialize",
+    "to_writer",
+    "to_vec",
+    "try_to_vec",
+];
+
+/// Check if a local is a variable of a struct type defined in the analyzed crate, e.g. the
+/// `state` in `let mut state = State::try_from_slice(&data)?`
+pub fn is_local_struct_variable(mir_analyzer: &MirAnalyzer, local: Local) -> bool {
+    let decl = &mir_analyzer.mir.local_decls[local];
+    decl.is_user_variable()
+        && matches!(decl.ty.kind(), TyKind::Adt(adt_def, _)
+            if adt_def.is_struct() && adt_def.did().is_local())
+}
+
+/// Find the first assignment to a field of `local`, e.g. `state.count += 1`
+pub fn first_field_write(mir_analyzer: &MirAnalyzer, local: Local) -> Option<Span> {
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata.statements.iter().find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (place, _))
+                if place.local == local && !place.projection.is_empty() =>
+            {
+                Some(stmt.source_info.span)
+            }
+            _ => None,
+        })
+    })
+}
+
+/// Check if `local` is passed to a call persisting it: a serialization call, e.g.
+/// `state.serialize(&mut data)`, or a helper writing it back, e.g. `save(&mut state, &account)`
+/// or `state.persist(&account)?`
+pub fn is_serialized(mir_analyzer: &MirAnalyzer, local: Local) -> bool {
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        if let Some((fn_def_id, args)) = called_fn(bbdata)
+            && args.iter().any(|arg| {
+                arg.node
+                    .place()
+                    .and_then(|place| place.as_local())
+                    .is_some_and(|arg_local| {
+                        mir_analyzer
+                            .collect_source_locals(arg_local)
+                            .contains(&local)
+                    })
+            })
+        {
+            is_serialize_method(mir_analyzer, fn_def_id)
+                || is_persisting_helper(mir_analyzer, fn_def_id, args)
+        } else {
+            false
+        }
+    })
+}
+
+/// The function called by the terminator of `bbdata`, with its arguments
+fn called_fn<'a, 'tcx>(
+    bbdata: &'a BasicBlockData<'tcx>,
+) -> Option<(DefId, &'a [Spanned<Operand<'tcx>>])> {
+    if let TerminatorKind::Call {
+        func: Operand::Constant(func),
+        args,
+        ..
+    } = &bbdata.terminator().kind
+        && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+    {
+        Some((*fn_def_id, &args[..]))
+    } else {
+        None
+    }
+}
+
+fn is_serialize_method(mir_analyzer: &MirAnalyzer, def_id: DefId) -> bool {
+    method_name(mir_analyzer, def_id)
+        .is_some_and(|method| SERIALIZE_METHODS.contains(&method.as_str()))
+}
+
+/// Check if a call given the struct may write it back: it also takes the account, or it is a
+/// function of the analyzed crate serializing a value
+fn is_persisting_helper<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    def_id: DefId,
+    args: &[Spanned<Operand<'tcx>>],
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    args.iter().any(|arg| {
+        let ty = arg.node.ty(mir_analyzer.mir, tcx);
+        is_account_info_type(tcx, ty) || is_anchor_unchecked_account_type(tcx, ty)
+    }) || def_id
+        .as_local()
+        .is_some_and(|def_id| serializes_a_value(mir_analyzer, def_id))
+}
+
+/// Check if the body of `def_id` calls a serialization method
+fn serializes_a_value(mir_analyzer: &MirAnalyzer, def_id: LocalDefId) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    tcx.def_kind(def_id).is_fn_like()
+        && tcx.is_mir_available(def_id)
+        && !fn_has_unsatisfiable_preds(mir_analyzer.cx, def_id.to_def_id())
+        && tcx
+            .optimized_mir(def_id)
+            .basic_blocks
+            .iter()
+            .filter_map(called_fn)
+            .any(|(fn_def_id, _)| is_serialize_method(mir_analyzer, fn_def_id))
+}
+
+/// Check if the whole value of `local` is moved out, e.g. returned or passed by value to a
+/// helper that may persist it
+pub fn is_moved_out(mir_analyzer: &MirAnalyzer, local: Local) -> bool {
+    let is_moved =
+        |op: &Operand<'_>| matches!(op, Operand::Move(place) if place.as_local() == Some(local));
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        let moved_by_statement = bbdata.statements.iter().any(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (_, Rvalue::Use(op))) => is_moved(op),
+            StatementKind::Assign(box (_, Rvalue::Aggregate(_, operands))) => {
+                operands.iter().any(is_moved)
+            }
+            _ => false,
+        });
+        let moved_by_call = matches!(&bbdata.terminator().kind,
+            TerminatorKind::Call { args, .. } if args.iter().any(|arg| is_moved(&arg.node)));
+        moved_by_statement || moved_by_call
+    })
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod account_written_without_persisting_tests {
+    use super::*;
+
+    // BAD: the counter is modified but never written back to the account data
+    pub fn increment_without_write_back(ctx: Context<RawCounter>) -> Result<()> {
+        let mut counter = Counter::try_from_slice(&ctx.accounts.counter.try_borrow_data()?)?;
+        counter.count += 1; // [account_written_without_persisting]
+        msg!("count: {}", counter.count);
+        Ok(())
+    }
+
+    // BAD: the authority is replaced on a copy decoded from `data.borrow()`
+    pub fn set_authority_without_write_back(
+        ctx: Context<RawCounter>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let data = ctx.accounts.counter.data.borrow();
+        let mut counter = Counter::deserialize(&mut &data[..])?;
+        counter.authority = new_authority; // [account_written_without_persisting]
+        Ok(())
+    }
+
+    // GOOD: the counter is serialized back into the account data
+    pub fn increment_with_write_back(ctx: Context<RawCounter>) -> Result<()> {
+        let mut data = ctx.accounts.counter.try_borrow_mut_data()?;
+        let mut counter = Counter::try_from_slice(&data)?;
+        counter.count += 1; // [safe_persisted_write]
+        counter.serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    // GOOD: a helper given the account writes the counter back
+    pub fn increment_through_save(ctx: Context<RawCounter>) -> Result<()> {
+        let mut counter = Counter::try_from_slice(&ctx.accounts.counter.try_borrow_data()?)?;
+        counter.count += 1; // [safe_persisted_write]
+        save(&counter, &ctx.accounts.counter)?;
+        Ok(())
+    }
+
+    // GOOD: the counter writes itself back into the account
+    pub fn increment_through_persist(ctx: Context<RawCounter>) -> Result<()> {
+        let mut counter = Counter::try_from_slice(&ctx.accounts.counter.try_borrow_data()?)?;
+        counter.count += 1; // [safe_persisted_write]
+        counter.persist(&ctx.accounts.counter)?;
+        Ok(())
+    }
+
+    // GOOD: a helper of the program serializes the counter into the borrowed data
+    pub fn increment_through_store(ctx: Context<RawCounter>) -> Result<()> {
+        let mut data = ctx.accounts.counter.try_borrow_mut_data()?;
+        let mut counter = Counter::try_from_slice(&data)?;
+        counter.count += 1; // [safe_persisted_write]
+        store(&mut counter, &mut data)?;
+        Ok(())
+    }
+
+    // GOOD: the counter is only read
+    pub fn read_counter(ctx: Context<RawCounter>) -> Result<()> {
+        let counter = Counter::try_from_slice(&ctx.accounts.counter.try_borrow_data()?)?;
+        msg!("count: {}", counter.count);
+        Ok(())
+    }
+
+    // GOOD: the modified counter is returned to the caller, which persists it
+    pub fn reset_through_helper(ctx: Context<RawCounter>) -> Result<()> {
+        let counter = load_reset_counter(&ctx.accounts.counter)?;
+        counter.serialize(&mut &mut ctx.accounts.counter.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+}
+
+pub fn load_reset_counter(account: &UncheckedAccount) -> Result<Counter> {
+    let mut counter = Counter::try_from_slice(&account.try_borrow_data()?)?;
+    counter.count = 0; // [safe_persisted_write]
+    Ok(counter)
+}
+
+pub fn save(counter: &Counter, account: &UncheckedAccount) -> Result<()> {
+    counter.serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+pub fn store(counter: &mut Counter, data: &mut [u8]) -> Result<()> {
+    counter.serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct Counter {
+    pub count: u64,
+    pub authority: Pubkey,
+}
+
+impl Counter {
+    pub fn persist(&self, account: &UncheckedAccount) -> Result<()> {
+        self.serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct RawCounter<'info> {
+    /// CHECK: manually deserialized counter account
+    #[account(mut)]
+    pub counter: UncheckedAccount<'info>,
+}
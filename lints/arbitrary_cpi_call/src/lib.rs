@@ -7,17 +7,21 @@ extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::{
-    diag_items::DiagnoticItem, models::NestedArgument, utils::get_hir_body_from_local_def_id,
+    cfg::{dominates, reachable_from},
+    diag_items::DiagnoticItem,
+    diagnostics::{span_lint_hir, span_lint_hir_and_note},
+    models::NestedArgument,
+    registry::is_lint_enabled,
+    utils::{get_hir_body_from_local_def_id, negated_condition},
 };
 
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint;
 use rustc_hir::{
-    Body as HirBody, FnDecl,
+    Body as HirBody, FnDecl, HirId,
     def_id::{DefId, LocalDefId},
     intravisit::FnKind,
 };
-use rustc_lint::{LateContext, LateLintPass};
+use rustc_lint::{LateContext, LateLintPass, Level};
 use rustc_middle::{
     mir::{BasicBlock, HasLocalDecls, Local, Operand, TerminatorKind},
     ty::{self as rustc_ty},
@@ -30,10 +34,12 @@ use std::collections::{HashMap, HashSet};
 mod models;
 mod utils;
 
-use models::{Cmp, CpiCallsInfo, CpiContextsInfo, IfThen};
+use models::{Cmp, CpiCallsInfo, CpiContextsInfo, IfThen, InstructionCreation, ProgramIdUpdate};
 use utils::*;
 
-use anchor_lints_utils::diag_items::{is_anchor_cpi_context, is_cpi_invoke_fn};
+use anchor_lints_utils::diag_items::{
+    is_anchor_cpi_context, is_anchor_cpi_context_with_remaining_accounts_fn, is_cpi_invoke_fn,
+};
 use anchor_lints_utils::mir_analyzer::MirAnalyzer;
 use anchor_lints_utils::models::Origin;
 
@@ -67,6 +73,19 @@ thread_local! {
 
     // Track functions that have been analyzed (to skip duplicate standalone analysis)
     static ANALYZED_FUNCTIONS: RefCell<HashSet<LocalDefId>> = RefCell::new(HashSet::new());
+
+    // Same-crate functions called from another body, collected once for the whole crate
+    static CALLED_FUNCTIONS: RefCell<Option<HashSet<LocalDefId>>> = const { RefCell::new(None) };
+}
+
+/// Check if `def_id` is called from another body of the crate, wherever it is declared
+fn is_called_in_crate(cx: &LateContext<'_>, def_id: LocalDefId) -> bool {
+    CALLED_FUNCTIONS.with(|called| {
+        called
+            .borrow_mut()
+            .get_or_insert_with(|| collect_called_local_fns(cx))
+            .contains(&def_id)
+    })
 }
 
 impl<'tcx> LateLintPass<'tcx> for ArbitraryCpiCall {
@@ -79,6 +98,11 @@ impl<'tcx> LateLintPass<'tcx> for ArbitraryCpiCall {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ARBITRARY_CPI_CALL) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -104,9 +128,9 @@ impl<'tcx> LateLintPass<'tcx> for ArbitraryCpiCall {
             body,
             def_id,
             &fn_crate_name,
-            false,
             None,
             &mut account_cmps,
+            None,
         );
     }
 }
@@ -116,10 +140,16 @@ fn analyze_arbitrary_cpi_call<'tcx>(
     body: &HirBody<'tcx>,
     def_id: LocalDefId,
     fn_crate_name: &String,
-    call_from_helper: bool,
     existing_nested_arg_accounts: Option<NestedArgument<'tcx>>,
     existing_account_cmps: &mut Vec<String>,
+    caller_lint_node: Option<HirId>,
 ) -> Vec<String> {
+    let call_from_helper = caller_lint_node.is_some();
+    // Findings follow the lint level of the function they are in, unless a caller allows the lint
+    let lint_node = caller_lint_node
+        .filter(|node| cx.tcx.lint_level_at_node(ARBITRARY_CPI_CALL, *node).level == Level::Allow)
+        .unwrap_or_else(|| cx.tcx.local_def_id_to_hir_id(def_id));
+
     // Mark as being analyzed recursively
     RECURSIVE_ANALYSIS.with(|rec| {
         rec.borrow_mut().insert(def_id);
@@ -131,11 +161,23 @@ fn analyze_arbitrary_cpi_call<'tcx>(
     });
     let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
 
-    // If fn does not take a anchor context, skip to avoid false positives
-    if call_from_helper {
+    // Without an anchor context, only helpers no function of the crate calls are analyzed on their
+    // own. Called helpers are analyzed with the accounts and validations of their callers.
+    let uncalled_helper = !call_from_helper
+        && mir_analyzer.anchor_context_info.is_none()
+        && !is_called_in_crate(cx, def_id);
+
+    // Helpers without a Context fall back to an accounts struct param
+    if call_from_helper || uncalled_helper {
         anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
     }
-    if mir_analyzer.anchor_context_info.is_none() && !call_from_helper {
+    mir_analyzer.debug_dump(ARBITRARY_CPI_CALL);
+
+    // If fn takes neither an anchor context nor account or pubkey params, skip to avoid false positives
+    if mir_analyzer.anchor_context_info.is_none()
+        && !call_from_helper
+        && !(uncalled_helper && takes_account_or_pubkey_params(&mir_analyzer))
+    {
         return vec![];
     }
     if let Some(existing_nested_arg_accounts) = &existing_nested_arg_accounts
@@ -153,7 +195,7 @@ fn analyze_arbitrary_cpi_call<'tcx>(
     // A) CPI calls
     // B) CPI contexts with user controllable program id
     // C) Conditional blocks for program id
-    // Then we check all CPI contexts where a CPI call is reachable from the context
+    // Then we check every CPI call reachable from a CPI context
     // and the program ID is not validated in any conditional blocks
 
     // Store if/else nesting level of each block
@@ -166,11 +208,15 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                 .iter_enumerated()
                 .filter(|(pred_bb, pred_data)| {
                     *pred_bb != bb
-                        && mir_analyzer.dominators.dominates(*pred_bb, bb)
-                        && matches!(
-                            pred_data.terminator().kind,
-                            TerminatorKind::SwitchInt { .. }
-                        )
+                        && dominates(&mir_analyzer.dominators, *pred_bb, bb)
+                        && match &pred_data.terminator().kind {
+                            // The byte tests of a `match` on a `Pubkey` are a single level
+                            TerminatorKind::SwitchInt { discr, .. } => discr
+                                .place()
+                                .and_then(|place| pubkey_byte(&mir_analyzer, &place))
+                                .is_none_or(|(_, index)| index == 0),
+                            _ => false,
+                        }
                 })
                 .count();
             (bb, level)
@@ -182,15 +228,32 @@ fn analyze_arbitrary_cpi_call<'tcx>(
     let mut switches: Vec<IfThen> = Vec::new();
     let mut program_id_cmps: Vec<Cmp> = Vec::new();
 
-    let mut instruction_to_program_id: HashMap<Local, BasicBlock> = HashMap::new();
+    // Accounts validated by nested calls of this function, with the blocks of those calls
+    let mut nested_validations: HashMap<String, Vec<BasicBlock>> = HashMap::new();
+
+    let mut instruction_creations: HashMap<Local, InstructionCreation> = HashMap::new();
+    let mut program_id_updates: HashMap<Local, Vec<ProgramIdUpdate>> = HashMap::new();
 
     for (bb, bbdata) in mir.basic_blocks.iter_enumerated() {
-        for statement in &bbdata.statements {
-            record_instruction_creation(
+        for (statement_index, statement) in bbdata.statements.iter().enumerate() {
+            // `matches!(program_id, A | B)` only holds if every byte matched one of the constants
+            if let Some((pubkey, ret)) =
+                pubkey_last_byte_eq(&mir_analyzer, &bbdata.statements, statement)
+            {
+                program_id_cmps.push(Cmp {
+                    lhs: pubkey,
+                    rhs: pubkey,
+                    ret,
+                    is_eq: true,
+                });
+            }
+            record_instruction_creation(&mir_analyzer, bb, statement, &mut instruction_creations);
+            record_instruction_program_id_update(
                 &mir_analyzer,
                 bb,
+                statement_index,
                 statement,
-                &mut instruction_to_program_id,
+                &mut program_id_updates,
             );
         }
         let terminator_kind = &bbdata.terminator().kind;
@@ -208,6 +271,15 @@ fn analyze_arbitrary_cpi_call<'tcx>(
 
             let crate_name = cx.tcx.crate_name(fn_def_id.krate).to_string();
 
+            record_instruction_builder_call(
+                &mir_analyzer,
+                bb,
+                *fn_def_id,
+                args,
+                destination,
+                &mut instruction_creations,
+            );
+
             if is_cpi_invoke_fn(cx.tcx, *fn_def_id) {
                 if let Some(instruction) = args.first()
                     && let Operand::Copy(place) | Operand::Move(place) = &instruction.node
@@ -221,7 +293,8 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                         bb,
                         &mut cpi_calls,
                         &mut cpi_contexts,
-                        &instruction_to_program_id,
+                        &instruction_creations,
+                        &program_id_updates,
                     );
                 }
             } else if mir_analyzer.takes_cpi_context(args)
@@ -253,6 +326,8 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                         mir_analyzer.origin_of_operand(&program_id.node),
                         Origin::Parameter | Origin::Unknown
                     )
+                    // Only the program can write a config PDA, or an account at a fixed address
+                    && !is_stored_in_pinned_account(&mir_analyzer, local)
                 {
                     cpi_contexts.insert(
                         bb,
@@ -272,11 +347,12 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                     ret,
                     is_eq: true,
                 });
-            } else if let [_receiver, arg] = args.as_ref()
+            } else if let [receiver, arg] = args.as_ref()
                 && let Some(maybe_pubkey) = mir_analyzer.pubkey_operand_to_local(&arg.node)
                 && let Some(name) = cx.tcx.opt_item_name(*fn_def_id)
                 && name.as_str() == "contains"
                 && return_ty.is_bool()
+                && is_pubkey_collection(&mir_analyzer, &receiver.node)
                 && let Some(ret) = destination.as_local()
             {
                 // FIXME: Represent this more accurately than a fake comparison to self
@@ -296,6 +372,40 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                     ret,
                     is_eq: false,
                 });
+            } else if crate_name == *fn_crate_name
+                && return_ty.is_bool()
+                && let Some(maybe_pubkey) = args
+                    .iter()
+                    .find_map(|arg| mir_analyzer.pubkey_operand_to_local(&arg.node))
+                && let Some(ret) = destination.as_local()
+                && is_pubkey_validator_fn(cx, *fn_def_id)
+            {
+                // A validator such as `is_allowed(program_id)` gates like the comparison it wraps
+                program_id_cmps.push(Cmp {
+                    lhs: maybe_pubkey,
+                    rhs: maybe_pubkey,
+                    ret,
+                    is_eq: true,
+                });
+            } else if crate_name != *fn_crate_name
+                && let Some((closure_index, closure_def_id)) = closure_argument(&mir_analyzer, args)
+            {
+                // Validations inside a closure, e.g. `(|| { require!(..); Ok(()) })()?` or
+                // `program_id.map(|id| require_keys_eq!(..))`, hold after the call running it,
+                // like those of a nested function
+                let account_cmps_before: HashSet<String> =
+                    existing_account_cmps.iter().cloned().collect();
+                for local in
+                    closure_compared_locals(&mir_analyzer, args, closure_index, closure_def_id)
+                {
+                    add_account_or_param_from_local(local, &mir_analyzer, existing_account_cmps);
+                }
+                record_nested_validations(
+                    bb,
+                    &account_cmps_before,
+                    existing_account_cmps,
+                    &mut nested_validations,
+                );
             } else if crate_name == *fn_crate_name && !fn_span.from_expansion() {
                 // Skip nested function analysis recursion
                 // If if/else nesting level greater than 'MAX_IF_ELSE_NESTING_LEVEL'
@@ -307,6 +417,15 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                 {
                     continue;
                 }
+                let account_cmps_before: HashSet<String> =
+                    existing_account_cmps.iter().cloned().collect();
+                // validations on a branch not leading to this call do not carry over into it
+                let withheld_account_cmps = withhold_non_dominating_validations(
+                    bb,
+                    existing_account_cmps,
+                    &nested_validations,
+                    &mir_analyzer,
+                );
                 // filter out program id comparisons that are not reachable & unsafe comparisons
                 let filtered_program_id_cmps =
                     filter_program_id_cmps(bb, &program_id_cmps, &switches, &mir_analyzer);
@@ -316,28 +435,31 @@ fn analyze_arbitrary_cpi_call<'tcx>(
                     existing_account_cmps,
                 );
                 let local_def_id = fn_def_id.expect_local();
-                let body_id = match get_hir_body_from_local_def_id(cx, local_def_id) {
-                    Some(body_id) => body_id,
-                    None => {
-                        continue;
+                if let Some(body_id) = get_hir_body_from_local_def_id(cx, local_def_id) {
+                    let body = cx.tcx.hir_body(body_id);
+                    let nested_arg_accounts = mir_analyzer
+                        .get_nested_fn_arguments(args, None)
+                        .or(mir_analyzer.get_nested_fn_arguments_as_params(args));
+                    let nested_program_id_cmps = analyze_arbitrary_cpi_call(
+                        cx,
+                        body,
+                        local_def_id,
+                        fn_crate_name,
+                        nested_arg_accounts.clone(),
+                        existing_account_cmps,
+                        Some(lint_node),
+                    );
+                    if !nested_program_id_cmps.is_empty() {
+                        existing_account_cmps.extend(nested_program_id_cmps);
                     }
-                };
-                let body = cx.tcx.hir_body(body_id);
-                let nested_arg_accounts = mir_analyzer
-                    .get_nested_fn_arguments(args, None)
-                    .or(mir_analyzer.get_nested_fn_arguments_as_params(args));
-                let nested_program_id_cmps = analyze_arbitrary_cpi_call(
-                    cx,
-                    body,
-                    local_def_id,
-                    fn_crate_name,
-                    true,
-                    nested_arg_accounts.clone(),
+                }
+                record_nested_validations(
+                    bb,
+                    &account_cmps_before,
                     existing_account_cmps,
+                    &mut nested_validations,
                 );
-                if !nested_program_id_cmps.is_empty() {
-                    existing_account_cmps.extend(nested_program_id_cmps);
-                }
+                existing_account_cmps.extend(withheld_account_cmps);
             }
         }
         // Find if/else switches which may be the result of a comparison
@@ -352,65 +474,111 @@ fn analyze_arbitrary_cpi_call<'tcx>(
         {
             let then_block = if val == 1 { then } else { els };
             let else_block = if then_block == then { els } else { then };
+            // `if !cond { return err!(..) }` switches on the negation, so the fall-through of the
+            // guard clause is where `cond` holds
+            let (discr, then_block, else_block) = match negated_condition(&bbdata.statements, discr)
+            {
+                Some(cond) => (cond, else_block, then_block),
+                None => (discr, then_block, else_block),
+            };
             switches.push(IfThen {
                 discr,
                 then: then_block,
                 els: else_block,
             });
         }
+        // A `match` on `Pubkey` constants enters the arm of a constant from the test of its last
+        // byte, while the other values take the `otherwise` target towards the fallback arm
+        else if let TerminatorKind::SwitchInt {
+            discr: Operand::Copy(discr),
+            targets,
+        } = terminator_kind
+            && let Some(pubkey) = pubkey_last_byte_local(&mir_analyzer, discr)
+        {
+            // FIXME: Represent this more accurately than a fake comparison to self, switched on
+            // the pubkey itself
+            program_id_cmps.push(Cmp {
+                lhs: pubkey,
+                rhs: pubkey,
+                ret: pubkey,
+                is_eq: true,
+            });
+            for (_, arm) in targets.iter() {
+                switches.push(IfThen {
+                    discr: pubkey,
+                    then: arm,
+                    els: targets.otherwise(),
+                });
+            }
+        }
     }
-    // check if the CPI call is reachable from a CPI context
-    // and the program ID is not validated in conditional blocks
+    // check every CPI call reachable from a CPI context for a program ID that is not validated
+    // in conditional blocks. Each call is checked on its own, a validation between two calls of
+    // the same context only covers the later one
+    let mut reported_cpi_calls = HashSet::new();
     for (bb, cpi_ctx_info) in cpi_contexts.into_iter() {
-        // check if the CPI call is reachable from the CPI context
-        let Some(cpi_call_bb) =
-            cpi_invocation_is_reachable_from_cpi_context(&mir.basic_blocks, bb, &cpi_calls)
-        else {
-            continue;
-        };
+        let reachable_cpi_calls = reachable_from(&mir.basic_blocks, [bb])
+            .into_iter()
+            .filter(|succ| cpi_calls.contains_key(succ));
+        for cpi_call_bb in reachable_cpi_calls {
+            // check if the CPI context variables are the same
+            if !mir_analyzer.check_cpi_context_variables_are_same(
+                &cpi_ctx_info.cpi_ctx_local,
+                &cpi_calls[&cpi_call_bb].local,
+                &mut HashSet::new(),
+            ) {
+                continue;
+            }
 
-        // check if the CPI context variables are the same
-        if !mir_analyzer.check_cpi_context_variables_are_same(
-            &cpi_ctx_info.cpi_ctx_local,
-            &cpi_calls[&cpi_call_bb].local,
-            &mut HashSet::new(),
-        ) {
-            continue;
-        }
+            // The program ID is validated only if a validating block dominates the CPI
+            let program_id_validated = pubkey_checked_in_this_block(
+                cpi_call_bb,
+                cpi_ctx_info.program_id_local,
+                &program_id_cmps,
+                &switches,
+                &mir_analyzer,
+            ) && check_program_id_included_in_conditional_blocks(
+                &cpi_ctx_info.program_id_local,
+                &program_id_cmps,
+                &mir_analyzer,
+            );
 
-        // Check if program ID is validated
-        let check_program_id_validated = pubkey_checked_in_this_block(
-            cpi_call_bb,
-            cpi_ctx_info.program_id_local,
-            &program_id_cmps,
-            &switches,
-            &mir_analyzer,
-        ) || !check_program_id_included_in_conditional_blocks(
-            &cpi_ctx_info.program_id_local,
-            &program_id_cmps,
-            &mir_analyzer,
-        );
+            if program_id_validated {
+                continue;
+            }
 
-        if !check_program_id_validated {
-            continue;
-        }
+            // Check if account was validated in previous blocks (inter-procedural)
+            if is_account_checked_in_previous_blocks(
+                &cpi_ctx_info.program_id_local,
+                cpi_call_bb,
+                existing_account_cmps,
+                &nested_validations,
+                &mir_analyzer,
+            ) {
+                continue;
+            }
 
-        // Check if account was validated in previous blocks (inter-procedural)
-        if is_account_checked_in_previous_blocks(
-            &cpi_ctx_info.program_id_local,
-            existing_account_cmps,
-            &mir_analyzer,
-        ) {
-            continue;
+            // A CPI reachable from several contexts is only reported once
+            if !reported_cpi_calls.insert(cpi_call_bb) {
+                continue;
+            }
+            span_lint_hir_and_note(
+                cx,
+                ARBITRARY_CPI_CALL,
+                lint_node,
+                cpi_calls[&cpi_call_bb].span,
+                "arbitrary CPI detected — program id appears user-controlled",
+                Some(program_id_origin_span(
+                    &mir_analyzer,
+                    cpi_ctx_info.program_id_local,
+                )),
+                "program id originates here",
+            );
         }
-
-        span_lint(
-            cx,
-            ARBITRARY_CPI_CALL,
-            cpi_calls[&cpi_call_bb].span,
-            "arbitrary CPI detected — program id appears user-controlled",
-        );
     }
+
+    check_remaining_account_programs(&mir_analyzer, fn_crate_name, lint_node);
+
     // Unmark as being analyzed recursively
     RECURSIVE_ANALYSIS.with(|rec| {
         rec.borrow_mut().remove(&def_id);
@@ -429,6 +597,80 @@ fn analyze_arbitrary_cpi_call<'tcx>(
     existing_account_cmps.clone()
 }
 
+/// Report unconstrained accounts forwarded in the remaining accounts of a CPI to a program that
+/// may invoke them, e.g. Token-2022 invoking a transfer hook program passed this way
+fn check_remaining_account_programs<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    fn_crate_name: &String,
+    lint_node: HirId,
+) {
+    let cx = mir_analyzer.cx;
+    let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+        return;
+    };
+    let forwards = collect_remaining_account_forwards(mir_analyzer);
+    if forwards.is_empty() {
+        return;
+    }
+
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind() else {
+            continue;
+        };
+
+        // Only the CPI itself, not the `CpiContext` builder methods or same-crate helpers
+        let return_ty = cx
+            .tcx
+            .fn_sig(*fn_def_id)
+            .skip_binder()
+            .skip_binder()
+            .output();
+        if !mir_analyzer.takes_cpi_context(args)
+            || is_anchor_cpi_context(cx.tcx, return_ty)
+            || is_anchor_cpi_context_with_remaining_accounts_fn(cx.tcx, *fn_def_id)
+            || cx.tcx.crate_name(fn_def_id.krate).as_str() == fn_crate_name
+            || is_builtin_program_cpi(cx, *fn_def_id)
+        {
+            continue;
+        }
+        let Some(cpi_ctx_local) = get_local_from_operand(args.first()) else {
+            continue;
+        };
+
+        let sources = mir_analyzer.collect_source_locals(cpi_ctx_local);
+        let mut reported = HashSet::new();
+        for forward in forwards
+            .iter()
+            .filter(|forward| sources.contains(&forward.cpi_ctx_local))
+        {
+            if !is_unconstrained_account_info(cx, anchor_context, &forward.account_name)
+                || !reported.insert(forward.account_name.as_str())
+            {
+                continue;
+            }
+            span_lint_hir(
+                cx,
+                ARBITRARY_CPI_CALL,
+                lint_node,
+                *fn_span,
+                format!(
+                    "arbitrary CPI detected — `{}` is forwarded unconstrained in the remaining accounts of this CPI, and the callee may invoke it as a program",
+                    forward.account_name
+                ),
+            );
+        }
+    }
+}
+
 /// For a given pubkey [`Local`], identify the [`BasicBlock`]s where its value is known/checked
 fn known_pubkey_basic_blocks<'tcx>(
     pk: Local,
@@ -460,7 +702,8 @@ fn known_pubkey_basic_blocks<'tcx>(
         })
         .collect()
 }
-/// Check if `pk` has been checked to be a known value at the point this basic block is reached
+/// Check if `pk` has been checked to be a known value at the point this basic block is reached,
+/// i.e. a block where its value is known dominates `block`
 fn pubkey_checked_in_this_block<'tcx>(
     block: BasicBlock,
     pk: Local,
@@ -471,7 +714,7 @@ fn pubkey_checked_in_this_block<'tcx>(
     let known_bbs = known_pubkey_basic_blocks(pk, cmps, switches, mir_analyzer);
     known_bbs
         .iter()
-        .any(|bb| !mir_analyzer.dominators.dominates(*bb, block))
+        .any(|bb| dominates(&mir_analyzer.dominators, *bb, block))
 }
 
 fn is_anchor_spl_token_transfer<'tcx>(cx: &LateContext<'tcx>, def_id: DefId) -> bool {
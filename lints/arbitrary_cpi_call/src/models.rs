@@ -1,4 +1,4 @@
-use rustc_middle::mir::{BasicBlock, Local};
+use rustc_middle::mir::{BasicBlock, Local, Operand};
 use rustc_span::Span;
 
 #[derive(Debug)]
@@ -7,12 +7,38 @@ pub struct CpiCallsInfo {
     pub local: Local,
 }
 
+/// An accounts struct field passed to `CpiContext::with_remaining_accounts`
+#[derive(Debug)]
+pub struct RemainingAccountForward {
+    /// The `CpiContext` returned by `with_remaining_accounts`
+    pub cpi_ctx_local: Local,
+    pub account_name: String,
+}
+
 #[derive(Debug)]
 pub struct CpiContextsInfo {
     pub cpi_ctx_local: Local,
     pub program_id_local: Local,
 }
 
+/// The block an invoked instruction is built in
+#[derive(Debug, Clone, Copy)]
+pub struct InstructionCreation {
+    pub bb: BasicBlock,
+    /// The program id passed to a builder such as `spl_token::instruction::transfer`
+    pub builder_program_id: Option<Local>,
+    /// The program id set as the `program_id` field of an `Instruction { .. }` literal
+    pub struct_program_id: Option<Local>,
+}
+
+/// A write to the `program_id` field of an already constructed instruction
+#[derive(Debug, Clone)]
+pub struct ProgramIdUpdate<'tcx> {
+    pub bb: BasicBlock,
+    pub statement_index: usize,
+    pub value: Operand<'tcx>,
+}
+
 /// A switch on `discr`, where a truthy value leads to `then`
 #[derive(Debug, Clone, Copy)]
 pub struct IfThen {
@@ -28,3 +54,12 @@ pub struct Cmp {
     pub ret: Local,
     pub is_eq: bool,
 }
+
+/// The value of a closure a compared `Pubkey` is read from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClosureInput {
+    /// The capture at this index of the closure
+    Capture(usize),
+    /// An argument the closure is called with
+    Param,
+}
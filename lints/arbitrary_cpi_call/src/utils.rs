@@ -1,21 +1,36 @@
 use anchor_lints_utils::{
-    diag_items::is_solana_instruction_type,
-    mir_analyzer::MirAnalyzer,
+    cfg::dominates,
+    diag_items::{
+        is_account_info_type, is_anchor_account_type,
+        is_anchor_cpi_context_with_remaining_accounts_fn, is_anchor_program_type,
+        is_solana_instruction_type, is_solana_pubkey_type, is_spl_token_instruction_builder_fn,
+    },
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
     models::{NestedArgument, NestedArgumentType, ParamInfo},
+    utils::{
+        account_constraints::parse_account_constraints, get_hir_body_from_local_def_id,
+        is_unchecked_account_type,
+    },
 };
+use clippy_utils::{fn_has_unsatisfiable_preds, ty::is_type_diagnostic_item};
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_lint::LateContext;
 use rustc_middle::{
     mir::{
-        BasicBlock, BasicBlocks, HasLocalDecls, Local, Operand, Place, Rvalue, Statement,
-        StatementKind,
+        AggregateKind, BasicBlock, BinOp, Body as MirBody, HasLocalDecls, Local, Operand, Place,
+        ProjectionElem, Rvalue, Statement, StatementKind, TerminatorKind,
     },
-    ty::{self as rustc_ty},
+    ty::{self as rustc_ty, FieldDef, TyKind},
 };
-use rustc_span::{Span, source_map::Spanned};
+use rustc_span::{Span, source_map::Spanned, sym};
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    models::{Cmp, CpiCallsInfo, CpiContextsInfo, IfThen},
+    models::{
+        ClosureInput, Cmp, CpiCallsInfo, CpiContextsInfo, IfThen, InstructionCreation,
+        ProgramIdUpdate, RemainingAccountForward,
+    },
     pubkey_checked_in_this_block,
 };
 use anchor_lints_utils::models::{AssignmentKind, Origin};
@@ -27,6 +42,170 @@ pub fn get_local_from_operand<'tcx>(operand: Option<&Spanned<Operand<'tcx>>>) ->
     })
 }
 
+/// Check if a same-crate function validates a `Pubkey`: it compares one against a constant, e.g.
+/// `fn is_allowed(program_id: Pubkey) -> bool { program_id == system_program::ID }`, or looks
+/// one up in an allowlist with `contains`
+pub fn is_pubkey_validator_fn(cx: &LateContext<'_>, fn_def_id: DefId) -> bool {
+    let Some(local_def_id) = fn_def_id.as_local() else {
+        return false;
+    };
+    let Some(body_id) = get_hir_body_from_local_def_id(cx, local_def_id) else {
+        return false;
+    };
+    let mir_analyzer = MirAnalyzer::new(cx, cx.tcx.hir_body(body_id), local_def_id);
+    let is_constant = |local: Local| {
+        mir_analyzer.origin_of_operand(&Operand::Copy(Place::from(local))) == Origin::Constant
+    };
+
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            return false;
+        };
+        let rustc_ty::FnDef(callee, _) = func.ty().kind() else {
+            return false;
+        };
+        if cx.tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *callee)
+            || cx.tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *callee)
+        {
+            mir_analyzer
+                .args_as_pubkey_locals(args)
+                .is_some_and(|(lhs, rhs)| is_constant(lhs) || is_constant(rhs))
+        } else {
+            cx.tcx
+                .opt_item_name(*callee)
+                .is_some_and(|name| name.as_str() == "contains")
+                && matches!(args.as_ref(), [_receiver, arg]
+                    if mir_analyzer.pubkey_operand_to_local(&arg.node).is_some())
+        }
+    })
+}
+
+/// Check if the receiver of a `contains` call is a collection of `Pubkey`s: an array, a slice, or
+/// a collection such as `Vec`, `HashSet` or `BTreeSet` whose first type parameter is `Pubkey`
+pub fn is_pubkey_collection<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    receiver: &Operand<'tcx>,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    let element_ty = match receiver.ty(mir_analyzer.mir, tcx).peel_refs().kind() {
+        TyKind::Array(element_ty, _) | TyKind::Slice(element_ty) => Some(*element_ty),
+        TyKind::Adt(_, generics) => generics.types().next(),
+        _ => None,
+    };
+    element_ty.is_some_and(|element_ty| is_solana_pubkey_type(tcx, element_ty))
+}
+
+/// Number of bytes of a `Pubkey`
+const PUBKEY_BYTES: u64 = 32;
+
+/// If `place` is a byte of a `Pubkey`, e.g. `(_1.0: [u8; 32])[31 of 32]`, return the `Pubkey`
+/// local and the index of the byte
+pub fn pubkey_byte<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    place: &Place<'tcx>,
+) -> Option<(Local, u64)> {
+    let tcx = mir_analyzer.cx.tcx;
+    let (
+        bytes,
+        ProjectionElem::ConstantIndex {
+            offset,
+            min_length: PUBKEY_BYTES,
+            from_end: false,
+        },
+    ) = place.iter_projections().last()?
+    else {
+        return None;
+    };
+    let (pubkey, ProjectionElem::Field(..)) = bytes.iter_projections().last()? else {
+        return None;
+    };
+    is_solana_pubkey_type(tcx, pubkey.ty(mir_analyzer.mir, tcx).ty).then_some((place.local, offset))
+}
+
+/// If `place` is the last byte of a `Pubkey`, return the `Pubkey` local. `match` and `matches!`
+/// on `Pubkey` constants test the bytes one by one, so a test of the last byte is only reached
+/// once every other byte matched one of the constants.
+pub fn pubkey_last_byte_local<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    place: &Place<'tcx>,
+) -> Option<Local> {
+    pubkey_byte(mir_analyzer, place)
+        .and_then(|(pubkey, index)| (index + 1 == PUBKEY_BYTES).then_some(pubkey))
+}
+
+/// If `statement` is the test of the last byte `matches!` on `Pubkey` constants ends with, e.g.
+/// `_2 = Eq(copy _3, const 3_u8)` after `_3 = copy (_1.0: [u8; 32])[31 of 32]`, return the
+/// `Pubkey` local and the result of `matches!`
+pub fn pubkey_last_byte_eq<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    statements: &[Statement<'tcx>],
+    statement: &Statement<'tcx>,
+) -> Option<(Local, Local)> {
+    let StatementKind::Assign(box (ret, Rvalue::BinaryOp(BinOp::Eq, box (lhs, rhs)))) =
+        &statement.kind
+    else {
+        return None;
+    };
+    let ((Operand::Copy(byte) | Operand::Move(byte), Operand::Constant(_))
+    | (Operand::Constant(_), Operand::Copy(byte) | Operand::Move(byte))) = (lhs, rhs)
+    else {
+        return None;
+    };
+    let pubkey = pubkey_last_byte_local(mir_analyzer, byte).or_else(|| {
+        let byte = byte.as_local()?;
+        statements.iter().find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (
+                dest,
+                Rvalue::Use(Operand::Copy(place) | Operand::Move(place)),
+            )) if dest.as_local() == Some(byte) => pubkey_last_byte_local(mir_analyzer, place),
+            _ => None,
+        })
+    })?;
+    Some((pubkey, ret.as_local()?))
+}
+
+/// Collect the same-crate functions called from any body of the crate, other than by themselves.
+/// Helpers missing from it are only reachable from outside and are analyzed on their own.
+pub fn collect_called_local_fns(cx: &LateContext<'_>) -> HashSet<LocalDefId> {
+    let tcx = cx.tcx;
+    let mut called = HashSet::new();
+    for caller in tcx.hir_body_owners() {
+        if !tcx.def_kind(caller).is_fn_like()
+            || !tcx.is_mir_available(caller)
+            || fn_has_unsatisfiable_preds(cx, caller.to_def_id())
+        {
+            continue;
+        }
+        for bbdata in tcx.optimized_mir(caller).basic_blocks.iter() {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func_const),
+                ..
+            } = &bbdata.terminator().kind
+                && let rustc_ty::FnDef(callee, _) = func_const.ty().kind()
+                && let Some(callee) = callee.as_local()
+                && callee != caller
+            {
+                called.insert(callee);
+            }
+        }
+    }
+    called
+}
+
+// Free helpers taking individual accounts or a program id can perform a CPI on their own
+pub fn takes_account_or_pubkey_params(mir_analyzer: &MirAnalyzer) -> bool {
+    !mir_analyzer.param_info.is_empty()
+        || mir_analyzer
+            .mir
+            .args_iter()
+            .any(|arg| mir_analyzer.is_pubkey_type(arg))
+}
+
 pub fn check_program_id_included_in_conditional_blocks<'tcx>(
     cpi_ctx_local: &Local,
     cmps: &[Cmp],
@@ -50,39 +229,11 @@ pub fn check_program_id_included_in_conditional_blocks<'tcx>(
     })
 }
 
-pub fn cpi_invocation_is_reachable_from_cpi_context(
-    graph: &BasicBlocks,
-    from: BasicBlock,
-    to: &HashMap<BasicBlock, CpiCallsInfo>,
-) -> Option<BasicBlock> {
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-
-    visited.insert(from);
-    queue.push_back(from);
-
-    while let Some(u) = queue.pop_front() {
-        if let Some(terminator) = &graph[u].terminator {
-            for succ in terminator.successors() {
-                if visited.contains(&succ) {
-                    continue;
-                }
-                if to.contains_key(&succ) {
-                    return Some(succ);
-                }
-                visited.insert(succ);
-                queue.push_back(succ);
-            }
-        }
-    }
-    None
-}
-
 pub fn record_instruction_creation<'tcx>(
     mir_analyzer: &MirAnalyzer<'_, 'tcx>,
     bb: BasicBlock,
     statement: &Statement<'tcx>,
-    instruction_to_program_id: &mut HashMap<Local, BasicBlock>,
+    instruction_creations: &mut HashMap<Local, InstructionCreation>,
 ) {
     if let StatementKind::Assign(box (place, rvalue)) = &statement.kind
         && let Some(dest_local) = place.as_local()
@@ -94,10 +245,127 @@ pub fn record_instruction_creation<'tcx>(
         && let Some(program_id_local) = place.as_local()
         && mir_analyzer.is_pubkey_type(program_id_local)
     {
-        instruction_to_program_id.insert(dest_local, bb);
+        instruction_creations.insert(
+            dest_local,
+            InstructionCreation {
+                bb,
+                builder_program_id: None,
+                struct_program_id: Some(program_id_local),
+            },
+        );
+    }
+}
+
+/// Record an instruction returned by an SPL Token instruction builder, e.g.
+/// `spl_token::instruction::transfer(token_program_id, ..)`, along with the program id it is built for
+pub fn record_instruction_builder_call<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    bb: BasicBlock,
+    fn_def_id: DefId,
+    args: &[Spanned<Operand<'tcx>>],
+    destination: &Place<'tcx>,
+    instruction_creations: &mut HashMap<Local, InstructionCreation>,
+) {
+    let tcx = mir_analyzer.cx.tcx;
+    if !is_spl_token_instruction_builder_fn(tcx, fn_def_id) {
+        return;
+    }
+    // A program id given as a constant, e.g. `&spl_token::ID`, is not user-controlled
+    if let Some(program_id_ref) = get_local_from_operand(args.first())
+        && let Some(dest_local) = destination.as_local()
+        && let Some(dest_decl) = mir_analyzer.mir.local_decls().get(dest_local)
+        && returns_instruction(mir_analyzer.cx, dest_decl.ty)
+    {
+        // The builders take the program id by reference
+        let program_id_local = match mir_analyzer.assignment_map.get(&program_id_ref) {
+            Some(AssignmentKind::RefTo(place)) => place.as_local().unwrap_or(program_id_ref),
+            _ => program_id_ref,
+        };
+        if !mir_analyzer.is_pubkey_type(program_id_local) {
+            return;
+        }
+        // The key of a `Program<'info, Token>` account is checked against the program id by Anchor
+        let is_anchor_program_key = mir_analyzer
+            .collect_source_locals(program_id_local)
+            .iter()
+            .any(|local| {
+                mir_analyzer
+                    .mir
+                    .local_decls()
+                    .get(*local)
+                    .is_some_and(|decl| is_anchor_program_type(tcx, decl.ty))
+            });
+        if is_anchor_program_key {
+            return;
+        }
+        instruction_creations.insert(
+            dest_local,
+            InstructionCreation {
+                bb,
+                builder_program_id: Some(program_id_local),
+                struct_program_id: None,
+            },
+        );
+    }
+}
+
+/// Check if a type is an `Instruction`, or a `Result` holding one
+fn returns_instruction<'tcx>(cx: &LateContext<'tcx>, ty: rustc_ty::Ty<'tcx>) -> bool {
+    if is_instruction_type(&cx.tcx, ty) {
+        return true;
+    }
+    is_type_diagnostic_item(cx, ty, sym::Result)
+        && matches!(ty.kind(), TyKind::Adt(_, generics)
+            if generics.types().next().is_some_and(|ok_ty| is_instruction_type(&cx.tcx, ok_ty)))
+}
+
+/// Record a write to the `program_id` field of a constructed instruction, e.g. `ix.program_id = id`
+pub fn record_instruction_program_id_update<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    bb: BasicBlock,
+    statement_index: usize,
+    statement: &Statement<'tcx>,
+    program_id_updates: &mut HashMap<Local, Vec<ProgramIdUpdate<'tcx>>>,
+) {
+    let tcx = mir_analyzer.cx.tcx;
+    if let StatementKind::Assign(box (place, Rvalue::Use(value))) = &statement.kind
+        && let Some((base, ProjectionElem::Field(field_idx, _))) = place.iter_projections().last()
+        && let base_ty = base.ty(mir_analyzer.mir, tcx).ty
+        && is_instruction_type(&tcx, base_ty)
+        && let TyKind::Adt(adt_def, _) = base_ty.kind()
+        && adt_def.non_enum_variant().fields[field_idx].name.as_str() == "program_id"
+    {
+        program_id_updates
+            .entry(place.local)
+            .or_default()
+            .push(ProgramIdUpdate {
+                bb,
+                statement_index,
+                value: value.clone(),
+            });
     }
 }
 
+/// Find the last `program_id` write executed on every path to `invoke_bb`
+fn final_program_id_update<'a, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    updates: &'a [ProgramIdUpdate<'tcx>],
+    invoke_bb: BasicBlock,
+) -> Option<&'a ProgramIdUpdate<'tcx>> {
+    let dominators = &mir_analyzer.dominators;
+    updates
+        .iter()
+        .filter(|update| dominates(dominators, update.bb, invoke_bb))
+        .reduce(|latest, update| {
+            let is_later = if update.bb == latest.bb {
+                update.statement_index > latest.statement_index
+            } else {
+                dominates(dominators, latest.bb, update.bb)
+            };
+            if is_later { update } else { latest }
+        })
+}
+
 fn is_instruction_type<'tcx>(tcx: &rustc_ty::TyCtxt<'tcx>, ty: rustc_ty::Ty<'tcx>) -> bool {
     is_solana_instruction_type(*tcx, ty)
 }
@@ -109,7 +377,8 @@ pub fn track_instruction_call<'tcx>(
     bb: BasicBlock,
     cpi_calls: &mut HashMap<BasicBlock, CpiCallsInfo>,
     cpi_contexts: &mut HashMap<BasicBlock, CpiContextsInfo>,
-    instruction_to_program_id: &HashMap<Local, BasicBlock>,
+    instruction_creations: &HashMap<Local, InstructionCreation>,
+    program_id_updates: &HashMap<Local, Vec<ProgramIdUpdate<'tcx>>>,
 ) {
     let mir = mir_analyzer.mir;
     let decl_ty = match mir
@@ -125,49 +394,43 @@ pub fn track_instruction_call<'tcx>(
         return;
     }
 
-    let mut program_id_local = None;
-    let mut program_id_bb = None;
-
-    if let Some(&pid) = instruction_to_program_id.get(&instruction_local) {
-        program_id_local = Some(instruction_local);
-        program_id_bb = Some(pid);
-    } else {
-        let mut to_check = vec![instruction_local];
-        let mut visited = HashSet::new();
-
-        while let Some(current) = to_check.pop() {
-            if !visited.insert(current) {
-                continue;
-            }
-
-            if let Some(&pid) = instruction_to_program_id.get(&current) {
-                program_id_local = Some(instruction_local);
-                program_id_bb = Some(pid);
-                break;
-            }
-
-            for (source_key, destinations) in &mir_analyzer.transitive_assignment_reverse_map {
-                if destinations.contains(&current) {
-                    to_check.push(*source_key);
-                } else if source_key == &current {
-                    to_check.extend(destinations);
-                }
-            }
+    let Some(instruction_root) = find_instruction_root(mir_analyzer, instruction_local, |local| {
+        instruction_creations.contains_key(&local) || program_id_updates.contains_key(&local)
+    }) else {
+        return;
+    };
 
-            if let Some(AssignmentKind::FromPlace(src_place)) =
-                mir_analyzer.assignment_map.get(&current)
-                && let Some(src_local) = src_place.as_local()
-            {
-                to_check.push(src_local);
-            }
+    // A `program_id` written after construction replaces the one the instruction was built with
+    let final_update = program_id_updates
+        .get(&instruction_root)
+        .and_then(|updates| final_program_id_update(mir_analyzer, updates, bb));
+    let (origin, pid_bb, pid_local) = if let Some(update) = final_update {
+        let pid_local = update
+            .value
+            .place()
+            .and_then(|place| place.as_local())
+            .unwrap_or(instruction_local);
+        (
+            mir_analyzer.origin_of_operand(&update.value),
+            update.bb,
+            pid_local,
+        )
+    } else if let Some(creation) = instruction_creations.get(&instruction_root) {
+        if let Some(program_id_local) = creation.builder_program_id {
+            let origin =
+                mir_analyzer.origin_of_operand(&Operand::Copy(Place::from(program_id_local)));
+            (origin, creation.bb, program_id_local)
+        } else {
+            let origin =
+                mir_analyzer.origin_of_operand(&Operand::Copy(Place::from(instruction_local)));
+            // Validations compare the program id itself, not the instruction built from it
+            let pid_local = creation.struct_program_id.unwrap_or(instruction_local);
+            (origin, creation.bb, pid_local)
         }
-    }
-
-    let (Some(pid_local), Some(pid_bb)) = (program_id_local, program_id_bb) else {
+    } else {
         return;
     };
 
-    let origin = mir_analyzer.origin_of_operand(&Operand::Copy(Place::from(pid_local)));
     if matches!(origin, Origin::Parameter | Origin::Unknown) {
         cpi_calls.insert(
             bb,
@@ -187,6 +450,47 @@ pub fn track_instruction_call<'tcx>(
     }
 }
 
+/// Follow the assignments of an invoked instruction back to the local it was built in
+fn find_instruction_root(
+    mir_analyzer: &MirAnalyzer,
+    instruction_local: Local,
+    is_root: impl Fn(Local) -> bool,
+) -> Option<Local> {
+    let mut to_check = vec![instruction_local];
+    let mut visited = HashSet::new();
+
+    while let Some(current) = to_check.pop() {
+        if !visited.insert(current) {
+            continue;
+        }
+
+        if is_root(current) {
+            return Some(current);
+        }
+
+        for (source_key, destinations) in &mir_analyzer.transitive_assignment_reverse_map {
+            if destinations.contains(&current) {
+                to_check.push(*source_key);
+            } else if source_key == &current {
+                to_check.extend(destinations);
+            }
+        }
+
+        if let Some(AssignmentKind::FromPlace(src_place)) =
+            mir_analyzer.assignment_map.get(&current)
+            && let Some(src_local) = src_place.as_local()
+        {
+            to_check.push(src_local);
+        }
+    }
+
+    // Instructions returned by a builder are unwrapped from a `Result`, e.g. with `?`
+    mir_analyzer
+        .collect_source_locals(instruction_local)
+        .into_iter()
+        .find(|local| is_root(*local))
+}
+
 pub fn map_nested_arg_accounts_to_account_cmps(
     nested_arg_accounts: &NestedArgument,
     param_info: &[ParamInfo],
@@ -285,19 +589,78 @@ pub fn add_program_id_to_existing_account_cmps<'tcx>(
 
 pub fn is_account_checked_in_previous_blocks<'tcx>(
     program_id: &Local,
+    cpi_call_bb: BasicBlock,
     existing_account_cmps: &[String],
+    nested_validations: &HashMap<String, Vec<BasicBlock>>,
     mir_analyzer: &MirAnalyzer<'_, 'tcx>,
 ) -> bool {
+    let is_checked = |account_name: &String| {
+        existing_account_cmps.contains(account_name)
+            && validation_dominates(account_name, cpi_call_bb, nested_validations, mir_analyzer)
+    };
     if let Some(account) = mir_analyzer.is_from_cpi_context(*program_id, None)
-        && existing_account_cmps.contains(&account.account_name)
+        && is_checked(&account.account_name)
     {
         return true;
     } else if let Some(param) = mir_analyzer.check_local_is_param(*program_id) {
-        return existing_account_cmps.contains(&param.param_name);
+        return is_checked(&param.param_name);
     }
     false
 }
 
+/// Accounts validated before entering the function hold everywhere, accounts validated by a
+/// nested call in this function only hold in blocks dominated by one of those calls
+fn validation_dominates(
+    account_name: &String,
+    bb: BasicBlock,
+    nested_validations: &HashMap<String, Vec<BasicBlock>>,
+    mir_analyzer: &MirAnalyzer,
+) -> bool {
+    nested_validations
+        .get(account_name)
+        .is_none_or(|validation_bbs| {
+            validation_bbs
+                .iter()
+                .any(|validation_bb| dominates(&mir_analyzer.dominators, *validation_bb, bb))
+        })
+}
+
+/// Record the accounts a nested call at `bb` added to the validated accounts
+pub fn record_nested_validations(
+    bb: BasicBlock,
+    account_cmps_before: &HashSet<String>,
+    existing_account_cmps: &[String],
+    nested_validations: &mut HashMap<String, Vec<BasicBlock>>,
+) {
+    for account_name in existing_account_cmps {
+        if !account_cmps_before.contains(account_name) {
+            let validation_bbs = nested_validations.entry(account_name.clone()).or_default();
+            if !validation_bbs.contains(&bb) {
+                validation_bbs.push(bb);
+            }
+        }
+    }
+}
+
+/// Remove the accounts whose validation does not dominate the nested call at `bb`, returning them
+/// so they can be restored once the call is analyzed
+pub fn withhold_non_dominating_validations(
+    bb: BasicBlock,
+    existing_account_cmps: &mut Vec<String>,
+    nested_validations: &HashMap<String, Vec<BasicBlock>>,
+    mir_analyzer: &MirAnalyzer,
+) -> Vec<String> {
+    let mut withheld = Vec::new();
+    existing_account_cmps.retain(|account_name| {
+        let dominates = validation_dominates(account_name, bb, nested_validations, mir_analyzer);
+        if !dominates {
+            withheld.push(account_name.clone());
+        }
+        dominates
+    });
+    withheld
+}
+
 pub fn filter_program_id_cmps<'tcx>(
     bb: BasicBlock,
     program_id_cmps: &[Cmp],
@@ -306,13 +669,334 @@ pub fn filter_program_id_cmps<'tcx>(
 ) -> Vec<Cmp> {
     let mut filtered_program_id_cmps = Vec::new();
     for cmp in program_id_cmps {
-        let is_lhs_reachable =
+        // Only comparisons whose validation dominates the nested call carry over into it
+        let is_lhs_checked =
             pubkey_checked_in_this_block(bb, cmp.lhs, program_id_cmps, switches, mir_analyzer);
-        let is_rhs_reachable =
+        let is_rhs_checked =
             pubkey_checked_in_this_block(bb, cmp.rhs, program_id_cmps, switches, mir_analyzer);
-        if !is_lhs_reachable || !is_rhs_reachable {
+        if is_lhs_checked || is_rhs_checked {
             filtered_program_id_cmps.push(*cmp); // Dereference since Cmp is Copy
         }
     }
     filtered_program_id_cmps
 }
+
+/// Check if a CPI helper targets a program that never invokes the accounts it is passed, i.e. the
+/// System program or the SPL Token program
+pub fn is_builtin_program_cpi(cx: &LateContext<'_>, fn_def_id: DefId) -> bool {
+    let crate_name = cx.tcx.crate_name(fn_def_id.krate);
+    let module = cx.tcx.opt_item_name(cx.tcx.parent(fn_def_id));
+    matches!(
+        (
+            crate_name.as_str(),
+            module.as_ref().map(|module| module.as_str())
+        ),
+        ("anchor_lang", Some("system_program")) | ("anchor_spl", Some("token"))
+    )
+}
+
+/// Collect the accounts struct fields passed to `CpiContext::with_remaining_accounts`
+pub fn collect_remaining_account_forwards(
+    mir_analyzer: &MirAnalyzer,
+) -> Vec<RemainingAccountForward> {
+    let mut forwards = Vec::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && is_anchor_cpi_context_with_remaining_accounts_fn(mir_analyzer.cx.tcx, *fn_def_id)
+            && let Some(cpi_ctx_local) = destination.as_local()
+            && let Some(accounts) = args.get(1)
+        {
+            forwards.extend(
+                mir_analyzer
+                    .collect_accounts_from_account_infos_arg(accounts, true)
+                    .into_iter()
+                    .map(|account| RemainingAccountForward {
+                        cpi_ctx_local,
+                        account_name: account.account_name,
+                    }),
+            );
+        }
+    }
+    forwards
+}
+
+/// Check if a field of the accounts struct is an `UncheckedAccount` or `AccountInfo` that no
+/// `address`, `owner`, `seeds` or `constraint` ties to a known account
+pub fn is_unconstrained_account_info<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    account_name: &str,
+) -> bool {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return false;
+    };
+    if !adt_def.is_struct() {
+        return false;
+    }
+    let field_name = account_name.rsplit('.').next().unwrap_or(account_name);
+    let Some(field) = adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == field_name)
+    else {
+        return false;
+    };
+    let field_ty = cx.tcx.type_of(field.did).instantiate_identity();
+    if !is_unchecked_account_type(cx, field_ty) && !is_account_info_type(cx.tcx, field_ty) {
+        return false;
+    }
+    let constraints = parse_account_constraints(cx, field);
+    constraints.address.is_none()
+        && constraints.owner.is_none()
+        && constraints.seeds.is_none()
+        && constraints.constraints.is_empty()
+}
+
+/// Get the closure passed to a call, e.g. to `Fn::call` for `(|| ..)()`, to `Option::map` or to
+/// `Iterator::for_each`, with the index of the argument it is passed as
+pub fn closure_argument<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    args: &[Spanned<Operand<'tcx>>],
+) -> Option<(usize, DefId)> {
+    args.iter().enumerate().find_map(|(index, arg)| {
+        match arg
+            .node
+            .ty(mir_analyzer.mir, mir_analyzer.cx.tcx)
+            .peel_refs()
+            .kind()
+        {
+            TyKind::Closure(closure_def_id, _) if closure_def_id.is_local() => {
+                Some((index, *closure_def_id))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Collect the locals of the caller holding the `Pubkey`s a closure compares, i.e. its captures
+/// and, for adaptors such as `program_id.map(|id| ..)`, the receiver whose values it is passed
+pub fn closure_compared_locals<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    args: &[Spanned<Operand<'tcx>>],
+    closure_index: usize,
+    closure_def_id: DefId,
+) -> Vec<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let closure_mir = tcx.optimized_mir(closure_def_id);
+
+    let mut inputs = Vec::new();
+    for bbdata in closure_mir.basic_blocks.iter() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args: cmp_args,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+        if !tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
+            && !tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id)
+        {
+            continue;
+        }
+        for cmp_arg in cmp_args.iter() {
+            if let Some(local) = cmp_arg.node.place().and_then(|place| place.as_local())
+                && is_solana_pubkey_type(tcx, closure_mir.local_decls[local].ty)
+                && let Some(input) = closure_input(closure_mir, local)
+                && !inputs.contains(&input)
+            {
+                inputs.push(input);
+            }
+        }
+    }
+
+    inputs
+        .into_iter()
+        .filter_map(|input| match input {
+            ClosureInput::Capture(index) => {
+                closure_capture_local(mir_analyzer, &args[closure_index].node, index)
+            }
+            // `(|id| ..)(program_id)` is called through `Fn::call` with a tuple of arguments,
+            // only adaptors pass the closure the values of their receiver
+            ClosureInput::Param if closure_index > 0 => args.first()?.node.place()?.as_local(),
+            ClosureInput::Param => None,
+        })
+        .collect()
+}
+
+/// Find the value of the closure `local` is read from, following copies, borrows and method calls
+/// such as `key()`
+fn closure_input(closure_mir: &MirBody, local: Local) -> Option<ClosureInput> {
+    let mut current = local;
+    let mut visited = HashSet::new();
+    while visited.insert(current) {
+        // `_1` is the closure itself, the following arguments are the ones it is called with
+        if current.index() > 1 && current.index() <= closure_mir.arg_count {
+            return Some(ClosureInput::Param);
+        }
+        let source = closure_mir.basic_blocks.iter().find_map(|bbdata| {
+            let assigned = bbdata.statements.iter().find_map(|stmt| match &stmt.kind {
+                StatementKind::Assign(box (dest, rvalue)) if dest.as_local() == Some(current) => {
+                    match rvalue {
+                        Rvalue::Use(Operand::Copy(src) | Operand::Move(src))
+                        | Rvalue::Ref(_, _, src)
+                        | Rvalue::CopyForDeref(src) => Some(*src),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            });
+            assigned.or_else(|| match &bbdata.terminator().kind {
+                TerminatorKind::Call {
+                    args, destination, ..
+                } if destination.as_local() == Some(current) => args.first()?.node.place(),
+                _ => None,
+            })
+        })?;
+        // `(*_1).N`, possibly dereferenced, reads the capture `N`
+        if source.local.index() == 1 {
+            return source.projection.iter().find_map(|elem| match elem {
+                ProjectionElem::Field(field, _) => Some(ClosureInput::Capture(field.index())),
+                _ => None,
+            });
+        }
+        current = source.local;
+    }
+    None
+}
+
+/// Get the local of the caller moved or borrowed into the capture `index` of the closure
+/// `closure`
+fn closure_capture_local<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    closure: &Operand<'tcx>,
+    index: usize,
+) -> Option<Local> {
+    let sources = mir_analyzer.collect_source_locals(closure.place()?.local);
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata.statements.iter().find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (
+                dest,
+                Rvalue::Aggregate(box AggregateKind::Closure(..), operands),
+            )) if sources.contains(&dest.local) => operands.iter().nth(index)?.place()?.as_local(),
+            _ => None,
+        })
+    })
+}
+
+/// Check if `program_id` is a `Pubkey` stored in the data of an `Account<'info, T>` whose address
+/// is pinned with `seeds` or `address`, e.g. `ctx.accounts.config.cpi_target` of a config PDA.
+/// Anchor only checks the owner of an `Account`, so the key stored in any other account of type
+/// `T` is picked by whoever created that account.
+pub fn is_stored_in_pinned_account(mir_analyzer: &MirAnalyzer, program_id: Local) -> bool {
+    let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+        return false;
+    };
+    let Some(field) = account_data_pubkey_source(mir_analyzer, anchor_context, program_id) else {
+        return false;
+    };
+    let constraints = parse_account_constraints(mir_analyzer.cx, field);
+    constraints.seeds.is_some() || constraints.address.is_some()
+}
+
+/// If `local` is a `Pubkey` field read from the data of an `Account<'info, T>`, e.g.
+/// `ctx.accounts.config.cpi_target`, get the field of the accounts struct declaring the account
+fn account_data_pubkey_source<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    local: Local,
+) -> Option<&'tcx FieldDef> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mir = mir_analyzer.mir;
+
+    // `_5 = copy ((*_4).cpi_target)`, where `_4` is the `&T` the `Account` derefs to
+    let pubkey_place = assigned_place(mir_analyzer, local)?;
+    let (data, ProjectionElem::Field(..)) = pubkey_place.iter_projections().last()? else {
+        return None;
+    };
+    if !data
+        .projection
+        .iter()
+        .all(|elem| matches!(elem, ProjectionElem::Deref))
+    {
+        return None;
+    }
+    let account = mir
+        .basic_blocks
+        .iter()
+        .find_map(|bbdata| match &bbdata.terminator().kind {
+            TerminatorKind::Call {
+                args, destination, ..
+            } if destination.as_local() == Some(data.local) => args.first()?.node.place(),
+            _ => None,
+        })?;
+    if !is_anchor_account_type(tcx, account.ty(mir, tcx).ty) {
+        return None;
+    }
+
+    // `_3 = &((*_2).config)`, a field of the accounts struct
+    let account_place = match account.as_local() {
+        Some(account_local) => assigned_place(mir_analyzer, account_local)?,
+        None => account,
+    };
+    let (accounts, ProjectionElem::Field(field_idx, _)) =
+        account_place.iter_projections().last()?
+    else {
+        return None;
+    };
+    let TyKind::Adt(adt_def, _) = accounts.ty(mir, tcx).ty.peel_refs().kind() else {
+        return None;
+    };
+    let TyKind::Adt(accounts_struct, _) = anchor_context.anchor_context_account_type.kind() else {
+        return None;
+    };
+    (adt_def.did() == accounts_struct.did() && adt_def.is_struct())
+        .then(|| &adt_def.non_enum_variant().fields[field_idx])
+}
+
+/// Follow the copies and borrows `local` is assigned from, back to the first place that is not a
+/// plain local
+fn assigned_place<'tcx>(mir_analyzer: &MirAnalyzer<'_, 'tcx>, local: Local) -> Option<Place<'tcx>> {
+    let mut current = local;
+    let mut visited = HashSet::new();
+    while visited.insert(current) {
+        let (AssignmentKind::FromPlace(place) | AssignmentKind::RefTo(place)) =
+            mir_analyzer.assignment_map.get(&current)?
+        else {
+            return None;
+        };
+        match place.as_local() {
+            Some(src) => current = src,
+            None => return Some(*place),
+        }
+    }
+    None
+}
+
+/// Span of the variable the program id of a CPI is first bound to, e.g. `let program_id =
+/// ctx.accounts.program.key();` for a `CpiContext` built with a copy of `program_id`. Falls back to
+/// the temporary holding the program id when no user variable flows into it.
+pub fn program_id_origin_span(mir_analyzer: &MirAnalyzer, program_id: Local) -> Span {
+    let local_decls = mir_analyzer.mir.local_decls();
+    let origin = mir_analyzer
+        .transitive_assignment_reverse_map
+        .iter()
+        .filter(|(_, destinations)| destinations.contains(&program_id))
+        .map(|(source, _)| *source)
+        .filter(|source| {
+            local_decls[*source].is_user_variable() && mir_analyzer.is_pubkey_type(*source)
+        })
+        .min()
+        .unwrap_or(program_id);
+    local_decls[origin].source_info.span
+}
@@ -3,6 +3,22 @@ use anchor_lang::system_program::{self, Transfer};
 
 declare_id!("Arb1tr4ryCpi11111111111111111111111111111111");
 
+/// Helper declared before the program module, validated by the handler calling it
+pub fn early_declared_cpi_call_with_account<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
 #[program]
 pub mod arbitrary_cpi_call_tests {
     use super::*;
@@ -16,7 +32,7 @@ pub mod arbitrary_cpi_call_tests {
 
         let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
 
-        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call: note=program id originates here]
         Ok(())
     }
 
@@ -696,207 +712,1396 @@ pub mod arbitrary_cpi_call_tests {
         ctx.accounts.cpi_call_unsafe(amount)?;
         Ok(())
     }
-}
 
-pub fn cpi_call_with_account<'info>(
-    from: &Signer<'info>,
-    to: &UncheckedAccount<'info>,
-    program: &UncheckedAccount<'info>,
-    amount: u64,
-) -> Result<()> {
-    let cpi_accounts = Transfer {
-        from: from.to_account_info(),
-        to: to.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
-    system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
-    Ok(())
-}
-
-/// Helper function that takes accounts struct and makes a CPI call
-/// This should be flagged as unsafe when called without validation
-pub fn cpi_call_with_accounts(ctx_accounts: &UncheckedCpi, amount: u64) -> Result<()> {
-    cpi_call_with_account(
-        &ctx_accounts.from,
-        &ctx_accounts.to,
-        &ctx_accounts.unchecked_program,
-        amount,
-    )?;
-    Ok(())
-}
+    // Case 36: unwrap_or a constant from an option holding a constant - safe
+    pub fn unwrap_or_constant_from_safe_option(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let preferred_program: Option<Pubkey> = Some(system_program::ID);
+        let pid = preferred_program.unwrap_or(system_program::ID);
 
-/// Helper function that validates the program account
-/// This is safe and should be used before making CPI calls
-pub fn checked_cpi_call_with_accounts(acc_mut: &mut UncheckedCpi) -> Result<()> {
-    checked_cpi_call_with_account(&mut acc_mut.unchecked_program)?;
-    Ok(())
-}
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
 
-pub fn checked_cpi_call_with_account(program_account: &UncheckedAccount) -> Result<()> {
-    require_keys_eq!(
-        program_account.key(),
-        system_program::ID,
-        CustomError::InvalidProgram
-    );
-    Ok(())
-}
+        let cpi_ctx = CpiContext::new(pid, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
 
-/// Nested helper function that validates then makes CPI call
-/// This should be safe because validation happens before CPI
-pub fn nested_cpi_in_helper_function(mut ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
-    checked_cpi_call_with_accounts(&mut ctx.accounts)?;
-    cpi_call_with_accounts(&mut ctx.accounts, amount)?;
-    Ok(())
-}
+    // Case 37: unwrap_or a constant over a user-controlled option - unsafe
+    pub fn unwrap_or_over_user_controlled_option(
+        ctx: Context<BasicTransfer>,
+        maybe_program: Option<Pubkey>,
+        amount: u64,
+    ) -> Result<()> {
+        let pid = maybe_program.unwrap_or(system_program::ID);
 
-/// Nested helper function with context accounts pattern
-/// Validates in helper then makes CPI - should be safe
-pub fn nested_cpi_in_helper_with_context_accounts(
-    mut ctx: Context<UncheckedCpi>,
-    amount: u64,
-) -> Result<()> {
-    checked_cpi_call_with_accounts(&mut ctx.accounts)?;
-    cpi_call_with_accounts(&mut ctx.accounts, amount)?;
-    Ok(())
-}
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
 
-pub fn cpi_call_with_account_safe<'info>(
-    from: &Signer<'info>,
-    to: &UncheckedAccount<'info>,
-    program: &UncheckedAccount<'info>,
-    amount: u64,
-) -> Result<()> {
-    let cpi_accounts = Transfer {
-        from: from.to_account_info(),
-        to: to.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
-    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
-    Ok(())
-}
+        let cpi_ctx = CpiContext::new(pid, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
 
-pub fn nested_cpi_call_with_individual_accounts<'info>(
-    from: &Signer<'info>,
-    to: &UncheckedAccount<'info>,
-    program: &mut UncheckedAccount<'info>,
-    amount: u64,
-) -> Result<()> {
-    // Validate first
-    checked_cpi_call_with_account(program)?;
-    // Then make CPI call with individual accounts - safe because validation happened
-    cpi_call_with_account_safe(from, to, program, amount)?;
-    Ok(())
-}
+    // Case 38: Self-CPI through `ctx.program_id` copied into a local - safe
+    pub fn self_cpi_via_ctx_program_id(ctx: Context<BasicTransfer>, amount: u64) -> Result<()> {
+        let self_program_id = *ctx.program_id;
 
-pub fn checked_cpi_call_with_function_param_account(program: Pubkey) -> Result<()> {
-    require_keys_eq!(program, system_program::ID, CustomError::InvalidProgram);
-    Ok(())
-}
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
 
-pub fn cpi_call_with_function_param_account<'info>(
-    from: &Signer<'info>,
-    to: &UncheckedAccount<'info>,
-    program: Pubkey,
-    amount: u64,
-) -> Result<()> {
-    let cpi_accounts = Transfer {
-        from: from.to_account_info(),
-        to: to.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(program, cpi_accounts);
-    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
-    Ok(())
-}
+        let cpi_ctx = CpiContext::new(self_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
 
-pub fn nested_cpi_call_with_function_param_account<'info>(
-    from: &Signer<'info>,
-    to: &UncheckedAccount<'info>,
-    program: Pubkey,
-    amount: u64,
-) -> Result<()> {
-    checked_cpi_call_with_function_param_account(program)?;
-    cpi_call_with_function_param_account(from, to, program, amount)?;
-    Ok(())
-}
+    // Case 39: Instruction program ID replaced with a user-controlled one after construction - unsafe
+    pub fn invoke_with_program_id_mutated_to_user_controlled(
+        ctx: Context<DirectInvokeTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::instruction::Instruction;
+        use anchor_lang::solana_program::program::invoke;
 
-#[derive(Accounts)]
-pub struct UncheckedCpi<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-    /// CHECK: test fixture
-    pub unchecked_program: UncheckedAccount<'info>,
-}
+        let mut instruction = Instruction::new_with_bincode(system_program::ID, &amount, vec![]);
+        instruction.program_id = ctx.accounts.unchecked_program.key();
 
-#[derive(Accounts)]
-pub struct ValidatedCpi<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-    /// CHECK: test fixture
-    pub unchecked_program: UncheckedAccount<'info>,
-}
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct BasicTransfer<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-}
+    // Case 40: Instruction program ID replaced with a constant after construction - safe
+    pub fn invoke_with_program_id_mutated_to_constant(
+        ctx: Context<DirectInvokeTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::instruction::Instruction;
+        use anchor_lang::solana_program::program::invoke;
 
-#[derive(Accounts)]
-pub struct AccountBasedTransfer<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-    /// CHECK: test fixture
-    pub unchecked_program: UncheckedAccount<'info>,
-}
+        let mut instruction = Instruction {
+            program_id: ctx.accounts.unchecked_program.key(),
+            accounts: vec![],
+            data: amount.to_le_bytes().to_vec(),
+        };
+        instruction.program_id = system_program::ID;
 
-#[derive(Accounts)]
-pub struct DataBasedTransfer<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub inner: Account<'info, InnerAccount>,
-}
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct DirectInvokeTransfer<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    /// CHECK: test fixture
-    pub to: UncheckedAccount<'info>,
-    /// CHECK: test fixture
-    pub unchecked_program: UncheckedAccount<'info>,
-    pub system_program: Program<'info, System>,
-}
+    // Case 41: Validation in a block dominating the CPI, plus an unrelated check in a branch - safe
+    pub fn cpi_after_dominating_validation(
+        ctx: Context<UncheckedCpi>,
+        verbose: bool,
+        amount: u64,
+    ) -> Result<()> {
+        if ctx.accounts.unchecked_program.key() != system_program::ID {
+            return err!(CustomError::InvalidProgram);
+        }
+        if verbose && ctx.accounts.unchecked_program.key() == system_program::ID {
+            msg!("transferring through the system program");
+        }
 
-#[derive(Accounts)]
-pub struct CpiBuilderAccounts<'info> {
-    #[account(mut)]
-    pub from: Signer<'info>,
-    #[account(mut)]
-    pub to: AccountInfo<'info>,
-    pub authority: Signer<'info>,
-    pub token_program: Program<'info, anchor_spl::token::Token>, // Validated by Anchor
-    pub system_program: Program<'info, System>,
-}
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
 
-#[derive(Accounts)]
-pub struct CpiBuilderUnchecked<'info> {
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 42: Validation only in a sibling branch that does not dominate the CPI - unsafe
+    pub fn cpi_after_sibling_branch_validation(
+        ctx: Context<UncheckedCpi>,
+        strict: bool,
+        amount: u64,
+    ) -> Result<()> {
+        if strict {
+            require_keys_eq!(
+                ctx.accounts.unchecked_program.key(),
+                system_program::ID,
+                CustomError::InvalidProgram
+            );
+        } else {
+            msg!("skipping program check");
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 43: Validation in a helper called on one branch only, CPI on both - unsafe
+    pub fn cpi_after_branch_only_helper_validation(
+        mut ctx: Context<UncheckedCpi>,
+        strict: bool,
+        amount: u64,
+    ) -> Result<()> {
+        if strict {
+            checked_cpi_call_with_account(&mut ctx.accounts.unchecked_program)?;
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 44: Program ID from an associated const - safe
+    pub fn program_id_from_associated_const(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(Router::ID, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 45: Program ID from the `const fn id()` generated by `declare_id!` - safe
+    pub fn program_id_from_const_fn(ctx: Context<BasicTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(crate::id(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 46: Program ID from `Id::id()` of an Anchor program type - safe
+    pub fn program_id_from_program_type_id(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(System::id(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 47: `const fn` called with a user-controlled program ID - unsafe
+    pub fn program_id_from_const_fn_with_user_argument(
+        ctx: Context<UncheckedCpi>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let program_id = Router::route(ctx.accounts.unchecked_program.key());
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 48: Lint allowed on the handler, CPI in the helper it calls - suppressed
+    #[allow(arbitrary_cpi_call)]
+    pub fn allowed_handler_with_unchecked_helper(
+        ctx: Context<UncheckedCpi>,
+        amount: u64,
+    ) -> Result<()> {
+        cpi_call_from_allowed_handler(
+            &ctx.accounts.from,
+            &ctx.accounts.to,
+            &ctx.accounts.unchecked_program,
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // Case 49: Lint allowed on the helper making the CPI - suppressed
+    pub fn handler_with_allowed_unchecked_helper(
+        ctx: Context<UncheckedCpi>,
+        amount: u64,
+    ) -> Result<()> {
+        allowed_cpi_call_with_account(
+            &ctx.accounts.from,
+            &ctx.accounts.to,
+            &ctx.accounts.unchecked_program,
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // Case 50: spl_token instruction built for a user-controlled program ID, then invoked - unsafe
+    pub fn spl_token_builder_with_unchecked_program(
+        ctx: Context<SplTokenBuilderTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program::invoke;
+        use anchor_spl::token::spl_token;
+
+        let instruction = spl_token::instruction::transfer(
+            &ctx.accounts.unchecked_program.key(),
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            amount,
+        )?;
+
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 51: spl_token instruction built for a validated program ID - safe
+    pub fn spl_token_builder_with_validated_program(
+        ctx: Context<SplTokenBuilderTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program::invoke;
+        use anchor_spl::token::spl_token;
+
+        require_keys_eq!(
+            ctx.accounts.unchecked_program.key(),
+            spl_token::ID,
+            CustomError::InvalidProgram
+        );
+
+        let instruction = spl_token::instruction::transfer(
+            &ctx.accounts.unchecked_program.key(),
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            amount,
+        )?;
+
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 52: spl_token instruction built for the hardcoded token program ID - safe
+    pub fn spl_token_builder_with_constant_program(
+        ctx: Context<SplTokenBuilderTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program::invoke;
+        use anchor_spl::token::spl_token;
+
+        let instruction = spl_token::instruction::transfer(
+            &spl_token::ID,
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            amount,
+        )?;
+
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 53: spl_token instruction built for a `Program<'info, Token>` account - safe
+    pub fn spl_token_builder_with_token_program(
+        ctx: Context<SplTokenBuilderTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program::invoke;
+        use anchor_spl::token::spl_token;
+
+        let instruction = spl_token::instruction::transfer(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            amount,
+        )?;
+
+        let account_infos = vec![
+            ctx.accounts.from.to_account_info(),
+            ctx.accounts.to.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+        ];
+        invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 54: Accounts struct built in a helper, user-controlled program ID - unsafe
+    pub fn helper_built_accounts_unchecked(ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
+        let cpi_accounts = make_transfer_accounts(&ctx);
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 55: Accounts struct built in a helper, validated program ID - safe
+    pub fn helper_built_accounts_validated(ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
+        require_keys_eq!(ctx.accounts.unchecked_program.key(), system_program::ID);
+
+        let cpi_accounts = make_transfer_accounts(&ctx);
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 56: Program ID reassigned in a loop, user-controlled before the loop - unsafe
+    pub fn program_id_selected_in_loop_unchecked(
+        ctx: Context<UncheckedCpi>,
+        rounds: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let mut program_id = ctx.accounts.unchecked_program.key();
+        for round in 0..rounds {
+            if round == 1 {
+                program_id = system_program::ID;
+                break;
+            }
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 57: Program ID reassigned in a loop, constant on every assignment - safe
+    pub fn program_id_selected_in_loop_constant(
+        ctx: Context<UncheckedCpi>,
+        rounds: u8,
+        amount: u64,
+    ) -> Result<()> {
+        const SOME_PROGRAM_ID: Pubkey = Pubkey::new_from_array([42u8; 32]);
+        let mut program_id = system_program::ID;
+        for round in 0..rounds {
+            if round == 1 {
+                program_id = SOME_PROGRAM_ID;
+                break;
+            }
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 58: Guard clause on a direct comparison, without an else - safe
+    pub fn guard_clause_comparison(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if !(target_program_id == system_program::ID) {
+            return err!(CustomError::InvalidProgram);
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 59: Guard clause on an inequality, without an else - safe
+    pub fn guard_clause_inequality(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if target_program_id != system_program::ID {
+            return err!(CustomError::InvalidProgram);
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 60: Guard clause on an allowlist array, without an else - safe
+    pub fn guard_clause_allowlist(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if ![system_program::ID, crate::ID].contains(&target_program_id) {
+            return err!(CustomError::InvalidProgram);
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 61: Guard clause on an allowlist that falls through instead of returning - unsafe
+    pub fn guard_clause_allowlist_without_return(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let allowed_programs = vec![system_program::ID, crate::ID];
+        if !allowed_programs.contains(&target_program_id) {
+            msg!("program is not allowlisted");
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 62: Program id checked by a boolean validator before the CPI - safe
+    pub fn boolean_validator_guard(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require!(is_allowed_program(target_program_id), CustomError::InvalidProgram);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 63: Result of the boolean validator is only logged - unsafe
+    pub fn boolean_validator_ignored(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let allowed = is_allowed_program(target_program_id);
+        msg!("program allowed: {}", allowed);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 64: Boolean helper comparing against another parameter, not a constant - unsafe
+    pub fn boolean_helper_without_constant(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        excluded_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            is_other_program(target_program_id, excluded_program_id),
+            CustomError::InvalidProgram
+        );
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 65: Key of a typed `Program` read through `AsRef<AccountInfo>` - safe
+    pub fn program_key_via_as_ref(ctx: Context<DirectInvokeTransfer>, amount: u64) -> Result<()> {
+        let program_id: Pubkey = *ctx.accounts.system_program.as_ref().key;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 66: Key of an `UncheckedAccount` read through `AsRef<AccountInfo>` - unsafe
+    pub fn unchecked_key_via_as_ref(
+        ctx: Context<DirectInvokeTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let program_id: Pubkey = *ctx.accounts.unchecked_program.as_ref().key;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 67: Key of a typed `Program` read through `Deref` - safe
+    pub fn program_key_via_deref(ctx: Context<DirectInvokeTransfer>, amount: u64) -> Result<()> {
+        let program_info: &AccountInfo = &ctx.accounts.system_program;
+        let program_id = *program_info.key;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 68: Key of an `UncheckedAccount` read through `Deref` - unsafe
+    pub fn unchecked_key_via_deref(ctx: Context<DirectInvokeTransfer>, amount: u64) -> Result<()> {
+        let program_id = *ctx.accounts.unchecked_program.key;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 69: Unconstrained account forwarded to Token-2022, which may invoke it as the
+    // transfer hook program - unsafe
+    pub fn transfer_with_unchecked_hook(
+        ctx: Context<HookedTransfer>,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<()> {
+        let cpi_accounts = anchor_spl::token_2022::TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(anchor_spl::token_2022::ID, cpi_accounts)
+            .with_remaining_accounts(vec![ctx.accounts.hook_program.to_account_info()]);
+        anchor_spl::token_2022::transfer_checked(cpi_ctx, amount, decimals)?; // [arbitrary_remaining_account_program]
+        Ok(())
+    }
+
+    // Case 70: Forwarded hook program pinned with `address = ...` - safe
+    pub fn transfer_with_pinned_hook(
+        ctx: Context<HookedTransfer>,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<()> {
+        let cpi_accounts = anchor_spl::token_2022::TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(anchor_spl::token_2022::ID, cpi_accounts)
+            .with_remaining_accounts(vec![ctx.accounts.pinned_hook_program.to_account_info()]);
+        anchor_spl::token_2022::transfer_checked(cpi_ctx, amount, decimals)?; // [safe_remaining_account_program]
+        Ok(())
+    }
+
+    // Case 71: Unconstrained account forwarded to the System program, which never invokes the
+    // accounts it is passed - safe
+    pub fn system_transfer_with_unchecked_remaining(
+        ctx: Context<HookedTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(system_program::ID, cpi_accounts)
+            .with_remaining_accounts(vec![ctx.accounts.hook_program.to_account_info()]);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_remaining_account_program]
+        Ok(())
+    }
+
+    // Case 72: Same instruction invoked before and after validating its program ID - only the
+    // first invoke is unsafe
+    pub fn invoke_before_and_after_validation(ctx: Context<DirectInvokeTransfer>) -> Result<()> {
+        use anchor_lang::solana_program::instruction::Instruction;
+        use anchor_lang::solana_program::program::invoke;
+
+        let instruction = Instruction {
+            program_id: ctx.accounts.unchecked_program.key(),
+            accounts: vec![],
+            data: vec![],
+        };
+        let account_infos = vec![ctx.accounts.from.to_account_info()];
+
+        invoke(&instruction, &account_infos)?; // [arbitrary_cpi_call]
+
+        require_keys_eq!(
+            ctx.accounts.unchecked_program.key(),
+            system_program::ID,
+            CustomError::InvalidProgram
+        );
+
+        invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 73: Same instruction invoked in a validated branch, then again after the branches
+    // join - only the second invoke is unsafe
+    pub fn invoke_in_validated_branch_then_after_join(
+        ctx: Context<DirectInvokeTransfer>,
+        strict: bool,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::instruction::Instruction;
+        use anchor_lang::solana_program::program::invoke;
+
+        let instruction = Instruction {
+            program_id: ctx.accounts.unchecked_program.key(),
+            accounts: vec![],
+            data: vec![],
+        };
+        let account_infos = vec![ctx.accounts.from.to_account_info()];
+
+        if strict {
+            require_keys_eq!(
+                ctx.accounts.unchecked_program.key(),
+                system_program::ID,
+                CustomError::InvalidProgram
+            );
+            invoke(&instruction, &account_infos)?; // [safe_cpi_call]
+        }
+
+        invoke(&instruction, &account_infos)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 74: Program ID validated with `matches!` against an allowlist - safe
+    pub fn matches_validation_safe(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if !matches!(target_program_id, system_program::ID | crate::ID) {
+            return err!(CustomError::InvalidProgram);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 75: Program ID validated with a `match` whose fallback arm returns an error - safe
+    pub fn match_validation_safe(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        match target_program_id {
+            system_program::ID | crate::ID => {}
+            _ => return err!(CustomError::InvalidProgram),
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 76: Program ID matched, but the catch-all arm still performs the CPI - unsafe
+    // (fallback path is unsafe, as in Case 9)
+    pub fn match_validation_with_cpi_fallback_unsafe(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        match target_program_id {
+            crate::ID => Ok(()),
+            _ => system_program::transfer(cpi_ctx, amount), // [arbitrary_cpi_call]
+        }
+    }
+
+    // Case 77: Guard clause on a `HashSet` allowlist - safe
+    pub fn guard_clause_hash_set_allowlist(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let allowed_programs = std::collections::HashSet::from([system_program::ID, crate::ID]);
+        if !allowed_programs.contains(&target_program_id) {
+            return err!(CustomError::InvalidProgram);
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 78: Guard clause on a `BTreeSet` allowlist - safe
+    pub fn guard_clause_btree_set_allowlist(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let allowed_programs = std::collections::BTreeSet::from([system_program::ID, crate::ID]);
+        if !allowed_programs.contains(&target_program_id) {
+            return err!(CustomError::InvalidProgram);
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 79: `BTreeSet` allowlist checked, but the miss only logs - unsafe
+    pub fn btree_set_allowlist_without_return(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let allowed_programs = std::collections::BTreeSet::from([system_program::ID, crate::ID]);
+        if !allowed_programs.contains(&target_program_id) {
+            msg!("program is not allowlisted");
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 80: Every remaining account invoked as the program of a CPI - unsafe
+    pub fn remaining_accounts_iteration_unsafe(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        for program in ctx.remaining_accounts.iter() {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        }
+        Ok(())
+    }
+
+    // Case 81: Indexed remaining account used as the program of a CPI - unsafe
+    pub fn remaining_accounts_index_unsafe(
+        ctx: Context<BasicTransfer>,
+        index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let program_id = *ctx.remaining_accounts[index as usize].key;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 82: Remaining account key compared before each CPI - safe
+    pub fn remaining_accounts_iteration_validated(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        for program in ctx.remaining_accounts.iter() {
+            let program_id = program.key();
+            require_keys_eq!(
+                program_id,
+                system_program::ID,
+                CustomError::InvalidProgram
+            );
+
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        }
+        Ok(())
+    }
+
+    // Case 83: Program ID from `create_program_address` with user seeds - unsafe
+    pub fn program_id_from_created_pda(
+        ctx: Context<BasicTransfer>,
+        seed: Vec<u8>,
+        bump: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let derived_key =
+            Pubkey::create_program_address(&[seed.as_slice(), &[bump]], &crate::ID)
+                .map_err(|_| CustomError::InvalidData)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(derived_key, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 84: Program ID from `create_program_address` with constant seeds - unsafe
+    // (a PDA is never a program, as in Case 21)
+    pub fn program_id_from_created_pda_constant_seeds(
+        ctx: Context<BasicTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let derived_key =
+            Pubkey::create_program_address(&[b"program", &[255]], &crate::ID).unwrap();
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(derived_key, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 85: Case 4 with the validation inside an immediately invoked closure - safe
+    pub fn validated_program_id_in_closure(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        (|| -> Result<()> {
+            require!(
+                target_program_id == system_program::ID,
+                CustomError::InvalidProgram
+            );
+            Ok(())
+        })()?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 86: Closure validates another program ID than the one invoked - unsafe
+    pub fn closure_validates_other_program_id(
+        ctx: Context<BasicTransfer>,
+        target_program_id: Pubkey,
+        other_program_id: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        (|| -> Result<()> {
+            require!(
+                other_program_id == system_program::ID,
+                CustomError::InvalidProgram
+            );
+            Ok(())
+        })()?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(target_program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 87: Program ID stored in an `Account` anyone can create - unsafe
+    pub fn program_id_from_config_account(
+        ctx: Context<ConfigTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(ctx.accounts.config.cpi_target, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 88: Program ID stored in a config PDA only this program writes - safe
+    pub fn program_id_from_config_pda(
+        ctx: Context<PdaConfigTransfer>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(ctx.accounts.config.cpi_target, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+        Ok(())
+    }
+
+    // Case 89: Two CPIs with user-controlled program IDs, the first one allowed inline - safe, then unsafe
+    pub fn inline_allowed_cpi(ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.unchecked_program.key(),
+            Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            },
+        );
+        // anchor-lints:allow(arbitrary_cpi_call)
+        system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.unchecked_program.key(),
+            Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // Case 90: Helper declared before the program module, called after validation - safe
+    pub fn early_declared_helper_validated(ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
+        checked_cpi_call_with_account(&ctx.accounts.unchecked_program)?;
+        early_declared_cpi_call_with_account(
+            &ctx.accounts.from,
+            &ctx.accounts.to,
+            &ctx.accounts.unchecked_program,
+            amount,
+        )?;
+        Ok(())
+    }
+}
+
+/// Transfer hook program the pinned cases forward
+pub const HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([9u8; 32]);
+
+/// Allowlist of the programs the transfer cases may invoke
+fn is_allowed_program(program_id: Pubkey) -> bool {
+    program_id == system_program::ID
+}
+
+/// Not a validator, both keys come from the caller
+fn is_other_program(program_id: Pubkey, other: Pubkey) -> bool {
+    program_id != other
+}
+
+/// Builds the accounts of a transfer out of `from`
+pub fn make_transfer_accounts<'info>(ctx: &Context<'_, '_, '_, 'info, UncheckedCpi<'info>>) -> Transfer<'info> {
+    Transfer {
+        from: ctx.accounts.from.to_account_info(),
+        to: ctx.accounts.to.to_account_info(),
+    }
+}
+
+pub struct Router;
+
+impl Router {
+    pub const ID: Pubkey = Pubkey::new_from_array([7u8; 32]);
+
+    pub const fn route(program_id: Pubkey) -> Pubkey {
+        program_id
+    }
+}
+
+/// Only called by a handler allowing the lint
+pub fn cpi_call_from_allowed_handler<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
+#[allow(arbitrary_cpi_call)]
+pub fn allowed_cpi_call_with_account<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
+pub fn cpi_call_with_account<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+    Ok(())
+}
+
+/// Helper function that takes accounts struct and makes a CPI call
+/// This should be flagged as unsafe when called without validation
+pub fn cpi_call_with_accounts(ctx_accounts: &UncheckedCpi, amount: u64) -> Result<()> {
+    cpi_call_with_account(
+        &ctx_accounts.from,
+        &ctx_accounts.to,
+        &ctx_accounts.unchecked_program,
+        amount,
+    )?;
+    Ok(())
+}
+
+/// Helper function that validates the program account
+/// This is safe and should be used before making CPI calls
+pub fn checked_cpi_call_with_accounts(acc_mut: &mut UncheckedCpi) -> Result<()> {
+    checked_cpi_call_with_account(&mut acc_mut.unchecked_program)?;
+    Ok(())
+}
+
+pub fn checked_cpi_call_with_account(program_account: &UncheckedAccount) -> Result<()> {
+    require_keys_eq!(
+        program_account.key(),
+        system_program::ID,
+        CustomError::InvalidProgram
+    );
+    Ok(())
+}
+
+/// Nested helper function that validates then makes CPI call
+/// This should be safe because validation happens before CPI
+pub fn nested_cpi_in_helper_function(mut ctx: Context<UncheckedCpi>, amount: u64) -> Result<()> {
+    checked_cpi_call_with_accounts(&mut ctx.accounts)?;
+    cpi_call_with_accounts(&mut ctx.accounts, amount)?;
+    Ok(())
+}
+
+/// Nested helper function with context accounts pattern
+/// Validates in helper then makes CPI - should be safe
+pub fn nested_cpi_in_helper_with_context_accounts(
+    mut ctx: Context<UncheckedCpi>,
+    amount: u64,
+) -> Result<()> {
+    checked_cpi_call_with_accounts(&mut ctx.accounts)?;
+    cpi_call_with_accounts(&mut ctx.accounts, amount)?;
+    Ok(())
+}
+
+pub fn cpi_call_with_account_safe<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
+pub fn nested_cpi_call_with_individual_accounts<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: &mut UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    // Validate first
+    checked_cpi_call_with_account(program)?;
+    // Then make CPI call with individual accounts - safe because validation happened
+    cpi_call_with_account_safe(from, to, program, amount)?;
+    Ok(())
+}
+
+pub fn checked_cpi_call_with_function_param_account(program: Pubkey) -> Result<()> {
+    require_keys_eq!(program, system_program::ID, CustomError::InvalidProgram);
+    Ok(())
+}
+
+pub fn cpi_call_with_function_param_account<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: to.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(program, cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
+pub fn nested_cpi_call_with_function_param_account<'info>(
+    from: &Signer<'info>,
+    to: &UncheckedAccount<'info>,
+    program: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    checked_cpi_call_with_function_param_account(program)?;
+    cpi_call_with_function_param_account(from, to, program, amount)?;
+    Ok(())
+}
+
+/// Free helper never called from a handler, analyzed on its own
+/// The program account is a parameter, so the CPI target is user-controlled
+pub fn uncalled_cpi_call_with_account<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer { from, to };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+    Ok(())
+}
+
+/// Free helper never called from a handler that validates the program id itself
+pub fn uncalled_checked_cpi_call_with_account<'info>(
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        program.key(),
+        system_program::ID,
+        CustomError::InvalidProgram
+    );
+    let cpi_accounts = Transfer { from, to };
+    let cpi_ctx = CpiContext::new(program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)?; // [safe_cpi_call]
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UncheckedCpi<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    /// CHECK: test fixture
+    pub unchecked_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ValidatedCpi<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    /// CHECK: test fixture
+    pub unchecked_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BasicTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AccountBasedTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    /// CHECK: test fixture
+    pub unchecked_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DataBasedTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub inner: Account<'info, InnerAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    pub config: Account<'info, CpiConfig>,
+}
+
+#[derive(Accounts)]
+pub struct PdaConfigTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, CpiConfig>,
+}
+
+#[derive(Accounts)]
+pub struct DirectInvokeTransfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    /// CHECK: test fixture
+    pub unchecked_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HookedTransfer<'info> {
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub from: UncheckedAccount<'info>,
+    /// CHECK: test fixture
+    pub mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: test fixture
+    pub hook_program: UncheckedAccount<'info>,
+    /// CHECK: pinned to the hook program
+    #[account(address = HOOK_PROGRAM_ID)]
+    pub pinned_hook_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CpiBuilderAccounts<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    pub to: AccountInfo<'info>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>, // Validated by Anchor
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SplTokenBuilderTransfer<'info> {
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub from: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: test fixture
+    pub to: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: test fixture
+    pub unchecked_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, anchor_spl::token::Token>,
+}
+
+#[derive(Accounts)]
+pub struct CpiBuilderUnchecked<'info> {
     #[account(mut)]
     pub from: Signer<'info>,
     #[account(mut)]
@@ -1023,6 +2228,11 @@ pub struct InnerAccount {
     pub data: u64,
 }
 
+#[account]
+pub struct CpiConfig {
+    pub cpi_target: Pubkey,
+}
+
 #[error_code]
 pub enum CustomError {
     #[msg("Invalid program")]
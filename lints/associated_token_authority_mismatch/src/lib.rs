@@ -0,0 +1,162 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{account_constraints::extract_constraint_values, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+use std::collections::HashSet;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects associated token accounts whose `associated_token::authority` is the account
+    /// paying for initialization while the accounts struct also has a separate `user` or
+    /// `owner` account, which is most likely the intended owner of the ATA.
+    ///
+    /// ### Why is this bad?
+    /// The ATA address is derived from its authority and mint. Using the payer as authority
+    /// silently creates or accepts the payer's token account instead of the user's, so tokens
+    /// end up in the wrong wallet or checks run against the wrong account.
+    ///
+    /// ### Bad
+    /// ```rust
+    /// #[derive(Accounts)]
+    /// pub struct CreateUserAta<'info> {
+    ///     #[account(init, payer = payer, associated_token::mint = mint, associated_token::authority = payer)]
+    ///     pub user_ata: Account<'info, TokenAccount>,
+    ///     pub user: SystemAccount<'info>,
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// ### Good
+    /// ```rust
+    /// #[derive(Accounts)]
+    /// pub struct CreateUserAta<'info> {
+    ///     #[account(init, payer = payer, associated_token::mint = mint, associated_token::authority = user)]
+    ///     pub user_ata: Account<'info, TokenAccount>,
+    ///     pub user: SystemAccount<'info>,
+    ///     // ...
+    /// }
+    /// ```
+    pub ASSOCIATED_TOKEN_AUTHORITY_MISMATCH,
+    Warn,
+    "associated token account authority is the payer instead of the intended owner"
+}
+
+impl<'tcx> LateLintPass<'tcx> for AssociatedTokenAuthorityMismatch {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ASSOCIATED_TOKEN_AUTHORITY_MISMATCH) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(ASSOCIATED_TOKEN_AUTHORITY_MISMATCH);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_associated_token_authorities(cx, anchor_context);
+    }
+}
+
+/// Account names that usually hold the wallet an ATA is meant for
+const INTENDED_OWNER_FIELDS: &[&str] = &["user", "owner"];
+
+fn analyze_associated_token_authorities<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+    let fields = &adt_def.non_enum_variant().fields;
+
+    let constraints: Vec<_> = fields
+        .iter()
+        .map(|field| (field, extract_constraint_values(cx, field)))
+        .collect();
+
+    // Accounts funding an `init`/`init_if_needed` in this struct
+    let payers: HashSet<&str> = constraints
+        .iter()
+        .flat_map(|(_, values)| values.iter())
+        .filter(|(key, _)| key == "payer")
+        .map(|(_, value)| referenced_account(value))
+        .collect();
+    if payers.is_empty() {
+        return;
+    }
+
+    for (field, values) in &constraints {
+        let Some(authority) = values
+            .iter()
+            .find(|(key, _)| key == "associated_token::authority")
+            .map(|(_, value)| referenced_account(value))
+        else {
+            continue;
+        };
+        if !payers.contains(authority) {
+            continue;
+        }
+
+        let Some(intended_owner) = fields.iter().find(|other| {
+            let name = other.ident(cx.tcx);
+            name.as_str() != authority && INTENDED_OWNER_FIELDS.contains(&name.as_str())
+        }) else {
+            continue;
+        };
+
+        span_lint_and_note(
+            cx,
+            ASSOCIATED_TOKEN_AUTHORITY_MISMATCH,
+            cx.tcx.def_span(field.did),
+            format!(
+                "associated token account `{}` uses the payer `{}` as `associated_token::authority` while `{}` looks like its intended owner — set `associated_token::authority = {}` if the ATA belongs to `{}`",
+                field.ident(cx.tcx),
+                authority,
+                intended_owner.ident(cx.tcx),
+                intended_owner.ident(cx.tcx),
+                intended_owner.ident(cx.tcx),
+            ),
+            Some(cx.tcx.def_span(intended_owner.did)),
+            "intended owner is declared here",
+        );
+    }
+}
+
+/// The account a constraint value refers to, e.g. `user` for `user.key`
+fn referenced_account(value: &str) -> &str {
+    value.split('.').next().unwrap_or(value)
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod associated_token_authority_mismatch_tests {
+    use super::*;
+
+    // BAD: the payer is used as ATA authority although a separate `user` exists
+    pub fn create_user_ata_for_payer(ctx: Context<CreateUserAtaForPayer>) -> Result<()> {
+        msg!("ATA: {}", ctx.accounts.user_ata.key());
+        Ok(())
+    }
+
+    // GOOD: the ATA is derived for the user, the payer only funds it
+    pub fn create_user_ata(ctx: Context<CreateUserAta>) -> Result<()> {
+        msg!("ATA: {}", ctx.accounts.user_ata.key());
+        Ok(())
+    }
+
+    // GOOD: the user pays for their own ATA
+    pub fn create_own_ata(ctx: Context<CreateOwnAta>) -> Result<()> {
+        msg!("ATA: {}", ctx.accounts.user_ata.key());
+        Ok(())
+    }
+
+    // BAD: an existing vault ATA is read for the payer instead of the `owner`
+    pub fn deposit_into_owner_vault(ctx: Context<DepositIntoOwnerVault>) -> Result<()> {
+        msg!("Vault: {}", ctx.accounts.owner_vault.key());
+        Ok(())
+    }
+
+    // GOOD: no separate owner account, the payer's ATA is intended
+    pub fn create_payer_ata(ctx: Context<CreatePayerAta>) -> Result<()> {
+        msg!("ATA: {}", ctx.accounts.payer_ata.key());
+        Ok(())
+    }
+}
+
+// BAD: the payer is used as ATA authority although a separate `user` exists
+#[derive(Accounts)]
+pub struct CreateUserAtaForPayer<'info> {
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+    )]
+    pub user_ata: Account<'info, TokenAccount>, // [associated_token_authority_mismatch]
+    pub mint: Account<'info, Mint>,
+    pub user: SystemAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: the ATA is derived for the user, the payer only funds it
+#[derive(Accounts)]
+pub struct CreateUserAta<'info> {
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_ata: Account<'info, TokenAccount>, // [safe_associated_token_authority]
+    pub mint: Account<'info, Mint>,
+    pub user: SystemAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: the user pays for their own ATA
+#[derive(Accounts)]
+pub struct CreateOwnAta<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_ata: Account<'info, TokenAccount>, // [safe_associated_token_authority]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: an existing vault ATA is read for the payer instead of the `owner`
+#[derive(Accounts)]
+pub struct DepositIntoOwnerVault<'info> {
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+    )]
+    pub owner_vault: Account<'info, TokenAccount>, // [associated_token_authority_mismatch]
+    #[account(init, payer = payer, space = 8 + 8)]
+    pub receipt: Account<'info, Receipt>,
+    pub mint: Account<'info, Mint>,
+    pub owner: SystemAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: no separate owner account, the payer's ATA is intended
+#[derive(Accounts)]
+pub struct CreatePayerAta<'info> {
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+    )]
+    pub payer_ata: Account<'info, TokenAccount>, // [safe_associated_token_authority]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Receipt {
+    pub amount: u64,
+}
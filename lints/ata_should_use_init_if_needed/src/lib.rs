@@ -11,11 +11,12 @@ use anchor_lints_utils::{
         is_anchor_spl_token_account_type, is_anchor_spl_token_interface_token_account_type,
         is_spl_token_account_type,
     },
+    diagnostics::span_lint,
     mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
 };
 
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint;
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::ty::{Ty, TyKind};
@@ -78,6 +79,11 @@ impl<'tcx> LateLintPass<'tcx> for AtaShouldUseInitIfNeeded {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(ATA_SHOULD_USE_INIT_IF_NEEDED) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -93,6 +99,7 @@ fn analyze_ata_init_constraint<'tcx>(
     def_id: LocalDefId,
 ) {
     let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+    mir_analyzer.debug_dump(ATA_SHOULD_USE_INIT_IF_NEEDED);
 
     // Analyze functions that take Anchor context
     let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -0,0 +1,151 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note, mir_analyzer::MirAnalyzer, registry::is_lint_enabled,
+    utils::should_skip_function,
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty as rustc_ty,
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects a `CpiContext` local that is used for a CPI, has its `program_id` reassigned, and
+    /// is then used for another CPI.
+    ///
+    /// ### Why is this bad?
+    /// The accounts of a `CpiContext` are built for the program it was created for. Pointing the
+    /// same context at another program sends those accounts to a program that expects different
+    /// ones, which often hides a logic bug and makes the invoked program hard to follow.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let mut cpi_ctx = CpiContext::new(ctx.accounts.program_a.key(), accounts);
+    /// invoke_with_context(&cpi_ctx, data_a)?;
+    /// cpi_ctx.program_id = ctx.accounts.program_b.key();
+    /// invoke_with_context(&cpi_ctx, data_b)?;
+    ///
+    /// // Good:
+    /// invoke_with_context(&CpiContext::new(ctx.accounts.program_a.key(), accounts_a), data_a)?;
+    /// invoke_with_context(&CpiContext::new(ctx.accounts.program_b.key(), accounts_b), data_b)?;
+    /// ```
+    pub CPI_CONTEXT_REUSED_ACROSS_DIFFERENT_PROGRAMS,
+    Warn,
+    "`CpiContext` reused for CPIs to different programs"
+}
+
+impl<'tcx> LateLintPass<'tcx> for CpiContextReusedAcrossDifferentPrograms {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(CPI_CONTEXT_REUSED_ACROSS_DIFFERENT_PROGRAMS) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(CPI_CONTEXT_REUSED_ACROSS_DIFFERENT_PROGRAMS);
+
+        analyze_cpi_context_reuse(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_cpi_context_reuse<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    let mir = mir_analyzer.mir;
+    let mut cpi_context_uses: Vec<CpiContextUse> = Vec::new();
+    let mut program_id_writes: Vec<ProgramIdWrite> = Vec::new();
+
+    for (bb, bbdata) in mir.basic_blocks.iter_enumerated() {
+        program_id_writes.extend(bbdata.statements.iter().filter_map(|stmt| {
+            cpi_context_program_id_write(mir_analyzer, stmt).map(|cpi_ctx_local| ProgramIdWrite {
+                bb,
+                span: stmt.source_info.span,
+                cpi_ctx_local,
+            })
+        }));
+
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && returns_result(cx, *fn_def_id)
+            && let Some(arg) = cpi_context_arg(mir_analyzer, args)
+        {
+            cpi_context_uses.push(CpiContextUse {
+                bb,
+                span: *fn_span,
+                arg,
+            });
+        }
+    }
+
+    for write in program_id_writes {
+        let uses_context = |cpi_context_use: &&CpiContextUse| {
+            mir_analyzer
+                .collect_source_locals(cpi_context_use.arg)
+                .contains(&write.cpi_ctx_local)
+        };
+        // A `program_id` set before the first CPI only configures the context
+        let Some(previous_use) = cpi_context_uses
+            .iter()
+            .filter(uses_context)
+            .find(|cpi_context_use| reachable_after(mir, cpi_context_use.bb, write.bb))
+        else {
+            continue;
+        };
+        let used_again = cpi_context_uses
+            .iter()
+            .filter(uses_context)
+            .any(|cpi_context_use| {
+                cpi_context_use.bb == write.bb || reachable_after(mir, write.bb, cpi_context_use.bb)
+            });
+        if !used_again {
+            continue;
+        }
+
+        let context = local_name(mir, write.cpi_ctx_local).map_or_else(
+            || "the CPI context".to_string(),
+            |name| format!("`{}`", name),
+        );
+        span_lint_and_note(
+            cx,
+            CPI_CONTEXT_REUSED_ACROSS_DIFFERENT_PROGRAMS,
+            write.span,
+            format!(
+                "{} is reused for a CPI to another program after its `program_id` is reassigned — build a separate `CpiContext` for each program",
+                context
+            ),
+            Some(previous_use.span),
+            "the context is first used for a CPI here",
+        );
+    }
+}
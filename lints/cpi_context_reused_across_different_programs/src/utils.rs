@@ -0,0 +1,96 @@
+use anchor_lints_utils::{diag_items::is_anchor_cpi_context, mir_analyzer::MirAnalyzer};
+
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::mir::{
+    BasicBlock, Body as MirBody, Local, Operand, ProjectionElem, Statement, StatementKind,
+    VarDebugInfoContents,
+};
+use rustc_middle::ty::TyKind;
+use rustc_span::{Span, source_map::Spanned, sym};
+
+use std::collections::{HashSet, VecDeque};
+
+/// A CPI performed with a `CpiContext`, passed by value or by reference
+pub struct CpiContextUse {
+    pub bb: BasicBlock,
+    pub span: Span,
+    /// The argument the context is passed through
+    pub arg: Local,
+}
+
+/// A write to the `program_id` field of a `CpiContext` local, e.g. `cpi_ctx.program_id = id`
+pub struct ProgramIdWrite {
+    pub bb: BasicBlock,
+    pub span: Span,
+    pub cpi_ctx_local: Local,
+}
+
+/// If `stmt` assigns the `program_id` of a `CpiContext`, return the context local
+pub fn cpi_context_program_id_write(mir_analyzer: &MirAnalyzer, stmt: &Statement) -> Option<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let StatementKind::Assign(box (place, _)) = &stmt.kind else {
+        return None;
+    };
+    let (base, ProjectionElem::Field(field_idx, _)) = place.iter_projections().last()? else {
+        return None;
+    };
+    let base_ty = base.ty(mir_analyzer.mir, tcx).ty;
+    let is_program_id = is_anchor_cpi_context(tcx, base_ty)
+        && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+            if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "program_id");
+    is_program_id.then_some(place.local)
+}
+
+/// Get the first argument holding a `CpiContext`, by value or by reference
+pub fn cpi_context_arg(mir_analyzer: &MirAnalyzer, args: &[Spanned<Operand>]) -> Option<Local> {
+    args.iter().find_map(|arg| match &arg.node {
+        Operand::Copy(place) | Operand::Move(place) => place.as_local().filter(|local| {
+            is_anchor_cpi_context(mir_analyzer.cx.tcx, mir_analyzer.mir.local_decls[*local].ty)
+        }),
+        Operand::Constant(_) => None,
+    })
+}
+
+/// Check if `fn_def_id` returns a `Result`, telling CPIs apart from builders such as
+/// `with_signer` and accessors such as `to_account_infos`
+pub fn returns_result<'tcx>(cx: &LateContext<'tcx>, fn_def_id: DefId) -> bool {
+    let return_ty = cx
+        .tcx
+        .fn_sig(fn_def_id)
+        .skip_binder()
+        .skip_binder()
+        .output();
+    is_type_diagnostic_item(cx, return_ty, sym::Result)
+}
+
+/// Check if `to` can be executed after the terminator of `from`
+pub fn reachable_after(mir: &MirBody, from: BasicBlock, to: BasicBlock) -> bool {
+    let mut queue: VecDeque<BasicBlock> =
+        mir.basic_blocks[from].terminator().successors().collect();
+    let mut visited = HashSet::new();
+    while let Some(current) = queue.pop_front() {
+        if current == to {
+            return true;
+        }
+        if visited.insert(current) {
+            queue.extend(mir.basic_blocks[current].terminator().successors());
+        }
+    }
+    false
+}
+
+/// Name of the user variable a local is bound to
+pub fn local_name(mir: &MirBody, local: Local) -> Option<String> {
+    mir.var_debug_info
+        .iter()
+        .find_map(|info| match &info.value {
+            VarDebugInfoContents::Place(place)
+                if place.local == local && place.projection.is_empty() =>
+            {
+                Some(info.name.to_string())
+            }
+            _ => None,
+        })
+}
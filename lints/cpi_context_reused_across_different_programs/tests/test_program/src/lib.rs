@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod cpi_context_reused_across_different_programs_tests {
+    use super::*;
+
+    // BAD: the context is pointed at another program between two CPIs
+    pub fn reuse_context_across_programs(ctx: Context<TwoPrograms>) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let mut cpi_ctx = CpiContext::new(ctx.accounts.program_a.key(), cpi_accounts);
+        invoke_with_context(&cpi_ctx, vec![0])?;
+        cpi_ctx.program_id = ctx.accounts.program_b.key(); // [cpi_context_reused_across_different_programs]
+        invoke_with_context(&cpi_ctx, vec![1])?;
+        Ok(())
+    }
+
+    // BAD: the reassigned context is consumed by an Anchor CPI helper
+    pub fn reuse_context_for_system_transfer(ctx: Context<TwoPrograms>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let mut cpi_ctx = CpiContext::new(ctx.accounts.program_a.key(), cpi_accounts);
+        invoke_with_context(&cpi_ctx, vec![0])?;
+        cpi_ctx.program_id = system_program::ID; // [cpi_context_reused_across_different_programs]
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // GOOD: a fresh context is built for each program
+    pub fn fresh_context_per_program(ctx: Context<TwoPrograms>) -> Result<()> {
+        let cpi_ctx_a = CpiContext::new( // [safe_cpi_context_per_program]
+            ctx.accounts.program_a.key(),
+            Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            },
+        );
+        invoke_with_context(&cpi_ctx_a, vec![0])?;
+        let cpi_ctx_b = CpiContext::new(
+            ctx.accounts.program_b.key(),
+            Transfer {
+                from: ctx.accounts.from.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            },
+        );
+        invoke_with_context(&cpi_ctx_b, vec![1])?;
+        Ok(())
+    }
+
+    // GOOD: the program id is set before the only CPI
+    pub fn program_id_set_before_cpi(ctx: Context<TwoPrograms>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let mut cpi_ctx = CpiContext::new(ctx.accounts.program_a.key(), cpi_accounts);
+        cpi_ctx.program_id = system_program::ID; // [safe_cpi_context_per_program]
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // GOOD: the same context is used twice for the same program
+    pub fn reuse_context_same_program(ctx: Context<TwoPrograms>) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.program_a.key(), cpi_accounts);
+        invoke_with_context(&cpi_ctx, vec![0])?; // [safe_cpi_context_per_program]
+        invoke_with_context(&cpi_ctx, vec![1])?;
+        Ok(())
+    }
+}
+
+/// Invoke the program of a CPI context with its accounts
+pub fn invoke_with_context<'info, T: ToAccountMetas + ToAccountInfos<'info>>(
+    cpi_ctx: &CpiContext<'_, '_, '_, 'info, T>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: cpi_ctx.program_id,
+        accounts: cpi_ctx.to_account_metas(None),
+        data,
+    };
+    invoke(&instruction, &cpi_ctx.to_account_infos())?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TwoPrograms<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    #[account(mut)]
+    pub to: SystemAccount<'info>,
+    /// CHECK: invoked program
+    pub program_a: UncheckedAccount<'info>,
+    /// CHECK: invoked program
+    pub program_b: UncheckedAccount<'info>,
+}
@@ -8,13 +8,12 @@ extern crate rustc_span;
 
 use anchor_lints_utils::{
     diag_items::{is_anchor_cpi_context, is_cpi_invoke_fn},
+    diagnostics::span_lint,
     mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
     utils::should_skip_function,
 };
-use clippy_utils::{
-    diagnostics::span_lint,
-    sym::{Result, unwrap_or, unwrap_or_default, unwrap_or_else},
-};
+use clippy_utils::sym::{Result, unwrap_or, unwrap_or_default, unwrap_or_else};
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::{
@@ -61,6 +60,11 @@ impl<'tcx> LateLintPass<'tcx> for CpiNoResult {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(CPI_NO_RESULT) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -74,6 +78,8 @@ impl<'tcx> LateLintPass<'tcx> for CpiNoResult {
 fn analyze_cpi_no_result<'tcx>(cx: &LateContext<'tcx>, body: &HirBody<'tcx>, def_id: LocalDefId) {
     let mir = cx.tcx.optimized_mir(def_id.to_def_id());
     let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+    mir_analyzer.debug_dump(CPI_NO_RESULT);
+
     let mut cpi_calls_with_silent_suppression: Vec<(BasicBlock, Span)> = Vec::new();
 
     for (bb, bbdata) in mir.basic_blocks.iter_enumerated() {
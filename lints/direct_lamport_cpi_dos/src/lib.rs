@@ -6,10 +6,12 @@ extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
+use anchor_lints_utils::cfg::reachable;
 use anchor_lints_utils::mir_analyzer::{AnchorContextInfo, MirAnalyzer};
+use anchor_lints_utils::registry::is_lint_enabled;
 
+use anchor_lints_utils::diagnostics::span_lint_and_note;
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint_and_note;
 
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
@@ -96,6 +98,11 @@ impl<'tcx> LateLintPass<'tcx> for DirectLamportCpiDos {
         main_fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(DIRECT_LAMPORT_CPI_DOS) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, main_fn_span, def_id) {
             return;
@@ -105,6 +112,7 @@ impl<'tcx> LateLintPass<'tcx> for DirectLamportCpiDos {
 
         // Update anchor context info with accounts
         anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(DIRECT_LAMPORT_CPI_DOS);
 
         // Analyze functions that take Anchor context
         let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -174,7 +182,13 @@ fn analyze_direct_lamport_cpi_dos<'cx, 'tcx>(
     for (account_name, lamport_mutation) in &lamport_mutated_accounts {
         for cpi_call in &cpi_calls {
             // Check if CPI is reachable from lamport mutation
-            if is_reachable(mir_analyzer.mir, lamport_mutation.block, cpi_call.block) {
+            if reachable(
+                &mir_analyzer.mir.basic_blocks,
+                lamport_mutation.block,
+                |bb| bb == cpi_call.block,
+            )
+            .is_some()
+            {
                 // Check if the account is included in the CPI
                 if !cpi_call.accounts.contains(account_name) {
                     span_lint_and_note(
@@ -16,7 +16,7 @@ use rustc_middle::{
 };
 use rustc_span::{Span, source_map::Spanned};
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 
 pub fn is_remaining_accounts_method(cx: &LateContext, fn_def_id: DefId) -> bool {
     is_anchor_cpi_context_with_remaining_accounts_fn(cx.tcx, fn_def_id)
@@ -236,39 +236,6 @@ pub fn extract_cpi_accounts_from_account_struct(
     accounts
 }
 
-pub fn is_reachable<'tcx>(
-    mir: &rustc_middle::mir::Body<'tcx>,
-    from: BasicBlock,
-    to: BasicBlock,
-) -> bool {
-    if from == to {
-        return true;
-    }
-
-    let mut queue = VecDeque::new();
-    let mut visited = HashSet::new();
-
-    visited.insert(from);
-    queue.push_back(from);
-
-    while let Some(current) = queue.pop_front() {
-        if current == to {
-            return true;
-        }
-
-        // Get successors from the terminator
-        if let Some(terminator) = &mir.basic_blocks[current].terminator {
-            for succ in terminator.successors() {
-                if visited.insert(succ) {
-                    queue.push_back(succ);
-                }
-            }
-        }
-    }
-
-    false
-}
-
 pub fn extract_accounts_from_vec_by_tracing(
     mir_analyzer: &MirAnalyzer,
     vec_local: Local,
@@ -5,21 +5,30 @@ extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
-use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::{diagnostics::span_lint_and_help, source::HasSession};
+use anchor_lints_utils::diagnostics::span_lint_and_help;
+use anchor_lints_utils::registry::is_lint_enabled;
+use anchor_lints_utils::utils::{AccountsAliasAnalysis, should_skip_function};
+use clippy_utils::source::HasSession;
 use rustc_hir::{
-    Body as HirBody, Expr, ExprKind, FnDecl,
+    Body as HirBody, BorrowKind, Expr, ExprKind, FnDecl, HirId, LetStmt, Mutability, PatKind,
+    QPath, UnOp,
+    def::Res,
     def_id::LocalDefId,
-    intravisit::{FnKind, Visitor, walk_expr},
+    intravisit::{FnKind, Visitor, walk_expr, walk_local},
 };
 use rustc_lint::{LateContext, LateLintPass};
-use rustc_middle::ty::{Ty, TyKind};
+use rustc_middle::ty::{
+    Ty, TyKind,
+    adjustment::{Adjust, AutoBorrow, AutoBorrowMutability},
+};
 use rustc_span::Span;
 use std::collections::{HashMap, HashSet};
 
 mod models;
+mod remaining_accounts;
 mod utils;
 use models::*;
+use remaining_accounts::check_remaining_accounts_aliasing;
 use utils::*;
 
 dylint_linting::declare_late_lint! {
@@ -27,6 +36,15 @@ dylint_linting::declare_late_lint! {
     /// Detects duplicate mutable account usage in functions,
     /// where the same account is passed into multiple mutable parameters.
     ///
+    /// An account is mutable when it has the `#[account(mut)]` constraint, which makes Anchor
+    /// persist it on exit, or when the handler actually writes it. Accounts that are only read
+    /// are not reported, even if both are `Account<'info, T>`.
+    ///
+    /// Accounts taken from distinct constant indices of `ctx.remaining_accounts`, e.g.
+    /// `ctx.remaining_accounts[0]` and `ctx.remaining_accounts[1]`, are reported when the handler
+    /// writes the lamports or the data of both, unless a `key() != key()` guard on them, e.g.
+    /// `require_keys_neq!`, runs before the writes.
+    ///
     /// ### Why is this bad?
     /// This can lead to unexpected aliasing of mutable data, logical errors, and vulnerabilities like
     /// account state corruption.
@@ -46,15 +64,22 @@ impl<'tcx> LateLintPass<'tcx> for DuplicateMutableAccounts {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(DUPLICATE_MUTABLE_ACCOUNTS) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
         }
 
-        let mut mutable_accounts: HashMap<Ty, DuplicateContextAccounts> = HashMap::new();
-        let mut conditional_account_comparisons: Vec<String> = Vec::new();
+        // `ctx.remaining_accounts` are not part of the accounts struct, so they have no constraints
+        check_remaining_accounts_aliasing(cx, body);
 
-        let mut has_one_constraint_accounts: Vec<String> = Vec::new();
+        let mut mutable_accounts: HashMap<Ty, DuplicateContextAccounts> = HashMap::new();
+        let mut alias_analysis = AccountsAliasAnalysis::default();
+        let mutated_accounts = collect_mutated_accounts(cx, body.value);
 
         // check function's first argument which is the context type
         let params = &body.params;
@@ -90,51 +115,36 @@ impl<'tcx> LateLintPass<'tcx> for DuplicateMutableAccounts {
                                 continue;
                             }
                             let accounts_variant = accounts_adt_def.non_enum_variant();
-                            for account_field in &accounts_variant.fields {
-                                let account_name = account_field.ident(cx.tcx).to_string();
-                                let account_constraints = extract_account_constraints(
-                                    cx,
-                                    account_field,
-                                    &mut has_one_constraint_accounts,
-                                );
+                            alias_analysis = AccountsAliasAnalysis::new(cx, accounts_variant);
+                            for (account_field, account_evidence) in
+                                accounts_variant.fields.iter().zip(alias_analysis.fields())
+                            {
                                 let account_span = cx.tcx.def_span(account_field.did);
 
                                 // Unwrap box type to get the inner type
                                 let account_ty = account_field.ty(cx.tcx, accounts_generics);
                                 let inner_ty = utils::unwrap_box_type(cx, account_ty);
 
-                                // Add account constraints to the list of conditional account comparisons
-                                conditional_account_comparisons
-                                    .extend(account_constraints.constraints.clone());
-
                                 if let TyKind::Adt(_adt_def, _) = inner_ty.kind() {
                                     if !is_anchor_mutable_account(
                                         cx,
                                         inner_ty,
-                                        &account_constraints,
+                                        account_evidence,
+                                        &mutated_accounts,
                                     ) {
                                         continue;
                                     }
 
-                                    let existing_accounts = mutable_accounts
-                                        .get(&account_ty)
-                                        .map(|d| d.accounts.clone())
-                                        .unwrap_or_default();
-                                    mutable_accounts.insert(
-                                        account_ty,
-                                        DuplicateContextAccounts {
-                                            accounts: {
-                                                let mut accounts = existing_accounts;
-                                                accounts.push(AccountDetails {
-                                                    span: account_span,
-                                                    account_name,
-                                                    seeds: account_constraints.seeds,
-                                                    attributes: account_constraints.attributes,
-                                                });
-                                                accounts
-                                            },
-                                        },
-                                    );
+                                    mutable_accounts
+                                        .entry(account_ty)
+                                        .or_insert_with(|| DuplicateContextAccounts {
+                                            accounts: Vec::new(),
+                                        })
+                                        .accounts
+                                        .push(AccountDetails {
+                                            span: account_span,
+                                            account_name: account_evidence.account_name.clone(),
+                                        });
                                 }
                             }
                         }
@@ -142,8 +152,7 @@ impl<'tcx> LateLintPass<'tcx> for DuplicateMutableAccounts {
                 }
             }
             // add manual account key checks
-            conditional_account_comparisons
-                .extend(check_manual_account_comparisons(cx, body.value));
+            alias_analysis.add_distinct_pairs(check_manual_account_comparisons(cx, body.value));
 
             // Track reported pairs to avoid duplicate reports
             let mut reported_pairs = HashSet::new();
@@ -162,8 +171,7 @@ impl<'tcx> LateLintPass<'tcx> for DuplicateMutableAccounts {
                                 first,
                                 second,
                                 &mut reported_pairs,
-                                &conditional_account_comparisons,
-                                &has_one_constraint_accounts,
+                                &alias_analysis,
                             ) {
                                 let help_message = format!(
                                     "`{}` and `{}` may refer to the same account. \
@@ -209,9 +217,21 @@ fn check_manual_account_comparisons<'tcx>(
     struct ExprVisitor<'a, 'tcx> {
         cx: &'a LateContext<'tcx>,
         conditional_account_comparisons: Vec<String>,
+        /// `let accounts = [&ctx.accounts.user_a, ..]` bindings, with the accounts they collect
+        account_collections: HashMap<HirId, Vec<String>>,
     }
 
     impl<'a, 'tcx> Visitor<'tcx> for ExprVisitor<'a, 'tcx> {
+        fn visit_local(&mut self, local: &'tcx LetStmt<'tcx>) {
+            if let Some(init) = local.init
+                && let PatKind::Binding(_, hir_id, _, None) = local.pat.kind
+                && let Some(accounts) = collection_account_names(init)
+            {
+                self.account_collections.insert(hir_id, accounts);
+            }
+            walk_local(self, local);
+        }
+
         fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
             // if expression is an if statement
             if let ExprKind::If(cond, then_block, _) = &expr.kind {
@@ -227,6 +247,20 @@ fn check_manual_account_comparisons<'tcx>(
                 }
             }
 
+            // `assert_ne!(a.key(), b.key())` and `assert_eq!(a.key() != b.key(), true)`
+            for (left, right) in extract_assert_comparisons(expr) {
+                self.conditional_account_comparisons
+                    .extend(check_and_add_account_comparison(left, right));
+            }
+
+            // Pairwise loops comparing every two accounts of a collection
+            self.conditional_account_comparisons
+                .extend(pairwise_loop_guarded_accounts(
+                    self.cx,
+                    expr,
+                    &self.account_collections,
+                ));
+
             walk_expr(self, expr);
         }
     }
@@ -234,12 +268,121 @@ fn check_manual_account_comparisons<'tcx>(
     let mut visitor = ExprVisitor {
         cx,
         conditional_account_comparisons: Vec::new(),
+        account_collections: HashMap::new(),
     };
     visitor.visit_expr(expr);
 
     visitor.conditional_account_comparisons
 }
 
+/// Accounts of the context that the handler writes: assigned through a field, e.g.
+/// `ctx.accounts.user.data = a`, borrowed mutably, e.g. `helper(&mut ctx.accounts.user)` or
+/// `ctx.accounts.user.reload()`. Uses of an alias bound with `let user = &mut ctx.accounts.user`
+/// count for the account it borrows, while the borrow alone does not.
+fn collect_mutated_accounts<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> HashSet<String> {
+    struct MutationVisitor<'a, 'tcx> {
+        cx: &'a LateContext<'tcx>,
+        /// `let user = &mut ctx.accounts.user` bindings, with the account they borrow
+        aliases: HashMap<HirId, String>,
+        mutated_accounts: HashSet<String>,
+    }
+
+    impl<'a, 'tcx> MutationVisitor<'a, 'tcx> {
+        /// The account a place is projected from, through fields, derefs, indexing and method
+        /// calls, e.g. `user` for `ctx.accounts.user.data[0]`
+        fn account_of_place(&self, expr: &Expr<'_>) -> Option<String> {
+            let mut current = expr;
+            loop {
+                if let ExprKind::Field(base, account) = current.kind
+                    && let ExprKind::Field(_, accounts) = base.kind
+                    && accounts.as_str() == "accounts"
+                {
+                    return Some(account.to_string());
+                }
+                match current.kind {
+                    ExprKind::Field(base, _)
+                    | ExprKind::Index(base, _, _)
+                    | ExprKind::Unary(UnOp::Deref, base)
+                    | ExprKind::MethodCall(_, base, _, _) => current = base,
+                    ExprKind::Path(QPath::Resolved(None, path)) => {
+                        return match path.res {
+                            Res::Local(hir_id) => self.aliases.get(&hir_id).cloned(),
+                            _ => None,
+                        };
+                    }
+                    _ => return None,
+                }
+            }
+        }
+
+        fn mark_mutated(&mut self, place: &Expr<'_>) {
+            if let Some(account) = self.account_of_place(place) {
+                self.mutated_accounts.insert(account);
+            }
+        }
+    }
+
+    impl<'a, 'tcx> Visitor<'tcx> for MutationVisitor<'a, 'tcx> {
+        fn visit_local(&mut self, local: &'tcx LetStmt<'tcx>) {
+            if let Some(init) = local.init
+                && let ExprKind::AddrOf(BorrowKind::Ref, Mutability::Mut, borrowed) = init.kind
+                && let PatKind::Binding(_, hir_id, _, None) = local.pat.kind
+                && let Some(account) = self.account_of_place(borrowed)
+            {
+                // The borrow alone is not a write, uses of the alias are checked instead
+                self.aliases.insert(hir_id, account);
+                return;
+            }
+            walk_local(self, local);
+        }
+
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            match expr.kind {
+                ExprKind::Assign(place, _, _) | ExprKind::AssignOp(_, place, _) => {
+                    self.mark_mutated(place);
+                }
+                ExprKind::AddrOf(BorrowKind::Ref, Mutability::Mut, place) => {
+                    self.mark_mutated(place);
+                }
+                _ => {}
+            }
+
+            // Mutable autoref of a method receiver, implicit reborrow of a `&mut` alias, or
+            // `DerefMut` of the account wrapper
+            let borrows_mutably =
+                self.cx
+                    .typeck_results()
+                    .expr_adjustments(expr)
+                    .iter()
+                    .any(|adjustment| match adjustment.kind {
+                        Adjust::Borrow(AutoBorrow::Ref(AutoBorrowMutability::Mut { .. })) => true,
+                        Adjust::Deref(Some(overloaded)) => overloaded.mutbl == Mutability::Mut,
+                        _ => false,
+                    });
+            if borrows_mutably {
+                self.mark_mutated(expr);
+            }
+
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = MutationVisitor {
+        cx,
+        aliases: HashMap::new(),
+        mutated_accounts: HashSet::new(),
+    };
+    visitor.visit_expr(expr);
+
+    visitor.mutated_accounts
+}
+
+/// Macros that panic when their condition does not hold
+const PANICKING_MACROS: &[&str] = &["panic!", "assert!", "assert_eq!", "assert_ne!"];
+
 fn contains_exit_statement<'tcx>(expr: &'tcx Expr<'tcx>, cx: &LateContext<'tcx>) -> bool {
     struct ExitFinder<'a, 'tcx> {
         cx: &'a LateContext<'tcx>,
@@ -258,11 +401,13 @@ fn contains_exit_statement<'tcx>(expr: &'tcx Expr<'tcx>, cx: &LateContext<'tcx>)
                 return;
             }
 
-            // check for panic! macro in source code
+            // check for panic! and assert macros in source code
             if expr.span.from_expansion() {
                 let source_span = expr.span.source_callsite();
                 if let Ok(source_text) = self.cx.sess().source_map().span_to_snippet(source_span)
-                    && source_text.trim_start().starts_with("panic!")
+                    && PANICKING_MACROS
+                        .iter()
+                        .any(|name| source_text.trim_start().starts_with(name))
                 {
                     self.found = true;
                     return;
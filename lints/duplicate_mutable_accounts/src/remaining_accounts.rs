@@ -0,0 +1,193 @@
+use anchor_lints_utils::{diag_items::is_anchor_context, diagnostics::span_lint_and_help};
+use rustc_ast::LitKind;
+use rustc_hir::{
+    Body as HirBody, Expr, ExprKind, HirId, LetStmt, PatKind, QPath, StmtKind, UnOp,
+    def::Res,
+    intravisit::{Visitor, walk_expr, walk_local},
+};
+use rustc_lint::LateContext;
+use rustc_span::{BytePos, Span};
+use std::collections::HashMap;
+
+use crate::{
+    DUPLICATE_MUTABLE_ACCOUNTS, contains_exit_statement,
+    utils::{extract_assert_comparisons, extract_comparisons},
+};
+
+/// Methods writing the lamports or the data of an `AccountInfo`, e.g.
+/// `**account.lamports.borrow_mut() -= amount` or `account.data.borrow_mut()`
+const ACCOUNT_INFO_WRITE_METHODS: &[&str] = &[
+    "borrow_mut",
+    "try_borrow_mut",
+    "try_borrow_mut_lamports",
+    "try_borrow_mut_data",
+    "add_lamports",
+    "sub_lamports",
+];
+
+/// Report two distinct constant indices of `ctx.remaining_accounts` that are both written, unless
+/// a top-level `key() != key()` guard on them, e.g. `require_keys_neq!`, comes before the writes.
+/// Dynamic indices are skipped, they may well be equal on purpose.
+pub fn check_remaining_accounts_aliasing<'tcx>(cx: &LateContext<'tcx>, body: &HirBody<'tcx>) {
+    let mut visitor = RemainingAccountsVisitor {
+        cx,
+        aliases: HashMap::new(),
+        writes: HashMap::new(),
+    };
+    visitor.visit_expr(body.value);
+    if visitor.writes.len() < 2 {
+        return;
+    }
+
+    // `(first index, second index, end of the guard)` of the guards exiting when both are equal
+    let guards = collect_key_guards(cx, &visitor, body.value);
+
+    let mut indices: Vec<_> = visitor.writes.keys().copied().collect();
+    indices.sort_unstable();
+    for (i, &first) in indices.iter().enumerate() {
+        for &second in &indices[i + 1..] {
+            let first_write = visitor.writes[&first];
+            let second_write = visitor.writes[&second];
+            let writes_start = first_write.lo().min(second_write.lo());
+            if guards.iter().any(|&(a, b, guard_end)| {
+                (a.min(b), a.max(b)) == (first, second) && guard_end <= writes_start
+            }) {
+                continue;
+            }
+            span_lint_and_help(
+                cx,
+                DUPLICATE_MUTABLE_ACCOUNTS,
+                second_write,
+                "duplicate mutable account found",
+                Some(first_write),
+                format!(
+                    "`remaining_accounts[{}]` and `remaining_accounts[{}]` may refer to the same account. \
+                    Add `require_keys_neq!(ctx.remaining_accounts[{}].key(), ctx.remaining_accounts[{}].key())` \
+                    before writing them or use `#[allow(duplicate_mutable_accounts)]` if they can be the same.",
+                    first, second, first, second,
+                ),
+            );
+        }
+    }
+}
+
+struct RemainingAccountsVisitor<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    /// `let account = &ctx.remaining_accounts[0]` bindings, with the index they take
+    aliases: HashMap<HirId, u128>,
+    /// First write of each constant index
+    writes: HashMap<u128, Span>,
+}
+
+impl<'a, 'tcx> RemainingAccountsVisitor<'a, 'tcx> {
+    /// The constant index of `ctx.remaining_accounts` a place is projected from, through fields,
+    /// derefs, borrows and method calls, e.g. `1` for `ctx.remaining_accounts[1].lamports`
+    fn remaining_account_index(&self, expr: &Expr<'_>) -> Option<u128> {
+        let mut current = expr;
+        loop {
+            if let ExprKind::Index(base, index, _) = current.kind
+                && let ExprKind::Field(ctx, field) = base.kind
+                && field.as_str() == "remaining_accounts"
+            {
+                let ctx_ty = self.cx.typeck_results().expr_ty(ctx).peel_refs();
+                if !is_anchor_context(self.cx.tcx, ctx_ty) {
+                    return None;
+                }
+                return match index.kind {
+                    ExprKind::Lit(lit) => match lit.node {
+                        LitKind::Int(value, _) => Some(value.get()),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+            }
+            match current.kind {
+                ExprKind::Field(base, _)
+                | ExprKind::Unary(UnOp::Deref, base)
+                | ExprKind::AddrOf(_, _, base)
+                | ExprKind::MethodCall(_, base, _, _) => current = base,
+                ExprKind::Path(QPath::Resolved(None, path)) => {
+                    return match path.res {
+                        Res::Local(hir_id) => self.aliases.get(&hir_id).copied(),
+                        _ => None,
+                    };
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// The constant index whose key `expr` reads, e.g. `0` for `ctx.remaining_accounts[0].key()`
+    /// or `*account.key`
+    fn key_index(&self, expr: &Expr<'_>) -> Option<u128> {
+        match expr.kind {
+            ExprKind::Unary(UnOp::Deref, inner) => self.key_index(inner),
+            ExprKind::MethodCall(segment, receiver, [], _) if segment.ident.as_str() == "key" => {
+                self.remaining_account_index(receiver)
+            }
+            ExprKind::Field(base, field) if field.as_str() == "key" => {
+                self.remaining_account_index(base)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for RemainingAccountsVisitor<'a, 'tcx> {
+    fn visit_local(&mut self, local: &'tcx LetStmt<'tcx>) {
+        if let Some(init) = local.init
+            && let PatKind::Binding(_, hir_id, _, None) = local.pat.kind
+            && let Some(index) = self.remaining_account_index(init)
+        {
+            self.aliases.insert(hir_id, index);
+        }
+        walk_local(self, local);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+        if let ExprKind::MethodCall(segment, receiver, _, _) = expr.kind
+            && ACCOUNT_INFO_WRITE_METHODS.contains(&segment.ident.as_str())
+            && let Some(index) = self.remaining_account_index(receiver)
+        {
+            self.writes.entry(index).or_insert(expr.span);
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Guards among the top-level statements of the handler that exit when two constant indices have
+/// the same key, e.g. `require_keys_neq!(..)`. A guard there runs before every later statement.
+fn collect_key_guards<'tcx>(
+    cx: &LateContext<'tcx>,
+    visitor: &RemainingAccountsVisitor<'_, 'tcx>,
+    body: &'tcx Expr<'tcx>,
+) -> Vec<(u128, u128, BytePos)> {
+    let ExprKind::Block(block, _) = body.kind else {
+        return Vec::new();
+    };
+    let mut guards = Vec::new();
+    for stmt in block.stmts {
+        let (StmtKind::Expr(expr) | StmtKind::Semi(expr)) = stmt.kind else {
+            continue;
+        };
+        // `assert_ne!(..)` and `assert_eq!(.., bool)` panic when both keys are equal
+        for (left, right) in extract_assert_comparisons(expr) {
+            if let (Some(a), Some(b)) = (visitor.key_index(left), visitor.key_index(right)) {
+                guards.push((a, b, stmt.span.source_callsite().hi()));
+            }
+        }
+        let ExprKind::If(cond, then_block, _) = expr.kind else {
+            continue;
+        };
+        if !contains_exit_statement(then_block, cx) {
+            continue;
+        }
+        for (left, right) in extract_comparisons(cond) {
+            if let (Some(a), Some(b)) = (visitor.key_index(left), visitor.key_index(right)) {
+                // `require_keys_neq!` and `require!` guards are expanded from the handler
+                guards.push((a, b, stmt.span.source_callsite().hi()));
+            }
+        }
+    }
+    guards
+}
@@ -1,15 +1,26 @@
-use anchor_lints_utils::diag_items::{
-    is_account_info_type, is_anchor_account_loader_type, is_anchor_account_type, is_anchor_context,
-    is_anchor_interface_account_type, is_box_type,
+use anchor_lints_utils::{
+    diag_items::{
+        is_anchor_account_type, is_anchor_context, is_anchor_interface_account_type, is_box_type,
+        is_solana_pubkey_type,
+    },
+    models::AccountAliasEvidence,
+    utils::AccountsAliasAnalysis,
+};
+use clippy_utils::higher::ForLoop;
+use rustc_ast::LitKind;
+use rustc_hir::{
+    Arm, BinOpKind, Expr, ExprKind, HirId, LangItem, Pat, PatKind, Path as HirPath, QPath,
+    StructTailExpr, UnOp,
+    def::Res,
+    def_id::DefId,
+    intravisit::{Visitor, walk_expr},
 };
-use rustc_ast::tokenstream::TokenStream;
-use rustc_hir::{BinOpKind, Expr, ExprKind, Path as HirPath, QPath, UnOp, def_id::DefId};
 use rustc_lint::LateContext;
 use rustc_middle::ty::{Ty, TyKind};
-use rustc_span::Symbol;
-use std::collections::{BTreeSet, HashSet};
+use rustc_span::{ExpnKind, hygiene::MacroKind};
+use std::collections::{HashMap, HashSet};
 
-use crate::models::*;
+use crate::{contains_exit_statement, models::*};
 
 pub fn path_to_string(path: &HirPath<'_>) -> String {
     path.segments
@@ -91,281 +102,404 @@ pub fn get_accounts_def_from_context<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>)
     None
 }
 
+/// Comparisons `(left, right)` such that `left == right` makes `expr` true, e.g. all three pairs
+/// of `a == b || b == c || !(a != c)`
 pub fn extract_comparisons<'a>(expr: &'a Expr<'a>) -> Vec<(&'a Expr<'a>, &'a Expr<'a>)> {
-    let mut comparisons = Vec::new();
     match &expr.kind {
-        ExprKind::Binary(op, left, right) => {
-            if BinOpKind::Eq == op.node {
-                comparisons.push((*left, *right));
-            } else if BinOpKind::Or == op.node {
-                let mut left_comparisons = extract_comparisons(left);
-                let mut right_comparisons = extract_comparisons(right);
-                comparisons.append(&mut left_comparisons);
-                comparisons.append(&mut right_comparisons);
-            }
-        }
-        ExprKind::Unary(UnOp::Not, inner_expr) => {
-            if let ExprKind::Binary(op, left, right) = &inner_expr.kind
-                && BinOpKind::Ne == op.node
-            {
-                comparisons.push((*left, *right));
+        ExprKind::Binary(op, left, right) => match op.node {
+            BinOpKind::Eq => vec![(*left, *right)],
+            BinOpKind::Or => {
+                let mut comparisons = extract_comparisons(left);
+                comparisons.append(&mut extract_comparisons(right));
+                comparisons
             }
-            let mut inner_comparisons = extract_inequality_comparisons(inner_expr);
-            comparisons.append(&mut inner_comparisons);
-        }
-        _ => {}
+            _ => Vec::new(),
+        },
+        // De Morgan: `!(a != b && b != c)` is true as soon as one pair is equal
+        ExprKind::Unary(UnOp::Not, inner_expr) => extract_inequality_comparisons(inner_expr),
+        ExprKind::DropTemps(inner_expr) => extract_comparisons(inner_expr),
+        ExprKind::Match(scrutinee, arms, _) => extract_matches_guard_comparisons(scrutinee, arms),
+        _ => Vec::new(),
     }
-
-    comparisons
 }
 
+/// Comparisons `(left, right)` such that `left == right` makes `expr` false, e.g. all three pairs
+/// of `a != b && b != c && !(a == c)`
 pub fn extract_inequality_comparisons<'a>(expr: &'a Expr<'a>) -> Vec<(&'a Expr<'a>, &'a Expr<'a>)> {
-    let mut comparisons = Vec::new();
-
-    if let ExprKind::Binary(op, left, right) = &expr.kind {
-        if BinOpKind::Ne == op.node {
-            comparisons.push((*left, *right));
-        } else if BinOpKind::And == op.node {
-            let mut left_comparisons = extract_inequality_comparisons(left);
-            let mut right_comparisons = extract_inequality_comparisons(right);
-            comparisons.append(&mut left_comparisons);
-            comparisons.append(&mut right_comparisons);
-        }
+    match &expr.kind {
+        ExprKind::Binary(op, left, right) => match op.node {
+            BinOpKind::Ne => vec![(*left, *right)],
+            BinOpKind::And => {
+                let mut comparisons = extract_inequality_comparisons(left);
+                comparisons.append(&mut extract_inequality_comparisons(right));
+                comparisons
+            }
+            _ => Vec::new(),
+        },
+        // De Morgan: `!(a == b || b == c)` is false as soon as one pair is equal
+        ExprKind::Unary(UnOp::Not, inner_expr) => extract_comparisons(inner_expr),
+        ExprKind::DropTemps(inner_expr) => extract_inequality_comparisons(inner_expr),
+        _ => Vec::new(),
     }
+}
 
-    comparisons
+/// Comparisons `(left, right)` such that `left == right` fails the `assert_ne!(a, b)`,
+/// `assert_eq!(cond, true)` or `assert_eq!(cond, false)` that `expr` is expanded from, e.g.
+/// `(a.key(), b.key())` for `assert_eq!(a.key() != b.key(), true)`
+pub fn extract_assert_comparisons<'a>(expr: &'a Expr<'a>) -> Vec<(&'a Expr<'a>, &'a Expr<'a>)> {
+    // Both expand to `match (&left, &right) { (left_val, right_val) => if .. { panic } }`
+    let ExpnKind::Macro(MacroKind::Bang, name) = expr.span.ctxt().outer_expn_data().kind else {
+        return Vec::new();
+    };
+    let ExprKind::Match(scrutinee, _, _) = expr.kind else {
+        return Vec::new();
+    };
+    let ExprKind::Tup([left, right]) = scrutinee.kind else {
+        return Vec::new();
+    };
+    let (left, right) = (peel_addr_of(left), peel_addr_of(right));
+    match name.as_str() {
+        "assert_ne" => vec![(left, right)],
+        "assert_eq" => {
+            let (cond, expected) = match (bool_lit_value(left), bool_lit_value(right)) {
+                (None, Some(expected)) => (left, expected),
+                (Some(expected), None) => (right, expected),
+                _ => return Vec::new(),
+            };
+            // The assertion fails with the comparisons making `cond` the opposite of `expected`
+            if expected {
+                extract_inequality_comparisons(cond)
+            } else {
+                extract_comparisons(cond)
+            }
+        }
+        _ => Vec::new(),
+    }
 }
 
-/// Check if two sets of constraints match
-pub fn constraints_match(constraints_a: &[String], constraints_b: &[String]) -> bool {
-    let set_a: BTreeSet<_> = constraints_a.iter().map(|s| s.trim()).collect();
-    let set_b: BTreeSet<_> = constraints_b.iter().map(|s| s.trim()).collect();
-    if set_a != set_b {
-        return false;
+fn peel_addr_of<'a>(expr: &'a Expr<'a>) -> &'a Expr<'a> {
+    match expr.kind {
+        ExprKind::AddrOf(_, _, inner) => inner,
+        _ => expr,
     }
-    true
 }
 
-/// Unwrap Box<T> to get T, handling nested Boxes recursively.
-pub fn unwrap_box_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
-    if let TyKind::Adt(_adt_def, substs) = ty.kind() {
-        if is_box_type(cx.tcx, ty) {
-            let inner = substs.type_at(0);
-            // Recursively unwrap nested boxed types
-            return unwrap_box_type(cx, inner);
-        }
+fn bool_lit_value(expr: &Expr<'_>) -> Option<bool> {
+    match &expr.kind {
+        ExprKind::Lit(lit) => match lit.node {
+            LitKind::Bool(value) => Some(value),
+            _ => None,
+        },
+        _ => None,
     }
-    ty
 }
 
-/// Parse a constraint string like "user_a.key!=user_b.key" to extract account names
-pub fn parse_constraint_string(constraint: &str) -> Option<(String, String)> {
-    let constraint = constraint.trim();
+/// Comparisons in the guard of `matches!(scrutinee, _ if guard)` or
+/// `matches!(scrutinee, key if guard)`, which is true exactly when the guard is.
+/// Comparisons against the binding are read as comparisons against the scrutinee.
+fn extract_matches_guard_comparisons<'a>(
+    scrutinee: &'a Expr<'a>,
+    arms: &'a [Arm<'a>],
+) -> Vec<(&'a Expr<'a>, &'a Expr<'a>)> {
+    let [arm, fallback] = arms else {
+        return Vec::new();
+    };
+    let Some(guard) = arm.guard else {
+        return Vec::new();
+    };
+    if !is_bool_lit(arm.body, true) || !is_bool_lit(fallback.body, false) {
+        return Vec::new();
+    }
+    let binding = match arm.pat.kind {
+        PatKind::Wild => None,
+        PatKind::Binding(_, hir_id, _, None) => Some(hir_id),
+        // Refutable patterns also have to match for the guard to count
+        _ => return Vec::new(),
+    };
+
+    let resolve_binding = |expr: &'a Expr<'a>| -> &'a Expr<'a> {
+        if let ExprKind::Path(QPath::Resolved(None, path)) = &expr.kind
+            && let Res::Local(hir_id) = path.res
+            && Some(hir_id) == binding
+        {
+            return scrutinee;
+        }
+        expr
+    };
+    extract_comparisons(guard)
+        .into_iter()
+        .map(|(left, right)| (resolve_binding(left), resolve_binding(right)))
+        .collect()
+}
 
-    // Find the != operator
-    if let Some(neq_pos) = constraint.find("!=") {
-        let left = &constraint[..neq_pos].trim();
-        let right = &constraint[neq_pos + 2..].trim();
+fn is_bool_lit(expr: &Expr<'_>, value: bool) -> bool {
+    matches!(&expr.kind, ExprKind::Lit(lit) if lit.node == LitKind::Bool(value))
+}
 
-        let acc1 = left.split('.').next()?.trim();
+/// Accounts of the context collected in an array or a `vec!`, e.g. `user_a` and `user_b` for
+/// `[&ctx.accounts.user_a, &ctx.accounts.user_b]` or `vec![ctx.accounts.user_a.key(), ..]`
+pub fn collection_account_names(expr: &Expr<'_>) -> Option<Vec<String>> {
+    let mut current = expr;
+    let elements = loop {
+        match current.kind {
+            ExprKind::Array(elements) => break elements,
+            ExprKind::AddrOf(_, _, inner) | ExprKind::MethodCall(_, inner, [], _) => {
+                current = inner
+            }
+            // `vec![..]` expands to `<[_]>::into_vec(box_new([..]))`
+            ExprKind::Call(_, [inner]) => current = inner,
+            _ => return None,
+        }
+    };
+    let names: Vec<String> = elements
+        .iter()
+        .filter_map(|element| {
+            let mut element = element;
+            while let ExprKind::AddrOf(_, _, inner) | ExprKind::Unary(UnOp::Deref, inner) =
+                element.kind
+            {
+                element = inner;
+            }
+            let chain = extract_field_chain(element)?;
+            (chain.len() == 3 && chain[1] == "accounts").then(|| chain[2].clone())
+        })
+        .collect();
+    (names.len() > 1).then_some(names)
+}
 
-        let acc2 = right.split('.').next()?.trim();
+/// `a:b` pairs of the accounts of a collection that a pairwise loop over it keeps distinct, e.g.
+/// `for (i, a) in accounts.iter().enumerate() { for b in &accounts[i + 1..] {
+/// require!(a.key() != b.key(), ..) } }`, where `collections` holds the accounts of each local
+/// collection. The guard must compare the keys of the two elements, and the inner loop must
+/// cover every element after the outer one, e.g. `&accounts[i + 1..]`, `.skip(i + 1)` or a full
+/// second pass.
+pub fn pairwise_loop_guarded_accounts<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+    collections: &HashMap<HirId, Vec<String>>,
+) -> Vec<String> {
+    let Some(outer) = ForLoop::hir(expr) else {
+        return Vec::new();
+    };
+    let Some(collection) = collection_local(outer.arg) else {
+        return Vec::new();
+    };
+    let Some(accounts) = collections.get(&collection) else {
+        return Vec::new();
+    };
+    let (outer_index, outer_bindings) = loop_bindings(outer.pat, outer.arg);
+
+    let guarded = any_expr(outer.body, |inner_expr| {
+        let Some(inner) = ForLoop::hir(inner_expr) else {
+            return false;
+        };
+        if collection_local(inner.arg) != Some(collection) || !covers_tail(inner.arg, outer_index) {
+            return false;
+        }
+        let (_, inner_bindings) = loop_bindings(inner.pat, inner.arg);
+        // The guard compares the key of the outer element with the key of the inner one
+        let compares_elements = |(left, right): (&Expr<'_>, &Expr<'_>)| match (
+            element_key_binding(cx, left),
+            element_key_binding(cx, right),
+        ) {
+            (Some(left), Some(right)) => {
+                (outer_bindings.contains(&left) && inner_bindings.contains(&right))
+                    || (outer_bindings.contains(&right) && inner_bindings.contains(&left))
+            }
+            _ => false,
+        };
+        any_expr(inner.body, |guard| {
+            if let ExprKind::If(cond, then_block, _) = guard.kind
+                && contains_exit_statement(then_block, cx)
+                && extract_comparisons(cond).into_iter().any(compares_elements)
+            {
+                return true;
+            }
+            extract_assert_comparisons(guard)
+                .into_iter()
+                .any(compares_elements)
+        })
+    });
+    if !guarded {
+        return Vec::new();
+    }
 
-        if !acc1.is_empty() && !acc2.is_empty() {
-            return Some((acc1.to_string(), acc2.to_string()));
+    let mut pairs = Vec::new();
+    for first in accounts {
+        for second in accounts {
+            if first != second {
+                pairs.push(format!("{}:{}", first, second));
+            }
         }
     }
+    pairs
+}
 
-    None
+/// The local collection a loop iterates, through borrows, slicing and iterator adapters, e.g.
+/// `accounts` for `accounts.iter().enumerate()` or `&accounts[i + 1..]`
+fn collection_local(expr: &Expr<'_>) -> Option<HirId> {
+    let mut current = expr;
+    loop {
+        match current.kind {
+            ExprKind::AddrOf(_, _, inner)
+            | ExprKind::Unary(UnOp::Deref, inner)
+            | ExprKind::Index(inner, _, _)
+            | ExprKind::MethodCall(_, inner, _, _) => current = inner,
+            ExprKind::Path(QPath::Resolved(None, path)) => {
+                return match path.res {
+                    Res::Local(hir_id) => Some(hir_id),
+                    _ => None,
+                };
+            }
+            _ => return None,
+        }
+    }
 }
 
-/// Extract PDA + account constraints from an anchor accounts struct.
-/// Return a vector of has_one constraint accounts
-pub fn extract_account_constraints<'tcx>(
-    cx: &LateContext<'tcx>,
-    account_field: &rustc_middle::ty::FieldDef,
-    has_one_constraint_accounts: &mut Vec<String>,
-) -> AccountConstraint {
-    let mut account_constraints: AccountConstraint = AccountConstraint::new();
-    let tcx = cx.tcx;
-    let attrs = tcx.get_all_attrs(account_field.did);
-    let mut last_ident_seeds: bool = false;
-    let mut last_ident_constraint: bool = false;
-    let mut latest_account_attribute = String::new();
-    let mut latest_account_constraint = String::new();
-
-    let mut constraints: Vec<String> = Vec::new();
-    for attr in attrs {
-        match attr {
-            rustc_hir::Attribute::Parsed(_) => {
-                // Anchor's #[account(...)] attributes are Unparsed
-                // but kept for completeness
+/// Check if a loop over `arg` visits every element after the one at `outer_index`, e.g.
+/// `&accounts[i + 1..]` or `accounts.iter().skip(i + 1)`, or every element of the collection
+fn covers_tail(arg: &Expr<'_>, outer_index: Option<HirId>) -> bool {
+    let mut current = arg;
+    loop {
+        match current.kind {
+            ExprKind::AddrOf(_, _, inner) | ExprKind::Unary(UnOp::Deref, inner) => current = inner,
+            ExprKind::MethodCall(segment, receiver, [], _)
+                if matches!(
+                    segment.ident.as_str(),
+                    "iter" | "iter_mut" | "into_iter" | "copied" | "cloned" | "enumerate"
+                ) =>
+            {
+                current = receiver
+            }
+            ExprKind::MethodCall(segment, receiver, [skipped], _)
+                if segment.ident.as_str() == "skip" && is_next_index(skipped, outer_index) =>
+            {
+                current = receiver
             }
-            rustc_hir::Attribute::Unparsed(_) => {
-                let attr_item = attr.get_normal_item();
-                if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                    delim_args.tokens.iter().for_each(|token| match token {
-                        rustc_ast::tokenstream::TokenTree::Token(token, _) => match token.kind {
-                            rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                if ident == Symbol::intern("mut") {
-                                    account_constraints.mutable = true;
-                                } else if ident == Symbol::intern("seeds") {
-                                    last_ident_seeds = true;
-                                } else if ident == Symbol::intern("constraint") {
-                                    last_ident_constraint = true;
-                                } else if last_ident_constraint {
-                                    latest_account_constraint =
-                                        latest_account_constraint.clone() + &ident.to_string();
-                                } else {
-                                    latest_account_attribute =
-                                        latest_account_attribute.clone() + &ident.to_string();
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Comma => {
-                                if last_ident_constraint {
-                                    last_ident_constraint = false;
-
-                                    if !latest_account_constraint.is_empty() {
-                                        constraints.push(latest_account_constraint.clone());
-                                        latest_account_constraint = String::new();
-                                    }
-                                } else if last_ident_seeds {
-                                    last_ident_seeds = false;
-                                }
-                                if !latest_account_attribute.is_empty() {
-                                    if latest_account_attribute != "bump" {
-                                        check_has_one_constraint(
-                                            &latest_account_attribute,
-                                            has_one_constraint_accounts,
-                                        );
-
-                                        account_constraints
-                                            .attributes
-                                            .push(latest_account_attribute.clone());
-                                    }
-                                    latest_account_attribute = String::new();
-                                }
-                            }
-
-                            rustc_ast::token::TokenKind::Dot => {
-                                push_if(last_ident_constraint, &mut latest_account_constraint, ".");
-                            }
-                            rustc_ast::token::TokenKind::Ne => {
-                                push_if(
-                                    last_ident_constraint,
-                                    &mut latest_account_constraint,
-                                    "!=",
-                                );
-                            }
-                            rustc_ast::token::TokenKind::Eq => {
-                                push_if(
-                                    !latest_account_attribute.is_empty(),
-                                    &mut latest_account_attribute,
-                                    "=",
-                                );
-                            }
-                            rustc_ast::token::TokenKind::At => {
-                                push_if(
-                                    !latest_account_attribute.is_empty(),
-                                    &mut latest_account_attribute,
-                                    "@",
-                                );
-                            }
-                            rustc_ast::token::TokenKind::PathSep => {
-                                push_if(
-                                    !latest_account_attribute.is_empty(),
-                                    &mut latest_account_attribute,
-                                    "::",
-                                );
-                            }
-                            _ => {
-                                if last_ident_seeds {
-                                    last_ident_seeds = false;
-                                }
-                                if last_ident_constraint {
-                                    last_ident_constraint = false;
-                                }
-                            }
-                        },
-                        rustc_ast::tokenstream::TokenTree::Delimited(_, _, _, token_stream) => {
-                            account_constraints
-                                .seeds
-                                .extend(recursively_extract_seeds(token_stream, last_ident_seeds));
-                        }
-                    });
+            ExprKind::Index(base, index, _) => {
+                let covered = match index.kind {
+                    ExprKind::Struct(
+                        QPath::LangItem(LangItem::RangeFrom, _),
+                        [start],
+                        StructTailExpr::None,
+                    ) => is_next_index(start.expr, outer_index),
+                    ExprKind::Struct(QPath::LangItem(LangItem::RangeFull, _), [], _) => true,
+                    _ => false,
+                };
+                if !covered {
+                    return false;
                 }
+                current = base
             }
-        };
-        if !latest_account_attribute.is_empty() && latest_account_attribute != "bump" {
-            check_has_one_constraint(&latest_account_attribute, has_one_constraint_accounts);
-            account_constraints
-                .attributes
-                .push(latest_account_attribute.clone());
+            ExprKind::Path(QPath::Resolved(None, _)) => return true,
+            _ => return false,
         }
+    }
+}
+
+/// Check if `expr` is the index following `index`, e.g. `i + 1`
+fn is_next_index(expr: &Expr<'_>, index: Option<HirId>) -> bool {
+    let ExprKind::Binary(op, left, right) = expr.kind else {
+        return false;
+    };
+    let is_one = matches!(&right.kind, ExprKind::Lit(lit)
+        if matches!(lit.node, LitKind::Int(value, _) if value.get() == 1));
+    let is_index = matches!(left.kind, ExprKind::Path(QPath::Resolved(None, path))
+        if index.is_some_and(|index| path.res == Res::Local(index)));
+    op.node == BinOpKind::Add && is_one && is_index
+}
 
-        if !latest_account_constraint.is_empty() {
-            constraints.push(latest_account_constraint.clone());
+/// The loop binding whose key `expr` reads, e.g. `a` for `a.key()` or `*a.key`, or `a` itself
+/// when the collection holds the keys, e.g. `[user_a.key(), user_b.key()]`
+fn element_key_binding(cx: &LateContext<'_>, expr: &Expr<'_>) -> Option<HirId> {
+    let mut current = expr;
+    let mut reads_key = false;
+    loop {
+        match current.kind {
+            ExprKind::AddrOf(_, _, inner) | ExprKind::Unary(UnOp::Deref, inner) => current = inner,
+            ExprKind::MethodCall(segment, receiver, [], _)
+                if !reads_key && segment.ident.as_str() == "key" =>
+            {
+                reads_key = true;
+                current = receiver
+            }
+            ExprKind::Field(base, field) if !reads_key && field.as_str() == "key" => {
+                reads_key = true;
+                current = base
+            }
+            ExprKind::Path(QPath::Resolved(None, path)) => {
+                let Res::Local(hir_id) = path.res else {
+                    return None;
+                };
+                let is_key = reads_key
+                    || is_solana_pubkey_type(
+                        cx.tcx,
+                        cx.typeck_results().expr_ty(current).peel_refs(),
+                    );
+                return is_key.then_some(hir_id);
+            }
+            _ => return None,
         }
     }
-    if !constraints.is_empty() {
-        for constraint_str in &constraints {
-            // Parse "user_a.key!=user_b.key" to extract account names
-            if let Some((acc1, acc2)) = parse_constraint_string(constraint_str) {
-                account_constraints
-                    .constraints
-                    .push(format!("{}:{}", acc1, acc2));
-                account_constraints
-                    .constraints
-                    .push(format!("{}:{}", acc2, acc1));
-            }
+}
+
+/// The index and the element bindings of a loop pattern, e.g. `i` and `a` for
+/// `for (i, a) in accounts.iter().enumerate()`
+fn loop_bindings(pat: &Pat<'_>, arg: &Expr<'_>) -> (Option<HirId>, Vec<HirId>) {
+    let mut element = pat;
+    let mut index = None;
+    if let ExprKind::MethodCall(segment, _, [], _) = arg.kind
+        && segment.ident.as_str() == "enumerate"
+        && let PatKind::Tuple([index_pat, element_pat], _) = pat.kind
+    {
+        if let PatKind::Binding(_, hir_id, _, None) = index_pat.kind {
+            index = Some(hir_id);
         }
+        element = element_pat;
     }
-    account_constraints
+    let mut bindings = Vec::new();
+    element.each_binding(|_, hir_id, _, _| bindings.push(hir_id));
+    (index, bindings)
 }
 
-/// Extract PDA seeds from a token stream
-pub fn recursively_extract_seeds(
-    token_stream: &TokenStream,
-    last_ident_seeds: bool,
-) -> Vec<String> {
-    let mut seeds = Vec::new();
-    token_stream
-        .iter()
-        .for_each(|delimited_token_tree| match delimited_token_tree {
-            rustc_ast::tokenstream::TokenTree::Token(delimited_token, _) => {
-                match delimited_token.kind {
-                    rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                        if last_ident_seeds {
-                            seeds.push(ident.to_string());
-                        }
-                    }
-                    rustc_ast::token::TokenKind::Dot => {
-                        if last_ident_seeds {
-                            seeds.push(".".to_string());
-                        }
-                    }
-                    rustc_ast::token::TokenKind::Literal(literal, ..) => {
-                        if last_ident_seeds {
-                            match &literal.kind {
-                                rustc_ast::token::LitKind::ByteStr => {
-                                    seeds.push(literal.symbol.to_string());
-                                }
-                                rustc_ast::token::LitKind::Str => {
-                                    seeds.push(literal.symbol.to_string());
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+/// Check if `expr` or an expression nested in it satisfies `predicate`
+fn any_expr<'tcx>(expr: &'tcx Expr<'tcx>, predicate: impl FnMut(&'tcx Expr<'tcx>) -> bool) -> bool {
+    struct AnyExpr<F> {
+        predicate: F,
+        found: bool,
+    }
+
+    impl<'tcx, F: FnMut(&'tcx Expr<'tcx>) -> bool> Visitor<'tcx> for AnyExpr<F> {
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            if self.found {
+                return;
             }
-            rustc_ast::tokenstream::TokenTree::Delimited(_, _, _, token_stream) => {
-                let nested_seeds = recursively_extract_seeds(token_stream, last_ident_seeds);
-                seeds.push(nested_seeds.join(""));
+            if (self.predicate)(expr) {
+                self.found = true;
+                return;
             }
-        });
-    seeds
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = AnyExpr {
+        predicate,
+        found: false,
+    };
+    visitor.visit_expr(expr);
+    visitor.found
+}
+
+/// Unwrap Box<T> to get T, handling nested Boxes recursively.
+pub fn unwrap_box_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+    if let TyKind::Adt(_adt_def, substs) = ty.kind() {
+        if is_box_type(cx.tcx, ty) {
+            let inner = substs.type_at(0);
+            // Recursively unwrap nested boxed types
+            return unwrap_box_type(cx, inner);
+        }
+    }
+    ty
 }
 
 pub fn should_report_duplicate(
@@ -373,13 +507,10 @@ pub fn should_report_duplicate(
     first: &AccountDetails,
     second: &AccountDetails,
     reported_pairs: &mut HashSet<(DefId, String, String)>,
-    conditional: &[String],
-    has_one_constraint_accounts: &[String],
+    alias_analysis: &AccountsAliasAnalysis,
 ) -> bool {
-    // Skip if one of the accounts is included in the has_one constraint accounts
-    if has_one_constraint_accounts.contains(&first.account_name)
-        || has_one_constraint_accounts.contains(&second.account_name)
-    {
+    // Skip accounts that constraints keep from aliasing
+    if !alias_analysis.accounts_may_alias(&first.account_name, &second.account_name) {
         return false;
     }
     // Deduplicate per struct
@@ -388,34 +519,16 @@ pub fn should_report_duplicate(
         first.account_name.clone(),
         second.account_name.clone(),
     );
-    if !reported_pairs.insert(canonical_pair) {
-        return false;
-    }
-
-    // Attributes must match (e.g. token::mint bindings)
-    if !constraints_match(&first.attributes, &second.attributes) {
-        return false;
-    }
-
-    // Seeds must match (unless one side has none)
-    if (!first.seeds.is_empty() || !second.seeds.is_empty()) && first.seeds != second.seeds {
-        return false;
-    }
-
-    // Skip if there’s already an explicit constraint in the context
-    let key = format!("{}:{}", first.account_name, second.account_name);
-    let reverse = format!("{}:{}", second.account_name, first.account_name);
-    if conditional.contains(&key) || conditional.contains(&reverse) {
-        return false;
-    }
-
-    true
+    reported_pairs.insert(canonical_pair)
 }
 
+/// Check if an account can be written by the handler: an `Account` or `InterfaceAccount` with
+/// the `mut` constraint, which Anchor persists on exit, or written in the handler body
 pub fn is_anchor_mutable_account<'tcx>(
     cx: &LateContext<'tcx>,
     ty: Ty<'tcx>,
-    constraints: &AccountConstraint,
+    evidence: &AccountAliasEvidence,
+    mutated_accounts: &HashSet<String>,
 ) -> bool {
     let is_supported =
         is_anchor_account_type(cx.tcx, ty) || is_anchor_interface_account_type(cx.tcx, ty);
@@ -424,29 +537,6 @@ pub fn is_anchor_mutable_account<'tcx>(
         return false;
     }
 
-    // Account<'info, T> always mutable; InterfaceAccount only when #[account(mut)]
-    if (is_anchor_account_type(cx.tcx, ty) || is_anchor_account_loader_type(cx.tcx, ty))
-        && !is_account_info_type(cx.tcx, ty)
-    {
-        return true;
-    }
-
-    constraints.mutable
-}
-
-/// Extract the account name from a has_one constraint
-fn check_has_one_constraint(constraint: &str, has_one_constraint_accounts: &mut Vec<String>) {
-    if let Some(rest) = constraint.split_once("has_one=") {
-        let account = rest.1.split('@').next().unwrap().trim().to_string();
-
-        if !account.is_empty() {
-            has_one_constraint_accounts.push(account);
-        }
-    }
-}
-
-fn push_if(check_if: bool, target: &mut String, value: &str) {
-    if check_if {
-        target.push_str(value);
-    }
+    // Accounts without `mut` are not persisted, so they only alias mutable state when written
+    evidence.mutable || mutated_accounts.contains(&evidence.account_name)
 }
@@ -7,7 +7,7 @@ declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 pub mod duplicate_mutable_accounts_tests {
     use super::*;
 
-    // Unsafe: writes two mutable accounts with no guard.
+    // Unsafe: writes two accounts with no guard, written accounts are mutable without `mut`.
     pub fn write_without_guard(
         ctx: Context<UnsafeDuplicateAccounts>,
         a: u64,
@@ -54,6 +54,170 @@ pub mod duplicate_mutable_accounts_tests {
         Ok(())
     }
 
+    // Safe: `assert_ne!` panics when the keys are equal.
+    pub fn write_with_assert_ne_guard(
+        ctx: Context<AssertNeGuardedAccounts>,
+        a: u64,
+        b: u64,
+    ) -> Result<()> {
+        assert_ne!(ctx.accounts.user_a.key(), ctx.accounts.user_b.key());
+
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+
+        user_a.data = a;
+        user_b.data = b;
+        Ok(())
+    }
+
+    // Safe: `assert_eq!` on the keys being different panics when they are equal.
+    pub fn write_with_assert_eq_guard(
+        ctx: Context<AssertEqGuardedAccounts>,
+        a: u64,
+        b: u64,
+    ) -> Result<()> {
+        assert_eq!(ctx.accounts.user_a.key() != ctx.accounts.user_b.key(), true);
+
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+
+        user_a.data = a;
+        user_b.data = b;
+        Ok(())
+    }
+
+    // Safe: `require_neq!` returns an error when the keys are equal.
+    pub fn write_with_require_neq_guard(
+        ctx: Context<RequireNeqGuardedAccounts>,
+        a: u64,
+        b: u64,
+    ) -> Result<()> {
+        require_neq!(
+            ctx.accounts.user_a.key(),
+            ctx.accounts.user_b.key(),
+            CustomError::DuplicateAccounts
+        );
+
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+
+        user_a.data = a;
+        user_b.data = b;
+        Ok(())
+    }
+
+    // Safe: `require_keys_neq!` returns an error when the keys are equal.
+    pub fn write_with_require_keys_neq_guard(
+        ctx: Context<RequireKeysNeqGuardedAccounts>,
+        a: u64,
+        b: u64,
+    ) -> Result<()> {
+        require_keys_neq!(
+            ctx.accounts.user_a.key(),
+            ctx.accounts.user_b.key(),
+            CustomError::DuplicateAccounts
+        );
+
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+
+        user_a.data = a;
+        user_b.data = b;
+        Ok(())
+    }
+
+    // Safe: a pairwise loop compares the keys of every two accounts.
+    pub fn write_with_pairwise_loop_guard(
+        ctx: Context<LoopGuardedAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        let accounts = [
+            &ctx.accounts.user_a,
+            &ctx.accounts.user_b,
+            &ctx.accounts.user_c,
+        ];
+        for (i, first) in accounts.iter().enumerate() {
+            for second in &accounts[i + 1..] {
+                require!(first.key() != second.key(), CustomError::DuplicateAccounts);
+            }
+        }
+
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;
+        ctx.accounts.user_c.data = c;
+        Ok(())
+    }
+
+    // Unsafe: `user_c` is left out of the pairwise loop.
+    pub fn write_with_partial_loop_guard(
+        ctx: Context<PartialLoopGuardedAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        let accounts = [ctx.accounts.user_a.key(), ctx.accounts.user_b.key()];
+        for (i, first) in accounts.iter().enumerate() {
+            for second in accounts.iter().skip(i + 1) {
+                require!(first != second, CustomError::DuplicateAccounts);
+            }
+        }
+
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;
+        ctx.accounts.user_c.data = c;
+        Ok(())
+    }
+
+    // Unsafe: the pairwise loop compares the data of the accounts, not their keys.
+    pub fn write_with_data_loop_guard(
+        ctx: Context<DataLoopGuardedAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        let accounts = [
+            &ctx.accounts.user_a,
+            &ctx.accounts.user_b,
+            &ctx.accounts.user_c,
+        ];
+        for (i, first) in accounts.iter().enumerate() {
+            for second in &accounts[i + 1..] {
+                require!(first.data != second.data, CustomError::DuplicateAccounts);
+            }
+        }
+
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;
+        ctx.accounts.user_c.data = c;
+        Ok(())
+    }
+
+    // Unsafe: the inner loop only covers the first account.
+    pub fn write_with_prefix_loop_guard(
+        ctx: Context<PrefixLoopGuardedAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        let accounts = [
+            &ctx.accounts.user_a,
+            &ctx.accounts.user_b,
+            &ctx.accounts.user_c,
+        ];
+        for first in accounts.iter() {
+            for second in &accounts[..1] {
+                require!(first.key() != second.key(), CustomError::DuplicateAccounts);
+            }
+        }
+
+        ctx.accounts.user_a.data = a;
+        ctx.accounts.user_b.data = b;
+        ctx.accounts.user_c.data = c;
+        Ok(())
+    }
+
     // Safe: compile-time constraint keeps accounts unique.
     pub fn write_with_struct_constraint(
         ctx: Context<ConstraintGuardedAccounts>,
@@ -154,7 +318,7 @@ pub mod duplicate_mutable_accounts_tests {
         Ok(())
     }
 
-    // Safe: Account<'info, T> is inherently mutable
+    // Safe: the duplicate is acknowledged with `#[allow]`
     #[allow(duplicate_mutable_accounts)]
     pub fn write_ignored_duplicate(
         ctx: Context<AllowAnnotatedAccounts>,
@@ -330,6 +494,26 @@ pub mod duplicate_mutable_accounts_tests {
         Ok(())
     }
 
+    // Unsafe: token accounts constrained to the same mint can be the same account.
+    pub fn touch_token_accounts_same_mint(ctx: Context<TokenAccountsSameMint>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault_token_account;
+        let recipient = &mut ctx.accounts.recipient_token_account;
+
+        let _ = vault.amount;
+        let _ = recipient.amount;
+        Ok(())
+    }
+
+    // Unsafe: only one token account is constrained to a mint.
+    pub fn touch_token_accounts_one_mint(ctx: Context<TokenAccountsOneMint>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault_token_account;
+        let recipient = &mut ctx.accounts.recipient_token_account;
+
+        let _ = vault.amount;
+        let _ = recipient.amount;
+        Ok(())
+    }
+
     // Safe: PDAs have distinct seed prefixes.
     pub fn write_state_accounts_different_seeds(
         ctx: Context<StateAccountsDifferentSeeds>,
@@ -395,7 +579,8 @@ pub mod duplicate_mutable_accounts_tests {
         Ok(())
     }
 
-    // Unsafe: four mutable users with no protections.
+    // Unsafe: `user_a` and `user_b` are written with no protections, `user_c` and `user_d` are
+    // only borrowed and have no `mut`, so they are not mutable.
     pub fn write_multiple_accounts_no_guard(
         ctx: Context<UnsafeMultipleAccounts>,
         a: u64,
@@ -420,6 +605,27 @@ pub mod duplicate_mutable_accounts_tests {
         Ok(())
     }
 
+    // Two vaults, each pinned by `has_one` on a different market
+    pub fn touch_vaults_of_two_markets(ctx: Context<VaultsOfTwoMarkets>) -> Result<()> {
+        let _ = ctx.accounts.market_base_vault.amount;
+        let _ = ctx.accounts.market_quote_vault.amount;
+        Ok(())
+    }
+
+    // Both vaults are pinned by `has_one`, on two markets that can be the same account
+    pub fn touch_vaults_of_aliasing_markets(ctx: Context<VaultsOfAliasingMarkets>) -> Result<()> {
+        let _ = ctx.accounts.market_base_vault.amount;
+        let _ = ctx.accounts.market_quote_vault.amount;
+        Ok(())
+    }
+
+    // Only one vault is pinned by `has_one`, the other can be passed as the same account
+    pub fn touch_vaults_one_has_one(ctx: Context<VaultsOneHasOne>) -> Result<()> {
+        let _ = ctx.accounts.market_base_vault.amount;
+        let _ = ctx.accounts.market_quote_vault.amount;
+        Ok(())
+    }
+
     // Tick arrays with constraints that don't differentiate accounts - raydium-clmm
     pub fn increase_liquidity_with_tick_arrays(
         ctx: Context<IncreaseLiquidityWithTickArrays>,
@@ -428,6 +634,152 @@ pub mod duplicate_mutable_accounts_tests {
         let _upper = &ctx.accounts.tick_array_upper;
         Ok(())
     }
+
+    // Safe: accounts pinned to different addresses can never alias
+    pub fn write_with_distinct_addresses(
+        ctx: Context<DistinctAddressAccounts>,
+        a: u64,
+        b: u64,
+    ) -> Result<()> {
+        ctx.accounts.vault_a.data = a;
+        ctx.accounts.vault_b.data = b;
+        Ok(())
+    }
+
+    // Unsafe: accounts pinned to the same address are always the same account
+    pub fn write_with_same_address(ctx: Context<SameAddressAccounts>, a: u64, b: u64) -> Result<()> {
+        ctx.accounts.vault_a.data = a;
+        ctx.accounts.vault_b.data = b;
+        Ok(())
+    }
+
+    // Safe: negated-or guard, !(a == b || b == c || a == c), covers all three pairs.
+    pub fn write_three_accounts_negated_or_guard(
+        ctx: Context<TripleNegatedOrAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        require!(
+            !(ctx.accounts.user_a.key() == ctx.accounts.user_b.key()
+                || ctx.accounts.user_b.key() == ctx.accounts.user_c.key()
+                || ctx.accounts.user_a.key() == ctx.accounts.user_c.key()),
+            CustomError::DuplicateAccounts
+        );
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+        let user_c = &mut ctx.accounts.user_c;
+
+        user_a.data = a;
+        user_b.data = b;
+        user_c.data = c;
+        Ok(())
+    }
+
+    // Safe: neither account has `mut` nor is written, reading both is fine if they alias.
+    pub fn read_accounts_without_mut(ctx: Context<ReadOnlyAccounts>) -> Result<u64> {
+        let user_a = &ctx.accounts.user_a;
+        let user_b = &ctx.accounts.user_b;
+
+        Ok(user_a.data + user_b.data)
+    }
+
+    // Unsafe: `mut` accounts are persisted on exit even if the handler only reads them.
+    pub fn read_mut_accounts(ctx: Context<ReadOnlyMutAccounts>) -> Result<u64> {
+        Ok(ctx.accounts.user_a.data + ctx.accounts.user_b.data)
+    }
+
+    // Unsafe: accounts without `mut` written directly through the context.
+    pub fn write_accounts_directly(ctx: Context<DirectlyWrittenAccounts>, a: u64) -> Result<()> {
+        ctx.accounts.user_a.data += a;
+        ctx.accounts.user_b.data += a;
+        Ok(())
+    }
+
+    // Unsafe: accounts without `mut` written by a helper taking them mutably.
+    pub fn write_accounts_in_helper(ctx: Context<HelperWrittenAccounts>, a: u64) -> Result<()> {
+        set_data(&mut ctx.accounts.user_a, a);
+        set_data(&mut ctx.accounts.user_b, a);
+        Ok(())
+    }
+
+    // Safe: `matches!` guard comparing the scrutinee binding covers all three pairs.
+    pub fn write_three_accounts_matches_guard(
+        ctx: Context<TripleMatchesAccounts>,
+        a: u64,
+        b: u64,
+        c: u64,
+    ) -> Result<()> {
+        require!(
+            !matches!(
+                ctx.accounts.user_a.key(),
+                key if key == ctx.accounts.user_b.key()
+                    || key == ctx.accounts.user_c.key()
+                    || ctx.accounts.user_b.key() == ctx.accounts.user_c.key()
+            ),
+            CustomError::DuplicateAccounts
+        );
+        let user_a = &mut ctx.accounts.user_a;
+        let user_b = &mut ctx.accounts.user_b;
+        let user_c = &mut ctx.accounts.user_c;
+
+        user_a.data = a;
+        user_b.data = b;
+        user_c.data = c;
+        Ok(())
+    }
+
+    // Unsafe: swaps the data of two remaining accounts that may be the same account.
+    pub fn swap_remaining_accounts(ctx: Context<RemainingAccountsSwap>) -> Result<()> {
+        let first = ctx.remaining_accounts[0].try_borrow_data()?.to_vec();
+        let second = ctx.remaining_accounts[1].try_borrow_data()?.to_vec();
+
+        ctx.remaining_accounts[0].try_borrow_mut_data()?.copy_from_slice(&second); // [duplicate_account]
+        ctx.remaining_accounts[1].try_borrow_mut_data()?.copy_from_slice(&first);
+        Ok(())
+    }
+
+    // Safe: the keys of the remaining accounts are compared before the swap.
+    pub fn swap_remaining_accounts_with_guard(ctx: Context<RemainingAccountsSwap>) -> Result<()> {
+        require!(
+            ctx.remaining_accounts[0].key() != ctx.remaining_accounts[1].key(),
+            CustomError::DuplicateAccounts
+        );
+        let first = ctx.remaining_accounts[0].try_borrow_data()?.to_vec();
+        let second = ctx.remaining_accounts[1].try_borrow_data()?.to_vec();
+
+        ctx.remaining_accounts[0].try_borrow_mut_data()?.copy_from_slice(&second); // [safe_account]
+        ctx.remaining_accounts[1].try_borrow_mut_data()?.copy_from_slice(&first);
+        Ok(())
+    }
+
+    // Unsafe: the lamports are moved before the keys are compared.
+    pub fn move_remaining_account_lamports_guarded_late(
+        ctx: Context<RemainingAccountsSwap>,
+        amount: u64,
+    ) -> Result<()> {
+        let source = &ctx.remaining_accounts[0];
+        let destination = &ctx.remaining_accounts[1];
+
+        **source.try_borrow_mut_lamports()? -= amount; // [duplicate_account]
+        **destination.try_borrow_mut_lamports()? += amount;
+        require!(source.key() != destination.key(), CustomError::DuplicateAccounts);
+        Ok(())
+    }
+
+    // Safe: dynamic indices are not checked, they may be equal on purpose.
+    pub fn swap_remaining_accounts_at(
+        ctx: Context<RemainingAccountsSwap>,
+        i: usize,
+        j: usize,
+    ) -> Result<()> {
+        let first = ctx.remaining_accounts[i].try_borrow_data()?.to_vec();
+        let second = ctx.remaining_accounts[j].try_borrow_data()?.to_vec();
+
+        ctx.remaining_accounts[i].try_borrow_mut_data()?.copy_from_slice(&second); // [safe_account]
+        ctx.remaining_accounts[j].try_borrow_mut_data()?.copy_from_slice(&first);
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -448,6 +800,58 @@ pub struct RequireGuardedAccounts<'info> {
     user_b: Account<'info, User>,
 }
 
+#[derive(Accounts)]
+pub struct AssertNeGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct AssertEqGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct RequireNeqGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct RequireKeysNeqGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct LoopGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>, // [safe_account]
+    user_c: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct PartialLoopGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [duplicate_account]
+    user_b: Account<'info, User>, // [duplicate_account]
+    user_c: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct DataLoopGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [duplicate_account]
+    user_b: Account<'info, User>, // [duplicate_account]
+    user_c: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct PrefixLoopGuardedAccounts<'info> {
+    user_a: Account<'info, User>, // [duplicate_account]
+    user_b: Account<'info, User>, // [duplicate_account]
+    user_c: Account<'info, User>,
+}
+
 #[derive(Accounts)]
 pub struct ConstraintGuardedAccounts<'info> {
     #[account(constraint = user_a.key() != user_b.key())]
@@ -510,6 +914,46 @@ pub struct TripleSinglePredicateAccounts<'info> {
     user_c: Account<'info, User>,
 }
 
+#[derive(Accounts)]
+pub struct TripleNegatedOrAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+    user_c: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct TripleMatchesAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+    user_c: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct ReadOnlyAccounts<'info> {
+    user_a: Account<'info, User>, // [safe_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct ReadOnlyMutAccounts<'info> {
+    #[account(mut)]
+    user_a: Account<'info, User>, // [duplicate_account]
+    #[account(mut)]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct DirectlyWrittenAccounts<'info> {
+    user_a: Account<'info, User>, // [duplicate_account]
+    user_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct HelperWrittenAccounts<'info> {
+    user_a: Account<'info, User>, // [duplicate_account]
+    user_b: Account<'info, User>,
+}
+
 #[derive(Accounts)]
 pub struct DistinctSeedAccounts<'info> {
     #[account(mut, seeds = [b"user_a", b"seed_a"], bump)]
@@ -618,6 +1062,28 @@ pub struct TokenAccountsDifferentMints<'info> {
     pub ext_token_program: Interface<'info, TokenInterface>,
 }
 
+#[derive(Accounts)]
+pub struct TokenAccountsSameMint<'info> {
+    #[account(mut, token::mint = same_mint)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>, // [duplicate_account]
+
+    #[account(mut, token::mint = same_mint)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub same_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct TokenAccountsOneMint<'info> {
+    #[account(mut, token::mint = mint)]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>, // [duplicate_account]
+
+    #[account(mut)]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
 // Real-world pattern: Multiple state accounts with different seeds
 #[derive(Accounts)]
 pub struct StateAccountsDifferentSeeds<'info> {
@@ -711,7 +1177,7 @@ pub struct TokenAccountsWithoutConstraints<'info> {
 pub struct UnsafeMultipleAccounts<'info> {
     user_a: Account<'info, User>, // [duplicate_account]
     user_b: Account<'info, User>,
-    user_c: Account<'info, Wallet>, // [duplicate_account]
+    user_c: Account<'info, Wallet>, // [safe_account]
     user_d: Account<'info, Wallet>,
 }
 
@@ -754,6 +1220,60 @@ pub struct AccountsDifferentiatedByHasOne<'info> {
     pub market_quote_vault: Account<'info, anchor_spl::token::TokenAccount>, // [safe_account]
 }
 
+// Vaults differentiated by has_one constraints on two parent accounts derived from different seeds
+#[derive(Accounts)]
+pub struct VaultsOfTwoMarkets<'info> {
+    #[account(
+        seeds = [b"market_a"],
+        bump,
+        has_one = market_base_vault @ CustomError::HasOneConstraint
+    )]
+    pub market_a: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"market_b"],
+        bump,
+        has_one = market_quote_vault @ CustomError::HasOneConstraint
+    )]
+    pub market_b: Account<'info, Market>,
+
+    #[account(mut)]
+    pub market_base_vault: Account<'info, anchor_spl::token::TokenAccount>, // [safe_account]
+
+    #[account(mut)]
+    pub market_quote_vault: Account<'info, anchor_spl::token::TokenAccount>, // [safe_account]
+}
+
+// Both vaults are bound by has_one, but the two markets can be the same account storing the same
+// vault twice
+#[derive(Accounts)]
+pub struct VaultsOfAliasingMarkets<'info> {
+    #[account(has_one = market_base_vault @ CustomError::HasOneConstraint)]
+    pub market_a: Account<'info, Market>,
+
+    #[account(has_one = market_quote_vault @ CustomError::HasOneConstraint)]
+    pub market_b: Account<'info, Market>,
+
+    #[account(mut)]
+    pub market_base_vault: Account<'info, anchor_spl::token::TokenAccount>, // [duplicate_account]
+
+    #[account(mut)]
+    pub market_quote_vault: Account<'info, anchor_spl::token::TokenAccount>,
+}
+
+// Only one vault is bound by has_one
+#[derive(Accounts)]
+pub struct VaultsOneHasOne<'info> {
+    #[account(has_one = market_base_vault @ CustomError::HasOneConstraint)]
+    pub market: Account<'info, Market>,
+
+    #[account(mut)]
+    pub market_base_vault: Account<'info, anchor_spl::token::TokenAccount>, // [duplicate_account]
+
+    #[account(mut)]
+    pub market_quote_vault: Account<'info, anchor_spl::token::TokenAccount>,
+}
+
 #[derive(Accounts)]
 pub struct IncreaseLiquidityWithTickArrays<'info> {
     #[account(mut)]
@@ -800,6 +1320,10 @@ pub struct User {
     ext_mint_authority_bump: u8,
 }
 
+fn set_data(user: &mut Account<User>, data: u64) {
+    user.data = data;
+}
+
 #[error_code]
 pub enum CustomError {
     #[msg("Duplicate accounts are found")]
@@ -808,3 +1332,27 @@ pub enum CustomError {
     #[msg("Account is included in a has_one constraint")]
     HasOneConstraint,
 }
+
+pub const VAULT_A_ADDRESS: Pubkey = Pubkey::new_from_array([1u8; 32]);
+pub const VAULT_B_ADDRESS: Pubkey = Pubkey::new_from_array([2u8; 32]);
+
+#[derive(Accounts)]
+pub struct DistinctAddressAccounts<'info> {
+    #[account(mut, address = VAULT_A_ADDRESS)]
+    pub vault_a: Account<'info, User>, // [safe_account]
+    #[account(mut, address = VAULT_B_ADDRESS)]
+    pub vault_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct SameAddressAccounts<'info> {
+    #[account(mut, address = VAULT_A_ADDRESS)]
+    pub vault_a: Account<'info, User>, // [duplicate_account]
+    #[account(mut, address = VAULT_A_ADDRESS)]
+    pub vault_b: Account<'info, User>,
+}
+
+#[derive(Accounts)]
+pub struct RemainingAccountsSwap<'info> {
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,142 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        AccountsAliasAnalysis, account_constraints::parse_account_constraints, compare_adt_def_ids,
+        seeds_match, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects accounts of different types in the same `#[derive(Accounts)]` struct that are
+    /// derived from identical PDA seeds and program.
+    ///
+    /// ### Why is this bad?
+    /// Identical seeds derive the same address, so the two accounts can never both exist: the
+    /// account initialized first occupies the address of the other, and every instruction using
+    /// both fails to deserialize one of them. Distinct account types need distinct seeds, e.g.
+    /// a prefix naming the type.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Initialize<'info> {
+    ///     #[account(init, payer = authority, space = 8 + User::INIT_SPACE, seeds = [b"state", authority.key().as_ref()], bump)]
+    ///     pub user: Account<'info, User>,
+    ///     #[account(init, payer = authority, space = 8 + Config::INIT_SPACE, seeds = [b"state", authority.key().as_ref()], bump)]
+    ///     pub config: Account<'info, Config>,
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Initialize<'info> {
+    ///     #[account(init, payer = authority, space = 8 + User::INIT_SPACE, seeds = [b"user", authority.key().as_ref()], bump)]
+    ///     pub user: Account<'info, User>,
+    ///     #[account(init, payer = authority, space = 8 + Config::INIT_SPACE, seeds = [b"config", authority.key().as_ref()], bump)]
+    ///     pub config: Account<'info, Config>,
+    ///     // ...
+    /// }
+    /// ```
+    pub DUPLICATE_SEEDS_ACROSS_DISTINCT_ACCOUNT_TYPES,
+    Warn,
+    "accounts of different types are derived from identical PDA seeds"
+}
+
+impl<'tcx> LateLintPass<'tcx> for DuplicateSeedsAcrossDistinctAccountTypes {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(DUPLICATE_SEEDS_ACROSS_DISTINCT_ACCOUNT_TYPES) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(DUPLICATE_SEEDS_ACROSS_DISTINCT_ACCOUNT_TYPES);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_account_seeds(cx, anchor_context);
+    }
+}
+
+fn analyze_account_seeds<'tcx>(cx: &LateContext<'tcx>, anchor_context: &AnchorContextInfo<'tcx>) {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+
+    let accounts_variant = adt_def.non_enum_variant();
+    let alias_analysis = AccountsAliasAnalysis::new(cx, accounts_variant);
+    let seeded_accounts: Vec<SeededAccount<'tcx>> = accounts_variant
+        .fields
+        .iter()
+        .zip(alias_analysis.fields())
+        .filter_map(|(field, evidence)| {
+            let constraints = parse_account_constraints(cx, field);
+            seeded_account(cx, field, generics, &constraints, evidence)
+        })
+        .collect();
+
+    // Report each account against the first account of another type it collides with
+    for (index, account) in seeded_accounts.iter().enumerate() {
+        let Some(colliding) = seeded_accounts[..index].iter().find(|earlier| {
+            !compare_adt_def_ids(earlier.data_ty, account.data_ty)
+                && earlier.seeds_program == account.seeds_program
+                && seeds_match(&earlier.evidence.seeds, &account.evidence.seeds)
+        }) else {
+            continue;
+        };
+
+        span_lint_and_note(
+            cx,
+            DUPLICATE_SEEDS_ACROSS_DISTINCT_ACCOUNT_TYPES,
+            account.span,
+            format!(
+                "`{}` (`{}`) is derived from the same seeds as `{}` (`{}`) — both resolve to one address, add a seed that tells the account types apart",
+                account.name,
+                data_type_name(cx, account.data_ty),
+                colliding.name,
+                data_type_name(cx, colliding.data_ty),
+            ),
+            Some(colliding.seeds_span),
+            format!("seeds of `{}` are here", colliding.name),
+        );
+    }
+}
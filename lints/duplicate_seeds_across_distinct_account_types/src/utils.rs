@@ -0,0 +1,59 @@
+use anchor_lints_utils::{
+    models::AccountAliasEvidence,
+    utils::{
+        account_constraints::{AccountConstraints, normalize_source},
+        account_data_type,
+    },
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::ty::{FieldDef, GenericArgsRef, Ty, TyKind};
+use rustc_span::Span;
+
+/// A PDA field of an accounts struct
+pub struct SeededAccount<'tcx> {
+    pub name: String,
+    pub span: Span,
+    /// Span of the `seeds = [...]` value
+    pub seeds_span: Span,
+    /// Type of the data stored in the account, e.g. `User` in `Account<'info, User>`
+    pub data_ty: Ty<'tcx>,
+    /// Source text of `seeds::program = ...`, `None` for the program itself
+    pub seeds_program: Option<String>,
+    pub evidence: AccountAliasEvidence,
+}
+
+/// Build the PDA description of a field, if it is a typed account with `seeds = [...]`
+pub fn seeded_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    field: &FieldDef,
+    generics: GenericArgsRef<'tcx>,
+    constraints: &AccountConstraints,
+    evidence: &AccountAliasEvidence,
+) -> Option<SeededAccount<'tcx>> {
+    let seeds = constraints.seeds.as_ref()?;
+    if evidence.seeds.is_empty() {
+        return None;
+    }
+    let data_ty = account_data_type(cx, field.ty(cx.tcx, generics))?;
+
+    Some(SeededAccount {
+        name: evidence.account_name.clone(),
+        span: cx.tcx.def_span(field.did),
+        seeds_span: seeds.value.span,
+        data_ty,
+        seeds_program: constraints
+            .seeds_program
+            .as_ref()
+            .map(|program| normalize_source(&program.text)),
+        evidence: evidence.clone(),
+    })
+}
+
+/// Name of an account data type for diagnostics, e.g. `User`
+pub fn data_type_name<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> String {
+    match ty.kind() {
+        TyKind::Adt(adt_def, _) => cx.tcx.item_name(adt_def.did()).to_string(),
+        _ => ty.to_string(),
+    }
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod duplicate_seeds_across_distinct_account_types_tests {
+    use super::*;
+
+    // BAD: `User` and `Config` share the seeds `[b"state", x.key()]`
+    pub fn initialize_colliding(ctx: Context<InitializeColliding>) -> Result<()> {
+        ctx.accounts.user.authority = ctx.accounts.x.key();
+        ctx.accounts.config.admin = ctx.accounts.x.key();
+        Ok(())
+    }
+
+    // BAD: the same seeds collide however they are formatted
+    pub fn update_colliding(ctx: Context<UpdateColliding>, fee: u64) -> Result<()> {
+        ctx.accounts.config.fee = fee;
+        Ok(())
+    }
+
+    // GOOD: a prefix per account type keeps the addresses apart
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        ctx.accounts.user.authority = ctx.accounts.x.key();
+        ctx.accounts.config.admin = ctx.accounts.x.key();
+        Ok(())
+    }
+
+    // GOOD: the seeds derive addresses of different programs
+    pub fn read_external(ctx: Context<ReadExternal>) -> Result<()> {
+        msg!("{}", ctx.accounts.config.fee);
+        Ok(())
+    }
+
+    // GOOD: accounts of the same type are left to duplicate_mutable_accounts
+    pub fn compare_users(ctx: Context<CompareUsers>) -> Result<()> {
+        msg!("{}", ctx.accounts.user.authority == ctx.accounts.other_user.authority);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeColliding<'info> {
+    #[account(init, payer = x, space = 8 + 32, seeds = [b"state", x.key().as_ref()], bump)]
+    pub user: Account<'info, User>,
+    #[account(init, payer = x, space = 8 + 40, seeds = [b"state", x.key().as_ref()], bump)]
+    pub config: Account<'info, Config>, // [duplicate_seeds_across_distinct_account_types]
+    #[account(mut)]
+    pub x: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateColliding<'info> {
+    #[account(seeds = [b"state", x.key().as_ref()], bump)]
+    pub user: Account<'info, User>,
+    #[account(mut, seeds = [b"state",x.key().as_ref()], bump)]
+    pub config: Box<Account<'info, Config>>, // [duplicate_seeds_across_distinct_account_types]
+    pub x: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = x, space = 8 + 32, seeds = [b"user", x.key().as_ref()], bump)]
+    pub user: Account<'info, User>,
+    #[account(init, payer = x, space = 8 + 40, seeds = [b"config", x.key().as_ref()], bump)]
+    pub config: Account<'info, Config>, // [safe_distinct_seeds]
+    #[account(mut)]
+    pub x: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReadExternal<'info> {
+    #[account(seeds = [b"state", x.key().as_ref()], bump)]
+    pub user: Account<'info, User>,
+    #[account(seeds = [b"state", x.key().as_ref()], bump, seeds::program = external_program.key())]
+    pub config: Account<'info, Config>, // [safe_distinct_seeds]
+    pub x: Signer<'info>,
+    /// CHECK: only used to derive `config`
+    pub external_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CompareUsers<'info> {
+    #[account(seeds = [b"state", x.key().as_ref()], bump)]
+    pub user: Account<'info, User>,
+    #[account(seeds = [b"state", x.key().as_ref()], bump)]
+    pub other_user: Account<'info, User>, // [safe_distinct_seeds]
+    pub x: Signer<'info>,
+}
+
+#[account]
+pub struct User {
+    pub authority: Pubkey,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee: u64,
+}
@@ -0,0 +1,143 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    findings::{Finding, emit_finding},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{account_constraints::parse_account_constraints, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects accounts with an `init` or `init_if_needed` constraint that are missing the
+    /// `payer` constraint, or the `space` constraint when no `associated_token::*`, `mint::*`
+    /// or `token::*` constraints supply the account size.
+    ///
+    /// ### Why is this bad?
+    /// Initializing an account requires someone to fund its rent and a known size to allocate.
+    /// Anchor rejects most of these misconfigurations at macro expansion, but manually written
+    /// or generated account structs can slip through and only fail once deployed.
+    ///
+    /// ### Bad
+    /// ```rust
+    /// #[derive(Accounts)]
+    /// pub struct Initialize<'info> {
+    ///     #[account(init, payer = user)] // missing `space`
+    ///     pub state: Account<'info, State>,
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// ### Good
+    /// ```rust
+    /// #[derive(Accounts)]
+    /// pub struct Initialize<'info> {
+    ///     #[account(init, payer = user, space = 8 + State::INIT_SPACE)]
+    ///     pub state: Account<'info, State>,
+    ///     // ...
+    /// }
+    /// ```
+    pub INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE,
+    Warn,
+    "`init`/`init_if_needed` account is missing the `payer` or `space` constraint"
+}
+
+impl<'tcx> LateLintPass<'tcx> for InitIfNeededWithoutPayerOrSpace {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_init_constraints(cx, anchor_context);
+    }
+}
+
+/// Constraint namespaces whose account kinds have their size computed by Anchor
+const SPACE_SUPPLYING_NAMESPACES: &[&str] = &["associated_token", "mint", "token"];
+
+fn analyze_init_constraints<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() && !adt_def.is_union() {
+        return;
+    }
+
+    for field in &adt_def.non_enum_variant().fields {
+        let constraints = parse_account_constraints(cx, field);
+
+        let init_constraint = if constraints.init_if_needed {
+            "init_if_needed"
+        } else if constraints.init {
+            "init"
+        } else {
+            continue;
+        };
+
+        let account_name = field.ident(cx.tcx).to_string();
+        let account_span = cx.tcx.def_span(field.did);
+
+        if constraints.payer.is_none() {
+            let finding = Finding::new(
+                INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE,
+                account_span,
+                format!(
+                    "account `{}` uses `{}` without a `payer` constraint — add `payer = <signer>` to fund the account's rent",
+                    account_name, init_constraint
+                ),
+            );
+            emit_finding(cx, INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE, finding);
+        }
+
+        let space_supplied = constraints.space.is_some()
+            || SPACE_SUPPLYING_NAMESPACES
+                .iter()
+                .any(|namespace| constraints.has(namespace));
+        if !space_supplied {
+            let finding = Finding::new(
+                INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE,
+                account_span,
+                format!(
+                    "account `{}` uses `{}` without a `space` constraint — add `space = ...` or use `associated_token::*`/`mint::*`/`token::*` constraints",
+                    account_name, init_constraint
+                ),
+            );
+            emit_finding(cx, INIT_IF_NEEDED_WITHOUT_PAYER_OR_SPACE, finding);
+        }
+    }
+}
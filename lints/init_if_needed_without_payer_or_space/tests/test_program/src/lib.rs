@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod init_if_needed_without_payer_or_space_tests {
+    use super::*;
+
+    // GOOD: `init` with both `payer` and `space`
+    pub fn init_complete(ctx: Context<InitComplete>, value: u64) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+
+    // BAD: `init` without `space`
+    pub fn init_missing_space(ctx: Context<InitMissingSpace>, value: u64) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+
+    // BAD: `init_if_needed` without `payer`
+    pub fn init_if_needed_missing_payer(
+        ctx: Context<InitIfNeededMissingPayer>,
+        value: u64,
+    ) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+
+    // GOOD: ATA with `init_if_needed`, `associated_token::*` supplies the space
+    pub fn init_if_needed_ata(ctx: Context<InitIfNeededAta>) -> Result<()> {
+        msg!("ATA: {}", ctx.accounts.user_token_account.key());
+        Ok(())
+    }
+
+    // GOOD: PDA with `payer` and `space` after a multi-seed `seeds` list
+    pub fn init_pda_complete(ctx: Context<InitPdaComplete>, value: u64) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+
+    // BAD: PDA without `space`, the commas of the `seeds` list do not end the constraint list
+    pub fn init_pda_missing_space(ctx: Context<InitPdaMissingSpace>, value: u64) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+}
+
+// GOOD: `init` with both `payer` and `space`
+#[derive(Accounts)]
+pub struct InitComplete<'info> {
+    #[account(init, payer = user, space = 8 + 8)]
+    pub state: Account<'info, State>, // [safe_init_payer_and_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: `init` without `space`
+#[derive(Accounts)]
+pub struct InitMissingSpace<'info> {
+    #[account(init, payer = user)]
+    pub state: Account<'info, State>, // [init_if_needed_without_payer_or_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: `init_if_needed` without `payer`
+#[derive(Accounts)]
+pub struct InitIfNeededMissingPayer<'info> {
+    #[account(init_if_needed, space = 8 + 8)]
+    pub state: Account<'info, State>, // [init_if_needed_without_payer_or_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: ATA with `init_if_needed`, `associated_token::*` supplies the space
+#[derive(Accounts)]
+pub struct InitIfNeededAta<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // [safe_init_payer_and_space]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// GOOD: PDA with `payer` and `space` after a multi-seed `seeds` list
+#[derive(Accounts)]
+pub struct InitPdaComplete<'info> {
+    #[account(init, seeds = [b"state", user.key().as_ref()], bump, payer = user, space = 8 + 8)]
+    pub state: Account<'info, State>, // [safe_init_payer_and_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// BAD: PDA without `space`
+#[derive(Accounts)]
+pub struct InitPdaMissingSpace<'info> {
+    #[account(init, seeds = [b"state", user.key().as_ref(), b"space"], bump, payer = user)]
+    pub state: Account<'info, State>, // [init_if_needed_without_payer_or_space]
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct State {
+    pub value: u64,
+}
@@ -0,0 +1,151 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::is_cpi_invoke_fn,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{extract_arg_local, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::impl_late_lint! {
+    /// ### What it does
+    /// Detects `invoke`/`invoke_signed` calls where the instruction's account metas reference
+    /// a context account whose `AccountInfo` is not included in the account infos passed to the CPI.
+    ///
+    /// ### Why is this bad?
+    /// The runtime resolves every account meta of the instruction against the provided account
+    /// infos. If one is missing, the CPI fails at runtime with a missing account error, making
+    /// the instruction unusable.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: `to` is referenced by the instruction but not passed to `invoke`
+    /// let ix = system_instruction::transfer(
+    ///     &ctx.accounts.from.key(),
+    ///     &ctx.accounts.to.key(),
+    ///     amount,
+    /// );
+    /// invoke(&ix, &[ctx.accounts.from.to_account_info()])?;
+    ///
+    /// Good: every account referenced by the instruction is passed
+    /// invoke(
+    ///     &ix,
+    ///     &[
+    ///         ctx.accounts.from.to_account_info(),
+    ///         ctx.accounts.to.to_account_info(),
+    ///     ],
+    /// )?;
+    /// ```
+    pub INVOKE_WITH_ACCOUNT_INFOS_MISMATCH,
+    Warn,
+    "instruction references an account that is not passed to `invoke`",
+    InvokeWithAccountInfosMismatch
+}
+
+#[derive(Default)]
+pub struct InvokeWithAccountInfosMismatch;
+
+impl<'tcx> LateLintPass<'tcx> for InvokeWithAccountInfosMismatch {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &FnDecl<'tcx>,
+        body: &HirBody<'tcx>,
+        main_fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(INVOKE_WITH_ACCOUNT_INFOS_MISMATCH) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, main_fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(INVOKE_WITH_ACCOUNT_INFOS_MISMATCH);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_invoke_account_infos(cx, &mir_analyzer, anchor_context_info);
+    }
+}
+
+fn analyze_invoke_account_infos<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context_info: &AnchorContextInfo<'tcx>,
+) {
+    for (_bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && is_cpi_invoke_fn(cx.tcx, *fn_def_id)
+            && let Some(instruction_local) = extract_arg_local(args, 0)
+            && let Some(account_infos_arg) = args.get(1)
+        {
+            // Only instructions created in this function can be inspected
+            let Some(instruction_metas) = collect_instruction_meta_accounts(
+                mir_analyzer,
+                instruction_local,
+                anchor_context_info,
+            ) else {
+                continue;
+            };
+
+            // Nothing to compare against if the account infos could not be resolved
+            let account_infos = collect_account_infos(mir_analyzer, account_infos_arg);
+            if account_infos.is_empty() {
+                continue;
+            }
+
+            for account_name in &instruction_metas.accounts {
+                if account_infos.contains(account_name) {
+                    continue;
+                }
+                span_lint_and_note(
+                    cx,
+                    INVOKE_WITH_ACCOUNT_INFOS_MISMATCH,
+                    *fn_span,
+                    format!(
+                        "account `{}` is referenced by the instruction but its `AccountInfo` is not passed to this CPI",
+                        account_name
+                    ),
+                    Some(instruction_metas.span),
+                    "instruction is created here",
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use anchor_lints_utils::{
+    diag_items::{is_anchor_program_type, is_solana_instruction_type},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::{extract_context_account, extract_vec_elements, remove_comments},
+};
+
+use clippy_utils::source::HasSession;
+
+use rustc_middle::{
+    mir::{AggregateKind, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    ty::{self as rustc_ty, Ty, TyCtxt, TyKind},
+};
+use rustc_span::{Span, source_map::Spanned};
+
+use std::collections::HashSet;
+
+/// Context accounts referenced by the account metas of an instruction
+pub struct InstructionMetas {
+    pub span: Span,
+    pub accounts: Vec<String>,
+}
+
+/// Check if a type is `Instruction` or wraps it, e.g. `Result<Instruction, ProgramError>`
+fn is_instruction_or_wrapped_instruction<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    let ty = ty.peel_refs();
+    if is_solana_instruction_type(tcx, ty) {
+        return true;
+    }
+    if let TyKind::Adt(_, generics) = ty.kind()
+        && let Some(inner_ty) = generics.types().next()
+    {
+        return is_solana_instruction_type(tcx, inner_ty);
+    }
+    false
+}
+
+/// Keep only context accounts that must be present in the account infos, preserving order
+fn filter_context_meta_accounts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    account_names: Vec<String>,
+    anchor_context_info: &AnchorContextInfo<'tcx>,
+) -> Vec<String> {
+    let mut seen = HashSet::new();
+    account_names
+        .into_iter()
+        .filter(|account_name| {
+            anchor_context_info
+                .anchor_context_arg_accounts_type
+                .get(account_name)
+                // The invoked program is passed as the instruction's program id, not as a meta
+                .is_some_and(|account_ty| !is_anchor_program_type(tcx, *account_ty))
+        })
+        .filter(|account_name| seen.insert(account_name.clone()))
+        .collect()
+}
+
+/// Find the instruction passed to `invoke` and collect the context accounts of its account metas.
+/// Handles both `Instruction { .. }` literals and instruction builder functions.
+pub fn collect_instruction_meta_accounts<'cx, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    instruction_local: Local,
+    anchor_context_info: &AnchorContextInfo<'tcx>,
+) -> Option<InstructionMetas> {
+    let tcx = mir_analyzer.cx.tcx;
+    let source_locals = mir_analyzer.collect_source_locals(instruction_local);
+
+    for (_bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        // `Instruction { program_id, accounts, data }` built in this function
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                && let Rvalue::Aggregate(box AggregateKind::Adt(..), operands) = rvalue
+                && let Some(dest_local) = place.as_local()
+                && source_locals.contains(&dest_local)
+                && is_solana_instruction_type(tcx, mir_analyzer.mir.local_decls[dest_local].ty)
+            {
+                let mut account_names = Vec::new();
+
+                // Second field holds the account metas
+                if let Some(Operand::Copy(accounts_place) | Operand::Move(accounts_place)) =
+                    operands.iter().nth(1)
+                    && let Some(accounts_local) = accounts_place.as_local()
+                {
+                    account_names.extend(
+                        mir_analyzer
+                            .get_vec_elements(&accounts_local, &mut HashSet::new(), true)
+                            .into_iter()
+                            .map(|account| account.account_name),
+                    );
+                }
+
+                // `accounts: vec![..]` written inline has no user local, read the literal instead
+                if account_names.is_empty()
+                    && let Ok(snippet) = mir_analyzer
+                        .cx
+                        .sess()
+                        .source_map()
+                        .span_to_snippet(stmt.source_info.span)
+                {
+                    account_names.extend(
+                        extract_vec_elements(&remove_comments(&snippet))
+                            .iter()
+                            .filter_map(|element| extract_context_account(element, true)),
+                    );
+                }
+
+                return Some(InstructionMetas {
+                    span: stmt.source_info.span,
+                    accounts: filter_context_meta_accounts(tcx, account_names, anchor_context_info),
+                });
+            }
+        }
+
+        // Instruction returned by a builder, e.g. `system_instruction::transfer`
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(..) = func.ty().kind()
+            && let Some(dest_local) = destination.as_local()
+            && source_locals.contains(&dest_local)
+            && is_instruction_or_wrapped_instruction(
+                tcx,
+                mir_analyzer.mir.local_decls[dest_local].ty,
+            )
+        {
+            let account_names = args
+                .iter()
+                .filter_map(|arg| mir_analyzer.pubkey_operand_to_local(&arg.node))
+                .filter_map(|local| mir_analyzer.extract_account_name_from_local(&local, true))
+                .map(|account| account.account_name)
+                .collect();
+
+            return Some(InstructionMetas {
+                span: *fn_span,
+                accounts: filter_context_meta_accounts(tcx, account_names, anchor_context_info),
+            });
+        }
+    }
+
+    None
+}
+
+/// Collect the context accounts passed as account infos to `invoke`
+pub fn collect_account_infos<'cx, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    account_infos_arg: &Spanned<Operand<'tcx>>,
+) -> HashSet<String> {
+    let mut account_infos: HashSet<String> = mir_analyzer
+        .collect_accounts_from_account_infos_arg(account_infos_arg, true)
+        .into_iter()
+        .map(|account| account.account_name)
+        .collect();
+
+    if !account_infos.is_empty() {
+        return account_infos;
+    }
+
+    // `&[a.to_account_info(), ..]` array literals are not `vec!` snippets, read the array elements instead
+    let Some(account_infos_local) = mir_analyzer.get_local_from_operand(Some(account_infos_arg))
+    else {
+        return account_infos;
+    };
+    let source_locals = mir_analyzer.collect_source_locals(account_infos_local);
+
+    for (_bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                && let Rvalue::Aggregate(box AggregateKind::Array(_), operands) = rvalue
+                && let Some(dest_local) = place.as_local()
+                && source_locals.contains(&dest_local)
+            {
+                for operand in operands.iter() {
+                    if let Operand::Copy(element_place) | Operand::Move(element_place) = operand
+                        && let Some(element_local) = element_place.as_local()
+                        && let Some(account) =
+                            mir_analyzer.extract_account_name_from_local(&element_local, true)
+                    {
+                        account_infos.insert(account.account_name);
+                    }
+                }
+            }
+        }
+    }
+
+    account_infos
+}
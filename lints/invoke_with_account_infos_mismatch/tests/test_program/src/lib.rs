@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod invoke_with_account_infos_mismatch {
+    use super::*;
+
+    // Bad: `to` is referenced by the instruction but its account info is not passed
+    pub fn transfer_missing_account_info(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let ix = system_instruction::transfer(
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            amount,
+        );
+        invoke(&ix, &[ctx.accounts.from.to_account_info(), ctx.accounts.system_program.to_account_info()])?; // [invoke_with_account_infos_mismatch]
+        Ok(())
+    }
+
+    // Good: every account referenced by the instruction is passed
+    pub fn transfer_complete_account_infos(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        let ix = system_instruction::transfer(
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            amount,
+        );
+        invoke(&ix, &[ctx.accounts.from.to_account_info(), ctx.accounts.to.to_account_info(), ctx.accounts.system_program.to_account_info()])?; // [safe_invoke]
+        Ok(())
+    }
+
+    // Bad: manually built instruction references `vault`, which is missing from the vec
+    pub fn manual_instruction_missing_account_info(ctx: Context<Manual>) -> Result<()> {
+        let metas = vec![
+            AccountMeta::new(ctx.accounts.vault.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.authority.key(), true),
+        ];
+        let ix = Instruction {
+            program_id: ctx.accounts.target_program.key(),
+            accounts: metas,
+            data: vec![],
+        };
+        let account_infos = vec![
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.target_program.to_account_info(),
+        ];
+        invoke_signed(&ix, &account_infos, &[])?; // [invoke_with_account_infos_mismatch]
+        Ok(())
+    }
+
+    // Good: manually built instruction with all account infos passed
+    pub fn manual_instruction_complete_account_infos(ctx: Context<Manual>) -> Result<()> {
+        let metas = vec![
+            AccountMeta::new(ctx.accounts.vault.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.authority.key(), true),
+        ];
+        let ix = Instruction {
+            program_id: ctx.accounts.target_program.key(),
+            accounts: metas,
+            data: vec![],
+        };
+        let account_infos = vec![
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.target_program.to_account_info(),
+        ];
+        invoke_signed(&ix, &account_infos, &[])?; // [safe_invoke]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut)]
+    pub from: Signer<'info>,
+    /// CHECK: recipient of the transfer
+    #[account(mut)]
+    pub to: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Manual<'info> {
+    /// CHECK: passed through to the target program
+    #[account(mut)]
+    pub vault: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: program invoked by the instruction
+    pub target_program: UncheckedAccount<'info>,
+}
@@ -7,9 +7,10 @@ extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::mir_analyzer::MirAnalyzer;
+use anchor_lints_utils::registry::is_lint_enabled;
 
+use anchor_lints_utils::diagnostics::span_lint_and_note;
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint_and_note;
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_span::Span;
@@ -19,7 +20,7 @@ use std::collections::HashSet;
 mod utils;
 use utils::{
     account_extraction::extract_init_accounts_and_inner_types,
-    field_analysis::{extract_inner_struct_fields, should_ignore_field},
+    field_analysis::{extract_required_fields, is_field_assigned},
     mir_analysis::collect_account_field_assignments,
 };
 
@@ -96,6 +97,11 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountFieldInit {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_ACCOUNT_FIELD_INIT) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -112,6 +118,8 @@ fn analyze_missing_account_field_init<'tcx>(
     fn_span: Span,
 ) {
     let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+    mir_analyzer.debug_dump(MISSING_ACCOUNT_FIELD_INIT);
+
     let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
         return;
     };
@@ -123,7 +131,7 @@ fn analyze_missing_account_field_init<'tcx>(
 
     let mut account_fields = std::collections::HashMap::new();
     for (account_name, info) in &init_accounts {
-        if let Some(fields) = extract_inner_struct_fields(cx, info.inner_ty) {
+        if let Some(fields) = extract_required_fields(cx, info.inner_ty) {
             account_fields.insert(account_name.clone(), fields);
         }
     }
@@ -154,10 +162,7 @@ fn analyze_missing_account_field_init<'tcx>(
 
             let mut missing = Vec::new();
             for f in fields {
-                if should_ignore_field(cx, f) {
-                    continue;
-                }
-                if !assigned.contains(&f.name) {
+                if !is_field_assigned(&assigned, &f.name) {
                     missing.push(f.name.clone());
                 }
             }
@@ -0,0 +1,22 @@
+use anchor_lints_utils::config::config;
+use regex::Regex;
+
+use std::sync::LazyLock;
+
+/// Patterns configured by `padding_field_patterns` in the `[missing_account_field_init]` table of
+/// the [configuration](anchor_lints_utils::config), validated when it is loaded
+static PADDING_FIELD_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    config()
+        .missing_account_field_init
+        .padding_field_patterns
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok())
+        .collect()
+});
+
+/// Check if a field name matches one of the configured padding/reserved patterns
+pub fn is_padding_field_name(name: &str) -> bool {
+    PADDING_FIELD_PATTERNS
+        .iter()
+        .any(|pattern| pattern.is_match(name))
+}
@@ -2,7 +2,9 @@ use anchor_lints_utils::diag_items::{is_anchor_signer_type, is_solana_pubkey_typ
 use rustc_lint::LateContext;
 use rustc_middle::ty::{Ty, TyKind};
 
-use crate::utils::types::AccountField;
+use crate::utils::{config::is_padding_field_name, types::AccountField};
+
+use std::collections::HashSet;
 
 /// Extract all fields from an account struct type.
 pub fn extract_inner_struct_fields<'tcx>(
@@ -27,16 +29,69 @@ pub fn extract_inner_struct_fields<'tcx>(
     }
 }
 
+/// Extract the fields of an account struct that must be assigned. A field holding a struct of the
+/// same crate is replaced by the fields of that struct, e.g. `settings.theme` and
+/// `settings.locale` for `settings: ProfileSettings`, one level down.
+pub fn extract_required_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    inner_ty: Ty<'tcx>,
+) -> Option<Vec<AccountField<'tcx>>> {
+    let mut required = Vec::new();
+    for field in extract_inner_struct_fields(cx, inner_ty)? {
+        if should_ignore_field(cx, &field) {
+            continue;
+        }
+        let nested_fields: Vec<_> = extract_nested_struct_fields(cx, field.ty)
+            .into_iter()
+            .flatten()
+            .filter(|nested| !should_ignore_field(cx, nested))
+            .collect();
+        if nested_fields.is_empty() {
+            required.push(field);
+            continue;
+        }
+        required.extend(nested_fields.into_iter().map(|nested| AccountField {
+            name: format!("{}.{}", field.name, nested.name),
+            ty: nested.ty,
+        }));
+    }
+    Some(required)
+}
+
+/// Extract the fields of a struct defined in the linted crate, e.g. an `AnchorSerialize` settings
+/// struct. `Pubkey`, `Vec`, `Option` and other upstream types are written as a whole.
+fn extract_nested_struct_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<Vec<AccountField<'tcx>>> {
+    match ty.kind() {
+        TyKind::Adt(adt_def, _) if adt_def.is_struct() && adt_def.did().is_local() => {
+            extract_inner_struct_fields(cx, ty)
+        }
+        _ => None,
+    }
+}
+
+/// Check if a required field is among the assigned ones. A nested field is also assigned by a
+/// write of the whole struct holding it, e.g. `profile.settings = settings`, and a field whose
+/// struct is not tracked field by field by a write of any of its fields.
+pub fn is_field_assigned(assigned: &HashSet<String>, field_name: &str) -> bool {
+    assigned.contains(field_name)
+        || field_name
+            .split_once('.')
+            .is_some_and(|(parent, _)| assigned.contains(parent))
+        || assigned.iter().any(|name| {
+            name.strip_prefix(field_name)
+                .is_some_and(|nested| nested.starts_with('.'))
+        })
+}
+
 /// Determine if a field should be ignored when checking for initialization.
 pub fn should_ignore_field<'tcx>(cx: &LateContext<'tcx>, field: &AccountField<'tcx>) -> bool {
     let n = field.name.as_str();
 
-    // Check for padding/reserved field name patterns
-    if n.starts_with("padding")
-        || n.starts_with("reserved")
-        || n == "padding"
-        || n == "reserved"
-        || n.starts_with('_')
+    // Check for padding/reserved field name patterns (configurable via `dylint.toml`)
+    if is_padding_field_name(n)
         // TokenAccounts/Mints etc. will get "0" as name, so skip them
         || n == "0"
     {
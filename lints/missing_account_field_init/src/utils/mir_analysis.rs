@@ -1,22 +1,28 @@
 use crate::utils::field_analysis::{extract_inner_struct_fields, should_ignore_field};
 use crate::utils::name_resolution::{
     build_local_account_alias_map, build_local_to_name_map, extract_field_write_info,
-    resolve_base_account_name, resolve_struct_field_name,
+    resolve_base_account_name, resolve_field_path_name,
 };
 use crate::utils::nested_functions::{
-    analyze_nested_init_function, check_if_args_corresponds_to_init_accounts,
+    analyze_nested_init_function, builder_assigned_fields,
+    check_if_args_corresponds_to_init_accounts, find_builder_call, is_same_adt,
 };
-use crate::utils::types::InitAccountInfo;
+use crate::utils::types::{FieldWrites, InitAccountInfo};
 use anchor_lints_utils::{
-    diag_items::{is_anchor_account_set_inner_fn, is_constructor_like_fn},
+    cfg::reachable_without_passing,
+    diag_items::{is_anchor_account_set_inner_fn, is_constructor_like_fn, is_default_fn},
     mir_analyzer::MirAnalyzer,
+    utils::extract_arg_local,
 };
+use clippy_utils::ty::is_type_diagnostic_item;
 use rustc_hir::def_id::LocalDefId;
 use rustc_lint::LateContext;
 use rustc_middle::mir::{
-    Body as MirBody, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+    AggregateKind, BasicBlock, Body as MirBody, BorrowKind, Local, Operand, Place, ProjectionElem,
+    RETURN_PLACE, Rvalue, START_BLOCK, StatementKind, TerminatorKind,
 };
-use rustc_span::{Span, source_map::Spanned};
+use rustc_middle::ty::{Ty, TyKind};
+use rustc_span::{Span, source_map::Spanned, sym};
 
 use std::collections::{HashMap, HashSet};
 
@@ -29,6 +35,8 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
     parent_fn_span: Span,
 ) -> HashMap<String, HashSet<String>> {
     let mut result: HashMap<String, HashSet<String>> = HashMap::new();
+    // Fields written by the code of the function, with the BBs writing them
+    let mut writes: FieldWrites = HashMap::new();
     let mir: &MirBody<'tcx> = mir_analyzer.mir;
     let local_account_alias_map = build_local_account_alias_map(
         cx,
@@ -47,7 +55,7 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
         result.entry(account_name).or_default().extend(fields);
     }
 
-    for (_bb, bbdata) in mir.basic_blocks.iter_enumerated() {
+    for (bb, bbdata) in mir.basic_blocks.iter_enumerated() {
         for stmt in &bbdata.statements {
             let StatementKind::Assign(box (place, rvalue)) = &stmt.kind else {
                 continue;
@@ -68,23 +76,35 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
                 {
                     for field in fields {
                         if !should_ignore_field(cx, &field) {
-                            result
+                            writes
                                 .entry(account_name.clone())
                                 .or_default()
-                                .insert(field.name);
+                                .entry(field.name)
+                                .or_default()
+                                .insert(bb);
                         }
                     }
                 }
                 continue;
             }
 
+            // `profile.delegates.push(key)` fills the `Vec` or `Option` field it mutably borrows
+            let written = match rvalue {
+                Rvalue::Ref(_, BorrowKind::Mut { .. }, borrowed)
+                    if is_collection_type(cx, borrowed.ty(mir, cx.tcx).ty) =>
+                {
+                    borrowed
+                }
+                _ => place,
+            };
+
             // We only care about field writes: <base>.<field> = ...
-            let Some((base_local, field_idx)) = extract_field_write_info(place) else {
+            let Some((base_local, field_path)) = extract_field_write_info(written) else {
                 continue;
             };
-            // Resolve struct field name
+            // Resolve struct field name, e.g. `settings.theme` for a nested field
             let base_ty = mir_analyzer.mir.local_decls[base_local].ty;
-            let Some(field_name) = resolve_struct_field_name(mir_analyzer.cx, base_ty, field_idx)
+            let Some(field_name) = resolve_field_path_name(mir_analyzer.cx, base_ty, &field_path)
             else {
                 continue;
             };
@@ -100,7 +120,12 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
             };
             // base = variable name like "collection"
             if init_accounts.contains_key(&base_name) {
-                result.entry(base_name).or_default().insert(field_name);
+                writes
+                    .entry(base_name)
+                    .or_default()
+                    .entry(field_name)
+                    .or_default()
+                    .insert(bb);
             }
         }
 
@@ -114,7 +139,7 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
         {
             // Handle set_inner() calls: account.set_inner(struct)
             if is_anchor_account_set_inner_fn(mir_analyzer.cx.tcx, *fn_def_id) {
-                handle_set_inner_call(mir_analyzer, init_accounts, args, &mut result);
+                handle_set_inner_call(mir_analyzer, init_accounts, args, bb, &mut writes);
                 continue;
             }
 
@@ -136,20 +161,84 @@ pub fn collect_account_field_assignments<'cx, 'tcx>(
 
                 for (acc_name, fields) in nested_fields {
                     if init_accounts.contains_key(&acc_name) {
-                        result
-                            .entry(acc_name)
-                            .or_default()
-                            .extend(fields.into_iter());
+                        let account_writes = writes.entry(acc_name).or_default();
+                        for field in fields {
+                            account_writes.entry(field).or_default().insert(bb);
+                        }
                     }
                 }
             }
         }
     }
 
+    // A field is only initialized if it is written on every path returning successfully, e.g.
+    // not when an early `return Ok(())` skips the write
+    let exit_blocks = successful_exit_blocks(mir_analyzer);
+    for (account_name, fields) in writes {
+        for (field_name, write_blocks) in fields {
+            if is_written_before_every_exit(mir, &write_blocks, &exit_blocks) {
+                result
+                    .entry(account_name.clone())
+                    .or_default()
+                    .insert(field_name);
+            }
+        }
+    }
+
     result
 }
 
-/// Detect full struct assignment via dereference: *local = Struct::new(...) or *local = Struct { ... }
+/// Collect the BBs returning successfully from the function: those setting the return value to
+/// `Ok(..)` if it returns a `Result`, those returning otherwise
+fn successful_exit_blocks<'cx, 'tcx>(mir_analyzer: &MirAnalyzer<'cx, 'tcx>) -> HashSet<BasicBlock> {
+    let mir = mir_analyzer.mir;
+    let tcx = mir_analyzer.cx.tcx;
+    let returns_result = is_type_diagnostic_item(
+        mir_analyzer.cx,
+        mir.local_decls[RETURN_PLACE].ty,
+        sym::Result,
+    );
+    mir.basic_blocks
+        .iter_enumerated()
+        .filter(|(_, bbdata)| {
+            if !returns_result {
+                return matches!(bbdata.terminator().kind, TerminatorKind::Return);
+            }
+            bbdata.statements.iter().any(|stmt| {
+                matches!(
+                    &stmt.kind,
+                    StatementKind::Assign(box (
+                        place,
+                        Rvalue::Aggregate(box AggregateKind::Adt(adt_def_id, variant_idx, ..), _),
+                    )) if place.local == RETURN_PLACE
+                        && place.projection.is_empty()
+                        && tcx.adt_def(*adt_def_id).variant(*variant_idx).name == sym::Ok
+                )
+            })
+        })
+        .map(|(bb, _)| bb)
+        .collect()
+}
+
+/// Check if every path from the start of the function to a successful exit passes through one of
+/// `write_blocks`
+fn is_written_before_every_exit(
+    mir: &MirBody<'_>,
+    write_blocks: &HashSet<BasicBlock>,
+    exit_blocks: &HashSet<BasicBlock>,
+) -> bool {
+    write_blocks.contains(&START_BLOCK)
+        || reachable_without_passing(
+            &mir.basic_blocks,
+            &HashSet::from([START_BLOCK]),
+            exit_blocks,
+            write_blocks,
+        )
+        .is_empty()
+}
+
+/// Detect full struct assignment via dereference: *local = Struct::new(...),
+/// *local = Struct::default() or *local = Struct { ... }
 fn detect_full_struct_assignment<'cx, 'tcx>(
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
     place: &Place<'tcx>,
@@ -208,7 +297,12 @@ fn detect_full_struct_assignment<'cx, 'tcx>(
     )
 }
 
-/// Check if a local was assigned from a constructor call (like Bank::new).
+/// Check if a type is a `Vec` or an `Option`, which are filled in place
+fn is_collection_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    is_type_diagnostic_item(cx, ty, sym::Vec) || is_type_diagnostic_item(cx, ty, sym::Option)
+}
+
+/// Check if a local was assigned from a constructor call (like Bank::new or Bank::default).
 fn check_if_local_from_constructor_call<'cx, 'tcx>(
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
     local: Local,
@@ -228,8 +322,11 @@ fn check_if_local_from_constructor_call<'cx, 'tcx>(
             if destination.local == local && destination.projection.is_empty() {
                 // Check if it's a constructor-like function
                 if let rustc_middle::ty::FnDef(fn_def_id, _) = func_const.ty().kind() {
-                    // Check if it looks like a constructor (name starts with new)
-                    if is_constructor_like_fn(mir_analyzer.cx.tcx, *fn_def_id) {
+                    // Check if it looks like a constructor (name starts with new) or is
+                    // `Default::default`
+                    if is_constructor_like_fn(mir_analyzer.cx.tcx, *fn_def_id)
+                        || is_default_fn(mir_analyzer.cx.tcx, *fn_def_id)
+                    {
                         return true;
                     }
                 }
@@ -246,7 +343,8 @@ fn handle_set_inner_call<'cx, 'tcx>(
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
     init_accounts: &HashMap<String, InitAccountInfo<'tcx>>,
     args: &[Spanned<Operand<'tcx>>],
-    out: &mut HashMap<String, HashSet<String>>,
+    bb: BasicBlock,
+    out: &mut FieldWrites,
 ) {
     // Receiver is first arg: &mut Account<'info, T>
     let Some(receiver_arg) = args.first() else {
@@ -276,14 +374,77 @@ fn handle_set_inner_call<'cx, 'tcx>(
         return;
     };
 
+    // A struct literal or a default value overwrites every field, e.g.
+    // `set_inner(UserProfile { owner, ..Default::default() })`. A value returned by a same-crate
+    // builder only sets the fields the builder sets.
+    let builder_fields = extract_arg_local(args, 1)
+        .filter(|&value_local| {
+            !is_struct_literal_or_default(mir_analyzer, value_local, init_info.inner_ty)
+        })
+        .and_then(|value_local| find_builder_call(mir_analyzer, value_local, init_info.inner_ty))
+        .map(|builder_def_id| {
+            builder_assigned_fields(
+                mir_analyzer.cx,
+                builder_def_id,
+                init_info.inner_ty,
+                &mut HashSet::new(),
+            )
+        });
+
     let entry = out.entry(account_name).or_default();
     for f in fields {
-        if !should_ignore_field(mir_analyzer.cx, &f) {
-            entry.insert(f.name);
+        if !should_ignore_field(mir_analyzer.cx, &f)
+            && builder_fields
+                .as_ref()
+                .is_none_or(|builder_fields| builder_fields.contains(&f.name))
+        {
+            entry.entry(f.name).or_default().insert(bb);
         }
     }
 }
 
+/// Check if `value_local` holds a struct literal of `account_ty` or a value returned by
+/// `Default::default`
+fn is_struct_literal_or_default<'cx, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    value_local: Local,
+    account_ty: Ty<'tcx>,
+) -> bool {
+    let TyKind::Adt(account_adt, _) = account_ty.peel_refs().kind() else {
+        return false;
+    };
+    let mir = mir_analyzer.mir;
+    let sources: HashSet<Local> = mir_analyzer
+        .collect_source_locals(value_local)
+        .into_iter()
+        .filter(|&local| is_same_adt(mir.local_decls[local].ty, account_ty))
+        .collect();
+    let is_source = |place: &Place<'tcx>| place.as_local().is_some_and(|l| sources.contains(&l));
+
+    mir.basic_blocks.iter().any(|bbdata| {
+        let is_literal = bbdata.statements.iter().any(|stmt| {
+            matches!(
+                &stmt.kind,
+                StatementKind::Assign(box (
+                    place,
+                    Rvalue::Aggregate(box AggregateKind::Adt(adt_def_id, ..), _),
+                )) if *adt_def_id == account_adt.did() && is_source(place)
+            )
+        });
+        is_literal
+            || matches!(
+                &bbdata.terminator().kind,
+                TerminatorKind::Call {
+                    func: Operand::Constant(func_const),
+                    destination,
+                    ..
+                } if is_source(destination)
+                    && matches!(func_const.ty().kind(), TyKind::FnDef(fn_def_id, _)
+                        if is_default_fn(mir_analyzer.cx.tcx, *fn_def_id))
+            )
+    })
+}
+
 /// Detect when all fields of an account are assigned from a struct literal.
 fn detect_struct_literal_full_assignment<'cx, 'tcx>(
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
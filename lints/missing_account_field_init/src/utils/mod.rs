@@ -1,4 +1,5 @@
 pub mod account_extraction;
+pub mod config;
 pub mod field_analysis;
 pub mod mir_analysis;
 pub mod name_resolution;
@@ -114,25 +114,30 @@ fn extract_account_from_rhs_place<'tcx>(
     None
 }
 
-/// Extract the base local and field index from a place that represents a field write.
-pub fn extract_field_write_info(place: &Place<'_>) -> Option<(Local, usize)> {
+/// Extract the base local and the fields, with their types, of a place that represents a field
+/// write, e.g. `settings` and `theme` for `profile.settings.theme = theme`.
+pub fn extract_field_write_info<'tcx>(
+    place: &Place<'tcx>,
+) -> Option<(Local, Vec<(usize, Ty<'tcx>)>)> {
     let base_local = place.local;
-    let mut field_idx = None;
+    let mut field_path = Vec::new();
     for proj in place.projection.iter() {
         match proj {
             ProjectionElem::Deref => {
                 // Continue through derefs
             }
-            ProjectionElem::Field(field, _ty) => {
-                field_idx = Some(field.index());
+            ProjectionElem::Field(field, ty) => {
+                field_path.push((field.index(), ty));
             }
             _ => return None,
         }
     }
 
-    let field_idx = field_idx?;
+    if field_path.is_empty() {
+        return None;
+    }
 
-    Some((base_local, field_idx))
+    Some((base_local, field_path))
 }
 
 /// Resolve the base account name from a MIR local.
@@ -331,6 +336,24 @@ pub fn resolve_struct_field_name<'tcx>(
     Some(field.ident(cx.tcx).to_string())
 }
 
+/// Resolve the name of a written field of `ty`, followed by the field of the nested struct it
+/// writes, if any, e.g. `settings.theme`. Only one level of nested fields is tracked.
+pub fn resolve_field_path_name<'tcx>(
+    cx: &LateContext<'tcx>,
+    ty: Ty<'tcx>,
+    field_path: &[(usize, Ty<'tcx>)],
+) -> Option<String> {
+    let (&(field_idx, field_ty), nested) = field_path.split_first()?;
+    let field_name = resolve_struct_field_name(cx, ty, field_idx)?;
+    match nested
+        .first()
+        .and_then(|&(nested_idx, _)| resolve_struct_field_name(cx, field_ty, nested_idx))
+    {
+        Some(nested_name) => Some(format!("{field_name}.{nested_name}")),
+        None => Some(field_name),
+    }
+}
+
 /// Check if the anchor context account type matches an init account.
 pub(crate) fn check_if_init_account_self_method<'tcx>(
     anchor_context: &AnchorContextInfo<'tcx>,
@@ -3,9 +3,12 @@ use anchor_lints_utils::{
     mir_analyzer::MirAnalyzer,
     utils::get_hir_body_from_local_def_id,
 };
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_lint::LateContext;
-use rustc_middle::mir::Operand;
+use rustc_middle::mir::{
+    AggregateKind, Local, Operand, ProjectionElem, RETURN_PLACE, Rvalue, StatementKind,
+    TerminatorKind,
+};
 use rustc_middle::ty::{Ty, TyKind};
 use rustc_span::{Span, source_map::Spanned};
 
@@ -133,3 +136,133 @@ fn inner_struct_ty<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx
     }
     Some(ty)
 }
+
+/// Find the same-crate function whose return value of type `account_ty` flows into `value_local`,
+/// e.g. `build_collection` in `collection.set_inner(build_collection(args))`.
+pub fn find_builder_call<'cx, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    value_local: Local,
+    account_ty: Ty<'tcx>,
+) -> Option<LocalDefId> {
+    let sources = mir_analyzer.collect_source_locals(value_local);
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && destination
+                .as_local()
+                .is_some_and(|local| sources.contains(&local))
+            && let TyKind::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            builder_def_id(mir_analyzer.cx, *fn_def_id, account_ty)
+        } else {
+            None
+        }
+    })
+}
+
+/// Get the [`LocalDefId`] of a same-crate function with a body returning `account_ty`
+fn builder_def_id<'tcx>(
+    cx: &LateContext<'tcx>,
+    fn_def_id: DefId,
+    account_ty: Ty<'tcx>,
+) -> Option<LocalDefId> {
+    let local_def_id = fn_def_id.as_local()?;
+    let return_ty = cx
+        .tcx
+        .fn_sig(fn_def_id)
+        .skip_binder()
+        .skip_binder()
+        .output();
+    (is_same_adt(return_ty, account_ty)
+        && get_hir_body_from_local_def_id(cx, local_def_id).is_some())
+    .then_some(local_def_id)
+}
+
+/// Collect the fields of the `account_ty` value returned by a builder function that the builder
+/// sets, through a struct literal, field writes or a nested builder. Fields of a struct literal
+/// copied from a base value, e.g. `..Default::default()`, are not considered set.
+pub fn builder_assigned_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    builder_def_id: LocalDefId,
+    account_ty: Ty<'tcx>,
+    visited: &mut HashSet<LocalDefId>,
+) -> HashSet<String> {
+    let mut assigned = HashSet::new();
+    if !visited.insert(builder_def_id) {
+        return assigned;
+    }
+    let Some(body_id) = get_hir_body_from_local_def_id(cx, builder_def_id) else {
+        return assigned;
+    };
+    let TyKind::Adt(account_adt, _) = account_ty.peel_refs().kind() else {
+        return assigned;
+    };
+    let fields = &account_adt.non_enum_variant().fields;
+    let mir_analyzer = MirAnalyzer::new(cx, cx.tcx.hir_body(body_id), builder_def_id);
+    let mir = mir_analyzer.mir;
+
+    // Locals the returned value is moved from
+    let returned = mir_analyzer.collect_source_locals(RETURN_PLACE);
+    let is_account_value = |local: Local| {
+        returned.contains(&local) && is_same_adt(mir.local_decls[local].ty, account_ty)
+    };
+
+    for bbdata in mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            let StatementKind::Assign(box (place, rvalue)) = &stmt.kind else {
+                continue;
+            };
+            if !is_account_value(place.local) {
+                continue;
+            }
+            match (place.projection.first(), rvalue) {
+                // `Collection { authority, ..Default::default() }`
+                (None, Rvalue::Aggregate(box AggregateKind::Adt(adt_def_id, ..), operands))
+                    if *adt_def_id == account_adt.did() =>
+                {
+                    for (field_idx, operand) in operands.iter_enumerated() {
+                        let copied_from_base = operand.place().is_some_and(|src| {
+                            matches!(src.projection.last(), Some(ProjectionElem::Field(idx, _)) if *idx == field_idx)
+                                && is_same_adt(mir.local_decls[src.local].ty, account_ty)
+                        });
+                        if !copied_from_base {
+                            assigned.insert(fields[field_idx].ident(cx.tcx).to_string());
+                        }
+                    }
+                }
+                // `collection.authority = authority`
+                (Some(ProjectionElem::Field(field_idx, _)), _) => {
+                    assigned.insert(fields[*field_idx].ident(cx.tcx).to_string());
+                }
+                _ => {}
+            }
+        }
+
+        // A value returned by a nested builder
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && destination.as_local().is_some_and(is_account_value)
+            && let TyKind::FnDef(fn_def_id, _) = func_const.ty().kind()
+            && let Some(nested_def_id) = builder_def_id(cx, *fn_def_id, account_ty)
+        {
+            assigned.extend(builder_assigned_fields(
+                cx,
+                nested_def_id,
+                account_ty,
+                visited,
+            ));
+        }
+    }
+    assigned
+}
+
+pub fn is_same_adt<'tcx>(ty: Ty<'tcx>, other: Ty<'tcx>) -> bool {
+    matches!((ty.peel_refs().kind(), other.peel_refs().kind()),
+        (TyKind::Adt(adt, _), TyKind::Adt(other_adt, _)) if adt.did() == other_adt.did())
+}
@@ -1,6 +1,9 @@
+use rustc_middle::mir::BasicBlock;
 use rustc_middle::ty::Ty;
 use rustc_span::Span;
 
+use std::collections::{HashMap, HashSet};
+
 /// To store initialized account information
 #[derive(Debug, Clone)]
 pub struct InitAccountInfo<'tcx> {
@@ -16,3 +19,6 @@ pub struct AccountField<'tcx> {
     #[allow(dead_code)]
     pub ty: Ty<'tcx>,
 }
+
+/// Fields written to each account, with the BBs writing them
+pub type FieldWrites = HashMap<String, HashMap<String, HashSet<BasicBlock>>>;
@@ -4,6 +4,7 @@ use anchor_spl::token_interface::{Mint, TokenAccount};
 declare_id!("11111111111111111111111111111111");
 
 #[account]
+#[derive(Default)]
 pub struct Collection {
     pub max_collectable_tokens: u64,
     pub authority: Pubkey,
@@ -11,12 +12,28 @@ pub struct Collection {
 }
 
 #[account]
+#[derive(Default)]
 pub struct UserProfile {
     pub owner: Pubkey,
     pub display_name: String,
     pub level: u64,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ProfileSettings {
+    pub theme: String,
+    pub locale: String,
+    pub delegates: Vec<Pubkey>,
+    pub recovery_authority: Option<Pubkey>,
+    pub font_size: u8,
+}
+
+#[account]
+pub struct ProfileWithSettings {
+    pub owner: Pubkey,
+    pub settings: ProfileSettings,
+}
+
 #[program]
 pub mod missing_account_field_init_tests {
     use super::*;
@@ -46,6 +63,36 @@ pub mod missing_account_field_init_tests {
         Ok(())
     }
 
+    // BAD: the early return skips the write of `authority`
+    pub fn init_collection_with_early_return(
+        ctx: Context<InitCollectionWithEarlyReturn>,
+        max_collectable_tokens: u64,
+    ) -> Result<()> {
+        let collection = &mut ctx.accounts.collection;
+        collection.max_collectable_tokens = max_collectable_tokens;
+        collection.lifetime_tokens_collected = 0;
+        if max_collectable_tokens == 0 {
+            return Ok(());
+        }
+        collection.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    // GOOD: only the failing path skips the write of `authority`
+    pub fn init_collection_with_early_error(
+        ctx: Context<InitCollectionWithEarlyError>,
+        max_collectable_tokens: u64,
+    ) -> Result<()> {
+        let collection = &mut ctx.accounts.collection;
+        collection.max_collectable_tokens = max_collectable_tokens;
+        collection.lifetime_tokens_collected = 0;
+        if max_collectable_tokens == 0 {
+            return Err(ProgramError::InvalidArgument.into());
+        }
+        collection.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
     // BAD: initializes no fields
     pub fn init_collection_empty(
         ctx: Context<InitCollectionEmpty>,
@@ -168,6 +215,92 @@ pub mod missing_account_field_init_tests {
         Ok(())
     }
 
+    // GOOD: initializes all fields via `set_inner` with a value built by a helper
+    pub fn init_collection_via_set_inner_builder(
+        ctx: Context<InitCollectionViaSetInnerBuilder>,
+        max_collectable_tokens: u64,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        ctx.accounts
+            .collection
+            .set_inner(build_collection(authority, max_collectable_tokens));
+        Ok(())
+    }
+
+    // GOOD: the helper sets the remaining fields on a default value
+    pub fn init_collection_via_set_inner_field_writes_builder(
+        ctx: Context<InitCollectionViaSetInnerFieldWritesBuilder>,
+        max_collectable_tokens: u64,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let collection = build_collection_with_field_writes(authority, max_collectable_tokens);
+        ctx.accounts.collection.set_inner(collection);
+        Ok(())
+    }
+
+    // BAD: the helper leaves `authority` at its default value
+    pub fn init_collection_via_set_inner_incomplete_builder(
+        ctx: Context<InitCollectionViaSetInnerIncompleteBuilder>,
+        max_collectable_tokens: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .collection
+            .set_inner(build_collection_without_authority(max_collectable_tokens));
+        Ok(())
+    }
+
+    // GOOD: initializes every field of the nested settings
+    pub fn init_profile_with_settings_complete(
+        ctx: Context<InitProfileWithSettingsComplete>,
+        theme: String,
+        locale: String,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let profile = &mut ctx.accounts.profile;
+        profile.owner = authority;
+        profile.settings.theme = theme;
+        profile.settings.locale = locale;
+        profile.settings.delegates.push(authority);
+        profile.settings.recovery_authority = Some(authority);
+        profile.settings.font_size = 12;
+        Ok(())
+    }
+
+    // BAD: forgets to initialize `settings.locale`
+    pub fn init_profile_with_settings_incomplete(
+        ctx: Context<InitProfileWithSettingsIncomplete>,
+        theme: String,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let profile = &mut ctx.accounts.profile;
+        profile.owner = authority;
+        profile.settings.theme = theme;
+        profile.settings.delegates.push(authority);
+        profile.settings.recovery_authority = Some(authority);
+        Ok(())
+    }
+
+    // GOOD: the struct literal overwrites the remaining fields with their default values
+    pub fn init_profile_via_set_inner_with_defaults(
+        ctx: Context<InitProfileViaSetInnerWithDefaults>,
+    ) -> Result<()> {
+        let owner = ctx.accounts.authority.key();
+        ctx.accounts.profile.set_inner(UserProfile {
+            owner,
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    // GOOD: overwrites the whole account with its default value
+    pub fn init_collection_via_default_assignment(
+        ctx: Context<InitCollectionViaDefaultAssignment>,
+    ) -> Result<()> {
+        let collection = &mut ctx.accounts.collection;
+        **collection = Collection::default();
+        Ok(())
+    }
+
     // BAD: uses self-method that fails to initialize all fields
     pub fn init_collection_via_method_incomplete(
         ctx: Context<InitCollectionViaMethodComplete>,
@@ -230,6 +363,12 @@ pub mod missing_account_field_init_tests {
         Ok(())
     }
 
+    // BAD by default: `gap` is not a padding name unless configured via `padding_field_patterns`
+    pub fn init_account_with_gap_field(ctx: Context<InitAccountWithGapField>) -> Result<()> {
+        ctx.accounts.account.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
     // BAD: non-primitive field (authority: Pubkey) is not initialized
     pub fn init_account_missing_non_primitive(
         ctx: Context<InitAccountMissingNonPrimitive>,
@@ -240,6 +379,28 @@ pub mod missing_account_field_init_tests {
     }
 }
 
+fn build_collection(authority: Pubkey, max_collectable_tokens: u64) -> Collection {
+    Collection {
+        max_collectable_tokens,
+        authority,
+        lifetime_tokens_collected: 0,
+    }
+}
+
+fn build_collection_with_field_writes(authority: Pubkey, max_collectable_tokens: u64) -> Collection {
+    let mut collection = Collection::default();
+    collection.authority = authority;
+    collection.max_collectable_tokens = max_collectable_tokens;
+    collection
+}
+
+fn build_collection_without_authority(max_collectable_tokens: u64) -> Collection {
+    Collection {
+        max_collectable_tokens,
+        ..Default::default()
+    }
+}
+
 pub fn init_collection_helper(
     ctx: Context<InitCollectionViaMethodComplete>,
     max_collectable_tokens: u64,
@@ -322,6 +483,36 @@ pub struct InitCollectionComplete<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitCollectionWithEarlyReturn<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_early_return", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [missing_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCollectionWithEarlyError<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_early_error", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct InitCollectionEmpty<'info> {
     #[account(
@@ -489,6 +680,111 @@ pub struct InitCollectionViaSetInner<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct InitCollectionViaSetInnerBuilder<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_set_inner_builder", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCollectionViaSetInnerFieldWritesBuilder<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_set_inner_field_writes", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCollectionViaSetInnerIncompleteBuilder<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_set_inner_incomplete", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [missing_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitProfileViaSetInnerWithDefaults<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 4 + 32 + 8,
+        seeds = [b"profile_set_inner_defaults", authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, UserProfile>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitCollectionViaDefaultAssignment<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 8 + 32 + 8,
+        seeds = [b"collection_default_assignment", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, Collection>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitProfileWithSettingsComplete<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + (4 + 32) + (4 + 32) + (4 + 32 * 4) + (1 + 32) + 1,
+        seeds = [b"profile_settings_complete", authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, ProfileWithSettings>, // [safe_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitProfileWithSettingsIncomplete<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + (4 + 32) + (4 + 32) + (4 + 32 * 4) + (1 + 32) + 1,
+        seeds = [b"profile_settings_incomplete", authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, ProfileWithSettings>, // [missing_account_field_init]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct TestCreateVestingEscrowCtx<'info> {
     #[account(mut)]
@@ -655,7 +951,28 @@ pub struct InitAccountMissingNonPrimitive<'info> {
         seeds = [b"account_missing", authority.key().as_ref()],
         bump
     )]
-    pub account: Account<'info, AccountWithPrimitivesAndPadding>, // [missing_account_field_init]
+    pub account: Account<'info, AccountWithPrimitivesAndPadding>, // [missing_account_field_init] [missing_account_field_init_with_gap_config]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct AccountWithGapField {
+    pub authority: Pubkey,
+    pub gap: Pubkey, // Reserved space under a custom naming convention
+}
+
+#[derive(Accounts)]
+pub struct InitAccountWithGapField<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 32,
+        seeds = [b"account_gap", authority.key().as_ref()],
+        bump
+    )]
+    pub account: Account<'info, AccountWithGapField>, // [missing_account_field_init] [safe_account_field_init_with_gap_config]
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
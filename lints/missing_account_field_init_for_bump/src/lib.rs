@@ -0,0 +1,142 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note, mir_analyzer::MirAnalyzer, registry::is_lint_enabled,
+    utils::should_skip_function,
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashSet;
+
+mod models;
+mod utils;
+
+use models::BumpFieldRead;
+use utils::*;
+
+// Bump field reads of the whole crate, collected once
+// Track (account span, field) pairs already warned about, accounts structs are shared by handlers
+thread_local! {
+    static BUMP_FIELD_READS: OnceCell<Vec<BumpFieldRead>> = const { OnceCell::new() };
+    static WARNED_FIELDS: RefCell<HashSet<(Span, String)>> = RefCell::new(HashSet::new());
+}
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `#[account(init, ...)]` accounts whose data type stores a PDA bump in a field
+    /// named `bump` or `*_bump` that is read by other functions, e.g. in a
+    /// `bump = vault.bump` constraint, but that the initializing handler never assigns.
+    ///
+    /// ### Why is this bad?
+    /// The field stays zero, while the canonical bump is almost never zero. Every instruction
+    /// re-deriving the PDA from the stored bump then fails, or signs for a different address,
+    /// and the account can be locked for good. `missing_account_field_init` skips primitive
+    /// fields, so a forgotten `u8` bump is not reported by it.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    ///     ctx.accounts.vault.authority = ctx.accounts.authority.key();
+    ///     // `vault.bump` stays zero, but `bump = vault.bump` is checked on withdraw
+    ///     Ok(())
+    /// }
+    ///
+    /// // Good:
+    /// pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    ///     ctx.accounts.vault.authority = ctx.accounts.authority.key();
+    ///     ctx.accounts.vault.bump = ctx.bumps.vault;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub MISSING_ACCOUNT_FIELD_INIT_FOR_BUMP,
+    Warn,
+    "account initialized without setting a stored PDA bump that is read elsewhere"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingAccountFieldInitForBump {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_ACCOUNT_FIELD_INIT_FOR_BUMP) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(MISSING_ACCOUNT_FIELD_INIT_FOR_BUMP);
+
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        let init_accounts = extract_init_accounts_with_bump_fields(cx, anchor_context);
+        if init_accounts.is_empty() {
+            return;
+        }
+
+        BUMP_FIELD_READS.with(|reads| {
+            let reads = reads.get_or_init(|| collect_bump_field_reads(cx));
+            for account in &init_accounts {
+                let assigned = collect_assigned_bump_fields(cx, def_id, account.data_adt);
+                for field_name in &account.bump_fields {
+                    if assigned.contains(field_name) {
+                        continue;
+                    }
+                    // Only bumps something relies on later are worth reporting
+                    let Some(read) = reads.iter().find(|read| {
+                        read.data_adt == account.data_adt
+                            && read.field_name == *field_name
+                            && read.reader != def_id.to_def_id()
+                    }) else {
+                        continue;
+                    };
+                    let newly_warned = WARNED_FIELDS.with(|warned| {
+                        warned
+                            .borrow_mut()
+                            .insert((account.span, field_name.clone()))
+                    });
+                    if !newly_warned {
+                        continue;
+                    }
+
+                    span_lint_and_note(
+                        cx,
+                        MISSING_ACCOUNT_FIELD_INIT_FOR_BUMP,
+                        account.span,
+                        format!(
+                            "account `{}` is initialized without setting `{}.{}`, which is read elsewhere — assign it, e.g. from `ctx.bumps.{}`",
+                            account.name,
+                            cx.tcx.item_name(account.data_adt),
+                            field_name,
+                            account.name,
+                        ),
+                        Some(read.span),
+                        format!("`{field_name}` is read here"),
+                    );
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,23 @@
+use rustc_hir::def_id::DefId;
+use rustc_span::Span;
+
+/// An `#[account(init, ...)]` account whose data type stores PDA bumps
+#[derive(Debug, Clone)]
+pub struct InitAccount {
+    pub name: String,
+    /// The `#[account]` struct the account holds
+    pub data_adt: DefId,
+    /// Fields of the struct named `bump` or `*_bump`
+    pub bump_fields: Vec<String>,
+    pub span: Span,
+}
+
+/// A read of a bump field, e.g. `vault.bump` in a `bump = vault.bump` constraint
+#[derive(Debug, Clone)]
+pub struct BumpFieldRead {
+    pub data_adt: DefId,
+    pub field_name: String,
+    /// The function the read is in, closures are attributed to their enclosing function
+    pub reader: DefId,
+    pub span: Span,
+}
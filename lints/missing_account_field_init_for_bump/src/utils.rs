@@ -0,0 +1,274 @@
+use anchor_lints_utils::{
+    diag_items::{anchor_inner_account_type, is_anchor_account_loader_type},
+    mir_analyzer::AnchorContextInfo,
+    utils::account_constraints::parse_account_constraints,
+};
+use clippy_utils::fn_has_unsatisfiable_preds;
+
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{Body as MirBody, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind},
+    ty::{Ty, TyCtxt, TyKind},
+};
+use rustc_span::Span;
+
+use std::collections::HashSet;
+
+use crate::models::{BumpFieldRead, InitAccount};
+
+/// Check if a field stores a PDA bump, i.e. is named `bump` or `*_bump`
+pub fn is_bump_field_name(name: &str) -> bool {
+    name == "bump" || name.ends_with("_bump")
+}
+
+/// Collect the `#[account(init, ...)]` accounts of an Anchor context whose data type has a bump
+/// field
+pub fn extract_init_accounts_with_bump_fields<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) -> Vec<InitAccount> {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return Vec::new();
+    };
+    if !adt_def.is_struct() {
+        return Vec::new();
+    }
+
+    let mut accounts = Vec::new();
+    for field in &adt_def.non_enum_variant().fields {
+        if !parse_account_constraints(cx, field).init {
+            continue;
+        }
+        let Some(data_ty) = account_data_type(cx.tcx, field.ty(cx.tcx, generics)) else {
+            continue;
+        };
+        let TyKind::Adt(data_adt, _) = data_ty.kind() else {
+            continue;
+        };
+        if !data_adt.is_struct() {
+            continue;
+        }
+        let bump_fields: Vec<String> = data_adt
+            .non_enum_variant()
+            .fields
+            .iter()
+            .map(|data_field| data_field.ident(cx.tcx).to_string())
+            .filter(|name| is_bump_field_name(name))
+            .collect();
+        if bump_fields.is_empty() {
+            continue;
+        }
+        accounts.push(InitAccount {
+            name: field.ident(cx.tcx).to_string(),
+            data_adt: data_adt.did(),
+            bump_fields,
+            span: cx.tcx.def_span(field.did),
+        });
+    }
+    accounts
+}
+
+/// The data type of an `Account<'info, T>` or `AccountLoader<'info, T>`
+fn account_data_type<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    if let Some(inner_ty) = anchor_inner_account_type(tcx, ty) {
+        return Some(inner_ty);
+    }
+    if is_anchor_account_loader_type(tcx, ty)
+        && let TyKind::Adt(_, generics) = ty.peel_refs().kind()
+    {
+        return generics.types().next();
+    }
+    None
+}
+
+/// Collect the reads of bump fields of any struct across all functions of the crate. Trait impls
+/// for the struct itself, e.g. the serialization derived by `#[account]`, are ignored, while the
+/// code Anchor generates for `bump = vault.bump` constraints is kept.
+pub fn collect_bump_field_reads(cx: &LateContext<'_>) -> Vec<BumpFieldRead> {
+    let tcx = cx.tcx;
+    let mut reads = Vec::new();
+    for def_id in tcx.hir_body_owners() {
+        let Some(mir) = available_mir(cx, def_id) else {
+            continue;
+        };
+        let reader = tcx.typeck_root_def_id(def_id.to_def_id());
+        for (data_adt, field_name, span) in bump_field_accesses(tcx, mir, false) {
+            if !is_trait_impl_for(tcx, reader, data_adt) {
+                reads.push(BumpFieldRead {
+                    data_adt,
+                    field_name,
+                    reader,
+                    span,
+                });
+            }
+        }
+    }
+    reads
+}
+
+/// Collect the bump fields of `data_adt` assigned by a function or by the same-crate functions
+/// it calls. Assigning the whole struct, e.g. `**vault = Vault { .. }` or
+/// `vault.set_inner(Vault { .. })`, assigns every field.
+pub fn collect_assigned_bump_fields(
+    cx: &LateContext<'_>,
+    def_id: LocalDefId,
+    data_adt: DefId,
+) -> HashSet<String> {
+    let tcx = cx.tcx;
+    let mut assigned = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![def_id];
+    while let Some(current) = pending.pop() {
+        if !visited.insert(current) {
+            continue;
+        }
+        let Some(mir) = available_mir(cx, current) else {
+            continue;
+        };
+        if assigns_whole_struct(tcx, mir, data_adt) {
+            let TyKind::Adt(adt_def, _) = tcx.type_of(data_adt).instantiate_identity().kind()
+            else {
+                continue;
+            };
+            assigned.extend(
+                adt_def
+                    .non_enum_variant()
+                    .fields
+                    .iter()
+                    .map(|field| field.ident(tcx).to_string()),
+            );
+            continue;
+        }
+        assigned.extend(
+            bump_field_accesses(tcx, mir, true)
+                .into_iter()
+                .filter(|(adt, _, _)| *adt == data_adt)
+                .map(|(_, field_name, _)| field_name),
+        );
+        pending.extend(mir.basic_blocks.iter().filter_map(|bbdata| {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                ..
+            } = &bbdata.terminator().kind
+                && let TyKind::FnDef(callee, _) = func.ty().kind()
+            {
+                callee.as_local()
+            } else {
+                None
+            }
+        }));
+    }
+    assigned
+}
+
+/// The optimized MIR of a function-like body, `None` for constants and for functions whose MIR
+/// cannot be built
+fn available_mir<'tcx>(cx: &LateContext<'tcx>, def_id: LocalDefId) -> Option<&'tcx MirBody<'tcx>> {
+    let tcx = cx.tcx;
+    (tcx.def_kind(def_id).is_fn_like()
+        && tcx.is_mir_available(def_id)
+        && !fn_has_unsatisfiable_preds(cx, def_id.to_def_id()))
+    .then(|| tcx.optimized_mir(def_id))
+}
+
+/// Check if `def_id` is a method of a trait implemented for `adt`
+fn is_trait_impl_for(tcx: TyCtxt, def_id: DefId, adt: DefId) -> bool {
+    tcx.trait_impl_of_assoc(def_id).is_some_and(|impl_id| {
+        matches!(tcx.type_of(impl_id).instantiate_identity().kind(),
+            TyKind::Adt(adt_def, _) if adt_def.did() == adt)
+    })
+}
+
+/// Check if a value of type `adt` is stored anywhere in the body
+fn assigns_whole_struct<'tcx>(tcx: TyCtxt<'tcx>, mir: &MirBody<'tcx>, adt: DefId) -> bool {
+    mir.basic_blocks
+        .iter()
+        .flat_map(|bbdata| bbdata.statements.iter())
+        .any(|stmt| {
+            matches!(&stmt.kind, StatementKind::Assign(box (dest, _))
+                if matches!(dest.ty(mir, tcx).ty.kind(),
+                    TyKind::Adt(adt_def, _) if adt_def.did() == adt))
+        })
+}
+
+/// Collect the bump fields accessed in the body as `(struct, field name, span)`: the fields
+/// assigned if `writes` is set, the fields read otherwise
+fn bump_field_accesses<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mir: &MirBody<'tcx>,
+    writes: bool,
+) -> Vec<(DefId, String, Span)> {
+    let mut accesses = Vec::new();
+    for bbdata in mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            let StatementKind::Assign(box (dest, rvalue)) = &stmt.kind else {
+                continue;
+            };
+            let places = if writes {
+                vec![dest]
+            } else {
+                rvalue_read_places(rvalue)
+            };
+            for place in places {
+                if let Some((adt, field_name)) = bump_field_projection(tcx, mir, place) {
+                    accesses.push((adt, field_name, stmt.source_info.span));
+                }
+            }
+        }
+        if !writes && let TerminatorKind::Call { args, fn_span, .. } = &bbdata.terminator().kind {
+            for arg in args.iter() {
+                if let Some(place) = arg.node.place()
+                    && let Some((adt, field_name)) = bump_field_projection(tcx, mir, &place)
+                {
+                    accesses.push((adt, field_name, *fn_span));
+                }
+            }
+        }
+    }
+    accesses
+}
+
+/// The places an rvalue reads from
+fn rvalue_read_places<'a, 'tcx>(rvalue: &'a Rvalue<'tcx>) -> Vec<&'a Place<'tcx>> {
+    let operand_place = |operand: &'a Operand<'tcx>| match operand {
+        Operand::Copy(place) | Operand::Move(place) => Some(place),
+        Operand::Constant(_) => None,
+    };
+    match rvalue {
+        Rvalue::Use(operand) | Rvalue::Cast(_, operand, _) | Rvalue::UnaryOp(_, operand) => {
+            operand_place(operand).into_iter().collect()
+        }
+        Rvalue::Ref(_, _, place) | Rvalue::RawPtr(_, place) | Rvalue::CopyForDeref(place) => {
+            vec![place]
+        }
+        Rvalue::BinaryOp(_, box (lhs, rhs)) => {
+            [lhs, rhs].into_iter().filter_map(operand_place).collect()
+        }
+        Rvalue::Aggregate(_, operands) => operands.iter().filter_map(operand_place).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// If the place projects a bump field of a struct, return the struct and the field name
+fn bump_field_projection<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mir: &MirBody<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<(DefId, String)> {
+    place.iter_projections().find_map(|(base, elem)| {
+        let ProjectionElem::Field(field_idx, _) = elem else {
+            return None;
+        };
+        let TyKind::Adt(adt_def, _) = base.ty(mir, tcx).ty.kind() else {
+            return None;
+        };
+        if !adt_def.is_struct() {
+            return None;
+        }
+        let field_name = adt_def.non_enum_variant().fields[field_idx]
+            .name
+            .to_string();
+        is_bump_field_name(&field_name).then(|| (adt_def.did(), field_name))
+    })
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_account_field_init_for_bump_tests {
+    use super::*;
+
+    // BAD: the bump is never set, but `bump = vault.bump` is checked on withdraw
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        ctx.accounts.vault.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    // GOOD: the bump is set from `ctx.bumps`
+    pub fn initialize_vault_with_bump(ctx: Context<InitializeVaultWithBump>) -> Result<()> {
+        let vault = &mut ctx.accounts.vault;
+        vault.authority = ctx.accounts.authority.key();
+        vault.bump = ctx.bumps.vault;
+        Ok(())
+    }
+
+    // GOOD: the whole struct is assigned
+    pub fn initialize_vault_with_struct(ctx: Context<InitializeVaultWithStruct>) -> Result<()> {
+        ctx.accounts.vault.set_inner(Vault {
+            authority: ctx.accounts.authority.key(),
+            bump: ctx.bumps.vault,
+        });
+        Ok(())
+    }
+
+    // GOOD: the bump is set by a helper
+    pub fn initialize_vault_with_helper(ctx: Context<InitializeVaultWithHelper>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        ctx.accounts.vault.init(authority, ctx.bumps.vault);
+        Ok(())
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.vault.sub_lamports(amount)?;
+        ctx.accounts.authority.add_lamports(amount)?;
+        Ok(())
+    }
+
+    // BAD: `escrow_bump` is never set, but it signs the transfer out of the escrow
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        ctx.accounts.pool.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+
+    pub fn release(ctx: Context<Release>, amount: u64) -> Result<()> {
+        let pool_key = ctx.accounts.pool.key();
+        let seeds: &[&[u8]] = &[b"escrow", pool_key.as_ref(), &[ctx.accounts.pool.escrow_bump]];
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.key(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.authority.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )
+    }
+
+    // GOOD: the bump is never read again
+    pub fn initialize_marker(ctx: Context<InitializeMarker>) -> Result<()> {
+        ctx.accounts.marker.authority = ctx.accounts.authority.key();
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(init, payer = authority, space = 8 + Vault::INIT_SPACE, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>, // [missing_account_field_init_for_bump: note=`bump` is read here]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultWithBump<'info> {
+    #[account(init, payer = authority, space = 8 + Vault::INIT_SPACE, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>, // [safe_account_field_init_for_bump]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultWithStruct<'info> {
+    #[account(init, payer = authority, space = 8 + Vault::INIT_SPACE, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>, // [safe_account_field_init_for_bump]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultWithHelper<'info> {
+    #[account(init, payer = authority, space = 8 + Vault::INIT_SPACE, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: Account<'info, Vault>, // [safe_account_field_init_for_bump]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vault", authority.key().as_ref()], bump = vault.bump, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(init, payer = authority, space = 8 + Pool::INIT_SPACE)]
+    pub pool: Account<'info, Pool>, // [missing_account_field_init_for_bump]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Release<'info> {
+    #[account(has_one = authority)]
+    pub pool: Account<'info, Pool>,
+    /// CHECK: PDA signed for with the stored bump
+    #[account(mut)]
+    pub escrow: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarker<'info> {
+    #[account(init, payer = authority, space = 8 + Marker::INIT_SPACE, seeds = [b"marker", authority.key().as_ref()], bump)]
+    pub marker: Account<'info, Marker>, // [safe_account_field_init_for_bump]
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Vault {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
+
+impl Vault {
+    pub fn init(&mut self, authority: Pubkey, bump: u8) {
+        self.authority = authority;
+        self.bump = bump;
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub escrow_bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Marker {
+    pub authority: Pubkey,
+    pub bump: u8,
+}
@@ -6,15 +6,21 @@ extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use anchor_lints_utils::utils::should_skip_function;
 use anchor_lints_utils::{
-    diag_items::{DiagnoticItem, is_cpi_invoke_fn},
+    cfg::{reachable, reachable_without_passing},
+    diag_items::{
+        DiagnoticItem, is_account_meta_constructor_fn, is_anchor_account_loader_load_fn,
+        is_cpi_invoke_fn,
+    },
+    diagnostics::{span_lint, span_lint_and_note},
+    findings::{Finding, emit_finding},
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
-    utils::get_hir_body_from_local_def_id,
+    registry::is_lint_enabled,
+    utils::{extract_arg_local, get_hir_body_from_local_def_id},
 };
-use clippy_utils::diagnostics::{span_lint, span_lint_and_note};
 
 use rustc_hir::{
     Body as HirBody, FnDecl,
@@ -23,7 +29,7 @@ use rustc_hir::{
 };
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::{
-    mir::{BasicBlock, Operand, TerminatorKind},
+    mir::{BasicBlock, BasicBlocks, Local, Operand, TerminatorKind},
     ty::{self as rustc_ty},
 };
 use rustc_span::Span;
@@ -41,6 +47,13 @@ dylint_linting::impl_late_lint! {
     /// ### Why is this bad?
     /// After a CPI, deserialized accounts do not have their data updated automatically.
     /// Accessing them without calling `reload` may lead to stale data being loaded.
+    /// Likewise, lamport balances read after a SOL transfer CPI do not account for the transfer,
+    /// and the `Ref`/`RefMut` of a zero-copy `AccountLoader` loaded before a CPI still holds the
+    /// data from before it, so the account must be loaded again. Account fields copied into a
+    /// variable before a CPI, e.g. `let balance = vault.amount;`, are just as stale after it.
+    /// Only the accounts a CPI can write are considered: accounts declared without `mut`, or only
+    /// passed to the CPI as `AccountMeta::new_readonly`, e.g. a PDA signing as authority, stay
+    /// unchanged.
     /// ```
     pub MISSING_ACCOUNT_RELOAD,
     Warn,
@@ -61,6 +74,11 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
         main_fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_ACCOUNT_RELOAD) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, main_fn_span, def_id) {
             return;
@@ -69,6 +87,8 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
         let fn_crate_name = cx.tcx.crate_name(def_id.to_def_id().krate).to_string();
 
         let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(MISSING_ACCOUNT_RELOAD);
+
         let mir = mir_analyzer.mir;
         // If fn does not take a anchor context, skip to avoid false positives
         let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -84,12 +104,25 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
 
         // BBs terminated by a CPI
         let mut cpi_calls: HashMap<BasicBlock, Span> = HashMap::new();
+        // BBs terminated by a CPI moving lamports, e.g. `system_program::transfer`
+        let mut lamport_cpi_calls: HashMap<BasicBlock, Span> = HashMap::new();
         // Map of account fields to BBs accessing them
         let mut account_accesses: HashMap<String, Vec<AccountAccess>> = HashMap::new();
+        // Map of account fields to BBs reading their lamport balance
+        let mut lamport_accesses: HashMap<String, Vec<AccountAccess>> = HashMap::new();
         // Map of account fields to BBs reloading them
         let mut account_reloads: HashMap<String, HashSet<BasicBlock>> = HashMap::new();
         // Map of CPI context account types
         let mut cpi_accounts: HashMap<String, BasicBlock> = HashMap::new();
+        // Map of guards returned by `AccountLoader::load`/`load_mut` to their account and BB
+        let mut loaded_guards: HashMap<Local, (String, BasicBlock)> = HashMap::new();
+        // Receivers of `Deref`/`DerefMut` calls, matched against the loaded guards afterwards
+        let mut deref_calls: Vec<(Local, BasicBlock, Span)> = Vec::new();
+        // Map of account fields to the references their derefs return, to find cached field copies
+        let mut account_derefs: HashMap<String, Vec<Local>> = HashMap::new();
+        // `AccountMeta::new`/`new_readonly` calls of hand-built instructions, with their account,
+        // BB and whether they pass the account as writable
+        let mut account_metas: Vec<(String, BasicBlock, bool)> = Vec::new();
 
         for (bb, bbdata) in mir.basic_blocks.iter_enumerated() {
             // Locate blocks ending with a call
@@ -97,6 +130,7 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                 func: Operand::Constant(func),
                 args,
                 fn_span,
+                destination,
                 ..
             } = &bbdata.terminator().kind
                 && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
@@ -124,10 +158,35 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                         }
                     }
                 }
+                // Or AccountLoader::load/load_mut, borrowing the data of a zero-copy account
+                else if is_anchor_account_loader_load_fn(cx.tcx, *fn_def_id) {
+                    if let Some(local) = extract_arg_local(args, 0)
+                        && let Some(guard) = destination.as_local()
+                        && let Some(account_name_and_local) =
+                            mir_analyzer.extract_account_name_from_local(&local, false)
+                    {
+                        loaded_guards.insert(guard, (account_name_and_local.account_name, bb));
+                    }
+                }
+                // Or AccountMeta::new/new_readonly, telling if an instruction writes the account
+                else if is_account_meta_constructor_fn(cx.tcx, *fn_def_id) {
+                    if let Some(local) = extract_arg_local(args, 0)
+                        && let Some(account_name_and_local) =
+                            mir_analyzer.extract_account_name_from_local(&local, false)
+                    {
+                        let is_writable =
+                            DiagnoticItem::SolanaAccountMetaNew.defid_is_item(cx.tcx, *fn_def_id);
+                        account_metas.push((account_name_and_local.account_name, bb, is_writable));
+                    }
+                }
                 // Or a CPI invoke function
                 else if is_cpi_invoke_fn(cx.tcx, *fn_def_id)
                     || mir_analyzer.takes_cpi_context(args)
                 {
+                    // Lamport transfers only make lamport balances stale
+                    if is_lamport_transfer_cpi(&mir_analyzer, *fn_def_id) {
+                        lamport_cpi_calls.insert(bb, *fn_span);
+                    }
                     // Skip lamports-only & metadata-only system program calls
                     if is_known_safe_cpi(cx, *fn_def_id) {
                         continue;
@@ -150,6 +209,9 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                     if fn_span.from_expansion() {
                         continue;
                     }
+                    if let Some(receiver) = extract_arg_local(args, 0) {
+                        deref_calls.push((receiver, bb, *fn_span));
+                    }
                     for account in args {
                         if let Operand::Move(account) = account.node
                             && let Some(local) = account.as_local()
@@ -162,7 +224,19 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                                     false,
                                     &mut String::new(),
                                 );
+                            if account_name_and_locals.is_empty() {
+                                continue;
+                            }
+                            let token_balance_field = destination
+                                .as_local()
+                                .and_then(|data| stale_token_balance_check(&mir_analyzer, data));
                             for account_name_and_local in account_name_and_locals {
+                                if let Some(data) = destination.as_local() {
+                                    account_derefs
+                                        .entry(account_name_and_local.account_name.clone())
+                                        .or_default()
+                                        .push(data);
+                                }
                                 account_accesses
                                     .entry(account_name_and_local.account_name)
                                     .or_default()
@@ -170,11 +244,53 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                                         access_block: bb,
                                         access_span: *fn_span,
                                         stale_data_access: false,
+                                        token_balance_field,
+                                        lamports_read: false,
+                                        zero_copy: false,
+                                        cached_copy: false,
                                     });
                             }
                         }
                     }
                 }
+                // Or a lamport balance read, e.g. `account.lamports()`
+                else if let Some(receiver) =
+                    lamports_read_receiver(&mir_analyzer, *fn_def_id, args)
+                {
+                    if fn_span.from_expansion() {
+                        continue;
+                    }
+                    for account_name_and_local in mir_analyzer.check_local_and_assignment_locals(
+                        &receiver,
+                        &mut HashSet::new(),
+                        false,
+                        &mut String::new(),
+                    ) {
+                        lamport_accesses
+                            .entry(account_name_and_local.account_name)
+                            .or_default()
+                            .push(AccountAccess {
+                                access_block: bb,
+                                access_span: *fn_span,
+                                stale_data_access: false,
+                                token_balance_field: None,
+                                lamports_read: true,
+                                zero_copy: false,
+                                cached_copy: false,
+                            });
+                    }
+                }
+                // Or a write through a `RefMut`, e.g. `pool.value = 1` on a zero-copy account
+                else if cx
+                    .tcx
+                    .is_diagnostic_item(rustc_span::sym::deref_mut_method, *fn_def_id)
+                {
+                    if !fn_span.from_expansion()
+                        && let Some(receiver) = extract_arg_local(args, 0)
+                    {
+                        deref_calls.push((receiver, bb, *fn_span));
+                    }
+                }
                 // CPI context
                 else if DiagnoticItem::AnchorCpiContext.defid_is_type(cx.tcx, return_ty) {
                     if let Some(cpi_accounts_struct) = args.get(1)
@@ -236,11 +352,57 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
             }
         }
 
+        // A CPI cannot change an account it only reads, e.g. a PDA signing as authority
+        cpi_accounts.retain(|name, _| is_writable_cpi_account(cx, anchor_context_info, name));
+        // Nor an account its hand-built instruction only passes as `AccountMeta::new_readonly`,
+        // while another CPI of the handler may still write it
+        let read_only_cpis = read_only_meta_cpis(&mir.basic_blocks, &account_metas, &cpi_calls);
+        let writing_cpis = |name: &str, cpis: &HashMap<BasicBlock, Span>| {
+            cpis_writing_account(cpis, read_only_cpis.get(name))
+        };
+
         let cpi_call_blocks: HashSet<_> = cpi_calls.keys().copied().collect();
+        let lamport_cpi_call_blocks: HashSet<_> = lamport_cpi_calls.keys().copied().collect();
+
+        // Lamport balances can be read from any account involved in a lamport transfer
+        lamport_accesses.retain(|name, _| {
+            cpi_accounts.get(name).is_some_and(|&block| {
+                reachable(&mir.basic_blocks, block, |bb| {
+                    lamport_cpi_call_blocks.contains(&bb)
+                })
+                .is_some()
+            })
+        });
+        for (name, accesses) in lamport_accesses {
+            let reloads = account_reloads.get(&name).cloned().unwrap_or_default();
+            report_stale_accesses(
+                cx,
+                &mir.basic_blocks,
+                &accesses,
+                &writing_cpis(&name, &lamport_cpi_calls),
+                reloads,
+            );
+        }
 
         // Filter accounts to only those involved in CPI calls
-        cpi_accounts
-            .retain(|_ty, &mut block| reachable_blocks(&mir.basic_blocks, block, &cpi_call_blocks));
+        cpi_accounts.retain(|_ty, &mut block| {
+            reachable(&mir.basic_blocks, block, |bb| cpi_call_blocks.contains(&bb)).is_some()
+        });
+
+        // Zero-copy data borrowed before a CPI is only refreshed by loading the account again
+        for ((name, load_block), accesses) in
+            zero_copy_guard_accesses(&mir_analyzer, &loaded_guards, &deref_calls)
+        {
+            if cpi_accounts.contains_key(&name) {
+                report_stale_accesses(
+                    cx,
+                    &mir.basic_blocks,
+                    &accesses,
+                    &writing_cpis(&name, &cpi_calls),
+                    HashSet::from([load_block]),
+                );
+            }
+        }
 
         // Filter accounts to only those involved in CPI calls
         account_accesses.retain(|name, _| cpi_accounts.contains_key(name));
@@ -256,73 +418,174 @@ impl<'tcx> LateLintPass<'tcx> for MissingAccountReload {
                     let access_blocks = HashSet::from([access.access_block]);
                     let violations = reachable_without_passing(
                         &mir.basic_blocks,
-                        cpi_call_blocks.clone(),
-                        access_blocks,
-                        HashSet::new(), // No reloads to check for stale accesses
+                        &cpi_call_blocks,
+                        &access_blocks,
+                        &HashSet::new(), // No reloads to check for stale accesses
                     );
                     if let Some(violation) = violations.first() {
                         trigger_missing_account_reload_lint_note(
                             cx,
-                            access.access_span,
+                            access,
                             Some(cpi_calls[&violation.1]),
                         );
                     } else {
-                        trigger_missing_account_reload_lint(cx, access.access_span);
+                        trigger_missing_account_reload_lint(cx, access);
                     }
                 }
             }
         }
 
-        for (ty, accesses) in account_accesses.into_iter() {
-            // Check all accesses (both stale and non-stale) for CPI reachability
-            let access_blocks: HashSet<BasicBlock> =
-                accesses.iter().map(|access| access.access_block).collect();
+        // Field copies taken before a CPI stay stale until they are assigned again
+        let mut cached_copies: BTreeMap<Local, &str> = BTreeMap::new();
+        for name in account_accesses.keys() {
+            for &data in account_derefs.get(name).into_iter().flatten() {
+                for copy in cached_field_copies(&mir_analyzer, data) {
+                    cached_copies.insert(copy, name);
+                }
+            }
+        }
+        for (copy, name) in cached_copies {
+            let (accesses, assignments) = cached_copy_accesses(mir, copy);
+            report_stale_accesses(
+                cx,
+                &mir.basic_blocks,
+                &accesses,
+                &writing_cpis(name, &cpi_calls),
+                assignments,
+            );
+        }
 
+        for (ty, accesses) in account_accesses.into_iter() {
             let reloads = account_reloads.remove(&ty).unwrap_or_default();
-
-            for (access_block, cpi) in reachable_without_passing(
+            report_stale_accesses(
+                cx,
                 &mir.basic_blocks,
-                cpi_call_blocks.clone(),
-                access_blocks,
+                &accesses,
+                &writing_cpis(&ty, &cpi_calls),
                 reloads,
-            ) {
-                if access_block == cpi {
-                    continue;
-                }
-                for access in accesses.iter().filter(|a| a.access_block == access_block) {
-                    trigger_missing_account_reload_lint_note(
-                        cx,
-                        access.access_span,
-                        Some(cpi_calls[&cpi]),
-                    );
-                }
-            }
+            );
         }
     }
 }
-pub fn trigger_missing_account_reload_lint(cx: &LateContext, access_span: Span) {
+
+// Report the accesses of an account reachable from a CPI without passing one of its reloads
+fn report_stale_accesses(
+    cx: &LateContext,
+    basic_blocks: &BasicBlocks,
+    accesses: &[AccountAccess],
+    cpi_calls: &HashMap<BasicBlock, Span>,
+    reloads: HashSet<BasicBlock>,
+) {
+    // Check all accesses (both stale and non-stale) for CPI reachability
+    let access_blocks: HashSet<BasicBlock> =
+        accesses.iter().map(|access| access.access_block).collect();
+
+    // Stale accesses of this account grouped by the CPI they follow
+    let mut stale_accesses_per_cpi: BTreeMap<
+        (BasicBlock, Option<&'static str>),
+        Vec<&AccountAccess>,
+    > = BTreeMap::new();
+    for (access_block, cpi) in reachable_without_passing(
+        basic_blocks,
+        &cpi_calls.keys().copied().collect(),
+        &access_blocks,
+        &reloads,
+    ) {
+        if access_block == cpi {
+            continue;
+        }
+        for access in accesses.iter().filter(|a| a.access_block == access_block) {
+            stale_accesses_per_cpi
+                .entry((cpi, access.token_balance_field))
+                .or_default()
+                .push(access);
+        }
+    }
+
+    // One diagnostic per CPI, pointing at every stale access after it
+    for ((cpi, _), mut stale_accesses) in stale_accesses_per_cpi {
+        stale_accesses.sort_by_key(|access| access.access_span.lo());
+        stale_accesses.dedup_by_key(|access| access.access_span);
+        trigger_missing_account_reload_lint_for_accesses(cx, &stale_accesses, cpi_calls[&cpi]);
+    }
+}
+pub fn trigger_missing_account_reload_lint(cx: &LateContext, access: &AccountAccess) {
     span_lint(
         cx,
         MISSING_ACCOUNT_RELOAD,
-        access_span,
-        "accessing an account after a CPI without calling `reload()`",
+        access.access_span,
+        missing_account_reload_message(access),
     );
 }
 pub fn trigger_missing_account_reload_lint_note(
     cx: &LateContext,
-    access_span: Span,
+    access: &AccountAccess,
     cpi_span: Option<Span>,
 ) {
     span_lint_and_note(
         cx,
         MISSING_ACCOUNT_RELOAD,
-        access_span,
-        "accessing an account after a CPI without calling `reload()`",
+        access.access_span,
+        missing_account_reload_message(access),
         cpi_span,
         "CPI is here",
     );
 }
 
+// Report the first stale access after a CPI, listing the later ones as secondary locations
+pub fn trigger_missing_account_reload_lint_for_accesses(
+    cx: &LateContext,
+    accesses: &[&AccountAccess],
+    cpi_span: Span,
+) {
+    let Some((first, rest)) = accesses.split_first() else {
+        return;
+    };
+    let mut finding = Finding::new(
+        MISSING_ACCOUNT_RELOAD,
+        first.access_span,
+        missing_account_reload_message(first),
+    )
+    .with_note(Some(cpi_span), cpi_note(first));
+    let also_accessed_note = if first.zero_copy {
+        "also accessed here through the data loaded before the CPI"
+    } else {
+        "also accessed here without calling `reload()`"
+    };
+    for access in rest {
+        finding = finding.with_note(Some(access.access_span), also_accessed_note);
+    }
+    emit_finding(cx, MISSING_ACCOUNT_RELOAD, finding);
+}
+
+// Stale token balances feeding a check are reported separately, as they can bypass a balance invariant
+fn missing_account_reload_message(access: &AccountAccess) -> String {
+    if access.cached_copy {
+        return "using a value copied from an account before a CPI — read it again after calling `reload()`".to_string();
+    }
+    if access.zero_copy {
+        return "accessing a zero-copy account after a CPI through data loaded before it — call `load()` again after the CPI".to_string();
+    }
+    if access.lamports_read {
+        return "reading the lamport balance of an account after a SOL transfer CPI without accounting for the transferred lamports".to_string();
+    }
+    match access.token_balance_field {
+        Some(field) => format!(
+            "checking a token balance after a CPI without calling `reload()` — `{}` still holds its pre-CPI value, so the balance check can be bypassed",
+            field
+        ),
+        None => "accessing an account after a CPI without calling `reload()`".to_string(),
+    }
+}
+
+fn cpi_note(access: &AccountAccess) -> &'static str {
+    if access.lamports_read {
+        "the lamport balance is changed by this transfer"
+    } else {
+        "CPI is here"
+    }
+}
+
 // Recursively checks nested functions for account reload operations and returns account names with their types.
 pub fn analyze_nested_function_operations<'tcx>(
     cx: &LateContext<'tcx>,
@@ -371,6 +634,7 @@ pub fn analyze_nested_function_operations<'tcx>(
                     args,
                     *fn_span,
                     bb,
+                    cpi_context_info.anchor_context_account_type,
                 ) {
                     nested_function_blocks.push(block);
                 }
@@ -32,6 +32,14 @@ pub struct AccountAccess {
     pub access_block: BasicBlock,
     pub access_span: Span,
     pub stale_data_access: bool,
+    /// Token balance field read by the access to decide a branch, e.g. `require!(vault.amount >= x)`
+    pub token_balance_field: Option<&'static str>,
+    /// The access reads the lamport balance of the account rather than its data
+    pub lamports_read: bool,
+    /// The access goes through a `Ref`/`RefMut` returned by `AccountLoader::load` or `load_mut`
+    pub zero_copy: bool,
+    /// The access uses a variable holding a copy of an account field, e.g. `let balance = vault.amount;`
+    pub cached_copy: bool,
 }
 
 #[derive(Debug, Clone)]
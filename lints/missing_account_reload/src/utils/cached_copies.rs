@@ -0,0 +1,101 @@
+use anchor_lints_utils::mir_analyzer::MirAnalyzer;
+use rustc_middle::mir::{
+    BasicBlock, Body as MirBody, Local, Location, Operand, Rvalue, StatementKind,
+    visit::{PlaceContext, Visitor},
+};
+use rustc_span::Span;
+
+use std::collections::HashSet;
+
+use crate::models::*;
+
+/// User variables holding a copy of a field read through `deref_destination`, the reference an
+/// account deref returns, e.g. `balance` in `let balance = ctx.accounts.vault.amount;`, along
+/// with the variables it is copied on to
+pub fn cached_field_copies(mir_analyzer: &MirAnalyzer, deref_destination: Local) -> Vec<Local> {
+    let mir = mir_analyzer.mir;
+    let mut copies = HashSet::new();
+    for bbdata in mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (
+                dest,
+                Rvalue::Use(Operand::Copy(src) | Operand::Move(src)),
+            )) = &stmt.kind
+                && src.local == deref_destination
+                && !src.projection.is_empty()
+                && let Some(dest_local) = dest.as_local()
+            {
+                copies.insert(dest_local);
+            }
+        }
+    }
+    // Copies of copies, e.g. `let cached = balance;`
+    for copy in copies.clone() {
+        if let Some(derived) = mir_analyzer.transitive_assignment_reverse_map.get(&copy) {
+            copies.extend(derived.iter().copied());
+        }
+    }
+
+    let mut copies: Vec<_> = copies
+        .into_iter()
+        .filter(|&local| {
+            let decl = &mir.local_decls[local];
+            decl.is_user_variable() && !decl.ty.is_ref()
+        })
+        .collect();
+    copies.sort();
+    copies
+}
+
+/// The uses of a cached copy as accesses, and the BBs assigning it. Assigning the copy again,
+/// e.g. re-reading the field after `reload()`, refreshes it.
+pub fn cached_copy_accesses(
+    mir: &MirBody<'_>,
+    copy: Local,
+) -> (Vec<AccountAccess>, HashSet<BasicBlock>) {
+    let mut visitor = LocalUses {
+        local: copy,
+        uses: Vec::new(),
+        assignments: HashSet::new(),
+    };
+    visitor.visit_body(mir);
+
+    let accesses = visitor
+        .uses
+        .into_iter()
+        .map(|location| AccountAccess {
+            access_block: location.block,
+            access_span: use_span(mir, location),
+            stale_data_access: false,
+            token_balance_field: None,
+            lamports_read: false,
+            zero_copy: false,
+            cached_copy: true,
+        })
+        .collect();
+    (accesses, visitor.assignments)
+}
+
+// Uses inside `msg!` or `require!` are reported at the macro call
+fn use_span(mir: &MirBody<'_>, location: Location) -> Span {
+    mir.source_info(location).span.source_callsite()
+}
+
+struct LocalUses {
+    local: Local,
+    uses: Vec<Location>,
+    assignments: HashSet<BasicBlock>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUses {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, location: Location) {
+        if local != self.local {
+            return;
+        }
+        if context.is_mutating_use() {
+            self.assignments.insert(location.block);
+        } else if matches!(context, PlaceContext::NonMutatingUse(_)) {
+            self.uses.push(location);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use anchor_lints_utils::{
+    diag_items::{DiagnoticItem, is_account_info_type},
+    mir_analyzer::MirAnalyzer,
+    utils::extract_arg_local,
+};
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_hir::def_id::DefId;
+use rustc_middle::{
+    mir::{Local, Operand, ProjectionElem, Rvalue, StatementKind},
+    ty::{TyKind, UintTy},
+};
+use rustc_span::{source_map::Spanned, sym};
+
+/// Check if the CPI moves lamports between the accounts it is given, e.g. `system_program::transfer`
+pub fn is_lamport_transfer_cpi(mir_analyzer: &MirAnalyzer, def_id: DefId) -> bool {
+    [
+        DiagnoticItem::AnchorSystemProgramTransfer,
+        DiagnoticItem::AnchorSystemProgramCreateAccount,
+    ]
+    .iter()
+    .any(|item| item.defid_is_item(mir_analyzer.cx.tcx, def_id))
+}
+
+/// If the call reads the lamport balance of an account, return the local holding the account:
+/// `account.lamports()`, `account.get_lamports()` or `account.lamports.borrow()`
+pub fn lamports_read_receiver<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    def_id: DefId,
+    args: &[Spanned<Operand<'tcx>>],
+) -> Option<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let receiver = extract_arg_local(args, 0)?;
+    match tcx.opt_item_name(def_id)?.as_str() {
+        "lamports" | "get_lamports" => {
+            let return_ty = tcx.fn_sig(def_id).skip_binder().skip_binder().output();
+            matches!(return_ty.kind(), TyKind::Uint(UintTy::U64)).then_some(receiver)
+        }
+        "borrow" => {
+            let receiver_ty = mir_analyzer.mir.local_decls[receiver].ty.peel_refs();
+            if !is_type_diagnostic_item(mir_analyzer.cx, receiver_ty, sym::RefCell) {
+                return None;
+            }
+            account_info_of_lamports_ref(mir_analyzer, receiver)
+        }
+        _ => None,
+    }
+}
+
+/// If `local` is a reference to the `lamports` field of an `AccountInfo`, return the local the
+/// field is borrowed from
+fn account_info_of_lamports_ref(mir_analyzer: &MirAnalyzer, local: Local) -> Option<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata.statements.iter().find_map(|stmt| {
+            let StatementKind::Assign(box (dest, Rvalue::Ref(_, _, borrowed))) = &stmt.kind else {
+                return None;
+            };
+            if dest.as_local() != Some(local) {
+                return None;
+            }
+            let (base, ProjectionElem::Field(field_idx, _)) = borrowed.iter_projections().last()?
+            else {
+                return None;
+            };
+            let base_ty = base.ty(mir_analyzer.mir, tcx).ty;
+            let is_lamports = is_account_info_type(tcx, base_ty)
+                && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                    if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "lamports");
+            is_lamports.then_some(borrowed.local)
+        })
+    })
+}
@@ -1,7 +1,15 @@
+pub mod cached_copies;
+pub mod lamports;
 pub mod mir;
 pub mod nested;
 pub mod paths;
+pub mod token_balance;
+pub mod zero_copy;
 
+pub use cached_copies::*;
+pub use lamports::*;
 pub use mir::*;
 pub use nested::*;
 pub use paths::*;
+pub use token_balance::*;
+pub use zero_copy::*;
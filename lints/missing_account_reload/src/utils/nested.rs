@@ -1,14 +1,20 @@
+use anchor_lints_utils::cfg::reachable;
 use anchor_lints_utils::mir_analyzer::{AnchorContextInfo, MirAnalyzer};
-use anchor_lints_utils::models::{AccountNameAndLocal, NestedArgument, NestedArgumentType};
+use anchor_lints_utils::models::{
+    AccountNameAndLocal, AssignmentKind, NestedArgument, NestedArgumentType,
+};
 use rustc_hir::{BodyId, ImplItemKind, ItemKind, Node, def_id::DefId};
 use rustc_lint::LateContext;
-use rustc_middle::mir::{BasicBlock, Body as MirBody, HasLocalDecls, Local, Operand};
+use rustc_middle::mir::{
+    BasicBlock, Body as MirBody, HasLocalDecls, Local, Operand, ProjectionElem,
+};
+use rustc_middle::ty::{Ty, TyKind};
 use rustc_span::Span;
 use rustc_span::source_map::Spanned;
 
 use std::collections::{HashMap, HashSet};
 
-use crate::utils::{contains_deserialized_data, reachable_block};
+use crate::utils::contains_deserialized_data;
 use crate::{analyze_nested_function_operations, models::*};
 
 // Processes nested function blocks and adds them to account_reloads or account_accesses
@@ -81,6 +87,10 @@ pub fn add_nested_function_block<'tcx>(
                 access_block: bb,
                 access_span: nested_function_block.account_span,
                 stale_data_access: nested_function_block.stale_data_access,
+                token_balance_field: None,
+                lamports_read: false,
+                zero_copy: false,
+                cached_copy: false,
             });
     }
 }
@@ -185,11 +195,10 @@ pub fn mark_unused_nested_reloads<'tcx>(
             continue;
         }
         reload.not_used_reload = !cpi_calls.iter().any(|cpi_call| {
-            reachable_block(
-                &mir_body.basic_blocks,
-                cpi_call.cpi_call_block,
-                reload.account_block,
-            )
+            reachable(&mir_body.basic_blocks, cpi_call.cpi_call_block, |bb| {
+                bb == reload.account_block
+            })
+            .is_some()
         });
     }
 }
@@ -220,11 +229,12 @@ pub fn check_stale_data_accesses<'tcx>(
                 && nested_account_access_block.account_ty == nested_account_reload_block.account_ty
                 && nested_account_access_block.account_block
                     != nested_account_reload_block.account_block
-                && reachable_block(
+                && reachable(
                     &mir.basic_blocks,
                     nested_account_reload_block.account_block,
-                    nested_account_access_block.account_block,
+                    |bb| bb == nested_account_access_block.account_block,
                 )
+                .is_some()
             {
                 is_rechable_from_reload = true;
                 break;
@@ -277,6 +287,7 @@ pub fn handle_account_reload_in_nested_function<'tcx>(
     args: &[Spanned<Operand>],
     fn_span: Span,
     bb: BasicBlock,
+    accounts_struct_ty: Ty<'tcx>,
 ) -> Option<NestedFunctionBlocks<'tcx>> {
     let account = args.first()?;
     let Operand::Move(account) = account.node else {
@@ -288,9 +299,13 @@ pub fn handle_account_reload_in_nested_function<'tcx>(
     let account_name_and_local = mir_analyzer.extract_account_name_from_local(&local, true)?;
     let arg_local = mir_analyzer
         .resolve_to_original_local(account_name_and_local.account_local, &mut HashSet::new());
+    // A helper reloading several accounts, e.g. `fn reload_all(a: &mut Accounts)`, names the
+    // accounts struct however it likes, the field it reloads is the account of the caller
+    let account_name = accounts_struct_field_name(mir_analyzer, local, accounts_struct_ty)
+        .unwrap_or(account_name_and_local.account_name);
 
     Some(NestedFunctionBlocks {
-        account_name: account_name_and_local.account_name.clone(),
+        account_name,
         account_ty,
         account_local: arg_local,
         account_span: fn_span,
@@ -301,6 +316,42 @@ pub fn handle_account_reload_in_nested_function<'tcx>(
     })
 }
 
+// Name of the accounts struct field a local borrows, e.g. `pda_account` for
+// `&mut a.pda_account` where `a: &mut SolTransfer2`
+fn accounts_struct_field_name<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    local: Local,
+    accounts_struct_ty: Ty<'tcx>,
+) -> Option<String> {
+    let TyKind::Adt(accounts_adt, _) = accounts_struct_ty.peel_refs().kind() else {
+        return None;
+    };
+    let tcx = mir_analyzer.cx.tcx;
+    let mut visited = HashSet::new();
+    let mut current = local;
+    while visited.insert(current) {
+        let (AssignmentKind::RefTo(place) | AssignmentKind::FromPlace(place)) =
+            mir_analyzer.assignment_map.get(&current)?
+        else {
+            return None;
+        };
+        for (base, elem) in place.iter_projections() {
+            if let ProjectionElem::Field(field_idx, _) = elem
+                && let TyKind::Adt(adt_def, _) = base.ty(mir_analyzer.mir, tcx).ty.kind()
+                && adt_def.did() == accounts_adt.did()
+            {
+                return Some(
+                    adt_def.non_enum_variant().fields[field_idx]
+                        .name
+                        .to_string(),
+                );
+            }
+        }
+        current = place.local;
+    }
+    None
+}
+
 // Handle account access (deref method) in nested function
 pub fn handle_account_access_in_nested_function<'tcx>(
     cx: &LateContext<'tcx>,
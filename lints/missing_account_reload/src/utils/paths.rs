@@ -1,15 +1,21 @@
 use anchor_lints_utils::{
+    cfg::reachable,
     diag_items::{
         is_account_info_type, is_anchor_account_loader_type, is_anchor_account_type,
         is_anchor_interface_account_type, is_anchor_signer_type, is_anchor_system_account_type,
         is_anchor_unchecked_account_type, is_box_type,
     },
     mir_analyzer::AnchorContextInfo,
+    utils::account_constraints::parse_account_constraints,
 };
 use rustc_lint::LateContext;
-use rustc_middle::{mir::BasicBlock, ty::Ty};
+use rustc_middle::{
+    mir::{BasicBlock, BasicBlocks},
+    ty::{Ty, TyKind},
+};
+use rustc_span::Span;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::models::*;
 
@@ -81,6 +87,81 @@ pub fn contains_deserialized_data<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) ->
     false
 }
 
+/// Check if a CPI may write an account it is given. Its `AccountInfo` is writable when the account
+/// is declared mutable, e.g. `#[account(mut)]`. Accounts outside the context struct are assumed
+/// writable.
+pub fn is_writable_cpi_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context_info: &AnchorContextInfo<'tcx>,
+    name: &str,
+) -> bool {
+    let TyKind::Adt(adt_def, _) = anchor_context_info.anchor_context_account_type.kind() else {
+        return true;
+    };
+    if !adt_def.is_struct() {
+        return true;
+    }
+    let normalized_name = normalize_account_name(name);
+    let Some(field) = adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == normalized_name)
+    else {
+        return true;
+    };
+    let constraints = parse_account_constraints(cx, field);
+    constraints.mut_
+        || constraints.init
+        || constraints.init_if_needed
+        || constraints.zero
+        || constraints.realloc.is_some()
+}
+
+/// Find the CPIs of each account whose hand-built instruction only passes it as
+/// `AccountMeta::new_readonly`. `account_metas` holds the account, BB and writability of each
+/// `AccountMeta::new`/`new_readonly` call, and a meta belongs to the closest CPI reachable from
+/// it, as an instruction is usually built right before it is invoked.
+pub fn read_only_meta_cpis(
+    basic_blocks: &BasicBlocks,
+    account_metas: &[(String, BasicBlock, bool)],
+    cpi_calls: &HashMap<BasicBlock, Span>,
+) -> HashMap<String, HashSet<BasicBlock>> {
+    // Whether any meta of the account passed to the CPI is writable
+    let mut writable_metas: HashMap<(&str, BasicBlock), bool> = HashMap::new();
+    for (name, meta_block, is_writable) in account_metas {
+        if let Some(cpi) = reachable(basic_blocks, *meta_block, |bb| cpi_calls.contains_key(&bb)) {
+            *writable_metas.entry((name.as_str(), cpi)).or_default() |= is_writable;
+        }
+    }
+
+    let mut read_only_cpis: HashMap<String, HashSet<BasicBlock>> = HashMap::new();
+    for ((name, cpi), is_writable) in writable_metas {
+        if !is_writable {
+            read_only_cpis
+                .entry(name.to_string())
+                .or_default()
+                .insert(cpi);
+        }
+    }
+    read_only_cpis
+}
+
+/// The CPIs of `cpi_calls` that may write an account, i.e. all of them but the `read_only_cpis`
+/// of the account
+pub fn cpis_writing_account(
+    cpi_calls: &HashMap<BasicBlock, Span>,
+    read_only_cpis: Option<&HashSet<BasicBlock>>,
+) -> HashMap<BasicBlock, Span> {
+    cpi_calls
+        .iter()
+        .filter(|(cpi, _)| {
+            read_only_cpis.is_none_or(|read_only_cpis| !read_only_cpis.contains(cpi))
+        })
+        .map(|(cpi, span)| (*cpi, *span))
+        .collect()
+}
+
 pub fn normalize_account_name(name: &str) -> &str {
     let stripped = if let Some(idx) = name.find(".accounts.") {
         let start = idx + ".accounts.".len();
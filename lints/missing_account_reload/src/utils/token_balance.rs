@@ -0,0 +1,152 @@
+use anchor_lints_utils::mir_analyzer::MirAnalyzer;
+use rustc_middle::{
+    mir::{
+        BinOp, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind, UnOp,
+    },
+    ty::{self as rustc_ty, TyKind},
+};
+use rustc_span::sym;
+
+use std::collections::HashSet;
+
+/// Token account fields whose value changes when a CPI moves tokens
+const TOKEN_BALANCE_FIELDS: &[&str] = &["amount", "delegated_amount"];
+
+/// If the dereferenced account data is a token account whose `amount`/`delegated_amount` feeds a
+/// comparison guarding a branch, e.g. `require!(vault.amount >= min)`, return the field name
+pub fn stale_token_balance_check(
+    mir_analyzer: &MirAnalyzer,
+    account_data: Local,
+) -> Option<&'static str> {
+    let account_data_locals = forward_derived_locals(mir_analyzer, HashSet::from([account_data]));
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (
+                dest,
+                Rvalue::Use(Operand::Copy(src) | Operand::Move(src)),
+            )) = &stmt.kind
+                && account_data_locals.contains(&src.local)
+                && let Some(field) = token_balance_field(mir_analyzer, src)
+                && let Some(balance_local) = dest.as_local()
+                && feeds_branch_comparison(mir_analyzer, balance_local)
+            {
+                return Some(field);
+            }
+        }
+    }
+    None
+}
+
+/// Return the balance field name if the place reads `amount`/`delegated_amount` of a struct
+fn token_balance_field<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    place: &Place<'tcx>,
+) -> Option<&'static str> {
+    let (base, ProjectionElem::Field(field_idx, _)) = place.iter_projections().last()? else {
+        return None;
+    };
+    let base_ty = base.ty(mir_analyzer.mir, mir_analyzer.cx.tcx).ty;
+    let TyKind::Adt(adt_def, _) = base_ty.kind() else {
+        return None;
+    };
+    if !adt_def.is_struct() {
+        return None;
+    }
+    let field_name = adt_def.non_enum_variant().fields[field_idx].name;
+    TOKEN_BALANCE_FIELDS
+        .iter()
+        .find(|balance_field| field_name.as_str() == **balance_field)
+        .copied()
+}
+
+/// Check if the value is compared and the comparison decides a branch, as `require!` does
+fn feeds_branch_comparison(mir_analyzer: &MirAnalyzer, value: Local) -> bool {
+    let values = forward_derived_locals(mir_analyzer, HashSet::from([value]));
+    let mut comparisons = HashSet::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (dest, Rvalue::BinaryOp(op, box (lhs, rhs)))) =
+                &stmt.kind
+                && matches!(
+                    op,
+                    BinOp::Ge | BinOp::Gt | BinOp::Le | BinOp::Lt | BinOp::Eq | BinOp::Ne
+                )
+                && [lhs, rhs]
+                    .iter()
+                    .any(|operand| operand_local(operand).is_some_and(|l| values.contains(&l)))
+                && let Some(comparison) = dest.as_local()
+            {
+                comparisons.insert(comparison);
+            }
+        }
+    }
+    if comparisons.is_empty() {
+        return false;
+    }
+
+    let conditions = forward_derived_locals(mir_analyzer, comparisons);
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        matches!(&bbdata.terminator().kind, TerminatorKind::SwitchInt { discr, .. }
+            if operand_local(discr).is_some_and(|l| conditions.contains(&l)))
+    })
+}
+
+/// Collect the locals holding the seeds through copies, references, negations and `Deref` calls
+fn forward_derived_locals(mir_analyzer: &MirAnalyzer, seeds: HashSet<Local>) -> HashSet<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mut derived = seeds;
+    loop {
+        let before = derived.len();
+        for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                let StatementKind::Assign(box (dest, rvalue)) = &stmt.kind else {
+                    continue;
+                };
+                let source = match rvalue {
+                    Rvalue::Use(operand) | Rvalue::UnaryOp(UnOp::Not, operand) => operand.place(),
+                    Rvalue::Ref(_, _, place) | Rvalue::CopyForDeref(place) => Some(*place),
+                    _ => None,
+                };
+                if let Some(source) = source
+                    && is_whole_value(&source)
+                    && derived.contains(&source.local)
+                    && let Some(dest_local) = dest.as_local()
+                {
+                    derived.insert(dest_local);
+                }
+            }
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+                && tcx.is_diagnostic_item(sym::deref_method, *fn_def_id)
+                && let Some(receiver) = args.first()
+                && operand_local(&receiver.node).is_some_and(|l| derived.contains(&l))
+                && let Some(dest_local) = destination.as_local()
+            {
+                derived.insert(dest_local);
+            }
+        }
+        if derived.len() == before {
+            return derived;
+        }
+    }
+}
+
+/// A place referring to the whole local, possibly through references
+fn is_whole_value(place: &Place) -> bool {
+    place
+        .projection
+        .iter()
+        .all(|elem| matches!(elem, ProjectionElem::Deref))
+}
+
+fn operand_local(operand: &Operand) -> Option<Local> {
+    operand
+        .place()
+        .filter(is_whole_value)
+        .map(|place| place.local)
+}
@@ -0,0 +1,44 @@
+use anchor_lints_utils::mir_analyzer::MirAnalyzer;
+use rustc_middle::mir::{BasicBlock, Local};
+use rustc_span::Span;
+
+use std::collections::HashMap;
+
+use crate::models::*;
+
+/// Group the derefs of the `Ref`/`RefMut` guards returned by `AccountLoader::load` and `load_mut`
+/// by the account and the BB loading them. The guard borrows the data as it was when loaded, so
+/// only the load of the guard refreshes an access through it.
+pub fn zero_copy_guard_accesses(
+    mir_analyzer: &MirAnalyzer,
+    loaded_guards: &HashMap<Local, (String, BasicBlock)>,
+    deref_calls: &[(Local, BasicBlock, Span)],
+) -> HashMap<(String, BasicBlock), Vec<AccountAccess>> {
+    let mut accesses: HashMap<(String, BasicBlock), Vec<AccountAccess>> = HashMap::new();
+    if loaded_guards.is_empty() {
+        return accesses;
+    }
+    for &(receiver, access_block, access_span) in deref_calls {
+        // `Try::branch` and the guard binding are followed back to the `load()` call
+        let Some(loaded) = mir_analyzer
+            .collect_source_locals(receiver)
+            .iter()
+            .find_map(|local| loaded_guards.get(local))
+        else {
+            continue;
+        };
+        accesses
+            .entry(loaded.clone())
+            .or_default()
+            .push(AccountAccess {
+                access_block,
+                access_span,
+                stale_data_access: false,
+                token_balance_field: None,
+                lamports_read: false,
+                zero_copy: true,
+                cached_copy: false,
+            });
+    }
+    accesses
+}
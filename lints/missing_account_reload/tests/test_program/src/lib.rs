@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
     program::{invoke, invoke_signed},
     system_instruction,
 };
 
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount};
 
 declare_id!("11111111111111111111111111111111");
 
@@ -21,7 +23,7 @@ pub mod missing_account_reload_tests {
             amount,
             &[],
         )?;
-        let _data = ctx.accounts.sender.data; // [unsafe_account_accessed]
+        let _data = ctx.accounts.sender.data; // [unsafe_account_accessed: note=CPI is here]
         let _sender_data = ctx.accounts.sender_state.data; // [safe_account_accessed]
         Ok(())
     }
@@ -193,8 +195,9 @@ pub mod missing_account_reload_tests {
         )?;
 
         // Multiple account accesses without reload
-        let _data1 = ctx.accounts.pda_account.data; // [unsafe_account_accessed]
+        let _data1 = ctx.accounts.pda_account.data; // [unsafe_account_accessed: note=CPI is here] [unsafe_account_accessed: note=also accessed here without calling `reload()`]
         let _data2 = ctx.accounts.pda_account_1.data; // [safe_account_accessed]
+        let _data3 = ctx.accounts.pda_account.data; // [unsafe_account_accessed] [additional_access]
         Ok(())
     }
 
@@ -331,6 +334,265 @@ pub mod missing_account_reload_tests {
         let _final_data = ctx.accounts.pda_account.data; // [unsafe_account_accessed]
         Ok(())
     }
+
+    // Pattern 19: Token balance checked after a transfer CPI without reload (UNSAFE)
+    pub fn check_vault_balance_after_transfer(
+        ctx: Context<VaultTransfer>,
+        amount: u64,
+        min_balance: u64,
+    ) -> Result<()> {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        require!(
+            ctx.accounts.vault.amount >= min_balance, // [stale_balance_check]
+            CustomError::InsufficientBalance
+        );
+        Ok(())
+    }
+
+    // Pattern 20: Token balance checked after a transfer CPI and reload (SAFE)
+    pub fn check_vault_balance_after_reload(
+        ctx: Context<VaultTransfer>,
+        amount: u64,
+        min_balance: u64,
+    ) -> Result<()> {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+
+        ctx.accounts.vault.reload()?;
+        require!(
+            ctx.accounts.vault.amount >= min_balance, // [safe_account_accessed]
+            CustomError::InsufficientBalance
+        );
+        Ok(())
+    }
+
+    // Pattern 21: Lamport balances read after a SOL transfer CPI (UNSAFE)
+    pub fn read_lamports_after_transfer(ctx: Context<LamportTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        transfer(cpi_ctx, amount)?; // [cpi_call]
+
+        let vault_balance = ctx.accounts.vault.lamports(); // [stale_lamports_read: note=the lamport balance is changed by this transfer]
+        let payer_balance = **ctx.accounts.payer.to_account_info().lamports.borrow(); // [stale_lamports_read]
+        msg!("balances: {} {}", vault_balance, payer_balance);
+        Ok(())
+    }
+
+    // Pattern 22: Lamport balance read before the SOL transfer CPI (SAFE)
+    pub fn read_lamports_before_transfer(ctx: Context<LamportTransfer>, amount: u64) -> Result<()> {
+        let vault_balance = ctx.accounts.vault.lamports(); // [safe_account_accessed]
+        msg!("balance: {}", vault_balance);
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        transfer(cpi_ctx, amount)
+    }
+
+    // Pattern 23: Several accounts reloaded by a helper taking the whole accounts struct (SAFE)
+    pub fn invoke_with_reload_all_helper(mut ctx: Context<SolTransfer2>, amount: u64) -> Result<()> {
+        cpi_mutating_allocate(
+            &ctx.accounts.pda_account,
+            &ctx.accounts.system_program,
+            amount,
+            &[],
+        )?;
+        cpi_mutating_allocate(
+            &ctx.accounts.pda_account_1,
+            &ctx.accounts.system_program,
+            amount,
+            &[],
+        )?;
+        reload_all(&mut ctx.accounts)?;
+
+        let _data = ctx.accounts.pda_account.data; // [safe_account_accessed]
+        let _data_1 = ctx.accounts.pda_account_1.data; // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 24: Zero-copy data loaded before a CPI and read after it (UNSAFE)
+    pub fn read_zero_copy_loaded_before_cpi(ctx: Context<ZeroCopyAllocate>, space: u64) -> Result<()> {
+        let pool = ctx.accounts.pool.load()?;
+        let _before = pool.value; // [safe_account_accessed]
+
+        let ix = system_instruction::allocate(&ctx.accounts.pool.key(), space);
+        let account_infos = vec![
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        invoke(&ix, &account_infos)?;
+
+        let _after = pool.value; // [unsafe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 25: Zero-copy data loaded again after the CPI (SAFE)
+    pub fn read_zero_copy_loaded_after_cpi(ctx: Context<ZeroCopyAllocate>, space: u64) -> Result<()> {
+        let ix = system_instruction::allocate(&ctx.accounts.pool.key(), space);
+        let account_infos = vec![
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        invoke(&ix, &account_infos)?;
+
+        let pool = ctx.accounts.pool.load()?;
+        let _after = pool.value; // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 26: Zero-copy data mutably loaded before a CPI and written after it (UNSAFE)
+    pub fn write_zero_copy_loaded_before_cpi(ctx: Context<ZeroCopyAllocate>, space: u64) -> Result<()> {
+        let mut pool = ctx.accounts.pool.load_mut()?;
+
+        let ix = system_instruction::allocate(&ctx.accounts.pool.key(), space);
+        let account_infos = vec![
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        invoke(&ix, &account_infos)?;
+
+        pool.value += 1; // [unsafe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 27: Zero-copy data mutably loaded again after the CPI (SAFE)
+    pub fn write_zero_copy_loaded_after_cpi(ctx: Context<ZeroCopyAllocate>, space: u64) -> Result<()> {
+        let ix = system_instruction::allocate(&ctx.accounts.pool.key(), space);
+        let account_infos = vec![
+            ctx.accounts.pool.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ];
+        invoke(&ix, &account_infos)?;
+
+        let mut pool = ctx.accounts.pool.load_mut()?;
+        pool.value += 1; // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 28: Field copied before a CPI and logged after it (UNSAFE)
+    pub fn log_field_copied_before_cpi(ctx: Context<SolTransfer2>, amount: u64) -> Result<()> {
+        let cached_data = ctx.accounts.pda_account.data; // [safe_account_accessed]
+        cpi_mutating_allocate(
+            &ctx.accounts.pda_account,
+            &ctx.accounts.system_program,
+            amount,
+            &[],
+        )?;
+        msg!("data: {}", cached_data); // [unsafe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 29: Field copied before a CPI, then read again after a reload (SAFE)
+    pub fn log_field_read_again_after_reload(ctx: Context<SolTransfer2>, amount: u64) -> Result<()> {
+        let mut cached_data = ctx.accounts.pda_account.data;
+        msg!("data: {}", cached_data); // [safe_account_accessed]
+        cpi_mutating_allocate(
+            &ctx.accounts.pda_account,
+            &ctx.accounts.system_program,
+            amount,
+            &[],
+        )?;
+        ctx.accounts.pda_account.reload()?;
+        cached_data = ctx.accounts.pda_account.data;
+        msg!("data: {}", cached_data); // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 30: PDA only signing a token transfer as its read-only authority (SAFE)
+    pub fn transfer_with_pda_authority(ctx: Context<PdaAuthorityTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.pda_authority.to_account_info(),
+        };
+        let bump_seed = ctx.bumps.pda_authority;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"authority", &[bump_seed]]];
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts)
+            .with_signer(signer_seeds);
+        token::transfer(cpi_ctx, amount)?;
+
+        let _data = ctx.accounts.pda_authority.data; // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 31: Mutable PDA passed as a read-only signer of a hand-built instruction (SAFE)
+    pub fn invoke_with_read_only_pda_meta(ctx: Context<SolTransfer2>, amount: u64) -> Result<()> {
+        let ix = Instruction {
+            program_id: ctx.accounts.system_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.pda_account.key(), true),
+                AccountMeta::new(ctx.accounts.recipient.key(), false),
+            ],
+            data: amount.to_le_bytes().to_vec(),
+        };
+        let account_infos = vec![
+            ctx.accounts.pda_account.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+        ];
+        let seed = ctx.accounts.recipient.key();
+        let bump_seed = ctx.bumps.pda_account;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"pda", seed.as_ref(), &[bump_seed]]];
+        invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+        let _data = ctx.accounts.pda_account.data; // [safe_account_accessed]
+        Ok(())
+    }
+
+    // Pattern 32: PDA read-only in a first hand-built instruction, then writable in a second (UNSAFE)
+    pub fn invoke_read_only_then_writable_pda_meta(
+        ctx: Context<SolTransfer2>,
+        amount: u64,
+    ) -> Result<()> {
+        let seed = ctx.accounts.recipient.key();
+        let bump_seed = ctx.bumps.pda_account;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"pda", seed.as_ref(), &[bump_seed]]];
+        let account_infos = vec![
+            ctx.accounts.pda_account.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+        ];
+
+        let read_only_ix = Instruction {
+            program_id: ctx.accounts.system_program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(ctx.accounts.pda_account.key(), true),
+                AccountMeta::new(ctx.accounts.recipient.key(), false),
+            ],
+            data: amount.to_le_bytes().to_vec(),
+        };
+        invoke_signed(&read_only_ix, &account_infos, signer_seeds)?;
+
+        let _before = ctx.accounts.pda_account.data; // [safe_account_accessed]
+
+        let writable_ix = Instruction {
+            program_id: ctx.accounts.system_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.pda_account.key(), true),
+                AccountMeta::new(ctx.accounts.recipient.key(), false),
+            ],
+            data: amount.to_le_bytes().to_vec(),
+        };
+        invoke_signed(&writable_ix, &account_infos, signer_seeds)?;
+
+        let _after = ctx.accounts.pda_account.data; // [unsafe_account_accessed]
+        Ok(())
+    }
 }
 pub fn cpi_call_safe(ctx_a: &mut Context<SolTransfer3>, amount: u64) -> Result<()> {
     let from_pubkey = ctx_a.accounts.pda_account.to_account_info();
@@ -378,6 +640,13 @@ fn reload_access_account_from_accounts(accounts: &mut SolTransfer2) -> Result<()
     Ok(())
 }
 
+// Reloads every account of the struct, whatever the parameter is named
+fn reload_all(a: &mut SolTransfer2) -> Result<()> {
+    a.pda_account.reload()?;
+    a.pda_account_1.reload()?;
+    Ok(())
+}
+
 fn cpi_call_ctx(ctx_a: &mut Context<SolTransfer2>, amount: u64) -> Result<()> {
     let seed = ctx_a.accounts.recipient.key();
 
@@ -559,6 +828,43 @@ pub struct SolTransfer3<'info> {
     system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct VaultTransfer<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct LamportTransfer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PdaAuthorityTransfer<'info> {
+    #[account(seeds = [b"authority"], bump)]
+    pub pda_authority: Account<'info, UserState>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ZeroCopyAllocate<'info> {
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+    pub system_program: Program<'info, System>,
+}
+
 impl<'info> SolTransfer3<'info> {
     pub fn cpi_call_safe(&mut self, amount: u64) -> Result<()> {
         let from_pubkey = self.pda_account.to_account_info();
@@ -619,6 +925,11 @@ pub struct InnerAccount {
     pub data: u64,
 }
 
+#[account(zero_copy)]
+pub struct Pool {
+    pub value: u64,
+}
+
 #[error_code]
 pub enum CustomError {
     #[msg("balance is less than amount")]
@@ -0,0 +1,139 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{should_skip_function, signed_cpi_seeds, signer_seed_bumps},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty as rustc_ty,
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects PDA signer seeds passed to `CpiContext::new_with_signer` or `invoke_signed` whose
+    /// trailing bump byte comes from an instruction argument, instead of `ctx.bumps` or a bump
+    /// stored in an account.
+    ///
+    /// ### Why is this bad?
+    /// The same seeds give a valid PDA for several bumps. A caller supplying the bump picks which
+    /// of these addresses the program signs for, which need not be the canonical PDA the program
+    /// validated and stores its state in.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// pub fn withdraw(ctx: Context<Withdraw>, bump: u8) -> Result<()> {
+    ///     let seeds: &[&[u8]] = &[b"vault", &[bump]];
+    ///     let cpi_ctx = CpiContext::new_with_signer(program, accounts, &[seeds]);
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+    ///     let seeds: &[&[u8]] = &[b"vault", &[ctx.bumps.vault]];
+    ///     let cpi_ctx = CpiContext::new_with_signer(program, accounts, &[seeds]);
+    ///     // ...
+    /// }
+    /// ```
+    pub MISSING_BUMP_VERIFICATION_ON_SIGNER_PDA,
+    Warn,
+    "PDA signer seeds use a bump supplied as an instruction argument"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingBumpVerificationOnSignerPda {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_BUMP_VERIFICATION_ON_SIGNER_PDA) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(MISSING_BUMP_VERIFICATION_ON_SIGNER_PDA);
+
+        // Only instruction handlers have arguments supplied by the caller of the instruction
+        if mir_analyzer.anchor_context_info.is_none() {
+            return;
+        }
+
+        analyze_signer_seed_bumps(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_signer_seed_bumps<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+        let Some(signer_seeds) = signed_cpi_seeds(mir_analyzer, *fn_def_id, args) else {
+            continue;
+        };
+
+        // Report the first caller-supplied bump of each signed CPI
+        let Some((bump_arg, bump_span)) = signer_seed_bumps(mir_analyzer, signer_seeds)
+            .iter()
+            .find_map(|bump| instruction_argument_source(mir_analyzer, bump))
+            .and_then(|arg| {
+                mir_analyzer
+                    .get_span_from_local(&arg)
+                    .map(|span| (arg, span))
+            })
+        else {
+            continue;
+        };
+        let bump_name = cx
+            .tcx
+            .sess
+            .source_map()
+            .span_to_snippet(bump_span)
+            .unwrap_or_else(|_| format!("{bump_arg:?}"));
+
+        span_lint_and_note(
+            cx,
+            MISSING_BUMP_VERIFICATION_ON_SIGNER_PDA,
+            *fn_span,
+            format!(
+                "signer seeds of this CPI end with a bump read from instruction argument `{bump_name}` — sign with `ctx.bumps` or the bump stored in the account instead"
+            ),
+            Some(bump_span),
+            "the caller of the instruction chooses this value, so it need not be the canonical bump",
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lints_utils::{
+    diag_items::is_anchor_context, mir_analyzer::MirAnalyzer, models::AssignmentKind,
+};
+
+use rustc_middle::mir::{HasLocalDecls, Local, Operand};
+
+use std::collections::HashSet;
+
+/// Resolve the instruction argument a value is read from, e.g. `bump` or `args.bump`, following
+/// copies and references. The Anchor `Context` is not considered an instruction argument, so
+/// `ctx.bumps` is not resolved.
+pub fn instruction_argument_source<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    value: &Operand<'tcx>,
+) -> Option<Local> {
+    let mir = mir_analyzer.mir;
+    let mut place = value.place()?;
+    let mut visited = HashSet::new();
+
+    while visited.insert(place.local) {
+        if mir.args_iter().any(|arg| arg == place.local) {
+            let arg_ty = mir.local_decls()[place.local].ty;
+            return (!is_anchor_context(mir_analyzer.cx.tcx, arg_ty)).then_some(place.local);
+        }
+        match mir_analyzer.assignment_map.get(&place.local) {
+            Some(AssignmentKind::FromPlace(src) | AssignmentKind::RefTo(src)) => place = *src,
+            _ => return None,
+        }
+    }
+    None
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_bump_verification_on_signer_pda_tests {
+    use super::*;
+
+    // BAD: the caller chooses the bump the vault signs with
+    pub fn withdraw_with_arg_bump(ctx: Context<Withdraw>, amount: u64, bump: u8) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"vault", &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.key(), cpi_accounts, signer_seeds); // [missing_bump_verification_on_signer_pda]
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // GOOD: the canonical bump found by Anchor when validating the vault
+    pub fn withdraw_with_ctx_bump(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"vault", &[ctx.bumps.vault]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.key(), cpi_accounts, signer_seeds); // [safe_signer_bump]
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // GOOD: the bump stored when the vault was created
+    pub fn withdraw_with_stored_bump(ctx: Context<WithdrawWithState>, amount: u64) -> Result<()> {
+        let bump = ctx.accounts.state.vault_bump;
+        let seeds: &[&[u8]] = &[b"vault", &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.key(), cpi_accounts, signer_seeds); // [safe_signer_bump]
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // BAD: the bump is a field of the instruction arguments
+    pub fn withdraw_invoke_signed_with_arg_bump(
+        ctx: Context<Withdraw>,
+        params: WithdrawParams,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::{program::invoke_signed, system_instruction};
+
+        let instruction = system_instruction::transfer(
+            &ctx.accounts.vault.key(),
+            &ctx.accounts.user.key(),
+            params.amount,
+        );
+        let account_infos = [
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+        ];
+        let seeds: &[&[u8]] = &[b"vault", &[params.bump]];
+        invoke_signed(&instruction, &account_infos, &[seeds])?; // [missing_bump_verification_on_signer_pda]
+        Ok(())
+    }
+
+    // GOOD: an instruction argument used as a seed, with the canonical bump last
+    pub fn withdraw_from_indexed_vault(
+        ctx: Context<WithdrawIndexed>,
+        index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"vault", &[index], &[ctx.bumps.vault]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.system_program.key(), cpi_accounts, signer_seeds); // [safe_signer_bump]
+        system_program::transfer(cpi_ctx, amount)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct WithdrawParams {
+    pub amount: u64,
+    pub bump: u8,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithState<'info> {
+    #[account(seeds = [b"state"], bump = state.bump)]
+    pub state: Account<'info, VaultState>,
+    #[account(mut, seeds = [b"vault"], bump = state.vault_bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct WithdrawIndexed<'info> {
+    #[account(mut, seeds = [b"vault", &[index]], bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct VaultState {
+    pub bump: u8,
+    pub vault_bump: u8,
+}
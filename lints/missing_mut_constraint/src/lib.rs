@@ -7,10 +7,11 @@ extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::{
+    diagnostics::span_lint,
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
-    utils::{extract_account_constraints, should_skip_function},
+    registry::is_lint_enabled,
+    utils::{account_constraints::parse_account_constraints, should_skip_function},
 };
-use clippy_utils::diagnostics::span_lint;
 
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
@@ -73,12 +74,18 @@ impl<'tcx> LateLintPass<'tcx> for MissingMutConstraint {
         main_fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_MUT_CONSTRAINT) {
+            return;
+        }
+
         if should_skip_function(cx, main_fn_span, def_id) {
             return;
         }
 
         let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
         anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_MUT_CONSTRAINT);
 
         // Analyze functions that take Anchor context
         let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -109,12 +116,12 @@ fn analyze_missing_mut_constraint<'cx, 'tcx>(
     for field in &variant.fields {
         let account_name = field.ident(cx.tcx).to_string();
         let account_span = cx.tcx.def_span(field.did);
-        let constraints = extract_account_constraints(cx, field);
+        let constraints = parse_account_constraints(cx, field);
         account_mutability.insert(
             account_name,
             AccountMutability {
                 span: account_span,
-                mutable: constraints.mutable,
+                mutable: constraints.mut_,
             },
         );
     }
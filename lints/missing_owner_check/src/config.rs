@@ -0,0 +1,45 @@
+use anchor_lints_utils::config::config;
+use clippy_utils::ty::implements_trait;
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::ty::{Ty, TyKind};
+
+/// Types configured by `owner_validated_types` in the `[missing_owner_check]` table of the
+/// [configuration](anchor_lints_utils::config)
+fn owner_validated_types() -> &'static [String] {
+    &config().missing_owner_check.owner_validated_types
+}
+
+/// Check if the type, or a trait it implements, is configured as owner-validated
+pub fn is_owner_validated_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    if owner_validated_types().is_empty() {
+        return false;
+    }
+
+    let ty = ty.peel_refs();
+    if let TyKind::Adt(adt_def, _) = ty.kind()
+        && is_configured_name(cx, adt_def.did())
+    {
+        return true;
+    }
+
+    cx.tcx
+        .visible_traits()
+        .filter(|trait_def_id| is_configured_name(cx, *trait_def_id))
+        .any(|trait_def_id| implements_trait(cx, ty, trait_def_id, &[]))
+}
+
+/// Match either the bare item name or its full path, e.g. `ValidatedMetadata` or
+/// `my_program::ValidatedMetadata`
+fn is_configured_name(cx: &LateContext<'_>, def_id: DefId) -> bool {
+    let name = cx.tcx.item_name(def_id);
+    let path = cx
+        .get_def_path(def_id)
+        .iter()
+        .map(|segment| segment.as_str())
+        .collect::<Vec<_>>()
+        .join("::");
+    owner_validated_types()
+        .iter()
+        .any(|configured| configured == name.as_str() || *configured == path)
+}
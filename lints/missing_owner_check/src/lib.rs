@@ -2,19 +2,20 @@
 #![warn(unused_extern_crates)]
 #![feature(box_patterns)]
 
-extern crate rustc_ast;
 extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::mir_analyzer::MirAnalyzer;
+use anchor_lints_utils::registry::is_lint_enabled;
 
+use anchor_lints_utils::diagnostics::span_lint;
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint;
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_span::Span;
 
+mod config;
 mod utils;
 use utils::*;
 
@@ -54,6 +55,11 @@ impl<'tcx> LateLintPass<'tcx> for MissingOwnerCheck {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_OWNER_CHECK) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -72,6 +78,7 @@ fn analyze_missing_owner_check<'tcx>(
 
     // Update anchor context info with accounts
     anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+    mir_analyzer.debug_dump(MISSING_OWNER_CHECK);
 
     // Analyze functions that take Anchor context
     let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -1,20 +1,27 @@
 use anchor_lints_utils::{
     diag_items::{
-        is_anchor_account_type, is_anchor_key_fn, is_anchor_to_account_info_fn, is_borrow_fn,
-        is_box_type, is_cpi_builder_constructor_fn, is_deserialize_fn,
+        is_account_info_type, is_anchor_account_type, is_anchor_key_fn, is_anchor_program_type,
+        is_anchor_signer_type, is_anchor_spl_transfer_accounts, is_anchor_to_account_info_fn,
+        is_anchor_unchecked_account_type, is_borrow_fn, is_box_type, is_cpi_builder_constructor_fn,
+        is_deserialize_fn,
     },
     mir_analyzer::MirAnalyzer,
-    utils::account_constraints::extract_account_constraints,
+    utils::{
+        DATA_ACCESS_METHODS, account_constraints::parse_account_constraints,
+        get_hir_body_from_local_def_id,
+    },
 };
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_lint::LateContext;
 use rustc_middle::{
-    mir::{Operand, TerminatorKind},
+    mir::{AggregateKind, Local, Operand, Rvalue, StatementKind, TerminatorKind},
     ty::{self as rustc_ty, Ty, TyKind},
 };
-use rustc_span::{Span, Symbol};
+use rustc_span::Span;
 use std::collections::{HashMap, HashSet};
 
+use crate::config::is_owner_validated_type;
+
 #[derive(Debug, Clone)]
 pub struct AccountInfo<'tcx> {
     pub name: String,
@@ -47,14 +54,16 @@ pub fn extract_accounts_needing_owner_check<'tcx>(
             let account_ty = account_field.ty(cx.tcx, accounts_generics);
             let inner_ty = unwrap_box_type(cx, account_ty);
 
-            let constraints = extract_account_constraints(cx, account_field);
             let has_seeds = has_seeds_constraint(cx, account_field);
-            let has_address = constraints.has_address_constraint;
-            let has_owner = has_owner_constraint(cx, account_field);
+            let has_address = parse_account_constraints(cx, account_field)
+                .address
+                .is_some();
+            let has_owner =
+                has_trusted_owner_constraint(cx, variant, accounts_generics, account_field);
 
             let is_account_type = is_anchor_account_type(cx.tcx, inner_ty);
 
-            if !is_account_type {
+            if !is_account_type && !is_owner_validated_type(cx, inner_ty) {
                 accounts_needing_check.insert(
                     account_name.clone(),
                     AccountInfo {
@@ -102,56 +111,53 @@ fn has_seeds_constraint<'tcx>(
     cx: &LateContext<'tcx>,
     account_field: &rustc_middle::ty::FieldDef,
 ) -> bool {
-    let attrs = cx.tcx.get_all_attrs(account_field.did);
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let attr_item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                for token in delim_args.tokens.iter() {
-                    if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token
-                        && let rustc_ast::token::TokenKind::Ident(ident, ..) = token.kind
-                        && ident == Symbol::intern("seeds")
-                    {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    false
+    parse_account_constraints(cx, account_field).seeds.is_some()
 }
 
-fn has_owner_constraint<'tcx>(
+// `#[account(owner = ...)]` validates the account only if the owner cannot be chosen by the
+// caller: a constant, e.g. `anchor_spl::token::ID`, or the key of a program, signer, PDA or
+// address-constrained account of the struct. `owner = user_supplied.key()` checks nothing.
+fn has_trusted_owner_constraint<'tcx>(
     cx: &LateContext<'tcx>,
-    account_field: &rustc_middle::ty::FieldDef,
+    variant: &rustc_ty::VariantDef,
+    accounts_generics: rustc_ty::GenericArgsRef<'tcx>,
+    account_field: &rustc_ty::FieldDef,
 ) -> bool {
-    let attrs = cx.tcx.get_all_attrs(account_field.did);
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let attr_item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                let mut found_owner = false;
-                for token in delim_args.tokens.iter() {
-                    if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token {
-                        match token.kind {
-                            rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                if ident == Symbol::intern("owner") {
-                                    found_owner = true;
-                                }
-                            }
-                            rustc_ast::token::TokenKind::Eq => {
-                                if found_owner {
-                                    return true;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
+    let Some(owner) = parse_account_constraints(cx, account_field).owner else {
+        return false;
+    };
+    let (root, rest) = owner
+        .path
+        .split_once('.')
+        .unwrap_or((owner.path.as_str(), ""));
+    if let Some(owner_field) = variant
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == root)
+    {
+        return !rest.is_empty() && is_validated_account(cx, owner_field, accounts_generics);
     }
-    false
+    // `ID` or `crate::id()`, while a lowercase name is an `#[instruction(..)]` argument
+    root.contains("::")
+        || root
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        || owner.text.trim_end().ends_with(')')
+}
+
+// Accounts whose key the caller cannot choose freely
+fn is_validated_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    account_field: &rustc_ty::FieldDef,
+    accounts_generics: rustc_ty::GenericArgsRef<'tcx>,
+) -> bool {
+    let ty = unwrap_box_type(cx, account_field.ty(cx.tcx, accounts_generics));
+    let constraints = parse_account_constraints(cx, account_field);
+    is_anchor_program_type(cx.tcx, ty)
+        || is_anchor_signer_type(cx.tcx, ty)
+        || constraints.signer
+        || constraints.seeds.is_some()
+        || constraints.address.is_some()
 }
 
 fn unwrap_box_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
@@ -171,7 +177,10 @@ pub fn extract_accounts_with_data_access<'cx, 'tcx>(
     anchor_context_info: &anchor_lints_utils::mir_analyzer::AnchorContextInfo<'tcx>,
 ) -> HashSet<String> {
     let mut accounts_with_data_access = HashSet::new();
+    let mut accounts_read_by_helpers = HashSet::new();
     let mut accounts_used_as_cpi_programs = HashSet::new();
+    let validated_by_trusted_cpi =
+        accounts_validated_by_trusted_cpi(mir_analyzer, anchor_context_info);
     let mir = mir_analyzer.mir;
 
     for (_bb, bbdata) in mir.basic_blocks.iter_enumerated() {
@@ -206,6 +215,21 @@ pub fn extract_accounts_with_data_access<'cx, 'tcx>(
                 continue;
             }
 
+            // Data read by a same-crate helper the account is passed to, one level deep
+            if let Some(helper_def_id) = fn_def_id.as_local() {
+                for (arg_index, arg) in args.iter().enumerate() {
+                    if let Operand::Copy(place) | Operand::Move(place) = &arg.node
+                        && is_raw_account_type(cx, place.ty(mir, cx.tcx).ty)
+                        && !reads_through_owner_validated_type(mir_analyzer, place.local)
+                        && let Some(account_name) =
+                            trace_account_from_place(mir_analyzer, place, anchor_context_info)
+                        && helper_reads_param_data(cx, helper_def_id, arg_index)
+                    {
+                        accounts_read_by_helpers.insert(account_name);
+                    }
+                }
+            }
+
             // extract account name from deserialize or borrow
             if let Some(account_name) = extract_account_from_deserialize(
                 cx,
@@ -222,6 +246,13 @@ pub fn extract_accounts_with_data_access<'cx, 'tcx>(
         }
     }
 
+    // Only direct reads in the handler count for the participants of a trusted CPI
+    accounts_with_data_access.extend(
+        accounts_read_by_helpers
+            .difference(&validated_by_trusted_cpi)
+            .cloned(),
+    );
+
     // return accounts with data access that are not used as cpi programs
     accounts_with_data_access
         .difference(&accounts_used_as_cpi_programs)
@@ -229,6 +260,41 @@ pub fn extract_accounts_with_data_access<'cx, 'tcx>(
         .collect()
 }
 
+// Accounts passed as `from`, `to` or `mint` of an `anchor_spl` token transfer. The token program
+// checks it owns them, so what a helper reads of them along the CPI is validated by the CPI.
+fn accounts_validated_by_trusted_cpi<'cx, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context_info: &anchor_lints_utils::mir_analyzer::AnchorContextInfo<'tcx>,
+) -> HashSet<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mut validated = HashSet::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            let StatementKind::Assign(box (_, Rvalue::Aggregate(box kind, operands))) = &stmt.kind
+            else {
+                continue;
+            };
+            let AggregateKind::Adt(adt_def_id, variant_index, ..) = kind else {
+                continue;
+            };
+            if !is_anchor_spl_transfer_accounts(tcx, *adt_def_id) {
+                continue;
+            }
+            let variant = tcx.adt_def(*adt_def_id).variant(*variant_index);
+            for (field, operand) in variant.fields.iter().zip(operands.iter()) {
+                if matches!(field.name.as_str(), "from" | "to" | "mint")
+                    && let Some(place) = operand.place()
+                    && let Some(account_name) =
+                        trace_account_from_place(mir_analyzer, &place, anchor_context_info)
+                {
+                    validated.insert(account_name);
+                }
+            }
+        }
+    }
+    validated
+}
+
 // from borrow method
 fn extract_account_from_borrow<'cx, 'tcx>(
     cx: &LateContext<'tcx>,
@@ -237,11 +303,14 @@ fn extract_account_from_borrow<'cx, 'tcx>(
     args: &[rustc_span::source_map::Spanned<Operand<'tcx>>],
     anchor_context_info: &anchor_lints_utils::mir_analyzer::AnchorContextInfo<'tcx>,
 ) -> Option<String> {
-    if !is_borrow_fn(cx.tcx, fn_def_id) {
+    if !is_borrow_fn(cx.tcx, fn_def_id) && !is_data_access_fn(cx, fn_def_id) {
         return None;
     }
     let receiver = args.first()?;
     if let Operand::Copy(place) | Operand::Move(place) = &receiver.node {
+        if reads_through_owner_validated_type(mir_analyzer, place.local) {
+            return None;
+        }
         if let Some(name) = trace_account_from_place(mir_analyzer, place, anchor_context_info) {
             return Some(name);
         }
@@ -254,6 +323,60 @@ fn extract_account_from_borrow<'cx, 'tcx>(
     None
 }
 
+// `AccountInfo` or `UncheckedAccount`, whose data is read without an owner check
+fn is_raw_account_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    is_account_info_type(cx.tcx, ty) || is_anchor_unchecked_account_type(cx.tcx, ty)
+}
+
+// `try_borrow_data` or `try_borrow_mut_data`
+fn is_data_access_fn(cx: &LateContext<'_>, fn_def_id: DefId) -> bool {
+    cx.tcx
+        .opt_item_name(fn_def_id)
+        .is_some_and(|name| DATA_ACCESS_METHODS.contains(&name.as_str()))
+}
+
+// Check if a helper borrows or deserializes the data of its `param_index`-th argument, e.g.
+// `fn read_len(account: &AccountInfo) -> Result<usize> { Ok(account.try_borrow_data()?.len()) }`
+fn helper_reads_param_data(
+    cx: &LateContext<'_>,
+    fn_def_id: LocalDefId,
+    param_index: usize,
+) -> bool {
+    let Some(body_id) = get_hir_body_from_local_def_id(cx, fn_def_id) else {
+        return false;
+    };
+    let mir_analyzer = MirAnalyzer::new(cx, cx.tcx.hir_body(body_id), fn_def_id);
+    if param_index >= mir_analyzer.mir.arg_count {
+        return false;
+    }
+    let param_local = Local::from_usize(param_index + 1);
+
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            return false;
+        };
+        let rustc_ty::FnDef(def_id, _) = func_const.ty().kind() else {
+            return false;
+        };
+        let reads_data = is_borrow_fn(cx.tcx, *def_id)
+            || is_data_access_fn(cx, *def_id)
+            || is_deserialize_fn(cx.tcx, *def_id);
+        reads_data
+            && args.iter().any(|arg| {
+                arg.node.place().is_some_and(|place| {
+                    mir_analyzer
+                        .collect_source_locals(place.local)
+                        .contains(&param_local)
+                })
+            })
+    })
+}
+
 // from deserialize method
 fn extract_account_from_deserialize<'cx, 'tcx>(
     cx: &LateContext<'tcx>,
@@ -269,6 +392,22 @@ fn extract_account_from_deserialize<'cx, 'tcx>(
     }
 }
 
+// Data read through a configured wrapper is owner-validated by the wrapper's constructor
+fn reads_through_owner_validated_type(
+    mir_analyzer: &MirAnalyzer,
+    local: rustc_middle::mir::Local,
+) -> bool {
+    mir_analyzer
+        .collect_source_locals(local)
+        .iter()
+        .any(|source_local| {
+            is_owner_validated_type(
+                mir_analyzer.cx,
+                mir_analyzer.mir.local_decls[*source_local].ty,
+            )
+        })
+}
+
 fn trace_account_from_local<'cx, 'tcx>(
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
     local: &rustc_middle::mir::Local,
@@ -304,6 +443,9 @@ fn extract_account_name_from_call_args<'cx, 'tcx>(
 ) -> Option<String> {
     for arg in args {
         if let Operand::Copy(place) | Operand::Move(place) = &arg.node {
+            if reads_through_owner_validated_type(mir_analyzer, place.local) {
+                continue;
+            }
             if let Some(account_name) =
                 trace_account_from_place(mir_analyzer, place, anchor_context_info)
             {
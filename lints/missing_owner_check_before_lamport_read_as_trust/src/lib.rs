@@ -0,0 +1,141 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::find_context_field, ensure_anchor_context_initialized,
+        should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+use std::collections::HashSet;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects instruction handlers comparing the lamport balance of an `UncheckedAccount` or
+    /// `AccountInfo` to decide a branch, e.g. `require!(vault.lamports() >= MIN_STAKE, ..)`,
+    /// when neither the accounts struct (`owner`, `address`, `seeds`, `constraint`, `has_one`)
+    /// nor the handler (a comparison of its key or owner) validates which account was passed.
+    ///
+    /// This is an advisory lint: a balance check is not always used to authorize the action.
+    ///
+    /// ### Why is this bad?
+    /// Anyone can send lamports to any account. When the balance of an unvalidated account is
+    /// treated as proof of a deposit, a stake or a legitimate participant, an attacker passes an
+    /// account they funded themselves and passes the check.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Claim<'info> {
+    ///     /// CHECK: stake vault of the claimer
+    ///     pub stake_vault: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    ///
+    /// require!(ctx.accounts.stake_vault.lamports() >= MIN_STAKE, ErrorCode::NotStaked);
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Claim<'info> {
+    ///     /// CHECK: stake vault of the claimer, a PDA of this program
+    ///     #[account(seeds = [b"stake", claimer.key().as_ref()], bump)]
+    ///     pub stake_vault: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    /// ```
+    pub MISSING_OWNER_CHECK_BEFORE_LAMPORT_READ_AS_TRUST,
+    Warn,
+    "lamport balance of an account with an unvalidated owner and key used to authorize an action"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingOwnerCheckBeforeLamportReadAsTrust {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_OWNER_CHECK_BEFORE_LAMPORT_READ_AS_TRUST) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_OWNER_CHECK_BEFORE_LAMPORT_READ_AS_TRUST);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_lamport_checks(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_lamport_checks<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    let mut reported = HashSet::new();
+    for read in collect_lamport_reads(mir_analyzer) {
+        if reported.contains(&read.account_name)
+            || !is_unchecked_account(cx, anchor_context, &read.account_name)
+        {
+            continue;
+        }
+
+        // A plain read, e.g. for logging or an amount, is not a trust decision
+        let Some(comparison_span) = find_gating_comparison(mir_analyzer, &read) else {
+            continue;
+        };
+
+        if has_identity_constraint(cx, anchor_context, &read.account_name)
+            || has_runtime_identity_check(cx, mir_analyzer, &read.account_name)
+        {
+            continue;
+        }
+
+        let Some(field) = find_context_field(cx, anchor_context, &read.account_name) else {
+            continue;
+        };
+        reported.insert(read.account_name.clone());
+        span_lint_and_note(
+            cx,
+            MISSING_OWNER_CHECK_BEFORE_LAMPORT_READ_AS_TRUST,
+            comparison_span,
+            format!(
+                "the lamport balance of `{}` decides this check, but its owner and key are not validated — anyone can fund an account to pass it; constrain the account with `seeds`, `address` or `owner`",
+                read.account_name
+            ),
+            Some(cx.tcx.def_span(field.did)),
+            "account is declared here without an owner or key check",
+        );
+    }
+}
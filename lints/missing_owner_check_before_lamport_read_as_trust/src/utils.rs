@@ -0,0 +1,201 @@
+use anchor_lints_utils::{
+    diag_items::{is_account_info_type, is_anchor_unchecked_account_type},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::{account_constraints::parse_account_constraints, extract_arg_local},
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BinOp, Local, Operand, Rvalue, StatementKind, TerminatorKind, UnOp},
+    ty::{self as rustc_ty, TyKind, UintTy},
+};
+use rustc_span::{Span, sym};
+
+use std::collections::HashSet;
+
+/// Methods reading the lamport balance of an account
+const LAMPORTS_METHODS: &[&str] = &["lamports", "get_lamports"];
+
+/// A read of the lamport balance of a context account
+pub struct LamportRead {
+    pub account_name: String,
+    pub destination: Local,
+}
+
+/// Collect the `account.lamports()`/`account.get_lamports()` calls on context accounts
+pub fn collect_lamport_reads(mir_analyzer: &MirAnalyzer) -> Vec<LamportRead> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mut reads = Vec::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && tcx
+                .opt_item_name(*fn_def_id)
+                .is_some_and(|name| LAMPORTS_METHODS.contains(&name.as_str()))
+            && matches!(
+                tcx.fn_sig(*fn_def_id)
+                    .skip_binder()
+                    .skip_binder()
+                    .output()
+                    .kind(),
+                TyKind::Uint(UintTy::U64)
+            )
+            && let Some(destination) = destination.as_local()
+            && let Some(receiver) = extract_arg_local(args, 0)
+            && let Some(account) = mir_analyzer.extract_account_name_from_local(&receiver, true)
+        {
+            let account_name = account
+                .account_name
+                .split('.')
+                .next()
+                .unwrap_or(&account.account_name)
+                .to_string();
+            reads.push(LamportRead {
+                account_name,
+                destination,
+            });
+        }
+    }
+    reads
+}
+
+/// Find an ordering comparison on the lamport balance whose result decides a branch, e.g.
+/// `require!(vault.lamports() >= MIN_STAKE, ..)` or `if vault.lamports() > THRESHOLD { .. }`
+pub fn find_gating_comparison(mir_analyzer: &MirAnalyzer, read: &LamportRead) -> Option<Span> {
+    let branch_conditions = collect_branch_conditions(mir_analyzer);
+    let uses_balance = |operand: &Operand<'_>| {
+        operand
+            .place()
+            .and_then(|place| place.as_local())
+            .is_some_and(|local| {
+                mir_analyzer
+                    .collect_source_locals(local)
+                    .contains(&read.destination)
+            })
+    };
+    mir_analyzer.mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata.statements.iter().find_map(|stmt| {
+            if let StatementKind::Assign(box (place, Rvalue::BinaryOp(op, box (lhs, rhs)))) =
+                &stmt.kind
+                && matches!(op, BinOp::Gt | BinOp::Ge | BinOp::Lt | BinOp::Le)
+                && let Some(result) = place.as_local()
+                && branch_conditions.contains(&result)
+                && (uses_balance(lhs) || uses_balance(rhs))
+            {
+                Some(stmt.source_info.span)
+            } else {
+                None
+            }
+        })
+    })
+}
+
+/// Collect the locals deciding a `switchInt`, directly or through a negation as in the
+/// `if !(cond) { return Err(..) }` expansion of `require!`
+fn collect_branch_conditions(mir_analyzer: &MirAnalyzer) -> HashSet<Local> {
+    let mir = mir_analyzer.mir;
+    let mut conditions: HashSet<Local> = mir
+        .basic_blocks
+        .iter()
+        .filter_map(|bbdata| match &bbdata.terminator().kind {
+            TerminatorKind::SwitchInt { discr, .. } => discr.place().and_then(|p| p.as_local()),
+            _ => None,
+        })
+        .collect();
+    for bbdata in mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (place, Rvalue::UnaryOp(UnOp::Not, operand))) =
+                &stmt.kind
+                && place
+                    .as_local()
+                    .is_some_and(|local| conditions.contains(&local))
+                && let Some(negated) = operand.place().and_then(|p| p.as_local())
+            {
+                conditions.insert(negated);
+            }
+        }
+    }
+    conditions
+}
+
+/// Check if the account is an `UncheckedAccount` or `AccountInfo`, whose owner is not checked
+/// by Anchor
+pub fn is_unchecked_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    account_name: &str,
+) -> bool {
+    anchor_context
+        .anchor_context_arg_accounts_type
+        .get(account_name)
+        .is_some_and(|ty| {
+            is_anchor_unchecked_account_type(cx.tcx, *ty) || is_account_info_type(cx.tcx, *ty)
+        })
+}
+
+/// Check if the accounts struct pins the owner or key of the account: an `owner`, `address`,
+/// `seeds` or `constraint` on the field, or a `has_one` on another field referring to it
+pub fn has_identity_constraint<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    account_name: &str,
+) -> bool {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return false;
+    };
+    if !adt_def.is_struct() {
+        return false;
+    }
+    adt_def.non_enum_variant().fields.iter().any(|field| {
+        let constraints = parse_account_constraints(cx, field);
+        if field.ident(cx.tcx).as_str() == account_name {
+            constraints.owner.is_some()
+                || constraints.address.is_some()
+                || constraints.seeds.is_some()
+                || !constraints.constraints.is_empty()
+        } else {
+            constraints
+                .has_one
+                .iter()
+                .any(|target| target.path == account_name)
+        }
+    })
+}
+
+/// Check if the handler compares the key or owner of the account, e.g.
+/// `require_keys_eq!(vault.key(), EXPECTED_VAULT)` or `vault.owner == &crate::ID`
+pub fn has_runtime_identity_check(
+    cx: &LateContext<'_>,
+    mir_analyzer: &MirAnalyzer,
+    account_name: &str,
+) -> bool {
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && (cx.tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
+                || cx.tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id))
+        {
+            (0..args.len()).any(|index| {
+                extract_arg_local(args, index)
+                    .and_then(|local| mir_analyzer.extract_account_name_from_local(&local, true))
+                    .is_some_and(|compared| {
+                        compared.account_name == account_name
+                            || compared
+                                .account_name
+                                .starts_with(&format!("{}.", account_name))
+                    })
+            })
+        } else {
+            false
+        }
+    })
+}
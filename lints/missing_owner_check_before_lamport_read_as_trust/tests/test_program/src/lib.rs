@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+const MIN_STAKE: u64 = 1_000_000_000;
+const EXPECTED_VAULT: Pubkey = anchor_lang::pubkey!("SysvarRent111111111111111111111111111111111");
+
+#[program]
+pub mod missing_owner_check_before_lamport_read_as_trust_tests {
+    use super::*;
+
+    // BAD: the balance of an unchecked account gates the claim
+    pub fn claim_with_unchecked_vault(ctx: Context<ClaimUnchecked>) -> Result<()> {
+        require!(ctx.accounts.stake_vault.lamports() >= MIN_STAKE, ErrorCode::NotStaked); // [missing_owner_check_before_lamport_read_as_trust]
+        msg!("claimed by {}", ctx.accounts.claimer.key());
+        Ok(())
+    }
+
+    // BAD: the balance of an `AccountInfo` decides whether the action runs
+    pub fn unlock_with_account_info(ctx: Context<UnlockAccountInfo>) -> Result<()> {
+        let balance = ctx.accounts.deposit.lamports();
+        if balance > MIN_STAKE { // [missing_owner_check_before_lamport_read_as_trust]
+            msg!("unlocked for {}", ctx.accounts.claimer.key());
+        }
+        Ok(())
+    }
+
+    // GOOD: the vault is a PDA of this program
+    pub fn claim_with_pda_vault(ctx: Context<ClaimPda>) -> Result<()> {
+        require!(ctx.accounts.stake_vault.lamports() >= MIN_STAKE, ErrorCode::NotStaked); // [safe_lamport_trust]
+        Ok(())
+    }
+
+    // GOOD: the vault is owned by this program
+    pub fn claim_with_owned_vault(ctx: Context<ClaimOwned>) -> Result<()> {
+        require!(ctx.accounts.stake_vault.lamports() >= MIN_STAKE, ErrorCode::NotStaked); // [safe_lamport_trust]
+        Ok(())
+    }
+
+    // GOOD: the handler checks the key of the vault
+    pub fn claim_with_key_check(ctx: Context<ClaimUnchecked>) -> Result<()> {
+        require_keys_eq!(ctx.accounts.stake_vault.key(), EXPECTED_VAULT);
+        require!(ctx.accounts.stake_vault.lamports() >= MIN_STAKE, ErrorCode::NotStaked); // [safe_lamport_trust]
+        Ok(())
+    }
+
+    // GOOD: the balance is only logged
+    pub fn log_balance(ctx: Context<ClaimUnchecked>) -> Result<()> {
+        msg!("balance: {}", ctx.accounts.stake_vault.lamports()); // [safe_lamport_trust]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimUnchecked<'info> {
+    /// CHECK: stake vault of the claimer
+    pub stake_vault: UncheckedAccount<'info>,
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnlockAccountInfo<'info> {
+    /// CHECK: deposit of the claimer
+    pub deposit: AccountInfo<'info>,
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPda<'info> {
+    /// CHECK: stake vault PDA of the claimer
+    #[account(seeds = [b"stake", claimer.key().as_ref()], bump)]
+    pub stake_vault: UncheckedAccount<'info>,
+    pub claimer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimOwned<'info> {
+    /// CHECK: stake vault owned by this program
+    #[account(owner = crate::ID)]
+    pub stake_vault: UncheckedAccount<'info>,
+    pub claimer: Signer<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Not enough stake")]
+    NotStaked,
+}
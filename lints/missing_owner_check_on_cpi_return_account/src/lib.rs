@@ -0,0 +1,196 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::{DiagnoticItem, is_cpi_invoke_fn},
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, has_account_constraint},
+        should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{BasicBlock, Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+use std::collections::{HashMap, HashSet};
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects raw account data reads (`try_borrow_data`, `try_borrow_mut_data`) after a CPI
+    /// the account was passed to, when the account is mutable and its `owner` is not checked
+    /// again between the CPI and the read.
+    ///
+    /// ### Why is this bad?
+    /// A writable account passed to a CPI can be reassigned to another program, e.g. with
+    /// `system_program::assign`. Bytes read afterwards may have been written by whichever
+    /// program now owns the account, not by the program expected to initialize it.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: the account may no longer be owned by the expected program
+    /// invoke(&init_ix, &account_infos)?;
+    /// let data = ctx.accounts.state.try_borrow_data()?;
+    ///
+    /// Good: the owner is re-validated after the CPI
+    /// invoke(&init_ix, &account_infos)?;
+    /// require_keys_eq!(*ctx.accounts.state.owner, EXPECTED_PROGRAM_ID);
+    /// let data = ctx.accounts.state.try_borrow_data()?;
+    /// ```
+    pub MISSING_OWNER_CHECK_ON_CPI_RETURN_ACCOUNT,
+    Warn,
+    "account data read after a CPI without re-checking the account owner"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingOwnerCheckOnCpiReturnAccount {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_OWNER_CHECK_ON_CPI_RETURN_ACCOUNT) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_OWNER_CHECK_ON_CPI_RETURN_ACCOUNT);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_post_cpi_data_reads(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+struct AccountDataRead {
+    block: BasicBlock,
+    span: Span,
+    account_name: String,
+}
+
+fn analyze_post_cpi_data_reads<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    // CPIs and the accounts passed to them
+    let mut cpi_calls: Vec<(BasicBlock, Span, HashSet<String>)> = Vec::new();
+    let mut data_reads: Vec<AccountDataRead> = Vec::new();
+    // Blocks reading the `owner` of each account
+    let mut owner_checks: HashMap<String, HashSet<BasicBlock>> = HashMap::new();
+
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        for stmt in &bbdata.statements {
+            if let Some(account_name) = owner_check_account(mir_analyzer, stmt) {
+                owner_checks.entry(account_name).or_default().insert(bb);
+            }
+        }
+
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+        if fn_span.from_expansion() {
+            continue;
+        }
+
+        let is_invoke = is_cpi_invoke_fn(cx.tcx, *fn_def_id);
+        if is_invoke || mir_analyzer.takes_cpi_context(args) {
+            // Moving lamports never changes the owner
+            if DiagnoticItem::AnchorSystemProgramTransfer.defid_is_item(cx.tcx, *fn_def_id) {
+                continue;
+            }
+            let accounts = extract_cpi_account_names(mir_analyzer, bb, args, is_invoke)
+                .iter()
+                .map(|account_name| account_base_name(account_name))
+                .collect();
+            cpi_calls.push((bb, *fn_span, accounts));
+        } else if cx
+            .tcx
+            .opt_item_name(*fn_def_id)
+            .is_some_and(|name| DATA_READ_METHODS.contains(&name.as_str()))
+            && let Some(receiver) = args.first()
+            && let Some(receiver_local) = receiver.node.place().and_then(|place| place.as_local())
+            && let Some(account) =
+                mir_analyzer.extract_account_name_from_local(&receiver_local, true)
+        {
+            data_reads.push(AccountDataRead {
+                block: bb,
+                span: *fn_span,
+                account_name: account_base_name(&account.account_name),
+            });
+        }
+    }
+
+    let no_checks = HashSet::new();
+    for read in data_reads {
+        // Only writable accounts can be reassigned by the CPI
+        let Some(field) = find_context_field(cx, anchor_context, &read.account_name) else {
+            continue;
+        };
+        if !has_account_constraint(cx, field, "mut") {
+            continue;
+        }
+
+        let checks = owner_checks.get(&read.account_name).unwrap_or(&no_checks);
+        if let Some((_, cpi_span, _)) = cpi_calls.iter().find(|(cpi_block, _, accounts)| {
+            accounts.contains(&read.account_name)
+                && reachable_without_check(
+                    &mir_analyzer.mir.basic_blocks,
+                    *cpi_block,
+                    read.block,
+                    checks,
+                )
+        }) {
+            span_lint_and_note(
+                cx,
+                MISSING_OWNER_CHECK_ON_CPI_RETURN_ACCOUNT,
+                read.span,
+                format!(
+                    "data of `{}` is read after a CPI that may have reassigned it to another program — check `{}.owner` again before reading its data",
+                    read.account_name, read.account_name
+                ),
+                Some(*cpi_span),
+                "the account is passed to this CPI",
+            );
+        }
+    }
+}
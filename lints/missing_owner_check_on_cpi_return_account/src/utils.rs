@@ -0,0 +1,129 @@
+use anchor_lints_utils::{
+    diag_items::{is_account_info_type, is_anchor_cpi_context},
+    mir_analyzer::MirAnalyzer,
+    utils::find_cpi_accounts,
+};
+
+use rustc_middle::{
+    mir::{
+        BasicBlock, BasicBlocks, Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind,
+    },
+    ty::TyKind,
+};
+use rustc_span::source_map::Spanned;
+
+use std::collections::{HashSet, VecDeque};
+
+/// `AccountInfo` methods reading the raw account data
+pub const DATA_READ_METHODS: &[&str] = &["try_borrow_data", "try_borrow_mut_data"];
+
+/// Collect the context accounts passed to the CPI terminating `cpi_block`, either in the
+/// `account_infos` of `invoke`/`invoke_signed` or in the accounts struct of a `CpiContext`
+pub fn extract_cpi_account_names(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    args: &[Spanned<Operand>],
+    is_invoke: bool,
+) -> HashSet<String> {
+    if is_invoke {
+        return args
+            .get(1)
+            .map(|account_infos| {
+                mir_analyzer
+                    .collect_accounts_from_account_infos_arg(account_infos, true)
+                    .into_iter()
+                    .map(|account| account.account_name)
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    let Some(cpi_ctx_local) = args.iter().find_map(|arg| {
+        arg.node
+            .place()
+            .and_then(|place| place.as_local())
+            .filter(|local| {
+                is_anchor_cpi_context(mir_analyzer.cx.tcx, mir_analyzer.mir.local_decls[*local].ty)
+            })
+    }) else {
+        return HashSet::new();
+    };
+    let Some(accounts_local) = find_cpi_accounts(mir_analyzer, cpi_block, cpi_ctx_local) else {
+        return HashSet::new();
+    };
+    mir_analyzer
+        .find_cpi_accounts_struct(&accounts_local, &mut HashSet::new())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|local| mir_analyzer.extract_account_name_from_local(local, true))
+        .map(|account| account.account_name)
+        .collect()
+}
+
+/// If `stmt` reads the `owner` of an `AccountInfo`, return the name of the account
+pub fn owner_check_account(mir_analyzer: &MirAnalyzer, stmt: &Statement) -> Option<String> {
+    let StatementKind::Assign(box (_, rvalue)) = &stmt.kind else {
+        return None;
+    };
+    let place = match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) | Rvalue::Ref(_, _, place) => {
+            place
+        }
+        _ => return None,
+    };
+    if !reads_account_info_owner(mir_analyzer, place) {
+        return None;
+    }
+    mir_analyzer
+        .extract_account_name_from_local(&place.local, true)
+        .map(|account| account_base_name(&account.account_name))
+}
+
+fn reads_account_info_owner(mir_analyzer: &MirAnalyzer, place: &Place) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    place.iter_projections().any(|(base, elem)| {
+        let ProjectionElem::Field(field_idx, _) = elem else {
+            return false;
+        };
+        let base_ty = base.ty(mir_analyzer.mir, tcx).ty;
+        is_account_info_type(tcx, base_ty)
+            && matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+                if adt_def.non_enum_variant().fields[field_idx].name.as_str() == "owner")
+    })
+}
+
+/// `vault` for `vault.to_account_info` and similar names resolved from method calls
+pub fn account_base_name(account_name: &str) -> String {
+    account_name
+        .split('.')
+        .next()
+        .unwrap_or(account_name)
+        .to_string()
+}
+
+/// Check whether `to` can be reached from `from` without going through any of `checks`.
+/// A check in `to` itself comes before its terminator and guards the read.
+pub fn reachable_without_check(
+    graph: &BasicBlocks,
+    from: BasicBlock,
+    to: BasicBlock,
+    checks: &HashSet<BasicBlock>,
+) -> bool {
+    let mut queue = VecDeque::from([from]);
+    let mut visited = HashSet::from([from]);
+
+    while let Some(bb) = queue.pop_front() {
+        if let Some(terminator) = &graph[bb].terminator {
+            for succ in terminator.successors() {
+                if checks.contains(&succ) || !visited.insert(succ) {
+                    continue;
+                }
+                if succ == to {
+                    return true;
+                }
+                queue.push_back(succ);
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::system_program::{self, Assign, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+pub const STATE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([9u8; 32]);
+
+#[program]
+pub mod missing_owner_check_on_cpi_return_account_tests {
+    use super::*;
+
+    // BAD: the state program may have handed the account to another program
+    pub fn initialize_and_read(ctx: Context<InitializeState>) -> Result<()> {
+        let ix = Instruction {
+            program_id: STATE_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(ctx.accounts.state.key(), true)],
+            data: vec![],
+        };
+        let account_infos = vec![ctx.accounts.state.to_account_info()];
+        invoke(&ix, &account_infos)?;
+
+        let data = ctx.accounts.state.try_borrow_data()?; // [missing_owner_check_on_cpi_return_account]
+        msg!("state: {:?}", &data[..8]);
+        Ok(())
+    }
+
+    // GOOD: the owner is checked again after the CPI
+    pub fn initialize_check_owner_and_read(ctx: Context<InitializeState>) -> Result<()> {
+        let ix = Instruction {
+            program_id: STATE_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(ctx.accounts.state.key(), true)],
+            data: vec![],
+        };
+        let account_infos = vec![ctx.accounts.state.to_account_info()];
+        invoke(&ix, &account_infos)?;
+
+        require_keys_eq!(*ctx.accounts.state.owner, STATE_PROGRAM_ID);
+        let data = ctx.accounts.state.try_borrow_data()?; // [safe_post_cpi_data_read]
+        msg!("state: {:?}", &data[..8]);
+        Ok(())
+    }
+
+    // BAD: the account is explicitly reassigned before its data is read
+    pub fn assign_and_read(ctx: Context<InitializeState>) -> Result<()> {
+        let cpi_accounts = Assign {
+            account_to_assign: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::assign(cpi_ctx, &STATE_PROGRAM_ID)?;
+
+        let data = ctx.accounts.state.try_borrow_mut_data()?; // [missing_owner_check_on_cpi_return_account]
+        msg!("state: {:?}", &data[..8]);
+        Ok(())
+    }
+
+    // GOOD: the data is read before the CPI
+    pub fn read_and_initialize(ctx: Context<InitializeState>) -> Result<()> {
+        {
+            let data = ctx.accounts.state.try_borrow_data()?; // [safe_post_cpi_data_read]
+            msg!("state: {:?}", &data[..8]);
+        }
+
+        let ix = Instruction {
+            program_id: STATE_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(ctx.accounts.state.key(), true)],
+            data: vec![],
+        };
+        let account_infos = vec![ctx.accounts.state.to_account_info()];
+        invoke(&ix, &account_infos)?;
+        Ok(())
+    }
+
+    // GOOD: a lamport transfer cannot change the owner
+    pub fn fund_and_read(ctx: Context<InitializeState>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.state.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let data = ctx.accounts.state.try_borrow_data()?; // [safe_post_cpi_data_read]
+        msg!("state: {:?}", &data[..8]);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: initialized by the state program
+    #[account(mut, signer)]
+    pub state: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,179 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::is_anchor_account_loader_type,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{extract_arg_local, owner_read_local, same_account_info, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Local, Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects zero-copy accounts constructed with `AccountLoader::try_from_unchecked` and then
+    /// read with `load()` or `load_mut()`, when the owner of the account is not checked
+    /// otherwise, neither by an accounts struct constraint nor by a manual `owner` check.
+    ///
+    /// ### Why is this bad?
+    /// `try_from_unchecked` skips the owner validation `AccountLoader::try_from` and typed
+    /// `AccountLoader<'info, T>` fields perform. An attacker can pass an account owned by another
+    /// program holding bytes of their choice, which the program then loads as trusted state.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let loader = AccountLoader::<Pool>::try_from_unchecked(&crate::ID, &ctx.remaining_accounts[0])?;
+    /// let pool = loader.load()?;
+    ///
+    /// // Good:
+    /// let loader = AccountLoader::<Pool>::try_from(&ctx.remaining_accounts[0])?;
+    /// let pool = loader.load()?;
+    /// ```
+    pub MISSING_OWNER_CHECK_ON_MANUALLY_LOADED_ZERO_COPY,
+    Warn,
+    "zero-copy account loaded through `try_from_unchecked` without an owner check"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingOwnerCheckOnManuallyLoadedZeroCopy {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_OWNER_CHECK_ON_MANUALLY_LOADED_ZERO_COPY) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_OWNER_CHECK_ON_MANUALLY_LOADED_ZERO_COPY);
+
+        analyze_unchecked_loaders(cx, &mir_analyzer);
+    }
+}
+
+/// An `AccountLoader::try_from_unchecked(program_id, account_info)` call
+struct UncheckedLoader {
+    span: Span,
+    account_info: Local,
+    loader: Local,
+}
+
+fn analyze_unchecked_loaders<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    let mir = mir_analyzer.mir;
+    let mut unchecked_loaders: Vec<UncheckedLoader> = Vec::new();
+    // Receivers of `load` calls, with the span of the call
+    let mut loads: Vec<(Local, Span)> = Vec::new();
+    // Locals whose `owner` is read
+    let mut owner_reads: Vec<Local> = Vec::new();
+
+    for bbdata in mir.basic_blocks.iter() {
+        owner_reads.extend(
+            bbdata
+                .statements
+                .iter()
+                .filter_map(|stmt| owner_read_local(mir_analyzer, stmt)),
+        );
+
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+
+        if is_loader_try_from_unchecked(cx, *fn_def_id)
+            && let Some(account_info) = extract_arg_local(args, 1)
+            && let Some(loader) = destination.as_local()
+        {
+            unchecked_loaders.push(UncheckedLoader {
+                span: *fn_span,
+                account_info,
+                loader,
+            });
+        } else if cx
+            .tcx
+            .opt_item_name(*fn_def_id)
+            .is_some_and(|name| LOAD_METHODS.contains(&name.as_str()))
+            && let Some(receiver) = extract_arg_local(args, 0)
+            && is_anchor_account_loader_type(cx.tcx, mir.local_decls[receiver].ty)
+        {
+            loads.push((receiver, *fn_span));
+        }
+    }
+
+    for unchecked_loader in unchecked_loaders {
+        // Only loaders whose data is read are reported
+        let Some((_, load_span)) = loads.iter().find(|(receiver, _)| {
+            mir_analyzer
+                .collect_source_locals(*receiver)
+                .contains(&unchecked_loader.loader)
+        }) else {
+            continue;
+        };
+
+        let account_name = context_account_name(mir_analyzer, unchecked_loader.account_info);
+        if account_name.as_deref().is_some_and(|name| {
+            is_validated_context_account(cx, mir_analyzer.anchor_context_info.as_ref(), name)
+        }) {
+            continue;
+        }
+
+        let owner_checked = owner_reads.iter().any(|owner_read| {
+            same_account_info(mir_analyzer, unchecked_loader.account_info, *owner_read)
+                || (account_name.is_some()
+                    && context_account_name(mir_analyzer, *owner_read) == account_name)
+        });
+        if owner_checked {
+            continue;
+        }
+
+        let account =
+            account_name.map_or_else(|| "the account".to_string(), |name| format!("`{}`", name));
+        span_lint_and_note(
+            cx,
+            MISSING_OWNER_CHECK_ON_MANUALLY_LOADED_ZERO_COPY,
+            unchecked_loader.span,
+            format!(
+                "`AccountLoader::try_from_unchecked` does not check the owner of {} before its data is loaded — use `AccountLoader::try_from` or check its `owner` first",
+                account
+            ),
+            Some(*load_span),
+            "the zero-copy data is loaded here",
+        );
+    }
+}
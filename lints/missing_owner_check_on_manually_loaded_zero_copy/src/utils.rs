@@ -0,0 +1,82 @@
+use anchor_lints_utils::{
+    diag_items::{is_account_info_type, is_anchor_account_loader_type},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::{account_constraints::parse_account_constraints, is_unchecked_account_type},
+};
+
+use clippy_utils::ty::is_type_diagnostic_item;
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::Local,
+    ty::{Ty, TyKind},
+};
+use rustc_span::sym;
+
+/// `AccountLoader` methods reading the zero-copy data of an initialized account
+pub const LOAD_METHODS: &[&str] = &["load", "load_mut"];
+
+/// Check if `fn_def_id` is `AccountLoader::try_from_unchecked`, which skips the owner check
+pub fn is_loader_try_from_unchecked<'tcx>(cx: &LateContext<'tcx>, fn_def_id: DefId) -> bool {
+    let named_try_from_unchecked = cx
+        .tcx
+        .opt_item_name(fn_def_id)
+        .is_some_and(|name| name.as_str() == "try_from_unchecked");
+    if !named_try_from_unchecked {
+        return false;
+    }
+    let return_ty = cx
+        .tcx
+        .fn_sig(fn_def_id)
+        .skip_binder()
+        .skip_binder()
+        .output();
+    returns_account_loader(cx, return_ty)
+}
+
+fn returns_account_loader<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    if is_anchor_account_loader_type(cx.tcx, ty) {
+        return true;
+    }
+    is_type_diagnostic_item(cx, ty, sym::Result)
+        && matches!(ty.kind(), TyKind::Adt(_, generics)
+            if generics.types().next().is_some_and(|ok_ty| is_anchor_account_loader_type(cx.tcx, ok_ty)))
+}
+
+/// Name of the accounts struct field a local is read from, e.g. `pool` for
+/// `ctx.accounts.pool.to_account_info()`
+pub fn context_account_name(mir_analyzer: &MirAnalyzer, local: Local) -> Option<String> {
+    mir_analyzer
+        .extract_account_name_from_local(&local, true)
+        .and_then(|account| account.account_name.split('.').next().map(str::to_string))
+}
+
+/// Check if the account info is a field of the accounts struct validated by Anchor, i.e. a typed
+/// field or an `UncheckedAccount`/`AccountInfo` with an `owner = ...` constraint
+pub fn is_validated_context_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: Option<&AnchorContextInfo<'tcx>>,
+    account_name: &str,
+) -> bool {
+    let Some(anchor_context) = anchor_context else {
+        return false;
+    };
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return false;
+    };
+    if !adt_def.is_struct() {
+        return false;
+    }
+    let Some(field) = adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == account_name)
+    else {
+        return false;
+    };
+    let field_ty = cx.tcx.type_of(field.did).instantiate_identity();
+    let is_unvalidated_type =
+        is_unchecked_account_type(cx, field_ty) || is_account_info_type(cx.tcx, field_ty);
+    !is_unvalidated_type || parse_account_constraints(cx, field).owner.is_some()
+}
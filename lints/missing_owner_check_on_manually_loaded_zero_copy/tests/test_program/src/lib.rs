@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_owner_check_on_manually_loaded_zero_copy_tests {
+    use super::*;
+
+    // BAD: `try_from_unchecked` skips the owner check and the data is loaded
+    pub fn load_unchecked<'info>(ctx: Context<'_, '_, 'info, 'info, LoadPool<'info>>) -> Result<()> {
+        let loader = AccountLoader::<Pool>::try_from_unchecked(&crate::ID, &ctx.remaining_accounts[0])?; // [missing_owner_check_on_manually_loaded_zero_copy]
+        let pool = loader.load()?;
+        msg!("{}", pool.value);
+        Ok(())
+    }
+
+    // BAD: chained `load_mut` on an unchecked loader
+    pub fn load_mut_unchecked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LoadPool<'info>>,
+        value: u64,
+    ) -> Result<()> {
+        let info = &ctx.remaining_accounts[0];
+        AccountLoader::<Pool>::try_from_unchecked(&crate::ID, info)?.load_mut()?.value = value; // [missing_owner_check_on_manually_loaded_zero_copy]
+        Ok(())
+    }
+
+    // GOOD: `try_from` validates the owner
+    pub fn load_checked<'info>(ctx: Context<'_, '_, 'info, 'info, LoadPool<'info>>) -> Result<()> {
+        let loader = AccountLoader::<Pool>::try_from(&ctx.remaining_accounts[0])?; // [safe_zero_copy_load]
+        let pool = loader.load()?;
+        msg!("{}", pool.value);
+        Ok(())
+    }
+
+    // GOOD: the owner is checked before `try_from_unchecked`
+    pub fn load_unchecked_with_owner_check<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LoadPool<'info>>,
+    ) -> Result<()> {
+        let info = &ctx.remaining_accounts[0];
+        require_keys_eq!(*info.owner, crate::ID);
+        let loader = AccountLoader::<Pool>::try_from_unchecked(&crate::ID, info)?; // [safe_zero_copy_load]
+        let pool = loader.load()?;
+        msg!("{}", pool.value);
+        Ok(())
+    }
+
+    // GOOD: a typed `AccountLoader` field is validated by Anchor
+    pub fn load_typed_field(ctx: Context<LoadPool>) -> Result<()> {
+        let pool = ctx.accounts.pool.load()?; // [safe_zero_copy_load]
+        msg!("{}", pool.value);
+        Ok(())
+    }
+
+    // GOOD: the data of an unchecked loader is never loaded
+    pub fn construct_unchecked_only<'info>(
+        ctx: Context<'_, '_, 'info, 'info, LoadPool<'info>>,
+    ) -> Result<()> {
+        let loader = AccountLoader::<Pool>::try_from_unchecked(&crate::ID, &ctx.remaining_accounts[0])?; // [safe_zero_copy_load]
+        msg!("{}", loader.key());
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct LoadPool<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub pool: AccountLoader<'info, Pool>,
+}
+
+#[account(zero_copy)]
+pub struct Pool {
+    pub value: u64,
+}
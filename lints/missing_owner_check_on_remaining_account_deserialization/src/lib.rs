@@ -0,0 +1,127 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::is_anchor_account_deserialize_fn,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{
+        collect_account_deserializations, collect_owner_reads, remaining_accounts_read,
+        same_account_info, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `#[account]` structs decoded with `try_deserialize` or
+    /// `try_deserialize_unchecked` from the data of an account taken from
+    /// `ctx.remaining_accounts`, when the `owner` of that account is never checked.
+    ///
+    /// ### Why is this bad?
+    /// Remaining accounts bypass the validation Anchor performs for `Account<'info, T>` fields.
+    /// The discriminator check of `try_deserialize` does not help: an attacker can create an
+    /// account owned by their own program that starts with the same discriminator and holds
+    /// fields of their choice, which the program then trusts as its own state.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let acc = &ctx.remaining_accounts[0];
+    /// let config = Config::try_deserialize(&mut &acc.try_borrow_data()?[..])?;
+    ///
+    /// // Good:
+    /// let acc = &ctx.remaining_accounts[0];
+    /// require_keys_eq!(*acc.owner, crate::ID, ErrorCode::InvalidOwner);
+    /// let config = Config::try_deserialize(&mut &acc.try_borrow_data()?[..])?;
+    /// ```
+    pub MISSING_OWNER_CHECK_ON_REMAINING_ACCOUNT_DESERIALIZATION,
+    Warn,
+    "`#[account]` struct deserialized from a remaining account without an owner check"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingOwnerCheckOnRemainingAccountDeserialization {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_OWNER_CHECK_ON_REMAINING_ACCOUNT_DESERIALIZATION) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(MISSING_OWNER_CHECK_ON_REMAINING_ACCOUNT_DESERIALIZATION);
+
+        analyze_remaining_account_deserializations(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_remaining_account_deserializations<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+) {
+    let deserializations = collect_account_deserializations(mir_analyzer);
+    if deserializations.is_empty() {
+        return;
+    }
+    let owner_reads = collect_owner_reads(mir_analyzer);
+
+    for deserialization in deserializations {
+        if !is_anchor_account_deserialize_fn(cx.tcx, deserialization.def_id) {
+            continue;
+        }
+
+        // Only data borrowed from a remaining account is unvalidated by Anchor
+        let Some(remaining_account_span) =
+            remaining_accounts_read(mir_analyzer, deserialization.data_borrow)
+        else {
+            continue;
+        };
+
+        let owner_checked = owner_reads.iter().any(|owner_read| {
+            same_account_info(mir_analyzer, deserialization.data_borrow, *owner_read)
+        });
+        if owner_checked {
+            continue;
+        }
+
+        let decoded_ty = decoded_type(
+            cx,
+            mir_analyzer.mir.local_decls[deserialization.destination].ty,
+        );
+        span_lint_and_note(
+            cx,
+            MISSING_OWNER_CHECK_ON_REMAINING_ACCOUNT_DESERIALIZATION,
+            deserialization.span,
+            format!(
+                "`{}` is deserialized from a remaining account whose owner is never checked — remaining accounts skip Anchor's `Account` validation; check its `owner` first or use `Account::<{}>::try_from`",
+                decoded_ty, decoded_ty,
+            ),
+            Some(remaining_account_span),
+            "the account is taken from `ctx.remaining_accounts` here",
+        );
+    }
+}
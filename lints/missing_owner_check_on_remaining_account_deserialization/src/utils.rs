@@ -0,0 +1,16 @@
+use clippy_utils::ty::is_type_diagnostic_item;
+
+use rustc_lint::LateContext;
+use rustc_middle::ty::{Ty, TyKind};
+use rustc_span::sym;
+
+/// The type a deserialization call decodes, i.e. `T` for a call returning `Result<T>`
+pub fn decoded_type<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+    if is_type_diagnostic_item(cx, ty, sym::Result)
+        && let TyKind::Adt(_, args) = ty.kind()
+    {
+        args.type_at(0)
+    } else {
+        ty
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_owner_check_on_remaining_account_deserialization_tests {
+    use super::*;
+
+    // BAD: the config is decoded from a remaining account of any owner
+    pub fn read_config(ctx: Context<ReadConfig>) -> Result<()> {
+        let acc = &ctx.remaining_accounts[0];
+        let data = acc.try_borrow_data()?;
+        let config = Config::try_deserialize(&mut &data[..])?; // [missing_owner_check_on_remaining_account_deserialization: note=the account is taken from `ctx.remaining_accounts` here]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+
+    // BAD: `try_deserialize_unchecked` does not even check the discriminator
+    pub fn read_config_unchecked(ctx: Context<ReadConfig>) -> Result<()> {
+        let acc = ctx.remaining_accounts.get(0).ok_or(ErrorCode::MissingConfig)?;
+        let config = Config::try_deserialize_unchecked(&mut &acc.data.borrow()[..])?; // [missing_owner_check_on_remaining_account_deserialization]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+
+    // BAD: the owner of another remaining account is checked
+    pub fn read_config_checking_vault(ctx: Context<ReadConfig>) -> Result<()> {
+        let acc = &ctx.remaining_accounts[0];
+        let vault = &ctx.remaining_accounts[1];
+        require_keys_eq!(*vault.owner, crate::ID);
+        let data = acc.try_borrow_data()?;
+        let config = Config::try_deserialize(&mut &data[..])?; // [missing_owner_check_on_remaining_account_deserialization]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+
+    // GOOD: the owner is checked before the config is decoded
+    pub fn read_config_with_owner_check(ctx: Context<ReadConfig>) -> Result<()> {
+        let acc = &ctx.remaining_accounts[0];
+        require_keys_eq!(*acc.owner, crate::ID);
+        let data = acc.try_borrow_data()?;
+        let config = Config::try_deserialize(&mut &data[..])?; // [safe_remaining_account_deserialization]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+
+    // GOOD: the owner is compared by reference
+    pub fn read_config_with_owner_comparison(ctx: Context<ReadConfig>) -> Result<()> {
+        let acc = &ctx.remaining_accounts[0];
+        if acc.owner != &crate::ID {
+            return err!(ErrorCode::InvalidOwner);
+        }
+        let data = acc.try_borrow_data()?;
+        let config = Config::try_deserialize(&mut &data[..])?; // [safe_remaining_account_deserialization]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+
+    // GOOD: `Account::try_from` checks the owner and the discriminator
+    pub fn read_config_as_account<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReadConfig<'info>>,
+    ) -> Result<()> {
+        let config = Account::<Config>::try_from(&ctx.remaining_accounts[0])?; // [safe_remaining_account_deserialization]
+        msg!("fee: {}", config.fee_bps);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReadConfig<'info> {
+    pub authority: Signer<'info>,
+}
+
+#[account]
+pub struct Config {
+    pub fee_bps: u16,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The config account is missing")]
+    MissingConfig,
+    #[msg("The config account is not owned by this program")]
+    InvalidOwner,
+}
@@ -0,0 +1,143 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    findings::{Finding, emit_finding},
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{extract_alias_evidence, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects handlers that mutate a config/governance account storing an `admin` or
+    /// `governance` pubkey without requiring that key to sign, either through
+    /// `has_one = admin` with `admin: Signer` or a runtime check such as
+    /// `require_keys_eq!(config.admin, admin.key())` against a `Signer`.
+    ///
+    /// ### Why is this bad?
+    /// The stored admin is the only account allowed to change the protocol configuration.
+    /// Without a signer-bound check against it, anyone can call the handler and rewrite fees,
+    /// authorities or other parameters of the program.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: anyone can update the config
+    /// #[derive(Accounts)]
+    /// pub struct UpdateConfig<'info> {
+    ///     #[account(mut)]
+    ///     pub config: Account<'info, Config>,
+    ///     pub admin: Signer<'info>,
+    /// }
+    ///
+    /// Good: the stored admin must sign
+    /// #[derive(Accounts)]
+    /// pub struct UpdateConfig<'info> {
+    ///     #[account(mut, has_one = admin)]
+    ///     pub config: Account<'info, Config>,
+    ///     pub admin: Signer<'info>,
+    /// }
+    /// ```
+    pub MISSING_SIGNER_CHECK_ON_GOVERNANCE_CONFIG_UPDATE,
+    Warn,
+    "governance config is updated without requiring its admin to sign"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingSignerCheckOnGovernanceConfigUpdate {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_SIGNER_CHECK_ON_GOVERNANCE_CONFIG_UPDATE) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_SIGNER_CHECK_ON_GOVERNANCE_CONFIG_UPDATE);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_governance_config_updates(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_governance_config_updates<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    let governed_accounts = extract_governed_accounts(cx, anchor_context);
+    if governed_accounts.is_empty() {
+        return;
+    }
+
+    let signer_accounts = extract_signer_accounts(cx, anchor_context);
+
+    for governed in &governed_accounts {
+        let Some(update_span) = find_account_update(mir_analyzer, &governed.account_name) else {
+            continue;
+        };
+
+        // `has_one = admin` binds the stored key to the `admin` account, which must sign
+        let has_one_signer = extract_alias_evidence(cx, governed.field)
+            .has_one
+            .iter()
+            .any(|target| target == &governed.governance_field && signer_accounts.contains(target));
+        if has_one_signer {
+            continue;
+        }
+
+        if has_runtime_governance_check(mir_analyzer, governed, &signer_accounts) {
+            continue;
+        }
+
+        let finding = Finding::new(
+            MISSING_SIGNER_CHECK_ON_GOVERNANCE_CONFIG_UPDATE,
+            update_span,
+            format!(
+                "config account `{}` is updated without requiring its `{}` to sign — add `has_one = {}` with a `Signer` `{}` account, or check `require_keys_eq!({}.{}, <signer>.key())`",
+                governed.account_name,
+                governed.governance_field,
+                governed.governance_field,
+                governed.governance_field,
+                governed.account_name,
+                governed.governance_field,
+            ),
+        )
+        .with_note(Some(governed.span), "config account is declared here");
+        emit_finding(
+            cx,
+            MISSING_SIGNER_CHECK_ON_GOVERNANCE_CONFIG_UPDATE,
+            finding,
+        );
+    }
+}
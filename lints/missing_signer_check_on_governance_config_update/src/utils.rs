@@ -0,0 +1,219 @@
+use anchor_lints_utils::{
+    diag_items::{
+        anchor_inner_account_type, is_anchor_signer_type, is_box_type, is_solana_pubkey_type,
+    },
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::{compare_adt_def_ids, has_account_constraint},
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind},
+    ty::{self as rustc_ty, FieldDef, Ty, TyKind},
+};
+use rustc_span::{Span, sym};
+
+use std::collections::HashSet;
+
+/// Names of config fields holding the key allowed to update the config
+const GOVERNANCE_FIELDS: &[&str] = &["admin", "governance"];
+
+/// A context account whose data stores the key allowed to update it
+pub struct GovernedAccount<'tcx> {
+    pub account_name: String,
+    pub span: Span,
+    pub field: &'tcx FieldDef,
+    pub inner_ty: Ty<'tcx>,
+    pub governance_field: String,
+}
+
+/// Extract the `Account<'info, T>` accounts whose `T` has an `admin`/`governance` pubkey field
+pub fn extract_governed_accounts<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) -> Vec<GovernedAccount<'tcx>> {
+    let mut governed_accounts = Vec::new();
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return governed_accounts;
+    };
+    if !adt_def.is_struct() {
+        return governed_accounts;
+    }
+
+    for field in &adt_def.non_enum_variant().fields {
+        // Initialization sets the admin, it does not update an existing config
+        if has_account_constraint(cx, field, "init")
+            || has_account_constraint(cx, field, "init_if_needed")
+        {
+            continue;
+        }
+
+        let mut account_ty = field.ty(cx.tcx, generics);
+        if is_box_type(cx.tcx, account_ty)
+            && let TyKind::Adt(_, box_generics) = account_ty.kind()
+        {
+            account_ty = box_generics.type_at(0);
+        }
+        let Some(inner_ty) = anchor_inner_account_type(cx.tcx, account_ty) else {
+            continue;
+        };
+        let TyKind::Adt(inner_adt_def, inner_generics) = inner_ty.kind() else {
+            continue;
+        };
+        if !inner_adt_def.is_struct() {
+            continue;
+        }
+
+        let governance_field = inner_adt_def.non_enum_variant().fields.iter().find(|f| {
+            GOVERNANCE_FIELDS.contains(&f.ident(cx.tcx).as_str())
+                && is_solana_pubkey_type(cx.tcx, f.ty(cx.tcx, inner_generics))
+        });
+        if let Some(governance_field) = governance_field {
+            governed_accounts.push(GovernedAccount {
+                account_name: field.ident(cx.tcx).to_string(),
+                span: cx.tcx.def_span(field.did),
+                field,
+                inner_ty,
+                governance_field: governance_field.ident(cx.tcx).to_string(),
+            });
+        }
+    }
+
+    governed_accounts
+}
+
+/// Extract the accounts that are `Signer<'info>` or carry `#[account(signer)]`
+pub fn extract_signer_accounts<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) -> HashSet<String> {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return HashSet::new();
+    };
+    if !adt_def.is_struct() {
+        return HashSet::new();
+    }
+
+    adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .filter(|field| {
+            is_anchor_signer_type(cx.tcx, field.ty(cx.tcx, generics))
+                || has_account_constraint(cx, field, "signer")
+        })
+        .map(|field| field.ident(cx.tcx).to_string())
+        .collect()
+}
+
+/// Find the first mutable dereference of the account, i.e. a write to its data
+pub fn find_account_update(mir_analyzer: &MirAnalyzer, account_name: &str) -> Option<Span> {
+    let tcx = mir_analyzer.cx.tcx;
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && tcx.is_diagnostic_item(sym::deref_mut_method, *fn_def_id)
+            && let Some(receiver_local) = mir_analyzer.get_local_from_operand(args.first())
+            && let Some(account) =
+                mir_analyzer.extract_account_name_from_local(&receiver_local, true)
+            && account.account_name == account_name
+        {
+            return Some(*fn_span);
+        }
+    }
+    None
+}
+
+/// Check for a `config.admin == signer.key()` comparison, e.g. through `require_keys_eq!`
+pub fn has_runtime_governance_check<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    governed: &GovernedAccount<'tcx>,
+    signer_accounts: &HashSet<String>,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && (tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
+                || tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id))
+            && let Some((lhs, rhs)) = mir_analyzer.args_as_pubkey_locals(args)
+        {
+            let lhs_is_governance = reads_governance_field(mir_analyzer, lhs, governed);
+            let rhs_is_governance = reads_governance_field(mir_analyzer, rhs, governed);
+            if (lhs_is_governance && is_signer_key(mir_analyzer, rhs, signer_accounts))
+                || (rhs_is_governance && is_signer_key(mir_analyzer, lhs, signer_accounts))
+            {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Check if the value is read from the governance field of the config account data
+fn reads_governance_field<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    local: Local,
+    governed: &GovernedAccount<'tcx>,
+) -> bool {
+    let source_locals = mir_analyzer.collect_source_locals(local);
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        bbdata.statements.iter().any(|stmt| {
+            let StatementKind::Assign(box (dest_place, rvalue)) = &stmt.kind else {
+                return false;
+            };
+            let (Rvalue::Use(Operand::Copy(src) | Operand::Move(src))
+            | Rvalue::Ref(_, _, src)
+            | Rvalue::CopyForDeref(src)) = rvalue
+            else {
+                return false;
+            };
+            dest_place
+                .as_local()
+                .is_some_and(|dest_local| source_locals.contains(&dest_local))
+                && is_governance_field_place(mir_analyzer, src, governed)
+        })
+    })
+}
+
+/// Check if the place projects the governance field out of the config account data
+fn is_governance_field_place<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    place: &Place<'tcx>,
+    governed: &GovernedAccount<'tcx>,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    place.iter_projections().any(|(base, elem)| {
+        let ProjectionElem::Field(field_idx, _) = elem else {
+            return false;
+        };
+        let base_ty = base.ty(mir_analyzer.mir, tcx).ty.peel_refs();
+        if !compare_adt_def_ids(base_ty, governed.inner_ty) {
+            return false;
+        }
+        matches!(base_ty.kind(), TyKind::Adt(adt_def, _)
+            if adt_def.non_enum_variant().fields[field_idx].name.as_str() == governed.governance_field)
+    })
+}
+
+/// Check if the pubkey is the key of a signer account
+fn is_signer_key(
+    mir_analyzer: &MirAnalyzer,
+    local: Local,
+    signer_accounts: &HashSet<String>,
+) -> bool {
+    mir_analyzer
+        .collect_source_locals(local)
+        .iter()
+        .filter_map(|source_local| mir_analyzer.extract_account_name_from_local(source_local, true))
+        .any(|account| signer_accounts.contains(&account.account_name))
+}
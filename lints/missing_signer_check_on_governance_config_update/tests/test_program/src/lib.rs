@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_signer_check_on_governance_config_update_tests {
+    use super::*;
+
+    // GOOD: initialization sets the admin, it is not an update
+    pub fn initialize_config(ctx: Context<InitializeConfig>, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key(); // [safe_governance_update]
+        config.fee_bps = fee_bps;
+        Ok(())
+    }
+
+    // GOOD: `has_one = admin` with `admin: Signer`
+    pub fn update_fee_with_has_one(ctx: Context<UpdateConfigHasOne>, fee_bps: u16) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps; // [safe_governance_update]
+        Ok(())
+    }
+
+    // GOOD: runtime check of the stored admin against a signer
+    pub fn update_fee_with_runtime_check(
+        ctx: Context<UpdateConfigRuntimeCheck>,
+        fee_bps: u16,
+    ) -> Result<()> {
+        require_keys_eq!(ctx.accounts.config.admin, ctx.accounts.admin.key());
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps; // [safe_governance_update]
+        Ok(())
+    }
+
+    // BAD: `has_one = admin` but the admin is never required to sign
+    pub fn update_fee_with_unsigned_has_one(
+        ctx: Context<UpdateConfigUnsignedHasOne>,
+        fee_bps: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps; // [missing_signer_check_on_governance_config_update]
+        Ok(())
+    }
+
+    // BAD: a signer is present but never checked against the stored admin
+    pub fn update_fee_without_check(
+        ctx: Context<UpdateConfigRuntimeCheck>,
+        fee_bps: u16,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.fee_bps = fee_bps; // [missing_signer_check_on_governance_config_update]
+        Ok(())
+    }
+
+    // BAD: governance key rotated without the current governance signing
+    pub fn rotate_governance(ctx: Context<RotateGovernance>, new_governance: Pubkey) -> Result<()> {
+        let dao = &mut ctx.accounts.dao;
+        dao.governance = new_governance; // [missing_signer_check_on_governance_config_update]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = admin, space = 8 + Config::INIT_SPACE)]
+    pub config: Account<'info, Config>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfigHasOne<'info> {
+    #[account(mut, has_one = admin)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfigRuntimeCheck<'info> {
+    #[account(mut)]
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfigUnsignedHasOne<'info> {
+    #[account(mut, has_one = admin)]
+    pub config: Account<'info, Config>,
+    /// CHECK: matched against `config.admin` but not required to sign
+    pub admin: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RotateGovernance<'info> {
+    #[account(mut)]
+    pub dao: Account<'info, Dao>,
+    pub payer: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct Dao {
+    pub governance: Pubkey,
+    pub proposal_count: u64,
+}
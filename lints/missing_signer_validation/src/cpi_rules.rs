@@ -5,6 +5,8 @@ pub struct CpiMeta {
     pub cpi_kind: CpiKind,
     pub signer_source: SignerSource,
     pub signer_field_name: &'static str,
+    // Index of the `signer_pubkeys` of a multisig authority, for the instruction builders taking one
+    pub multisig_signers_index: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -18,81 +20,97 @@ static CPI_RULES: &[CpiMeta] = &[
         cpi_kind: CpiKind::SystemTransfer,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "from",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Transfer,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::MintTo,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Burn,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Token2022Transfer,
         signer_source: SignerSource::ArgIndex(3),
         signer_field_name: "authority",
+        multisig_signers_index: Some(4),
     },
     CpiMeta {
         cpi_kind: CpiKind::Token2022TransferChecked,
         signer_source: SignerSource::ArgIndex(4),
         signer_field_name: "authority",
+        multisig_signers_index: Some(5),
     },
     CpiMeta {
         cpi_kind: CpiKind::CreateAta,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::SetAuthority,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "current_authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::CloseAccount,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::FreezeAccount,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::ThawAccount,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Approve,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Revoke,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "authority",
+        multisig_signers_index: None,
     },
     CpiMeta {
         cpi_kind: CpiKind::Token2022MintToChecked,
         signer_source: SignerSource::ArgIndex(3),
         signer_field_name: "mint_authority",
+        multisig_signers_index: Some(4),
     },
     CpiMeta {
         cpi_kind: CpiKind::Token2022BurnChecked,
         signer_source: SignerSource::ArgIndex(3),
         signer_field_name: "authority",
+        multisig_signers_index: Some(4),
     },
     CpiMeta {
         cpi_kind: CpiKind::SyncNative,
         signer_source: SignerSource::ContextSigner,
         signer_field_name: "account",
+        multisig_signers_index: None,
     },
 ];
 
@@ -2,17 +2,16 @@
 #![warn(unused_extern_crates)]
 #![feature(box_patterns)]
 
-extern crate rustc_ast;
 extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::{
-    cpi_types::detect_cpi_kind, mir_analyzer::MirAnalyzer, utils::extract_arg_local,
+    cpi_types::detect_cpi_kind, diag_items::is_account_meta_constructor_fn, diagnostics::span_lint,
+    mir_analyzer::MirAnalyzer, registry::is_lint_enabled, utils::extract_arg_local,
 };
 
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint;
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::{
@@ -33,7 +32,23 @@ dylint_linting::declare_late_lint! {
     /// Warns when a CPI requires a signer (e.g., authority/owner/current_authority)
     /// but the passed account is **not**:
     /// - declared as a signer (`Signer<'info>` or `#[account(signer)]`), and
-    /// - **not** signed via PDA seeds (`CpiContext::new_with_signer`).
+    /// - **not** signed via PDA seeds (`CpiContext::new_with_signer` or `with_signer`).
+    ///
+    /// An `#[account(address = KNOWN_SIGNER)]` constraint only pins which account is passed,
+    /// it is not a substitute for signer validation.
+    ///
+    /// A runtime check of the `is_signer` flag, e.g.
+    /// `require!(ctx.accounts.authority.is_signer, ..)` or
+    /// `if !ctx.accounts.authority.to_account_info().is_signer { return Err(..) }`, validates the
+    /// account for the CPIs it dominates.
+    ///
+    /// The authority of an SPL Token instruction builder given a non-empty `signer_pubkeys` slice,
+    /// e.g. `&[ctx.accounts.signer_1.key, ctx.accounts.signer_2.key]`, is a multisig signed by
+    /// those members instead.
+    ///
+    /// A hand-built `Instruction` is checked too: the account of every
+    /// `AccountMeta::new(pubkey, true)` or `AccountMeta::new_readonly(pubkey, true)` must be
+    /// validated as a signer, unless the function signs with PDA seeds through `invoke_signed`.
     ///
     /// ### Why it's important
     /// Missing signer validation allows attackers to perform unauthorized
@@ -80,6 +95,11 @@ impl<'tcx> LateLintPass<'tcx> for MissingSignerValidation {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_SIGNER_VALIDATION) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -100,8 +120,10 @@ fn analyze_missing_signer_validation<'tcx>(
 
     // Update anchor context info with accounts
     anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+    mir_analyzer.debug_dump(MISSING_SIGNER_VALIDATION);
 
-    // Analyze functions that take Anchor context
+    // Analyze functions that take an Anchor context, or an accounts struct such as the `&mut self`
+    // receiver of a method implemented on it
     let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
         return;
     };
@@ -109,6 +131,15 @@ fn analyze_missing_signer_validation<'tcx>(
     // Extract accounts with signer attribute/type
     let accounts_with_signer = extract_accounts_with_signer_attribute(cx, anchor_context);
 
+    // A pinned address only fixes which account is passed, it does not prove the account signed
+    let accounts_with_address = extract_accounts_with_address_constraint(cx, anchor_context);
+
+    // Runtime signer checks, e.g. `require!(ctx.accounts.authority.is_signer, ..)`
+    let signer_checks = collect_runtime_signer_checks(&mir_analyzer);
+
+    // PDAs signing an `invoke_signed` may be marked as signers of a hand-built instruction
+    let signs_with_seeds = calls_invoke_signed(&mir_analyzer);
+
     // Track accounts used as signers in CPIs
     let mut accounts_used_as_signer: HashMap<String, Span> = HashMap::new();
 
@@ -121,42 +152,62 @@ fn analyze_missing_signer_validation<'tcx>(
         } = &bbdata.terminator().kind
             && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
         {
-            let Some(cpi_kind) = detect_cpi_kind(cx, *fn_def_id) else {
-                continue;
-            };
-
-            let Some(cpi_meta) = get_cpi_rule(cpi_kind) else {
-                continue;
-            };
-            match cpi_meta.signer_source {
-                SignerSource::ContextSigner => {
-                    // signer comes from CpiContext::new(...)
-                    if let Some(cpi_ctx_local) = extract_arg_local(args, 0) {
-                        accounts_used_as_signer.extend(extract_cpi_accounts_from_context(
-                            &mir_analyzer,
-                            bb,
-                            cpi_ctx_local,
-                            cpi_meta,
-                        ));
-                    }
+            let mut cpi_signers = HashMap::new();
+            if is_account_meta_constructor_fn(cx.tcx, *fn_def_id) {
+                // A hand-built `Instruction` marks its signers with `AccountMeta::new(pubkey, true)`,
+                // unless it is invoked with `invoke_signed` for the PDAs of the program
+                if is_signer_account_meta(args) && !signs_with_seeds {
+                    insert_authority_from_arg(&mir_analyzer, args, 0, &mut cpi_signers);
                 }
+            } else if let Some(cpi_meta) = detect_cpi_kind(cx, *fn_def_id).and_then(get_cpi_rule) {
+                match cpi_meta.signer_source {
+                    SignerSource::ContextSigner => {
+                        // signer comes from CpiContext::new(...)
+                        if let Some(cpi_ctx_local) = extract_arg_local(args, 0) {
+                            cpi_signers.extend(extract_cpi_accounts_from_context(
+                                &mir_analyzer,
+                                bb,
+                                cpi_ctx_local,
+                                cpi_meta,
+                            ));
+                        }
+                    }
 
-                SignerSource::ArgIndex(idx) => {
-                    insert_authority_from_arg(
-                        &mir_analyzer,
-                        args,
-                        idx,
-                        &mut accounts_used_as_signer,
-                    );
+                    SignerSource::ArgIndex(idx) => {
+                        // The members of a multisig authority sign instead of the authority itself
+                        if !cpi_meta.multisig_signers_index.is_some_and(|signers_idx| {
+                            has_multisig_signers(&mir_analyzer, args, signers_idx)
+                        }) {
+                            insert_authority_from_arg(&mir_analyzer, args, idx, &mut cpi_signers);
+                        }
+                    }
                 }
             }
+
+            // Signers whose `is_signer` flag is required before the CPI are validated at runtime
+            accounts_used_as_signer.extend(cpi_signers.into_iter().filter(|(account_name, _)| {
+                !is_signer_checked_before(&mir_analyzer, &signer_checks, account_name, bb)
+            }));
         }
     }
 
     // Check each account used as signer for validation
     for (account_name, cpi_span) in accounts_used_as_signer {
         let has_signer_attr = accounts_with_signer.contains(&account_name);
-        if !has_signer_attr {
+        if has_signer_attr {
+            continue;
+        }
+        if accounts_with_address.contains(&account_name) {
+            span_lint(
+                cx,
+                MISSING_SIGNER_VALIDATION,
+                cpi_span,
+                format!(
+                    "account `{}` is used as a signer but lacks signer validation — its `address` constraint pins the pubkey but does not require a signature, add `#[account(signer)]`",
+                    account_name
+                ),
+            );
+        } else {
             span_lint(
                 cx,
                 MISSING_SIGNER_VALIDATION,
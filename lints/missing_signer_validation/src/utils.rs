@@ -1,15 +1,26 @@
 use anchor_lints_utils::{
-    diag_items::{is_anchor_cpi_context, is_anchor_signer_type},
+    cfg::dominates,
+    diag_items::{
+        DiagnoticItem, is_account_info_type, is_anchor_cpi_context, is_anchor_signer_type,
+    },
     mir_analyzer::MirAnalyzer,
-    utils::{check_cpi_call_is_new_with_signer, check_locals_are_related, extract_arg_local},
+    models::AssignmentKind,
+    utils::{
+        account_constraints::has_account_constraint, check_cpi_call_is_new_with_signer,
+        check_locals_are_related, compare_adt_def_ids, extract_arg_local, negated_condition,
+    },
 };
 
+use rustc_hir::def_id::DefId;
 use rustc_lint::LateContext;
 use rustc_middle::{
-    mir::{AggregateKind, BasicBlock, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    mir::{
+        AggregateKind, BasicBlock, Local, Operand, PlaceRef, ProjectionElem, Rvalue, StatementKind,
+        TerminatorKind,
+    },
     ty::{self as rustc_ty, TyKind},
 };
-use rustc_span::{Span, Symbol, source_map::Spanned};
+use rustc_span::{Span, source_map::Spanned};
 use std::collections::{HashMap, HashSet};
 
 use crate::cpi_rules::CpiMeta;
@@ -31,15 +42,55 @@ pub fn insert_authority_from_arg(
     }
 }
 
-// Extract the signer accounts from the CPI context
-pub fn extract_cpi_accounts_from_context(
+// Check if the `signer_pubkeys` argument of an SPL Token instruction builder is a non-empty slice
+// literal, e.g. `&[ctx.accounts.signer_1.key, ctx.accounts.signer_2.key]`, so the authority is a
+// multisig whose members sign instead of it
+pub fn has_multisig_signers(
     mir_analyzer: &MirAnalyzer,
+    args: &[Spanned<Operand>],
+    idx: usize,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    // `&[]` is passed as a constant
+    let Some(signers_local) = extract_arg_local(args, idx) else {
+        return false;
+    };
+    mir_analyzer
+        .mir
+        .basic_blocks
+        .iter()
+        .flat_map(|bbdata| &bbdata.statements)
+        .any(|stmt| {
+            // The array the slice is unsized from, e.g. `_3: &[&Pubkey; 2]`
+            if let StatementKind::Assign(box (place, Rvalue::Cast(_, operand, _))) = &stmt.kind
+                && place.as_local() == Some(signers_local)
+                && let TyKind::Array(_, len) = operand.ty(mir_analyzer.mir, tcx).peel_refs().kind()
+            {
+                len.try_to_target_usize(tcx).is_some_and(|len| len > 0)
+            } else {
+                false
+            }
+        })
+}
+
+// A call returning a `CpiContext`: a constructor such as `CpiContext::new`, or a builder method
+// such as `with_signer` or `with_remaining_accounts` consuming the context it is called on
+pub struct CpiContextCall<'a, 'tcx> {
+    pub block: BasicBlock,
+    pub fn_def_id: DefId,
+    pub args: &'a [Spanned<Operand<'tcx>>],
+    pub destination: Local,
+    // The context a builder method is called on, `None` for constructors
+    pub receiver: Option<Local>,
+}
+
+// Collect the calls returning a `CpiContext` until the CPI block
+pub fn collect_cpi_context_calls<'a, 'tcx>(
+    mir_analyzer: &MirAnalyzer<'a, 'tcx>,
     cpi_block: BasicBlock,
-    cpi_ctx_local: Local,
-    cpi_meta: &CpiMeta,
-) -> HashMap<String, Span> {
-    let mut signer_account_names = HashMap::new();
-    // Iterate over the basic blocks of the MIR to find the CPI accounts until we reach the CPI block
+) -> Vec<CpiContextCall<'a, 'tcx>> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mut calls = Vec::new();
     for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
         if let TerminatorKind::Call {
             func: Operand::Constant(func_const),
@@ -48,46 +99,146 @@ pub fn extract_cpi_accounts_from_context(
             ..
         } = &bbdata.terminator().kind
             && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+            && let Some(destination) = destination.as_local()
         {
-            // Get the return type of the function
-            let fn_sig = mir_analyzer.cx.tcx.fn_sig(*fn_def_id).skip_binder();
-            let return_ty = fn_sig.skip_binder().output();
-
-            // Check if the function return type is a CPI context
-            if !is_anchor_cpi_context(mir_analyzer.cx.tcx, return_ty) {
-                continue;
+            let return_ty = tcx.fn_sig(*fn_def_id).skip_binder().skip_binder().output();
+            if is_anchor_cpi_context(tcx, return_ty) {
+                let receiver = args
+                    .first()
+                    .filter(|arg| is_anchor_cpi_context(tcx, arg.node.ty(mir_analyzer.mir, tcx)))
+                    .and_then(|_| extract_arg_local(args, 0));
+                calls.push(CpiContextCall {
+                    block: bb,
+                    fn_def_id: *fn_def_id,
+                    args,
+                    destination,
+                    receiver,
+                });
             }
+        }
 
-            // Skip if the CPI call is new_with_signer - PDA signer
-            if check_cpi_call_is_new_with_signer(mir_analyzer, args, *fn_def_id) {
-                continue;
-            }
+        // The context must be built before the CPI consumes it
+        if cpi_block == bb {
+            break;
+        }
+    }
+    calls
+}
 
-            // Extract the accounts passed to the CPI call
-            if let Some(cpi_accounts_local) = extract_arg_local(args, 1)
-                && let Some(destination_local) = destination.as_local()
+// Collect the locals of the context passed to a CPI and of the contexts it is built from, e.g.
+// `CpiContext::new(..)` for `CpiContext::new(..).with_signer(seeds)`
+fn cpi_context_locals(
+    mir_analyzer: &MirAnalyzer,
+    cpi_context_calls: &[CpiContextCall],
+    cpi_ctx_local: Local,
+) -> Vec<Local> {
+    let mut locals = vec![cpi_ctx_local];
+    let mut index = 0;
+    while let Some(&local) = locals.get(index) {
+        for call in cpi_context_calls {
+            if let Some(receiver) = call.receiver
+                && !locals.contains(&receiver)
                 && check_locals_are_related(
                     &mir_analyzer.reverse_assignment_map,
-                    &destination_local,
-                    &cpi_ctx_local,
+                    &call.destination,
+                    &local,
                 )
             {
-                signer_account_names.extend(extract_cpi_accounts_from_transfer(
-                    mir_analyzer,
-                    bb,
-                    cpi_accounts_local,
-                    cpi_meta,
-                ));
-                break;
+                locals.push(receiver);
             }
         }
+        index += 1;
+    }
+    locals
+}
 
-        // Stop iterating over the basic blocks if we've reached the CPI block
-        if cpi_block == bb {
-            break;
+// Check if the context passed to a CPI is signed with PDA seeds, by `CpiContext::new_with_signer`
+// or `with_signer`, so its authority is a PDA of the program rather than a user who must sign
+pub fn is_signed_cpi_context<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    cpi_context_calls: &[CpiContextCall<'_, 'tcx>],
+    cpi_ctx_local: Local,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    let locals = cpi_context_locals(mir_analyzer, cpi_context_calls, cpi_ctx_local);
+    cpi_context_calls.iter().any(|call| {
+        (check_cpi_call_is_new_with_signer(mir_analyzer, call.args, call.fn_def_id)
+            || tcx
+                .opt_item_name(call.fn_def_id)
+                .is_some_and(|name| name.as_str() == "with_signer"))
+            && locals.iter().any(|local| {
+                check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &call.destination,
+                    local,
+                )
+            })
+    })
+}
+
+// Check if an `AccountMeta::new` or `AccountMeta::new_readonly` call marks its account as a signer,
+// i.e. its `is_signer` argument is `true`
+pub fn is_signer_account_meta(args: &[Spanned<Operand>]) -> bool {
+    matches!(args.get(1).map(|arg| &arg.node), Some(Operand::Constant(is_signer))
+        if is_signer.const_.try_to_bool() == Some(true))
+}
+
+// Check if the function invokes an instruction with `invoke_signed`, signing for its PDAs
+pub fn calls_invoke_signed(mir_analyzer: &MirAnalyzer) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            DiagnoticItem::AnchorCpiInvokeSigned.defid_is_item(tcx, *fn_def_id)
+                || DiagnoticItem::AnchorCpiInvokeSignedUnchecked.defid_is_item(tcx, *fn_def_id)
+        } else {
+            false
         }
+    })
+}
+
+// Extract the signer accounts from the CPI context
+pub fn extract_cpi_accounts_from_context(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    cpi_ctx_local: Local,
+    cpi_meta: &CpiMeta,
+) -> HashMap<String, Span> {
+    let cpi_context_calls = collect_cpi_context_calls(mir_analyzer, cpi_block);
+
+    // Skip contexts signed with PDA seeds, whatever the CPI - PDA signer
+    if is_signed_cpi_context(mir_analyzer, &cpi_context_calls, cpi_ctx_local) {
+        return HashMap::new();
     }
-    signer_account_names
+
+    // Extract the accounts passed to the constructor of the context, builder methods only
+    // change its signer seeds or remaining accounts
+    let locals = cpi_context_locals(mir_analyzer, &cpi_context_calls, cpi_ctx_local);
+    let Some(constructor) = cpi_context_calls.iter().find(|call| {
+        call.receiver.is_none()
+            && locals.iter().any(|local| {
+                check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &call.destination,
+                    local,
+                )
+            })
+    }) else {
+        return HashMap::new();
+    };
+    let Some(cpi_accounts_local) = extract_arg_local(constructor.args, 1) else {
+        return HashMap::new();
+    };
+    extract_cpi_accounts_from_transfer(
+        mir_analyzer,
+        constructor.block,
+        cpi_accounts_local,
+        cpi_meta,
+    )
 }
 
 // Extract the signer accounts from the CPI transfer
@@ -186,22 +337,8 @@ pub fn extract_accounts_with_signer_attribute<'tcx>(
             }
 
             // 2. Check for #[account(signer)] attribute
-            let attrs = cx.tcx.get_all_attrs(account_field.did);
-            for attr in attrs {
-                if let rustc_hir::Attribute::Unparsed(_) = attr {
-                    let attr_item = attr.get_normal_item();
-                    if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                        for token in delim_args.tokens.iter() {
-                            if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token
-                                && let rustc_ast::token::TokenKind::Ident(ident, ..) = token.kind
-                                && ident == Symbol::intern("signer")
-                            {
-                                accounts_with_signer.insert(account_name.clone());
-                                break;
-                            }
-                        }
-                    }
-                }
+            if has_account_constraint(cx, account_field, "signer") {
+                accounts_with_signer.insert(account_name);
             }
         }
     }
@@ -209,4 +346,143 @@ pub fn extract_accounts_with_signer_attribute<'tcx>(
     accounts_with_signer
 }
 
+// Extract the accounts pinned to a fixed pubkey with `#[account(address = ...)]`
+pub fn extract_accounts_with_address_constraint<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context_info: &anchor_lints_utils::mir_analyzer::AnchorContextInfo<'tcx>,
+) -> HashSet<String> {
+    let TyKind::Adt(accounts_adt_def, _) = anchor_context_info.anchor_context_account_type.kind()
+    else {
+        return HashSet::new();
+    };
+    if !accounts_adt_def.is_struct() && !accounts_adt_def.is_union() {
+        return HashSet::new();
+    }
+
+    accounts_adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .filter(|account_field| has_account_constraint(cx, account_field, "address"))
+        .map(|account_field| account_field.ident(cx.tcx).to_string())
+        .collect()
+}
+
+// Collect the runtime signer checks: the account whose `is_signer` flag decides a branch, and the
+// block only entered when the flag is set, e.g. the code after `require!(authority.is_signer, ..)`
+pub fn collect_runtime_signer_checks(mir_analyzer: &MirAnalyzer) -> Vec<(String, BasicBlock)> {
+    let mir = mir_analyzer.mir;
+    let predecessors = mir.basic_blocks.predecessors();
+    let mut checks = Vec::new();
+    for bbdata in mir.basic_blocks.iter() {
+        let TerminatorKind::SwitchInt { discr, targets } = &bbdata.terminator().kind else {
+            continue;
+        };
+        let Some(mut condition) = discr.place().and_then(|place| place.as_local()) else {
+            continue;
+        };
+        let mut signed_block = targets.otherwise();
+        // `if !authority.is_signer { .. }` usually swaps the targets, but may keep the negation
+        if let Some(negated) = negated_condition(&bbdata.statements, condition) {
+            condition = negated;
+            signed_block = targets.target_for_value(0);
+        }
+        // A block also entered from elsewhere, e.g. the other operand of `||`, is not guarded
+        if predecessors[signed_block].len() != 1 {
+            continue;
+        }
+        if let Some(account_name) = signer_flag_account(mir_analyzer, condition) {
+            checks.push((account_name, signed_block));
+        }
+    }
+    checks
+}
+
+// Check if a runtime `is_signer` check of `account_name` dominates the CPI in `cpi_block`
+pub fn is_signer_checked_before(
+    mir_analyzer: &MirAnalyzer,
+    signer_checks: &[(String, BasicBlock)],
+    account_name: &str,
+    cpi_block: BasicBlock,
+) -> bool {
+    signer_checks.iter().any(|(checked_account, signed_block)| {
+        checked_account == account_name
+            && dominates(&mir_analyzer.dominators, *signed_block, cpi_block)
+    })
+}
+
+// If `condition` is read from the `is_signer` flag of an `AccountInfo`, e.g.
+// `ctx.accounts.authority.is_signer` or `ctx.accounts.authority.to_account_info().is_signer`,
+// return the name of the account
+fn signer_flag_account(mir_analyzer: &MirAnalyzer, condition: Local) -> Option<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    let Some(AssignmentKind::FromPlace(place)) = mir_analyzer.assignment_map.get(&condition) else {
+        return None;
+    };
+    let (account_info, ProjectionElem::Field(field, _)) = place.as_ref().last_projection()? else {
+        return None;
+    };
+    let account_info_ty = account_info.ty(mir_analyzer.mir, tcx).ty;
+    let TyKind::Adt(adt_def, _) = account_info_ty.kind() else {
+        return None;
+    };
+    if !is_account_info_type(tcx, account_info_ty)
+        || adt_def.non_enum_variant().fields[field].name.as_str() != "is_signer"
+    {
+        return None;
+    }
+    projected_account_name(mir_analyzer, account_info, &mut HashSet::new())
+}
+
+// Name of the accounts struct field `place` is projected from, following the references, copies
+// and method calls, e.g. `to_account_info()` or `Deref`, it is derived through
+fn projected_account_name<'tcx>(
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    place: PlaceRef<'tcx>,
+    visited: &mut HashSet<Local>,
+) -> Option<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    let accounts_ty = mir_analyzer
+        .anchor_context_info
+        .as_ref()?
+        .anchor_context_account_type;
+    for (base, elem) in place.iter_projections() {
+        if let ProjectionElem::Field(field, _) = elem
+            && let base_ty = base.ty(mir_analyzer.mir, tcx).ty
+            && compare_adt_def_ids(base_ty, accounts_ty)
+            && let TyKind::Adt(adt_def, _) = base_ty.kind()
+        {
+            return Some(
+                adt_def.non_enum_variant().fields[field]
+                    .ident(tcx)
+                    .to_string(),
+            );
+        }
+    }
+    if place
+        .projection
+        .iter()
+        .any(|elem| !matches!(elem, ProjectionElem::Deref))
+        || !visited.insert(place.local)
+    {
+        return None;
+    }
+    match mir_analyzer.assignment_map.get(&place.local) {
+        Some(AssignmentKind::FromPlace(source) | AssignmentKind::RefTo(source)) => {
+            projected_account_name(mir_analyzer, source.as_ref(), visited)
+        }
+        _ => {
+            let receiver = *mir_analyzer.method_call_receiver_map.get(&place.local)?;
+            projected_account_name(
+                mir_analyzer,
+                PlaceRef {
+                    local: receiver,
+                    projection: &[],
+                },
+                visited,
+            )
+        }
+    }
+}
+
 // Check if the type is a Signer<'info>
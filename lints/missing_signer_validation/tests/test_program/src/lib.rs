@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
 use anchor_lang::solana_program::program::invoke;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::system_program::{self, Transfer};
@@ -12,6 +12,8 @@ use anchor_spl::token_2022::{Token2022, spl_token_2022};
 
 declare_id!("M1ss1ngS1gn3r111111111111111111111111111111");
 
+pub const ADMIN_KEY: Pubkey = Pubkey::new_from_array([7u8; 32]);
+
 #[program]
 pub mod missing_signer_validation_tests {
     use super::*;
@@ -118,6 +120,68 @@ pub mod missing_signer_validation_tests {
 
         Ok(())
     }
+
+    // Case 40: spl_token_2022::instruction::transfer_checked signed by the members of a multisig
+    pub fn transfer_checked_cpi_with_multisig_signers(
+        ctx: Context<MultisigTransferChecked>,
+    ) -> Result<()> {
+        let ix = spl_token_2022::instruction::transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.from.key,
+            ctx.accounts.mint.key,
+            ctx.accounts.to.key,
+            ctx.accounts.authority.key, // [safe_signer_validation]
+            &[ctx.accounts.signer_1.key, ctx.accounts.signer_2.key],
+            100,
+            6,
+        )?;
+
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.from.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.to.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.signer_1.to_account_info(),
+                ctx.accounts.signer_2.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    // Case 41: spl_token_2022::instruction::transfer_checked with no multisig member signing
+    pub fn transfer_checked_cpi_with_empty_multisig_signers(
+        ctx: Context<MultisigTransferChecked>,
+    ) -> Result<()> {
+        let ix = spl_token_2022::instruction::transfer_checked(
+            ctx.accounts.token_program.key,
+            ctx.accounts.from.key,
+            ctx.accounts.mint.key,
+            ctx.accounts.to.key,
+            ctx.accounts.authority.key, // [missing_signer_validation]
+            &[],
+            100,
+            6,
+        )?;
+
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.from.to_account_info(),
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.to.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.signer_1.to_account_info(),
+                ctx.accounts.signer_2.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
     // Case 6: spl_token_2022::instruction::transfer_checked
     pub fn transfer_checked_cpi_with_signer(ctx: Context<TestAccounts>) -> Result<()> {
         let ix = spl_token_2022::instruction::transfer(
@@ -599,6 +663,262 @@ pub mod missing_signer_validation_tests {
 
         Ok(())
     }
+
+    // Case 32: token::transfer with authority pinned by `address` but not a signer
+    pub fn spl_transfer_address_pinned_authority(
+        ctx: Context<SplTransferAddressPinnedAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = SplTransfer { // [missing_signer_validation]
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // Case 33: fixed by `baseline-diff-modified`, which the baseline diff test compares against
+    pub fn baseline_diff_fixed(ctx: Context<BaselineDiffTransfer>, amount: u64) -> Result<()> {
+        #[cfg(not(feature = "baseline-diff-modified"))]
+        {
+            let cpi_accounts = Transfer { // [missing_signer_validation]
+                from: ctx.accounts.old_payer.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+        Ok(())
+    }
+
+    // Case 34: introduced by `baseline-diff-modified`, absent from the default program
+    pub fn baseline_diff_introduced(ctx: Context<BaselineDiffTransfer>, amount: u64) -> Result<()> {
+        #[cfg(feature = "baseline-diff-modified")]
+        {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.new_payer.to_account_info(),
+                to: ctx.accounts.to.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+        Ok(())
+    }
+
+    // Case 35: suppressed with a reason, recorded as suppressed in the summary report
+    pub fn transfer_suppressed(ctx: Context<TransferMissingSigner>, amount: u64) -> Result<()> {
+        // anchor-lints: validated-elsewhere `from` is checked to be a signer by the router program
+        let cpi_accounts = Transfer { // [safe_signer_validation]
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // Case 36: allowed with a reason, recorded as suppressed in the summary report
+    #[allow(
+        missing_signer_validation,
+        reason = "`allowed_payer` signs through the multisig program"
+    )]
+    pub fn transfer_allowed(ctx: Context<BaselineDiffTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer { // [safe_signer_validation]
+            from: ctx.accounts.allowed_payer.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // Case 37: AccountInfo authority required to be a signer at runtime => no error
+    pub fn burn_with_runtime_signer_check(
+        ctx: Context<BurnMissingSigner>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(ctx.accounts.authority.is_signer, CustomError::NotSigner);
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.key(),
+                Burn { // [safe_signer_validation]
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.from.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // Case 38: negated runtime signer check through `to_account_info()` => no error
+    pub fn burn_with_negated_runtime_signer_check(
+        ctx: Context<BurnMissingSigner>,
+        amount: u64,
+    ) -> Result<()> {
+        if !ctx.accounts.authority.to_account_info().is_signer {
+            return err!(CustomError::NotSigner);
+        }
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.key(),
+                Burn { // [safe_signer_validation]
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.from.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        Ok(())
+    }
+
+    // Case 39: runtime signer check only after the CPI => should trigger missing_signer_validation
+    pub fn burn_with_late_runtime_signer_check(
+        ctx: Context<BurnMissingSigner>,
+        amount: u64,
+    ) -> Result<()> {
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.key(),
+                Burn { // [missing_signer_validation]
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.from.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        require!(ctx.accounts.authority.is_signer, CustomError::NotSigner);
+        Ok(())
+    }
+
+    // Case 42: Burn with the PDA authority signing through new_with_signer => no error
+    pub fn burn_with_pda_signer(ctx: Context<BurnMissingSigner>, amount: u64) -> Result<()> {
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[b"burn_authority", binding.as_ref()];
+        let signer = &[&seeds[..]];
+
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.key(),
+                Burn { // [safe_signer_validation]
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.from.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    // Case 43: Close account with the PDA authority signing through new_with_signer => no error
+    pub fn close_account_with_pda_signer(ctx: Context<CloseAccountMissingSigner>) -> Result<()> {
+        let binding = ctx.accounts.account.key();
+        let seeds = &[b"close_authority", binding.as_ref()];
+        let signer = &[&seeds[..]];
+
+        token::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.key(),
+            CloseAccount { // [safe_signer_validation]
+                account: ctx.accounts.account.to_account_info(),
+                destination: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+            signer,
+        ))?;
+
+        Ok(())
+    }
+
+    // Case 44: Burn with the PDA authority signing through with_signer => no error
+    pub fn burn_with_pda_signer_builder(ctx: Context<BurnMissingSigner>, amount: u64) -> Result<()> {
+        let binding = ctx.accounts.mint.key();
+        let seeds = &[b"burn_authority", binding.as_ref()];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Burn { // [safe_signer_validation]
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.from.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts).with_signer(signer);
+        token::burn(cpi_ctx, amount)?;
+
+        Ok(())
+    }
+
+    // Case 45: Burn through a builder method, still without signer seeds => missing_signer_validation
+    pub fn burn_with_remaining_accounts_missing_signer(
+        ctx: Context<BurnMissingSigner>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Burn { // [missing_signer_validation]
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.from.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts)
+            .with_remaining_accounts(vec![ctx.accounts.mint.to_account_info()]);
+        token::burn(cpi_ctx, amount)?;
+
+        Ok(())
+    }
+
+    // Case 46: Hand-built transfer marking a plain AccountInfo as signer => missing_signer_validation
+    pub fn hand_built_transfer_missing_signer(ctx: Context<TestAccounts>) -> Result<()> {
+        let ix = Instruction {
+            program_id: ctx.accounts.token_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.from.key(), false),
+                AccountMeta::new(ctx.accounts.to.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.authority.key(), true), // [missing_signer_validation]
+            ],
+            data: vec![3],
+        };
+        invoke(
+            &ix,
+            &[
+                ctx.accounts.from.to_account_info(),
+                ctx.accounts.to.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    // Case 47: Hand-built transfer marking a Signer as signer => no error
+    pub fn hand_built_transfer_with_signer(
+        ctx: Context<TransferCheckedCpiWithSigner>,
+    ) -> Result<()> {
+        let ix = Instruction {
+            program_id: ctx.accounts.token_program.key(),
+            accounts: vec![
+                AccountMeta::new(ctx.accounts.from.key(), false),
+                AccountMeta::new(ctx.accounts.to.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.authority.key(), true), // [safe_signer_validation]
+            ],
+            data: vec![3],
+        };
+        invoke(
+            &ix,
+            &[
+                ctx.accounts.from.to_account_info(),
+                ctx.accounts.to.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
 }
 
 impl<'info> TransferMissingSigner<'info> {
@@ -614,7 +934,45 @@ impl<'info> TransferMissingSigner<'info> {
     }
 }
 
+impl<'info> TransferWithSigner<'info> {
+    // Safe: system_program::transfer from the `&mut self` accounts struct, `from` has #[account(signer)]
+    pub fn self_implemented_transfer_with_signer(&mut self, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer { // [safe_signer_validation]
+            from: self.from.to_account_info(),
+            to: self.to.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+}
+
 // Account structs for unsafe cases
+#[derive(Accounts)]
+pub struct BaselineDiffTransfer<'info> {
+    #[account(mut)]
+    pub old_payer: Account<'info, UserState>, // Missing #[account(signer)]
+    #[account(mut)]
+    pub new_payer: Account<'info, UserState>, // Missing #[account(signer)]
+    #[account(mut)]
+    pub allowed_payer: Account<'info, UserState>, // Missing #[account(signer)]
+    #[account(mut)]
+    pub to: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SplTransferAddressPinnedAuthority<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: pinned to the admin key, but the admin never has to sign
+    #[account(address = ADMIN_KEY)]
+    pub authority: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct TransferMissingSigner<'info> {
     #[account(mut)]
@@ -656,6 +1014,18 @@ pub struct TestAccounts<'info> {
     pub token_program: Program<'info, Token2022>,
 }
 
+#[derive(Accounts)]
+pub struct MultisigTransferChecked<'info> {
+    pub from: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub to: AccountInfo<'info>,
+    /// CHECK: SPL Token multisig account whose members sign instead of it
+    pub authority: AccountInfo<'info>,
+    pub signer_1: Signer<'info>,
+    pub signer_2: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+}
+
 #[derive(Accounts)]
 pub struct TransferCheckedCpiWithSigner<'info> {
     pub from: AccountInfo<'info>,
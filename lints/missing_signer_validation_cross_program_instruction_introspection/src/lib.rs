@@ -0,0 +1,105 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{ensure_anchor_context_initialized, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects instruction handlers loading another instruction of the transaction from the
+    /// instructions sysvar, with `load_instruction_at_checked` or `get_instruction_relative`,
+    /// and deciding a check from its data or accounts without comparing its `program_id` to a
+    /// constant, e.g. `crate::ID` or `ed25519_program::ID`.
+    ///
+    /// ### Why is this bad?
+    /// Introspection is used to authorize an action by requiring another instruction in the same
+    /// transaction, e.g. a signature verification or the matching half of a flash loan, or to
+    /// reject sandwiching instructions. Any program can emit an instruction with the expected
+    /// data and accounts, so unless its program ID is checked an attacker forges the
+    /// precondition with an instruction of their own program.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let ix = get_instruction_relative(1, &ctx.accounts.instructions.to_account_info())?;
+    /// require!(ix.data[0] == END_SWAP_TAG, ErrorCode::MissingEndSwap);
+    ///
+    /// // Good:
+    /// let ix = get_instruction_relative(1, &ctx.accounts.instructions.to_account_info())?;
+    /// require_keys_eq!(ix.program_id, crate::ID, ErrorCode::MissingEndSwap);
+    /// require!(ix.data[0] == END_SWAP_TAG, ErrorCode::MissingEndSwap);
+    /// ```
+    pub MISSING_SIGNER_VALIDATION_CROSS_PROGRAM_INSTRUCTION_INTROSPECTION,
+    Warn,
+    "instruction loaded from the instructions sysvar authorizes an action without a program ID check"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingSignerValidationCrossProgramInstructionIntrospection {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_SIGNER_VALIDATION_CROSS_PROGRAM_INSTRUCTION_INTROSPECTION) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_SIGNER_VALIDATION_CROSS_PROGRAM_INSTRUCTION_INTROSPECTION);
+
+        // Analyze functions that take Anchor context
+        if mir_analyzer.anchor_context_info.is_none() {
+            return;
+        }
+
+        analyze_introspection(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_introspection<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+) {
+    for load in collect_introspection_loads(mir_analyzer) {
+        // An instruction only logged or counted authorizes nothing
+        if !decides_a_check(cx, mir_analyzer, &load)
+            || has_constant_program_id_check(cx, mir_analyzer, &load)
+        {
+            continue;
+        }
+
+        span_lint(
+            cx,
+            MISSING_SIGNER_VALIDATION_CROSS_PROGRAM_INSTRUCTION_INTROSPECTION,
+            load.span,
+            "this introspected instruction decides a check, but its `program_id` is never compared to a constant — any program can emit an instruction with the same data and accounts; check it with e.g. `require_keys_eq!(ix.program_id, crate::ID)`",
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use anchor_lints_utils::{
+    diag_items::is_instruction_introspection_fn, mir_analyzer::MirAnalyzer, models::Origin,
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BinOp, Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind},
+    ty::{self as rustc_ty, TyKind},
+};
+use rustc_span::{Span, sym};
+
+use std::collections::HashSet;
+
+/// A call loading an instruction of the transaction from the instructions sysvar, e.g.
+/// `load_instruction_at_checked(0, &instructions)`
+pub struct IntrospectionLoad {
+    pub destination: Local,
+    pub span: Span,
+}
+
+/// Collect the calls loading an instruction from the instructions sysvar
+pub fn collect_introspection_loads(mir_analyzer: &MirAnalyzer) -> Vec<IntrospectionLoad> {
+    let tcx = mir_analyzer.cx.tcx;
+    mir_analyzer
+        .mir
+        .basic_blocks
+        .iter()
+        .filter_map(|bbdata| {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                destination,
+                fn_span,
+                ..
+            } = &bbdata.terminator().kind
+                && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+                && is_instruction_introspection_fn(tcx, *fn_def_id)
+                && let Some(destination) = destination.as_local()
+            {
+                Some(IntrospectionLoad {
+                    destination,
+                    span: *fn_span,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Check if the operand holds a value read from the loaded instruction, e.g. `ix.data[0]`
+fn is_read_from(mir_analyzer: &MirAnalyzer, load: &IntrospectionLoad, operand: &Operand) -> bool {
+    operand
+        .place()
+        .is_some_and(|place| is_place_read_from(mir_analyzer, load, place))
+}
+
+fn is_place_read_from(mir_analyzer: &MirAnalyzer, load: &IntrospectionLoad, place: Place) -> bool {
+    mir_analyzer
+        .collect_source_locals(place.local)
+        .contains(&load.destination)
+}
+
+/// Collect the comparisons of the body, `==`/`!=` on values implementing `PartialEq` as well as
+/// the comparisons of primitive values, as pairs of operands
+fn collect_comparisons<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+) -> Vec<(Operand<'tcx>, Operand<'tcx>)> {
+    let mut comparisons = Vec::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (_, Rvalue::BinaryOp(op, box (lhs, rhs)))) = &stmt.kind
+                && matches!(
+                    op,
+                    BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge
+                )
+            {
+                comparisons.push((lhs.clone(), rhs.clone()));
+            }
+        }
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && (cx.tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
+                || cx.tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id))
+            && let [lhs, rhs] = args.as_ref()
+        {
+            comparisons.push((lhs.node.clone(), rhs.node.clone()));
+        }
+    }
+    comparisons
+}
+
+/// Check if the loaded instruction decides a check, e.g. `ix.data[..8] == DISCRIMINATOR` or
+/// `ix.accounts[0].pubkey == user.key()`
+pub fn decides_a_check<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    load: &IntrospectionLoad,
+) -> bool {
+    collect_comparisons(cx, mir_analyzer)
+        .iter()
+        .any(|(lhs, rhs)| {
+            is_read_from(mir_analyzer, load, lhs) || is_read_from(mir_analyzer, load, rhs)
+        })
+}
+
+/// Collect the locals holding the `program_id` of the loaded instruction, e.g. `ix.program_id`
+/// or `&ix.program_id`
+fn collect_program_id_locals(
+    mir_analyzer: &MirAnalyzer,
+    load: &IntrospectionLoad,
+) -> HashSet<Local> {
+    let tcx = mir_analyzer.cx.tcx;
+    let mir = mir_analyzer.mir;
+    let reads_program_id = |place: &Place| {
+        place.iter_projections().any(|(base, elem)| {
+            let ProjectionElem::Field(field_idx, _) = elem else {
+                return false;
+            };
+            matches!(base.ty(mir, tcx).ty.kind(), TyKind::Adt(adt_def, _)
+                if adt_def.is_struct()
+                    && adt_def.non_enum_variant().fields[field_idx].name.as_str() == "program_id")
+        })
+    };
+    mir.basic_blocks
+        .iter()
+        .flat_map(|bbdata| &bbdata.statements)
+        .filter_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (
+                place,
+                Rvalue::Ref(_, _, src)
+                | Rvalue::CopyForDeref(src)
+                | Rvalue::Use(Operand::Copy(src) | Operand::Move(src)),
+            )) if reads_program_id(src) && is_place_read_from(mir_analyzer, load, *src) => {
+                place.as_local()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check if the `program_id` of the loaded instruction is compared to a constant, e.g.
+/// `require_keys_eq!(ix.program_id, crate::ID)` or `ix.program_id == ed25519_program::ID`
+pub fn has_constant_program_id_check<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    load: &IntrospectionLoad,
+) -> bool {
+    let program_id_locals = collect_program_id_locals(mir_analyzer, load);
+    if program_id_locals.is_empty() {
+        return false;
+    }
+    let is_program_id = |operand: &Operand| {
+        operand
+            .place()
+            .and_then(|place| place.as_local())
+            .is_some_and(|local| {
+                mir_analyzer
+                    .collect_source_locals(local)
+                    .iter()
+                    .any(|source| program_id_locals.contains(source))
+            })
+    };
+    collect_comparisons(cx, mir_analyzer)
+        .iter()
+        .any(|(lhs, rhs)| {
+            (is_program_id(lhs) && mir_analyzer.origin_of_operand(rhs) == Origin::Constant)
+                || (is_program_id(rhs) && mir_analyzer.origin_of_operand(lhs) == Origin::Constant)
+        })
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    get_instruction_relative, load_current_index_checked, load_instruction_at_checked,
+    ID as INSTRUCTIONS_SYSVAR_ID,
+};
+
+declare_id!("11111111111111111111111111111111");
+
+const END_SWAP_TAG: u8 = 2;
+const ORACLE_PROGRAM_ID: Pubkey = anchor_lang::pubkey!("SysvarRent111111111111111111111111111111111");
+
+#[program]
+pub mod missing_signer_validation_cross_program_instruction_introspection_tests {
+    use super::*;
+
+    // BAD: the next instruction is required to end the swap, but any program can emit it
+    pub fn begin_swap_unchecked(ctx: Context<Introspect>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let next_ix = get_instruction_relative(1, &instructions)?; // [missing_signer_validation_cross_program_instruction_introspection]
+        require!(next_ix.data[0] == END_SWAP_TAG, ErrorCode::MissingEndSwap);
+        Ok(())
+    }
+
+    // BAD: the first instruction is trusted to have been signed by the user
+    pub fn repay_unchecked(ctx: Context<Introspect>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let first_ix = load_instruction_at_checked(0, &instructions)?; // [missing_signer_validation_cross_program_instruction_introspection]
+        require_keys_eq!(first_ix.accounts[0].pubkey, ctx.accounts.user.key(), ErrorCode::MissingBorrow);
+        Ok(())
+    }
+
+    // BAD: the program ID is compared to an account chosen by the caller
+    pub fn begin_swap_with_caller_program(ctx: Context<IntrospectWithProgram>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let next_ix = get_instruction_relative(1, &instructions)?; // [missing_signer_validation_cross_program_instruction_introspection]
+        require_keys_eq!(next_ix.program_id, ctx.accounts.swap_program.key(), ErrorCode::MissingEndSwap);
+        require!(next_ix.data[0] == END_SWAP_TAG, ErrorCode::MissingEndSwap);
+        Ok(())
+    }
+
+    // GOOD: the next instruction must belong to this program
+    pub fn begin_swap_checked(ctx: Context<Introspect>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let next_ix = get_instruction_relative(1, &instructions)?; // [safe_instruction_introspection]
+        require_keys_eq!(next_ix.program_id, crate::ID, ErrorCode::MissingEndSwap);
+        require!(next_ix.data[0] == END_SWAP_TAG, ErrorCode::MissingEndSwap);
+        Ok(())
+    }
+
+    // GOOD: the sibling instruction must belong to the oracle program
+    pub fn verify_oracle_update(ctx: Context<Introspect>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let current_index = load_current_index_checked(&instructions)?;
+        require!(current_index > 0, ErrorCode::MissingOracleUpdate);
+        let oracle_ix = load_instruction_at_checked(current_index as usize - 1, &instructions)?; // [safe_instruction_introspection]
+        if oracle_ix.program_id != ORACLE_PROGRAM_ID {
+            return err!(ErrorCode::MissingOracleUpdate);
+        }
+        require_keys_eq!(oracle_ix.accounts[0].pubkey, ctx.accounts.user.key(), ErrorCode::MissingOracleUpdate);
+        Ok(())
+    }
+
+    // GOOD: the previous instruction is only logged
+    pub fn log_previous_instruction(ctx: Context<Introspect>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let previous_ix = get_instruction_relative(-1, &instructions)?; // [safe_instruction_introspection]
+        msg!("previous program: {}", previous_ix.program_id);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Introspect<'info> {
+    pub user: Signer<'info>,
+    /// CHECK: the instructions sysvar
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct IntrospectWithProgram<'info> {
+    pub user: Signer<'info>,
+    /// CHECK: the instructions sysvar
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+    /// CHECK: the program expected to end the swap
+    pub swap_program: UncheckedAccount<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The swap is not ended in this transaction")]
+    MissingEndSwap,
+    #[msg("The borrow is not in this transaction")]
+    MissingBorrow,
+    #[msg("The oracle is not updated in this transaction")]
+    MissingOracleUpdate,
+}
@@ -0,0 +1,146 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    cpi_types::{CpiKind, detect_cpi_kind},
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::find_context_field, extract_arg_local, extract_cpi_account,
+        find_cpi_accounts, pda_detection::is_pda_account, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `system_program::transfer` CPIs moving native SOL out of a program-controlled
+    /// vault (an account with a `seeds` constraint) in a handler whose accounts struct has no
+    /// signer (`Signer<'info>` or `#[account(signer)]`).
+    ///
+    /// ### Why is this bad?
+    /// The program signs for its own PDA vault with `CpiContext::new_with_signer`, so the system
+    /// program accepts the transfer for whoever calls the instruction. Without a user or
+    /// authority required to sign, anyone can drain the vault to an account of their choice.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: nobody authorizes the withdrawal
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     #[account(mut, seeds = [b"vault"], bump)]
+    ///     pub vault: SystemAccount<'info>,
+    ///     /// CHECK: receives the lamports
+    ///     #[account(mut)]
+    ///     pub recipient: UncheckedAccount<'info>,
+    ///     pub system_program: Program<'info, System>,
+    /// }
+    /// let cpi_ctx = CpiContext::new_with_signer(system_program, Transfer { from: vault, to: recipient }, signer_seeds);
+    /// system_program::transfer(cpi_ctx, amount)?;
+    ///
+    /// Good: the vault is derived from an authority that must sign
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     #[account(mut, seeds = [b"vault", authority.key().as_ref()], bump)]
+    ///     pub vault: SystemAccount<'info>,
+    ///     #[account(mut)]
+    ///     pub authority: Signer<'info>,
+    ///     pub system_program: Program<'info, System>,
+    /// }
+    /// ```
+    pub MISSING_SIGNER_VALIDATION_FOR_NATIVE_SOL_WITHDRAWAL,
+    Warn,
+    "native SOL withdrawn from a PDA vault without any signer authorizing it"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingSignerValidationForNativeSolWithdrawal {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_SIGNER_VALIDATION_FOR_NATIVE_SOL_WITHDRAWAL) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_SIGNER_VALIDATION_FOR_NATIVE_SOL_WITHDRAWAL);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_native_sol_withdrawals(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_native_sol_withdrawals<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    // A signer anywhere in the accounts struct gates the handler
+    if has_signer_account(cx, anchor_context) {
+        return;
+    }
+
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && detect_cpi_kind(cx, *fn_def_id) == Some(CpiKind::SystemTransfer)
+            && let Some(cpi_ctx_local) = extract_arg_local(args, 0)
+            && let Some(accounts_local) = find_cpi_accounts(mir_analyzer, bb, cpi_ctx_local)
+            && let Some(from) = extract_cpi_account(mir_analyzer, accounts_local, "from")
+            && let Some(from_field) = find_context_field(cx, anchor_context, &from)
+            // Only program-controlled vaults, a user wallet as `from` has to sign itself
+            && is_pda_account(cx, from_field).is_some_and(|pda_signer| pda_signer.has_seeds)
+        {
+            span_lint_and_note(
+                cx,
+                MISSING_SIGNER_VALIDATION_FOR_NATIVE_SOL_WITHDRAWAL,
+                *fn_span,
+                format!(
+                    "native SOL is withdrawn from PDA `{}` but no account of the instruction is required to sign — add the user or authority allowed to withdraw as a `Signer`",
+                    from
+                ),
+                Some(cx.tcx.def_span(from_field.did)),
+                "vault account is declared here",
+            );
+        }
+    }
+}
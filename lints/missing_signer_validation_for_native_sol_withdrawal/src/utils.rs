@@ -0,0 +1,24 @@
+use anchor_lints_utils::{
+    diag_items::is_anchor_signer_type, mir_analyzer::AnchorContextInfo,
+    utils::account_constraints::has_account_constraint,
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::ty::TyKind;
+
+/// Check whether any account of the accounts struct is a `Signer` or has `#[account(signer)]`
+pub fn has_signer_account<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) -> bool {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return false;
+    };
+    if !adt_def.is_struct() {
+        return false;
+    }
+    adt_def.non_enum_variant().fields.iter().any(|field| {
+        is_anchor_signer_type(cx.tcx, field.ty(cx.tcx, generics))
+            || has_account_constraint(cx, field, "signer")
+    })
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_signer_validation_for_native_sol_withdrawal_tests {
+    use super::*;
+
+    // GOOD: the vault is derived from the authority, who must sign the withdrawal
+    pub fn withdraw_authorized(ctx: Context<WithdrawAuthorized>, amount: u64) -> Result<()> {
+        let authority_key = ctx.accounts.authority.key();
+        let bump = ctx.bumps.vault;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            cpi_accounts,
+            &[&[b"vault".as_ref(), authority_key.as_ref(), &[bump]]],
+        );
+        system_program::transfer(cpi_ctx, amount)?; // [safe_native_sol_withdrawal]
+        Ok(())
+    }
+
+    // GOOD: depositing from the user's own wallet, the user signs as `from`
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_native_sol_withdrawal]
+        Ok(())
+    }
+
+    // BAD: the program signs for its vault but nobody authorizes the withdrawal
+    pub fn withdraw_unauthorized(ctx: Context<WithdrawUnauthorized>, amount: u64) -> Result<()> {
+        let bump = ctx.bumps.vault;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            cpi_accounts,
+            &[&[b"vault".as_ref(), &[bump]]],
+        );
+        system_program::transfer(cpi_ctx, amount)?; // [missing_signer_validation_for_native_sol_withdrawal]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawAuthorized<'info> {
+    #[account(mut, seeds = [b"vault", authority.key().as_ref()], bump)]
+    pub vault: SystemAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"vault", user.key().as_ref()], bump)]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnauthorized<'info> {
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: SystemAccount<'info>,
+    /// CHECK: receives the withdrawn lamports
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
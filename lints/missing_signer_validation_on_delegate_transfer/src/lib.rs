@@ -0,0 +1,163 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    cpi_types::{CpiKind, detect_cpi_kind},
+    diag_items::is_anchor_signer_type,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, parse_account_constraints},
+        ensure_anchor_context_initialized, extract_arg_local, extract_cpi_account,
+        find_cpi_accounts, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `token::transfer` CPIs from a delegated token account whose `authority` is not a
+    /// signer. The `from` account is treated as delegated when one of its constraints refers to
+    /// its `delegate`, or when its `token::authority`/`associated_token::authority` (the owner)
+    /// is another account than the authority of the transfer.
+    ///
+    /// PDA authorities (`seeds` constraint) are skipped, as the program signs for them.
+    ///
+    /// ### Why is this bad?
+    /// A transfer of delegated tokens is authorized by the delegate instead of the owner, and the
+    /// token program only accepts it when the delegate signed. Passing the delegate as a plain
+    /// `AccountInfo` gives no guarantee that it signed the transaction, so the handler either fails
+    /// at runtime or relies on a signer check that is missing from the accounts struct.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct DelegatedTransfer<'info> {
+    ///     #[account(mut, constraint = from.delegate == COption::Some(delegate.key()))]
+    ///     pub from: Account<'info, TokenAccount>,
+    ///     /// CHECK: delegate of `from`
+    ///     pub delegate: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct DelegatedTransfer<'info> {
+    ///     #[account(mut, constraint = from.delegate == COption::Some(delegate.key()))]
+    ///     pub from: Account<'info, TokenAccount>,
+    ///     pub delegate: Signer<'info>,
+    ///     // ...
+    /// }
+    /// ```
+    pub MISSING_SIGNER_VALIDATION_ON_DELEGATE_TRANSFER,
+    Warn,
+    "token transfer from a delegated account whose delegate authority is not a signer"
+}
+
+impl<'tcx> LateLintPass<'tcx> for MissingSignerValidationOnDelegateTransfer {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(MISSING_SIGNER_VALIDATION_ON_DELEGATE_TRANSFER) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(MISSING_SIGNER_VALIDATION_ON_DELEGATE_TRANSFER);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_delegate_transfers(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_delegate_transfers<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && detect_cpi_kind(cx, *fn_def_id) == Some(CpiKind::Transfer)
+            && let Some(cpi_ctx_local) = extract_arg_local(args, 0)
+            && let Some(cpi_accounts) = find_cpi_accounts(mir_analyzer, bb, cpi_ctx_local)
+            && let Some(from) = extract_cpi_account(mir_analyzer, cpi_accounts, "from")
+            && let Some(authority) = extract_cpi_account(mir_analyzer, cpi_accounts, "authority")
+            && let Some(from_field) = find_context_field(cx, anchor_context, &from)
+            && let Some(authority_field) = find_context_field(cx, anchor_context, &authority)
+        {
+            if !is_delegated_transfer(cx, from_field, &authority) {
+                continue;
+            }
+
+            // `Signer` accounts and `#[account(signer)]` are checked by Anchor
+            if anchor_context
+                .anchor_context_arg_accounts_type
+                .get(&authority)
+                .is_some_and(|ty| is_anchor_signer_type(cx.tcx, *ty))
+            {
+                continue;
+            }
+            let authority_constraints = parse_account_constraints(cx, authority_field);
+            if authority_constraints.signer {
+                continue;
+            }
+
+            // A PDA delegate is signed for by the program
+            if authority_constraints.seeds.is_some() {
+                continue;
+            }
+
+            span_lint_and_note(
+                cx,
+                MISSING_SIGNER_VALIDATION_ON_DELEGATE_TRANSFER,
+                *fn_span,
+                format!(
+                    "account `{}` is the delegate authority of this transfer from `{}` but is not a signer — declare it as `Signer<'info>` or add `#[account(signer)]`",
+                    authority, from
+                ),
+                Some(cx.tcx.def_span(authority_field.did)),
+                "delegate authority is declared here",
+            );
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lints_utils::utils::account_constraints::{ConstraintValue, parse_account_constraints};
+
+use rustc_lint::LateContext;
+use rustc_middle::ty::FieldDef;
+
+/// Check if the transfer from `from_field` is authorized by a delegate: a constraint of the
+/// account refers to its `delegate`, or its owner is another account than `authority`
+pub fn is_delegated_transfer<'tcx>(
+    cx: &LateContext<'tcx>,
+    from_field: &FieldDef,
+    authority: &str,
+) -> bool {
+    let constraints = parse_account_constraints(cx, from_field);
+    if constraints
+        .constraints
+        .iter()
+        .any(|constraint| constraint.path.contains("delegate"))
+    {
+        return true;
+    }
+
+    [
+        constraints.token.authority.as_ref(),
+        constraints.associated_token.authority.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|owner| constraint_account_name(owner) != authority)
+}
+
+/// The account a constraint value refers to, e.g. `owner` for `owner.key()`
+fn constraint_account_name(value: &ConstraintValue) -> &str {
+    value.path.split('.').next().unwrap_or_default()
+}
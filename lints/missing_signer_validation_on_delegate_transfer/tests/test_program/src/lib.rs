@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod missing_signer_validation_on_delegate_transfer_tests {
+    use super::*;
+
+    // BAD: `from` is checked to be delegated to `delegate`, which is not a signer
+    pub fn transfer_with_unchecked_delegate(
+        ctx: Context<UncheckedDelegate>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [missing_signer_validation_on_delegate_transfer]
+        Ok(())
+    }
+
+    // BAD: `from` is owned by another account than the non-signer authority
+    pub fn transfer_with_non_owner_authority(
+        ctx: Context<NonOwnerAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [missing_signer_validation_on_delegate_transfer]
+        Ok(())
+    }
+
+    // GOOD: the delegate is a `Signer`
+    pub fn transfer_with_signer_delegate(ctx: Context<SignerDelegate>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_delegate_transfer]
+        Ok(())
+    }
+
+    // GOOD: the delegate is checked with `#[account(signer)]`
+    pub fn transfer_with_signer_constraint_delegate(
+        ctx: Context<SignerConstraintDelegate>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.delegate.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_delegate_transfer]
+        Ok(())
+    }
+
+    // GOOD: the authority is the owner of `from`, not a delegate
+    pub fn transfer_with_owner_authority(ctx: Context<OwnerAuthority>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_delegate_transfer]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct UncheckedDelegate<'info> {
+    #[account(mut, constraint = from.delegate == COption::Some(delegate.key()))]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: delegate of `from`, not validated as a signer
+    pub delegate: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct NonOwnerAuthority<'info> {
+    #[account(mut, token::authority = owner)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: owner of `from`
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: delegate of `from`, not validated as a signer
+    pub delegate: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SignerDelegate<'info> {
+    #[account(mut, constraint = from.delegate == COption::Some(delegate.key()))]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub delegate: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SignerConstraintDelegate<'info> {
+    #[account(mut, token::authority = owner)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: owner of `from`
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: delegate of `from`, checked to be a signer
+    #[account(signer)]
+    pub delegate: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct OwnerAuthority<'info> {
+    #[account(mut, token::authority = owner)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: owner of `from`
+    pub owner: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
@@ -1,7 +1,7 @@
 use anchor_lints_utils::{
     diag_items::is_anchor_system_program_type,
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
-    utils::account_constraints::has_account_constraint,
+    utils::account_constraints::{has_account_constraint, parse_account_constraints},
 };
 
 use clippy_utils::source::HasSession;
@@ -53,59 +53,22 @@ pub fn is_init_instruction<'tcx>(
     false
 }
 
-/// Extract account names referenced in PDA seeds
+/// Extract account names referenced in PDA seeds, e.g. `authority` for
+/// `seeds = [b"vault", authority.key().as_ref()]`
 pub fn extract_seed_accounts_from_pda<'tcx>(
     cx: &LateContext<'tcx>,
     pda_field: &rustc_middle::ty::FieldDef,
 ) -> Vec<String> {
-    use rustc_span::Symbol;
-
-    let mut seed_accounts = Vec::new();
-    let attrs = cx.tcx.get_all_attrs(pda_field.did);
-    let mut last_ident_seeds = false;
-
-    for attr in attrs {
-        if let rustc_hir::Attribute::Unparsed(_) = attr {
-            let attr_item = attr.get_normal_item();
-            if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                for token in delim_args.tokens.iter() {
-                    match token {
-                        rustc_ast::tokenstream::TokenTree::Token(token, _) => {
-                            match token.kind {
-                                rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                    if ident == Symbol::intern("seeds") {
-                                        last_ident_seeds = true;
-                                    } else if last_ident_seeds {
-                                        // Check if this is an account name (not a method like "key", "as_ref", etc.)
-                                        let ident_str = ident.as_str();
-                                        if ident_str != "key"
-                                            && ident_str != "as_ref"
-                                            && ident_str != "b"
-                                            && ident_str != "bump"
-                                            && !ident_str.starts_with('&')
-                                        {
-                                            seed_accounts.push(ident_str.to_string());
-                                        }
-                                    }
-                                }
-                                rustc_ast::token::TokenKind::Comma => {
-                                    last_ident_seeds = false;
-                                }
-                                _ => {}
-                            }
-                        }
-                        rustc_ast::tokenstream::TokenTree::Delimited(_, _, _, token_stream) => {
-                            // Recursively extract account names from nested structures
-                            let nested_accounts =
-                                recursively_extract_seed_accounts(token_stream, last_ident_seeds);
-                            seed_accounts.extend(nested_accounts);
-                            last_ident_seeds = false;
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let Some(seed_list) = parse_account_constraints(cx, pda_field).seeds else {
+        return Vec::new();
+    };
+    let mut seed_accounts: Vec<String> = seed_list
+        .seeds
+        .iter()
+        .filter_map(|seed| seed.path.split('.').next())
+        .filter(|root| !root.is_empty() && !root.contains("::"))
+        .map(str::to_string)
+        .collect();
 
     // Remove duplicates and return
     seed_accounts.sort();
@@ -113,62 +76,6 @@ pub fn extract_seed_accounts_from_pda<'tcx>(
     seed_accounts
 }
 
-/// Check if a type is SystemProgram
-
-/// Recursively extract account names from seed token streams
-pub fn recursively_extract_seed_accounts(
-    token_stream: &rustc_ast::tokenstream::TokenStream,
-    in_seeds_context: bool,
-) -> Vec<String> {
-    let mut account_names = Vec::new();
-    let mut potential_account: Option<String> = None;
-
-    for token_tree in token_stream.iter() {
-        match token_tree {
-            rustc_ast::tokenstream::TokenTree::Token(token, _) => {
-                match token.kind {
-                    rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                        if in_seeds_context {
-                            let ident_str = ident.as_str();
-                            // Skip common methods and keywords
-                            if ident_str == "key" || ident_str == "as_ref" || ident_str == "b" {
-                                if let Some(account) = potential_account.take() {
-                                    account_names.push(account);
-                                }
-                            } else if ident_str != "bump" && !ident_str.starts_with('&') {
-                                potential_account = Some(ident_str.to_string());
-                            }
-                        }
-                    }
-                    rustc_ast::token::TokenKind::Dot => {
-                        if let Some(account) = potential_account.take() {
-                            account_names.push(account);
-                        }
-                    }
-                    rustc_ast::token::TokenKind::Comma => {
-                        if let Some(account) = potential_account.take() {
-                            account_names.push(account);
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            rustc_ast::tokenstream::TokenTree::Delimited(_, _, _, nested_stream) => {
-                let nested_accounts =
-                    recursively_extract_seed_accounts(nested_stream, in_seeds_context);
-                account_names.extend(nested_accounts);
-                potential_account = None;
-            }
-        }
-    }
-
-    if let Some(account) = potential_account {
-        account_names.push(account);
-    }
-
-    account_names
-}
-
 pub fn is_account_required<'cx, 'tcx>(
     cx: &'cx LateContext<'tcx>,
     field: &rustc_middle::ty::FieldDef,
@@ -223,52 +130,12 @@ pub fn is_account_used_as_close_target<'tcx>(
 
         let variant = adt_def.non_enum_variant();
 
+        // `close = receiver` sends the lamports of the closed account to `receiver`
         for field in &variant.fields {
-            let attrs = cx.tcx.get_all_attrs(field.did);
-
-            for attr in attrs {
-                if let rustc_hir::Attribute::Unparsed(_) = attr {
-                    let attr_item = attr.get_normal_item();
-                    if let rustc_hir::AttrArgs::Delimited(delim_args) = &attr_item.args {
-                        let mut found_close = false;
-                        let mut after_eq = false;
-
-                        for token in delim_args.tokens.iter() {
-                            if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token {
-                                match token.kind {
-                                    rustc_ast::token::TokenKind::Ident(ident, ..) => {
-                                        if ident.as_str() == "close" {
-                                            found_close = true;
-                                        } else if found_close && after_eq {
-                                            // Check if this identifier matches account_name
-                                            if ident.as_str() == account_name {
-                                                return true;
-                                            }
-                                        }
-                                    }
-                                    rustc_ast::token::TokenKind::Eq => {
-                                        if found_close {
-                                            after_eq = true;
-                                        }
-                                    }
-                                    _ => {
-                                        if found_close
-                                            && after_eq
-                                            && let Ok(snippet) = cx
-                                                .tcx
-                                                .sess()
-                                                .source_map()
-                                                .span_to_snippet(token.span)
-                                            && snippet.contains(account_name)
-                                        {
-                                            return true;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            if let Some(close) = parse_account_constraints(cx, field).close
+                && close.path.split('.').next() == Some(account_name)
+            {
+                return true;
             }
         }
     }
@@ -8,10 +8,11 @@ extern crate rustc_span;
 
 use anchor_lints_utils::{
     diag_items::is_anchor_system_account_type,
+    diagnostics::span_lint,
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
     utils::{pda_detection::is_pda_account, should_skip_function},
 };
-use clippy_utils::diagnostics::span_lint;
 
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
@@ -71,6 +72,11 @@ impl<'tcx> LateLintPass<'tcx> for OverconstrainedSeedAccount {
         main_fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(OVERCONSTRAINED_SEED_ACCOUNT) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, main_fn_span, def_id) {
             return;
@@ -80,6 +86,7 @@ impl<'tcx> LateLintPass<'tcx> for OverconstrainedSeedAccount {
 
         // Update anchor context info with accounts
         anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(OVERCONSTRAINED_SEED_ACCOUNT);
 
         // Analyze functions that take Anchor context
         let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
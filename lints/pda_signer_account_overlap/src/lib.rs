@@ -9,6 +9,7 @@ extern crate rustc_span;
 use anchor_lints_utils::{
     mir_analyzer::MirAnalyzer,
     models::{PdaSigner, UnsafeAccount},
+    registry::is_lint_enabled,
     utils::get_hir_body_from_local_def_id,
 };
 
@@ -70,6 +71,11 @@ impl<'tcx> LateLintPass<'tcx> for PdaSignerAccountOverlap {
         fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(PDA_SIGNER_ACCOUNT_OVERLAP) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, fn_span, def_id) {
             return;
@@ -93,6 +99,7 @@ fn analyze_pda_signer_account_overlap<'tcx>(
     def_id: LocalDefId,
 ) {
     let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+    mir_analyzer.debug_dump(PDA_SIGNER_ACCOUNT_OVERLAP);
 
     // Only analyze functions that take Anchor context
     let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -1,5 +1,6 @@
 use anchor_lints_utils::{
     diag_items::is_anchor_cpi_context,
+    diagnostics::span_lint_and_help,
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
     models::{PdaSigner, UnsafeAccount},
     utils::{check_cpi_call_is_new_with_signer, is_implementation_method},
@@ -14,7 +15,6 @@ use crate::{
     PDA_SIGNER_ACCOUNT_OVERLAP, analyze_nested_function_if_available, check_cpi_uses_pda_signer,
     extract_accounts_passed_to_cpi,
 };
-use clippy_utils::diagnostics::span_lint_and_help;
 
 /// Analyzer context that holds all commonly passed parameters for PDA signer account overlap analysis
 pub struct PdaSignerAnalyzer {
@@ -0,0 +1,172 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_ast;
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::parse_account_constraints, ensure_anchor_context_initialized,
+        pda_detection::is_pda_account, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::ty::TyKind;
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects PDAs whose `seeds` include the raw bytes of a variable-length instruction
+    /// argument, e.g. `seeds = [name.as_bytes()]` with `#[instruction(name: String)]`, when no
+    /// constant seed comes before it and neither a `constraint` nor the handler checks its
+    /// length.
+    ///
+    /// Arguments of a fixed length, e.g. a `u64` or a `Pubkey`, are not reported. This is an
+    /// advisory lint: the seeds may be unique to the program for other reasons.
+    ///
+    /// ### Why is this bad?
+    /// The seeds of a PDA are concatenated before hashing. A caller choosing the bytes of a seed
+    /// can make them line up with the seeds of another account type, so the address they derive
+    /// collides with, or impersonates, an account the program derives elsewhere.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// #[instruction(name: String)]
+    /// pub struct CreateProfile<'info> {
+    ///     #[account(init, payer = user, space = 8 + 64, seeds = [name.as_bytes()], bump)]
+    ///     pub profile: Account<'info, Profile>,
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// #[instruction(name: String)]
+    /// pub struct CreateProfile<'info> {
+    ///     #[account(init, payer = user, space = 8 + 64, seeds = [b"profile", name.as_bytes()], bump)]
+    ///     pub profile: Account<'info, Profile>,
+    ///     // ...
+    /// }
+    /// ```
+    pub SEED_CONTAINS_USER_CONTROLLED_VALUE_WITHOUT_VALIDATION,
+    Warn,
+    "PDA seeded with the raw bytes of an unvalidated instruction argument"
+}
+
+impl<'tcx> LateLintPass<'tcx> for SeedContainsUserControlledValueWithoutValidation {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(SEED_CONTAINS_USER_CONTROLLED_VALUE_WITHOUT_VALIDATION) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(SEED_CONTAINS_USER_CONTROLLED_VALUE_WITHOUT_VALIDATION);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_user_seeds(cx, &mir_analyzer, anchor_context, body);
+    }
+}
+
+fn analyze_user_seeds<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    body: &HirBody<'tcx>,
+) {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+
+    let args: Vec<_> = collect_instruction_args(cx, mir_analyzer, adt_def.did())
+        .into_iter()
+        .filter(|arg| is_variable_length(cx, arg.ty))
+        .collect();
+    if args.is_empty() {
+        return;
+    }
+
+    let fields = &adt_def.non_enum_variant().fields;
+    let constraints: Vec<_> = fields
+        .iter()
+        .map(|field| parse_account_constraints(cx, field))
+        .collect();
+    let struct_constraints: Vec<_> = constraints
+        .iter()
+        .flat_map(|constraints| &constraints.constraints)
+        .collect();
+
+    for (field, constraints) in fields.iter().zip(&constraints) {
+        if is_pda_account(cx, field).is_none() {
+            continue;
+        }
+        let Some(seed_list) = &constraints.seeds else {
+            continue;
+        };
+
+        for arg in &args {
+            let Some(position) = seed_list
+                .seeds
+                .iter()
+                .position(|seed| seed_uses_arg(seed, arg))
+            else {
+                continue;
+            };
+
+            // A constant seed before the argument keeps the PDAs of this account type apart
+            if seed_list.seeds[..position].iter().any(is_constant_seed)
+                || has_length_constraint(&struct_constraints, arg)
+                || has_length_check(mir_analyzer, arg)
+            {
+                continue;
+            }
+
+            let seed = &seed_list.seeds[position];
+            let account_name = field.ident(cx.tcx);
+            span_lint_and_note(
+                cx,
+                SEED_CONTAINS_USER_CONTROLLED_VALUE_WITHOUT_VALIDATION,
+                seed.span,
+                format!(
+                    "seed `{}` of `{}` is the raw bytes of the instruction argument `{}`, with no constant seed before it and no length check — a caller can choose it to collide with another PDA; prefix the seeds with a constant such as `b\"{}\"`",
+                    seed.text, account_name, arg.name, account_name
+                ),
+                body.params.get(arg.param_index).map(|param| param.span),
+                format!("`{}` is chosen by the caller of the instruction", arg.name),
+            );
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use anchor_lints_utils::{
+    mir_analyzer::MirAnalyzer,
+    utils::{account_constraints::ConstraintValue, extract_arg_local},
+};
+
+use clippy_utils::ty::is_type_diagnostic_item;
+
+use rustc_ast::{token::TokenKind, tokenstream::TokenTree};
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BinOp, HasLocalDecls, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    ty::{self as rustc_ty, Ty, TyKind},
+};
+use rustc_span::{Symbol, sym};
+
+use std::collections::HashSet;
+
+/// An instruction argument declared with `#[instruction(...)]` on the accounts struct
+pub struct InstructionArg<'tcx> {
+    pub name: String,
+    /// Index of the argument in the parameters of the handler, after the context
+    pub param_index: usize,
+    pub local: Local,
+    pub ty: Ty<'tcx>,
+}
+
+/// Collect the instruction arguments the seeds of the accounts struct can refer to, matched by
+/// position to the parameters of the handler following the context, as Anchor deserializes them
+pub fn collect_instruction_args<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    accounts_struct: DefId,
+) -> Vec<InstructionArg<'tcx>> {
+    let names = instruction_arg_names(cx, accounts_struct);
+    let mir = mir_analyzer.mir;
+    names
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, name)| {
+            // Local 1 is the context
+            let param_index = index + 1;
+            let local = Local::from_usize(param_index + 1);
+            if param_index >= mir.arg_count {
+                return None;
+            }
+            Some(InstructionArg {
+                name,
+                param_index,
+                local,
+                ty: mir.local_decls()[local].ty,
+            })
+        })
+        .collect()
+}
+
+/// Get the names of the `#[instruction(name: Type, ..)]` arguments, in order
+fn instruction_arg_names(cx: &LateContext<'_>, accounts_struct: DefId) -> Vec<String> {
+    let mut names = Vec::new();
+    for attr in cx.tcx.get_all_attrs(accounts_struct) {
+        let rustc_hir::Attribute::Unparsed(_) = attr else {
+            continue;
+        };
+        if !attr.has_name(Symbol::intern("instruction")) {
+            continue;
+        }
+        let rustc_hir::AttrArgs::Delimited(args) = &attr.get_normal_item().args else {
+            continue;
+        };
+        // Types only contain `::`, so an identifier followed by a single `:` is an argument name
+        let tokens: Vec<&TokenTree> = args.tokens.iter().collect();
+        for pair in tokens.windows(2) {
+            if let [TokenTree::Token(name, _), TokenTree::Token(colon, _)] = pair
+                && let TokenKind::Ident(ident, ..) = name.kind
+                && colon.kind == TokenKind::Colon
+            {
+                names.push(ident.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Check if the argument has no fixed length, i.e. a `String`, `&str`, `Vec` or slice, whose
+/// bytes can be chosen to line up with the seeds of another PDA
+pub fn is_variable_length<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
+    let ty = ty.peel_refs();
+    ty.is_str()
+        || matches!(ty.kind(), TyKind::Slice(_))
+        || is_type_diagnostic_item(cx, ty, sym::String)
+        || is_type_diagnostic_item(cx, ty, sym::Vec)
+}
+
+/// Check if the seed is the argument itself, e.g. `name.as_bytes()` or `name.as_ref()`
+pub fn seed_uses_arg(seed: &ConstraintValue, arg: &InstructionArg) -> bool {
+    seed.path.split('.').next() == Some(arg.name.as_str())
+}
+
+/// Check if the seed is a constant, a byte string literal such as `b"profile"` or a constant
+/// such as `PROFILE_SEED`, separating the PDAs of this account type from the others
+pub fn is_constant_seed(seed: &ConstraintValue) -> bool {
+    let head = seed.path.split(['.', ':']).next().unwrap_or_default();
+    head.is_empty()
+        || head
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Check if a `constraint` of the accounts struct bounds the length of the argument, e.g.
+/// `constraint = name.len() <= MAX_NAME_LEN`
+pub fn has_length_constraint(constraints: &[&ConstraintValue], arg: &InstructionArg) -> bool {
+    let len_call = format!("{}.len", arg.name);
+    constraints
+        .iter()
+        .any(|constraint| constraint.path.contains(&len_call))
+}
+
+/// Check if the handler compares the length of the argument, e.g.
+/// `require!(name.len() <= MAX_NAME_LEN, ..)`
+pub fn has_length_check(mir_analyzer: &MirAnalyzer, arg: &InstructionArg) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    let mir = mir_analyzer.mir;
+    let lengths: HashSet<Local> = mir
+        .basic_blocks
+        .iter()
+        .filter_map(|bbdata| {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+                && tcx
+                    .opt_item_name(*fn_def_id)
+                    .is_some_and(|name| name.as_str() == "len")
+                && let Some(receiver) = extract_arg_local(args, 0)
+                && mir_analyzer
+                    .collect_source_locals(receiver)
+                    .contains(&arg.local)
+            {
+                destination.as_local()
+            } else {
+                None
+            }
+        })
+        .collect();
+    if lengths.is_empty() {
+        return false;
+    }
+
+    let uses_length = |operand: &Operand<'_>| {
+        operand
+            .place()
+            .and_then(|place| place.as_local())
+            .is_some_and(|local| {
+                mir_analyzer
+                    .collect_source_locals(local)
+                    .iter()
+                    .any(|source| lengths.contains(source))
+            })
+    };
+    mir.basic_blocks
+        .iter()
+        .flat_map(|bbdata| &bbdata.statements)
+        .any(|stmt| {
+            matches!(
+                &stmt.kind,
+                StatementKind::Assign(box (_, Rvalue::BinaryOp(op, box (lhs, rhs))))
+                    if matches!(
+                        op,
+                        BinOp::Gt | BinOp::Ge | BinOp::Lt | BinOp::Le | BinOp::Eq | BinOp::Ne
+                    ) && (uses_length(lhs) || uses_length(rhs))
+            )
+        })
+}
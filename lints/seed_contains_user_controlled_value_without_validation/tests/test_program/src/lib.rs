@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+const MAX_NAME_LEN: usize = 32;
+const PROFILE_SEED: &[u8] = b"profile";
+
+#[program]
+pub mod seed_contains_user_controlled_value_without_validation_tests {
+    use super::*;
+
+    // BAD: the PDA is seeded with nothing but the name chosen by the caller
+    pub fn create_raw_profile(_ctx: Context<CreateRawProfile>, name: String) -> Result<()> {
+        msg!("created {}", name);
+        Ok(())
+    }
+
+    // BAD: the raw bytes come first, the user key after them does not separate the domain
+    pub fn create_raw_record(_ctx: Context<CreateRawRecord>, data: Vec<u8>) -> Result<()> {
+        msg!("stored {} bytes", data.len());
+        Ok(())
+    }
+
+    // GOOD: a constant prefix separates the PDAs of profiles from the others
+    pub fn create_prefixed_profile(_ctx: Context<CreatePrefixedProfile>, name: String) -> Result<()> {
+        msg!("created {}", name);
+        Ok(())
+    }
+
+    // GOOD: the prefix is a named constant
+    pub fn create_const_prefixed_profile(
+        _ctx: Context<CreateConstPrefixedProfile>,
+        name: String,
+    ) -> Result<()> {
+        msg!("created {}", name);
+        Ok(())
+    }
+
+    // GOOD: a constraint bounds the length of the name
+    pub fn create_bounded_profile(_ctx: Context<CreateBoundedProfile>, name: String) -> Result<()> {
+        msg!("created {}", name);
+        Ok(())
+    }
+
+    // GOOD: the handler checks the length of the name
+    pub fn create_checked_profile(_ctx: Context<CreateCheckedProfile>, name: String) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+        Ok(())
+    }
+
+    // GOOD: a `u64` always has the same length
+    pub fn create_numbered_profile(_ctx: Context<CreateNumberedProfile>, id: u64) -> Result<()> {
+        msg!("created {}", id);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateRawProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [name.as_bytes()], // [seed_contains_user_controlled_value_without_validation]
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(data: Vec<u8>)]
+pub struct CreateRawRecord<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [data.as_ref(), user.key().as_ref()], // [seed_contains_user_controlled_value_without_validation]
+        bump
+    )]
+    pub record: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreatePrefixedProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [b"profile", name.as_bytes()], // [safe_user_seed]
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateConstPrefixedProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [PROFILE_SEED, name.as_bytes()], // [safe_user_seed]
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateBoundedProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [name.as_bytes()], // [safe_user_seed]
+        bump,
+        constraint = name.len() <= MAX_NAME_LEN @ ErrorCode::NameTooLong
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateCheckedProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [name.as_bytes()], // [safe_user_seed]
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateNumberedProfile<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + 64,
+        seeds = [id.to_le_bytes().as_ref()], // [safe_user_seed]
+        bump
+    )]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Profile {
+    pub owner: Pubkey,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Name too long")]
+    NameTooLong,
+}
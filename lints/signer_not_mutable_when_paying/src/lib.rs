@@ -0,0 +1,187 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    cpi_types::{CpiKind, detect_cpi_kind},
+    diag_items::is_anchor_signer_type,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, parse_account_constraints},
+        extract_arg_local, extract_cpi_account, find_cpi_accounts, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty, TyKind},
+};
+use rustc_span::Span;
+
+use std::collections::HashSet;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects accounts that pay for another account through `payer = ...` or
+    /// `realloc::payer = ...`, and signers debited by a `system_program::transfer` CPI, that are
+    /// not marked `#[account(mut)]`.
+    ///
+    /// ### Why is this bad?
+    /// The runtime only lets a program debit the lamports of writable accounts. A payer or
+    /// funding signer that is not `mut` is passed read-only, so every call of the instruction
+    /// fails at runtime.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Deposit<'info> {
+    ///     pub payer: Signer<'info>, // debited by `system_program::transfer`
+    ///     // ...
+    /// }
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Deposit<'info> {
+    ///     #[account(mut)]
+    ///     pub payer: Signer<'info>,
+    ///     // ...
+    /// }
+    /// ```
+    pub SIGNER_NOT_MUTABLE_WHEN_PAYING,
+    Warn,
+    "account paying lamports is not marked `mut`"
+}
+
+impl<'tcx> LateLintPass<'tcx> for SignerNotMutableWhenPaying {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(SIGNER_NOT_MUTABLE_WHEN_PAYING) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(SIGNER_NOT_MUTABLE_WHEN_PAYING);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        // Each account is reported once, even if it pays for several accounts
+        let mut reported: HashSet<String> = HashSet::new();
+        analyze_payer_constraints(cx, anchor_context, &mut reported);
+        analyze_lamport_debits(cx, &mir_analyzer, anchor_context, &mut reported);
+    }
+}
+
+fn analyze_payer_constraints<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    reported: &mut HashSet<String>,
+) {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+    if !adt_def.is_struct() {
+        return;
+    }
+
+    for field in &adt_def.non_enum_variant().fields {
+        let constraints = parse_account_constraints(cx, field);
+        for (key, payer) in payer_constraints(&constraints) {
+            let Some(payer_field) = find_context_field(cx, anchor_context, &payer.path) else {
+                continue;
+            };
+            if parse_account_constraints(cx, payer_field).mut_
+                || !reported.insert(payer.path.clone())
+            {
+                continue;
+            }
+
+            span_lint_and_note(
+                cx,
+                SIGNER_NOT_MUTABLE_WHEN_PAYING,
+                cx.tcx.def_span(payer_field.did),
+                format!(
+                    "`{}` pays for `{}` but is not marked `#[account(mut)]` — add `mut` so its lamports can be debited",
+                    payer.path,
+                    field.ident(cx.tcx)
+                ),
+                Some(payer.span),
+                format!("`{}` constraint is here", key),
+            );
+        }
+    }
+}
+
+fn analyze_lamport_debits<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    reported: &mut HashSet<String>,
+) {
+    let TyKind::Adt(_, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return;
+    };
+
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && detect_cpi_kind(cx, *fn_def_id) == Some(CpiKind::SystemTransfer)
+            && let Some(cpi_ctx_local) = extract_arg_local(args, 0)
+            && let Some(accounts_local) = find_cpi_accounts(mir_analyzer, bb, cpi_ctx_local)
+            && let Some(from) = extract_cpi_account(mir_analyzer, accounts_local, "from")
+            && let Some(from_field) = find_context_field(cx, anchor_context, &from)
+        {
+            let constraints = parse_account_constraints(cx, from_field);
+            let is_signer = constraints.signer
+                || is_anchor_signer_type(cx.tcx, from_field.ty(cx.tcx, generics));
+            if !is_signer || constraints.mut_ || !reported.insert(from.clone()) {
+                continue;
+            }
+
+            span_lint_and_note(
+                cx,
+                SIGNER_NOT_MUTABLE_WHEN_PAYING,
+                *fn_span,
+                format!(
+                    "`{}` is debited by this transfer but is not marked `#[account(mut)]` — add `mut` so its lamports can be debited",
+                    from
+                ),
+                Some(cx.tcx.def_span(from_field.did)),
+                "signer is declared here",
+            );
+        }
+    }
+}
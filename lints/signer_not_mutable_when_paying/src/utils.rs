@@ -0,0 +1,14 @@
+use anchor_lints_utils::utils::account_constraints::{AccountConstraints, ConstraintValue};
+
+/// The constraints naming the account that funds the field, e.g. `payer` in `payer = user`
+pub fn payer_constraints(
+    constraints: &AccountConstraints,
+) -> Vec<(&'static str, &ConstraintValue)> {
+    [
+        ("payer", constraints.payer.as_ref()),
+        ("realloc::payer", constraints.realloc_payer.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|value| (key, value)))
+    .collect()
+}
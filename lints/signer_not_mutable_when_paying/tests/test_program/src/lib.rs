@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod signer_not_mutable_when_paying_tests {
+    use super::*;
+
+    // BAD: the funding signer is not writable
+    pub fn deposit_readonly_payer(ctx: Context<DepositReadonlyPayer>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [signer_not_mutable_when_paying]
+        Ok(())
+    }
+
+    // GOOD: the funding signer is `mut`
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [safe_payer]
+        Ok(())
+    }
+
+    // GOOD: the `init` payer is `mut`
+    pub fn initialize(ctx: Context<Initialize>, value: u64) -> Result<()> {
+        ctx.accounts.state.value = value;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct DepositReadonlyPayer<'info> {
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = user, space = 8 + 8)]
+    pub state: Account<'info, State>,
+    #[account(mut)]
+    pub user: Signer<'info>, // [safe_payer]
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct State {
+    pub value: u64,
+}
@@ -0,0 +1,125 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::is_anchor_spl_token_2022_transfer_fn,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{ensure_anchor_context_initialized, extract_arg_local, should_skip_function},
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty, FieldDef},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects Token-2022 transfers (`token_2022::transfer` or `transfer_checked`, also exported
+    /// by `token_interface`) in a handler whose accounts struct declares a mint with
+    /// `extensions::transfer_hook::*` constraints, when the `CpiContext` of the transfer is not
+    /// given any remaining accounts.
+    ///
+    /// The lint is advisory: whether a mint has a transfer hook is only known from the constraints
+    /// of the accounts struct.
+    ///
+    /// ### Why is this bad?
+    /// The Token-2022 program invokes the transfer hook program of the mint on every transfer, and
+    /// the hook needs its extra accounts, e.g. its validation account. A transfer built only from
+    /// the basic accounts struct omits them, so every transfer of the mint fails at runtime.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let cpi_ctx = CpiContext::new(token_program, TransferChecked { from, mint, to, authority });
+    /// token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+    ///
+    /// // Good:
+    /// let cpi_ctx = CpiContext::new(token_program, TransferChecked { from, mint, to, authority })
+    ///     .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+    /// token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+    /// ```
+    pub TOKEN_2022_MISSING_EXTENSION_ACCOUNT_HANDLING,
+    Warn,
+    "Token-2022 transfer of a transfer hook mint without the hook accounts"
+}
+
+impl<'tcx> LateLintPass<'tcx> for Token2022MissingExtensionAccountHandling {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(TOKEN_2022_MISSING_EXTENSION_ACCOUNT_HANDLING) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(TOKEN_2022_MISSING_EXTENSION_ACCOUNT_HANDLING);
+
+        // Only handlers whose accounts struct declares a transfer hook mint are checked
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+        let Some(hook_mint) = transfer_hook_mint(cx, anchor_context) else {
+            return;
+        };
+
+        analyze_token_2022_transfers(cx, &mir_analyzer, hook_mint);
+    }
+}
+
+fn analyze_token_2022_transfers<'tcx>(
+    cx: &LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'_, 'tcx>,
+    hook_mint: &FieldDef,
+) {
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && is_anchor_spl_token_2022_transfer_fn(cx.tcx, *fn_def_id)
+            && let Some(cpi_ctx) = extract_arg_local(args, 0)
+            && !has_remaining_accounts(mir_analyzer, cpi_ctx)
+        {
+            span_lint_and_note(
+                cx,
+                TOKEN_2022_MISSING_EXTENSION_ACCOUNT_HANDLING,
+                *fn_span,
+                format!(
+                    "Token-2022 transfer without the transfer hook accounts of `{}` — pass them to the `CpiContext` with `with_remaining_accounts`",
+                    hook_mint.ident(cx.tcx)
+                ),
+                Some(cx.tcx.def_span(hook_mint.did)),
+                "the mint is declared with a transfer hook here",
+            );
+        }
+    }
+}
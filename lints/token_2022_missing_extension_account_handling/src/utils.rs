@@ -0,0 +1,49 @@
+use anchor_lints_utils::{
+    diag_items::is_anchor_cpi_context_with_remaining_accounts_fn,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::account_constraints::parse_account_constraints,
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{Local, Operand, TerminatorKind},
+    ty::{self as rustc_ty, FieldDef, TyKind},
+};
+
+/// Find the field of the accounts struct declared with `extensions::transfer_hook::*` constraints
+pub fn transfer_hook_mint<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) -> Option<&'tcx FieldDef> {
+    let TyKind::Adt(adt_def, _) = anchor_context.anchor_context_account_type.kind() else {
+        return None;
+    };
+    if !adt_def.is_struct() {
+        return None;
+    }
+    adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| parse_account_constraints(cx, field).has("extensions::transfer_hook"))
+}
+
+/// Check if the `CpiContext` passed to a CPI is built with `with_remaining_accounts`
+pub fn has_remaining_accounts(mir_analyzer: &MirAnalyzer, cpi_ctx: Local) -> bool {
+    let sources = mir_analyzer.collect_source_locals(cpi_ctx);
+    mir_analyzer.mir.basic_blocks.iter().any(|bbdata| {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && is_anchor_cpi_context_with_remaining_accounts_fn(mir_analyzer.cx.tcx, *fn_def_id)
+            && let Some(local) = destination.as_local()
+        {
+            sources.contains(&local)
+        } else {
+            false
+        }
+    })
+}
@@ -0,0 +1,91 @@
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod token_2022_missing_extension_account_handling_tests {
+    use super::*;
+
+    // BAD: the hook accounts are not passed to the transfer of a transfer hook mint
+    pub fn transfer_hook_mint(ctx: Context<HookTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals) // [token_2022_missing_extension_account_handling]
+    }
+
+    // BAD: the deprecated transfer of a transfer hook mint, without the hook accounts
+    pub fn transfer_hook_mint_unchecked(ctx: Context<HookTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = token_2022::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token_2022::transfer(cpi_ctx, amount) // [token_2022_missing_extension_account_handling]
+    }
+
+    // GOOD: the hook accounts are passed as remaining accounts
+    pub fn transfer_hook_mint_with_hook_accounts<'info>(
+        ctx: Context<'_, '_, '_, 'info, HookTransfer<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals) // [safe_transfer_hook_accounts]
+    }
+
+    // GOOD: the mint is not declared with a transfer hook
+    pub fn transfer_plain_mint(ctx: Context<PlainTransfer>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals) // [safe_transfer_hook_accounts]
+    }
+}
+
+#[derive(Accounts)]
+pub struct HookTransfer<'info> {
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        extensions::transfer_hook::authority = authority,
+        extensions::transfer_hook::program_id = hook_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: the transfer hook program of the mint
+    pub hook_program: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct PlainTransfer<'info> {
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub from: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub to: InterfaceAccount<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,195 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::{is_account_info_type, is_anchor_unchecked_account_type},
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, has_account_constraint},
+        extract_arg_local, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+mod utils;
+use utils::*;
+
+// Track token program declarations already warned about, accounts structs are shared by handlers
+thread_local! {
+    static WARNED_FIELDS: RefCell<HashSet<Span>> = RefCell::new(HashSet::new());
+}
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects token CPIs whose program id is the key of an `UncheckedAccount` or `AccountInfo`,
+    /// e.g. `CpiContext::new(ctx.accounts.token_program.key(), ..)` where `token_program` is not
+    /// declared as `Program<'info, Token>` or `Interface<'info, TokenInterface>`.
+    ///
+    /// ### Why is this bad?
+    /// Nothing checks that the untyped account is the token program, so the caller can pass any
+    /// program and have it invoked with the accounts of the CPI, e.g. a fake program that reports
+    /// a transfer as successful without moving any tokens. The typed declarations make Anchor
+    /// validate the program id for free.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     /// CHECK: token program
+    ///     pub token_program: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), accounts);
+    /// token::transfer(cpi_ctx, amount)?;
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     pub token_program: Program<'info, Token>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), accounts);
+    /// token::transfer(cpi_ctx, amount)?;
+    /// ```
+    pub TOKEN_PROGRAM_ID_FROM_ACCOUNT_INSTEAD_OF_TYPED_PROGRAM,
+    Warn,
+    "token CPI program id taken from an untyped account instead of a typed token program"
+}
+
+impl<'tcx> LateLintPass<'tcx> for TokenProgramIdFromAccountInsteadOfTypedProgram {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(TOKEN_PROGRAM_ID_FROM_ACCOUNT_INSTEAD_OF_TYPED_PROGRAM) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(TOKEN_PROGRAM_ID_FROM_ACCOUNT_INSTEAD_OF_TYPED_PROGRAM);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_token_cpi_programs(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_token_cpi_programs<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+
+        // `anchor_spl` CPIs take the program id through their `CpiContext`, the instruction
+        // builders take it as first argument
+        let (program_id_local, typed_program) =
+            if let Some(typed_program) = anchor_token_cpi_program_type(cx, *fn_def_id) {
+                let Some(program_id_local) = extract_arg_local(args, 0).and_then(|cpi_ctx_local| {
+                    find_cpi_context_program_id(mir_analyzer, bb, cpi_ctx_local)
+                }) else {
+                    continue;
+                };
+                (program_id_local, typed_program)
+            } else if let Some(typed_program) = instruction_builder_program_type(cx, *fn_def_id)
+                && let Some(program_id_local) = extract_arg_local(args, 0)
+            {
+                (program_id_local, typed_program)
+            } else {
+                continue;
+            };
+
+        let Some(program_account) =
+            mir_analyzer.extract_account_name_from_local(&program_id_local, true)
+        else {
+            continue;
+        };
+        let program_name = program_account.account_name;
+
+        // Typed programs have their id validated by Anchor
+        let Some(program_ty) = anchor_context
+            .anchor_context_arg_accounts_type
+            .get(&program_name)
+        else {
+            continue;
+        };
+        let declared_type = if is_anchor_unchecked_account_type(cx.tcx, *program_ty) {
+            "UncheckedAccount<'info>"
+        } else if is_account_info_type(cx.tcx, *program_ty) {
+            "AccountInfo<'info>"
+        } else {
+            continue;
+        };
+
+        let Some(program_field) = find_context_field(cx, anchor_context, &program_name) else {
+            continue;
+        };
+        // An `address` constraint pins the program id as well
+        if has_account_constraint(cx, program_field, "address") {
+            continue;
+        }
+
+        let field_span = cx.tcx.def_span(program_field.did);
+        if !WARNED_FIELDS.with(|warned| warned.borrow_mut().insert(field_span)) {
+            continue;
+        }
+
+        span_lint_and_note(
+            cx,
+            TOKEN_PROGRAM_ID_FROM_ACCOUNT_INSTEAD_OF_TYPED_PROGRAM,
+            field_span,
+            format!(
+                "token program `{}` is declared as `{}`, so its key is used as the program id of a token CPI unchecked — declare it as `{}` to have Anchor validate it",
+                program_name, declared_type, typed_program
+            ),
+            Some(*fn_span),
+            "the token CPI is made here",
+        );
+    }
+}
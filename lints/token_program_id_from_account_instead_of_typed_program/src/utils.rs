@@ -0,0 +1,98 @@
+use anchor_lints_utils::{
+    cpi_types::{CpiKind, detect_cpi_kind},
+    diag_items::{
+        is_anchor_cpi_context, is_anchor_spl_token_2022_transfer_fn,
+        is_spl_token_instruction_builder_fn,
+    },
+    mir_analyzer::MirAnalyzer,
+    utils::{check_locals_are_related, extract_arg_local},
+};
+
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BasicBlock, Local, Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+
+/// Typed declaration of the SPL Token program
+pub const TOKEN_PROGRAM_TYPE: &str = "Program<'info, Token>";
+/// Typed declaration of a program implementing the token interface, SPL Token or Token-2022
+pub const TOKEN_INTERFACE_TYPE: &str = "Interface<'info, TokenInterface>";
+
+/// The typed declaration to suggest for the token program of an `anchor_spl` token CPI, e.g.
+/// `anchor_spl::token::transfer`, `None` if the function is not one
+pub fn anchor_token_cpi_program_type(cx: &LateContext<'_>, def_id: DefId) -> Option<&'static str> {
+    if is_anchor_spl_token_2022_transfer_fn(cx.tcx, def_id) {
+        return Some(TOKEN_INTERFACE_TYPE);
+    }
+    match detect_cpi_kind(cx, def_id)? {
+        CpiKind::SetAuthority
+        | CpiKind::Burn
+        | CpiKind::MintTo
+        | CpiKind::Transfer
+        | CpiKind::CloseAccount
+        | CpiKind::FreezeAccount
+        | CpiKind::ThawAccount
+        | CpiKind::Approve
+        | CpiKind::Revoke
+        | CpiKind::SyncNative => Some(TOKEN_PROGRAM_TYPE),
+        _ => None,
+    }
+}
+
+/// The typed declaration to suggest for the program id passed to an SPL Token or Token-2022
+/// instruction builder, e.g. `spl_token::instruction::transfer`, `None` if the function is not one
+pub fn instruction_builder_program_type(
+    cx: &LateContext<'_>,
+    def_id: DefId,
+) -> Option<&'static str> {
+    if !is_spl_token_instruction_builder_fn(cx.tcx, def_id) {
+        return None;
+    }
+    let crate_name = cx.tcx.crate_name(def_id.krate);
+    Some(if crate_name.as_str().starts_with("spl_token_2022") {
+        TOKEN_INTERFACE_TYPE
+    } else {
+        TOKEN_PROGRAM_TYPE
+    })
+}
+
+/// Find the program id passed to the `CpiContext` constructor whose result is passed to the CPI
+/// in `cpi_block`
+pub fn find_cpi_context_program_id(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    cpi_ctx_local: Local,
+) -> Option<Local> {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            let fn_sig = mir_analyzer.cx.tcx.fn_sig(*fn_def_id).skip_binder();
+            let return_ty = fn_sig.skip_binder().output();
+
+            if is_anchor_cpi_context(mir_analyzer.cx.tcx, return_ty)
+                && let Some(destination_local) = destination.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &destination_local,
+                    &cpi_ctx_local,
+                )
+            {
+                return extract_arg_local(args, 0);
+            }
+        }
+
+        // The context must be built before the CPI consumes it
+        if cpi_block == bb {
+            break;
+        }
+    }
+    None
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{
+    self, Mint, TokenAccount as InterfaceTokenAccount, TokenInterface, TransferChecked,
+};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod token_program_id_from_account_instead_of_typed_program_tests {
+    use super::*;
+
+    // BAD: the token program is an `UncheckedAccount`
+    pub fn transfer_with_unchecked_program(
+        ctx: Context<UncheckedTokenProgram>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)
+    }
+
+    // BAD: the token program is an `AccountInfo`, used for a Token-2022 transfer
+    pub fn transfer_checked_with_account_info_program(
+        ctx: Context<AccountInfoTokenProgram>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)
+    }
+
+    // BAD: the instruction is built for the key of an `UncheckedAccount`
+    pub fn transfer_with_instruction_builder(
+        ctx: Context<BuilderTokenProgram>,
+        amount: u64,
+    ) -> Result<()> {
+        use anchor_lang::solana_program::program::invoke;
+        use anchor_spl::token::spl_token;
+
+        let instruction = spl_token::instruction::transfer(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.from.key(),
+            &ctx.accounts.to.key(),
+            &ctx.accounts.authority.key(),
+            &[],
+            amount,
+        )?;
+        invoke(
+            &instruction,
+            &[
+                ctx.accounts.from.to_account_info(),
+                ctx.accounts.to.to_account_info(),
+                ctx.accounts.authority.to_account_info(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    // GOOD: the token program is typed
+    pub fn transfer_with_typed_program(ctx: Context<TypedTokenProgram>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)
+    }
+
+    // GOOD: the token program is a typed interface
+    pub fn transfer_checked_with_interface_program(
+        ctx: Context<InterfaceTokenProgram>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.from.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)
+    }
+
+    // GOOD: the unchecked token program is pinned with `address`
+    pub fn transfer_with_address_constrained_program(
+        ctx: Context<AddressConstrainedTokenProgram>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct UncheckedTokenProgram<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: not validated
+    pub token_program: UncheckedAccount<'info>, // [token_program_id_from_account_instead_of_typed_program]
+}
+
+#[derive(Accounts)]
+pub struct AccountInfoTokenProgram<'info> {
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, InterfaceTokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub to: InterfaceAccount<'info, InterfaceTokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: not validated
+    pub token_program: AccountInfo<'info>, // [token_program_id_from_account_instead_of_typed_program]
+}
+
+#[derive(Accounts)]
+pub struct BuilderTokenProgram<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: not validated
+    pub token_program: UncheckedAccount<'info>, // [token_program_id_from_account_instead_of_typed_program]
+}
+
+#[derive(Accounts)]
+pub struct TypedTokenProgram<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>, // [safe_token_program]
+}
+
+#[derive(Accounts)]
+pub struct InterfaceTokenProgram<'info> {
+    #[account(mut)]
+    pub from: InterfaceAccount<'info, InterfaceTokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub to: InterfaceAccount<'info, InterfaceTokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>, // [safe_token_program]
+}
+
+#[derive(Accounts)]
+pub struct AddressConstrainedTokenProgram<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    /// CHECK: pinned to the SPL Token program
+    #[account(address = token::ID)]
+    pub token_program: UncheckedAccount<'info>, // [safe_token_program]
+}
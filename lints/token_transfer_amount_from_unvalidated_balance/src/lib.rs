@@ -0,0 +1,135 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    cpi_types::detect_cpi_kind,
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::should_skip_function,
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects token transfers whose `amount` is read from the `amount` field of a token account
+    /// (`Account<'info, TokenAccount>` or `InterfaceAccount<'info, TokenAccount>`) that is not
+    /// tied to the expected mint or owner by a `token::mint`, `token::authority`,
+    /// `associated_token::*`, `address`, `has_one`, `seeds` or `constraint` constraint.
+    ///
+    /// Checks performed in the handler body are not considered.
+    ///
+    /// ### Why is this bad?
+    /// The account type only proves the account is owned by the token program. An attacker can
+    /// pass any token account they control, e.g. one of a worthless mint holding a huge balance,
+    /// and have the program transfer that many tokens out of its own vault.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: any token account can be passed as `user_deposit`
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     pub user_deposit: Account<'info, TokenAccount>,
+    ///     ...
+    /// }
+    /// token::transfer(cpi_ctx, ctx.accounts.user_deposit.amount)?;
+    ///
+    /// Good: the balance is read from an account of the expected mint and owner
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     #[account(token::mint = mint, token::authority = user)]
+    ///     pub user_deposit: Account<'info, TokenAccount>,
+    ///     ...
+    /// }
+    /// ```
+    pub TOKEN_TRANSFER_AMOUNT_FROM_UNVALIDATED_BALANCE,
+    Warn,
+    "transfer amount read from the balance of an unconstrained token account"
+}
+
+impl<'tcx> LateLintPass<'tcx> for TokenTransferAmountFromUnvalidatedBalance {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(TOKEN_TRANSFER_AMOUNT_FROM_UNVALIDATED_BALANCE) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(TOKEN_TRANSFER_AMOUNT_FROM_UNVALIDATED_BALANCE);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_transfer_amounts(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_transfer_amounts<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && let Some(cpi_kind) = detect_cpi_kind(cx, *fn_def_id)
+            && let Some(amount_arg_index) = transfer_amount_arg_index(cpi_kind)
+            && let Some(amount_arg) = args.get(amount_arg_index)
+            && let Some(account_name) =
+                find_balance_source_account(mir_analyzer, &amount_arg.node, bb)
+            && let Some(token_account_field) =
+                find_token_account_field(cx, anchor_context, &account_name)
+            && !is_token_account_constrained(cx, token_account_field)
+        {
+            span_lint_and_note(
+                cx,
+                TOKEN_TRANSFER_AMOUNT_FROM_UNVALIDATED_BALANCE,
+                *fn_span,
+                format!(
+                    "transfer amount is read from the balance of `{}`, which is not constrained to the expected mint or owner — add `token::mint` and `token::authority` constraints",
+                    account_name
+                ),
+                Some(cx.tcx.def_span(token_account_field.did)),
+                "token account is declared here",
+            );
+        }
+    }
+}
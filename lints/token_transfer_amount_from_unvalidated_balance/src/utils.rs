@@ -0,0 +1,127 @@
+use anchor_lints_utils::{
+    cpi_types::CpiKind,
+    diag_items::{
+        is_anchor_account_type, is_anchor_interface_account_type, is_anchor_spl_token_account_type,
+        is_anchor_spl_token_interface_token_account_type,
+    },
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    utils::account_constraints::extract_constraint_keys,
+};
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BasicBlock, Operand, Place, ProjectionElem, Rvalue, StatementKind},
+    ty::{FieldDef, TyKind},
+};
+
+/// Constraints tying a token account to the mint or owner the instruction expects
+const VALIDATING_CONSTRAINTS: &[&str] = &[
+    "token::mint",
+    "token::authority",
+    "associated_token::mint",
+    "associated_token::authority",
+    "address",
+    "constraint",
+    "has_one",
+    "seeds",
+];
+
+/// Index of the `amount` argument of the transfer `cpi_kind`, if it moves tokens
+pub fn transfer_amount_arg_index(cpi_kind: CpiKind) -> Option<usize> {
+    match cpi_kind {
+        // `transfer(ctx, amount)`
+        CpiKind::Transfer => Some(1),
+        // `transfer(token_program_id, source, destination, authority, signers, amount)`
+        CpiKind::Token2022Transfer => Some(5),
+        // `transfer_checked(token_program_id, source, mint, destination, authority, signers, amount, decimals)`
+        CpiKind::Token2022TransferChecked => Some(6),
+        _ => None,
+    }
+}
+
+/// Find the context account whose `amount` field the transfer amount `amount_arg` is read from
+pub fn find_balance_source_account(
+    mir_analyzer: &MirAnalyzer,
+    amount_arg: &Operand,
+    transfer_block: BasicBlock,
+) -> Option<String> {
+    let (Operand::Copy(amount_place) | Operand::Move(amount_place)) = amount_arg else {
+        return None;
+    };
+
+    // `transfer(ctx, ctx.accounts.vault.amount)` passes the field directly
+    if let Some(account_name) = balance_account_name(mir_analyzer, amount_place) {
+        return Some(account_name);
+    }
+
+    let amount_sources = mir_analyzer.collect_source_locals(amount_place.as_local()?);
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (dest, Rvalue::Use(Operand::Copy(place)))) = &stmt.kind
+                && let Some(dest_local) = dest.as_local()
+                && amount_sources.contains(&dest_local)
+                && let Some(account_name) = balance_account_name(mir_analyzer, place)
+            {
+                return Some(account_name);
+            }
+        }
+
+        // The amount must be read before the transfer consumes it
+        if bb == transfer_block {
+            break;
+        }
+    }
+    None
+}
+
+// Resolve the account whose `amount` field `place` reads, e.g. `vault` for `(*_5).amount`
+fn balance_account_name(mir_analyzer: &MirAnalyzer, place: &Place) -> Option<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    if let Some((base, ProjectionElem::Field(field_idx, _))) = place.iter_projections().last()
+        && let TyKind::Adt(adt_def, _) = base.ty(mir_analyzer.mir, tcx).ty.kind()
+        && adt_def.is_struct()
+        && adt_def.non_enum_variant().fields[field_idx].name.as_str() == "amount"
+        && let Some(account) = mir_analyzer.extract_account_name_from_local(&place.local, true)
+    {
+        return account.account_name.split('.').next().map(str::to_string);
+    }
+    None
+}
+
+/// Find the token account field named `account_name` of the accounts struct
+pub fn find_token_account_field<'tcx>(
+    cx: &LateContext<'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+    account_name: &str,
+) -> Option<&'tcx FieldDef> {
+    let TyKind::Adt(adt_def, generics) = anchor_context.anchor_context_account_type.kind() else {
+        return None;
+    };
+    if !adt_def.is_struct() {
+        return None;
+    }
+    let field = adt_def
+        .non_enum_variant()
+        .fields
+        .iter()
+        .find(|field| field.ident(cx.tcx).as_str() == account_name)?;
+
+    let field_ty = field.ty(cx.tcx, generics);
+    let TyKind::Adt(_, account_generics) = field_ty.kind() else {
+        return None;
+    };
+    let is_token_account = (is_anchor_account_type(cx.tcx, field_ty)
+        || is_anchor_interface_account_type(cx.tcx, field_ty))
+        && account_generics.types().any(|inner_ty| {
+            is_anchor_spl_token_account_type(cx.tcx, inner_ty)
+                || is_anchor_spl_token_interface_token_account_type(cx.tcx, inner_ty)
+        });
+    is_token_account.then_some(field)
+}
+
+/// Check whether the token account is tied to a mint, an owner or an address by its constraints
+pub fn is_token_account_constrained<'tcx>(cx: &LateContext<'tcx>, field: &'tcx FieldDef) -> bool {
+    extract_constraint_keys(cx, field)
+        .iter()
+        .any(|key| VALIDATING_CONSTRAINTS.contains(&key.as_str()))
+}
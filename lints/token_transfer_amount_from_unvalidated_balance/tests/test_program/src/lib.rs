@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod token_transfer_amount_from_unvalidated_balance_tests {
+    use super::*;
+
+    // BAD: the balance of any token account decides how much leaves the vault
+    pub fn withdraw_unvalidated(ctx: Context<WithdrawUnvalidated>) -> Result<()> {
+        let amount = ctx.accounts.user_deposit.amount;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [token_transfer_amount_from_unvalidated_balance]
+        Ok(())
+    }
+
+    // BAD: the field is passed to the transfer directly
+    pub fn withdraw_unvalidated_inline(ctx: Context<WithdrawUnvalidated>) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, ctx.accounts.user_deposit.amount)?; // [token_transfer_amount_from_unvalidated_balance]
+        Ok(())
+    }
+
+    // GOOD: the deposit account is bound to the expected mint and owner
+    pub fn withdraw_validated(ctx: Context<WithdrawValidated>) -> Result<()> {
+        let amount = ctx.accounts.user_deposit.amount;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_transfer_amount]
+        Ok(())
+    }
+
+    // GOOD: the amount is an instruction argument, not a balance
+    pub fn withdraw_fixed(ctx: Context<WithdrawUnvalidated>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_transfer_amount]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawUnvalidated<'info> {
+    pub user: Signer<'info>,
+    pub user_deposit: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = user)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub recipient: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawValidated<'info> {
+    pub user: Signer<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(token::mint = mint, token::authority = user)]
+    pub user_deposit: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = user)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub recipient: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
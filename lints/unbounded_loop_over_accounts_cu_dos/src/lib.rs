@@ -0,0 +1,129 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note, registry::is_lint_enabled, utils::should_skip_function,
+};
+
+use clippy_utils::higher::ForLoop;
+
+use rustc_hir::{
+    Body as HirBody, Expr, FnDecl, HirId,
+    def_id::LocalDefId,
+    intravisit::{FnKind, Visitor, walk_expr},
+};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+use std::collections::HashMap;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `for` loops whose number of iterations is controlled by the caller, either
+    /// `remaining_accounts` or an instruction argument such as a count or a `Vec`, and that
+    /// perform a CPI or an account deserialization on every iteration without an explicit
+    /// maximum on the number of iterations.
+    ///
+    /// This is a heuristic, performance-focused lint: a bound whose upper limit is enforced before
+    /// the loop, e.g. `require!(ctx.remaining_accounts.len() <= MAX, ..)` or
+    /// `if count > MAX { return Err(..) }`, or an iterator capped with `take`, `min` or `clamp`,
+    /// is considered bounded. Lower limits, e.g. `require!(ctx.remaining_accounts.len() > 0, ..)`,
+    /// and checks after the loop do not bound it.
+    ///
+    /// ### Why is this bad?
+    /// CPIs and deserialization are expensive. An attacker passing enough accounts or a large
+    /// enough count makes the instruction exceed the compute budget, so it always fails — a
+    /// denial of service for every user relying on it.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: one CPI per remaining account, however many are passed
+    /// for recipient in ctx.remaining_accounts.iter() {
+    ///     system_program::transfer(cpi_ctx, amount)?;
+    /// }
+    ///
+    /// Good: the number of remaining accounts is capped
+    /// require!(ctx.remaining_accounts.len() <= MAX_RECIPIENTS, ErrorCode::TooManyRecipients);
+    /// for recipient in ctx.remaining_accounts.iter() {
+    ///     system_program::transfer(cpi_ctx, amount)?;
+    /// }
+    /// ```
+    pub UNBOUNDED_LOOP_OVER_ACCOUNTS_CU_DOS,
+    Warn,
+    "loop over an attacker-sized bound performs expensive work on every iteration"
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnboundedLoopOverAccountsCuDos {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNBOUNDED_LOOP_OVER_ACCOUNTS_CU_DOS) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let user_sized_params = collect_user_sized_params(cx, body);
+        analyze_loops(cx, body, &user_sized_params);
+    }
+}
+
+fn analyze_loops<'tcx>(
+    cx: &LateContext<'tcx>,
+    body: &'tcx HirBody<'tcx>,
+    user_sized_params: &HashMap<HirId, String>,
+) {
+    struct LoopVisitor<'a, 'tcx> {
+        cx: &'a LateContext<'tcx>,
+        user_sized_params: &'a HashMap<HirId, String>,
+    }
+
+    impl<'a, 'tcx> Visitor<'tcx> for LoopVisitor<'a, 'tcx> {
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            if let Some(for_loop) = ForLoop::hir(expr)
+                && !for_loop.span.from_expansion()
+                && let Some(bound) = attacker_controlled_bound(for_loop.arg, self.user_sized_params)
+                && !bound_is_checked(self.cx, expr, &bound)
+                && let Some((operation_span, operation)) =
+                    find_expensive_operation(self.cx, for_loop.body)
+            {
+                span_lint_and_note(
+                    self.cx,
+                    UNBOUNDED_LOOP_OVER_ACCOUNTS_CU_DOS,
+                    for_loop.arg.span,
+                    format!(
+                        "loop bounded by {} performs {} on every iteration — enforce an explicit maximum number of iterations so callers cannot exceed the compute budget",
+                        bound.describe(),
+                        operation
+                    ),
+                    Some(operation_span),
+                    "repeated on every iteration here",
+                );
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = LoopVisitor {
+        cx,
+        user_sized_params,
+    };
+    visitor.visit_expr(body.value);
+}
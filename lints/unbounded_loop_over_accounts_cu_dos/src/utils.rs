@@ -0,0 +1,315 @@
+use anchor_lints_utils::diag_items::{
+    is_anchor_account_loader_type, is_anchor_account_type, is_anchor_cpi_context,
+    is_anchor_interface_account_type, is_cpi_invoke_fn, is_deserialize_fn,
+};
+
+use clippy_utils::ty::is_type_diagnostic_item;
+
+use rustc_hir::{
+    BinOpKind, Body as HirBody, Expr, ExprKind, HirId, Node, PatKind, QPath, StmtKind, UnOp,
+    def::Res,
+    intravisit::{Visitor, walk_expr},
+};
+use rustc_lint::LateContext;
+use rustc_middle::ty::TyKind;
+use rustc_span::{Span, sym};
+
+use std::collections::HashMap;
+
+/// Methods capping the number of iterations of the iterator they are called on
+const LIMITING_METHODS: &[&str] = &["take", "min", "clamp"];
+
+/// What the number of iterations of a loop is derived from
+pub enum LoopBound {
+    /// `ctx.remaining_accounts`, sized by the caller
+    RemainingAccounts,
+    /// An instruction argument, e.g. a count or a `Vec`
+    InstructionArgument(HirId, String),
+}
+
+impl LoopBound {
+    pub fn describe(&self) -> String {
+        match self {
+            LoopBound::RemainingAccounts => "`remaining_accounts`".to_string(),
+            LoopBound::InstructionArgument(_, name) => format!("instruction argument `{}`", name),
+        }
+    }
+
+    fn is_mentioned_by(&self, facts: &ExprFacts) -> bool {
+        match self {
+            LoopBound::RemainingAccounts => facts.remaining_accounts,
+            LoopBound::InstructionArgument(hir_id, _) => facts.locals.contains(hir_id),
+        }
+    }
+}
+
+/// Facts about the sub-expressions of an expression
+#[derive(Default)]
+struct ExprFacts {
+    remaining_accounts: bool,
+    locals: Vec<HirId>,
+    limited: bool,
+}
+
+fn collect_expr_facts<'tcx>(expr: &'tcx Expr<'tcx>) -> ExprFacts {
+    struct FactsVisitor {
+        facts: ExprFacts,
+    }
+
+    impl<'tcx> Visitor<'tcx> for FactsVisitor {
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            match &expr.kind {
+                ExprKind::Field(_, ident) if ident.as_str() == "remaining_accounts" => {
+                    self.facts.remaining_accounts = true;
+                }
+                ExprKind::Path(QPath::Resolved(None, path)) => {
+                    if let Res::Local(hir_id) = path.res {
+                        self.facts.locals.push(hir_id);
+                    }
+                }
+                ExprKind::MethodCall(segment, ..)
+                    if LIMITING_METHODS.contains(&segment.ident.as_str()) =>
+                {
+                    self.facts.limited = true;
+                }
+                _ => {}
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = FactsVisitor {
+        facts: ExprFacts::default(),
+    };
+    visitor.visit_expr(expr);
+    visitor.facts
+}
+
+/// Collect the instruction arguments an attacker can size: integers and `Vec`s or slices
+pub fn collect_user_sized_params<'tcx>(
+    cx: &LateContext<'tcx>,
+    body: &HirBody<'tcx>,
+) -> HashMap<HirId, String> {
+    let mut params = HashMap::new();
+    for param in body.params {
+        if let PatKind::Binding(_, hir_id, ident, _) = param.pat.kind {
+            let ty = cx.typeck_results().pat_ty(param.pat).peel_refs();
+            if ty.is_integral() || ty.is_slice() || is_type_diagnostic_item(cx, ty, sym::Vec) {
+                params.insert(hir_id, ident.to_string());
+            }
+        }
+    }
+    params
+}
+
+/// Determine the attacker-controlled bound of a `for` loop iterating over `arg`, if any.
+/// Iterators capped with `take`, `min` or `clamp` are bounded.
+pub fn attacker_controlled_bound<'tcx>(
+    arg: &'tcx Expr<'tcx>,
+    user_sized_params: &HashMap<HirId, String>,
+) -> Option<LoopBound> {
+    let facts = collect_expr_facts(arg);
+    if facts.limited {
+        return None;
+    }
+    if facts.remaining_accounts {
+        return Some(LoopBound::RemainingAccounts);
+    }
+    facts.locals.iter().find_map(|hir_id| {
+        user_sized_params
+            .get(hir_id)
+            .map(|name| LoopBound::InstructionArgument(*hir_id, name.clone()))
+    })
+}
+
+/// Check whether an upper limit on the bound is enforced before `loop_expr` runs: a statement
+/// preceding the loop rejects bounds over the limit, e.g.
+/// `require!(ctx.remaining_accounts.len() <= MAX_ACCOUNTS, ..)` or
+/// `if count > MAX_COUNT { return Err(..) }`, or the loop only runs within the limit, e.g.
+/// `if count <= MAX_COUNT { for .. }`
+pub fn bound_is_checked<'tcx>(
+    cx: &LateContext<'tcx>,
+    loop_expr: &'tcx Expr<'tcx>,
+    bound: &LoopBound,
+) -> bool {
+    let mut child = loop_expr.hir_id;
+    for (parent_id, node) in cx.tcx.hir_parent_iter(loop_expr.hir_id) {
+        match node {
+            // The tail expression of a block follows all of its statements
+            Node::Block(block)
+                if block
+                    .stmts
+                    .iter()
+                    .take_while(|stmt| stmt.hir_id != child)
+                    .any(|stmt| {
+                        matches!(stmt.kind, StmtKind::Expr(expr) | StmtKind::Semi(expr)
+                            if rejects_exceeded_bound(expr, bound))
+                    }) =>
+            {
+                return true;
+            }
+            Node::Expr(Expr {
+                kind: ExprKind::If(cond, then, _),
+                ..
+            }) if then.hir_id == child && is_upper_bound_check(cond, bound) => return true,
+            Node::Item(_) | Node::TraitItem(_) | Node::ImplItem(_) => break,
+            _ => {}
+        }
+        child = parent_id;
+    }
+    false
+}
+
+/// Check if `expr` returns early when the bound is over a limit, e.g. the expansion of
+/// `require!(bound <= LIMIT, ..)` or `require_gte!(LIMIT, bound, ..)`
+fn rejects_exceeded_bound<'tcx>(expr: &'tcx Expr<'tcx>, bound: &LoopBound) -> bool {
+    matches!(expr.kind, ExprKind::If(cond, then, None)
+        if is_exceeded_check(cond, bound) && contains_return(then))
+}
+
+/// Check if `cond` only holds when the bound is under a limit, e.g. `bound <= LIMIT` or
+/// `bound > 0 && bound < LIMIT`
+fn is_upper_bound_check(cond: &Expr<'_>, bound: &LoopBound) -> bool {
+    match peel_drop_temps(cond).kind {
+        ExprKind::Binary(op, lhs, rhs) if op.node == BinOpKind::And => {
+            is_upper_bound_check(lhs, bound) || is_upper_bound_check(rhs, bound)
+        }
+        ExprKind::Unary(UnOp::Not, inner) => is_exceeded_check(inner, bound),
+        ExprKind::Binary(op, lhs, rhs) => match op.node {
+            BinOpKind::Lt | BinOpKind::Le => compares_bound_to_limit(lhs, rhs, bound),
+            BinOpKind::Gt | BinOpKind::Ge => compares_bound_to_limit(rhs, lhs, bound),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check if `cond` holds whenever the bound is over a limit, e.g. `bound > LIMIT` or
+/// `bound == 0 || bound > LIMIT`
+fn is_exceeded_check(cond: &Expr<'_>, bound: &LoopBound) -> bool {
+    match peel_drop_temps(cond).kind {
+        ExprKind::Binary(op, lhs, rhs) if op.node == BinOpKind::Or => {
+            is_exceeded_check(lhs, bound) || is_exceeded_check(rhs, bound)
+        }
+        ExprKind::Unary(UnOp::Not, inner) => is_upper_bound_check(inner, bound),
+        ExprKind::Binary(op, lhs, rhs) => match op.node {
+            BinOpKind::Gt | BinOpKind::Ge => compares_bound_to_limit(lhs, rhs, bound),
+            BinOpKind::Lt | BinOpKind::Le => compares_bound_to_limit(rhs, lhs, bound),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Check if `smaller` is derived from the bound and `larger` is a limit independent of it
+fn compares_bound_to_limit(smaller: &Expr<'_>, larger: &Expr<'_>, bound: &LoopBound) -> bool {
+    bound.is_mentioned_by(&collect_expr_facts(smaller))
+        && !bound.is_mentioned_by(&collect_expr_facts(larger))
+}
+
+fn peel_drop_temps<'tcx>(mut expr: &'tcx Expr<'tcx>) -> &'tcx Expr<'tcx> {
+    while let ExprKind::DropTemps(inner) = expr.kind {
+        expr = inner;
+    }
+    expr
+}
+
+fn contains_return<'tcx>(expr: &'tcx Expr<'tcx>) -> bool {
+    struct ReturnFinder {
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for ReturnFinder {
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            if matches!(expr.kind, ExprKind::Ret(_)) {
+                self.found = true;
+                return;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = ReturnFinder { found: false };
+    visitor.visit_expr(expr);
+    visitor.found
+}
+
+/// Find the first operation in `loop_body` that is expensive enough to exhaust the compute
+/// budget when repeated: a CPI or an account deserialization
+pub fn find_expensive_operation<'tcx>(
+    cx: &LateContext<'tcx>,
+    loop_body: &'tcx Expr<'tcx>,
+) -> Option<(Span, &'static str)> {
+    struct ExpensiveOperationFinder<'a, 'tcx> {
+        cx: &'a LateContext<'tcx>,
+        found: Option<(Span, &'static str)>,
+    }
+
+    impl<'a, 'tcx> Visitor<'tcx> for ExpensiveOperationFinder<'a, 'tcx> {
+        fn visit_expr(&mut self, expr: &'tcx Expr<'tcx>) {
+            if self.found.is_some() {
+                return;
+            }
+            if let Some(operation) = expensive_operation_kind(self.cx, expr) {
+                self.found = Some((expr.span, operation));
+                return;
+            }
+            walk_expr(self, expr);
+        }
+    }
+
+    let mut visitor = ExpensiveOperationFinder { cx, found: None };
+    visitor.visit_expr(loop_body);
+    visitor.found
+}
+
+fn expensive_operation_kind<'tcx>(
+    cx: &LateContext<'tcx>,
+    expr: &'tcx Expr<'tcx>,
+) -> Option<&'static str> {
+    let (fn_def_id, args) = match &expr.kind {
+        ExprKind::Call(func, args) => {
+            let ExprKind::Path(qpath) = &func.kind else {
+                return None;
+            };
+            (cx.qpath_res(qpath, func.hir_id).opt_def_id()?, *args)
+        }
+        ExprKind::MethodCall(_, _, args, _) => (
+            cx.typeck_results().type_dependent_def_id(expr.hir_id)?,
+            *args,
+        ),
+        _ => return None,
+    };
+    let tcx = cx.tcx;
+
+    if is_cpi_invoke_fn(tcx, fn_def_id)
+        || args
+            .iter()
+            .any(|arg| is_anchor_cpi_context(tcx, cx.typeck_results().expr_ty(arg)))
+    {
+        return Some("a CPI");
+    }
+
+    let fn_name = tcx.opt_item_name(fn_def_id)?;
+    if is_deserialize_fn(tcx, fn_def_id)
+        || (fn_name.as_str() == "try_from" && returns_anchor_account(cx, expr))
+    {
+        return Some("an account deserialization");
+    }
+
+    None
+}
+
+// `Account::try_from` and friends deserialize the account data
+fn returns_anchor_account<'tcx>(cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) -> bool {
+    let ty = cx.typeck_results().expr_ty(expr);
+    if let TyKind::Adt(_, generics) = ty.kind()
+        && is_type_diagnostic_item(cx, ty, sym::Result)
+        && let Some(ok_ty) = generics.types().next()
+    {
+        return is_anchor_account_type(cx.tcx, ok_ty)
+            || is_anchor_account_loader_type(cx.tcx, ok_ty)
+            || is_anchor_interface_account_type(cx.tcx, ok_ty);
+    }
+    false
+}
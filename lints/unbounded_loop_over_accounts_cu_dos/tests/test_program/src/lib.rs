@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+const MAX_RECIPIENTS: usize = 8;
+const MAX_VAULTS: u64 = 8;
+
+#[program]
+pub mod unbounded_loop_over_accounts_cu_dos_tests {
+    use super::*;
+
+    // BAD: one CPI per remaining account, however many the caller passes
+    pub fn distribute_unbounded<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        for recipient in ctx.remaining_accounts.iter() { // [unbounded_loop_over_accounts_cu_dos]
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: recipient.clone(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+        Ok(())
+    }
+
+    // GOOD: the number of remaining accounts is capped before the loop
+    pub fn distribute_bounded<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_RECIPIENTS,
+            CustomError::TooManyAccounts
+        );
+        for recipient in ctx.remaining_accounts.iter() { // [safe_loop_over_accounts]
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: recipient.clone(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+        Ok(())
+    }
+
+    // BAD: requiring at least one remaining account does not cap how many are passed
+    pub fn distribute_non_empty<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(ctx.remaining_accounts.len() > 0, CustomError::NoAccounts);
+        for recipient in ctx.remaining_accounts.iter() { // [unbounded_loop_over_accounts_cu_dos]
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: recipient.clone(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+            system_program::transfer(cpi_ctx, amount)?;
+        }
+        Ok(())
+    }
+
+    // GOOD: only cheap work per remaining account
+    pub fn sum_lamports<'info>(ctx: Context<'_, '_, 'info, 'info, SumVaults<'info>>) -> Result<()> {
+        let mut total: u64 = 0;
+        for account in ctx.remaining_accounts.iter() { // [safe_loop_over_accounts]
+            total = total.saturating_add(account.lamports());
+        }
+        msg!("total lamports: {}", total);
+        Ok(())
+    }
+
+    // BAD: the caller picks how many accounts are deserialized
+    pub fn sum_vaults_unbounded<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumVaults<'info>>,
+        count: u64,
+    ) -> Result<()> {
+        let mut total: u64 = 0;
+        for i in 0..count { // [unbounded_loop_over_accounts_cu_dos]
+            let data = ctx.remaining_accounts[i as usize].try_borrow_data()?;
+            let vault = Vault::try_deserialize(&mut &data[..])?;
+            total = total.saturating_add(vault.amount);
+        }
+        msg!("total: {}", total);
+        Ok(())
+    }
+
+    // BAD: the count is only checked once every vault was deserialized
+    pub fn sum_vaults_checked_late<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumVaults<'info>>,
+        count: u64,
+    ) -> Result<()> {
+        let mut total: u64 = 0;
+        for i in 0..count { // [unbounded_loop_over_accounts_cu_dos]
+            let data = ctx.remaining_accounts[i as usize].try_borrow_data()?;
+            let vault = Vault::try_deserialize(&mut &data[..])?;
+            total = total.saturating_add(vault.amount);
+        }
+        require!(count <= MAX_VAULTS, CustomError::TooManyAccounts);
+        msg!("total: {}", total);
+        Ok(())
+    }
+
+    // GOOD: counts over the limit are rejected before the loop
+    pub fn sum_vaults_rejecting_large_counts<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumVaults<'info>>,
+        count: u64,
+    ) -> Result<()> {
+        if count > MAX_VAULTS {
+            return err!(CustomError::TooManyAccounts);
+        }
+        let mut total: u64 = 0;
+        for i in 0..count { // [safe_loop_over_accounts]
+            let data = ctx.remaining_accounts[i as usize].try_borrow_data()?;
+            let vault = Vault::try_deserialize(&mut &data[..])?;
+            total = total.saturating_add(vault.amount);
+        }
+        msg!("total: {}", total);
+        Ok(())
+    }
+
+    // GOOD: the caller-supplied count is capped
+    pub fn sum_vaults_bounded<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumVaults<'info>>,
+        count: u64,
+    ) -> Result<()> {
+        let mut total: u64 = 0;
+        for i in 0..count.min(MAX_VAULTS) { // [safe_loop_over_accounts]
+            let data = ctx.remaining_accounts[i as usize].try_borrow_data()?;
+            let vault = Vault::try_deserialize(&mut &data[..])?;
+            total = total.saturating_add(vault.amount);
+        }
+        msg!("total: {}", total);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SumVaults<'info> {
+    pub payer: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Too many accounts")]
+    TooManyAccounts,
+    #[msg("No accounts")]
+    NoAccounts,
+}
@@ -0,0 +1,175 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_ast;
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    cpi_types::detect_cpi_kind,
+    diag_items::{is_account_info_type, is_anchor_unchecked_account_type},
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, has_account_constraint},
+        extract_arg_local,
+        pda_detection::is_pda_account,
+        should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects token CPIs whose `authority` is an `UncheckedAccount` or `AccountInfo` that is
+    /// neither a signer (`#[account(signer)]`) nor a PDA (`seeds` constraint) signed for with
+    /// `CpiContext::new_with_signer` using matching seeds.
+    ///
+    /// ### Why is this bad?
+    /// The token program only checks that the authority signed the outer transaction or was
+    /// signed for by the invoking program. An authority that is validated neither way lets the
+    /// instruction operate on whatever account the caller passes in, and the intended
+    /// authorization check is effectively missing from the program.
+    ///
+    /// ### Example
+    /// ```rust
+    /// Bad: `authority` is never validated
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     /// CHECK: unchecked
+    ///     pub authority: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new(token_program, Transfer { from, to, authority });
+    /// token::transfer(cpi_ctx, amount)?;
+    ///
+    /// Good: `authority` is a PDA of this program and signed for with its seeds
+    /// #[derive(Accounts)]
+    /// pub struct Withdraw<'info> {
+    ///     /// CHECK: PDA authority
+    ///     #[account(seeds = [b"vault"], bump)]
+    ///     pub authority: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new_with_signer(token_program, accounts, &[&[b"vault", &[bump]]]);
+    /// token::transfer(cpi_ctx, amount)?;
+    /// ```
+    pub UNCHECKED_ACCOUNT_USED_AS_AUTHORITY_WITHOUT_SIGNER_OR_PDA,
+    Warn,
+    "unchecked account used as a token CPI authority without signer or PDA validation"
+}
+
+impl<'tcx> LateLintPass<'tcx> for UncheckedAccountUsedAsAuthorityWithoutSignerOrPda {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNCHECKED_ACCOUNT_USED_AS_AUTHORITY_WITHOUT_SIGNER_OR_PDA) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(UNCHECKED_ACCOUNT_USED_AS_AUTHORITY_WITHOUT_SIGNER_OR_PDA);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_cpi_authorities(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_cpi_authorities<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind()
+            && let Some(cpi_kind) = detect_cpi_kind(cx, *fn_def_id)
+            && let Some(authority_field) = token_cpi_authority_field(cpi_kind)
+            && let Some(cpi_ctx_local) = extract_arg_local(args, 0)
+            && let Some(cpi_context) = find_cpi_context(mir_analyzer, bb, cpi_ctx_local)
+            && let Some(authority) =
+                extract_cpi_authority(mir_analyzer, cpi_context.accounts_local, authority_field)
+        {
+            // Typed accounts are validated by their own wrapper
+            let Some(authority_ty) = anchor_context
+                .anchor_context_arg_accounts_type
+                .get(&authority)
+            else {
+                continue;
+            };
+            if !is_anchor_unchecked_account_type(cx.tcx, *authority_ty)
+                && !is_account_info_type(cx.tcx, *authority_ty)
+            {
+                continue;
+            }
+
+            let Some(authority_field_def) = find_context_field(cx, anchor_context, &authority)
+            else {
+                continue;
+            };
+
+            if has_account_constraint(cx, authority_field_def, "signer") {
+                continue;
+            }
+
+            // A PDA of this program is validated by its seeds and signed for by the program
+            let is_seeded_pda = is_pda_account(cx, authority_field_def)
+                .is_some_and(|pda_signer| pda_signer.has_seeds);
+            if is_seeded_pda
+                && let Some(signer_seeds_span) = cpi_context.signer_seeds_span
+                && signer_seeds_match_constraint(cx, authority_field_def, signer_seeds_span)
+            {
+                continue;
+            }
+
+            span_lint_and_note(
+                cx,
+                UNCHECKED_ACCOUNT_USED_AS_AUTHORITY_WITHOUT_SIGNER_OR_PDA,
+                *fn_span,
+                format!(
+                    "account `{}` is used as the authority of this token CPI but is neither a signer nor a PDA signed via `new_with_signer` — add `#[account(signer)]` or derive it from `seeds` and sign with the same seeds",
+                    authority
+                ),
+                Some(cx.tcx.def_span(authority_field_def.did)),
+                "authority account is declared here",
+            );
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use anchor_lints_utils::{
+    cpi_types::CpiKind,
+    diag_items::is_anchor_cpi_context,
+    mir_analyzer::MirAnalyzer,
+    utils::{
+        account_constraints::parse_account_constraints, check_cpi_call_is_new_with_signer,
+        check_locals_are_related, extract_arg_local,
+    },
+};
+
+use clippy_utils::source::HasSession;
+
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{AggregateKind, BasicBlock, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    ty::{self as rustc_ty, FieldDef},
+};
+use rustc_span::Span;
+
+/// The `CpiContext` constructor a token CPI was called with
+pub struct CpiContextInfo {
+    pub accounts_local: Local,
+    /// Span of the signer seeds when built with `CpiContext::new_with_signer`
+    pub signer_seeds_span: Option<Span>,
+}
+
+/// Name of the authority field in the accounts struct of an `anchor_spl::token` CPI
+pub fn token_cpi_authority_field(cpi_kind: CpiKind) -> Option<&'static str> {
+    match cpi_kind {
+        CpiKind::Transfer
+        | CpiKind::MintTo
+        | CpiKind::Burn
+        | CpiKind::CloseAccount
+        | CpiKind::FreezeAccount
+        | CpiKind::ThawAccount
+        | CpiKind::Approve
+        | CpiKind::Revoke => Some("authority"),
+        CpiKind::SetAuthority => Some("current_authority"),
+        _ => None,
+    }
+}
+
+/// Find the `CpiContext` constructor whose result is passed to the CPI in `cpi_block`
+pub fn find_cpi_context(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    cpi_ctx_local: Local,
+) -> Option<CpiContextInfo> {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            let fn_sig = mir_analyzer.cx.tcx.fn_sig(*fn_def_id).skip_binder();
+            let return_ty = fn_sig.skip_binder().output();
+
+            if is_anchor_cpi_context(mir_analyzer.cx.tcx, return_ty)
+                && let Some(accounts_local) = extract_arg_local(args, 1)
+                && let Some(destination_local) = destination.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &destination_local,
+                    &cpi_ctx_local,
+                )
+            {
+                let signer_seeds_span =
+                    check_cpi_call_is_new_with_signer(mir_analyzer, args, *fn_def_id)
+                        .then(|| args[2].span);
+                return Some(CpiContextInfo {
+                    accounts_local,
+                    signer_seeds_span,
+                });
+            }
+        }
+
+        // The context must be built before the CPI consumes it
+        if cpi_block == bb {
+            break;
+        }
+    }
+    None
+}
+
+/// Resolve the context account passed as `authority_field` in the CPI accounts struct
+pub fn extract_cpi_authority(
+    mir_analyzer: &MirAnalyzer,
+    cpi_accounts_local: Local,
+    authority_field: &str,
+) -> Option<String> {
+    let tcx = mir_analyzer.cx.tcx;
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        for stmt in &bbdata.statements {
+            if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                && let Rvalue::Aggregate(box AggregateKind::Adt(adt_def_id, _, _, _, _), fields) =
+                    rvalue
+                && let Some(account_struct_local) = place.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &account_struct_local,
+                    &cpi_accounts_local,
+                )
+            {
+                let adt_def = tcx.adt_def(*adt_def_id);
+                if !adt_def.is_struct() {
+                    continue;
+                }
+                let field_index = adt_def
+                    .non_enum_variant()
+                    .fields
+                    .iter()
+                    .position(|f| f.ident(tcx).as_str() == authority_field)?;
+
+                if let Some(Operand::Copy(p) | Operand::Move(p)) = fields.iter().nth(field_index)
+                    && let Some(local) = p.as_local()
+                    && let Some(account_info) =
+                        mir_analyzer.extract_account_name_from_local(&local, true)
+                {
+                    return Some(account_info.account_name);
+                }
+                return None;
+            }
+        }
+    }
+    None
+}
+
+/// Collect the string literals of the `seeds` constraint, e.g. `vault` for `seeds = [b"vault", ..]`
+fn extract_seed_literals<'tcx>(cx: &LateContext<'tcx>, field: &FieldDef) -> Vec<String> {
+    let Some(seeds) = parse_account_constraints(cx, field).seeds else {
+        return Vec::new();
+    };
+    seeds
+        .seeds
+        .iter()
+        .flat_map(|seed| &seed.tokens)
+        .filter_map(|token| {
+            if let rustc_ast::tokenstream::TokenTree::Token(token, _) = token
+                && let rustc_ast::token::TokenKind::Literal(lit) = token.kind
+                && matches!(
+                    lit.kind,
+                    rustc_ast::token::LitKind::ByteStr | rustc_ast::token::LitKind::Str
+                )
+            {
+                Some(lit.symbol.to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Check whether the signer seeds passed to `new_with_signer` contain the string literals of the
+/// account's `seeds` constraint. Seeds that cannot be read from source, e.g. a local variable,
+/// are assumed to match.
+pub fn signer_seeds_match_constraint<'tcx>(
+    cx: &LateContext<'tcx>,
+    field: &FieldDef,
+    signer_seeds_span: Span,
+) -> bool {
+    let Ok(snippet) = cx.sess().source_map().span_to_snippet(signer_seeds_span) else {
+        return true;
+    };
+
+    // Every odd chunk between quotes is the content of a string literal
+    let signer_literals: Vec<&str> = snippet.split('"').skip(1).step_by(2).collect();
+    if signer_literals.is_empty() {
+        return true;
+    }
+
+    extract_seed_literals(cx, field)
+        .iter()
+        .all(|literal| signer_literals.contains(&literal.as_str()))
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod unchecked_account_used_as_authority_without_signer_or_pda_tests {
+    use super::*;
+
+    // GOOD: authority is a `Signer`
+    pub fn transfer_with_signer_authority(
+        ctx: Context<SignerAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [safe_cpi_authority]
+        Ok(())
+    }
+
+    // GOOD: authority is a PDA signed for with its seeds
+    pub fn transfer_with_pda_authority(ctx: Context<PdaAuthority>, amount: u64) -> Result<()> {
+        let bump = ctx.bumps.vault_authority;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.key(),
+            cpi_accounts,
+            &[&[b"vault_authority".as_ref(), &[bump]]],
+        );
+        token::transfer(cpi_ctx, amount)?; // [safe_cpi_authority]
+        Ok(())
+    }
+
+    // BAD: plain unchecked authority, neither a signer nor a PDA
+    pub fn transfer_with_unchecked_authority(
+        ctx: Context<UncheckedAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?; // [unchecked_account_used_as_authority_without_signer_or_pda]
+        Ok(())
+    }
+
+    // BAD: PDA authority signed for with seeds that do not derive it
+    pub fn transfer_with_mismatched_pda_seeds(
+        ctx: Context<PdaAuthority>,
+        amount: u64,
+    ) -> Result<()> {
+        let bump = ctx.bumps.vault_authority;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.key(),
+            cpi_accounts,
+            &[&[b"treasury".as_ref(), &[bump]]],
+        );
+        token::transfer(cpi_ctx, amount)?; // [unchecked_account_used_as_authority_without_signer_or_pda]
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SignerAuthority<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct PdaAuthority<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: PDA authority of the vault, signed for by this program
+    #[account(seeds = [b"vault_authority"], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UncheckedAuthority<'info> {
+    #[account(mut)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    /// CHECK: not validated
+    pub authority: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
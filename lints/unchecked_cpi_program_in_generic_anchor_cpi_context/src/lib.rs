@@ -0,0 +1,185 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diag_items::{
+        is_account_info_type, is_anchor_generated_cpi_fn, is_anchor_unchecked_account_type,
+    },
+    diagnostics::span_lint_and_note,
+    mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
+    utils::{
+        account_constraints::{find_context_field, parse_account_constraints},
+        extract_arg_local, should_skip_function,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects calls to the `cpi` module Anchor generates for a program, e.g.
+    /// `other_program::cpi::deposit(CpiContext::new(ctx.accounts.other_program.key(), ..))`, where
+    /// the program account is an `UncheckedAccount` or `AccountInfo` that nothing constrains.
+    ///
+    /// ### Why is this bad?
+    /// The generated instruction invokes whatever program its `CpiContext` holds, so the caller
+    /// picks the program that receives the accounts and the signer seeds of the CPI. Declaring the
+    /// account as `Program<'info, OtherProgram>` makes Anchor check it is the intended program.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// #[derive(Accounts)]
+    /// pub struct Forward<'info> {
+    ///     /// CHECK: target program
+    ///     pub other_program: UncheckedAccount<'info>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new(ctx.accounts.other_program.key(), accounts);
+    /// other_program::cpi::deposit(cpi_ctx, amount)?;
+    ///
+    /// // Good:
+    /// #[derive(Accounts)]
+    /// pub struct Forward<'info> {
+    ///     pub other_program: Program<'info, OtherProgram>,
+    ///     // ...
+    /// }
+    /// let cpi_ctx = CpiContext::new(ctx.accounts.other_program.key(), accounts);
+    /// other_program::cpi::deposit(cpi_ctx, amount)?;
+    /// ```
+    pub UNCHECKED_CPI_PROGRAM_IN_GENERIC_ANCHOR_CPI_CONTEXT,
+    Warn,
+    "generated Anchor CPI invoked with an unconstrained program account"
+}
+
+impl<'tcx> LateLintPass<'tcx> for UncheckedCpiProgramInGenericAnchorCpiContext {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNCHECKED_CPI_PROGRAM_IN_GENERIC_ANCHOR_CPI_CONTEXT) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mut mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+
+        // Update anchor context info with accounts
+        anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(UNCHECKED_CPI_PROGRAM_IN_GENERIC_ANCHOR_CPI_CONTEXT);
+
+        // Analyze functions that take Anchor context
+        let Some(anchor_context) = mir_analyzer.anchor_context_info.as_ref() else {
+            return;
+        };
+
+        analyze_generated_cpi_calls(cx, &mir_analyzer, anchor_context);
+    }
+}
+
+fn analyze_generated_cpi_calls<'cx, 'tcx>(
+    cx: &'cx LateContext<'tcx>,
+    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
+    anchor_context: &AnchorContextInfo<'tcx>,
+) {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+        if fn_span.from_expansion() || !is_anchor_generated_cpi_fn(cx.tcx, *fn_def_id) {
+            continue;
+        }
+
+        // The generated instruction invokes the program its `CpiContext` is built with
+        let Some(program_local) = extract_arg_local(args, 0)
+            .and_then(|cpi_ctx_local| find_cpi_context_program(mir_analyzer, bb, cpi_ctx_local))
+        else {
+            continue;
+        };
+        let Some(program_account) =
+            mir_analyzer.extract_account_name_from_local(&program_local, true)
+        else {
+            continue;
+        };
+        let program_name = program_account.account_name;
+
+        // Typed programs have their id validated by Anchor
+        let Some(program_ty) = anchor_context
+            .anchor_context_arg_accounts_type
+            .get(&program_name)
+        else {
+            continue;
+        };
+        let declared_type = if is_anchor_unchecked_account_type(cx.tcx, *program_ty) {
+            "UncheckedAccount<'info>"
+        } else if is_account_info_type(cx.tcx, *program_ty) {
+            "AccountInfo<'info>"
+        } else {
+            continue;
+        };
+
+        let Some(program_field) = find_context_field(cx, anchor_context, &program_name) else {
+            continue;
+        };
+        // `address`, `owner`, `seeds` or `constraint` tie the account to a known program
+        let constraints = parse_account_constraints(cx, program_field);
+        if constraints.address.is_some()
+            || constraints.owner.is_some()
+            || constraints.seeds.is_some()
+            || !constraints.constraints.is_empty()
+        {
+            continue;
+        }
+        // So does comparing its key before the CPI, e.g. with `require_keys_eq!`
+        if is_program_key_checked_before(mir_analyzer, &program_name, bb) {
+            continue;
+        }
+
+        span_lint_and_note(
+            cx,
+            UNCHECKED_CPI_PROGRAM_IN_GENERIC_ANCHOR_CPI_CONTEXT,
+            *fn_span,
+            format!(
+                "`{}` is invoked with `{}`, an unconstrained `{}`, so the caller chooses the program of the CPI — declare it as a `Program<'info, ..>` of the target program",
+                generated_cpi_fn_name(cx, *fn_def_id),
+                program_name,
+                declared_type
+            ),
+            Some(cx.tcx.def_span(program_field.did)),
+            "the program account is declared here",
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lints_utils::{
+    cfg::reachable,
+    diag_items::is_anchor_cpi_context,
+    mir_analyzer::MirAnalyzer,
+    utils::{check_locals_are_related, extract_arg_local},
+};
+
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{BasicBlock, Local, Operand, TerminatorKind},
+    ty::{self as rustc_ty},
+};
+use rustc_span::sym;
+
+/// Name of a generated CPI instruction as written by the caller, e.g. `other_program::cpi::deposit`
+pub fn generated_cpi_fn_name(cx: &LateContext<'_>, def_id: DefId) -> String {
+    let instruction = cx
+        .tcx
+        .opt_item_name(def_id)
+        .map_or_else(String::new, |name| name.to_string());
+    format!("{}::cpi::{}", cx.tcx.crate_name(def_id.krate), instruction)
+}
+
+/// Find the program passed to the `CpiContext` constructor whose result is passed to the CPI in
+/// `cpi_block`
+pub fn find_cpi_context_program(
+    mir_analyzer: &MirAnalyzer,
+    cpi_block: BasicBlock,
+    cpi_ctx_local: Local,
+) -> Option<Local> {
+    for (bb, bbdata) in mir_analyzer.mir.basic_blocks.iter_enumerated() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func_const),
+            args,
+            destination,
+            ..
+        } = &bbdata.terminator().kind
+            && let rustc_ty::FnDef(fn_def_id, _) = func_const.ty().kind()
+        {
+            let fn_sig = mir_analyzer.cx.tcx.fn_sig(*fn_def_id).skip_binder();
+            let return_ty = fn_sig.skip_binder().output();
+
+            if is_anchor_cpi_context(mir_analyzer.cx.tcx, return_ty)
+                && let Some(destination_local) = destination.as_local()
+                && check_locals_are_related(
+                    &mir_analyzer.reverse_assignment_map,
+                    &destination_local,
+                    &cpi_ctx_local,
+                )
+            {
+                return extract_arg_local(args, 0);
+            }
+        }
+
+        // The context must be built before the CPI consumes it
+        if cpi_block == bb {
+            break;
+        }
+    }
+    None
+}
+
+/// Check if the key of the account `program_name` is compared before the CPI in `cpi_block`
+pub fn is_program_key_checked_before(
+    mir_analyzer: &MirAnalyzer,
+    program_name: &str,
+    cpi_block: BasicBlock,
+) -> bool {
+    let tcx = mir_analyzer.cx.tcx;
+    let is_program_key = |local: Local| {
+        mir_analyzer
+            .extract_account_name_from_local(&local, true)
+            .is_some_and(|account| account.account_name == program_name)
+    };
+    mir_analyzer
+        .mir
+        .basic_blocks
+        .iter_enumerated()
+        .any(|(bb, bbdata)| {
+            let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                ..
+            } = &bbdata.terminator().kind
+            else {
+                return false;
+            };
+            let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+                return false;
+            };
+            (tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
+                || tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id))
+                && mir_analyzer
+                    .args_as_pubkey_locals(args)
+                    .is_some_and(|(lhs, rhs)| is_program_key(lhs) || is_program_key(rhs))
+                && bb != cpi_block
+                && reachable(&mir_analyzer.mir.basic_blocks, bb, |node| node == cpi_block).is_some()
+        })
+}
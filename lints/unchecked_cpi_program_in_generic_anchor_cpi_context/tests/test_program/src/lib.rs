@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+// The handlers forward to `record` through the `cpi` module `#[program]` generates, the same
+// module another program depending on this one with the `cpi` feature would call
+#[program]
+pub mod unchecked_cpi_program_in_generic_anchor_cpi_context_tests {
+    use super::*;
+
+    // Target instruction of the CPIs
+    pub fn record(ctx: Context<Record>, value: u64) -> Result<()> {
+        ctx.accounts.counter.value = value;
+        Ok(())
+    }
+
+    // BAD: the target program is an `UncheckedAccount`
+    pub fn forward_with_unchecked_program(
+        ctx: Context<UncheckedTargetProgram>,
+        value: u64,
+    ) -> Result<()> {
+        let cpi_accounts = crate::cpi::accounts::Record {
+            counter: ctx.accounts.counter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.target_program.key(), cpi_accounts);
+        crate::cpi::record(cpi_ctx, value) // [unchecked_cpi_program_in_generic_anchor_cpi_context]
+    }
+
+    // BAD: the target program is an `AccountInfo`
+    pub fn forward_with_account_info_program(
+        ctx: Context<AccountInfoTargetProgram>,
+        value: u64,
+    ) -> Result<()> {
+        let cpi_accounts = crate::cpi::accounts::Record {
+            counter: ctx.accounts.counter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.target_program.key(), cpi_accounts);
+        crate::cpi::record(cpi_ctx, value) // [unchecked_cpi_program_in_generic_anchor_cpi_context: note=the program account is declared here]
+    }
+
+    // GOOD: the target program is typed
+    pub fn forward_with_typed_program(ctx: Context<TypedTargetProgram>, value: u64) -> Result<()> {
+        let cpi_accounts = crate::cpi::accounts::Record {
+            counter: ctx.accounts.counter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.target_program.key(), cpi_accounts);
+        crate::cpi::record(cpi_ctx, value) // [safe_generated_cpi]
+    }
+
+    // GOOD: the unchecked target program is pinned with `address`
+    pub fn forward_with_address_constrained_program(
+        ctx: Context<AddressConstrainedTargetProgram>,
+        value: u64,
+    ) -> Result<()> {
+        let cpi_accounts = crate::cpi::accounts::Record {
+            counter: ctx.accounts.counter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.target_program.key(), cpi_accounts);
+        crate::cpi::record(cpi_ctx, value) // [safe_generated_cpi]
+    }
+
+    // GOOD: the key of the unchecked target program is checked before the CPI
+    pub fn forward_with_checked_program_key(
+        ctx: Context<UncheckedTargetProgram>,
+        value: u64,
+    ) -> Result<()> {
+        require_keys_eq!(ctx.accounts.target_program.key(), crate::ID);
+        let cpi_accounts = crate::cpi::accounts::Record {
+            counter: ctx.accounts.counter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.target_program.key(), cpi_accounts);
+        crate::cpi::record(cpi_ctx, value) // [safe_generated_cpi]
+    }
+}
+
+#[account]
+pub struct Counter {
+    pub value: u64,
+}
+
+#[derive(Accounts)]
+pub struct Record<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[derive(Accounts)]
+pub struct UncheckedTargetProgram<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+    /// CHECK: not validated
+    pub target_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AccountInfoTargetProgram<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+    /// CHECK: not validated
+    pub target_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TypedTargetProgram<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+    pub target_program:
+        Program<'info, crate::program::UncheckedCpiProgramInGenericAnchorCpiContextTests>,
+}
+
+#[derive(Accounts)]
+pub struct AddressConstrainedTargetProgram<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+    /// CHECK: pinned to the target program
+    #[account(address = crate::ID)]
+    pub target_program: UncheckedAccount<'info>,
+}
@@ -0,0 +1,165 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+use anchor_lints_utils::{
+    diagnostics::span_lint_and_note,
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::{
+        extract_arg_local, seed_list_bump, seed_lists, seeds_match, should_skip_function,
+        signed_cpi_seeds,
+    },
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_middle::{
+    mir::{Operand, TerminatorKind},
+    ty as rustc_ty,
+};
+use rustc_span::Span;
+
+mod utils;
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `Pubkey::find_program_address` calls whose returned bump is discarded, e.g.
+    /// `let (pda, _) = ...`, while a CPI is signed for the same seeds with a bump taken from
+    /// somewhere else.
+    ///
+    /// ### Why is this bad?
+    /// The program checks the account against the canonical address derived with the canonical
+    /// bump, but signs with another bump. If the two bumps differ, the signature is for another
+    /// address than the one that was checked, so the CPI fails or signs for the wrong PDA.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let (vault, _) = Pubkey::find_program_address(&[b"vault", user.as_ref()], ctx.program_id);
+    /// require_keys_eq!(ctx.accounts.vault.key(), vault);
+    /// let seeds: &[&[u8]] = &[b"vault", user.as_ref(), &[bump]];
+    ///
+    /// // Good:
+    /// let (vault, vault_bump) = Pubkey::find_program_address(&[b"vault", user.as_ref()], ctx.program_id);
+    /// require_keys_eq!(ctx.accounts.vault.key(), vault);
+    /// let seeds: &[&[u8]] = &[b"vault", user.as_ref(), &[vault_bump]];
+    /// ```
+    pub UNCHECKED_PDA_CANONICAL_BUMP_IN_FIND_PROGRAM_ADDRESS_MISMATCH,
+    Warn,
+    "bump of `find_program_address` discarded while the same seeds sign with another bump"
+}
+
+impl<'tcx> LateLintPass<'tcx> for UncheckedPdaCanonicalBumpInFindProgramAddressMismatch {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNCHECKED_PDA_CANONICAL_BUMP_IN_FIND_PROGRAM_ADDRESS_MISMATCH) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(UNCHECKED_PDA_CANONICAL_BUMP_IN_FIND_PROGRAM_ADDRESS_MISMATCH);
+
+        analyze_discarded_bumps(cx, &mir_analyzer);
+    }
+}
+
+/// A `find_program_address` call whose bump is never read
+struct DiscardedBumpDerivation {
+    span: Span,
+    seeds: Vec<String>,
+}
+
+/// A CPI signed with a list of seeds ending with a bump
+struct SignedCpi {
+    span: Span,
+    seeds: Vec<String>,
+}
+
+fn analyze_discarded_bumps<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    let mir = mir_analyzer.mir;
+    let mut derivations: Vec<DiscardedBumpDerivation> = Vec::new();
+    let mut signed_cpis: Vec<SignedCpi> = Vec::new();
+
+    for bbdata in mir.basic_blocks.iter() {
+        let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            destination,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+        else {
+            continue;
+        };
+        let rustc_ty::FnDef(fn_def_id, _) = func.ty().kind() else {
+            continue;
+        };
+
+        if is_find_program_address(cx, *fn_def_id) {
+            if let Some(result) = destination.as_local()
+                && is_bump_discarded(mir, result)
+                && let Some(seeds_arg) = extract_arg_local(args, 0)
+            {
+                derivations.extend(
+                    seed_lists(mir_analyzer, seeds_arg)
+                        .into_iter()
+                        .filter_map(|seed_list| seed_sources(mir_analyzer, seed_list))
+                        .map(|seeds| DiscardedBumpDerivation {
+                            span: *fn_span,
+                            seeds,
+                        }),
+                );
+            }
+        } else if let Some(signer_seeds) = signed_cpi_seeds(mir_analyzer, *fn_def_id, args) {
+            for seed_list in seed_lists(mir_analyzer, signer_seeds) {
+                if seed_list_bump(mir_analyzer, seed_list).is_none() {
+                    continue;
+                }
+                // The bump ends the list, the other seeds are those passed to the derivation
+                if let Some(mut seeds) = seed_sources(mir_analyzer, seed_list) {
+                    seeds.pop();
+                    signed_cpis.push(SignedCpi {
+                        span: *fn_span,
+                        seeds,
+                    });
+                }
+            }
+        }
+    }
+
+    for derivation in derivations {
+        let Some(signed_cpi) = signed_cpis
+            .iter()
+            .find(|signed_cpi| seeds_match(&signed_cpi.seeds, &derivation.seeds))
+        else {
+            continue;
+        };
+        span_lint_and_note(
+            cx,
+            UNCHECKED_PDA_CANONICAL_BUMP_IN_FIND_PROGRAM_ADDRESS_MISMATCH,
+            derivation.span,
+            "the canonical bump returned by `find_program_address` is discarded while the same seeds sign a CPI with another bump — sign with the returned bump",
+            Some(signed_cpi.span),
+            "the seeds are signed with a separately sourced bump here",
+        );
+    }
+}
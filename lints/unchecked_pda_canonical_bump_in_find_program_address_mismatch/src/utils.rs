@@ -0,0 +1,105 @@
+use anchor_lints_utils::{mir_analyzer::MirAnalyzer, utils::array_operands};
+
+use rustc_hir::def_id::DefId;
+use rustc_lint::LateContext;
+use rustc_middle::{
+    mir::{
+        Body as MirBody, Local, Location, Operand, Place, ProjectionElem, StatementKind,
+        TerminatorKind,
+        visit::{PlaceContext, Visitor},
+    },
+    ty::{TyKind, UintTy},
+};
+use rustc_span::Span;
+
+/// Check if `fn_def_id` is `Pubkey::find_program_address`, returning the address and its bump
+pub fn is_find_program_address<'tcx>(cx: &LateContext<'tcx>, fn_def_id: DefId) -> bool {
+    let named_find_program_address = cx
+        .tcx
+        .opt_item_name(fn_def_id)
+        .is_some_and(|name| name.as_str() == "find_program_address");
+    if !named_find_program_address {
+        return false;
+    }
+    let return_ty = cx
+        .tcx
+        .fn_sig(fn_def_id)
+        .skip_binder()
+        .skip_binder()
+        .output();
+    matches!(return_ty.kind(), TyKind::Tuple(fields)
+        if fields.len() == 2 && matches!(fields[1].kind(), TyKind::Uint(UintTy::U8)))
+}
+
+/// Check if the bump of a `find_program_address` result is never read, e.g. `let (pda, _) = ...`
+pub fn is_bump_discarded(mir: &MirBody, result: Local) -> bool {
+    struct BumpReads {
+        result: Local,
+        read: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for BumpReads {
+        fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, _: Location) {
+            // Only `result.0`, the address, may be read
+            let reads_address = matches!(
+                place.projection.as_slice(),
+                [ProjectionElem::Field(field, _)] if field.index() == 0
+            );
+            if place.local == self.result
+                && matches!(context, PlaceContext::NonMutatingUse(_))
+                && !reads_address
+            {
+                self.read = true;
+            }
+        }
+    }
+
+    let mut visitor = BumpReads {
+        result,
+        read: false,
+    };
+    visitor.visit_body(mir);
+    !visitor.read
+}
+
+/// Get the source of each seed of a list, without whitespace, e.g. `b"vault"` and
+/// `user.key().as_ref()`. Seeds are compared by source since both lists build their own copies.
+pub fn seed_sources(mir_analyzer: &MirAnalyzer, seed_list: Local) -> Option<Vec<String>> {
+    let source_map = mir_analyzer.cx.tcx.sess.source_map();
+    array_operands(mir_analyzer.mir, seed_list)?
+        .iter()
+        .map(|seed| {
+            let span = match seed {
+                Operand::Constant(constant) => constant.span,
+                Operand::Copy(place) | Operand::Move(place) => {
+                    definition_span(mir_analyzer.mir, place.as_local()?)?
+                }
+            };
+            let source = source_map.span_to_snippet(span).ok()?;
+            Some(source.chars().filter(|c| !c.is_whitespace()).collect())
+        })
+        .collect()
+}
+
+/// Span of the expression a local is assigned from, by a statement or as the result of a call
+fn definition_span(mir: &MirBody, local: Local) -> Option<Span> {
+    mir.basic_blocks.iter().find_map(|bbdata| {
+        bbdata
+            .statements
+            .iter()
+            .find_map(|stmt| match &stmt.kind {
+                StatementKind::Assign(box (place, _)) if place.as_local() == Some(local) => {
+                    Some(stmt.source_info.span)
+                }
+                _ => None,
+            })
+            .or_else(|| match &bbdata.terminator().kind {
+                TerminatorKind::Call {
+                    destination,
+                    fn_span,
+                    ..
+                } if destination.as_local() == Some(local) => Some(*fn_span),
+                _ => None,
+            })
+    })
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod unchecked_pda_canonical_bump_in_find_program_address_mismatch_tests {
+    use super::*;
+
+    // BAD: the derived bump is discarded and the vault signs with a caller supplied bump
+    pub fn withdraw_with_argument_bump(ctx: Context<Withdraw>, bump: u8, amount: u64) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", user_key.as_ref()], ctx.program_id); // [unchecked_pda_canonical_bump_in_find_program_address_mismatch]
+        require_keys_eq!(ctx.accounts.vault.key(), vault);
+
+        let seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // BAD: the derived bump is discarded and the vault signs with the bump stored in state
+    pub fn withdraw_with_stored_bump(ctx: Context<WithdrawWithState>, amount: u64) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", user_key.as_ref()], ctx.program_id); // [unchecked_pda_canonical_bump_in_find_program_address_mismatch]
+        require_keys_eq!(ctx.accounts.vault.key(), vault);
+
+        let stored_bump = ctx.accounts.state.vault_bump;
+        let seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[stored_bump]];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // GOOD: the vault signs with the derived bump
+    pub fn withdraw_with_derived_bump(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        let (vault, vault_bump) = Pubkey::find_program_address(&[b"vault", user_key.as_ref()], ctx.program_id); // [safe_derived_bump]
+        require_keys_eq!(ctx.accounts.vault.key(), vault);
+
+        let seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[vault_bump]];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, amount)
+    }
+
+    // GOOD: the discarded derivation is for other seeds than the signer
+    pub fn withdraw_other_seeds(ctx: Context<Withdraw>, bump: u8, amount: u64) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        let (receipt, _) = Pubkey::find_program_address(&[b"receipt", user_key.as_ref()], ctx.program_id); // [safe_derived_bump]
+        msg!("receipt {}", receipt);
+
+        let seeds: &[&[u8]] = &[b"vault", user_key.as_ref(), &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.key(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.user.to_account_info(),
+            },
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, amount)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithState<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub vault: SystemAccount<'info>,
+    pub state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct VaultState {
+    pub vault_bump: u8,
+}
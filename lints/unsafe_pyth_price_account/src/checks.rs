@@ -2,11 +2,10 @@ extern crate rustc_hir;
 extern crate rustc_middle;
 extern crate rustc_span;
 
-use std::collections::HashSet;
-
 use anchor_lints_utils::{
     diag_items::{
         is_anchor_account_type, is_pyth_get_price_no_older_than_fn, is_pyth_price_update_v2_type,
+        is_solana_pubkey_type,
     },
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
     utils::extract_arg_local,
@@ -14,11 +13,17 @@ use anchor_lints_utils::{
 use rustc_hir::def_id::DefId;
 use rustc_lint::LateContext;
 use rustc_middle::{
-    mir::{Local, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind},
+    mir::{
+        ConstOperand, ConstValue, Operand, Place, ProjectionElem, Rvalue, StatementKind,
+        TerminatorKind,
+        interpret::{GlobalAlloc, Scalar},
+    },
     ty::{self as rustc_ty, Ty, TyKind},
 };
 use rustc_span::{source_map::Spanned, sym};
 
+use crate::config::canonical_feed_addresses;
+
 /// Check account type is Account<PriceUpdateV2>
 pub fn is_account_price_update_v2<'tcx>(cx: &LateContext<'tcx>, ty: Ty<'tcx>) -> bool {
     let ty = ty.peel_refs();
@@ -60,7 +65,8 @@ pub fn extract_price_account_from_args<'cx, 'tcx>(
     None
 }
 
-/// Check if account key is compared
+/// Check if account key is compared, against one of the configured canonical feed addresses if
+/// any are configured
 pub fn has_pubkey_constant_check<'cx, 'tcx>(
     cx: &LateContext<'tcx>,
     mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
@@ -78,20 +84,32 @@ pub fn has_pubkey_constant_check<'cx, 'tcx>(
             && (cx.tcx.is_diagnostic_item(sym::cmp_partialeq_eq, *fn_def_id)
                 || cx.tcx.is_diagnostic_item(sym::cmp_partialeq_ne, *fn_def_id))
         {
-            for index in 0..args.len() {
-                if let Some(local) = extract_arg_local(args, index) {
-                    let compared_account_name =
-                        mir_analyzer.extract_account_name_from_local(&local, true);
-
-                    if let Some(compared_account_name) = compared_account_name
-                        && (compared_account_name.account_name == account_name
+            let compares_account = (0..args.len()).any(|index| {
+                extract_arg_local(args, index)
+                    .and_then(|local| mir_analyzer.extract_account_name_from_local(&local, true))
+                    .is_some_and(|compared_account_name| {
+                        compared_account_name.account_name == account_name
                             || compared_account_name
                                 .account_name
-                                .starts_with(&format!("{}.", account_name)))
-                    {
-                        return true;
-                    }
-                }
+                                .starts_with(&format!("{}.", account_name))
+                    })
+            });
+            if !compares_account {
+                continue;
+            }
+
+            let Some(canonical_addresses) = canonical_feed_addresses() else {
+                return true;
+            };
+            if args.iter().any(|arg| {
+                compared_constants(mir_analyzer, &arg.node)
+                    .into_iter()
+                    .any(|constant| {
+                        pubkey_constant_bytes(cx, constant)
+                            .is_some_and(|address| canonical_addresses.contains(&address))
+                    })
+            }) {
+                return true;
             }
         }
     }
@@ -99,6 +117,67 @@ pub fn has_pubkey_constant_check<'cx, 'tcx>(
     false
 }
 
+/// The constants an operand of a comparison may hold, either directly or through the locals it
+/// is copied or borrowed from
+fn compared_constants<'a, 'tcx>(
+    mir_analyzer: &'a MirAnalyzer<'_, 'tcx>,
+    operand: &'a Operand<'tcx>,
+) -> Vec<&'a ConstOperand<'tcx>> {
+    let place = match operand {
+        Operand::Constant(constant) => return vec![&**constant],
+        Operand::Copy(place) | Operand::Move(place) => place,
+    };
+    let sources = mir_analyzer.collect_source_locals(place.local);
+    mir_analyzer
+        .mir
+        .basic_blocks
+        .iter()
+        .flat_map(|bbdata| bbdata.statements.iter())
+        .filter_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box (dest, Rvalue::Use(Operand::Constant(constant))))
+                if sources.contains(&dest.local) =>
+            {
+                Some(&**constant)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Evaluate a `Pubkey` constant, or a reference to one, to the bytes of the key
+fn pubkey_constant_bytes<'tcx>(
+    cx: &LateContext<'tcx>,
+    constant: &ConstOperand<'tcx>,
+) -> Option<[u8; 32]> {
+    if !is_solana_pubkey_type(cx.tcx, constant.ty().peel_refs()) {
+        return None;
+    }
+    let value = constant
+        .const_
+        .eval(cx.tcx, cx.typing_env(), constant.span)
+        .ok()?;
+    let (alloc_id, offset) = match value {
+        ConstValue::Indirect { alloc_id, offset } => (alloc_id, offset),
+        ConstValue::Scalar(Scalar::Ptr(pointer, _)) => {
+            let (provenance, offset) = pointer.prov_and_relative_offset();
+            (provenance.alloc_id(), offset)
+        }
+        _ => return None,
+    };
+    let GlobalAlloc::Memory(allocation) = cx.tcx.global_alloc(alloc_id) else {
+        return None;
+    };
+    let allocation = allocation.inner();
+    let start = offset.bytes_usize();
+    if start + 32 > allocation.size().bytes_usize() {
+        return None;
+    }
+    allocation
+        .inspect_with_uninit_and_ptr_outside_interpreter(start..start + 32)
+        .try_into()
+        .ok()
+}
+
 fn extract_field_path(place: &Place<'_>) -> Vec<usize> {
     let mut path = Vec::new();
     for proj in place.projection.iter() {
@@ -193,51 +272,6 @@ fn is_state_last_publish_time<'cx, 'tcx>(
     false
 }
 
-/// Check if a local transitively represents a value from the tracked locals set
-fn is_tracked_local<'cx, 'tcx>(
-    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
-    local: Local,
-    tracked_locals: &HashSet<Local>,
-) -> bool {
-    // Direct check
-    if tracked_locals.contains(&local) {
-        return true;
-    }
-
-    // Check transitive assignments
-    if let Some(sources) = mir_analyzer.transitive_assignment_reverse_map.get(&local) {
-        for &src_local in sources {
-            if tracked_locals.contains(&src_local) {
-                return true;
-            }
-        }
-    }
-
-    false
-}
-
-/// Classify a place as publish_time or last_publish_time (direct field access or via tracked locals)
-fn classify_place_for_comparison<'cx, 'tcx>(
-    cx: &LateContext<'tcx>,
-    mir_analyzer: &MirAnalyzer<'cx, 'tcx>,
-    place: &Place<'tcx>,
-    account_name: &str,
-    publish_time_locals: &HashSet<Local>,
-    last_publish_time_locals: &HashSet<Local>,
-) -> (bool, bool) {
-    if let Some(local) = place.as_local() {
-        (
-            is_tracked_local(mir_analyzer, local, publish_time_locals),
-            is_tracked_local(mir_analyzer, local, last_publish_time_locals),
-        )
-    } else {
-        (
-            is_price_publish_time(cx, mir_analyzer, place, account_name),
-            is_state_last_publish_time(mir_analyzer, place),
-        )
-    }
-}
-
 /// Check if publish_time is stored and compared for monotonicity
 pub fn has_monotonic_publish_time_enforcement<'cx, 'tcx>(
     cx: &LateContext<'tcx>,
@@ -245,97 +279,11 @@ pub fn has_monotonic_publish_time_enforcement<'cx, 'tcx>(
     account_name: &str,
     _anchor_context_info: &AnchorContextInfo<'tcx>,
 ) -> bool {
-    let mir = mir_analyzer.mir;
-    let mut has_publish_time_comparison = false;
-    let mut has_publish_time_store = false;
-
-    let mut publish_time_locals = HashSet::new();
-    let mut last_publish_time_locals = HashSet::new();
-
-    // Identify publish_time and state account field locals
-    for (_bb, bbdata) in mir.basic_blocks.iter_enumerated() {
-        for stmt in &bbdata.statements {
-            if let StatementKind::Assign(box (dest_place, rvalue)) = &stmt.kind {
-                // Check for publish_time field access
-                if let Rvalue::Use(Operand::Copy(src) | Operand::Move(src)) = rvalue {
-                    if is_price_publish_time(cx, mir_analyzer, src, account_name)
-                        && let Some(dest_local) = dest_place.as_local()
-                    {
-                        publish_time_locals.insert(dest_local);
-                    }
-
-                    // Check for state account field access
-                    if is_state_last_publish_time(mir_analyzer, src)
-                        && let Some(dest_local) = dest_place.as_local()
-                    {
-                        last_publish_time_locals.insert(dest_local);
-                    }
-                }
-
-                // Check for assignments that copy publish_time or state account field
-                if let Rvalue::Use(Operand::Copy(src_place) | Operand::Move(src_place)) = rvalue
-                    && let Some(dest_local) = dest_place.as_local()
-                    && let Some(src_local) = src_place.as_local()
-                {
-                    if is_tracked_local(mir_analyzer, src_local, &publish_time_locals) {
-                        publish_time_locals.insert(dest_local);
-                    }
-                    if is_tracked_local(mir_analyzer, src_local, &last_publish_time_locals) {
-                        last_publish_time_locals.insert(dest_local);
-                    }
-                }
-
-                if let Rvalue::Use(Operand::Copy(src_place) | Operand::Move(src_place)) = rvalue
-                    && is_state_last_publish_time(mir_analyzer, dest_place)
-                    && let Some(src_local) = src_place.as_local()
-                    && is_tracked_local(mir_analyzer, src_local, &publish_time_locals)
-                {
-                    has_publish_time_store = true;
-                }
-                // Check for comparison: publish_time > state account field
-                if let Rvalue::BinaryOp(op, box (left, right)) = rvalue
-                    && matches!(
-                        op,
-                        rustc_middle::mir::BinOp::Gt
-                            | rustc_middle::mir::BinOp::Lt
-                            | rustc_middle::mir::BinOp::Ge
-                            | rustc_middle::mir::BinOp::Le
-                    )
-                    && let (
-                        Operand::Copy(left_place) | Operand::Move(left_place),
-                        Operand::Copy(right_place) | Operand::Move(right_place),
-                    ) = (left, right)
-                {
-                    let (lhs_is_publish_time, lhs_is_last_publish_time) =
-                        classify_place_for_comparison(
-                            cx,
-                            mir_analyzer,
-                            left_place,
-                            account_name,
-                            &publish_time_locals,
-                            &last_publish_time_locals,
-                        );
-
-                    let (rhs_is_publish_time, rhs_is_last_publish_time) =
-                        classify_place_for_comparison(
-                            cx,
-                            mir_analyzer,
-                            right_place,
-                            account_name,
-                            &publish_time_locals,
-                            &last_publish_time_locals,
-                        );
-
-                    // Comparison must involve one publish_time and one state account field
-                    if (lhs_is_publish_time && rhs_is_last_publish_time)
-                        || (rhs_is_publish_time && lhs_is_last_publish_time)
-                    {
-                        has_publish_time_comparison = true;
-                    }
-                }
-            }
-        }
-    }
     // Both comparison and storage are required for monotonicity enforcement
-    has_publish_time_comparison && has_publish_time_store
+    mir_analyzer
+        .detects_monotonic_store_and_compare(
+            |place| is_price_publish_time(cx, mir_analyzer, place, account_name),
+            |place| is_state_last_publish_time(mir_analyzer, place),
+        )
+        .is_complete()
 }
@@ -0,0 +1,19 @@
+use anchor_lints_utils::config::{config, decode_pubkey};
+
+use std::sync::LazyLock;
+
+/// Addresses configured by `canonical_feed_addresses` in the `[unsafe_pyth_price_account]` table
+/// of the [configuration](anchor_lints_utils::config), validated when it is loaded
+static CANONICAL_FEED_ADDRESSES: LazyLock<Vec<[u8; 32]>> = LazyLock::new(|| {
+    config()
+        .unsafe_pyth_price_account
+        .canonical_feed_addresses
+        .iter()
+        .filter_map(|address| decode_pubkey(address))
+        .collect()
+});
+
+/// The configured canonical feed addresses, `None` if any constant is accepted
+pub fn canonical_feed_addresses() -> Option<&'static [[u8; 32]]> {
+    (!CANONICAL_FEED_ADDRESSES.is_empty()).then_some(CANONICAL_FEED_ADDRESSES.as_slice())
+}
@@ -7,12 +7,13 @@ extern crate rustc_middle;
 extern crate rustc_span;
 
 use anchor_lints_utils::{
+    diagnostics::span_lint,
     mir_analyzer::{AnchorContextInfo, MirAnalyzer},
+    registry::is_lint_enabled,
     utils::pda_detection::is_pda_account,
 };
 
 use anchor_lints_utils::utils::should_skip_function;
-use clippy_utils::diagnostics::span_lint;
 use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
 use rustc_lint::{LateContext, LateLintPass};
 use rustc_middle::{
@@ -24,6 +25,7 @@ use rustc_span::{DUMMY_SP, Span, sym};
 use std::collections::HashMap;
 
 mod checks;
+mod config;
 use checks::*;
 
 #[derive(Default)]
@@ -90,6 +92,11 @@ impl<'tcx> LateLintPass<'tcx> for UnsafePythPriceAccount {
         main_fn_span: Span,
         def_id: LocalDefId,
     ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNSAFE_PYTH_PRICE_ACCOUNT) {
+            return;
+        }
+
         // Skip macro expansions, unsatisfiable predicates, and test files
         if should_skip_function(cx, main_fn_span, def_id) {
             return;
@@ -99,6 +106,7 @@ impl<'tcx> LateLintPass<'tcx> for UnsafePythPriceAccount {
 
         // Update anchor context info with accounts
         anchor_lints_utils::utils::ensure_anchor_context_initialized(&mut mir_analyzer, body);
+        mir_analyzer.debug_dump(UNSAFE_PYTH_PRICE_ACCOUNT);
 
         // Analyze functions that take Anchor context
         let Some(anchor_context_info) = mir_analyzer.anchor_context_info.as_ref() else {
@@ -234,13 +242,18 @@ fn analyze_unsafe_pyth_price_accounts<'cx, 'tcx>(
                 && !usage.has_monotonic_publish_time
             {
                 let span = usage.get_price_span.unwrap_or(usage.account_span);
+                let canonical_source = if config::canonical_feed_addresses().is_some() {
+                    "one of the configured `canonical_feed_addresses`"
+                } else {
+                    "a known canonical feed address"
+                };
                 span_lint(
                     cx,
                     UNSAFE_PYTH_PRICE_ACCOUNT,
                     span,
                     format!(
-                        "Pyth PriceUpdateV2 account `{}` is used without canonical source validation or monotonic publish time enforcement. Consider comparing the account's pubkey against a known canonical feed address, or storing and enforcing monotonicity on publish_time.",
-                        account_name
+                        "Pyth PriceUpdateV2 account `{}` is used without canonical source validation or monotonic publish time enforcement. Consider comparing the account's pubkey against {}, or storing and enforcing monotonicity on publish_time.",
+                        account_name, canonical_source
                     ),
                 );
             }
@@ -4,7 +4,9 @@ use pyth_solana_receiver_sdk::price_update::{FeedId, PriceUpdateV2, get_feed_id_
 declare_id!("Test111111111111111111111111111111111111111");
 
 // In real code, this would be a constant canonical feed address
-const CANONICAL_FEED_ADDRESS: Pubkey = anchor_lang::pubkey!("11111111111111111111111111111111");
+const CANONICAL_FEED_ADDRESS: Pubkey = anchor_lang::pubkey!("7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE");
+// A constant that is not listed in `canonical_feed_addresses`
+const DEPRECATED_FEED_ADDRESS: Pubkey = anchor_lang::pubkey!("Gnt27xtC473ZT2Mw5u8wZ68Z3gULkSTb5DuxJy7eJotD");
 
 pub const MAXIMUM_AGE: u64 = 60; // One minute
 pub const FEED_ID: &str = "0xef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d"; // SOL/USD price feed id from https://pyth.network/developers/price-feed-ids
@@ -24,7 +26,7 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
 pub fn bad_price_usage(ctx: Context<BadPriceUsage>) -> Result<()> {
     // unsafe_account_accessed
     let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
-    let price = ctx.accounts.price_account.get_price_no_older_than(  // [unsafe_account_accessed]
+    let price = ctx.accounts.price_account.get_price_no_older_than(  // [unsafe_account_accessed] [unsafe_account_accessed_with_canonical_feed_config]
         &ctx.accounts.clock,
         60,
         &feed_id,
@@ -38,7 +40,37 @@ pub fn good_price_usage_with_key_check(ctx: Context<GoodPriceUsageWithKeyCheck>)
     require_keys_eq!(ctx.accounts.price_account.key(), CANONICAL_FEED_ADDRESS);
     // safe_account_accessed
     let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
-    let price = ctx.accounts.price_account.get_price_no_older_than(  // [safe_account_accessed]
+    let price = ctx.accounts.price_account.get_price_no_older_than(  // [safe_account_accessed] [safe_account_accessed_with_canonical_feed_config]
+        &ctx.accounts.clock,
+        60,
+        &feed_id,
+    )?;
+    msg!("Price: {}", price.price);
+    Ok(())
+}
+
+// Good with `canonical_feed_addresses` configured: the canonical address is compared through a local
+pub fn good_price_usage_with_key_check_via_local(ctx: Context<GoodPriceUsageWithKeyCheck>) -> Result<()> {
+    let expected_feed = CANONICAL_FEED_ADDRESS;
+    if ctx.accounts.price_account.key() != expected_feed {
+        return err!(ErrorCode::UnexpectedFeed);
+    }
+    let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
+    let price = ctx.accounts.price_account.get_price_no_older_than(  // [safe_account_accessed] [safe_account_accessed_with_canonical_feed_config]
+        &ctx.accounts.clock,
+        60,
+        &feed_id,
+    )?;
+    msg!("Price: {}", price.price);
+    Ok(())
+}
+
+// Good by default, bad with `canonical_feed_addresses` configured: the key is compared against a
+// constant that is not a configured canonical feed
+pub fn price_usage_with_deprecated_feed_check(ctx: Context<GoodPriceUsageWithKeyCheck>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.price_account.key(), DEPRECATED_FEED_ADDRESS);
+    let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
+    let price = ctx.accounts.price_account.get_price_no_older_than(  // [safe_account_accessed] [unsafe_account_accessed_with_canonical_feed_config]
         &ctx.accounts.clock,
         60,
         &feed_id,
@@ -170,6 +202,46 @@ pub fn incomplete_monotonicity_store_only(
     Ok(())
 }
 
+// case: Incomplete monotonicity: checks publish_time but never stores it
+pub fn incomplete_monotonicity_compare_only(
+    ctx: Context<IncompleteMonotonicityCompareOnly>,
+) -> Result<()> {
+    let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
+    let price = ctx.accounts.price_account.get_price_no_older_than( // [unsafe_account_accessed]
+        &ctx.accounts.clock,
+        MAXIMUM_AGE,
+        &feed_id,
+    )?;
+
+    require!(
+        ctx.accounts.price_account.price_message.publish_time > ctx.accounts.state.last_publish_time,
+        ErrorCode::StalePrice
+    );
+
+    msg!("Price: {}", price.price);
+    Ok(())
+}
+
+// case: Complete monotonicity through locals: compares and stores publish_time
+pub fn complete_monotonicity_via_locals(
+    ctx: Context<CompleteMonotonicityViaLocals>,
+) -> Result<()> {
+    let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
+    let price = ctx.accounts.price_account.get_price_no_older_than( // [safe_account_accessed]
+        &ctx.accounts.clock,
+        MAXIMUM_AGE,
+        &feed_id,
+    )?;
+
+    let publish_time = ctx.accounts.price_account.price_message.publish_time;
+    let last_publish_time = ctx.accounts.state.last_publish_time;
+    require!(publish_time > last_publish_time, ErrorCode::StalePrice);
+    ctx.accounts.state.last_publish_time = publish_time;
+
+    msg!("Price: {}", price.price);
+    Ok(())
+}
+
 // Attack scenario: Replay attack - accepts older price after newer one
 pub fn replay_attack_scenario(ctx: Context<ReplayAttackScenario>) -> Result<()> {
     let feed_id = get_feed_id_from_hex(FEED_ID).unwrap();
@@ -331,6 +403,23 @@ pub struct IncompleteMonotonicityStoreOnly<'info> {
     pub clock: Sysvar<'info, Clock>,
 }
 
+// Incomplete: Checks publish_time but doesn't store it
+#[derive(Accounts)]
+pub struct IncompleteMonotonicityCompareOnly<'info> {
+    pub price_account: Account<'info, PriceUpdateV2>,
+    pub state: Account<'info, PriceState>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Complete: Checks and stores publish_time through locals
+#[derive(Accounts)]
+pub struct CompleteMonotonicityViaLocals<'info> {
+    pub price_account: Account<'info, PriceUpdateV2>,
+    #[account(mut)]
+    pub state: Account<'info, PriceState>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
 // Attack scenario: Replay attack vulnerability
 #[derive(Accounts)]
 pub struct ReplayAttackScenario<'info> {
@@ -342,4 +431,6 @@ pub struct ReplayAttackScenario<'info> {
 pub enum ErrorCode {
     #[msg("Stale price")]
     StalePrice,
+    #[msg("Unexpected price feed")]
+    UnexpectedFeed,
 }
@@ -0,0 +1,116 @@
+#![feature(rustc_private)]
+#![warn(unused_extern_crates)]
+#![feature(box_patterns)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_span;
+
+mod utils;
+
+use anchor_lints_utils::{
+    findings::{Finding, emit_finding},
+    mir_analyzer::MirAnalyzer,
+    registry::is_lint_enabled,
+    utils::should_skip_function,
+};
+
+use rustc_hir::{Body as HirBody, FnDecl, def_id::LocalDefId, intravisit::FnKind};
+use rustc_lint::{LateContext, LateLintPass};
+use rustc_span::Span;
+
+use utils::*;
+
+dylint_linting::declare_late_lint! {
+    /// ### What it does
+    /// Detects `.unwrap()` and `.expect()` in instruction handlers on the result of borrowing an
+    /// account's data, or of decoding values derived from account data or instruction input,
+    /// e.g. `Pubkey::try_from(&data[8..40]).unwrap()`.
+    ///
+    /// ### Why is this bad?
+    /// Account data and instruction input are controlled by the caller. Unwrapping their
+    /// decoding panics on malformed input, aborting the transaction without a meaningful
+    /// error, and usually signals missing error handling.
+    ///
+    /// ### Example
+    /// ```rust
+    /// // Bad:
+    /// let data = ctx.accounts.config.try_borrow_data().unwrap();
+    /// let admin = Pubkey::try_from(&data[8..40]).unwrap();
+    ///
+    /// // Good:
+    /// let data = ctx.accounts.config.try_borrow_data()?;
+    /// let admin = Pubkey::try_from(&data[8..40]).map_err(|_| error!(CustomError::InvalidConfig))?;
+    /// ```
+    pub UNSAFE_UNWRAP_ON_ACCOUNT_DESERIALIZATION,
+    Warn,
+    "`unwrap`/`expect` on a value decoded from account data or instruction input"
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnsafeUnwrapOnAccountDeserialization {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'tcx>,
+        _kind: FnKind<'tcx>,
+        _: &'tcx FnDecl<'tcx>,
+        body: &'tcx HirBody<'tcx>,
+        fn_span: Span,
+        def_id: LocalDefId,
+    ) {
+        // Skip lints disabled with `ANCHOR_LINTS_ONLY` or `ANCHOR_LINTS_SKIP`
+        if !is_lint_enabled(UNSAFE_UNWRAP_ON_ACCOUNT_DESERIALIZATION) {
+            return;
+        }
+
+        // Skip macro expansions, unsatisfiable predicates, and test files
+        if should_skip_function(cx, fn_span, def_id) {
+            return;
+        }
+
+        let mir_analyzer = MirAnalyzer::new(cx, body, def_id);
+        mir_analyzer.debug_dump(UNSAFE_UNWRAP_ON_ACCOUNT_DESERIALIZATION);
+
+        // Only instruction handlers take instruction input
+        if mir_analyzer.anchor_context_info.is_none() {
+            return;
+        }
+
+        analyze_unwraps(cx, &mir_analyzer);
+    }
+}
+
+fn analyze_unwraps<'tcx>(cx: &LateContext<'tcx>, mir_analyzer: &MirAnalyzer<'_, 'tcx>) {
+    let unwraps = collect_unwrap_calls(mir_analyzer);
+    if unwraps.is_empty() {
+        return;
+    }
+    let untrusted = collect_untrusted_locals(mir_analyzer);
+
+    for unwrap in unwraps {
+        let Some(fallible_call) = find_fallible_call(mir_analyzer, unwrap.receiver, &untrusted)
+        else {
+            continue;
+        };
+
+        let message = if DATA_ACCESS_METHODS.contains(&fallible_call.method.as_str()) {
+            format!(
+                "`.{}()` on `{}` panics if the account data is already borrowed — use `?` to return the error instead",
+                unwrap.method, fallible_call.method
+            )
+        } else {
+            format!(
+                "`.{}()` on `{}` of {} panics on malformed input — use `?` or return an explicit error instead",
+                unwrap.method,
+                fallible_call.method,
+                fallible_call.source.describe()
+            )
+        };
+        let finding = Finding::new(
+            UNSAFE_UNWRAP_ON_ACCOUNT_DESERIALIZATION,
+            unwrap.span,
+            message,
+        )
+        .with_note(Some(fallible_call.span), "the fallible call is here");
+        emit_finding(cx, UNSAFE_UNWRAP_ON_ACCOUNT_DESERIALIZATION, finding);
+    }
+}
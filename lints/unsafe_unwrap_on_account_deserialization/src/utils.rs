@@ -0,0 +1,218 @@
+use anchor_lints_utils::{
+    diag_items::is_anchor_context, mir_analyzer::MirAnalyzer, models::AssignmentKind,
+    utils::method_name,
+};
+use clippy_utils::ty::is_type_diagnostic_item;
+
+use rustc_middle::{
+    mir::{HasLocalDecls, Local, Operand, Rvalue, StatementKind, TerminatorKind},
+    ty::TyKind,
+};
+use rustc_span::{Span, sym};
+
+use std::collections::{HashMap, HashSet};
+
+/// Fallible borrows of an account's data
+pub const DATA_ACCESS_METHODS: &[&str] = &["try_borrow_data", "try_borrow_mut_data"];
+
+/// Fallible conversions that fail on malformed input
+pub const DECODING_METHODS: &[&str] = &[
+    "try_from",
+    "try_into",
+    "try_from_slice",
+    "try_from_slice_unchecked",
+    "try_deserialize",
+    "try_deserialize_unchecked",
+    "deserialize",
+    "from_utf8",
+    "parse",
+    "get",
+];
+
+/// Untrusted data a value is derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataSource {
+    AccountData,
+    InstructionInput,
+}
+
+impl DataSource {
+    pub fn describe(self) -> &'static str {
+        match self {
+            DataSource::AccountData => "account data",
+            DataSource::InstructionInput => "instruction input",
+        }
+    }
+}
+
+/// A call to `unwrap`/`expect` on an `Option` or `Result`
+pub struct UnwrapCall {
+    pub method: String,
+    pub receiver: Local,
+    pub span: Span,
+}
+
+/// The fallible call producing a value that is unwrapped
+pub struct FallibleCall {
+    pub method: String,
+    pub source: DataSource,
+    pub span: Span,
+}
+
+/// Collect the locals derived from account data or from the handler's instruction arguments
+pub fn collect_untrusted_locals(mir_analyzer: &MirAnalyzer) -> HashMap<Local, DataSource> {
+    let mir = mir_analyzer.mir;
+    let mut untrusted: HashMap<Local, DataSource> = HashMap::new();
+
+    // Every argument but the `Context` is instruction input
+    for local in mir.args_iter() {
+        if !is_anchor_context(mir_analyzer.cx.tcx, mir.local_decls()[local].ty) {
+            untrusted.insert(local, DataSource::InstructionInput);
+        }
+    }
+
+    // Propagate until no new local is derived from untrusted data
+    loop {
+        let mut derived: Vec<(Local, DataSource)> = Vec::new();
+        for bbdata in mir.basic_blocks.iter() {
+            for stmt in &bbdata.statements {
+                if let StatementKind::Assign(box (place, rvalue)) = &stmt.kind
+                    && let Some(source) = rvalue_locals(rvalue)
+                        .iter()
+                        .find_map(|local| untrusted.get(local).copied())
+                {
+                    derived.push((place.local, source));
+                }
+            }
+
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                args,
+                destination,
+                ..
+            } = &bbdata.terminator().kind
+                && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+            {
+                let source = if method_name(mir_analyzer, *fn_def_id)
+                    .is_some_and(|name| DATA_ACCESS_METHODS.contains(&name.as_str()))
+                {
+                    Some(DataSource::AccountData)
+                } else {
+                    args.iter()
+                        .filter_map(|arg| arg.node.place())
+                        .find_map(|place| untrusted.get(&place.local).copied())
+                };
+                if let Some(source) = source {
+                    derived.push((destination.local, source));
+                }
+            }
+        }
+
+        let mut changed = false;
+        for (local, source) in derived {
+            if !untrusted.contains_key(&local) {
+                untrusted.insert(local, source);
+                changed = true;
+            }
+        }
+        if !changed {
+            return untrusted;
+        }
+    }
+}
+
+fn rvalue_locals(rvalue: &Rvalue<'_>) -> Vec<Local> {
+    let operand_local = |op: &Operand<'_>| op.place().map(|place| place.local);
+    match rvalue {
+        Rvalue::Use(op)
+        | Rvalue::Repeat(op, _)
+        | Rvalue::Cast(_, op, _)
+        | Rvalue::UnaryOp(_, op) => operand_local(op).into_iter().collect(),
+        Rvalue::BinaryOp(_, box (lhs, rhs)) => operand_local(lhs)
+            .into_iter()
+            .chain(operand_local(rhs))
+            .collect(),
+        Rvalue::Aggregate(_, operands) => operands.iter().filter_map(operand_local).collect(),
+        Rvalue::Ref(_, _, place)
+        | Rvalue::RawPtr(_, place)
+        | Rvalue::CopyForDeref(place)
+        | Rvalue::Discriminant(place) => vec![place.local],
+        _ => Vec::new(),
+    }
+}
+
+/// Collect the `unwrap`/`expect` calls on `Option` and `Result` values
+pub fn collect_unwrap_calls(mir_analyzer: &MirAnalyzer) -> Vec<UnwrapCall> {
+    let mut unwraps = Vec::new();
+    for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+        if let TerminatorKind::Call {
+            func: Operand::Constant(func),
+            args,
+            fn_span,
+            ..
+        } = &bbdata.terminator().kind
+            && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+            && let Some(method) = method_name(mir_analyzer, *fn_def_id)
+            && matches!(method.as_str(), "unwrap" | "expect")
+            && let Some(receiver) = args.first()
+            && let Some(receiver_local) = receiver.node.place().and_then(|place| place.as_local())
+        {
+            let receiver_ty = receiver.node.ty(mir_analyzer.mir, mir_analyzer.cx.tcx);
+            if is_type_diagnostic_item(mir_analyzer.cx, receiver_ty, sym::Option)
+                || is_type_diagnostic_item(mir_analyzer.cx, receiver_ty, sym::Result)
+            {
+                unwraps.push(UnwrapCall {
+                    method,
+                    receiver: receiver_local,
+                    span: *fn_span,
+                });
+            }
+        }
+    }
+    unwraps
+}
+
+/// Find the fallible borrow or decoding call producing `local` from untrusted data,
+/// following moves of its result
+pub fn find_fallible_call(
+    mir_analyzer: &MirAnalyzer,
+    local: Local,
+    untrusted: &HashMap<Local, DataSource>,
+) -> Option<FallibleCall> {
+    let mut visited = HashSet::new();
+    let mut current = local;
+    while visited.insert(current) {
+        for bbdata in mir_analyzer.mir.basic_blocks.iter() {
+            if let TerminatorKind::Call {
+                func: Operand::Constant(func),
+                destination,
+                fn_span,
+                ..
+            } = &bbdata.terminator().kind
+                && destination.as_local() == Some(current)
+                && let TyKind::FnDef(fn_def_id, _) = func.ty().kind()
+            {
+                let method = method_name(mir_analyzer, *fn_def_id)?;
+                let source = if DATA_ACCESS_METHODS.contains(&method.as_str()) {
+                    DataSource::AccountData
+                } else if DECODING_METHODS.contains(&method.as_str()) {
+                    *untrusted.get(&current)?
+                } else {
+                    return None;
+                };
+                return Some(FallibleCall {
+                    method,
+                    source,
+                    span: *fn_span,
+                });
+            }
+        }
+
+        let Some(AssignmentKind::FromPlace(place)) = mir_analyzer.assignment_map.get(&current)
+        else {
+            return None;
+        };
+        current = place.as_local()?;
+    }
+    None
+}
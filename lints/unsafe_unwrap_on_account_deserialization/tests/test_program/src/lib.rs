@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod unsafe_unwrap_on_account_deserialization_tests {
+    use super::*;
+
+    // BAD: both the borrow and the decoding of the account data are unwrapped
+    pub fn read_config_unwrap(ctx: Context<ReadConfig>) -> Result<()> {
+        let data = ctx.accounts.config.try_borrow_data().unwrap(); // [unsafe_unwrap_on_account_deserialization]
+        let admin = Pubkey::try_from(&data[8..40]).unwrap(); // [unsafe_unwrap_on_account_deserialization]
+        msg!("admin: {}", admin);
+        Ok(())
+    }
+
+    // GOOD: errors are propagated
+    pub fn read_config_handled(ctx: Context<ReadConfig>) -> Result<()> {
+        let data = ctx.accounts.config.try_borrow_data()?; // [safe_unwrap]
+        let admin =
+            Pubkey::try_from(&data[8..40]).map_err(|_| error!(CustomError::InvalidConfig))?; // [safe_unwrap]
+        msg!("admin: {}", admin);
+        Ok(())
+    }
+
+    // BAD: deserializing account data with `expect`
+    pub fn load_vault_expect(ctx: Context<ReadConfig>) -> Result<()> {
+        let data = ctx.accounts.config.try_borrow_data()?;
+        let vault = Vault::try_deserialize(&mut &data[..]).expect("invalid vault"); // [unsafe_unwrap_on_account_deserialization]
+        msg!("amount: {}", vault.amount);
+        Ok(())
+    }
+
+    // BAD: decoding instruction input
+    pub fn set_label_unwrap(_ctx: Context<ReadConfig>, label: Vec<u8>) -> Result<()> {
+        let label = String::from_utf8(label).unwrap(); // [unsafe_unwrap_on_account_deserialization]
+        msg!("label: {}", label);
+        Ok(())
+    }
+
+    // GOOD: invalid instruction input is rejected with an error
+    pub fn set_label_handled(_ctx: Context<ReadConfig>, label: Vec<u8>) -> Result<()> {
+        let label = String::from_utf8(label).map_err(|_| error!(CustomError::InvalidLabel))?; // [safe_unwrap]
+        msg!("label: {}", label);
+        Ok(())
+    }
+
+    // GOOD: the unwrapped value is not decoded from untrusted data
+    pub fn add_amount(_ctx: Context<ReadConfig>, amount: u64) -> Result<()> {
+        let total = amount.checked_add(1).unwrap(); // [safe_unwrap]
+        msg!("total: {}", total);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ReadConfig<'info> {
+    /// CHECK: data is read manually
+    pub config: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum CustomError {
+    #[msg("Invalid config")]
+    InvalidConfig,
+    #[msg("Invalid label")]
+    InvalidLabel,
+}
@@ -0,0 +1,215 @@
+//! Prints the findings of a lint run that are not in a baseline run, so PR checks only report
+//! regressions.
+//!
+//! Both runs record their findings with `ANCHOR_LINTS_SUMMARY`, e.g. on the target branch and
+//! on the PR:
+//!
+//! ```bash
+//! ANCHOR_LINTS_SUMMARY=/tmp/baseline.tsv cargo dylint --path lints --pattern "*"
+//! ANCHOR_LINTS_SUMMARY=/tmp/findings.tsv cargo dylint --path lints --pattern "*"
+//! cargo run --bin anchor-lints-diff -- /tmp/baseline.tsv /tmp/findings.tsv --fixed --fail-on-new
+//! ```
+//!
+//! Findings are matched by lint, crate, file and message, so moving code around does not report
+//! it again, and suppressed findings are left out. Their confidence does not take part in the
+//! match, a finding whose lint changed confidence is not reported again. With `--fixed`,
+//! baseline findings that are gone are listed too. With `--fail-on-new`, the process exits with
+//! status 1 when there are new findings. A record with another number of columns than the lints
+//! write fails the diff instead of being matched on part of its columns.
+//!
+//! With `--json`, the report is printed as a JSON object with `new`, `fixed` and `suppressed`
+//! arrays, each finding with its `confidence`. `suppressed` lists the findings of the run kept from being reported, with the `source`
+//! and the `reason` of their suppression: `allow`, `comment` and `confidence` as recorded during
+//! the run, and `baseline` for findings already present in the baseline. The lints do not know
+//! about the baseline, so `baseline` suppressions only exist in this report.
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+const USAGE: &str =
+    "usage: anchor-lints-diff <baseline file> <findings file> [--fixed] [--fail-on-new] [--json]";
+
+/// Reason of the findings suppressed because the baseline already has them
+const BASELINE_REASON: &str = "present in the baseline";
+
+/// A recorded finding, `(lint, crate, file, message)`
+type Record<'a> = (&'a str, &'a str, &'a str, &'a str);
+
+/// A finding kept from being reported, with the source and the reason of its suppression
+struct Suppressed<'a> {
+    record: Record<'a>,
+    confidence: &'a str,
+    source: &'a str,
+    reason: &'a str,
+}
+
+/// The records of a findings file, split into reported and suppressed findings
+struct Records<'a> {
+    counts: HashMap<Record<'a>, usize>,
+    /// Confidence of each reported record, as recorded by the lint
+    confidences: HashMap<Record<'a>, &'a str>,
+    suppressed: Vec<Suppressed<'a>>,
+}
+
+fn main() -> Result<()> {
+    let mut paths = Vec::new();
+    let mut show_fixed = false;
+    let mut fail_on_new = false;
+    let mut json_output = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--fixed" => show_fixed = true,
+            "--fail-on-new" => fail_on_new = true,
+            "--json" => json_output = true,
+            _ if arg.starts_with("--") => bail!("unknown option `{}`\n{}", arg, USAGE),
+            _ => paths.push(arg),
+        }
+    }
+    let [baseline_path, findings_path] = paths.as_slice() else {
+        bail!(USAGE);
+    };
+
+    let baseline = read_findings_file(baseline_path)?;
+    let findings = read_findings_file(findings_path)?;
+    let baseline_records = parse_records(&baseline)?;
+    let mut findings_records = parse_records(&findings)?;
+
+    let new_findings = difference(&findings_records.counts, &baseline_records.counts);
+    let fixed_findings = difference(&baseline_records.counts, &findings_records.counts);
+
+    if json_output {
+        findings_records.suppressed.extend(
+            intersection(&findings_records.counts, &baseline_records.counts)
+                .into_iter()
+                .map(|record| Suppressed {
+                    record,
+                    confidence: findings_records.confidences[&record],
+                    source: "baseline",
+                    reason: BASELINE_REASON,
+                }),
+        );
+        let report = json!({
+            "new": new_findings
+                .iter()
+                .map(|record| record_json(record, findings_records.confidences[record]))
+                .collect::<Vec<_>>(),
+            "fixed": fixed_findings
+                .iter()
+                .map(|record| record_json(record, baseline_records.confidences[record]))
+                .collect::<Vec<_>>(),
+            "suppressed": findings_records
+                .suppressed
+                .iter()
+                .map(|suppressed| {
+                    let mut entry = record_json(&suppressed.record, suppressed.confidence);
+                    entry["source"] = json!(suppressed.source);
+                    entry["reason"] = json!(suppressed.reason);
+                    entry
+                })
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!(
+            "anchor-lints diff: {} new findings, {} fixed findings",
+            new_findings.len(),
+            fixed_findings.len()
+        );
+        print_section("New", &new_findings);
+        if show_fixed {
+            print_section("Fixed", &fixed_findings);
+        }
+    }
+
+    if fail_on_new && !new_findings.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn read_findings_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read findings file `{}`", path))
+}
+
+/// Count every reported record, the same finding can be emitted more than once per file, and
+/// collect the suppressed ones
+fn parse_records(findings: &str) -> Result<Records<'_>> {
+    let mut records = Records {
+        counts: HashMap::new(),
+        confidences: HashMap::new(),
+        suppressed: Vec::new(),
+    };
+    for record in findings.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<_> = record.split('\t').collect();
+        match fields[..] {
+            [lint, crate_name, file, message, confidence] => {
+                let record = (lint, crate_name, file, message);
+                *records.counts.entry(record).or_default() += 1;
+                records.confidences.insert(record, confidence);
+            }
+            // Suppressed findings carry their reason after the confidence, then their source
+            [lint, crate_name, file, message, confidence, reason, source] => {
+                records.suppressed.push(Suppressed {
+                    record: (lint, crate_name, file, message),
+                    confidence,
+                    source,
+                    reason,
+                });
+            }
+            _ => bail!("Malformed finding record `{}`", record),
+        }
+    }
+    Ok(records)
+}
+
+/// Records of `counts` not covered by `other`, repeated once per missing occurrence and sorted
+fn difference<'a>(
+    counts: &HashMap<Record<'a>, usize>,
+    other: &HashMap<Record<'a>, usize>,
+) -> Vec<Record<'a>> {
+    let mut records: Vec<_> = counts
+        .iter()
+        .flat_map(|(record, count)| {
+            let covered = other.get(record).copied().unwrap_or_default();
+            std::iter::repeat_n(*record, count.saturating_sub(covered))
+        })
+        .collect();
+    records.sort();
+    records
+}
+
+/// Records of `counts` covered by `other`, repeated once per covered occurrence and sorted
+fn intersection<'a>(
+    counts: &HashMap<Record<'a>, usize>,
+    other: &HashMap<Record<'a>, usize>,
+) -> Vec<Record<'a>> {
+    let mut records: Vec<_> = counts
+        .iter()
+        .flat_map(|(record, count)| {
+            let covered = other.get(record).copied().unwrap_or_default();
+            std::iter::repeat_n(*record, (*count).min(covered))
+        })
+        .collect();
+    records.sort();
+    records
+}
+
+fn record_json((lint, crate_name, file, message): &Record, confidence: &str) -> Value {
+    json!({
+        "lint": lint,
+        "crate": crate_name,
+        "file": file,
+        "message": message,
+        "confidence": confidence,
+    })
+}
+
+fn print_section(title: &str, records: &[Record]) {
+    println!();
+    println!("{}:", title);
+    for (lint, crate_name, file, message) in records {
+        println!("  {}  {}  {}  {}", lint, crate_name, file, message);
+    }
+}
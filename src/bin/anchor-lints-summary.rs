@@ -0,0 +1,98 @@
+//! Prints a one-screen summary of the findings recorded during a lint run.
+//!
+//! Findings are only recorded when `ANCHOR_LINTS_SUMMARY` points at a file:
+//!
+//! ```bash
+//! ANCHOR_LINTS_SUMMARY=/tmp/findings.tsv cargo dylint --path lints --pattern "*"
+//! cargo run --bin anchor-lints-summary -- /tmp/findings.tsv
+//! ```
+//!
+//! Suppressed findings, e.g. with `#[allow]` or an `// anchor-lints: validated-elsewhere` comment,
+//! are not counted, they are listed with the source and the reason of their suppression in a
+//! "Suppressed" section instead. Findings only suppressed because a baseline run already has
+//! them are not known here, `anchor-lints-diff --json` lists them.
+
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+
+/// Number of files listed in the "Top files" section
+const TOP_FILES: usize = 10;
+
+fn main() -> Result<()> {
+    let Some(path) = std::env::args().nth(1) else {
+        bail!("usage: anchor-lints-summary <findings file>");
+    };
+    let findings = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read findings file `{}`", path))?;
+
+    let mut by_lint: HashMap<&str, usize> = HashMap::new();
+    let mut by_crate: HashMap<&str, usize> = HashMap::new();
+    let mut by_file: HashMap<&str, usize> = HashMap::new();
+    let mut by_confidence: HashMap<&str, usize> = HashMap::new();
+    let mut suppressed: HashMap<String, usize> = HashMap::new();
+    let mut total = 0;
+
+    for record in findings.lines().filter(|line| !line.trim().is_empty()) {
+        let fields: Vec<_> = record.split('\t').collect();
+        let (lint, crate_name, file, confidence) = match fields[..] {
+            [lint, crate_name, file, _, confidence] => (lint, crate_name, file, confidence),
+            // Suppressed findings carry their reason after the confidence, then their source
+            [lint, _, file, _, _, reason, source] => {
+                record_suppressed(&mut suppressed, lint, file, source, reason);
+                continue;
+            }
+            _ => bail!("Malformed finding record `{}`", record),
+        };
+        *by_lint.entry(lint).or_default() += 1;
+        *by_crate.entry(crate_name).or_default() += 1;
+        *by_file.entry(file).or_default() += 1;
+        *by_confidence.entry(confidence).or_default() += 1;
+        total += 1;
+    }
+
+    println!("anchor-lints summary: {} findings", total);
+    print_section("By lint", by_lint, usize::MAX);
+    print_section("By crate", by_crate, usize::MAX);
+    print_section("By confidence", by_confidence, usize::MAX);
+    print_section("Top files", by_file, TOP_FILES);
+    if !suppressed.is_empty() {
+        let suppressed = suppressed
+            .iter()
+            .map(|(entry, count)| (entry.as_str(), *count))
+            .collect();
+        print_section("Suppressed", suppressed, usize::MAX);
+    }
+    Ok(())
+}
+
+/// Count a suppressed finding under its lint, file, source and reason
+fn record_suppressed(
+    suppressed: &mut HashMap<String, usize>,
+    lint: &str,
+    file: &str,
+    source: &str,
+    reason: &str,
+) {
+    let reason = if reason.is_empty() {
+        "no reason given"
+    } else {
+        reason
+    };
+    *suppressed
+        .entry(format!("{}  {}  {}: {}", lint, file, source, reason))
+        .or_default() += 1;
+}
+
+/// Print `counts` sorted by descending count, then by name
+fn print_section(title: &str, counts: HashMap<&str, usize>, limit: usize) {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a_name, a_count), (b_name, b_count)| {
+        b_count.cmp(a_count).then(a_name.cmp(b_name))
+    });
+
+    println!();
+    println!("{}:", title);
+    for (name, count) in counts.into_iter().take(limit) {
+        println!("{:>6}  {}", count, name);
+    }
+}
@@ -0,0 +1,135 @@
+//! Patterns of several lints combined in the same handlers and accounts structs, checking that
+//! each lint reports its own findings when all lints run together
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::system_program::{self, Transfer};
+
+declare_id!("11111111111111111111111111111111");
+
+#[program]
+pub mod kitchen_sink {
+    use super::*;
+
+    // Missing reload and arbitrary CPI in one handler
+    pub fn allocate_and_forward(ctx: Context<AllocateAndForward>, amount: u64) -> Result<()> {
+        cpi_mutating_allocate(&ctx.accounts.vault, &ctx.accounts.system_program, amount)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+
+        let _data = ctx.accounts.vault.data; // [missing_account_reload]
+        Ok(())
+    }
+
+    // Missing signer and arbitrary CPI in one handler, on an accounts struct with duplicate seeds
+    pub fn forward_from_state(ctx: Context<ForwardFromState>, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer { // [missing_signer_validation]
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.unchecked_program.key(), cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?; // [arbitrary_cpi_call]
+        Ok(())
+    }
+
+    // User-controlled account next to a PDA signer, analyzed once per function
+    pub fn sign_for_pool(ctx: Context<SignForPool>) -> Result<()> {
+        let cpi_accounts = PoolCpiAccounts {
+            user_account: ctx.accounts.user_account.to_account_info(),
+            pool_authority: ctx.accounts.pool_authority.to_account_info(),
+        };
+
+        let pool_key = ctx.accounts.pool.key();
+        let pool_key_bytes = pool_key.to_bytes();
+        let (_, pool_authority_bump) =
+            Pubkey::find_program_address(&[b"pool_authority", &pool_key_bytes], ctx.program_id);
+
+        let bump_array = [pool_authority_bump];
+        let seeds: &[&[u8]] = &[b"pool_authority", &pool_key_bytes, &bump_array];
+
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        let _cpi_ctx = CpiContext::new_with_signer( // [pda_signer_account_overlap]
+            ctx.accounts.target_program.key(),
+            cpi_accounts,
+            signer_seeds,
+        );
+
+        Ok(())
+    }
+}
+
+fn cpi_mutating_allocate<'info>(
+    account: &Account<'info, UserState>,
+    system_program: &Program<'info, System>,
+    extra_space: u64,
+) -> Result<()> {
+    let new_space = account.to_account_info().data_len() as u64 + extra_space;
+    let ix = system_instruction::allocate(&account.key(), new_space);
+    invoke(&ix, &[account.to_account_info(), system_program.to_account_info()])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AllocateAndForward<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub vault: Account<'info, UserState>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: deliberately unchecked program
+    pub unchecked_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ForwardFromState<'info> {
+    #[account(mut, seeds = [b"state", recipient.key().as_ref()], bump)]
+    pub user: Account<'info, UserState>,
+    #[account(seeds = [b"state", recipient.key().as_ref()], bump)]
+    pub config: Account<'info, Config>, // [duplicate_seeds_across_distinct_account_types]
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: deliberately unchecked program
+    pub unchecked_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct PoolCpiAccounts<'info> {
+    pub user_account: AccountInfo<'info>,
+    pub pool_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SignForPool<'info> {
+    #[account(mut)]
+    /// CHECK: User-controlled account
+    pub user_account: UncheckedAccount<'info>,
+    #[account(seeds = [b"pool_authority", pool.key().as_ref()], bump)]
+    /// CHECK: PDA signer
+    pub pool_authority: AccountInfo<'info>,
+    pub pool: Account<'info, PoolState>,
+    /// CHECK: Target program
+    pub target_program: UncheckedAccount<'info>,
+}
+
+#[account]
+pub struct PoolState {
+    pub total: u64,
+}
+
+#[account]
+pub struct UserState {
+    pub data: u64,
+}
+
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+}
@@ -1,9 +1,11 @@
+use anchor_lints_utils::registry::{LINTS, confidence_for, help_for};
 use anyhow::{Context, Result};
 use regex::Regex;
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::fs;
 
@@ -39,6 +41,11 @@ async fn arbitrary_cpi_call_tests() -> Result<()> {
     run_arbitrary_cpi_call_tests().await
 }
 
+#[tokio::test]
+async fn arbitrary_cpi_call_remaining_accounts_tests() -> Result<()> {
+    run_arbitrary_cpi_call_remaining_accounts_tests().await
+}
+
 #[tokio::test]
 async fn cpi_no_result_tests() -> Result<()> {
     run_cpi_no_result_tests().await
@@ -59,11 +66,21 @@ async fn missing_owner_check_tests() -> Result<()> {
     run_missing_owner_check_tests().await
 }
 
+#[tokio::test]
+async fn missing_owner_check_config_tests() -> Result<()> {
+    run_missing_owner_check_config_tests().await
+}
+
 #[tokio::test]
 async fn missing_account_field_init_tests() -> Result<()> {
     run_missing_account_field_init_tests().await
 }
 
+#[tokio::test]
+async fn missing_account_field_init_config_tests() -> Result<()> {
+    run_missing_account_field_init_config_tests().await
+}
+
 #[tokio::test]
 async fn ata_should_use_init_if_needed_tests() -> Result<()> {
     run_ata_should_use_init_if_needed_tests().await
@@ -84,11 +101,233 @@ async fn unsafe_pyth_price_account_tests() -> Result<()> {
     run_unsafe_pyth_price_account_tests().await
 }
 
+#[tokio::test]
+async fn unsafe_pyth_price_account_config_tests() -> Result<()> {
+    run_unsafe_pyth_price_account_config_tests().await
+}
+
 #[tokio::test]
 async fn missing_mut_constraint_tests() -> Result<()> {
     run_missing_mut_constraint_tests().await
 }
 
+#[tokio::test]
+async fn invoke_with_account_infos_mismatch_tests() -> Result<()> {
+    run_invoke_with_account_infos_mismatch_tests().await
+}
+
+#[tokio::test]
+async fn init_if_needed_without_payer_or_space_tests() -> Result<()> {
+    run_init_if_needed_without_payer_or_space_tests().await
+}
+
+#[tokio::test]
+async fn summary_report_tests() -> Result<()> {
+    run_summary_report_tests().await
+}
+
+#[tokio::test]
+async fn baseline_diff_tests() -> Result<()> {
+    run_baseline_diff_tests().await
+}
+
+#[tokio::test]
+async fn suppressed_findings_tests() -> Result<()> {
+    run_suppressed_findings_tests().await
+}
+
+#[tokio::test]
+async fn help_notes_tests() -> Result<()> {
+    run_help_notes_tests().await
+}
+
+#[tokio::test]
+async fn kitchen_sink_tests() -> Result<()> {
+    run_kitchen_sink_tests().await
+}
+
+#[tokio::test]
+async fn min_confidence_tests() -> Result<()> {
+    run_min_confidence_tests().await
+}
+
+#[tokio::test]
+async fn lint_filter_tests() -> Result<()> {
+    run_lint_filter_tests().await
+}
+
+#[tokio::test]
+async fn mir_dump_tests() -> Result<()> {
+    run_mir_dump_tests().await
+}
+
+#[tokio::test]
+async fn suppression_tests() -> Result<()> {
+    run_suppression_tests().await
+}
+
+#[tokio::test]
+#[ignore = "benchmark, run with `cargo test perf_benchmark_tests -- --ignored`"]
+async fn perf_benchmark_tests() -> Result<()> {
+    run_perf_benchmark_tests().await
+}
+
+#[test]
+fn registry_help_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    for entry in std::fs::read_dir(lint_root.join("lints"))? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        // The example lint is only a template for new lints
+        if !entry.file_type()?.is_dir() || name == "example_lint" {
+            continue;
+        }
+        if !LINTS.iter().any(|lint| lint.name == name) {
+            anyhow::bail!("Lint `{}` is missing from the registry", name);
+        }
+    }
+    for lint in LINTS {
+        if lint.help.trim().is_empty() || help_for(&lint.name.to_uppercase()) != lint.help {
+            anyhow::bail!("Lint `{}` has no remediation help", lint.name);
+        }
+        // Findings take the confidence of their lint, which must resolve from the declared name
+        if confidence_for(&lint.name.to_uppercase()) != lint.confidence {
+            anyhow::bail!("Lint `{}` has no assigned confidence", lint.name);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn unchecked_account_used_as_authority_without_signer_or_pda_tests() -> Result<()> {
+    run_unchecked_account_used_as_authority_without_signer_or_pda_tests().await
+}
+
+#[tokio::test]
+async fn missing_signer_check_on_governance_config_update_tests() -> Result<()> {
+    run_missing_signer_check_on_governance_config_update_tests().await
+}
+
+#[tokio::test]
+async fn associated_token_authority_mismatch_tests() -> Result<()> {
+    run_associated_token_authority_mismatch_tests().await
+}
+
+#[tokio::test]
+async fn missing_signer_validation_for_native_sol_withdrawal_tests() -> Result<()> {
+    run_missing_signer_validation_for_native_sol_withdrawal_tests().await
+}
+
+#[tokio::test]
+async fn unbounded_loop_over_accounts_cu_dos_tests() -> Result<()> {
+    run_unbounded_loop_over_accounts_cu_dos_tests().await
+}
+
+#[tokio::test]
+async fn token_transfer_amount_from_unvalidated_balance_tests() -> Result<()> {
+    run_token_transfer_amount_from_unvalidated_balance_tests().await
+}
+
+#[tokio::test]
+async fn missing_owner_check_on_cpi_return_account_tests() -> Result<()> {
+    run_missing_owner_check_on_cpi_return_account_tests().await
+}
+
+#[tokio::test]
+async fn account_space_mismatch_with_struct_size_tests() -> Result<()> {
+    run_account_space_mismatch_with_struct_size_tests().await
+}
+
+#[tokio::test]
+async fn unsafe_unwrap_on_account_deserialization_tests() -> Result<()> {
+    run_unsafe_unwrap_on_account_deserialization_tests().await
+}
+
+#[tokio::test]
+async fn signer_not_mutable_when_paying_tests() -> Result<()> {
+    run_signer_not_mutable_when_paying_tests().await
+}
+
+#[tokio::test]
+async fn missing_bump_verification_on_signer_pda_tests() -> Result<()> {
+    run_missing_bump_verification_on_signer_pda_tests().await
+}
+
+#[tokio::test]
+async fn duplicate_seeds_across_distinct_account_types_tests() -> Result<()> {
+    run_duplicate_seeds_across_distinct_account_types_tests().await
+}
+
+#[tokio::test]
+async fn missing_owner_check_on_manually_loaded_zero_copy_tests() -> Result<()> {
+    run_missing_owner_check_on_manually_loaded_zero_copy_tests().await
+}
+
+#[tokio::test]
+async fn cpi_context_reused_across_different_programs_tests() -> Result<()> {
+    run_cpi_context_reused_across_different_programs_tests().await
+}
+
+#[tokio::test]
+async fn unchecked_pda_canonical_bump_in_find_program_address_mismatch_tests() -> Result<()> {
+    run_unchecked_pda_canonical_bump_in_find_program_address_mismatch_tests().await
+}
+
+#[tokio::test]
+async fn token_2022_missing_extension_account_handling_tests() -> Result<()> {
+    run_token_2022_missing_extension_account_handling_tests().await
+}
+
+#[tokio::test]
+async fn missing_signer_validation_on_delegate_transfer_tests() -> Result<()> {
+    run_missing_signer_validation_on_delegate_transfer_tests().await
+}
+
+#[tokio::test]
+async fn account_written_without_persisting_tests() -> Result<()> {
+    run_account_written_without_persisting_tests().await
+}
+
+#[tokio::test]
+async fn missing_owner_check_before_lamport_read_as_trust_tests() -> Result<()> {
+    run_missing_owner_check_before_lamport_read_as_trust_tests().await
+}
+
+#[tokio::test]
+async fn seed_contains_user_controlled_value_without_validation_tests() -> Result<()> {
+    run_seed_contains_user_controlled_value_without_validation_tests().await
+}
+
+#[tokio::test]
+async fn missing_signer_validation_cross_program_instruction_introspection_tests() -> Result<()> {
+    run_missing_signer_validation_cross_program_instruction_introspection_tests().await
+}
+
+#[tokio::test]
+async fn account_reinit_via_close_then_init_in_same_tx_tests() -> Result<()> {
+    run_account_reinit_via_close_then_init_in_same_tx_tests().await
+}
+
+#[tokio::test]
+async fn missing_owner_check_on_remaining_account_deserialization_tests() -> Result<()> {
+    run_missing_owner_check_on_remaining_account_deserialization_tests().await
+}
+
+#[tokio::test]
+async fn missing_account_field_init_for_bump_tests() -> Result<()> {
+    run_missing_account_field_init_for_bump_tests().await
+}
+
+#[tokio::test]
+async fn token_program_id_from_account_instead_of_typed_program_tests() -> Result<()> {
+    run_token_program_id_from_account_instead_of_typed_program_tests().await
+}
+
+#[tokio::test]
+async fn unchecked_cpi_program_in_generic_anchor_cpi_context_tests() -> Result<()> {
+    run_unchecked_cpi_program_in_generic_anchor_cpi_context_tests().await
+}
+
 async fn run_missing_account_reload_tests() -> Result<()> {
     let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let test_program = lint_root.join("lints/missing_account_reload/tests/test_program");
@@ -99,17 +338,23 @@ async fn run_missing_account_reload_tests() -> Result<()> {
         "unsafe_account_accessed",
         "cpi_call",
         "safe_account_accessed",
+        "stale_balance_check",
+        "stale_lamports_read",
+        "additional_access",
     ];
 
     let expected = collect_expected_markers(&test_program, &allowed_lints).await?;
 
-    let out = run_dylint_command(&lint_root, &test_program, "missing_account_reload")?;
+    let out = run_dylint_command(&lint_root, &test_program, "missing_account_reload", None)?;
     let stderr = out.stderr.clone();
     let out = out.combined;
 
     #[derive(Debug)]
     enum OutputTypes {
         DataAccess,
+        StaleBalanceCheck,
+        StaleLamportsRead,
+        AdditionalAccess,
         CpiCall,
     }
 
@@ -117,18 +362,40 @@ async fn run_missing_account_reload_tests() -> Result<()> {
     let mut previous_line: Option<OutputTypes> = None;
 
     let lint_heading = "warning: accessing an account after a CPI without calling `reload()`";
+    let stale_balance_heading =
+        "warning: checking a token balance after a CPI without calling `reload()`";
+    let stale_lamports_heading =
+        "warning: reading the lamport balance of an account after a SOL transfer CPI";
+    let zero_copy_heading = "warning: accessing a zero-copy account after a CPI";
+    let cached_copy_heading = "warning: using a value copied from an account before a CPI";
 
     // Parse `cargo dylint` output
     for line in out.lines() {
         match line {
-            x if x == lint_heading => {
+            x if x == lint_heading
+                || x.starts_with(zero_copy_heading)
+                || x.starts_with(cached_copy_heading) =>
+            {
                 previous_line = Some(OutputTypes::DataAccess);
                 continue;
             }
-            "note: CPI is here" => {
+            x if x.starts_with(stale_balance_heading) => {
+                previous_line = Some(OutputTypes::StaleBalanceCheck);
+                continue;
+            }
+            x if x.starts_with(stale_lamports_heading) => {
+                previous_line = Some(OutputTypes::StaleLamportsRead);
+                continue;
+            }
+            "note: CPI is here" | "note: the lamport balance is changed by this transfer" => {
                 previous_line = Some(OutputTypes::CpiCall);
                 continue;
             }
+            "note: also accessed here without calling `reload()`"
+            | "note: also accessed here through the data loaded before the CPI" => {
+                previous_line = Some(OutputTypes::AdditionalAccess);
+                continue;
+            }
             _ => {}
         }
 
@@ -154,6 +421,27 @@ async fn run_missing_account_reload_tests() -> Result<()> {
                         .or_default()
                         .insert((file, line_no));
                 }
+                OutputTypes::StaleBalanceCheck => {
+                    actual
+                        .entry("stale_balance_check".into())
+                        .or_default()
+                        .insert((file, line_no));
+                }
+                OutputTypes::StaleLamportsRead => {
+                    actual
+                        .entry("stale_lamports_read".into())
+                        .or_default()
+                        .insert((file, line_no));
+                }
+                OutputTypes::AdditionalAccess => {
+                    // Later accesses after the same CPI are secondary locations of one diagnostic
+                    for key in ["data_access", "additional_access"] {
+                        actual
+                            .entry(key.into())
+                            .or_default()
+                            .insert((file.clone(), line_no));
+                    }
+                }
                 OutputTypes::CpiCall => {
                     actual
                         .entry("cpi_call".into())
@@ -177,6 +465,9 @@ async fn run_missing_account_reload_tests() -> Result<()> {
             "unsafe_account_accessed" => "data_access",
             "cpi_call" => "cpi_call",
             "safe_account_accessed" => "data_access",
+            "stale_balance_check" => "stale_balance_check",
+            "stale_lamports_read" => "stale_lamports_read",
+            "additional_access" => "additional_access",
             _ => bail_with_stderr!(stderr, "Invalid lint name: {}", lint),
         }
         .to_string();
@@ -201,7 +492,12 @@ async fn run_missing_account_reload_tests() -> Result<()> {
         }
 
         let missing: Vec<_> = expected_set.difference(&actual_set).cloned().collect();
-        let unexpected: Vec<_> = actual_set.difference(&expected_set).cloned().collect();
+        // Nested accesses are aggregated as well, only the marked secondary locations are required
+        let unexpected: Vec<_> = if lint == "additional_access" {
+            Vec::new()
+        } else {
+            actual_set.difference(&expected_set).cloned().collect()
+        };
 
         if !missing.is_empty() || !unexpected.is_empty() {
             bail_with_stderr!(
@@ -214,7 +510,18 @@ async fn run_missing_account_reload_tests() -> Result<()> {
         }
     }
 
-    Ok(())
+    check_expected_notes(
+        &lint_root,
+        &test_program,
+        "missing_account_reload",
+        &[
+            "unsafe_account_accessed",
+            "stale_balance_check",
+            "stale_lamports_read",
+        ],
+        None,
+    )
+    .await
 }
 
 async fn run_duplicate_mutable_accounts_tests() -> Result<()> {
@@ -227,7 +534,12 @@ async fn run_duplicate_mutable_accounts_tests() -> Result<()> {
 
     let expected = collect_expected_markers(&test_program, &allowed_lints).await?;
 
-    let out = run_dylint_command(&lint_root, &test_program, "duplicate_mutable_accounts")?;
+    let out = run_dylint_command(
+        &lint_root,
+        &test_program,
+        "duplicate_mutable_accounts",
+        None,
+    )?;
     let stderr = out.stderr.clone();
     let out = out.combined;
 
@@ -235,7 +547,7 @@ async fn run_duplicate_mutable_accounts_tests() -> Result<()> {
     let mut previous_line_lint_warn: bool = false;
 
     let duplicate_mutable_account_regex =
-        Regex::new(r#"help: `(\w+)` and `(\w+)` may refer to the same account\."#)
+        Regex::new(r#"help: `([\w\[\]]+)` and `([\w\[\]]+)` may refer to the same account\."#)
             .context("Failed to compile duplicate mutable account regex")?;
 
     // Parse `cargo dylint` output
@@ -331,6 +643,20 @@ async fn run_arbitrary_cpi_call_tests() -> Result<()> {
     .await
 }
 
+async fn run_arbitrary_cpi_call_remaining_accounts_tests() -> Result<()> {
+    run_standard_lint_test(
+        "arbitrary_cpi_call",
+        &[
+            "arbitrary_remaining_account_program",
+            "safe_remaining_account_program",
+        ],
+        "warning: arbitrary CPI detected —",
+        Some("in the remaining accounts of this CPI"),
+        "arbitrary CPI (remaining accounts)",
+    )
+    .await
+}
+
 async fn run_cpi_no_result_tests() -> Result<()> {
     run_standard_lint_test(
         "cpi_no_result",
@@ -375,6 +701,25 @@ async fn run_missing_owner_check_tests() -> Result<()> {
     .await
 }
 
+async fn run_missing_owner_check_config_tests() -> Result<()> {
+    run_standard_lint_test_with_config(
+        "missing_owner_check",
+        &[
+            "missing_owner_check_with_validated_type_config",
+            "safe_owner_check_with_validated_type_config",
+        ],
+        "warning: account",
+        Some("has its data accessed but no owner validation detected"),
+        "missing owner check (owner-validated types config)",
+        Some(
+            r#"[missing_owner_check]
+owner_validated_types = ["ValidatedMetadata"]
+"#,
+        ),
+    )
+    .await
+}
+
 async fn run_missing_account_field_init_tests() -> Result<()> {
     run_standard_lint_test(
         "missing_account_field_init",
@@ -386,6 +731,25 @@ async fn run_missing_account_field_init_tests() -> Result<()> {
     .await
 }
 
+async fn run_missing_account_field_init_config_tests() -> Result<()> {
+    run_standard_lint_test_with_config(
+        "missing_account_field_init",
+        &[
+            "missing_account_field_init_with_gap_config",
+            "safe_account_field_init_with_gap_config",
+        ],
+        "warning: account",
+        Some("is initialized but the following fields are never assigned"),
+        "missing_account_field_init (gap config)",
+        Some(
+            r#"[missing_account_field_init]
+padding_field_patterns = ["^padding", "^reserved", "^_", "^gap"]
+"#,
+        ),
+    )
+    .await
+}
+
 async fn run_ata_should_use_init_if_needed_tests() -> Result<()> {
     run_standard_lint_test(
         "ata_should_use_init_if_needed",
@@ -430,6 +794,25 @@ async fn run_unsafe_pyth_price_account_tests() -> Result<()> {
     .await
 }
 
+async fn run_unsafe_pyth_price_account_config_tests() -> Result<()> {
+    run_standard_lint_test_with_config(
+        "unsafe_pyth_price_account",
+        &[
+            "unsafe_account_accessed_with_canonical_feed_config",
+            "safe_account_accessed_with_canonical_feed_config",
+        ],
+        "warning: Pyth PriceUpdateV2 account",
+        Some("one of the configured `canonical_feed_addresses`"),
+        "unsafe_pyth_price_account (canonical feed config)",
+        Some(
+            r#"[unsafe_pyth_price_account]
+canonical_feed_addresses = ["7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE"]
+"#,
+        ),
+    )
+    .await
+}
+
 async fn run_missing_mut_constraint_tests() -> Result<()> {
     run_standard_lint_test(
         "missing_mut_constraint",
@@ -441,48 +824,1256 @@ async fn run_missing_mut_constraint_tests() -> Result<()> {
     .await
 }
 
-// Recursively find all .rs files
-fn find_rust_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
+async fn run_invoke_with_account_infos_mismatch_tests() -> Result<()> {
+    run_standard_lint_test(
+        "invoke_with_account_infos_mismatch",
+        &["invoke_with_account_infos_mismatch", "safe_invoke"],
+        "warning: account",
+        Some("is referenced by the instruction but its `AccountInfo` is not passed to this CPI"),
+        "invoke_with_account_infos_mismatch",
+    )
+    .await
+}
 
-    for entry in std::fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
+async fn run_init_if_needed_without_payer_or_space_tests() -> Result<()> {
+    run_standard_lint_test(
+        "init_if_needed_without_payer_or_space",
+        &[
+            "init_if_needed_without_payer_or_space",
+            "safe_init_payer_and_space",
+        ],
+        "warning: account",
+        Some("without a `"),
+        "init_if_needed_without_payer_or_space",
+    )
+    .await
+}
 
-        if path.is_dir() {
-            files.extend(find_rust_files(&path)?);
-        } else if path.extension().map(|e| e == "rs").unwrap_or(false) {
-            files.push(path);
+async fn run_summary_report_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/missing_signer_validation/tests/test_program");
+    let findings_file =
+        std::env::temp_dir().join(format!("anchor-lints-summary-{}.tsv", std::process::id()));
+    let _ = std::fs::remove_file(&findings_file);
+
+    // Findings are only recorded when the program is analyzed again, not when cargo replays
+    // cached diagnostics
+    std::fs::File::options()
+        .append(true)
+        .open(test_program.join("src/lib.rs"))?
+        .set_modified(SystemTime::now())?;
+
+    let mut command = dylint_command(&lint_root, &test_program, "missing_signer_validation");
+    command.env("ANCHOR_LINTS_SUMMARY", &findings_file);
+    let out = run_command(command)?;
+    let emitted = out
+        .combined
+        .lines()
+        .filter(|line| {
+            line.contains("warning: account")
+                && line.contains("is used as a signer but lacks signer validation")
+        })
+        .count();
+    if emitted == 0 {
+        bail_with_stderr!(
+            out.stderr,
+            "No missing_signer_validation findings were emitted"
+        );
+    }
+
+    let summary = Command::new(env!("CARGO_BIN_EXE_anchor-lints-summary"))
+        .arg(&findings_file)
+        .output()
+        .context("Failed to run `anchor-lints-summary`")?;
+    let _ = std::fs::remove_file(&findings_file);
+    let summary = String::from_utf8_lossy(&summary.stdout).to_string();
+
+    // Parse `<count>  <name>` entries per section
+    let mut total = None;
+    let mut sections: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let mut current_section = None;
+    for line in summary.lines() {
+        if let Some(count) = line
+            .strip_prefix("anchor-lints summary: ")
+            .and_then(|rest| rest.strip_suffix(" findings"))
+        {
+            total = Some(count.parse::<usize>()?);
+        } else if let Some(title) = line.strip_suffix(':') {
+            current_section = Some(title.to_string());
+        } else if let Some(section) = &current_section
+            && let Some((count, name)) = line.trim().split_once("  ")
+        {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(name.trim().to_string(), count.parse()?);
         }
     }
 
-    Ok(files)
-}
+    let section_total = |title: &str| -> usize {
+        sections
+            .get(title)
+            .map(|entries| entries.values().sum())
+            .unwrap_or_default()
+    };
+    let by_lint = sections.get("By lint").cloned().unwrap_or_default();
+    let by_confidence = sections.get("By confidence").cloned().unwrap_or_default();
+    let confidence = confidence_for("missing_signer_validation").as_str();
+
+    if total != Some(emitted)
+        || by_lint.get("missing_signer_validation") != Some(&emitted)
+        || by_confidence.get(confidence) != Some(&emitted)
+        || section_total("By lint") != emitted
+        || section_total("By crate") != emitted
+        || section_total("Top files") != emitted
+    {
+        anyhow::bail!(
+            "Summary does not match the {} emitted findings:\n{}",
+            emitted,
+            summary
+        );
+    }
 
-async fn collect_expected_markers(
-    test_program: &Path,
-    allowed_lints: &[&str],
-) -> Result<HashMap<String, Vec<(String, usize)>>> {
-    let src_root = test_program.join("src");
-    let marker_regex = Regex::new(r#"\[(\w+)\]"#).context("Failed to compile marker regex")?;
-    let mut expected: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+    println!("summary report matches {} emitted findings", emitted);
+    Ok(())
+}
 
-    let rust_files =
-        find_rust_files(&src_root).context("Failed to scan Rust files in test program")?;
+async fn run_help_notes_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/missing_signer_validation/tests/test_program");
+
+    // Help notes are only attached when the program is analyzed again, not when cargo replays
+    // cached diagnostics
+    std::fs::File::options()
+        .append(true)
+        .open(test_program.join("src/lib.rs"))?
+        .set_modified(SystemTime::now())?;
+
+    let mut command = dylint_command(&lint_root, &test_program, "missing_signer_validation");
+    command.env("ANCHOR_LINTS_HELP_NOTES", "1");
+    let out = run_command(command)?;
+    let emitted = out
+        .combined
+        .lines()
+        .filter(|line| line.contains("is used as a signer but lacks signer validation"))
+        .count();
+    let help = format!("help: {}", help_for("missing_signer_validation"));
+    let helps = out
+        .combined
+        .lines()
+        .filter(|line| line.contains(&help))
+        .count();
+
+    // Only the first finding of the lint carries the remediation help
+    if emitted < 2 || helps != 1 {
+        bail_with_stderr!(
+            out.stderr,
+            "Expected the remediation help once for {} findings, found it {} times",
+            emitted,
+            helps
+        );
+    }
 
-    for file in rust_files {
-        let content = fs::read_to_string(&file)
-            .await
-            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    println!(
+        "remediation help attached to the first of {} findings",
+        emitted
+    );
+    Ok(())
+}
 
-        for (idx, line) in content.lines().enumerate() {
-            for caps in marker_regex.captures_iter(line) {
-                let lint_name = caps.get(1).unwrap().as_str().to_string();
+/// Lints with findings in the kitchen sink program, with the heading and text of their findings
+const KITCHEN_SINK_LINTS: &[(&str, &str, Option<&str>)] = &[
+    (
+        "missing_account_reload",
+        "warning: accessing an account after a CPI without calling `reload()`",
+        None,
+    ),
+    (
+        "arbitrary_cpi_call",
+        "warning: arbitrary CPI detected — program id appears user-controlled",
+        None,
+    ),
+    (
+        "missing_signer_validation",
+        "warning: account",
+        Some("is used as a signer but lacks signer validation"),
+    ),
+    (
+        "duplicate_seeds_across_distinct_account_types",
+        "warning: `",
+        Some("is derived from the same seeds as"),
+    ),
+    (
+        "pda_signer_account_overlap",
+        "warning: user-controlled account passed to CPI with PDA signer",
+        None,
+    ),
+];
 
-                if allowed_lints.contains(&lint_name.as_str()) {
-                    let relative = file
-                        .strip_prefix(test_program)
-                        .context("Failed to compute relative path")?
+async fn run_kitchen_sink_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("tests/kitchen_sink");
+    let lint_names: Vec<&str> = KITCHEN_SINK_LINTS
+        .iter()
+        .map(|(name, _, _)| *name)
+        .collect();
+    let expected = collect_expected_markers(&test_program, &lint_names).await?;
+
+    // All lints are loaded into the same driver, so state leaking between them changes findings
+    let out = run_dylint_command(&lint_root, &test_program, "*", None)?;
+
+    for (lint_name, lint_heading, additional_text) in KITCHEN_SINK_LINTS {
+        let actual = collect_reported_lines(
+            &out.combined,
+            &lint_root,
+            &test_program,
+            lint_heading,
+            *additional_text,
+        )?;
+        let expected: HashSet<_> = expected
+            .get(*lint_name)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        if actual != expected {
+            let mut missing: Vec<_> = expected.difference(&actual).collect();
+            let mut unexpected: Vec<_> = actual.difference(&expected).collect();
+            missing.sort();
+            unexpected.sort();
+            bail_with_stderr!(
+                out.stderr,
+                "`{}` findings differ when run with every lint, missing: {:#?}, unexpected: {:#?}",
+                lint_name,
+                missing,
+                unexpected
+            );
+        }
+    }
+
+    println!(
+        "{} lints report their findings when run together",
+        KITCHEN_SINK_LINTS.len()
+    );
+    Ok(())
+}
+
+async fn run_min_confidence_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // A low confidence lint is silenced by `high`, a high confidence lint still reports
+    for (lint_name, message, expect_findings) in [
+        (
+            "unbounded_loop_over_accounts_cu_dos",
+            "on every iteration — enforce an explicit maximum",
+            false,
+        ),
+        (
+            "signer_not_mutable_when_paying",
+            "is not marked `#[account(mut)]`",
+            true,
+        ),
+    ] {
+        let test_program = lint_root.join(format!("lints/{}/tests/test_program", lint_name));
+        // Findings are only filtered when the program is analyzed again, not when cargo replays
+        // cached diagnostics
+        std::fs::File::options()
+            .append(true)
+            .open(test_program.join("src/lib.rs"))?
+            .set_modified(SystemTime::now())?;
+
+        let mut command = dylint_command(&lint_root, &test_program, lint_name);
+        command.env("ANCHOR_LINTS_MIN_CONFIDENCE", "high");
+        let out = run_command(command)?;
+        let emitted = out
+            .combined
+            .lines()
+            .filter(|line| line.contains(message))
+            .count();
+        if (emitted > 0) != expect_findings {
+            bail_with_stderr!(
+                out.stderr,
+                "`{}` ({} confidence) emitted {} findings with a minimum confidence of high",
+                lint_name,
+                confidence_for(lint_name).as_str(),
+                emitted
+            );
+        }
+    }
+
+    println!("minimum confidence drops low confidence findings");
+    Ok(())
+}
+
+async fn run_lint_filter_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("tests/kitchen_sink");
+
+    for (env_var, expect_arbitrary_cpi_call, expect_other_lints) in [
+        ("ANCHOR_LINTS_ONLY", true, false),
+        ("ANCHOR_LINTS_SKIP", false, true),
+    ] {
+        let findings_file = std::env::temp_dir().join(format!(
+            "anchor-lints-filter-{}-{}.tsv",
+            env_var,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&findings_file);
+        // Lints are only skipped when the program is analyzed again, not when cargo replays
+        // cached diagnostics
+        std::fs::File::options()
+            .append(true)
+            .open(test_program.join("src/lib.rs"))?
+            .set_modified(SystemTime::now())?;
+
+        let mut command = dylint_command(&lint_root, &test_program, "*");
+        command
+            .env(env_var, "arbitrary_cpi_call")
+            .env("ANCHOR_LINTS_SUMMARY", &findings_file);
+        let out = run_command(command)?;
+        let records = std::fs::read_to_string(&findings_file).unwrap_or_default();
+        let _ = std::fs::remove_file(&findings_file);
+
+        let recorded_lints: HashSet<&str> = records
+            .lines()
+            .filter_map(|record| record.split('\t').next())
+            .collect();
+        let arbitrary_cpi_call = recorded_lints.contains("arbitrary_cpi_call");
+        let other_lints = recorded_lints
+            .iter()
+            .any(|lint| *lint != "arbitrary_cpi_call");
+        if arbitrary_cpi_call != expect_arbitrary_cpi_call || other_lints != expect_other_lints {
+            bail_with_stderr!(
+                out.stderr,
+                "`{}=arbitrary_cpi_call` reported findings of {:?}",
+                env_var,
+                recorded_lints
+            );
+        }
+    }
+
+    println!("lint filters select the lints that run");
+    Ok(())
+}
+
+/// Analysis time of each lint on `tests/perf_program`, recorded by the last baseline refresh
+const PERF_BASELINE: &str = "tests/perf_baseline.tsv";
+/// A lint may take this many times its baseline analysis time
+const PERF_TOLERANCE: f64 = 1.5;
+/// Allowed on top of the tolerance, absorbing the noise of short analyses
+const PERF_SLACK: Duration = Duration::from_secs(2);
+/// Number of timed runs per lint, the fastest one counts
+const PERF_RUNS: usize = 2;
+/// A run taking longer than this is killed, failing the benchmark whatever the baseline
+const PERF_RUN_TIMEOUT: Duration = Duration::from_secs(600);
+/// Header of the baseline file
+const PERF_BASELINE_HEADER: &str = "\
+# Analysis time of each lint on tests/perf_program, in milliseconds.
+# Refresh with `ANCHOR_LINTS_PERF_UPDATE_BASELINE=1 cargo test perf_benchmark_tests -- --ignored`.
+";
+
+async fn run_perf_benchmark_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("tests/perf_program");
+    let baseline_path = lint_root.join(PERF_BASELINE);
+    let update_baseline = std::env::var_os("ANCHOR_LINTS_PERF_UPDATE_BASELINE").is_some();
+    let warn_only = std::env::var_os("ANCHOR_LINTS_PERF_WARN_ONLY").is_some();
+    let baseline = read_perf_baseline(&baseline_path)?;
+
+    // The first run builds the lints and the dependencies of the program
+    time_lint_run(&lint_root, &test_program, "")?;
+    // Every run checks the program again and loads all lints, a run with no lint enabled
+    // measures that overhead
+    let overhead = time_lint_run(&lint_root, &test_program, "")?;
+
+    let mut timings = Vec::new();
+    let mut regressions = Vec::new();
+    let mut missing = Vec::new();
+    for lint in LINTS {
+        let analysis =
+            time_lint_run(&lint_root, &test_program, lint.name)?.saturating_sub(overhead);
+        println!("{:<72} {:>8} ms", lint.name, analysis.as_millis());
+        match baseline.get(lint.name) {
+            Some(baseline_ms) => {
+                let budget =
+                    Duration::from_millis(*baseline_ms).mul_f64(PERF_TOLERANCE) + PERF_SLACK;
+                if analysis > budget {
+                    regressions.push(format!(
+                        "`{}` took {} ms, over its budget of {} ms (baseline {} ms)",
+                        lint.name,
+                        analysis.as_millis(),
+                        budget.as_millis(),
+                        baseline_ms
+                    ));
+                }
+            }
+            None => missing.push(format!("`{}`", lint.name)),
+        }
+        timings.push((lint.name, analysis));
+    }
+
+    if update_baseline {
+        write_perf_baseline(&baseline_path, &timings)?;
+        println!(
+            "recorded the analysis time of {} lints in {}",
+            timings.len(),
+            PERF_BASELINE
+        );
+        return Ok(());
+    }
+    if !regressions.is_empty() {
+        let message = format!(
+            "{} lints exceed their analysis time budget:\n{}",
+            regressions.len(),
+            regressions.join("\n")
+        );
+        if !warn_only {
+            anyhow::bail!(message);
+        }
+        println!("warning: {}", message);
+    }
+    // Lints without a baseline are not checked until the baseline is refreshed
+    if !missing.is_empty() {
+        println!(
+            "warning: {} lints have no baseline and were not checked, refresh {}: {}",
+            missing.len(),
+            PERF_BASELINE,
+            missing.join(", ")
+        );
+    }
+
+    println!(
+        "{} lints analyze the perf program within their budget",
+        LINTS.len() - missing.len()
+    );
+    Ok(())
+}
+
+/// Time a `cargo dylint` run on `test_program` with only `lint_name` enabled, keeping the fastest
+/// of [`PERF_RUNS`] runs
+fn time_lint_run(lint_root: &Path, test_program: &Path, lint_name: &str) -> Result<Duration> {
+    let stderr_path =
+        std::env::temp_dir().join(format!("anchor-lints-perf-{}.stderr", std::process::id()));
+    let mut fastest = Duration::MAX;
+    for _ in 0..PERF_RUNS {
+        // Lints only run when the program is analyzed again, not when cargo replays cached
+        // diagnostics
+        std::fs::File::options()
+            .append(true)
+            .open(test_program.join("src/lib.rs"))?
+            .set_modified(SystemTime::now())?;
+
+        let mut command = dylint_command(lint_root, test_program, "*");
+        command
+            .env("ANCHOR_LINTS_ONLY", lint_name)
+            .stdout(Stdio::null())
+            .stderr(std::fs::File::create(&stderr_path)?);
+        let start = Instant::now();
+        let mut child = command
+            .spawn()
+            .with_context(|| "Failed to run `cargo dylint`. Is dylint installed?")?;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if start.elapsed() > PERF_RUN_TIMEOUT {
+                child.kill()?;
+                let _ = child.wait();
+                anyhow::bail!(
+                    "`{}` did not finish analyzing the perf program within {} s",
+                    lint_name,
+                    PERF_RUN_TIMEOUT.as_secs()
+                );
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let elapsed = start.elapsed();
+
+        let stderr = std::fs::read_to_string(&stderr_path).unwrap_or_default();
+        let _ = std::fs::remove_file(&stderr_path);
+        if !status.success() {
+            bail_with_stderr!(
+                stderr,
+                "`cargo dylint` failed with only `{}` enabled",
+                lint_name
+            );
+        }
+        fastest = fastest.min(elapsed);
+    }
+    Ok(fastest)
+}
+
+/// Read the baseline analysis times, one `lint<TAB>milliseconds` record per line, lines starting
+/// with `#` are comments
+fn read_perf_baseline(path: &Path) -> Result<HashMap<String, u64>> {
+    let baseline = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read the perf baseline `{}`", path.display()))?;
+    let mut times = HashMap::new();
+    for record in baseline
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let Some((lint, millis)) = record.split_once('\t') else {
+            anyhow::bail!("Malformed perf baseline record `{}`", record);
+        };
+        let millis = millis
+            .trim()
+            .parse()
+            .with_context(|| format!("Malformed perf baseline record `{}`", record))?;
+        times.insert(lint.to_string(), millis);
+    }
+    Ok(times)
+}
+
+fn write_perf_baseline(path: &Path, timings: &[(&str, Duration)]) -> Result<()> {
+    let mut baseline = String::from(PERF_BASELINE_HEADER);
+    for (lint, analysis) in timings {
+        baseline.push_str(&format!("{}\t{}\n", lint, analysis.as_millis()));
+    }
+    std::fs::write(path, baseline)
+        .with_context(|| format!("Failed to write the perf baseline `{}`", path.display()))
+}
+
+async fn run_mir_dump_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/arbitrary_cpi_call/tests/test_program");
+    let dump_header = "constant_program_id` for `arbitrary_cpi_call` ====";
+
+    for (selected_fn, expect_dump) in [("constant_program_id", true), ("no_such_function", false)] {
+        // The dump is only printed when the program is analyzed again
+        std::fs::File::options()
+            .append(true)
+            .open(test_program.join("src/lib.rs"))?
+            .set_modified(SystemTime::now())?;
+
+        let mut command = dylint_command(&lint_root, &test_program, "arbitrary_cpi_call");
+        command.env("ANCHOR_LINTS_DUMP_MIR", selected_fn);
+        let out = run_command(command)?;
+
+        let has_dump =
+            out.stderr.contains(dump_header) && out.stderr.contains("-- basic blocks --");
+        if has_dump != expect_dump {
+            bail_with_stderr!(
+                out.stderr,
+                "`ANCHOR_LINTS_DUMP_MIR={}` {} the MIR dump of `constant_program_id`",
+                selected_fn,
+                if has_dump { "printed" } else { "did not print" }
+            );
+        }
+        if !expect_dump && out.stderr.contains("==== MIR of `") {
+            bail_with_stderr!(
+                out.stderr,
+                "`ANCHOR_LINTS_DUMP_MIR={}` dumped another function",
+                selected_fn
+            );
+        }
+    }
+
+    println!("MIR dump is printed for the selected function only");
+    Ok(())
+}
+
+async fn run_suppression_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/missing_signer_validation/tests/test_program");
+    let findings_file = std::env::temp_dir().join(format!(
+        "anchor-lints-suppressed-{}.tsv",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&findings_file);
+    let reason = "`from` is checked to be a signer by the router program";
+
+    // Findings are only recorded when the program is analyzed again, not when cargo replays
+    // cached diagnostics
+    let source = test_program.join("src/lib.rs");
+    std::fs::File::options()
+        .append(true)
+        .open(&source)?
+        .set_modified(SystemTime::now())?;
+
+    let mut command = dylint_command(&lint_root, &test_program, "missing_signer_validation");
+    command.env("ANCHOR_LINTS_SUMMARY", &findings_file);
+    let out = run_command(command)?;
+
+    // The finding is on the line following the suppression comment
+    let contents = std::fs::read_to_string(&source)?;
+    let suppressed_line = contents
+        .lines()
+        .position(|line| line.contains("anchor-lints: validated-elsewhere"))
+        .context("No suppression comment in the test program")?
+        + 2;
+    let reported = collect_reported_lines(
+        &out.combined,
+        &lint_root,
+        &test_program,
+        "warning: account",
+        Some("is used as a signer but lacks signer validation"),
+    )?;
+    if reported.contains(&("src/lib.rs".to_string(), suppressed_line)) {
+        bail_with_stderr!(
+            out.stderr,
+            "The finding on line {} was reported despite its suppression comment",
+            suppressed_line
+        );
+    }
+
+    let records = std::fs::read_to_string(&findings_file).unwrap_or_default();
+    let suppressed_records: Vec<_> = records
+        .lines()
+        .filter(|record| record.split('\t').nth(5) == Some(reason))
+        .collect();
+    if suppressed_records.len() != 1
+        || !suppressed_records[0].starts_with("missing_signer_validation\t")
+    {
+        let _ = std::fs::remove_file(&findings_file);
+        bail_with_stderr!(
+            out.stderr,
+            "Expected one suppressed record with its reason, found:\n{}",
+            records
+        );
+    }
+
+    let summary = Command::new(env!("CARGO_BIN_EXE_anchor-lints-summary"))
+        .arg(&findings_file)
+        .output()
+        .context("Failed to run `anchor-lints-summary`")?;
+    let _ = std::fs::remove_file(&findings_file);
+    let summary = String::from_utf8_lossy(&summary.stdout).to_string();
+    let suppressed_section = summary
+        .split_once("Suppressed:")
+        .map(|(_, section)| section)
+        .unwrap_or_default();
+    if !suppressed_section
+        .lines()
+        .any(|line| line.contains("missing_signer_validation") && line.ends_with(reason))
+    {
+        anyhow::bail!(
+            "The summary does not list the suppressed finding with its reason:\n{}",
+            summary
+        );
+    }
+
+    println!("suppressed finding is recorded with its reason");
+    Ok(())
+}
+
+async fn run_baseline_diff_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/missing_signer_validation/tests/test_program");
+    let baseline_file =
+        std::env::temp_dir().join(format!("anchor-lints-baseline-{}.tsv", std::process::id()));
+    let findings_file =
+        std::env::temp_dir().join(format!("anchor-lints-findings-{}.tsv", std::process::id()));
+
+    // The `baseline-diff-modified` feature fixes the finding on `old_payer` and introduces one
+    // on `new_payer`
+    record_baseline_diff_runs(&lint_root, &test_program, &baseline_file, &findings_file)?;
+
+    let diff = |extra_args: &[&str]| -> Result<(String, Option<i32>)> {
+        let output = Command::new(env!("CARGO_BIN_EXE_anchor-lints-diff"))
+            .arg(&baseline_file)
+            .arg(&findings_file)
+            .args(extra_args)
+            .output()
+            .context("Failed to run `anchor-lints-diff`")?;
+        Ok((
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            output.status.code(),
+        ))
+    };
+    let (report, status) = diff(&["--fixed", "--fail-on-new"])?;
+    let (_, status_without_flag) = diff(&[])?;
+    let _ = std::fs::remove_file(&baseline_file);
+    let _ = std::fs::remove_file(&findings_file);
+
+    // Collect the entries listed per section
+    let mut sections: HashMap<String, Vec<String>> = HashMap::new();
+    let mut current_section = None;
+    for line in report.lines() {
+        if let Some(title) = line.strip_suffix(':') {
+            current_section = Some(title.to_string());
+        } else if let Some(section) = &current_section
+            && let Some(entry) = line.strip_prefix("  ")
+        {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .push(entry.to_string());
+        }
+    }
+
+    let lists_only = |title: &str, account: &str| {
+        sections.get(title).is_some_and(|entries| {
+            entries.len() == 1
+                && entries[0].starts_with("missing_signer_validation")
+                && entries[0].contains(&format!("account `{}`", account))
+        })
+    };
+
+    if !lists_only("New", "new_payer") || !lists_only("Fixed", "old_payer") {
+        anyhow::bail!(
+            "Diff should list exactly the finding on `new_payer` as new and the one on \
+             `old_payer` as fixed:\n{}",
+            report
+        );
+    }
+    if status != Some(1) || status_without_flag != Some(0) {
+        anyhow::bail!(
+            "Diff should only fail with `--fail-on-new`, exited with {:?} and {:?} without it",
+            status,
+            status_without_flag
+        );
+    }
+
+    println!("baseline diff lists the new and fixed findings");
+    Ok(())
+}
+
+async fn run_suppressed_findings_tests() -> Result<()> {
+    let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let test_program = lint_root.join("lints/missing_signer_validation/tests/test_program");
+    let baseline_file = std::env::temp_dir().join(format!(
+        "anchor-lints-suppressed-baseline-{}.tsv",
+        std::process::id()
+    ));
+    let findings_file = std::env::temp_dir().join(format!(
+        "anchor-lints-suppressed-findings-{}.tsv",
+        std::process::id()
+    ));
+    record_baseline_diff_runs(&lint_root, &test_program, &baseline_file, &findings_file)?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_anchor-lints-diff"))
+        .arg(&baseline_file)
+        .arg(&findings_file)
+        .arg("--json")
+        .output()
+        .context("Failed to run `anchor-lints-diff`")?;
+    let records = std::fs::read_to_string(&findings_file).unwrap_or_default();
+    let _ = std::fs::remove_file(&baseline_file);
+    let _ = std::fs::remove_file(&findings_file);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .context("`anchor-lints-diff --json` did not print JSON")?;
+
+    // Every suppression recorded by the lints is listed with its source and reason, the
+    // `baseline` source only comes from the diff
+    let recorded_suppressions: Vec<Vec<&str>> = records
+        .lines()
+        .map(|record| record.split('\t').collect::<Vec<_>>())
+        .filter(|fields| fields.len() == 7)
+        .collect();
+    let listed = |fields: &[&str]| {
+        report["suppressed"].as_array().is_some_and(|entries| {
+            entries.iter().any(|entry| {
+                entry["message"] == fields[3]
+                    && entry["reason"] == fields[5]
+                    && entry["source"] == fields[6]
+            })
+        })
+    };
+    if !recorded_suppressions
+        .iter()
+        .any(|fields| fields[3].contains("account `allowed_payer`") && fields[6] == "allow")
+        || recorded_suppressions
+            .iter()
+            .any(|fields| fields[6] == "baseline" || !listed(fields))
+    {
+        anyhow::bail!(
+            "Expected the recorded suppressions, including the allowed finding on \
+             `allowed_payer`, to be listed as suppressed:\n{}\n{}",
+            records,
+            serde_json::to_string_pretty(&report)?
+        );
+    }
+
+    let about = |entry: &serde_json::Value, account: &str| {
+        entry["lint"] == "missing_signer_validation"
+            && entry["message"]
+                .as_str()
+                .is_some_and(|message| message.contains(&format!("account `{}`", account)))
+    };
+    let entries = |key: &str| report[key].as_array().cloned().unwrap_or_default();
+
+    // `allowed_payer` is allowed on its handler, `current_authority` is reported by both runs
+    for (account, source, reason) in [
+        (
+            "allowed_payer",
+            "allow",
+            "`allowed_payer` signs through the multisig program",
+        ),
+        ("current_authority", "baseline", "present in the baseline"),
+    ] {
+        let suppressed = entries("suppressed")
+            .into_iter()
+            .filter(|entry| about(entry, account))
+            .collect::<Vec<_>>();
+        if suppressed.len() != 1
+            || suppressed[0]["source"] != source
+            || suppressed[0]["reason"] != reason
+            || suppressed[0]["confidence"] != "medium"
+        {
+            anyhow::bail!(
+                "Expected the medium confidence finding on `{}` to be suppressed by `{}` with \
+                 reason `{}`:\n{}",
+                account,
+                source,
+                reason,
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+        if entries("new").iter().any(|entry| about(entry, account)) {
+            anyhow::bail!(
+                "The suppressed finding on `{}` is listed as new:\n{}",
+                account,
+                serde_json::to_string_pretty(&report)?
+            );
+        }
+    }
+
+    println!("allowed and baseline findings are listed as suppressed");
+    Ok(())
+}
+
+/// Record the findings of `missing_signer_validation` on its test program, then on the program
+/// modified by the `baseline-diff-modified` feature
+fn record_baseline_diff_runs(
+    lint_root: &Path,
+    test_program: &Path,
+    baseline_file: &Path,
+    findings_file: &Path,
+) -> Result<()> {
+    for (findings_file, features) in [
+        (baseline_file, None),
+        (findings_file, Some("baseline-diff-modified")),
+    ] {
+        let _ = std::fs::remove_file(findings_file);
+        // Findings are only recorded when the program is analyzed again, not when cargo replays
+        // cached diagnostics
+        std::fs::File::options()
+            .append(true)
+            .open(test_program.join("src/lib.rs"))?
+            .set_modified(SystemTime::now())?;
+
+        let mut command = dylint_command(lint_root, test_program, "missing_signer_validation");
+        command.env("ANCHOR_LINTS_SUMMARY", findings_file);
+        if let Some(features) = features {
+            command.arg("--").arg("--features").arg(features);
+        }
+        let out = run_command(command)?;
+        if !findings_file.exists() {
+            bail_with_stderr!(
+                out.stderr,
+                "No missing_signer_validation findings were recorded"
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn run_unchecked_account_used_as_authority_without_signer_or_pda_tests() -> Result<()> {
+    run_standard_lint_test(
+        "unchecked_account_used_as_authority_without_signer_or_pda",
+        &[
+            "unchecked_account_used_as_authority_without_signer_or_pda",
+            "safe_cpi_authority",
+        ],
+        "warning: account",
+        Some("is used as the authority of this token CPI"),
+        "unchecked_account_used_as_authority_without_signer_or_pda",
+    )
+    .await
+}
+
+async fn run_missing_signer_check_on_governance_config_update_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_signer_check_on_governance_config_update",
+        &[
+            "missing_signer_check_on_governance_config_update",
+            "safe_governance_update",
+        ],
+        "warning: config account",
+        Some("is updated without requiring its"),
+        "missing_signer_check_on_governance_config_update",
+    )
+    .await
+}
+
+async fn run_associated_token_authority_mismatch_tests() -> Result<()> {
+    run_standard_lint_test(
+        "associated_token_authority_mismatch",
+        &[
+            "associated_token_authority_mismatch",
+            "safe_associated_token_authority",
+        ],
+        "warning: associated token account",
+        Some("as `associated_token::authority` while"),
+        "associated_token_authority_mismatch",
+    )
+    .await
+}
+
+async fn run_missing_signer_validation_for_native_sol_withdrawal_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_signer_validation_for_native_sol_withdrawal",
+        &[
+            "missing_signer_validation_for_native_sol_withdrawal",
+            "safe_native_sol_withdrawal",
+        ],
+        "warning: native SOL is withdrawn from PDA",
+        Some("but no account of the instruction is required to sign"),
+        "native SOL withdrawal",
+    )
+    .await
+}
+
+async fn run_unbounded_loop_over_accounts_cu_dos_tests() -> Result<()> {
+    run_standard_lint_test(
+        "unbounded_loop_over_accounts_cu_dos",
+        &[
+            "unbounded_loop_over_accounts_cu_dos",
+            "safe_loop_over_accounts",
+        ],
+        "warning: loop bounded by",
+        Some("on every iteration — enforce an explicit maximum"),
+        "unbounded loop",
+    )
+    .await
+}
+
+async fn run_token_transfer_amount_from_unvalidated_balance_tests() -> Result<()> {
+    run_standard_lint_test(
+        "token_transfer_amount_from_unvalidated_balance",
+        &[
+            "token_transfer_amount_from_unvalidated_balance",
+            "safe_transfer_amount",
+        ],
+        "warning: transfer amount is read from the balance of",
+        Some("transfer amount is read from the balance of"),
+        "Token transfer amount from unvalidated balance",
+    )
+    .await
+}
+
+async fn run_missing_owner_check_on_cpi_return_account_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_owner_check_on_cpi_return_account",
+        &[
+            "missing_owner_check_on_cpi_return_account",
+            "safe_post_cpi_data_read",
+        ],
+        "warning: data of",
+        Some("is read after a CPI that may have reassigned it"),
+        "Missing owner check on CPI return account",
+    )
+    .await
+}
+
+async fn run_account_space_mismatch_with_struct_size_tests() -> Result<()> {
+    run_standard_lint_test(
+        "account_space_mismatch_with_struct_size",
+        &[
+            "account_space_mismatch_with_struct_size",
+            "safe_account_space",
+        ],
+        "warning: account `",
+        Some("allocates `space = "),
+        "Account space mismatch with struct size",
+    )
+    .await
+}
+
+async fn run_unsafe_unwrap_on_account_deserialization_tests() -> Result<()> {
+    run_standard_lint_test(
+        "unsafe_unwrap_on_account_deserialization",
+        &["unsafe_unwrap_on_account_deserialization", "safe_unwrap"],
+        "warning: `.",
+        Some("panics"),
+        "Unsafe unwrap on account deserialization",
+    )
+    .await
+}
+
+async fn run_signer_not_mutable_when_paying_tests() -> Result<()> {
+    run_standard_lint_test(
+        "signer_not_mutable_when_paying",
+        &["signer_not_mutable_when_paying", "safe_payer"],
+        "warning: `",
+        Some("is not marked `#[account(mut)]`"),
+        "Signer not mutable when paying",
+    )
+    .await
+}
+
+async fn run_missing_bump_verification_on_signer_pda_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_bump_verification_on_signer_pda",
+        &[
+            "missing_bump_verification_on_signer_pda",
+            "safe_signer_bump",
+        ],
+        "warning: signer seeds",
+        Some("end with a bump read from instruction argument"),
+        "Missing bump verification on signer PDA",
+    )
+    .await
+}
+
+async fn run_duplicate_seeds_across_distinct_account_types_tests() -> Result<()> {
+    run_standard_lint_test(
+        "duplicate_seeds_across_distinct_account_types",
+        &[
+            "duplicate_seeds_across_distinct_account_types",
+            "safe_distinct_seeds",
+        ],
+        "warning: `",
+        Some("is derived from the same seeds as"),
+        "Duplicate seeds across distinct account types",
+    )
+    .await
+}
+
+async fn run_missing_owner_check_on_manually_loaded_zero_copy_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_owner_check_on_manually_loaded_zero_copy",
+        &[
+            "missing_owner_check_on_manually_loaded_zero_copy",
+            "safe_zero_copy_load",
+        ],
+        "warning: `AccountLoader::try_from_unchecked`",
+        Some("does not check the owner of"),
+        "Missing owner check on manually loaded zero copy",
+    )
+    .await
+}
+
+async fn run_cpi_context_reused_across_different_programs_tests() -> Result<()> {
+    run_standard_lint_test(
+        "cpi_context_reused_across_different_programs",
+        &[
+            "cpi_context_reused_across_different_programs",
+            "safe_cpi_context_per_program",
+        ],
+        "warning: ",
+        Some("is reused for a CPI to another program after its `program_id` is reassigned"),
+        "CPI context reused across different programs",
+    )
+    .await
+}
+
+async fn run_unchecked_pda_canonical_bump_in_find_program_address_mismatch_tests() -> Result<()> {
+    run_standard_lint_test(
+        "unchecked_pda_canonical_bump_in_find_program_address_mismatch",
+        &[
+            "unchecked_pda_canonical_bump_in_find_program_address_mismatch",
+            "safe_derived_bump",
+        ],
+        "warning: the canonical bump",
+        Some("is discarded while the same seeds sign a CPI with another bump"),
+        "Unchecked PDA canonical bump in find_program_address mismatch",
+    )
+    .await
+}
+
+async fn run_token_2022_missing_extension_account_handling_tests() -> Result<()> {
+    run_standard_lint_test(
+        "token_2022_missing_extension_account_handling",
+        &[
+            "token_2022_missing_extension_account_handling",
+            "safe_transfer_hook_accounts",
+        ],
+        "warning: Token-2022 transfer without the transfer hook accounts",
+        Some("pass them to the `CpiContext` with `with_remaining_accounts`"),
+        "Token-2022 missing extension account handling",
+    )
+    .await
+}
+
+async fn run_missing_signer_validation_on_delegate_transfer_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_signer_validation_on_delegate_transfer",
+        &[
+            "missing_signer_validation_on_delegate_transfer",
+            "safe_delegate_transfer",
+        ],
+        "warning: account",
+        Some("is the delegate authority of this transfer"),
+        "delegate transfer without signer",
+    )
+    .await
+}
+
+async fn run_account_written_without_persisting_tests() -> Result<()> {
+    run_standard_lint_test(
+        "account_written_without_persisting",
+        &["account_written_without_persisting", "safe_persisted_write"],
+        "warning: `",
+        Some("is deserialized from account data and modified, but never serialized back"),
+        "unpersisted account write",
+    )
+    .await
+}
+
+async fn run_missing_owner_check_before_lamport_read_as_trust_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_owner_check_before_lamport_read_as_trust",
+        &[
+            "missing_owner_check_before_lamport_read_as_trust",
+            "safe_lamport_trust",
+        ],
+        "warning: the lamport balance of",
+        Some("decides this check"),
+        "Missing owner check before lamport read as trust",
+    )
+    .await
+}
+
+async fn run_seed_contains_user_controlled_value_without_validation_tests() -> Result<()> {
+    run_standard_lint_test(
+        "seed_contains_user_controlled_value_without_validation",
+        &[
+            "seed_contains_user_controlled_value_without_validation",
+            "safe_user_seed",
+        ],
+        "warning: seed `",
+        Some("is the raw bytes of the instruction argument"),
+        "Seed contains user controlled value without validation",
+    )
+    .await
+}
+
+async fn run_missing_signer_validation_cross_program_instruction_introspection_tests() -> Result<()>
+{
+    run_standard_lint_test(
+        "missing_signer_validation_cross_program_instruction_introspection",
+        &[
+            "missing_signer_validation_cross_program_instruction_introspection",
+            "safe_instruction_introspection",
+        ],
+        "warning: this introspected instruction decides a check",
+        None,
+        "Missing signer validation cross program instruction introspection",
+    )
+    .await
+}
+
+async fn run_account_reinit_via_close_then_init_in_same_tx_tests() -> Result<()> {
+    run_standard_lint_test(
+        "account_reinit_via_close_then_init_in_same_tx",
+        &[
+            "account_reinit_via_close_then_init_in_same_tx",
+            "safe_close_then_init",
+        ],
+        "warning: `",
+        Some("is initialized by the instruction closing"),
+        "Account reinit via close then init",
+    )
+    .await
+}
+
+async fn run_missing_owner_check_on_remaining_account_deserialization_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_owner_check_on_remaining_account_deserialization",
+        &[
+            "missing_owner_check_on_remaining_account_deserialization",
+            "safe_remaining_account_deserialization",
+        ],
+        "warning: `",
+        Some("is deserialized from a remaining account"),
+        "Missing Owner Check On Remaining Account Deserialization",
+    )
+    .await
+}
+
+async fn run_missing_account_field_init_for_bump_tests() -> Result<()> {
+    run_standard_lint_test(
+        "missing_account_field_init_for_bump",
+        &[
+            "missing_account_field_init_for_bump",
+            "safe_account_field_init_for_bump",
+        ],
+        "warning: account",
+        Some("is initialized without setting"),
+        "missing account field init for bump",
+    )
+    .await
+}
+
+async fn run_token_program_id_from_account_instead_of_typed_program_tests() -> Result<()> {
+    run_standard_lint_test(
+        "token_program_id_from_account_instead_of_typed_program",
+        &[
+            "token_program_id_from_account_instead_of_typed_program",
+            "safe_token_program",
+        ],
+        "warning: token program",
+        Some("is used as the program id of a token CPI"),
+        "Token Program Id From Account Instead Of Typed Program",
+    )
+    .await
+}
+
+async fn run_unchecked_cpi_program_in_generic_anchor_cpi_context_tests() -> Result<()> {
+    run_standard_lint_test(
+        "unchecked_cpi_program_in_generic_anchor_cpi_context",
+        &[
+            "unchecked_cpi_program_in_generic_anchor_cpi_context",
+            "safe_generated_cpi",
+        ],
+        "warning: `",
+        Some("so the caller chooses the program of the CPI"),
+        "Unchecked Cpi Program In Generic Anchor Cpi Context",
+    )
+    .await
+}
+
+// Recursively find all .rs files
+fn find_rust_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            files.extend(find_rust_files(&path)?);
+        } else if path.extension().map(|e| e == "rs").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Matches `[lint]` markers, and `[lint: note=<text>]` markers also expecting a note on the
+/// diagnostic reported at the marked line
+fn marker_regex() -> Result<Regex> {
+    Regex::new(r#"\[(\w+)(?::\s*note=([^\]]+))?\]"#).context("Failed to compile marker regex")
+}
+
+async fn collect_expected_markers(
+    test_program: &Path,
+    allowed_lints: &[&str],
+) -> Result<HashMap<String, Vec<(String, usize)>>> {
+    let src_root = test_program.join("src");
+    let marker_regex = marker_regex()?;
+    let mut expected: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+
+    let rust_files =
+        find_rust_files(&src_root).context("Failed to scan Rust files in test program")?;
+
+    for file in rust_files {
+        let content = fs::read_to_string(&file)
+            .await
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        for (idx, line) in content.lines().enumerate() {
+            for caps in marker_regex.captures_iter(line) {
+                let lint_name = caps.get(1).unwrap().as_str().to_string();
+
+                if allowed_lints.contains(&lint_name.as_str()) {
+                    let relative = file
+                        .strip_prefix(test_program)
+                        .context("Failed to compute relative path")?
                         .to_string_lossy()
                         .to_string();
 
@@ -498,6 +2089,53 @@ async fn collect_expected_markers(
     Ok(expected)
 }
 
+/// A note expected on the diagnostic reported at a marked line, from `// [lint: note=<text>]`
+struct ExpectedNote {
+    lint: String,
+    location: (String, usize),
+    text: String,
+}
+
+async fn collect_expected_notes(
+    test_program: &Path,
+    allowed_lints: &[&str],
+) -> Result<Vec<ExpectedNote>> {
+    let src_root = test_program.join("src");
+    let marker_regex = marker_regex()?;
+    let mut expected = Vec::new();
+
+    let rust_files =
+        find_rust_files(&src_root).context("Failed to scan Rust files in test program")?;
+
+    for file in rust_files {
+        let content = fs::read_to_string(&file)
+            .await
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        let relative = file
+            .strip_prefix(test_program)
+            .context("Failed to compute relative path")?
+            .to_string_lossy()
+            .to_string();
+
+        for (idx, line) in content.lines().enumerate() {
+            for caps in marker_regex.captures_iter(line) {
+                let lint_name = caps.get(1).unwrap().as_str();
+                if let Some(text) = caps.get(2)
+                    && allowed_lints.contains(&lint_name)
+                {
+                    expected.push(ExpectedNote {
+                        lint: lint_name.to_string(),
+                        location: (relative.clone(), idx + 1),
+                        text: text.as_str().trim().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(expected)
+}
+
 /// Generic helper function for standard lint tests that follow the common pattern:
 async fn run_standard_lint_test(
     lint_name: &str,
@@ -505,51 +2143,40 @@ async fn run_standard_lint_test(
     lint_heading: &str,
     additional_text: Option<&str>,
     lint_display_name: &str,
+) -> Result<()> {
+    run_standard_lint_test_with_config(
+        lint_name,
+        allowed_lints,
+        lint_heading,
+        additional_text,
+        lint_display_name,
+        None,
+    )
+    .await
+}
+
+/// Same as [`run_standard_lint_test`], passing `dylint_toml` as the lint configuration
+async fn run_standard_lint_test_with_config(
+    lint_name: &str,
+    allowed_lints: &[&str],
+    lint_heading: &str,
+    additional_text: Option<&str>,
+    lint_display_name: &str,
+    dylint_toml: Option<&str>,
 ) -> Result<()> {
     let lint_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     let test_program = lint_root.join(format!("lints/{}/tests/test_program", lint_name));
-    let span_re =
-        Regex::new(r#"-->[ ]*([^\s]+\.rs):(\d+)"#).context("Failed to compile span regex")?;
 
     let expected = collect_expected_markers(&test_program, allowed_lints).await?;
-    let out = run_dylint_command(&lint_root, &test_program, lint_name)?;
+    let out = run_dylint_command(&lint_root, &test_program, lint_name, dylint_toml)?;
     let stderr = out.stderr.clone();
-    let out = out.combined;
-
-    let mut actual: HashSet<(String, usize)> = HashSet::new();
-    let mut capture_span = false;
-
-    for line in out.lines() {
-        let matches_heading = if let Some(additional) = additional_text {
-            line.contains(lint_heading) && line.contains(additional)
-        } else {
-            line == lint_heading || line.contains(lint_heading)
-        };
-
-        if matches_heading {
-            capture_span = true;
-            continue;
-        }
-
-        if capture_span {
-            if let Some(cap) = span_re.captures(line) {
-                // FIXME: This stripping logic is duplicated
-                let file = lint_root.join(PathBuf::from(cap.get(1).unwrap().as_str()));
-                let file = file
-                    .strip_prefix(&test_program)?
-                    .to_string_lossy()
-                    .to_string();
-                let line_no: usize = cap
-                    .get(2)
-                    .unwrap()
-                    .as_str()
-                    .parse()
-                    .context("Invalid line number")?;
-                actual.insert((file, line_no));
-            }
-            capture_span = false;
-        }
-    }
+    let actual = collect_reported_lines(
+        &out.combined,
+        &lint_root,
+        &test_program,
+        lint_heading,
+        additional_text,
+    )?;
 
     let warn_key = allowed_lints[0];
     let safe_key = allowed_lints.get(1).copied();
@@ -611,15 +2238,227 @@ async fn run_standard_lint_test(
         }
     }
 
+    check_expected_notes(
+        &lint_root,
+        &test_program,
+        lint_name,
+        &allowed_lints[..1],
+        dylint_toml,
+    )
+    .await
+}
+
+/// Collect the `(file, line)` locations of the findings whose heading matches `lint_heading` and,
+/// if given, `additional_text`
+fn collect_reported_lines(
+    out: &str,
+    lint_root: &Path,
+    test_program: &Path,
+    lint_heading: &str,
+    additional_text: Option<&str>,
+) -> Result<HashSet<(String, usize)>> {
+    let span_re =
+        Regex::new(r#"-->[ ]*([^\s]+\.rs):(\d+)"#).context("Failed to compile span regex")?;
+    let mut actual: HashSet<(String, usize)> = HashSet::new();
+    let mut capture_span = false;
+
+    for line in out.lines() {
+        let matches_heading = if let Some(additional) = additional_text {
+            line.contains(lint_heading) && line.contains(additional)
+        } else {
+            line == lint_heading || line.contains(lint_heading)
+        };
+
+        if matches_heading {
+            capture_span = true;
+            continue;
+        }
+
+        if capture_span {
+            if let Some(cap) = span_re.captures(line) {
+                // FIXME: This stripping logic is duplicated
+                // Paths are relative to the test workspace, or absolute for programs outside of it
+                let file = lint_root
+                    .join("tests")
+                    .join(PathBuf::from(cap.get(1).unwrap().as_str()));
+                let file = file
+                    .strip_prefix(test_program)?
+                    .to_string_lossy()
+                    .to_string();
+                let line_no: usize = cap
+                    .get(2)
+                    .unwrap()
+                    .as_str()
+                    .parse()
+                    .context("Invalid line number")?;
+                actual.insert((file, line_no));
+            }
+            capture_span = false;
+        }
+    }
+
+    Ok(actual)
+}
+
+/// A diagnostic read from the `--message-format=json` output of `cargo dylint`
+struct Diagnostic {
+    message: String,
+    location: Option<(String, usize)>,
+    notes: Vec<DiagnosticNote>,
+}
+
+struct DiagnosticNote {
+    message: String,
+    location: Option<(String, usize)>,
+}
+
+impl std::fmt::Display for DiagnosticNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some((file, line)) => write!(f, "{} ({}:{})", self.message, file, line),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Check that the diagnostics reported at lines marked with `[lint: note=<text>]` carry a note
+/// containing `<text>`, for the markers of `allowed_lints`
+async fn check_expected_notes(
+    lint_root: &Path,
+    test_program: &Path,
+    lint_name: &str,
+    allowed_lints: &[&str],
+    dylint_toml: Option<&str>,
+) -> Result<()> {
+    let expected = collect_expected_notes(test_program, allowed_lints).await?;
+    if expected.is_empty() {
+        return Ok(());
+    }
+
+    let mut command = dylint_command(lint_root, test_program, lint_name);
+    if let Some(dylint_toml) = dylint_toml {
+        command.env("DYLINT_TOML", dylint_toml);
+    }
+    command.args(["--", "--message-format=json"]);
+    let out = run_command(command)?;
+    let diagnostics = collect_diagnostics(&out.combined, lint_root, test_program)?;
+
+    for note in expected {
+        let reported: Vec<_> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.location.as_ref() == Some(&note.location))
+            .collect();
+        if reported.is_empty() {
+            bail_with_stderr!(
+                out.stderr,
+                "Expected `{}` note {:?} at {}:{}, but no diagnostic is reported there",
+                note.lint,
+                note.text,
+                note.location.0,
+                note.location.1
+            );
+        }
+        if !reported.iter().any(|diagnostic| {
+            diagnostic
+                .notes
+                .iter()
+                .any(|found| found.message.contains(&note.text))
+        }) {
+            let found: Vec<_> = reported
+                .iter()
+                .flat_map(|diagnostic| {
+                    diagnostic
+                        .notes
+                        .iter()
+                        .map(move |found| format!("{}: {}", diagnostic.message, found))
+                })
+                .collect();
+            bail_with_stderr!(
+                out.stderr,
+                "Missing `{}` note {:?} at {}:{}\nFound notes: {:#?}",
+                note.lint,
+                note.text,
+                note.location.0,
+                note.location.1,
+                found
+            );
+        }
+    }
+
     Ok(())
 }
 
+/// Collect the warnings of the `compiler-message` lines of the JSON output, with their primary
+/// location and the notes attached to them
+fn collect_diagnostics(
+    out: &str,
+    lint_root: &Path,
+    test_program: &Path,
+) -> Result<Vec<Diagnostic>> {
+    let primary_location = |spans: &serde_json::Value| {
+        let span = spans
+            .as_array()?
+            .iter()
+            .find(|span| span["is_primary"] == true)?;
+        let file = test_program_location(lint_root, test_program, span["file_name"].as_str()?)?;
+        Some((file, span["line_start"].as_u64()? as usize))
+    };
+
+    let mut diagnostics = Vec::new();
+    for line in out.lines().filter(|line| line.starts_with('{')) {
+        let value: serde_json::Value =
+            serde_json::from_str(line).context("Invalid JSON message from `cargo dylint`")?;
+        if value["reason"] != "compiler-message" || value["message"]["level"] != "warning" {
+            continue;
+        }
+        let message = &value["message"];
+        let notes = message["children"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter(|child| child["level"] == "note")
+            .map(|child| DiagnosticNote {
+                message: child["message"].as_str().unwrap_or_default().to_string(),
+                location: primary_location(&child["spans"]),
+            })
+            .collect();
+        diagnostics.push(Diagnostic {
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+            location: primary_location(&message["spans"]),
+            notes,
+        });
+    }
+
+    Ok(diagnostics)
+}
+
+/// Make a reported file name relative to the test program, `None` for files outside of it. File
+/// names are relative to the test workspace, or absolute for programs outside of it
+fn test_program_location(lint_root: &Path, test_program: &Path, file_name: &str) -> Option<String> {
+    let file = lint_root.join("tests").join(PathBuf::from(file_name));
+    file.strip_prefix(test_program)
+        .ok()
+        .map(|file| file.to_string_lossy().to_string())
+}
+
 fn run_dylint_command(
     lint_root: &Path,
     test_program: &Path,
     lint_name: &str,
+    dylint_toml: Option<&str>,
 ) -> Result<DylintOutput> {
-    let output = Command::new("cargo")
+    let mut command = dylint_command(lint_root, test_program, lint_name);
+    // Lint configuration is otherwise read from the test workspace's `dylint.toml`
+    if let Some(dylint_toml) = dylint_toml {
+        command.env("DYLINT_TOML", dylint_toml);
+    }
+    run_command(command)
+}
+
+fn dylint_command(lint_root: &Path, test_program: &Path, lint_name: &str) -> Command {
+    let mut command = Command::new("cargo");
+    command
         .arg("dylint")
         .arg("--path")
         .arg(lint_root.join("lints"))
@@ -627,7 +2466,12 @@ fn run_dylint_command(
         .arg("--pattern")
         .arg(lint_name)
         .env("TERM", "DUMB")
-        .env("CARGO_TERM_COLOR", "never")
+        .env("CARGO_TERM_COLOR", "never");
+    command
+}
+
+fn run_command(mut command: Command) -> Result<DylintOutput> {
+    let output = command
         .output()
         .with_context(|| "Failed to run `cargo dylint`. Is dylint installed?")?;
 
@@ -0,0 +1,1088 @@
+//! A large synthetic program for the performance benchmark, `cargo test perf_benchmark_tests --
+//! --ignored`. Each family of handlers is repeated so the recursive analyses (nested helpers,
+//! deep branching, loops over remaining accounts) run on about as much code as a real program.
+//! Findings are not checked here, only the time it takes to analyze the program.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount};
+
+declare_id!("11111111111111111111111111111111");
+
+const ALLOWED_PROGRAM_A: Pubkey = Pubkey::new_from_array([1u8; 32]);
+const ALLOWED_PROGRAM_B: Pubkey = Pubkey::new_from_array([2u8; 32]);
+const ALLOWED_PROGRAM_C: Pubkey = Pubkey::new_from_array([3u8; 32]);
+
+#[program]
+pub mod perf_program {
+    use super::*;
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_0(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 100 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 10 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_0(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_0(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_0<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 1 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_0(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_0(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(1).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_1(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 200 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 20 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_1(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_1(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_1<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 2 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_1(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_1(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(2).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_2(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 300 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 30 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_2(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_2(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_2<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 3 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_2(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_2(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(3).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_3(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 400 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 40 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_3(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_3(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_3<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 4 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_3(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_3(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(4).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_4(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 500 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 50 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_4(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_4(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_4<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 5 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_4(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_4(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(5).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_5(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 600 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 60 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_5(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_5(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_5<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 6 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_5(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_5(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(6).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_6(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 700 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 70 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_6(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_6(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_6<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 7 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_6(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_6(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(7).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+
+    // Program id validated in one of several nested branches
+    pub fn validated_forward_7(ctx: Context<Forward>, amount: u64, route: u8) -> Result<()> {
+        let program_id = ctx.accounts.target_program.key();
+        if route == 0 {
+            require_keys_eq!(program_id, ALLOWED_PROGRAM_A, PerfError::InvalidProgram);
+        } else if route == 1 {
+            if amount > 800 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_B, PerfError::InvalidProgram);
+            } else if amount > 80 {
+                require_keys_eq!(program_id, ALLOWED_PROGRAM_C, PerfError::InvalidProgram);
+            } else {
+                require!(
+                    program_id == ALLOWED_PROGRAM_A || program_id == ALLOWED_PROGRAM_B,
+                    PerfError::InvalidProgram
+                );
+            }
+        } else {
+            return err!(PerfError::InvalidRoute);
+        }
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(program_id, cpi_accounts);
+        system_program::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    // CPI made at the end of a chain of helpers, validated halfway
+    pub fn helper_chain_7(ctx: Context<Forward>, amount: u64) -> Result<()> {
+        let accounts = &ctx.accounts;
+        chain_step_a_7(
+            &accounts.payer,
+            &accounts.recipient,
+            &accounts.target_program,
+            amount,
+        )
+    }
+
+    // Lamports moved to every remaining account
+    pub fn remaining_sweep_7<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        let mut total = 0u64;
+        for account in ctx.remaining_accounts.iter() {
+            if account.lamports() > 8 {
+                let cpi_accounts = Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                };
+                let cpi_ctx = CpiContext::new(ctx.accounts.system_program.key(), cpi_accounts);
+                system_program::transfer(cpi_ctx, amount)?;
+                total = total.checked_add(amount).ok_or(PerfError::Overflow)?;
+            }
+        }
+        ctx.accounts.state.total = total;
+        Ok(())
+    }
+
+    // Token transfer followed by reads of the transferred accounts
+    pub fn token_flow_7(ctx: Context<TokenFlow>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.from.amount >= amount,
+            PerfError::InsufficientFunds
+        );
+        let cpi_accounts = token::Transfer {
+            from: ctx.accounts.from.to_account_info(),
+            to: ctx.accounts.to.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.key(), cpi_accounts);
+        token::transfer(cpi_ctx, amount)?;
+        ctx.accounts.from.reload()?;
+        ctx.accounts.to.reload()?;
+        ctx.accounts.state.total = ctx.accounts.to.amount;
+        Ok(())
+    }
+
+    // Account initialized with every field set
+    pub fn initialize_state_7(ctx: Context<InitializeState>, seed: u64) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        state.authority = ctx.accounts.authority.key();
+        state.total = seed.checked_mul(8).ok_or(PerfError::Overflow)?;
+        state.bump = ctx.bumps.state;
+        Ok(())
+    }
+}
+
+fn chain_step_a_0<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(0).ok_or(PerfError::Overflow)?;
+    chain_step_b_0(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_0<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_0(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_0<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_1<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(1).ok_or(PerfError::Overflow)?;
+    chain_step_b_1(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_1<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_1(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_1<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_2<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(2).ok_or(PerfError::Overflow)?;
+    chain_step_b_2(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_2<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_2(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_2<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_3<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(3).ok_or(PerfError::Overflow)?;
+    chain_step_b_3(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_3<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_3(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_3<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_4<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(4).ok_or(PerfError::Overflow)?;
+    chain_step_b_4(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_4<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_4(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_4<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_5<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(5).ok_or(PerfError::Overflow)?;
+    chain_step_b_5(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_5<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_5(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_5<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_6<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(6).ok_or(PerfError::Overflow)?;
+    chain_step_b_6(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_6<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_6(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_6<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+fn chain_step_a_7<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let amount = amount.checked_add(7).ok_or(PerfError::Overflow)?;
+    chain_step_b_7(payer, recipient, target_program, amount)
+}
+
+fn chain_step_b_7<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    require_keys_eq!(
+        target_program.key(),
+        ALLOWED_PROGRAM_A,
+        PerfError::InvalidProgram
+    );
+    chain_step_c_7(payer, recipient, target_program, amount)
+}
+
+fn chain_step_c_7<'info>(
+    payer: &Signer<'info>,
+    recipient: &SystemAccount<'info>,
+    target_program: &UncheckedAccount<'info>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: payer.to_account_info(),
+        to: recipient.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(target_program.key(), cpi_accounts);
+    system_program::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+pub struct Forward<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+    /// CHECK: validated by the handlers
+    pub target_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut, has_one = authority, seeds = [b"state", authority.key().as_ref()], bump = state.bump)]
+    pub state: Account<'info, State>,
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TokenFlow<'info> {
+    #[account(mut, token::authority = authority)]
+    pub from: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub to: Account<'info, TokenAccount>,
+    pub authority: Signer<'info>,
+    #[account(mut, has_one = authority)]
+    pub state: Account<'info, State>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeState<'info> {
+    #[account(init, payer = authority, space = 8 + State::INIT_SPACE, seeds = [b"state", authority.key().as_ref()], bump)]
+    pub state: Account<'info, State>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct State {
+    pub authority: Pubkey,
+    pub total: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum PerfError {
+    #[msg("Invalid program")]
+    InvalidProgram,
+    #[msg("Invalid route")]
+    InvalidRoute,
+    #[msg("Insufficient funds")]
+    InsufficientFunds,
+    #[msg("Overflow")]
+    Overflow,
+}